@@ -0,0 +1,56 @@
+-- =====================================================
+-- Migration: bookings.payment_status
+-- =====================================================
+-- Tracks whether a booking has been paid for, driven by the payment slips
+-- attached to it in `booking_slips`:
+--
+--   unpaid               -> no slip attached yet (default for new rows)
+--   pending_verification -> a slip was attached via
+--                           `POST /api/bookings/:id/slips`
+--   paid                 -> SlipOK verified a slip referencing the booking
+--
+-- Transitions only move forward. A failed SlipOK verification leaves the
+-- booking at `pending_verification` (the slip is still there for an admin
+-- to review) rather than walking it back to `unpaid`. The forward-only rule
+-- is enforced in the application (`PaymentStatus::advance_to` and the
+-- guarded UPDATEs in `services/booking.rs`), not here.
+--
+-- VARCHAR + CHECK rather than a Postgres enum, matching
+-- `chk_bookings_payment_type` from `20260512020000_booking_admin_fields.sql`
+-- so adding a state later doesn't need `ALTER TYPE ... ADD VALUE`.
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+ALTER TABLE "public"."bookings"
+    ADD COLUMN IF NOT EXISTS "payment_status" VARCHAR(30) NOT NULL DEFAULT 'unpaid';
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'chk_bookings_payment_status'
+    ) THEN
+        ALTER TABLE "public"."bookings"
+            ADD CONSTRAINT "chk_bookings_payment_status"
+            CHECK (payment_status IN ('unpaid', 'pending_verification', 'paid'));
+    END IF;
+END $$;
+
+-- ----- Backfill from existing slips -------------------------------------
+-- Only ever moves rows forward, so re-running is a no-op.
+
+UPDATE "public"."bookings" b
+SET payment_status = 'pending_verification'
+WHERE b.payment_status = 'unpaid'
+  AND EXISTS (
+      SELECT 1 FROM "public"."booking_slips" bs WHERE bs.booking_id = b.id
+  );
+
+UPDATE "public"."bookings" b
+SET payment_status = 'paid'
+WHERE b.payment_status <> 'paid'
+  AND EXISTS (
+      SELECT 1 FROM "public"."booking_slips" bs
+      WHERE bs.booking_id = b.id AND bs.slipok_status = 'verified'
+  );
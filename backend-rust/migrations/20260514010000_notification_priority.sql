@@ -0,0 +1,33 @@
+-- =====================================================
+-- Migration: notifications.priority + notifications.category
+-- =====================================================
+-- Push clients need to tell high-priority notifications (security alerts)
+-- apart from routine ones to pick a sound / banner style.
+--
+--   priority  low | normal | high, default 'normal'. Existing rows and any
+--             insert that doesn't name the column (the notification
+--             triggers in the init migration, admin broadcasts) stay
+--             'normal'. 'high' bypasses digest batching.
+--   category  optional free-form grouping key (e.g. 'security',
+--             'promotion'); NULL when the sender doesn't set one.
+--
+-- VARCHAR + CHECK rather than a Postgres enum so adding a level later
+-- doesn't need `ALTER TYPE ... ADD VALUE`. Idempotent so a partial
+-- application can be re-run safely.
+-- =====================================================
+
+ALTER TABLE "public"."notifications"
+    ADD COLUMN IF NOT EXISTS "priority" VARCHAR(10) NOT NULL DEFAULT 'normal',
+    ADD COLUMN IF NOT EXISTS "category" VARCHAR(50);
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'chk_notifications_priority'
+    ) THEN
+        ALTER TABLE "public"."notifications"
+            ADD CONSTRAINT "chk_notifications_priority"
+            CHECK (priority IN ('low', 'normal', 'high'));
+    END IF;
+END $$;
@@ -0,0 +1,47 @@
+-- =====================================================
+-- Migration: referrals
+-- =====================================================
+-- One row per referred member. A new registrant enters an existing
+-- member's referral code (their membership ID); the row starts `pending`
+-- and moves to `rewarded` when the referee completes their first stay,
+-- at which point both sides get the configured bonus points.
+--
+--   status                pending | rewarded
+--   referrer_points       points awarded to the referrer on qualification
+--   referee_points        points awarded to the referee on qualification
+--   qualifying_reference  what qualified the referral (e.g. BOOKING-<id>)
+--
+-- Fraud guards enforced here as well as in `services/referral.rs`:
+--   * UNIQUE (referee_id)  - a member can only ever be referred once
+--   * CHECK  (referrer_id <> referee_id) - no self-referral
+--
+-- VARCHAR + CHECK rather than a Postgres enum so adding a state later
+-- doesn't need `ALTER TYPE ... ADD VALUE`. Idempotent so a partial
+-- application can be re-run safely.
+-- =====================================================
+
+CREATE TABLE IF NOT EXISTS "public"."referrals" (
+    "id" UUID NOT NULL DEFAULT gen_random_uuid(),
+    "referrer_id" UUID NOT NULL,
+    "referee_id" UUID NOT NULL,
+    "referral_code" VARCHAR(20) NOT NULL,
+    "status" VARCHAR(20) NOT NULL DEFAULT 'pending',
+    "referrer_points" INTEGER NOT NULL DEFAULT 0,
+    "referee_points" INTEGER NOT NULL DEFAULT 0,
+    "qualifying_reference" VARCHAR(255),
+    "qualified_at" TIMESTAMPTZ(6),
+    "created_at" TIMESTAMPTZ(6) NOT NULL DEFAULT NOW(),
+    "updated_at" TIMESTAMPTZ(6) NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT "referrals_pkey" PRIMARY KEY ("id"),
+    CONSTRAINT "referrals_referrer_id_fkey" FOREIGN KEY ("referrer_id")
+        REFERENCES "public"."users"("id") ON DELETE CASCADE,
+    CONSTRAINT "referrals_referee_id_fkey" FOREIGN KEY ("referee_id")
+        REFERENCES "public"."users"("id") ON DELETE CASCADE,
+    CONSTRAINT "uq_referrals_referee" UNIQUE ("referee_id"),
+    CONSTRAINT "chk_referrals_not_self" CHECK ("referrer_id" <> "referee_id"),
+    CONSTRAINT "chk_referrals_status" CHECK ("status" IN ('pending', 'rewarded'))
+);
+
+CREATE INDEX IF NOT EXISTS "idx_referrals_referrer_id"
+    ON "public"."referrals"("referrer_id");
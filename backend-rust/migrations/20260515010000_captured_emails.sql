@@ -0,0 +1,31 @@
+-- =====================================================
+-- Migration: captured_emails
+-- =====================================================
+-- Storage for test-mode message capture. When `EMAIL_CAPTURE_MODE=true`
+-- (rejected at startup in production), `CapturingEmailService` writes each
+-- rendered message here instead of handing it to SMTP, so staging can
+-- verify content without mailing real users. Listed by
+-- `GET /api/admin/email/captured`.
+--
+--   channel  email | sms. Only email is captured today; the column lets an
+--            SMS sender share the table and admin view when one exists.
+--
+-- Rows are disposable test artefacts — nothing references them.
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+CREATE TABLE IF NOT EXISTS "public"."captured_emails" (
+    "id" UUID NOT NULL DEFAULT gen_random_uuid(),
+    "channel" VARCHAR(10) NOT NULL DEFAULT 'email',
+    "recipient" VARCHAR(255) NOT NULL,
+    "subject" TEXT,
+    "html_body" TEXT,
+    "text_body" TEXT NOT NULL,
+    "created_at" TIMESTAMPTZ(6) NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT "captured_emails_pkey" PRIMARY KEY ("id"),
+    CONSTRAINT "chk_captured_emails_channel" CHECK ("channel" IN ('email', 'sms'))
+);
+
+CREATE INDEX IF NOT EXISTS "idx_captured_emails_created_at"
+    ON "public"."captured_emails"("created_at" DESC);
@@ -0,0 +1,48 @@
+-- =====================================================
+-- Migration: bookings.source / bookings.channel
+-- =====================================================
+-- Records where a booking came from, for filtering and reporting:
+--
+--   source   who sold the stay:  direct | ota | travel_agent | corporate
+--   channel  how it reached us:  web | app | phone | walk_in | email
+--
+-- Both are set once on creation. Existing rows predate the columns and
+-- were all made through our own site, so they take the column defaults
+-- (`direct` / `web`) when the columns are added.
+--
+-- VARCHAR + CHECK rather than a Postgres enum, matching
+-- `chk_bookings_payment_status` from `20260514000000_booking_payment_status.sql`,
+-- so adding a value later doesn't need `ALTER TYPE ... ADD VALUE`. Keep
+-- the lists in sync with `BookingSource` / `BookingChannel` in
+-- `src/models/booking.rs`.
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+ALTER TABLE "public"."bookings"
+    ADD COLUMN IF NOT EXISTS "source" VARCHAR(20) NOT NULL DEFAULT 'direct',
+    ADD COLUMN IF NOT EXISTS "channel" VARCHAR(20) NOT NULL DEFAULT 'web';
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'chk_bookings_source'
+    ) THEN
+        ALTER TABLE "public"."bookings"
+            ADD CONSTRAINT "chk_bookings_source"
+            CHECK (source IN ('direct', 'ota', 'travel_agent', 'corporate'));
+    END IF;
+
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'chk_bookings_channel'
+    ) THEN
+        ALTER TABLE "public"."bookings"
+            ADD CONSTRAINT "chk_bookings_channel"
+            CHECK (channel IN ('web', 'app', 'phone', 'walk_in', 'email'));
+    END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS "idx_bookings_source_channel"
+    ON "public"."bookings"("source", "channel");
@@ -0,0 +1,64 @@
+-- =====================================================
+-- Migration: non-negative user_loyalty balances
+-- =====================================================
+-- Points and nights can never go below zero:
+--
+--   chk_user_loyalty_current_points_non_negative  current_points >= 0
+--   chk_user_loyalty_total_nights_non_negative    total_nights  >= 0
+--
+-- The admin deduct handlers pre-check the balance to give a friendly
+-- error, but two concurrent deductions can both pass that check. These
+-- constraints are the backstop; `services::loyalty::map_balance_violation`
+-- turns a violation (SQLSTATE 23514) back into the same validation error
+-- the pre-check returns. Keep the names in sync with the constants there.
+--
+-- NULL balances (the columns are nullable) pass a CHECK, which matches
+-- the application treating NULL as 0.
+--
+-- Constraints are added NOT VALID so the migration can't fail on a
+-- database that already holds a negative balance; new writes are checked
+-- immediately. Existing rows are then validated only when none violate —
+-- otherwise a NOTICE is raised and the offending rows need a manual
+-- correction (not an automatic clamp: these are member balances) before
+-- `VALIDATE CONSTRAINT` is run by hand.
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'chk_user_loyalty_current_points_non_negative'
+    ) THEN
+        ALTER TABLE "public"."user_loyalty"
+            ADD CONSTRAINT "chk_user_loyalty_current_points_non_negative"
+            CHECK (current_points >= 0) NOT VALID;
+    END IF;
+
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'chk_user_loyalty_total_nights_non_negative'
+    ) THEN
+        ALTER TABLE "public"."user_loyalty"
+            ADD CONSTRAINT "chk_user_loyalty_total_nights_non_negative"
+            CHECK (total_nights >= 0) NOT VALID;
+    END IF;
+END $$;
+
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM "public"."user_loyalty" WHERE current_points < 0) THEN
+        RAISE NOTICE 'user_loyalty has negative current_points; chk_user_loyalty_current_points_non_negative left NOT VALID';
+    ELSE
+        ALTER TABLE "public"."user_loyalty"
+            VALIDATE CONSTRAINT "chk_user_loyalty_current_points_non_negative";
+    END IF;
+
+    IF EXISTS (SELECT 1 FROM "public"."user_loyalty" WHERE total_nights < 0) THEN
+        RAISE NOTICE 'user_loyalty has negative total_nights; chk_user_loyalty_total_nights_non_negative left NOT VALID';
+    ELSE
+        ALTER TABLE "public"."user_loyalty"
+            VALIDATE CONSTRAINT "chk_user_loyalty_total_nights_non_negative";
+    END IF;
+END $$;
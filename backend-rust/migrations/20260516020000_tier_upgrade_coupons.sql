@@ -0,0 +1,57 @@
+-- =====================================================
+-- Migration: tier upgrade coupons
+-- =====================================================
+-- Lets each tier name a welcome coupon that members receive automatically
+-- when they move up into it.
+--
+--   tiers.upgrade_coupon_id  coupon template granted on upgrade into the
+--                            tier; NULL (the default) grants nothing
+--
+--   tier_upgrade_grants      one row per (user, tier) upgrade that was
+--                            granted; UNIQUE (user_id, tier_id) is what
+--                            makes the grant fire at most once per member
+--                            per tier, however many recalculations or
+--                            concurrent awards observe the same upgrade.
+--                            `user_coupon_id` is NULL when the coupon's
+--                            own limits (status, expiry, usage limits)
+--                            rejected the assignment.
+--
+-- Granting lives in `services::loyalty::grant_tier_upgrade_coupon`.
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+ALTER TABLE "public"."tiers"
+    ADD COLUMN IF NOT EXISTS "upgrade_coupon_id" UUID;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'tiers_upgrade_coupon_id_fkey'
+    ) THEN
+        ALTER TABLE "public"."tiers"
+            ADD CONSTRAINT "tiers_upgrade_coupon_id_fkey"
+            FOREIGN KEY ("upgrade_coupon_id") REFERENCES "public"."coupons"("id")
+            ON DELETE SET NULL;
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS "public"."tier_upgrade_grants" (
+    "id" UUID NOT NULL DEFAULT gen_random_uuid(),
+    "user_id" UUID NOT NULL,
+    "tier_id" UUID NOT NULL,
+    "coupon_id" UUID NOT NULL,
+    "user_coupon_id" UUID,
+    "created_at" TIMESTAMPTZ(6) NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT "tier_upgrade_grants_pkey" PRIMARY KEY ("id"),
+    CONSTRAINT "tier_upgrade_grants_user_id_fkey" FOREIGN KEY ("user_id")
+        REFERENCES "public"."users"("id") ON DELETE CASCADE,
+    CONSTRAINT "tier_upgrade_grants_tier_id_fkey" FOREIGN KEY ("tier_id")
+        REFERENCES "public"."tiers"("id") ON DELETE CASCADE,
+    CONSTRAINT "tier_upgrade_grants_coupon_id_fkey" FOREIGN KEY ("coupon_id")
+        REFERENCES "public"."coupons"("id") ON DELETE CASCADE,
+    CONSTRAINT "tier_upgrade_grants_user_coupon_id_fkey" FOREIGN KEY ("user_coupon_id")
+        REFERENCES "public"."user_coupons"("id") ON DELETE SET NULL,
+    CONSTRAINT "uq_tier_upgrade_grants_user_tier" UNIQUE ("user_id", "tier_id")
+);
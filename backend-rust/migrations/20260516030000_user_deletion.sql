@@ -0,0 +1,21 @@
+-- =====================================================
+-- Migration: user deletion tombstones
+-- =====================================================
+-- Deleting an account no longer removes the `users` row: points
+-- transactions, bookings and redeemed coupons reference it with
+-- ON DELETE CASCADE, and those financial/audit records must survive.
+-- Instead `services::user_deletion` scrubs the row and its profile of
+-- personal data and stamps `deleted_at`, leaving a PII-free tombstone the
+-- retained records (and aggregate analytics) still join to.
+--
+--   deleted_at  when the account was deleted; NULL for live accounts
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+ALTER TABLE "public"."users"
+    ADD COLUMN IF NOT EXISTS "deleted_at" TIMESTAMPTZ(6);
+
+CREATE INDEX IF NOT EXISTS "idx_users_deleted_at"
+    ON "public"."users"("deleted_at")
+    WHERE "deleted_at" IS NOT NULL;
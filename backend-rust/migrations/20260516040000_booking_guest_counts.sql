@@ -0,0 +1,51 @@
+-- =====================================================
+-- Migration: booking guest counts
+-- =====================================================
+-- Splits a booking's guest count into adults and children for capacity
+-- planning.
+--
+--   bookings.adults    at least one adult per booking
+--   bookings.children  zero or more children
+--
+-- `num_guests` stays as the stored total (adults + children) so existing
+-- reports and queries keep working; every write path sets all three.
+-- Existing rows are backfilled as all adults, which is what the old
+-- single count meant.
+--
+-- Room-type capacity (`room_types.max_guests`) is enforced by
+-- `services::booking::GuestCount::ensure_fits`, not here, because
+-- capacity can change after a booking is made.
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+ALTER TABLE "public"."bookings"
+    ADD COLUMN IF NOT EXISTS "adults" INTEGER,
+    ADD COLUMN IF NOT EXISTS "children" INTEGER NOT NULL DEFAULT 0;
+
+UPDATE "public"."bookings"
+SET "adults" = GREATEST("num_guests", 1),
+    "num_guests" = GREATEST("num_guests", 1)
+WHERE "adults" IS NULL;
+
+ALTER TABLE "public"."bookings"
+    ALTER COLUMN "adults" SET DEFAULT 1,
+    ALTER COLUMN "adults" SET NOT NULL;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'chk_bookings_adults'
+    ) THEN
+        ALTER TABLE "public"."bookings"
+            ADD CONSTRAINT "chk_bookings_adults" CHECK ("adults" >= 1);
+    END IF;
+
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'chk_bookings_children'
+    ) THEN
+        ALTER TABLE "public"."bookings"
+            ADD CONSTRAINT "chk_bookings_children" CHECK ("children" >= 0);
+    END IF;
+END $$;
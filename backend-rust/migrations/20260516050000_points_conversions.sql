@@ -0,0 +1,51 @@
+-- =====================================================
+-- Migration: points conversions
+-- =====================================================
+-- Records points converted from partner loyalty programs into ours
+-- (`POST /api/loyalty/admin/convert-points`).
+--
+--   points_transaction_type  gains 'converted', so converted points are
+--                            distinguishable from ordinary admin awards
+--
+--   points_conversions       one row per converted external balance,
+--                            keeping the external amount, the rate that
+--                            was applied and the admin who ran it.
+--                            UNIQUE (source_program, external_reference)
+--                            is what makes a conversion idempotent: the
+--                            same partner statement can never be credited
+--                            twice, however often the request is retried.
+--
+-- Rates come from `POINTS_CONVERSION_RATES`; the rate used is stored on
+-- the row so later rate changes don't rewrite history.
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+ALTER TYPE "public"."points_transaction_type" ADD VALUE IF NOT EXISTS 'converted';
+
+CREATE TABLE IF NOT EXISTS "public"."points_conversions" (
+    "id" UUID NOT NULL DEFAULT gen_random_uuid(),
+    "user_id" UUID NOT NULL,
+    "source_program" VARCHAR(50) NOT NULL,
+    "external_reference" VARCHAR(100) NOT NULL,
+    "external_points" BIGINT NOT NULL,
+    "rate" NUMERIC(12, 6) NOT NULL,
+    "points_awarded" INTEGER NOT NULL,
+    "transaction_id" UUID,
+    "converted_by" UUID,
+    "created_at" TIMESTAMPTZ(6) NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT "points_conversions_pkey" PRIMARY KEY ("id"),
+    CONSTRAINT "points_conversions_user_id_fkey" FOREIGN KEY ("user_id")
+        REFERENCES "public"."users"("id") ON DELETE CASCADE,
+    CONSTRAINT "points_conversions_transaction_id_fkey" FOREIGN KEY ("transaction_id")
+        REFERENCES "public"."points_transactions"("id") ON DELETE SET NULL,
+    CONSTRAINT "points_conversions_converted_by_fkey" FOREIGN KEY ("converted_by")
+        REFERENCES "public"."users"("id") ON DELETE SET NULL,
+    CONSTRAINT "uq_points_conversions_source_reference"
+        UNIQUE ("source_program", "external_reference"),
+    CONSTRAINT "chk_points_conversions_positive"
+        CHECK ("external_points" > 0 AND "rate" > 0 AND "points_awarded" > 0)
+);
+
+CREATE INDEX IF NOT EXISTS "idx_points_conversions_user_id"
+    ON "public"."points_conversions" ("user_id");
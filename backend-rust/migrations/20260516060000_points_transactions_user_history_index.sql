@@ -0,0 +1,16 @@
+-- =====================================================
+-- Migration: points history index
+-- =====================================================
+-- `GET /api/loyalty/transactions` reads a member's newest transactions
+-- within the configured history window:
+--
+--   WHERE user_id = $1 AND created_at >= $2 ORDER BY created_at DESC
+--
+-- With only the single-column `user_id` and `created_at` indexes, a
+-- member with a very long history forces a sort of every row they own.
+-- The composite index serves the window and the ordering directly.
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+CREATE INDEX IF NOT EXISTS "idx_points_transactions_user_created_at"
+    ON "public"."points_transactions" ("user_id", "created_at" DESC);
@@ -0,0 +1,19 @@
+-- =====================================================
+-- Migration: refresh token authenticated_at
+-- =====================================================
+-- Records when the session behind each refresh token last had its
+-- credentials entered (login or `POST /api/auth/reauth`).
+--
+--   refresh_tokens.authenticated_at  copied onto the rotated row on every
+--                                    refresh and into the access token's
+--                                    `last_auth_at` claim, which sensitive
+--                                    admin actions check. Admin sessions
+--                                    also end a fixed time after it.
+--
+-- Existing rows get NOW(); those sessions keep working but their access
+-- tokens only carry `last_auth_at` from the next refresh on.
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+ALTER TABLE "public"."refresh_tokens"
+    ADD COLUMN IF NOT EXISTS "authenticated_at" TIMESTAMPTZ(6) NOT NULL DEFAULT NOW();
@@ -0,0 +1,23 @@
+-- =====================================================
+-- Migration: membership code version
+-- =====================================================
+-- Lets a member replace a lost card without changing their membership ID
+-- (`POST /api/membership/regenerate-code`).
+--
+--   user_profiles.membership_code_version     part of the signed code
+--                                             printed on the card / QR.
+--                                             Bumping it invalidates
+--                                             every earlier code while
+--                                             membership_id stays as is.
+--
+--   user_profiles.membership_code_rotated_at  last regeneration, used to
+--                                             rate-limit regeneration
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+ALTER TABLE "public"."user_profiles"
+    ADD COLUMN IF NOT EXISTS "membership_code_version" INTEGER NOT NULL DEFAULT 1;
+
+ALTER TABLE "public"."user_profiles"
+    ADD COLUMN IF NOT EXISTS "membership_code_rotated_at" TIMESTAMPTZ(6);
@@ -0,0 +1,56 @@
+-- =====================================================
+-- Migration: points refund adjustments
+-- =====================================================
+-- Records proportional points reversals for partially refunded bookings
+-- (`POST /api/loyalty/admin/partial-adjust`).
+--
+--   points_transaction_type     gains 'refund_adjustment', so refund
+--                               reversals are distinguishable from
+--                               ordinary admin deductions
+--
+--   points_refund_adjustments   one row per refund, keeping the fraction
+--                               that was refunded and what it took back
+--                               from the original award.
+--                               UNIQUE (refund_reference) is what makes an
+--                               adjustment idempotent: the same refund can
+--                               never be deducted twice, however often the
+--                               request is retried.
+--
+-- The sum of adjustments against one original transaction never exceeds
+-- that transaction's points (or nights); the handler enforces this while
+-- holding a lock on the original transaction row.
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+ALTER TYPE "public"."points_transaction_type" ADD VALUE IF NOT EXISTS 'refund_adjustment';
+
+CREATE TABLE IF NOT EXISTS "public"."points_refund_adjustments" (
+    "id" UUID NOT NULL DEFAULT gen_random_uuid(),
+    "user_id" UUID NOT NULL,
+    "original_transaction_id" UUID NOT NULL,
+    "refund_reference" VARCHAR(100) NOT NULL,
+    "refunded_fraction" NUMERIC(12, 6) NOT NULL,
+    "points_deducted" INTEGER NOT NULL,
+    "nights_deducted" INTEGER NOT NULL DEFAULT 0,
+    "transaction_id" UUID,
+    "adjusted_by" UUID,
+    "created_at" TIMESTAMPTZ(6) NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT "points_refund_adjustments_pkey" PRIMARY KEY ("id"),
+    CONSTRAINT "points_refund_adjustments_user_id_fkey" FOREIGN KEY ("user_id")
+        REFERENCES "public"."users"("id") ON DELETE CASCADE,
+    CONSTRAINT "points_refund_adjustments_original_transaction_id_fkey"
+        FOREIGN KEY ("original_transaction_id")
+        REFERENCES "public"."points_transactions"("id") ON DELETE CASCADE,
+    CONSTRAINT "points_refund_adjustments_transaction_id_fkey" FOREIGN KEY ("transaction_id")
+        REFERENCES "public"."points_transactions"("id") ON DELETE SET NULL,
+    CONSTRAINT "points_refund_adjustments_adjusted_by_fkey" FOREIGN KEY ("adjusted_by")
+        REFERENCES "public"."users"("id") ON DELETE SET NULL,
+    CONSTRAINT "uq_points_refund_adjustments_reference" UNIQUE ("refund_reference"),
+    CONSTRAINT "chk_points_refund_adjustments_amounts"
+        CHECK ("refunded_fraction" > 0 AND "refunded_fraction" <= 1
+               AND "points_deducted" >= 0 AND "nights_deducted" >= 0)
+);
+
+CREATE INDEX IF NOT EXISTS "idx_points_refund_adjustments_original"
+    ON "public"."points_refund_adjustments" ("original_transaction_id");
@@ -0,0 +1,35 @@
+-- =====================================================
+-- Migration: stay milestones
+-- =====================================================
+-- Marks the stay-award transactions that marketing treats specially.
+--
+--   points_transactions.stay_milestone   'first_stay' on a member's first
+--                                        earned_stay, 'win_back' on a
+--                                        stay after a long enough gap
+--                                        (LOYALTY_WIN_BACK_AFTER_DAYS),
+--                                        NULL otherwise
+--
+-- The partial unique index allows one first_stay per member, so two
+-- concurrent first-stay awards can't both be flagged.
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+ALTER TABLE "public"."points_transactions"
+    ADD COLUMN IF NOT EXISTS "stay_milestone" VARCHAR(20);
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'points_transactions_stay_milestone_check'
+    ) THEN
+        ALTER TABLE "public"."points_transactions"
+            ADD CONSTRAINT "points_transactions_stay_milestone_check"
+            CHECK ("stay_milestone" IN ('first_stay', 'win_back'));
+    END IF;
+END $$;
+
+CREATE UNIQUE INDEX IF NOT EXISTS "idx_points_transactions_first_stay"
+    ON "public"."points_transactions" ("user_id")
+    WHERE "stay_milestone" = 'first_stay';
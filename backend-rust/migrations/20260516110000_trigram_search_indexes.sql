@@ -0,0 +1,53 @@
+-- =====================================================
+-- Migration: trigram indexes for admin partial-text search
+-- =====================================================
+-- The admin member and coupon searches match `column ILIKE '%term%'`.
+-- A leading wildcard can't use a btree index, so every search was a
+-- sequential scan over users/user_profiles/coupons.
+--
+-- GIN indexes with `gin_trgm_ops` (pg_trgm) serve ILIKE with wildcards
+-- on both sides for terms of three or more characters, which is why the
+-- handlers reject shorter terms (SEARCH_MIN_LENGTH, see
+-- `utils::search`). Results are unchanged — the predicates are the same
+-- ILIKE comparisons; only the plan changes.
+--
+-- The member searches look in users and user_profiles; the handlers
+-- collect matching ids per table (`u.id IN (... UNION ...)`) so each
+-- table's OR can be answered from its own indexes.
+--
+-- ## Idempotency
+--
+-- `CREATE EXTENSION IF NOT EXISTS` and `CREATE INDEX IF NOT EXISTS`, so a
+-- partial apply followed by a re-run is a no-op.
+-- =====================================================
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- ----- Members -----------------------------------------------------------
+
+CREATE INDEX IF NOT EXISTS "idx_users_email_trgm"
+    ON "public"."users" USING gin ("email" gin_trgm_ops);
+
+-- `GET /loyalty/admin/users` also matches the member's UUID text
+CREATE INDEX IF NOT EXISTS "idx_users_id_text_trgm"
+    ON "public"."users" USING gin (("id"::text) gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS "idx_user_profiles_first_name_trgm"
+    ON "public"."user_profiles" USING gin ("first_name" gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS "idx_user_profiles_last_name_trgm"
+    ON "public"."user_profiles" USING gin ("last_name" gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS "idx_user_profiles_membership_id_trgm"
+    ON "public"."user_profiles" USING gin ("membership_id" gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS "idx_user_profiles_phone_trgm"
+    ON "public"."user_profiles" USING gin ("phone" gin_trgm_ops);
+
+-- ----- Coupons -----------------------------------------------------------
+
+CREATE INDEX IF NOT EXISTS "idx_coupons_code_trgm"
+    ON "public"."coupons" USING gin ("code" gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS "idx_coupons_name_trgm"
+    ON "public"."coupons" USING gin ("name" gin_trgm_ops);
@@ -0,0 +1,42 @@
+-- =====================================================
+-- Migration: user account merges
+-- =====================================================
+-- Records duplicate accounts folded into another one by an admin
+-- (`POST /api/admin/users/merge`): the source account's points
+-- transactions, coupons, bookings and nights move to the target, and the
+-- source is soft-deleted.
+--
+--   user_account_merges   one row per merge, with what was moved.
+--                         UNIQUE (merge_reference) makes a merge
+--                         idempotent: retrying the same request returns
+--                         the recorded result instead of merging again.
+--                         UNIQUE (source_user_id) because a merged
+--                         account is deleted and can't be merged twice.
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+CREATE TABLE IF NOT EXISTS "public"."user_account_merges" (
+    "id" UUID NOT NULL DEFAULT gen_random_uuid(),
+    "merge_reference" VARCHAR(100) NOT NULL,
+    "source_user_id" UUID NOT NULL,
+    "target_user_id" UUID NOT NULL,
+    "merged_by" UUID,
+    "summary" JSONB NOT NULL DEFAULT '{}',
+    "created_at" TIMESTAMPTZ(6) NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT "user_account_merges_pkey" PRIMARY KEY ("id"),
+    CONSTRAINT "user_account_merges_source_user_id_fkey" FOREIGN KEY ("source_user_id")
+        REFERENCES "public"."users"("id") ON DELETE CASCADE,
+    CONSTRAINT "user_account_merges_target_user_id_fkey" FOREIGN KEY ("target_user_id")
+        REFERENCES "public"."users"("id") ON DELETE CASCADE,
+    CONSTRAINT "user_account_merges_merged_by_fkey" FOREIGN KEY ("merged_by")
+        REFERENCES "public"."users"("id") ON DELETE SET NULL,
+    CONSTRAINT "uq_user_account_merges_reference" UNIQUE ("merge_reference"),
+    CONSTRAINT "uq_user_account_merges_source" UNIQUE ("source_user_id"),
+    CONSTRAINT "chk_user_account_merges_distinct"
+        CHECK ("source_user_id" <> "target_user_id")
+);
+
+CREATE INDEX IF NOT EXISTS "idx_user_account_merges_target"
+    ON "public"."user_account_merges" ("target_user_id");
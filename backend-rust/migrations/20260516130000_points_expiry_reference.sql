@@ -0,0 +1,18 @@
+-- =====================================================
+-- Migration: points expiry reference
+-- =====================================================
+-- Points expiration (`POST /api/loyalty/admin/expire-points` and the
+-- scheduled expiry worker) writes one negative `expired` transaction per
+-- expired earning, with `reference_id` set to the earning's id. This index
+-- guarantees an earning is expired at most once, even if an admin run
+-- and a scheduled run overlap.
+--
+-- `expired` rows written before this migration have no reference and are
+-- not covered.
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+CREATE UNIQUE INDEX IF NOT EXISTS "idx_points_transactions_expired_reference"
+    ON "public"."points_transactions" ("reference_id")
+    WHERE "type" = 'expired' AND "reference_id" IS NOT NULL;
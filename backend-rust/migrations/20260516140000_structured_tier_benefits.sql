@@ -0,0 +1,87 @@
+-- =====================================================
+-- Migration: structured tier benefits
+-- =====================================================
+-- `tiers.benefits` was free-form JSON. It now has a fixed shape
+-- (`models::tier::TierBenefits`):
+--
+--   description       string, optional
+--   discount_percent  number, 0-100
+--   free_nights       integer >= 0
+--   late_checkout     boolean
+--   perks             array of strings
+--   extra             object, optional; anything not covered above
+--
+-- Existing rows are coerced the same way the application reads legacy
+-- JSON (`TierBenefits::from_json`): `discount` becomes `discount_percent`,
+-- numeric strings are parsed, a lone perk string becomes a one-item list,
+-- a bare array is taken as the perks, a bare string as the description,
+-- and every other key moves into `extra`.
+--
+-- Idempotent: a row already in the structured shape is rewritten to
+-- itself.
+-- =====================================================
+
+WITH legacy AS (
+    SELECT
+        id,
+        CASE jsonb_typeof(benefits)
+            WHEN 'object' THEN benefits
+            WHEN 'array' THEN jsonb_build_object('perks', benefits)
+            WHEN 'string' THEN jsonb_build_object('description', benefits)
+            ELSE '{}'::jsonb
+        END AS b
+    FROM "public"."tiers"
+),
+fields AS (
+    SELECT
+        id,
+        b,
+        COALESCE(b->'discount_percent', b->'discount') AS discount,
+        b->'free_nights' AS free_nights,
+        (b - ARRAY['description', 'discount_percent', 'discount', 'free_nights',
+                   'late_checkout', 'perks', 'extra'])
+            || CASE WHEN jsonb_typeof(b->'extra') = 'object' THEN b->'extra' ELSE '{}'::jsonb END
+            AS extra
+    FROM legacy
+)
+UPDATE "public"."tiers" t
+SET benefits =
+    jsonb_build_object(
+        'discount_percent', LEAST(GREATEST(COALESCE(
+            CASE
+                WHEN jsonb_typeof(f.discount) = 'number' THEN (f.discount #>> '{}')::numeric
+                WHEN jsonb_typeof(f.discount) = 'string'
+                     AND btrim(f.discount #>> '{}') ~ '^[0-9]+(\.[0-9]+)?%?$'
+                    THEN rtrim(btrim(f.discount #>> '{}'), '%')::numeric
+            END, 0), 0), 100),
+        'free_nights', GREATEST(COALESCE(
+            CASE
+                WHEN jsonb_typeof(f.free_nights) = 'number' THEN floor((f.free_nights #>> '{}')::numeric)
+                WHEN jsonb_typeof(f.free_nights) = 'string'
+                     AND btrim(f.free_nights #>> '{}') ~ '^[0-9]+(\.[0-9]+)?$'
+                    THEN floor(btrim(f.free_nights #>> '{}')::numeric)
+            END, 0), 0)::int,
+        'late_checkout', CASE jsonb_typeof(f.b->'late_checkout')
+            WHEN 'boolean' THEN (f.b->'late_checkout')::boolean
+            WHEN 'string' THEN lower(f.b->>'late_checkout') = 'true'
+            ELSE false
+        END,
+        'perks', CASE jsonb_typeof(f.b->'perks')
+            WHEN 'array' THEN jsonb_path_query_array(
+                f.b->'perks', '$[*] ? (@.type() == "string" && @ != "")'
+            )
+            WHEN 'string' THEN jsonb_build_array(f.b->'perks')
+            ELSE '[]'::jsonb
+        END
+    )
+    || CASE WHEN jsonb_typeof(f.b->'description') = 'string'
+            THEN jsonb_build_object('description', f.b->'description')
+            ELSE '{}'::jsonb END
+    || CASE WHEN f.extra <> '{}'::jsonb
+            THEN jsonb_build_object('extra', f.extra)
+            ELSE '{}'::jsonb END
+FROM fields f
+WHERE t.id = f.id;
+
+ALTER TABLE "public"."tiers" ALTER COLUMN "benefits" SET DEFAULT
+    '{"discount_percent": 0, "free_nights": 0, "late_checkout": false, "perks": []}'::jsonb;
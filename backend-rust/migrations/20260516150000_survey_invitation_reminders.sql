@@ -0,0 +1,22 @@
+-- =====================================================
+-- Migration: survey invitation reminders
+-- =====================================================
+-- A background job reminds members who were invited to a survey and
+-- haven't completed it (`services::survey::send_survey_reminders`).
+--
+--   survey_invitations.reminder_sent_at   set when the reminder goes out;
+--                                          each invitation gets at most
+--                                          one.
+--
+-- The partial index covers the job's scan: open invitations that haven't
+-- been reminded yet.
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+ALTER TABLE "public"."survey_invitations"
+    ADD COLUMN IF NOT EXISTS "reminder_sent_at" TIMESTAMP(6);
+
+CREATE INDEX IF NOT EXISTS "idx_survey_invitations_reminder_due"
+    ON "public"."survey_invitations" (COALESCE("sent_at", "created_at"))
+    WHERE "reminder_sent_at" IS NULL AND "status" IN ('pending', 'sent', 'viewed');
@@ -0,0 +1,188 @@
+-- =====================================================
+-- Migration: tier downgrade grace period
+-- =====================================================
+-- Members can keep their tier for a configurable number of days after
+-- their nights drop below it (`LOYALTY_TIER_DOWNGRADE_GRACE_DAYS`).
+--
+--   user_loyalty.tier_downgrade_eligible_at   when a pending downgrade may
+--                                              be applied; NULL when none
+--                                              is pending.
+--
+-- `recalculate_user_tier_by_nights` gains a `p_grace_days` argument
+-- (default 0, so existing one-argument callers keep working):
+--
+--   * upgrades and unchanged tiers apply as before and clear any pending
+--     downgrade (the member has earned their tier back);
+--   * a downgrade with no pending one is applied immediately when
+--     p_grace_days is 0, otherwise scheduled p_grace_days from now and
+--     the current tier kept;
+--   * a pending downgrade is honoured by every caller: the tier is kept
+--     until tier_downgrade_eligible_at, then lowered and the column
+--     cleared.
+--
+-- The partial index covers the sweep that applies due downgrades
+-- (`services::loyalty::apply_due_tier_downgrades`).
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+ALTER TABLE "public"."user_loyalty"
+    ADD COLUMN IF NOT EXISTS "tier_downgrade_eligible_at" TIMESTAMPTZ(6);
+
+CREATE INDEX IF NOT EXISTS "idx_user_loyalty_tier_downgrade_due"
+    ON "public"."user_loyalty" ("tier_downgrade_eligible_at")
+    WHERE "tier_downgrade_eligible_at" IS NOT NULL;
+
+-- The argument list changes, so the old signature has to go first;
+-- otherwise one-argument calls would be ambiguous between the two.
+DROP FUNCTION IF EXISTS recalculate_user_tier_by_nights(UUID);
+
+CREATE OR REPLACE FUNCTION recalculate_user_tier_by_nights(
+  p_user_id UUID,
+  p_grace_days INTEGER DEFAULT 0
+)
+RETURNS TABLE (
+  new_tier_id UUID,
+  new_tier_name VARCHAR(50),
+  tier_changed BOOLEAN
+) AS $$
+DECLARE
+  v_total_nights INTEGER;
+  v_current_tier_id UUID;
+  v_current_tier_name VARCHAR(50);
+  v_current_sort_order INTEGER;
+  v_downgrade_eligible_at TIMESTAMPTZ;
+  v_new_tier_id UUID;
+  v_new_tier_name VARCHAR(50);
+  v_new_sort_order INTEGER;
+  v_tier_changed BOOLEAN := FALSE;
+BEGIN
+  -- Get user's current total nights, tier and any pending downgrade
+  SELECT ul.total_nights, ul.tier_id, ul.tier_downgrade_eligible_at, t.name, t.sort_order
+  INTO v_total_nights, v_current_tier_id, v_downgrade_eligible_at,
+       v_current_tier_name, v_current_sort_order
+  FROM user_loyalty ul
+  LEFT JOIN tiers t ON t.id = ul.tier_id
+  WHERE ul.user_id = p_user_id
+  FOR UPDATE OF ul;
+
+  IF NOT FOUND THEN
+    RAISE EXCEPTION 'User loyalty record not found for user_id: %', p_user_id;
+  END IF;
+
+  -- Find the appropriate tier based on total nights
+  -- Select the highest tier where min_nights <= user's total_nights
+  SELECT t.id, t.name, t.sort_order
+  INTO v_new_tier_id, v_new_tier_name, v_new_sort_order
+  FROM tiers t
+  WHERE t.is_active = TRUE
+    AND t.min_nights <= COALESCE(v_total_nights, 0)
+  ORDER BY t.min_nights DESC, t.sort_order DESC
+  LIMIT 1;
+
+  IF NOT FOUND THEN
+    -- If no tier found, assign Bronze (lowest tier)
+    SELECT t.id, t.name, t.sort_order
+    INTO v_new_tier_id, v_new_tier_name, v_new_sort_order
+    FROM tiers t
+    WHERE t.is_active = TRUE
+    ORDER BY t.sort_order ASC
+    LIMIT 1;
+  END IF;
+
+  IF v_current_sort_order IS NOT NULL AND v_new_sort_order < v_current_sort_order THEN
+    -- Downgrade: keep the current tier while a grace period runs
+    IF v_downgrade_eligible_at IS NULL AND COALESCE(p_grace_days, 0) > 0 THEN
+      UPDATE user_loyalty
+      SET tier_downgrade_eligible_at = NOW() + make_interval(days => p_grace_days),
+          updated_at = NOW()
+      WHERE user_id = p_user_id;
+
+      INSERT INTO user_audit_log (user_id, action, details, created_at)
+      VALUES (
+        p_user_id,
+        'tier_downgrade_scheduled',
+        jsonb_build_object(
+          'current_tier_id', v_current_tier_id,
+          'pending_tier_id', v_new_tier_id,
+          'pending_tier_name', v_new_tier_name,
+          'total_nights', v_total_nights,
+          'eligible_at', NOW() + make_interval(days => p_grace_days)
+        ),
+        NOW()
+      );
+
+      RETURN QUERY SELECT v_current_tier_id, v_current_tier_name, FALSE;
+      RETURN;
+    END IF;
+
+    IF v_downgrade_eligible_at IS NOT NULL AND v_downgrade_eligible_at > NOW() THEN
+      RETURN QUERY SELECT v_current_tier_id, v_current_tier_name, FALSE;
+      RETURN;
+    END IF;
+
+    UPDATE user_loyalty
+    SET tier_id = v_new_tier_id,
+        tier_downgrade_eligible_at = NULL,
+        tier_updated_at = NOW(),
+        updated_at = NOW()
+    WHERE user_id = p_user_id;
+
+    INSERT INTO user_audit_log (user_id, action, details, created_at)
+    VALUES (
+      p_user_id,
+      'tier_downgrade_by_nights',
+      jsonb_build_object(
+        'old_tier_id', v_current_tier_id,
+        'new_tier_id', v_new_tier_id,
+        'new_tier_name', v_new_tier_name,
+        'total_nights', v_total_nights,
+        'grace_ended_at', v_downgrade_eligible_at
+      ),
+      NOW()
+    );
+
+    RETURN QUERY SELECT v_new_tier_id, v_new_tier_name, TRUE;
+    RETURN;
+  END IF;
+
+  -- Upgrade or no change: the member is at or above their tier again, so
+  -- any pending downgrade is cancelled
+  IF v_downgrade_eligible_at IS NOT NULL THEN
+    UPDATE user_loyalty
+    SET tier_downgrade_eligible_at = NULL,
+        updated_at = NOW()
+    WHERE user_id = p_user_id;
+  END IF;
+
+  -- Check if tier changed
+  IF v_current_tier_id IS DISTINCT FROM v_new_tier_id THEN
+    v_tier_changed := TRUE;
+
+    -- Update user's tier
+    UPDATE user_loyalty
+    SET tier_id = v_new_tier_id,
+        tier_updated_at = NOW(),
+        updated_at = NOW()
+    WHERE user_id = p_user_id;
+
+    INSERT INTO user_audit_log (user_id, action, details, created_at)
+    VALUES (
+      p_user_id,
+      'tier_upgrade_by_nights',
+      jsonb_build_object(
+        'old_tier_id', v_current_tier_id,
+        'new_tier_id', v_new_tier_id,
+        'new_tier_name', v_new_tier_name,
+        'total_nights', v_total_nights,
+        'upgrade_reason', 'nights_threshold_met'
+      ),
+      NOW()
+    );
+  END IF;
+
+  RETURN QUERY SELECT v_new_tier_id, v_new_tier_name, v_tier_changed;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION recalculate_user_tier_by_nights IS 'Recalculates and updates user tier based on total_nights. Downgrades wait out p_grace_days (default 0) via user_loyalty.tier_downgrade_eligible_at. Returns the tier the user holds afterwards and whether it changed.';
@@ -0,0 +1,41 @@
+-- =====================================================
+-- Migration: webhook outbox
+-- =====================================================
+-- Events for the PMS (`services::webhook`) are written here in the same
+-- transaction as the change they describe, and delivered by a background
+-- worker once committed. A rolled-back change therefore never produces an
+-- event, and an event is never lost to a crash between commit and send.
+--
+--   webhook_events.event_type        e.g. slip.verified, slip.rejected
+--   webhook_events.payload           JSON body sent to WEBHOOK_URL
+--   webhook_events.attempts          deliveries tried so far
+--   webhook_events.next_attempt_at   earliest time of the next try; also
+--                                    pushed forward while a worker holds
+--                                    the event
+--   webhook_events.delivered_at      set on a 2xx response
+--   webhook_events.failed_at         set when WEBHOOK_MAX_ATTEMPTS is used
+--                                    up without one
+--   webhook_events.last_error        why the latest attempt failed
+--
+-- The partial index covers the worker's scan for events still due.
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+CREATE TABLE IF NOT EXISTS "public"."webhook_events" (
+    "id" UUID NOT NULL DEFAULT gen_random_uuid(),
+    "event_type" VARCHAR(64) NOT NULL,
+    "payload" JSONB NOT NULL,
+    "attempts" INTEGER NOT NULL DEFAULT 0,
+    "next_attempt_at" TIMESTAMPTZ(6) NOT NULL DEFAULT NOW(),
+    "delivered_at" TIMESTAMPTZ(6),
+    "failed_at" TIMESTAMPTZ(6),
+    "last_error" TEXT,
+    "created_at" TIMESTAMPTZ(6) NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT "webhook_events_pkey" PRIMARY KEY ("id")
+);
+
+CREATE INDEX IF NOT EXISTS "idx_webhook_events_pending"
+    ON "public"."webhook_events" ("next_attempt_at")
+    WHERE "delivered_at" IS NULL AND "failed_at" IS NULL;
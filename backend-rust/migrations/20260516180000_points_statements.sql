@@ -0,0 +1,39 @@
+-- =====================================================
+-- Migration: monthly points statements
+-- =====================================================
+-- One row per member per statement month, written by
+-- `services::loyalty::send_points_statements` before the email goes out.
+-- The unique (user_id, period_start) key is what makes each member get at
+-- most one statement per month, however many instances run the job.
+--
+--   points_statements.period_start    first day of the statement month
+--   points_statements.period_end      first day of the following month
+--   points_statements.points_earned   positive transactions in the month
+--   points_statements.points_redeemed redemptions in the month
+--   points_statements.points_expiring earnings expiring soon after sending
+--   points_statements.current_points  balance when the statement was made
+--   points_statements.tier_name       tier when the statement was made
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+CREATE TABLE IF NOT EXISTS "public"."points_statements" (
+    "id" UUID NOT NULL DEFAULT gen_random_uuid(),
+    "user_id" UUID NOT NULL,
+    "period_start" DATE NOT NULL,
+    "period_end" DATE NOT NULL,
+    "points_earned" INTEGER NOT NULL DEFAULT 0,
+    "points_redeemed" INTEGER NOT NULL DEFAULT 0,
+    "points_expiring" INTEGER NOT NULL DEFAULT 0,
+    "current_points" INTEGER NOT NULL DEFAULT 0,
+    "tier_name" VARCHAR(50),
+    "created_at" TIMESTAMPTZ(6) NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT "points_statements_pkey" PRIMARY KEY ("id"),
+    CONSTRAINT "points_statements_user_period_key" UNIQUE ("user_id", "period_start"),
+    CONSTRAINT "points_statements_user_id_fkey" FOREIGN KEY ("user_id")
+        REFERENCES "public"."users"("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_points_statements_period"
+    ON "public"."points_statements" ("period_start");
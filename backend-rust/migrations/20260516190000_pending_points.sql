@@ -0,0 +1,56 @@
+-- =====================================================
+-- Migration: pending points for unverified stays
+-- =====================================================
+-- With LOYALTY_PENDING_POINTS_ENABLED, a booking completed before its
+-- payment slip is verified earns its nights straight away but holds its
+-- points here instead of crediting `user_loyalty.current_points`. The
+-- points are released (as an `earned_stay` transaction) when a slip for
+-- the booking is verified, or by the points expiry worker once
+-- `clears_at` passes. Only released points can be redeemed.
+--
+--   pending_points.booking_id      the stay the points are for; at most
+--                                  one hold per booking
+--   pending_points.status          'pending' until released, then
+--                                  'available'
+--   pending_points.clears_at       auto-release time; NULL waits for the
+--                                  slip however long it takes
+--   pending_points.transaction_id  the points_transactions row written on
+--                                  release
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+CREATE TABLE IF NOT EXISTS "public"."pending_points" (
+    "id" UUID NOT NULL DEFAULT gen_random_uuid(),
+    "user_id" UUID NOT NULL,
+    "booking_id" UUID,
+    "points" INTEGER NOT NULL,
+    "reference_id" VARCHAR(255),
+    "status" VARCHAR(20) NOT NULL DEFAULT 'pending',
+    "clears_at" TIMESTAMPTZ(6),
+    "cleared_at" TIMESTAMPTZ(6),
+    "transaction_id" UUID,
+    "created_at" TIMESTAMPTZ(6) NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT "pending_points_pkey" PRIMARY KEY ("id"),
+    CONSTRAINT "pending_points_points_check" CHECK ("points" > 0),
+    CONSTRAINT "pending_points_status_check" CHECK ("status" IN ('pending', 'available')),
+    CONSTRAINT "pending_points_user_id_fkey" FOREIGN KEY ("user_id")
+        REFERENCES "public"."users"("id") ON DELETE CASCADE,
+    CONSTRAINT "pending_points_booking_id_fkey" FOREIGN KEY ("booking_id")
+        REFERENCES "public"."bookings"("id") ON DELETE SET NULL,
+    CONSTRAINT "pending_points_transaction_id_fkey" FOREIGN KEY ("transaction_id")
+        REFERENCES "public"."points_transactions"("id") ON DELETE SET NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "idx_pending_points_booking"
+    ON "public"."pending_points" ("booking_id")
+    WHERE "booking_id" IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS "idx_pending_points_user_pending"
+    ON "public"."pending_points" ("user_id")
+    WHERE "status" = 'pending';
+
+CREATE INDEX IF NOT EXISTS "idx_pending_points_due"
+    ON "public"."pending_points" ("clears_at")
+    WHERE "status" = 'pending' AND "clears_at" IS NOT NULL;
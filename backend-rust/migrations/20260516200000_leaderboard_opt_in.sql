@@ -0,0 +1,16 @@
+-- =====================================================
+-- Migration: leaderboard opt-in
+-- =====================================================
+-- Members only appear on GET /api/loyalty/leaderboard once they opt in
+-- through their profile (PUT /api/users/me with `leaderboardOptIn`).
+-- Existing members default to opted out.
+--
+--   user_profiles.leaderboard_opt_in   TRUE to be listed (masked name
+--                                      and tier only)
+
+ALTER TABLE user_profiles
+    ADD COLUMN IF NOT EXISTS leaderboard_opt_in BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_user_profiles_leaderboard_opt_in
+    ON user_profiles (user_id)
+    WHERE leaderboard_opt_in = TRUE;
@@ -0,0 +1,38 @@
+-- =====================================================
+-- Migration: notification quiet hours
+-- =====================================================
+-- Members can set a daily quiet window in their own time zone
+-- (PUT /api/notifications/quiet-hours). A non-critical notification
+-- created inside the window is still stored, but its live push is held in
+-- held_notifications until the window ends; the release worker then sends
+-- each member's held notifications as one batch, or drops the push if it
+-- has gone stale. High priority and security notifications are never
+-- held.
+--
+--   notification_quiet_hours.start_time / end_time   local wall-clock
+--                                                    times; start > end
+--                                                    spans midnight
+--   notification_quiet_hours.timezone                IANA name, e.g.
+--                                                    'Asia/Bangkok'
+--   held_notifications.release_at                    when the window ends
+
+CREATE TABLE IF NOT EXISTS notification_quiet_hours (
+    user_id     UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    enabled     BOOLEAN NOT NULL DEFAULT TRUE,
+    start_time  TIME NOT NULL,
+    end_time    TIME NOT NULL,
+    timezone    TEXT NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT notification_quiet_hours_window_check CHECK (start_time <> end_time)
+);
+
+CREATE TABLE IF NOT EXISTS held_notifications (
+    notification_id UUID PRIMARY KEY REFERENCES notifications(id) ON DELETE CASCADE,
+    user_id         UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    release_at      TIMESTAMPTZ NOT NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_held_notifications_release_at
+    ON held_notifications (release_at);
@@ -0,0 +1,20 @@
+-- =====================================================
+-- Migration: password rotation
+-- =====================================================
+-- Records when each user's password was last set, so admin (and,
+-- optionally, customer) passwords can be given a maximum age
+-- (ADMIN_PASSWORD_MAX_AGE_DAYS / CUSTOMER_PASSWORD_MAX_AGE_DAYS). A login
+-- with an expired password gets a 403 password_expired and no tokens
+-- until the password is changed.
+--
+--   users.password_changed_at   set on registration, password change and
+--                               reset; existing rows are backfilled with
+--                               created_at, the best known bound
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS password_changed_at TIMESTAMPTZ;
+
+UPDATE users
+SET password_changed_at = COALESCE(created_at, NOW())
+WHERE password_changed_at IS NULL;
+
+ALTER TABLE users ALTER COLUMN password_changed_at SET DEFAULT NOW();
@@ -0,0 +1,36 @@
+-- =====================================================
+-- Migration: booking notes
+-- =====================================================
+-- Free-text notes staff attach to a booking
+-- (POST /api/bookings/:id/notes). Each note has a visibility scope:
+-- 'internal' notes are for staff only, 'guest' notes are also shown to the
+-- guest who owns the booking. GET /api/bookings/:id/notes filters by the
+-- requester's role in the query itself, so an internal note is never
+-- loaded for a guest.
+--
+--   booking_notes.visibility   'internal' or 'guest'
+--   booking_notes.author_id    the staff member who wrote the note; kept
+--                              (NULL) if their account is deleted
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+CREATE TABLE IF NOT EXISTS "public"."booking_notes" (
+    "id" UUID NOT NULL DEFAULT gen_random_uuid(),
+    "booking_id" UUID NOT NULL,
+    "author_id" UUID,
+    "visibility" VARCHAR(20) NOT NULL DEFAULT 'internal',
+    "body" TEXT NOT NULL,
+    "created_at" TIMESTAMPTZ(6) NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT "booking_notes_pkey" PRIMARY KEY ("id"),
+    CONSTRAINT "booking_notes_visibility_check" CHECK ("visibility" IN ('internal', 'guest')),
+    CONSTRAINT "booking_notes_body_check" CHECK (length(btrim("body")) > 0),
+    CONSTRAINT "booking_notes_booking_id_fkey" FOREIGN KEY ("booking_id")
+        REFERENCES "public"."bookings"("id") ON DELETE CASCADE,
+    CONSTRAINT "booking_notes_author_id_fkey" FOREIGN KEY ("author_id")
+        REFERENCES "public"."users"("id") ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS "idx_booking_notes_booking"
+    ON "public"."booking_notes" ("booking_id", "created_at");
@@ -0,0 +1,15 @@
+-- =====================================================
+-- Migration: refresh token sessions
+-- =====================================================
+-- Ties each refresh token to the login session it belongs to, so a single
+-- session can be ended from GET/DELETE /api/auth/sessions without touching
+-- the user's other devices.
+--
+--   refresh_tokens.session_id   the Redis session id the token was issued
+--                               under; copied onto the rotated row on every
+--                               refresh. NULL for tokens issued before
+--                               sessions were tracked.
+
+ALTER TABLE refresh_tokens ADD COLUMN IF NOT EXISTS session_id UUID;
+
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_session_id ON refresh_tokens(session_id);
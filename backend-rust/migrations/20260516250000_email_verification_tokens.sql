@@ -0,0 +1,29 @@
+-- =====================================================
+-- Migration: email verification tokens
+-- =====================================================
+-- Codes emailed to confirm a user's address (`EmailVerificationToken` in
+-- models/password_reset.rs). POST /api/auth/resend-verification issues a
+-- fresh one and marks the user's earlier codes used, so only the newest
+-- code in the inbox works.
+--
+--   new_email   the address the code was sent to
+--   code        SHA-256 (hex) of the code; the code itself only travels
+--               in the email
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+CREATE TABLE IF NOT EXISTS "public"."email_verification_tokens" (
+    "id" UUID NOT NULL DEFAULT gen_random_uuid(),
+    "user_id" UUID NOT NULL REFERENCES "public"."users"("id") ON DELETE CASCADE,
+    "new_email" VARCHAR(255) NOT NULL,
+    "code" VARCHAR(64) NOT NULL,
+    "expires_at" TIMESTAMPTZ(6) NOT NULL,
+    "used" BOOLEAN DEFAULT false,
+    "created_at" TIMESTAMPTZ(6) DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT "email_verification_tokens_pkey" PRIMARY KEY ("id")
+);
+
+CREATE INDEX IF NOT EXISTS "idx_email_verification_tokens_user_id"
+    ON "public"."email_verification_tokens"("user_id");
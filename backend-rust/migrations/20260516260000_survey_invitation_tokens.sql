@@ -0,0 +1,34 @@
+-- =====================================================
+-- Migration: survey invitation links
+-- =====================================================
+-- POST /api/surveys/:id/invitations/send emails each recipient a link
+-- carrying a random token; GET /api/surveys/invite/:token shows the
+-- survey and POST /api/surveys/invite/:token/submit records the answers
+-- without signing in, so guests without an account can respond.
+--
+--   survey_invitations.token_hash    SHA-256 (hex) of the link token; the
+--                                    token itself only travels in the
+--                                    email
+--   survey_invitations.guest_email   recipient when the invitation isn't
+--                                    for a member (user_id is NULL)
+--   survey_invitations.used_at       set when the link is submitted; a
+--                                    link works once
+--   survey_responses.invitation_id   the invitation a response came in
+--                                    through, so guest responses (no
+--                                    user_id) can be traced
+--
+-- Idempotent so a partial application can be re-run safely.
+-- =====================================================
+
+ALTER TABLE "public"."survey_invitations"
+    ADD COLUMN IF NOT EXISTS "token_hash" VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS "guest_email" VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS "used_at" TIMESTAMP(6);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "idx_survey_invitations_token_hash"
+    ON "public"."survey_invitations" ("token_hash")
+    WHERE "token_hash" IS NOT NULL;
+
+ALTER TABLE "public"."survey_responses"
+    ADD COLUMN IF NOT EXISTS "invitation_id" UUID
+        REFERENCES "public"."survey_invitations"("id") ON DELETE SET NULL;
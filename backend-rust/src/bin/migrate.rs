@@ -0,0 +1,119 @@
+//! Database migration binary, run separately from the web server.
+//!
+//! Applies the migrations embedded in the backend (`db::migrations`, built
+//! from `migrations/` - the same `MIGRATOR` the server uses) to the
+//! database in `DATABASE_URL`, then exits:
+//!
+//! ```text
+//! cargo run --bin migrate            # apply pending migrations
+//! cargo run --bin migrate -- --check # only report whether any are pending
+//! ```
+//!
+//! Exit status is 0 when the schema is up to date afterwards and 1
+//! otherwise (bad configuration, unreachable database, a failed migration,
+//! or with `--check`, anything pending), so CI can gate a deploy on it.
+//!
+//! Safe to run before every deploy: applied migrations are skipped, and
+//! sqlx holds a Postgres advisory lock while migrating, so two concurrent
+//! runs (or a run racing a server with `RUN_MIGRATIONS_ON_STARTUP=true`)
+//! apply each migration once. Pair it with `RUN_MIGRATIONS_ON_STARTUP=false`
+//! so the server only checks the schema instead of changing it.
+
+use std::process::ExitCode;
+use std::time::Duration;
+
+use loyalty_backend::{config::Settings, db};
+
+/// Applying migrations needs a single connection; the second covers the
+/// bookkeeping queries sqlx runs alongside it.
+const MIGRATE_MAX_CONNECTIONS: u32 = 2;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let _ = dotenvy::dotenv();
+
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "loyalty_backend=info,sqlx=warn".into()),
+        )
+        .with_target(false)
+        .init();
+
+    let check_only = match parse_args(std::env::args().skip(1)) {
+        Ok(check_only) => check_only,
+        Err(message) => {
+            eprintln!("migrate: {message}");
+            eprintln!("usage: migrate [--check]");
+            return ExitCode::FAILURE;
+        },
+    };
+
+    match run(check_only).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            // `{:#}` walks the anyhow cause chain down to the sqlx error.
+            eprintln!("migrate: {e:#}");
+            ExitCode::FAILURE
+        },
+    }
+}
+
+/// Whether `--check` was given; anything else is an error
+fn parse_args(args: impl Iterator<Item = String>) -> Result<bool, String> {
+    let mut check_only = false;
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check_only = true,
+            other => return Err(format!("unknown argument '{other}'")),
+        }
+    }
+    Ok(check_only)
+}
+
+async fn run(check_only: bool) -> anyhow::Result<()> {
+    let config = Settings::new()?;
+
+    let db_config = db::DbConfig {
+        max_connections: MIGRATE_MAX_CONNECTIONS,
+        min_connections: 0,
+        acquire_timeout: Duration::from_secs(config.database.connection_timeout_secs),
+        idle_timeout: Duration::from_secs(60),
+    };
+    let db = db::init_pool_with_url(&config.database.url, Some(db_config)).await?;
+
+    let result = if check_only {
+        db::migrations::ensure_migrations_applied(db.pool()).await
+    } else {
+        db::migrations::run_migrations(db.pool()).await
+    };
+    db.close().await;
+    result?;
+
+    let applied = db::migrations::get_available_migrations().len();
+    if check_only {
+        println!("migrate: all {applied} migrations are applied");
+    } else {
+        println!("migrate: database is up to date ({applied} migrations)");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_args;
+
+    fn args(list: &[&str]) -> impl Iterator<Item = String> {
+        list.iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn parses_check_flag() {
+        assert_eq!(parse_args(args(&[])), Ok(false));
+        assert_eq!(parse_args(args(&["--check"])), Ok(true));
+        assert!(parse_args(args(&["--force"])).is_err());
+    }
+}
@@ -78,6 +78,19 @@ pub struct DatabaseConfig {
     /// Connection timeout in seconds
     #[serde(default = "default_connection_timeout")]
     pub connection_timeout_secs: u64,
+
+    /// Whether the server applies pending migrations when it starts
+    /// (default true). With this off, migrations are applied by the
+    /// `migrate` binary and the server refuses to start while any are
+    /// pending. Sourced from `RUN_MIGRATIONS_ON_STARTUP`.
+    #[serde(default = "default_run_migrations_on_startup")]
+    pub run_migrations_on_startup: bool,
+
+    /// Queries timed with `db::timed_query` that take at least this many
+    /// milliseconds are logged at WARN (default 500, 0 disables). Sourced
+    /// from `SLOW_QUERY_THRESHOLD_MS`.
+    #[serde(default = "default_slow_query_threshold_ms")]
+    pub slow_query_threshold_ms: u64,
 }
 
 fn default_database_url() -> String {
@@ -96,6 +109,14 @@ fn default_connection_timeout() -> u64 {
     30
 }
 
+fn default_run_migrations_on_startup() -> bool {
+    true
+}
+
+fn default_slow_query_threshold_ms() -> u64 {
+    500
+}
+
 impl Default for DatabaseConfig {
     fn default() -> Self {
         Self {
@@ -103,6 +124,8 @@ impl Default for DatabaseConfig {
             max_connections: default_max_connections(),
             min_connections: default_min_connections(),
             connection_timeout_secs: default_connection_timeout(),
+            run_migrations_on_startup: default_run_migrations_on_startup(),
+            slow_query_threshold_ms: default_slow_query_threshold_ms(),
         }
     }
 }
@@ -158,6 +181,47 @@ pub struct AuthConfig {
     /// Refresh token expiration in seconds (default: 7 days)
     #[serde(default = "default_refresh_token_expiry")]
     pub refresh_token_expiry_secs: u64,
+
+    /// Access token expiration for admin and super_admin users, in seconds
+    /// (default: 10 minutes). Never longer than `access_token_expiry_secs`.
+    #[serde(default = "default_admin_access_token_expiry")]
+    pub admin_access_token_expiry_secs: u64,
+
+    /// Refresh token expiration for admin and super_admin users, in seconds
+    /// (default: 8 hours). "Remember me" does not extend it.
+    #[serde(default = "default_admin_refresh_token_expiry")]
+    pub admin_refresh_token_expiry_secs: u64,
+
+    /// How recently a user must have entered their credentials for
+    /// sensitive admin actions (role changes, deletions), in seconds
+    /// (default: 5 minutes)
+    #[serde(default = "default_reauth_max_age")]
+    pub reauth_max_age_secs: u64,
+
+    /// When a refresh request also carries an access token, how long ago
+    /// that token may have expired, in seconds (default 0: not checked).
+    /// Refreshing with just the refresh cookie is unaffected.
+    #[serde(default)]
+    pub refresh_grace_secs: u64,
+
+    /// Days an admin or super_admin password stays valid before it must be
+    /// changed at the next login (default 0: never expires)
+    #[serde(default)]
+    pub admin_password_max_age_days: u32,
+
+    /// The same for customer passwords (default 0: never expires)
+    #[serde(default)]
+    pub customer_password_max_age_days: u32,
+
+    /// How long a passwordless sign-in link stays valid, in seconds
+    /// (default: 15 minutes)
+    #[serde(default = "default_magic_link_ttl")]
+    pub magic_link_ttl_secs: u64,
+
+    /// Minimum gap between verification emails to the same address, in
+    /// seconds (default: 60)
+    #[serde(default = "default_verification_resend_cooldown")]
+    pub verification_resend_cooldown_secs: u64,
 }
 
 fn default_jwt_secret() -> String {
@@ -180,6 +244,63 @@ fn default_refresh_token_expiry() -> u64 {
     604800 // 7 days
 }
 
+fn default_admin_access_token_expiry() -> u64 {
+    600 // 10 minutes
+}
+
+fn default_admin_refresh_token_expiry() -> u64 {
+    28800 // 8 hours
+}
+
+fn default_reauth_max_age() -> u64 {
+    300 // 5 minutes
+}
+
+fn default_magic_link_ttl() -> u64 {
+    900 // 15 minutes
+}
+
+fn default_verification_resend_cooldown() -> u64 {
+    60
+}
+
+/// Whether `role` gets the shorter admin session lifetimes
+pub fn is_admin_role(role: &str) -> bool {
+    matches!(role, "admin" | "super_admin")
+}
+
+impl AuthConfig {
+    /// Access token lifetime in seconds for a user with `role`, where
+    /// `default_secs` is what a customer would get for this login
+    pub fn access_token_expiry_for(&self, role: &str, default_secs: u64) -> u64 {
+        if is_admin_role(role) {
+            self.admin_access_token_expiry_secs.min(default_secs)
+        } else {
+            default_secs
+        }
+    }
+
+    /// Maximum password age in days for a user with `role`; 0 means the
+    /// password never has to be rotated
+    pub fn password_max_age_days_for(&self, role: &str) -> u32 {
+        if is_admin_role(role) {
+            self.admin_password_max_age_days
+        } else {
+            self.customer_password_max_age_days
+        }
+    }
+
+    /// Refresh token lifetime in seconds for a user with `role`, where
+    /// `default_secs` is what a customer would get for this login
+    pub fn refresh_token_expiry_for(&self, role: &str, default_secs: u64) -> u64 {
+        if is_admin_role(role) {
+            self.admin_refresh_token_expiry_secs.min(default_secs)
+        } else {
+            default_secs
+        }
+    }
+}
+
 impl Default for AuthConfig {
     fn default() -> Self {
         Self {
@@ -188,6 +309,14 @@ impl Default for AuthConfig {
             session_secret: default_session_secret(),
             access_token_expiry_secs: default_access_token_expiry(),
             refresh_token_expiry_secs: default_refresh_token_expiry(),
+            admin_access_token_expiry_secs: default_admin_access_token_expiry(),
+            admin_refresh_token_expiry_secs: default_admin_refresh_token_expiry(),
+            reauth_max_age_secs: default_reauth_max_age(),
+            refresh_grace_secs: 0,
+            admin_password_max_age_days: 0,
+            customer_password_max_age_days: 0,
+            magic_link_ttl_secs: default_magic_link_ttl(),
+            verification_resend_cooldown_secs: default_verification_resend_cooldown(),
         }
     }
 }
@@ -245,6 +374,53 @@ impl LineOAuthConfig {
 pub struct OAuthConfig {
     pub google: GoogleOAuthConfig,
     pub line: LineOAuthConfig,
+
+    /// Comma-separated origins (e.g. `https://app.example.com`) that the
+    /// post-login `return_url` may point at in addition to the frontend
+    /// origin. Sourced from `OAUTH_ALLOWED_REDIRECT_ORIGINS`.
+    #[serde(default)]
+    pub allowed_redirect_origins: String,
+}
+
+impl OAuthConfig {
+    /// Normalized origins from `allowed_redirect_origins`.
+    pub fn redirect_origins(&self) -> Result<Vec<String>, String> {
+        parse_origins(&self.allowed_redirect_origins)
+    }
+}
+
+/// Origin (`scheme://host[:port]`) of an http(s) URL, normalized so that
+/// host case and default ports don't affect comparisons. Returns `None`
+/// for anything that isn't an absolute http(s) URL.
+pub fn url_origin(url: &str) -> Option<String> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host().is_none() {
+        return None;
+    }
+    Some(parsed.origin().ascii_serialization())
+}
+
+/// Parse a comma-separated list of http(s) origins. Blank entries are
+/// skipped; anything carrying a path, query or credentials is rejected so
+/// a typo can't quietly widen the allowlist.
+pub fn parse_origins(list: &str) -> Result<Vec<String>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let invalid = || format!("'{}' is not a valid http(s) origin", entry);
+            let parsed = reqwest::Url::parse(entry).map_err(|_| invalid())?;
+            if !parsed.username().is_empty()
+                || parsed.password().is_some()
+                || parsed.path() != "/"
+                || parsed.query().is_some()
+                || parsed.fragment().is_some()
+            {
+                return Err(invalid());
+            }
+            url_origin(entry).ok_or_else(invalid)
+        })
+        .collect()
 }
 
 /// SMTP email configuration
@@ -322,16 +498,44 @@ impl ImapConfig {
 pub struct EmailConfig {
     pub smtp: SmtpConfig,
     pub imap: ImapConfig,
+
+    /// Test-mode capture: store rendered emails in `captured_emails`
+    /// instead of sending them (see `services::email::CapturingEmailService`).
+    /// Off by default and rejected by `validate()` in production. Sourced
+    /// from `EMAIL_CAPTURE_MODE`.
+    #[serde(default)]
+    pub capture_mode: bool,
 }
 
 /// SlipOK payment integration configuration
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct SlipokConfig {
     /// SlipOK branch ID
     pub branch_id: Option<String>,
 
     /// SlipOK API key
     pub api_key: Option<String>,
+
+    /// Seconds a definitive SlipOK verdict is cached in Redis by slip image
+    /// fingerprint, so re-verifying the same slip doesn't use quota (see
+    /// `SlipOKService::with_result_cache`). `0` disables the cache.
+    /// Sourced from `SLIPOK_RESULT_CACHE_TTL_SECS`.
+    #[serde(default = "default_slipok_result_cache_ttl_secs")]
+    pub result_cache_ttl_secs: u64,
+}
+
+fn default_slipok_result_cache_ttl_secs() -> u64 {
+    86_400
+}
+
+impl Default for SlipokConfig {
+    fn default() -> Self {
+        Self {
+            branch_id: None,
+            api_key: None,
+            result_cache_ttl_secs: default_slipok_result_cache_ttl_secs(),
+        }
+    }
 }
 
 impl SlipokConfig {
@@ -340,6 +544,67 @@ impl SlipokConfig {
     }
 }
 
+/// Outbound webhooks to the PMS (see `services::webhook`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookConfig {
+    /// Endpoint events are POSTed to; webhooks are off while unset.
+    /// Sourced from `WEBHOOK_URL`.
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Key for the `X-Webhook-Signature` HMAC. Sourced from
+    /// `WEBHOOK_SECRET`.
+    #[serde(default)]
+    pub secret: Option<String>,
+
+    /// Delivery attempts before an event is given up on (default 8).
+    /// Sourced from `WEBHOOK_MAX_ATTEMPTS`.
+    #[serde(default = "default_webhook_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Seconds between outbox polls (default 10). Sourced from
+    /// `WEBHOOK_POLL_INTERVAL_SECS`.
+    #[serde(default = "default_webhook_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+
+    /// Per-request timeout in seconds (default 10). Sourced from
+    /// `WEBHOOK_TIMEOUT_SECS`.
+    #[serde(default = "default_webhook_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_webhook_max_attempts() -> u32 {
+    8
+}
+
+fn default_webhook_poll_interval_secs() -> u64 {
+    10
+}
+
+fn default_webhook_timeout_secs() -> u64 {
+    10
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            secret: None,
+            max_attempts: default_webhook_max_attempts(),
+            poll_interval_secs: default_webhook_poll_interval_secs(),
+            timeout_secs: default_webhook_timeout_secs(),
+        }
+    }
+}
+
+impl WebhookConfig {
+    pub fn is_configured(&self) -> bool {
+        self.url
+            .as_deref()
+            .is_some_and(|url| !url.trim().is_empty())
+    }
+}
+
 /// PromptPay QR code configuration
 ///
 /// Holds the merchant Tax ID (or registered phone number) used to generate
@@ -359,137 +624,1227 @@ impl PromptPayConfig {
     }
 }
 
-/// Server configuration
+/// Database seeding configuration
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct SeedConfig {
+    /// Path to a JSON or YAML file with sample data (tiers, coupons, users,
+    /// surveys). When unset, the built-in development fixtures are used.
+    /// Sourced from the `SEED_FILE` environment variable.
+    pub file: Option<String>,
+}
+
+/// Loyalty earning rules
 #[derive(Debug, Clone, Deserialize)]
-pub struct ServerConfig {
-    /// Server port
-    #[serde(default = "default_port")]
-    pub port: u16,
+pub struct LoyaltyConfig {
+    /// Minimum stay length (in nights) required to earn points.
+    ///
+    /// A stay shorter than this still earns its nights — tier progress is
+    /// unaffected — but awards zero points. Applies to
+    /// `POST /loyalty/admin/award-spending-with-nights` and the points
+    /// awarded when a booking is completed. Awards that carry no nights
+    /// (spending-only, deductions) are not stays and are left alone.
+    ///
+    /// `0` (the default) disables the rule. Sourced from the
+    /// `LOYALTY_MIN_NIGHTS_FOR_POINTS` environment variable.
+    #[serde(default)]
+    pub min_nights_for_points: u32,
+
+    /// Fixed points credited per night by `POST /loyalty/admin/award-nights`,
+    /// independent of spend, for properties that reward stays rather than
+    /// revenue. The points go through the same `award_points` call as the
+    /// nights, so both land in one transaction; `min_nights_for_points`
+    /// still applies.
+    ///
+    /// `0` (the default) keeps nights-only awards at zero points. Sourced
+    /// from the `LOYALTY_POINTS_PER_NIGHT` environment variable.
+    #[serde(default)]
+    pub points_per_night: u32,
 
-    /// Server host
-    #[serde(default = "default_host")]
-    pub host: String,
+    /// Bonus points for the referring member when a referral qualifies
+    /// (the referee completes their first stay). `0` (the default) records
+    /// referrals without awarding anything. Sourced from the
+    /// `LOYALTY_REFERRER_BONUS_POINTS` environment variable.
+    #[serde(default)]
+    pub referrer_bonus_points: u32,
 
-    /// Frontend URL for CORS
-    #[serde(default = "default_frontend_url")]
-    pub frontend_url: String,
+    /// Bonus points for the referred member on the same qualification.
+    /// Sourced from the `LOYALTY_REFEREE_BONUS_POINTS` environment variable.
+    #[serde(default)]
+    pub referee_bonus_points: u32,
 
-    /// Log level
-    #[serde(default = "default_log_level")]
-    pub log_level: String,
+    /// Days without a stay after which a member's next stay counts as a
+    /// `win_back` (see `services::loyalty::record_stay_milestone`). A
+    /// member's first stay is always flagged `first_stay`. `0` (the
+    /// default) disables win-back detection. Sourced from the
+    /// `LOYALTY_WIN_BACK_AFTER_DAYS` environment variable.
+    #[serde(default)]
+    pub win_back_after_days: u32,
+
+    /// Seconds between runs of the background points expiry worker, which
+    /// does what `POST /loyalty/admin/expire-points` does on demand.
+    /// Defaults to hourly; `0` disables the worker and leaves expiration
+    /// to the admin endpoint. Sourced from the `POINTS_EXPIRY_INTERVAL_SECS`
+    /// environment variable.
+    #[serde(default = "default_points_expiry_interval_secs")]
+    pub points_expiry_interval_secs: u64,
+
+    /// Largest points change (award or deduction) one admin operation may
+    /// make without `confirmLarge: true` or the super_admin role, as a
+    /// guard against typos. `0` removes the cap. Sourced from the
+    /// `LOYALTY_MAX_POINTS_PER_AWARD` environment variable.
+    #[serde(default = "default_max_points_per_award")]
+    pub max_points_per_award: u32,
+
+    /// Same cap for nights. Sourced from the `LOYALTY_MAX_NIGHTS_PER_AWARD`
+    /// environment variable.
+    #[serde(default = "default_max_nights_per_award")]
+    pub max_nights_per_award: u32,
+
+    /// Days a member keeps their tier after their nights fall below it
+    /// (e.g. an admin deducts nights). The downgrade is scheduled in
+    /// `user_loyalty.tier_downgrade_eligible_at` and applied once it's due;
+    /// earning the tier back in the meantime cancels it. `0` (the default)
+    /// downgrades immediately. Sourced from the
+    /// `LOYALTY_TIER_DOWNGRADE_GRACE_DAYS` environment variable.
+    #[serde(default)]
+    pub tier_downgrade_grace_days: u32,
+
+    /// Hold the points of a booking completed before its payment slip is
+    /// verified as pending (see `services::loyalty::hold_pending_points`).
+    /// The nights count straight away; the points become available, and
+    /// redeemable, once a slip for the booking is verified. `false` (the
+    /// default) credits them on completion. Sourced from the
+    /// `LOYALTY_PENDING_POINTS_ENABLED` environment variable.
+    #[serde(default)]
+    pub pending_points_enabled: bool,
+
+    /// Days after which pending points are released even without a
+    /// verified slip, by the points expiry worker. `0` waits for the slip
+    /// indefinitely. Sourced from the `LOYALTY_PENDING_POINTS_CLEAR_DAYS`
+    /// environment variable.
+    #[serde(default = "default_pending_points_clear_days")]
+    pub pending_points_clear_days: u32,
+
+    /// Seconds the active tier list is kept in Redis under
+    /// `loyalty:tiers:active` (see `LoyaltyService::get_tiers_cached`).
+    /// The admin tier endpoints drop the entry on every change, so this
+    /// only bounds staleness from edits made outside the API. `0` disables
+    /// the cache. Sourced from the `LOYALTY_TIERS_CACHE_TTL_SECS`
+    /// environment variable.
+    #[serde(default = "default_tiers_cache_ttl_secs")]
+    pub tiers_cache_ttl_secs: u64,
+
+    /// Currency value of one point when redeemed (`POST /loyalty/redeem`),
+    /// for members whose tier sets no `points_redemption_rate` in its
+    /// benefits (default 0.1, i.e. 10 points per currency unit). Must be
+    /// positive. Sourced from the `LOYALTY_POINTS_REDEMPTION_RATE`
+    /// environment variable.
+    #[serde(default = "default_points_redemption_rate")]
+    pub points_redemption_rate: f64,
+
+    /// Members recalculated per transaction by the bulk tier recalculation
+    /// (`POST /loyalty/admin/recalculate-all`), unless the request sets its
+    /// own. Sourced from `LOYALTY_TIER_RECALC_BATCH_SIZE`.
+    #[serde(default = "default_tier_recalc_batch_size")]
+    pub tier_recalc_batch_size: u32,
 }
 
-fn default_port() -> u16 {
-    4001
+fn default_points_redemption_rate() -> f64 {
+    0.1
 }
 
-fn default_host() -> String {
-    "0.0.0.0".to_string()
+impl LoyaltyConfig {
+    /// [`Self::points_redemption_rate`] as a decimal, for pricing
+    /// redemptions
+    pub fn default_redemption_rate(&self) -> rust_decimal::Decimal {
+        rust_decimal::Decimal::try_from(self.points_redemption_rate)
+            .unwrap_or(rust_decimal::Decimal::ZERO)
+    }
 }
 
-fn default_frontend_url() -> String {
-    "http://localhost:4001".to_string()
+fn default_tiers_cache_ttl_secs() -> u64 {
+    300
 }
 
-fn default_log_level() -> String {
-    "info".to_string()
+fn default_tier_recalc_batch_size() -> u32 {
+    500
 }
 
-impl Default for ServerConfig {
+fn default_pending_points_clear_days() -> u32 {
+    14
+}
+
+fn default_points_expiry_interval_secs() -> u64 {
+    3600
+}
+
+fn default_max_points_per_award() -> u32 {
+    100_000
+}
+
+fn default_max_nights_per_award() -> u32 {
+    60
+}
+
+impl Default for LoyaltyConfig {
     fn default() -> Self {
         Self {
-            port: default_port(),
-            host: default_host(),
-            frontend_url: default_frontend_url(),
-            log_level: default_log_level(),
+            min_nights_for_points: 0,
+            points_per_night: 0,
+            referrer_bonus_points: 0,
+            referee_bonus_points: 0,
+            win_back_after_days: 0,
+            points_expiry_interval_secs: default_points_expiry_interval_secs(),
+            max_points_per_award: default_max_points_per_award(),
+            max_nights_per_award: default_max_nights_per_award(),
+            tier_downgrade_grace_days: 0,
+            pending_points_enabled: false,
+            pending_points_clear_days: default_pending_points_clear_days(),
+            tiers_cache_ttl_secs: default_tiers_cache_ttl_secs(),
+            points_redemption_rate: default_points_redemption_rate(),
+            tier_recalc_batch_size: default_tier_recalc_batch_size(),
         }
     }
 }
 
-/// Security configuration
+/// Coupon creation rules
 #[derive(Debug, Clone, Deserialize)]
-pub struct SecurityConfig {
-    /// Maximum file upload size in bytes
-    #[serde(default = "default_max_file_size")]
-    pub max_file_size: usize,
+pub struct CouponConfig {
+    /// Currency given to a new coupon that doesn't name one. Must be in
+    /// `allowed_currencies`. Sourced from `COUPON_DEFAULT_CURRENCY`.
+    #[serde(default = "default_coupon_currency")]
+    pub default_currency: String,
+
+    /// Comma-separated ISO 4217 codes a coupon may be denominated in
+    /// (e.g. `THB,USD`). Sourced from `COUPON_ALLOWED_CURRENCIES`; defaults
+    /// to the default currency alone.
+    #[serde(default = "default_coupon_currency")]
+    pub allowed_currencies: String,
+
+    /// Which coupons may be combined on one transaction
+    /// (`POST /coupons/redeem-multiple`). Sourced from `COUPON_STACKING`.
+    #[serde(default)]
+    pub stacking: CouponStacking,
 
-    /// Rate limit window in milliseconds
-    #[serde(default = "default_rate_limit_window")]
-    pub rate_limit_window_ms: u64,
+    /// Prefix of auto-generated coupon codes (e.g. `PROMO-`), used when a
+    /// coupon is created without a code. Empty by default. Sourced from
+    /// `COUPON_CODE_PREFIX`.
+    #[serde(default)]
+    pub code_prefix: String,
+
+    /// Number of random characters after the prefix (default 8). Sourced
+    /// from `COUPON_CODE_LENGTH`.
+    #[serde(default = "default_coupon_code_length")]
+    pub code_length: usize,
+
+    /// Characters the random part is drawn from. Uppercased, and
+    /// look-alike characters (`0 O 1 I L`) are always removed (see
+    /// [`CouponConfig::code_charset`]). Sourced from `COUPON_CODE_CHARSET`.
+    #[serde(default = "default_coupon_code_charset")]
+    pub code_charset: String,
+
+    /// Flip a coupon to `exhausted` in the same statement that counts the
+    /// redemption reaching its `usage_limit` (default true). Redemptions
+    /// past the limit are refused either way. Sourced from
+    /// `COUPON_EXHAUST_AT_LIMIT`.
+    #[serde(default = "default_coupon_exhaust_at_limit")]
+    pub exhaust_at_limit: bool,
+
+    /// The hotel's UTC offset (`+HH:MM`, default `+07:00`). Coupon dates
+    /// given as local days (`valid_from_date` / `valid_until_date`) start
+    /// and end at local midnight in this offset. Sourced from
+    /// `COUPON_UTC_OFFSET`.
+    #[serde(default = "default_coupon_utc_offset")]
+    pub utc_offset: String,
+
+    /// Seconds between runs of the background coupon expiry sweep, which
+    /// expires coupons past `valid_until`, exhausts those at their usage
+    /// limit and expires their unusable user coupons. Defaults to hourly;
+    /// `0` disables it. Sourced from `COUPON_EXPIRY_SWEEP_INTERVAL_SECS`.
+    #[serde(default = "default_coupon_expiry_sweep_interval_secs")]
+    pub expiry_sweep_interval_secs: u64,
+}
 
-    /// Maximum requests per rate limit window
-    #[serde(default = "default_rate_limit_max")]
-    pub rate_limit_max_requests: u32,
+/// Which coupons may be redeemed together on one transaction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CouponStacking {
+    /// One coupon per transaction
+    #[default]
+    None,
+    /// Any number of coupons, but no two of the same coupon type
+    OnePerType,
+    /// Any combination
+    Unlimited,
 }
 
-fn default_max_file_size() -> usize {
-    5_242_880 // 5MB
+impl CouponStacking {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CouponStacking::None => "none",
+            CouponStacking::OnePerType => "one_per_type",
+            CouponStacking::Unlimited => "unlimited",
+        }
+    }
 }
 
-fn default_rate_limit_window() -> u64 {
-    900_000 // 15 minutes
+impl CouponConfig {
+    /// The allowed currency codes, uppercased, blanks skipped
+    pub fn allowed_currencies(&self) -> Vec<String> {
+        self.allowed_currencies
+            .split(',')
+            .map(|c| c.trim().to_ascii_uppercase())
+            .filter(|c| !c.is_empty())
+            .collect()
+    }
+
+    /// Whether `currency` (any case) is one of the allowed codes
+    pub fn is_allowed_currency(&self, currency: &str) -> bool {
+        let currency = currency.trim().to_ascii_uppercase();
+        self.allowed_currencies().iter().any(|c| *c == currency)
+    }
+
+    /// [`Self::utc_offset`] parsed, e.g. `+07:00` or `-05:30`
+    pub fn local_offset(&self) -> Result<chrono::FixedOffset, String> {
+        let offset = self.utc_offset.trim();
+        let invalid = || format!("'{}' is not a +HH:MM offset", offset);
+        let (sign, rest) = match offset.as_bytes().first() {
+            Some(b'+') => (1, &offset[1..]),
+            Some(b'-') => (-1, &offset[1..]),
+            _ => return Err(invalid()),
+        };
+        let (hours, minutes) = rest.split_once(':').ok_or_else(invalid)?;
+        let hours: i32 = hours.parse().map_err(|_| invalid())?;
+        let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+        if !(0..=14).contains(&hours) || !(0..60).contains(&minutes) {
+            return Err(invalid());
+        }
+        chrono::FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)
+    }
+
+    /// The characters auto-generated codes are drawn from: the configured
+    /// charset uppercased and deduplicated, keeping only ASCII letters and
+    /// digits that can't be mistaken for one another
+    pub fn code_charset(&self) -> Vec<u8> {
+        let mut charset = Vec::new();
+        for c in self.code_charset.bytes().map(|c| c.to_ascii_uppercase()) {
+            if c.is_ascii_alphanumeric()
+                && !AMBIGUOUS_CODE_CHARS.contains(&c)
+                && !charset.contains(&c)
+            {
+                charset.push(c);
+            }
+        }
+        charset
+    }
 }
 
-fn default_rate_limit_max() -> u32 {
-    10_000
+/// Characters left out of auto-generated coupon codes because they are
+/// easily confused when read aloud or typed from print
+const AMBIGUOUS_CODE_CHARS: &[u8] = b"0O1IL";
+
+/// Shortest random part allowed for auto-generated coupon codes
+const MIN_COUPON_CODE_LENGTH: usize = 4;
+
+/// Width of `coupons.code`
+const MAX_COUPON_CODE_LENGTH: usize = 20;
+
+/// Fewest usable characters an auto-generated code may be drawn from
+const MIN_COUPON_CODE_CHARSET: usize = 10;
+
+fn default_coupon_currency() -> String {
+    "THB".to_string()
 }
 
-impl Default for SecurityConfig {
+fn default_coupon_code_length() -> usize {
+    8
+}
+
+fn default_coupon_code_charset() -> String {
+    "ABCDEFGHJKMNPQRSTUVWXYZ23456789".to_string()
+}
+
+fn default_coupon_exhaust_at_limit() -> bool {
+    true
+}
+
+fn default_coupon_utc_offset() -> String {
+    "+07:00".to_string()
+}
+
+fn default_coupon_expiry_sweep_interval_secs() -> u64 {
+    3600
+}
+
+impl Default for CouponConfig {
     fn default() -> Self {
         Self {
-            max_file_size: default_max_file_size(),
-            rate_limit_window_ms: default_rate_limit_window(),
-            rate_limit_max_requests: default_rate_limit_max(),
+            default_currency: default_coupon_currency(),
+            allowed_currencies: default_coupon_currency(),
+            stacking: CouponStacking::default(),
+            code_prefix: String::new(),
+            code_length: default_coupon_code_length(),
+            code_charset: default_coupon_code_charset(),
+            exhaust_at_limit: default_coupon_exhaust_at_limit(),
+            utc_offset: default_coupon_utc_offset(),
+            expiry_sweep_interval_secs: default_coupon_expiry_sweep_interval_secs(),
         }
     }
 }
 
-/// Main application settings
+/// What account deletion does with a user's non-financial records
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RelatedRecordPolicy {
+    /// Keep the records, detached from the user or marked revoked
+    #[default]
+    Anonymize,
+    /// Remove the records outright
+    Delete,
+}
+
+impl RelatedRecordPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelatedRecordPolicy::Anonymize => "anonymize",
+            RelatedRecordPolicy::Delete => "delete",
+        }
+    }
+}
+
+/// User deletion cascade (see `services::user_deletion`)
 #[derive(Debug, Clone, Default, Deserialize)]
-pub struct Settings {
-    /// Application environment
+pub struct UserDeletionConfig {
+    /// Policy for survey responses/invitations and unused coupons.
+    /// Financial and audit records (points, bookings, redeemed coupons,
+    /// audit logs) are always kept and anonymized regardless. Sourced from
+    /// `USER_DELETION_RELATED_RECORDS` (`anonymize`, the default, or
+    /// `delete`).
     #[serde(default)]
-    pub environment: Environment,
+    pub related_records: RelatedRecordPolicy,
+}
 
-    /// Server configuration
-    #[serde(default)]
-    pub server: ServerConfig,
+/// Admin account merges (see `services::account_merge`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountMergeConfig {
+    /// Whether `POST /admin/users/merge` is available (default true). The
+    /// merged-away account is deleted under `user_deletion.related_records`.
+    /// Sourced from `ACCOUNT_MERGE_ENABLED`.
+    #[serde(default = "default_account_merge_enabled")]
+    pub enabled: bool,
+}
 
-    /// Database configuration
-    #[serde(default)]
-    pub database: DatabaseConfig,
+fn default_account_merge_enabled() -> bool {
+    true
+}
 
-    /// Redis configuration
-    #[serde(default)]
-    pub redis: RedisConfig,
+impl Default for AccountMergeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_account_merge_enabled(),
+        }
+    }
+}
 
-    /// Authentication configuration
-    #[serde(default)]
-    pub auth: AuthConfig,
+/// Admin bulk member export (`GET /admin/members/export`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct MemberExportConfig {
+    /// Exports one admin may start per hour (default 5). `0` disables the
+    /// endpoint. Sourced from `MEMBER_EXPORT_MAX_PER_HOUR`.
+    #[serde(default = "default_member_export_max_per_hour")]
+    pub max_per_hour: u32,
+
+    /// Members read from the database per query while streaming (default
+    /// 1000). Sourced from `MEMBER_EXPORT_BATCH_SIZE`.
+    #[serde(default = "default_member_export_batch_size")]
+    pub batch_size: u32,
+}
 
-    /// OAuth providers configuration
-    #[serde(default)]
-    pub oauth: OAuthConfig,
+fn default_member_export_max_per_hour() -> u32 {
+    5
+}
 
-    /// Email configuration
-    #[serde(default)]
-    pub email: EmailConfig,
+fn default_member_export_batch_size() -> u32 {
+    1000
+}
 
-    /// SlipOK payment configuration
-    #[serde(default)]
-    pub slipok: SlipokConfig,
+impl Default for MemberExportConfig {
+    fn default() -> Self {
+        Self {
+            max_per_hour: default_member_export_max_per_hour(),
+            batch_size: default_member_export_batch_size(),
+        }
+    }
+}
 
-    /// PromptPay QR code configuration
-    #[serde(default)]
-    pub promptpay: PromptPayConfig,
+/// How points balances are shortened for display
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PointsDisplayStyle {
+    /// Every digit, grouped: `12,345`
+    #[default]
+    Full,
+    /// Thousands/millions with one decimal: `12.3K`
+    Compact,
+}
 
-    /// Security configuration
+/// Points display formatting (see `utils::points`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct PointsDisplayConfig {
+    /// `full` (default) or `compact`. Sourced from `POINTS_DISPLAY_STYLE`.
     #[serde(default)]
-    pub security: SecurityConfig,
+    pub style: PointsDisplayStyle,
+
+    /// Locale used when the request has no usable `Accept-Language`
+    /// (default `en-US`). Sourced from `POINTS_DISPLAY_LOCALE`.
+    #[serde(default = "default_points_display_locale")]
+    pub default_locale: String,
 }
 
-impl Settings {
-    /// Load settings from environment variables and optional config files
+fn default_points_display_locale() -> String {
+    "en-US".to_string()
+}
+
+impl Default for PointsDisplayConfig {
+    fn default() -> Self {
+        Self {
+            style: PointsDisplayStyle::default(),
+            default_locale: default_points_display_locale(),
+        }
+    }
+}
+
+/// How far back a member's paginated points history reaches
+///
+/// `GET /loyalty/transactions` only pages through the newest
+/// `max_rows` transactions from the last `max_age_days` days, so the query
+/// stays fast however long the history is. Older transactions are in the
+/// CSV export (`GET /loyalty/transactions/export`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransactionHistoryConfig {
+    /// Oldest transaction shown, in days (default 730). Sourced from
+    /// `TRANSACTION_HISTORY_MAX_AGE_DAYS`.
+    #[serde(default = "default_history_max_age_days")]
+    pub max_age_days: u32,
+
+    /// Most transactions shown (default 10000). Sourced from
+    /// `TRANSACTION_HISTORY_MAX_ROWS`.
+    #[serde(default = "default_history_max_rows")]
+    pub max_rows: u32,
+}
+
+fn default_history_max_age_days() -> u32 {
+    730
+}
+
+fn default_history_max_rows() -> u32 {
+    10_000
+}
+
+impl Default for TransactionHistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_age_days: default_history_max_age_days(),
+            max_rows: default_history_max_rows(),
+        }
+    }
+}
+
+/// Conversion of points from partner loyalty programs
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PointsConversionConfig {
+    /// Comma-separated `program=rate` pairs giving the points awarded per
+    /// external point (e.g. `siam_rewards=0.5,partner_x=2`). Empty (the
+    /// default) allows no conversions. Sourced from `POINTS_CONVERSION_RATES`.
+    #[serde(default)]
+    pub rates: String,
+}
+
+impl PointsConversionConfig {
+    /// The configured rates keyed by lowercase program name
+    pub fn parsed_rates(&self) -> Result<Vec<(String, rust_decimal::Decimal)>, String> {
+        self.rates
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (program, rate) = entry
+                    .split_once('=')
+                    .ok_or_else(|| format!("'{}' is not a program=rate pair", entry))?;
+                let program = program.trim().to_ascii_lowercase();
+                if program.is_empty() {
+                    return Err(format!("'{}' has no program name", entry));
+                }
+                let rate: rust_decimal::Decimal = rate
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("'{}' has an invalid rate", entry))?;
+                if rate <= rust_decimal::Decimal::ZERO {
+                    return Err(format!("'{}' must have a positive rate", entry));
+                }
+                Ok((program, rate))
+            })
+            .collect()
+    }
+
+    /// The rate for `program` (any case), if it is configured
+    pub fn rate_for(&self, program: &str) -> Option<rust_decimal::Decimal> {
+        let program = program.trim().to_ascii_lowercase();
+        self.parsed_rates()
+            .ok()?
+            .into_iter()
+            .find(|(name, _)| *name == program)
+            .map(|(_, rate)| rate)
+    }
+}
+
+/// Points taken back when a booking is partially refunded
+/// (`POST /loyalty/admin/partial-adjust`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct PointsRefundConfig {
+    /// Also take back the same fraction of the nights credited by the
+    /// original transaction, recalculating the member's tier (default
+    /// true). Sourced from `POINTS_REFUND_ADJUST_NIGHTS`.
+    #[serde(default = "default_refund_adjust_nights")]
+    pub adjust_nights: bool,
+}
+
+fn default_refund_adjust_nights() -> bool {
+    true
+}
+
+impl Default for PointsRefundConfig {
+    fn default() -> Self {
+        Self {
+            adjust_nights: default_refund_adjust_nights(),
+        }
+    }
+}
+
+/// Per-user, per-type notification throttling (see
+/// `services::notification::NotificationThrottle`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotificationThrottleConfig {
+    /// Notifications of one type a user may receive per window (default
+    /// 10). `0` disables throttling. Sourced from `NOTIFICATION_THROTTLE_MAX`.
+    #[serde(default = "default_notification_throttle_max")]
+    pub max_per_window: u32,
+
+    /// Length of the throttling window in seconds (default 3600). Sourced
+    /// from `NOTIFICATION_THROTTLE_WINDOW_SECS`.
+    #[serde(default = "default_notification_throttle_window_secs")]
+    pub window_secs: u64,
+
+    /// Comma-separated `type=max` pairs overriding `max_per_window` for
+    /// individual notification types (e.g. `coupon=3,points=20`; `0`
+    /// exempts the type). Sourced from `NOTIFICATION_THROTTLE_LIMITS`.
+    #[serde(default)]
+    pub limits: String,
+}
+
+fn default_notification_throttle_max() -> u32 {
+    10
+}
+
+fn default_notification_throttle_window_secs() -> u64 {
+    3600
+}
+
+impl Default for NotificationThrottleConfig {
+    fn default() -> Self {
+        Self {
+            max_per_window: default_notification_throttle_max(),
+            window_secs: default_notification_throttle_window_secs(),
+            limits: String::new(),
+        }
+    }
+}
+
+impl NotificationThrottleConfig {
+    /// The per-type overrides keyed by lowercase notification type
+    pub fn parsed_limits(&self) -> Result<Vec<(String, u32)>, String> {
+        self.limits
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (kind, max) = entry
+                    .split_once('=')
+                    .ok_or_else(|| format!("'{}' is not a type=max pair", entry))?;
+                let kind = kind.trim().to_ascii_lowercase();
+                if kind.is_empty() {
+                    return Err(format!("'{}' has no notification type", entry));
+                }
+                let max: u32 = max
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("'{}' has an invalid limit", entry))?;
+                Ok((kind, max))
+            })
+            .collect()
+    }
+
+    /// Notifications of `notification_type` allowed per user per window;
+    /// `0` means unthrottled
+    pub fn limit_for(&self, notification_type: &str) -> u32 {
+        let kind = notification_type.trim().to_ascii_lowercase();
+        self.parsed_limits()
+            .ok()
+            .and_then(|limits| {
+                limits
+                    .into_iter()
+                    .find(|(name, _)| *name == kind)
+                    .map(|(_, max)| max)
+            })
+            .unwrap_or(self.max_per_window)
+    }
+}
+
+/// Members' notification quiet hours (see
+/// `services::notification::release_held_notifications`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotificationQuietHoursConfig {
+    /// Seconds between checks for quiet windows that have ended (default
+    /// 60). Sourced from `NOTIFICATION_QUIET_HOURS_INTERVAL_SECS`.
+    #[serde(default = "default_quiet_hours_interval_secs")]
+    pub interval_secs: u64,
+
+    /// A held notification older than this many hours when its window
+    /// ends stays in the inbox but is not pushed (default 12). Sourced
+    /// from `NOTIFICATION_QUIET_HOURS_MAX_DELAY_HOURS`.
+    #[serde(default = "default_quiet_hours_max_delay_hours")]
+    pub max_delay_hours: u32,
+
+    /// Time zone for members who set quiet hours without one (default
+    /// `Asia/Bangkok`). Sourced from `NOTIFICATION_QUIET_HOURS_DEFAULT_TIMEZONE`.
+    #[serde(default = "default_quiet_hours_timezone")]
+    pub default_timezone: String,
+}
+
+fn default_quiet_hours_interval_secs() -> u64 {
+    60
+}
+
+fn default_quiet_hours_max_delay_hours() -> u32 {
+    12
+}
+
+fn default_quiet_hours_timezone() -> String {
+    "Asia/Bangkok".to_string()
+}
+
+impl Default for NotificationQuietHoursConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_quiet_hours_interval_secs(),
+            max_delay_hours: default_quiet_hours_max_delay_hours(),
+            default_timezone: default_quiet_hours_timezone(),
+        }
+    }
+}
+
+/// Admin partial-text search (see `utils::search`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchConfig {
+    /// Shortest search term accepted by the admin member and coupon
+    /// searches, in characters (default 3, the shortest term the trigram
+    /// indexes can serve). Sourced from `SEARCH_MIN_LENGTH`.
+    #[serde(default = "default_search_min_length")]
+    pub min_length: usize,
+}
+
+fn default_search_min_length() -> usize {
+    crate::utils::search::DEFAULT_MIN_SEARCH_LENGTH
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            min_length: default_search_min_length(),
+        }
+    }
+}
+
+/// Reminders for survey invitations left unanswered (see
+/// `services::survey::send_survey_reminders`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct SurveyReminderConfig {
+    /// Hours after an invitation is sent before an unanswered one gets its
+    /// single reminder (default 72). `0` disables reminders. Sourced from
+    /// `SURVEY_REMINDER_DELAY_HOURS`.
+    #[serde(default = "default_survey_reminder_delay_hours")]
+    pub delay_hours: u64,
+
+    /// Seconds between checks for invitations due a reminder (default
+    /// 3600). Sourced from `SURVEY_REMINDER_INTERVAL_SECS`.
+    #[serde(default = "default_survey_reminder_interval_secs")]
+    pub interval_secs: u64,
+}
+
+fn default_survey_reminder_delay_hours() -> u64 {
+    72
+}
+
+fn default_survey_reminder_interval_secs() -> u64 {
+    3600
+}
+
+impl Default for SurveyReminderConfig {
+    fn default() -> Self {
+        Self {
+            delay_hours: default_survey_reminder_delay_hours(),
+            interval_secs: default_survey_reminder_interval_secs(),
+        }
+    }
+}
+
+/// Minimum length of `ANALYTICS_USER_ID_SALT` when pseudonymization is on
+const MIN_ANALYTICS_SALT_LENGTH: usize = 32;
+
+/// Pseudonymous user IDs in the analytics tables (see `services::analytics`)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AnalyticsConfig {
+    /// Record a keyed hash of each user ID instead of the ID itself in
+    /// `coupon_usage_analytics` and `profile_change_analytics` (default
+    /// false). Sourced from `ANALYTICS_PSEUDONYMIZE_USER_IDS`.
+    #[serde(default)]
+    pub pseudonymize_user_ids: bool,
+
+    /// Secret salt keying the hash. Required (at least 32 characters) when
+    /// pseudonymizing; changing it starts every member over with a new
+    /// pseudonym. Sourced from `ANALYTICS_USER_ID_SALT`.
+    #[serde(default)]
+    pub user_id_salt: String,
+}
+
+/// Monthly points statement emails (see
+/// `services::loyalty::send_points_statements`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct PointsStatementConfig {
+    /// Email members a summary of last month's points activity (default
+    /// false). Sourced from `POINTS_STATEMENT_ENABLED`.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Seconds between checks for statements still to send (default
+    /// 3600). Sourced from `POINTS_STATEMENT_INTERVAL_SECS`.
+    #[serde(default = "default_points_statement_interval_secs")]
+    pub interval_secs: u64,
+
+    /// Earnings expiring within this many days are shown as "expiring
+    /// soon" (default 30). Sourced from `POINTS_STATEMENT_EXPIRING_DAYS`.
+    #[serde(default = "default_points_statement_expiring_days")]
+    pub expiring_within_days: u32,
+}
+
+fn default_points_statement_interval_secs() -> u64 {
+    3600
+}
+
+fn default_points_statement_expiring_days() -> u32 {
+    30
+}
+
+impl Default for PointsStatementConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: default_points_statement_interval_secs(),
+            expiring_within_days: default_points_statement_expiring_days(),
+        }
+    }
+}
+
+/// HTTP caching of public responses (see `middleware::cache_control`) and
+/// the shared server-side response cache (see `middleware::response_cache`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpCacheConfig {
+    /// `max-age` for `GET /api/loyalty/tiers`, in seconds (default 300).
+    /// `0` disables caching. Sourced from `CACHE_TIERS_MAX_AGE_SECS`.
+    #[serde(default = "default_tiers_max_age_secs")]
+    pub tiers_max_age_secs: u32,
+
+    /// Comma-separated `scope=seconds` pairs giving how long the Redis
+    /// response cache keeps each route scope's responses (e.g.
+    /// `tiers=60`). Scopes not listed, or listed with `0`, are not cached;
+    /// empty (the default) disables the cache. Sourced from
+    /// `RESPONSE_CACHE_TTLS`.
+    #[serde(default)]
+    pub response_cache_ttls: String,
+}
+
+impl HttpCacheConfig {
+    /// The configured response cache TTLs keyed by lowercase scope
+    pub fn parsed_response_cache_ttls(&self) -> Result<Vec<(String, u64)>, String> {
+        self.response_cache_ttls
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (scope, ttl) = entry
+                    .split_once('=')
+                    .ok_or_else(|| format!("'{}' is not a scope=seconds pair", entry))?;
+                let scope = scope.trim().to_ascii_lowercase();
+                if scope.is_empty() {
+                    return Err(format!("'{}' has no scope name", entry));
+                }
+                let ttl: u64 = ttl
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("'{}' has an invalid number of seconds", entry))?;
+                Ok((scope, ttl))
+            })
+            .collect()
+    }
+}
+
+fn default_tiers_max_age_secs() -> u32 {
+    300
+}
+
+impl Default for HttpCacheConfig {
+    fn default() -> Self {
+        Self {
+            tiers_max_age_secs: default_tiers_max_age_secs(),
+            response_cache_ttls: String::new(),
+        }
+    }
+}
+
+/// Server configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerConfig {
+    /// Server port
+    #[serde(default = "default_port")]
+    pub port: u16,
+
+    /// Server host
+    #[serde(default = "default_host")]
+    pub host: String,
+
+    /// Frontend URL for CORS
+    #[serde(default = "default_frontend_url")]
+    pub frontend_url: String,
+
+    /// Log level
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+
+    /// Log line format. Unset picks by environment: pretty in
+    /// development, JSON elsewhere. Sourced from `LOG_FORMAT`.
+    #[serde(default)]
+    pub log_format: Option<LogFormat>,
+
+    /// After a shutdown signal, how long in-flight requests get to finish
+    /// before the server stops waiting, in seconds. Background workers and
+    /// the database pool close get the same bound each. The default of 25
+    /// sits just under docker compose's 30s stop timeout so we exit before
+    /// being killed. Sourced from `SHUTDOWN_TIMEOUT_SECS`.
+    #[serde(default = "default_shutdown_timeout_secs")]
+    pub shutdown_timeout_secs: u64,
+}
+
+/// How log lines are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable text, for a terminal
+    Pretty,
+    /// One JSON object per line, for log aggregators (Loki, Datadog)
+    Json,
+}
+
+impl LogFormat {
+    /// `LOG_FORMAT` read straight from the environment, for logging set up
+    /// before `Settings::new()` runs. Unset or unrecognised is `None`; the
+    /// settings load rejects an unrecognised value afterwards.
+    pub fn from_env() -> Option<Self> {
+        env::var("LOG_FORMAT").ok()?.parse().ok()
+    }
+
+    /// The explicit format if there is one, else the environment's default
+    pub fn resolve(explicit: Option<Self>, is_development: bool) -> Self {
+        explicit.unwrap_or(if is_development {
+            LogFormat::Pretty
+        } else {
+            LogFormat::Json
+        })
+    }
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("Invalid log format: {}", s)),
+        }
+    }
+}
+
+fn default_port() -> u16 {
+    4001
+}
+
+fn default_host() -> String {
+    "0.0.0.0".to_string()
+}
+
+fn default_frontend_url() -> String {
+    "http://localhost:4001".to_string()
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    25
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            port: default_port(),
+            host: default_host(),
+            frontend_url: default_frontend_url(),
+            log_level: default_log_level(),
+            log_format: None,
+            shutdown_timeout_secs: default_shutdown_timeout_secs(),
+        }
+    }
+}
+
+/// Security configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct SecurityConfig {
+    /// Maximum file upload size in bytes
+    #[serde(default = "default_max_file_size")]
+    pub max_file_size: usize,
+
+    /// Rate limit window in milliseconds
+    #[serde(default = "default_rate_limit_window")]
+    pub rate_limit_window_ms: u64,
+
+    /// Maximum requests per rate limit window
+    #[serde(default = "default_rate_limit_max")]
+    pub rate_limit_max_requests: u32,
+
+    /// Send `X-RateLimit-Limit` / `-Remaining` / `-Reset` on responses from
+    /// rate-limited routes (successful ones included). Defaults to on.
+    /// Sourced from `RATE_LIMIT_HEADERS`.
+    #[serde(default = "default_rate_limit_headers")]
+    pub rate_limit_headers: bool,
+
+    /// What the API rate limiters count requests against: `ip` (default)
+    /// or `user`, which keys authenticated requests on the user id and
+    /// falls back to the IP for anonymous ones. Sourced from
+    /// `RATE_LIMIT_KEY`.
+    #[serde(default)]
+    pub rate_limit_key: RateLimitKeyStrategy,
+
+    /// Comma-separated CIDRs / IPs allowed to reach admin endpoints
+    /// (e.g. `10.8.0.0/24,203.0.113.7`). Empty means no restriction.
+    /// Sourced from `ADMIN_IP_ALLOWLIST`.
+    #[serde(default)]
+    pub admin_ip_allowlist: String,
+
+    /// Comma-separated CIDRs / IPs of reverse proxies whose
+    /// `X-Forwarded-For` header is trusted when resolving the client IP
+    /// for the admin allowlist. Requests from any other peer are judged by
+    /// their TCP address alone. Sourced from `TRUSTED_PROXIES`.
+    #[serde(default)]
+    pub trusted_proxies: String,
+
+    /// Comma-separated path prefixes (e.g. `/api/auth,/api/users`) whose
+    /// cookie-authenticated writes must pass the double-submit CSRF check
+    /// (see `middleware::csrf`). Empty turns the check off. Sourced from
+    /// `CSRF_PROTECTED_PATHS`.
+    #[serde(default)]
+    pub csrf_protected_paths: String,
+}
+
+/// How a rate limiter groups requests into buckets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKeyStrategy {
+    /// One bucket per client IP (the TCP peer)
+    #[default]
+    Ip,
+    /// One bucket per authenticated user; anonymous requests by IP. Keeps
+    /// users behind a shared NAT or corporate proxy from throttling each
+    /// other.
+    User,
+}
+
+/// Parse a comma-separated list of CIDRs or bare IP addresses.
+///
+/// Bare addresses become single-host networks (`/32` or `/128`). Blank
+/// entries are skipped so trailing commas are harmless.
+pub fn parse_ip_networks(list: &str) -> Result<Vec<ipnet::IpNet>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .parse::<ipnet::IpNet>()
+                .or_else(|_| entry.parse::<std::net::IpAddr>().map(ipnet::IpNet::from))
+                .map_err(|_| format!("'{}' is not a valid IP address or CIDR", entry))
+        })
+        .collect()
+}
+
+fn default_max_file_size() -> usize {
+    5_242_880 // 5MB
+}
+
+fn default_rate_limit_window() -> u64 {
+    900_000 // 15 minutes
+}
+
+fn default_rate_limit_max() -> u32 {
+    10_000
+}
+
+fn default_rate_limit_headers() -> bool {
+    true
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            max_file_size: default_max_file_size(),
+            rate_limit_window_ms: default_rate_limit_window(),
+            rate_limit_max_requests: default_rate_limit_max(),
+            rate_limit_headers: default_rate_limit_headers(),
+            rate_limit_key: RateLimitKeyStrategy::default(),
+            admin_ip_allowlist: String::new(),
+            trusted_proxies: String::new(),
+            csrf_protected_paths: String::new(),
+        }
+    }
+}
+
+/// Main application settings
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Settings {
+    /// Application environment
+    #[serde(default)]
+    pub environment: Environment,
+
+    /// Server configuration
+    #[serde(default)]
+    pub server: ServerConfig,
+
+    /// Database configuration
+    #[serde(default)]
+    pub database: DatabaseConfig,
+
+    /// Redis configuration
+    #[serde(default)]
+    pub redis: RedisConfig,
+
+    /// Authentication configuration
+    #[serde(default)]
+    pub auth: AuthConfig,
+
+    /// OAuth providers configuration
+    #[serde(default)]
+    pub oauth: OAuthConfig,
+
+    /// Email configuration
+    #[serde(default)]
+    pub email: EmailConfig,
+
+    /// SlipOK payment configuration
+    #[serde(default)]
+    pub slipok: SlipokConfig,
+
+    /// Outbound webhooks
+    #[serde(default)]
+    pub webhooks: WebhookConfig,
+
+    /// PromptPay QR code configuration
+    #[serde(default)]
+    pub promptpay: PromptPayConfig,
+
+    /// Security configuration
+    #[serde(default)]
+    pub security: SecurityConfig,
+
+    /// Database seeding configuration
+    #[serde(default)]
+    pub seed: SeedConfig,
+
+    /// Loyalty earning rules
+    #[serde(default)]
+    pub loyalty: LoyaltyConfig,
+
+    /// Coupon creation rules
+    #[serde(default)]
+    pub coupons: CouponConfig,
+
+    /// User deletion cascade
+    #[serde(default)]
+    pub user_deletion: UserDeletionConfig,
+
+    /// Admin account merges
+    #[serde(default)]
+    pub account_merge: AccountMergeConfig,
+
+    /// Admin bulk member export
+    #[serde(default)]
+    pub member_export: MemberExportConfig,
+
+    /// Points display formatting in API responses
+    #[serde(default)]
+    pub points_display: PointsDisplayConfig,
+
+    /// `Cache-Control` lifetimes for public responses
+    #[serde(default)]
+    pub http_cache: HttpCacheConfig,
+
+    /// Partner program points conversion rates
+    #[serde(default)]
+    pub points_conversion: PointsConversionConfig,
+
+    /// Points adjustment for partial refunds
+    #[serde(default)]
+    pub points_refund: PointsRefundConfig,
+
+    /// Reach of the paginated points history
+    #[serde(default)]
+    pub transaction_history: TransactionHistoryConfig,
+
+    /// Per-user, per-type notification rate limits
+    #[serde(default)]
+    pub notification_throttle: NotificationThrottleConfig,
+
+    /// Per-member quiet hours for non-critical notifications
+    #[serde(default)]
+    pub notification_quiet_hours: NotificationQuietHoursConfig,
+
+    /// Admin partial-text search
+    #[serde(default)]
+    pub search: SearchConfig,
+
+    /// Reminders for unanswered survey invitations
+    #[serde(default)]
+    pub survey_reminders: SurveyReminderConfig,
+
+    /// Monthly points statement emails
+    #[serde(default)]
+    pub points_statements: PointsStatementConfig,
+
+    /// User ID pseudonymization for analytics events
+    #[serde(default)]
+    pub analytics: AnalyticsConfig,
+}
+
+impl Settings {
+    /// Load settings from environment variables and optional config files
     pub fn new() -> Result<Self, ConfigurationError> {
         // Load .env file if present (ignore errors if not found)
         let _ = dotenvy::dotenv();
@@ -506,6 +1861,7 @@ impl Settings {
             .set_default("database.max_connections", 10)?
             .set_default("database.min_connections", 1)?
             .set_default("database.connection_timeout_secs", 30)?
+            .set_default("database.run_migrations_on_startup", true)?
             .set_default("redis.url", "redis://localhost:6379")?
             .set_default("redis.pool_size", 5)?
             .set_default("auth.access_token_expiry_secs", 900)?
@@ -529,56 +1885,314 @@ impl Settings {
             )
             // Manual overrides from specific environment variables
             .set_override_option(
-                "environment",
-                env::var("RUST_ENV")
-                    .or_else(|_| env::var("NODE_ENV"))
-                    .ok(),
+                "environment",
+                env::var("RUST_ENV")
+                    .or_else(|_| env::var("NODE_ENV"))
+                    .ok(),
+            )?
+            .set_override_option("server.port", env::var("PORT").ok())?
+            .set_override_option("server.frontend_url", env::var("FRONTEND_URL").ok())?
+            .set_override_option("server.log_level", env::var("LOG_LEVEL").ok())?
+            .set_override_option("server.log_format", env::var("LOG_FORMAT").ok())?
+            .set_override_option(
+                "server.shutdown_timeout_secs",
+                env::var("SHUTDOWN_TIMEOUT_SECS").ok(),
+            )?
+            .set_override_option("database.url", env::var("DATABASE_URL").ok())?
+            .set_override_option(
+                "database.run_migrations_on_startup",
+                env::var("RUN_MIGRATIONS_ON_STARTUP").ok(),
+            )?
+            .set_override_option(
+                "database.slow_query_threshold_ms",
+                env::var("SLOW_QUERY_THRESHOLD_MS").ok(),
+            )?
+            .set_override_option("redis.url", env::var("REDIS_URL").ok())?
+            .set_override_option("auth.jwt_secret", env::var("JWT_SECRET").ok())?
+            .set_override_option("auth.jwt_refresh_secret", env::var("JWT_REFRESH_SECRET").ok())?
+            .set_override_option("auth.session_secret", env::var("SESSION_SECRET").ok())?
+            .set_override_option(
+                "auth.admin_access_token_expiry_secs",
+                env::var("ADMIN_ACCESS_TOKEN_EXPIRY_SECS").ok(),
+            )?
+            .set_override_option(
+                "auth.admin_refresh_token_expiry_secs",
+                env::var("ADMIN_REFRESH_TOKEN_EXPIRY_SECS").ok(),
+            )?
+            .set_override_option("auth.reauth_max_age_secs", env::var("REAUTH_MAX_AGE_SECS").ok())?
+            .set_override_option(
+                "auth.refresh_grace_secs",
+                env::var("REFRESH_ACCESS_TOKEN_GRACE_SECS").ok(),
+            )?
+            .set_override_option(
+                "auth.admin_password_max_age_days",
+                env::var("ADMIN_PASSWORD_MAX_AGE_DAYS").ok(),
+            )?
+            .set_override_option(
+                "auth.customer_password_max_age_days",
+                env::var("CUSTOMER_PASSWORD_MAX_AGE_DAYS").ok(),
+            )?
+            .set_override_option("auth.magic_link_ttl_secs", env::var("MAGIC_LINK_TTL_SECS").ok())?
+            .set_override_option(
+                "auth.verification_resend_cooldown_secs",
+                env::var("VERIFICATION_RESEND_COOLDOWN_SECS").ok(),
+            )?
+            .set_override_option("oauth.google.client_id", env::var("GOOGLE_CLIENT_ID").ok())?
+            .set_override_option(
+                "oauth.google.client_secret",
+                env::var("GOOGLE_CLIENT_SECRET").ok(),
+            )?
+            .set_override_option(
+                "oauth.google.callback_url",
+                env::var("GOOGLE_CALLBACK_URL").ok(),
+            )?
+            .set_override_option("oauth.line.client_id", env::var("LINE_CLIENT_ID").ok())?
+            .set_override_option(
+                "oauth.line.client_secret",
+                env::var("LINE_CLIENT_SECRET").ok(),
+            )?
+            .set_override_option(
+                "oauth.line.callback_url",
+                env::var("LINE_CALLBACK_URL").ok(),
+            )?
+            .set_override_option(
+                "oauth.allowed_redirect_origins",
+                env::var("OAUTH_ALLOWED_REDIRECT_ORIGINS").ok(),
+            )?
+            .set_override_option("email.smtp.host", env::var("SMTP_HOST").ok())?
+            .set_override_option("email.smtp.port", env::var("SMTP_PORT").ok())?
+            .set_override_option("email.smtp.user", env::var("SMTP_USER").ok())?
+            .set_override_option("email.smtp.pass", env::var("SMTP_PASS").ok())?
+            .set_override_option("email.capture_mode", env::var("EMAIL_CAPTURE_MODE").ok())?
+            .set_override_option("email.imap.host", env::var("IMAP_HOST").ok())?
+            .set_override_option("email.imap.port", env::var("IMAP_PORT").ok())?
+            .set_override_option("email.imap.user", env::var("IMAP_USER").ok())?
+            .set_override_option("email.imap.pass", env::var("IMAP_PASS").ok())?
+            .set_override_option("slipok.branch_id", env::var("SLIPOK_BRANCH_ID").ok())?
+            .set_override_option("slipok.api_key", env::var("SLIPOK_API_KEY").ok())?
+            .set_override_option(
+                "slipok.result_cache_ttl_secs",
+                env::var("SLIPOK_RESULT_CACHE_TTL_SECS").ok(),
+            )?
+            .set_override_option("webhooks.url", env::var("WEBHOOK_URL").ok())?
+            .set_override_option("webhooks.secret", env::var("WEBHOOK_SECRET").ok())?
+            .set_override_option(
+                "webhooks.max_attempts",
+                env::var("WEBHOOK_MAX_ATTEMPTS").ok(),
+            )?
+            .set_override_option(
+                "webhooks.poll_interval_secs",
+                env::var("WEBHOOK_POLL_INTERVAL_SECS").ok(),
+            )?
+            .set_override_option(
+                "webhooks.timeout_secs",
+                env::var("WEBHOOK_TIMEOUT_SECS").ok(),
+            )?
+            .set_override_option("promptpay.tax_id", env::var("PROMPTPAY_TAX_ID").ok())?
+            .set_override_option("security.max_file_size", env::var("MAX_FILE_SIZE").ok())?
+            .set_override_option(
+                "security.rate_limit_window_ms",
+                env::var("RATE_LIMIT_WINDOW_MS").ok(),
+            )?
+            .set_override_option(
+                "security.rate_limit_max_requests",
+                env::var("RATE_LIMIT_MAX_REQUESTS").ok(),
+            )?
+            .set_override_option(
+                "security.rate_limit_headers",
+                env::var("RATE_LIMIT_HEADERS").ok(),
+            )?
+            .set_override_option("security.rate_limit_key", env::var("RATE_LIMIT_KEY").ok())?
+            .set_override_option(
+                "security.admin_ip_allowlist",
+                env::var("ADMIN_IP_ALLOWLIST").ok(),
+            )?
+            .set_override_option("security.trusted_proxies", env::var("TRUSTED_PROXIES").ok())?
+            .set_override_option(
+                "security.csrf_protected_paths",
+                env::var("CSRF_PROTECTED_PATHS").ok(),
+            )?
+            .set_override_option("seed.file", env::var("SEED_FILE").ok())?
+            .set_override_option(
+                "loyalty.min_nights_for_points",
+                env::var("LOYALTY_MIN_NIGHTS_FOR_POINTS").ok(),
+            )?
+            .set_override_option(
+                "loyalty.points_per_night",
+                env::var("LOYALTY_POINTS_PER_NIGHT").ok(),
+            )?
+            .set_override_option(
+                "loyalty.referrer_bonus_points",
+                env::var("LOYALTY_REFERRER_BONUS_POINTS").ok(),
+            )?
+            .set_override_option(
+                "loyalty.referee_bonus_points",
+                env::var("LOYALTY_REFEREE_BONUS_POINTS").ok(),
+            )?
+            .set_override_option(
+                "loyalty.win_back_after_days",
+                env::var("LOYALTY_WIN_BACK_AFTER_DAYS").ok(),
+            )?
+            .set_override_option(
+                "loyalty.points_expiry_interval_secs",
+                env::var("POINTS_EXPIRY_INTERVAL_SECS").ok(),
+            )?
+            .set_override_option(
+                "loyalty.max_points_per_award",
+                env::var("LOYALTY_MAX_POINTS_PER_AWARD").ok(),
+            )?
+            .set_override_option(
+                "loyalty.max_nights_per_award",
+                env::var("LOYALTY_MAX_NIGHTS_PER_AWARD").ok(),
+            )?
+            .set_override_option(
+                "loyalty.tier_downgrade_grace_days",
+                env::var("LOYALTY_TIER_DOWNGRADE_GRACE_DAYS").ok(),
+            )?
+            .set_override_option(
+                "loyalty.pending_points_enabled",
+                env::var("LOYALTY_PENDING_POINTS_ENABLED").ok(),
+            )?
+            .set_override_option(
+                "loyalty.pending_points_clear_days",
+                env::var("LOYALTY_PENDING_POINTS_CLEAR_DAYS").ok(),
+            )?
+            .set_override_option(
+                "loyalty.tiers_cache_ttl_secs",
+                env::var("LOYALTY_TIERS_CACHE_TTL_SECS").ok(),
+            )?
+            .set_override_option(
+                "loyalty.points_redemption_rate",
+                env::var("LOYALTY_POINTS_REDEMPTION_RATE").ok(),
+            )?
+            .set_override_option(
+                "loyalty.tier_recalc_batch_size",
+                env::var("LOYALTY_TIER_RECALC_BATCH_SIZE").ok(),
+            )?
+            .set_override_option(
+                "coupons.default_currency",
+                env::var("COUPON_DEFAULT_CURRENCY").ok(),
             )?
-            .set_override_option("server.port", env::var("PORT").ok())?
-            .set_override_option("server.frontend_url", env::var("FRONTEND_URL").ok())?
-            .set_override_option("server.log_level", env::var("LOG_LEVEL").ok())?
-            .set_override_option("database.url", env::var("DATABASE_URL").ok())?
-            .set_override_option("redis.url", env::var("REDIS_URL").ok())?
-            .set_override_option("auth.jwt_secret", env::var("JWT_SECRET").ok())?
-            .set_override_option("auth.jwt_refresh_secret", env::var("JWT_REFRESH_SECRET").ok())?
-            .set_override_option("auth.session_secret", env::var("SESSION_SECRET").ok())?
-            .set_override_option("oauth.google.client_id", env::var("GOOGLE_CLIENT_ID").ok())?
             .set_override_option(
-                "oauth.google.client_secret",
-                env::var("GOOGLE_CLIENT_SECRET").ok(),
+                "coupons.allowed_currencies",
+                env::var("COUPON_ALLOWED_CURRENCIES").ok(),
             )?
+            .set_override_option("coupons.stacking", env::var("COUPON_STACKING").ok())?
+            .set_override_option("coupons.code_prefix", env::var("COUPON_CODE_PREFIX").ok())?
+            .set_override_option("coupons.code_length", env::var("COUPON_CODE_LENGTH").ok())?
             .set_override_option(
-                "oauth.google.callback_url",
-                env::var("GOOGLE_CALLBACK_URL").ok(),
+                "coupons.code_charset",
+                env::var("COUPON_CODE_CHARSET").ok(),
             )?
-            .set_override_option("oauth.line.client_id", env::var("LINE_CLIENT_ID").ok())?
             .set_override_option(
-                "oauth.line.client_secret",
-                env::var("LINE_CLIENT_SECRET").ok(),
+                "coupons.exhaust_at_limit",
+                env::var("COUPON_EXHAUST_AT_LIMIT").ok(),
             )?
+            .set_override_option("coupons.utc_offset", env::var("COUPON_UTC_OFFSET").ok())?
             .set_override_option(
-                "oauth.line.callback_url",
-                env::var("LINE_CALLBACK_URL").ok(),
+                "coupons.expiry_sweep_interval_secs",
+                env::var("COUPON_EXPIRY_SWEEP_INTERVAL_SECS").ok(),
             )?
-            .set_override_option("email.smtp.host", env::var("SMTP_HOST").ok())?
-            .set_override_option("email.smtp.port", env::var("SMTP_PORT").ok())?
-            .set_override_option("email.smtp.user", env::var("SMTP_USER").ok())?
-            .set_override_option("email.smtp.pass", env::var("SMTP_PASS").ok())?
-            .set_override_option("email.imap.host", env::var("IMAP_HOST").ok())?
-            .set_override_option("email.imap.port", env::var("IMAP_PORT").ok())?
-            .set_override_option("email.imap.user", env::var("IMAP_USER").ok())?
-            .set_override_option("email.imap.pass", env::var("IMAP_PASS").ok())?
-            .set_override_option("slipok.branch_id", env::var("SLIPOK_BRANCH_ID").ok())?
-            .set_override_option("slipok.api_key", env::var("SLIPOK_API_KEY").ok())?
-            .set_override_option("promptpay.tax_id", env::var("PROMPTPAY_TAX_ID").ok())?
-            .set_override_option("security.max_file_size", env::var("MAX_FILE_SIZE").ok())?
             .set_override_option(
-                "security.rate_limit_window_ms",
-                env::var("RATE_LIMIT_WINDOW_MS").ok(),
+                "user_deletion.related_records",
+                env::var("USER_DELETION_RELATED_RECORDS").ok(),
             )?
             .set_override_option(
-                "security.rate_limit_max_requests",
-                env::var("RATE_LIMIT_MAX_REQUESTS").ok(),
+                "account_merge.enabled",
+                env::var("ACCOUNT_MERGE_ENABLED").ok(),
+            )?
+            .set_override_option(
+                "member_export.max_per_hour",
+                env::var("MEMBER_EXPORT_MAX_PER_HOUR").ok(),
+            )?
+            .set_override_option(
+                "member_export.batch_size",
+                env::var("MEMBER_EXPORT_BATCH_SIZE").ok(),
+            )?
+            .set_override_option(
+                "points_display.style",
+                env::var("POINTS_DISPLAY_STYLE").ok(),
+            )?
+            .set_override_option(
+                "points_display.default_locale",
+                env::var("POINTS_DISPLAY_LOCALE").ok(),
+            )?
+            .set_override_option(
+                "http_cache.tiers_max_age_secs",
+                env::var("CACHE_TIERS_MAX_AGE_SECS").ok(),
+            )?
+            .set_override_option(
+                "http_cache.response_cache_ttls",
+                env::var("RESPONSE_CACHE_TTLS").ok(),
+            )?
+            .set_override_option(
+                "points_conversion.rates",
+                env::var("POINTS_CONVERSION_RATES").ok(),
+            )?
+            .set_override_option(
+                "points_refund.adjust_nights",
+                env::var("POINTS_REFUND_ADJUST_NIGHTS").ok(),
+            )?
+            .set_override_option(
+                "transaction_history.max_age_days",
+                env::var("TRANSACTION_HISTORY_MAX_AGE_DAYS").ok(),
+            )?
+            .set_override_option(
+                "transaction_history.max_rows",
+                env::var("TRANSACTION_HISTORY_MAX_ROWS").ok(),
+            )?
+            .set_override_option(
+                "notification_throttle.max_per_window",
+                env::var("NOTIFICATION_THROTTLE_MAX").ok(),
+            )?
+            .set_override_option(
+                "notification_throttle.window_secs",
+                env::var("NOTIFICATION_THROTTLE_WINDOW_SECS").ok(),
+            )?
+            .set_override_option(
+                "notification_throttle.limits",
+                env::var("NOTIFICATION_THROTTLE_LIMITS").ok(),
+            )?
+            .set_override_option(
+                "notification_quiet_hours.interval_secs",
+                env::var("NOTIFICATION_QUIET_HOURS_INTERVAL_SECS").ok(),
+            )?
+            .set_override_option(
+                "notification_quiet_hours.max_delay_hours",
+                env::var("NOTIFICATION_QUIET_HOURS_MAX_DELAY_HOURS").ok(),
+            )?
+            .set_override_option(
+                "notification_quiet_hours.default_timezone",
+                env::var("NOTIFICATION_QUIET_HOURS_DEFAULT_TIMEZONE").ok(),
+            )?
+            .set_override_option("search.min_length", env::var("SEARCH_MIN_LENGTH").ok())?
+            .set_override_option(
+                "survey_reminders.delay_hours",
+                env::var("SURVEY_REMINDER_DELAY_HOURS").ok(),
+            )?
+            .set_override_option(
+                "survey_reminders.interval_secs",
+                env::var("SURVEY_REMINDER_INTERVAL_SECS").ok(),
+            )?
+            .set_override_option(
+                "points_statements.enabled",
+                env::var("POINTS_STATEMENT_ENABLED").ok(),
+            )?
+            .set_override_option(
+                "points_statements.interval_secs",
+                env::var("POINTS_STATEMENT_INTERVAL_SECS").ok(),
+            )?
+            .set_override_option(
+                "points_statements.expiring_within_days",
+                env::var("POINTS_STATEMENT_EXPIRING_DAYS").ok(),
+            )?
+            .set_override_option(
+                "analytics.pseudonymize_user_ids",
+                env::var("ANALYTICS_PSEUDONYMIZE_USER_IDS").ok(),
+            )?
+            .set_override_option(
+                "analytics.user_id_salt",
+                env::var("ANALYTICS_USER_ID_SALT").ok(),
             )?
             .build()?;
 
@@ -671,6 +2285,12 @@ impl Settings {
             if self.database.url.contains("localhost") {
                 errors.push("Production environment should not use localhost database".to_string());
             }
+
+            // Capture mode silently swallows every outgoing email; it must
+            // never be live where real users are waiting on them.
+            if self.email.capture_mode {
+                errors.push("EMAIL_CAPTURE_MODE must not be enabled in production".to_string());
+            }
         } else {
             // In development/staging, just validate minimum requirements
             if self.auth.jwt_secret.len() < 32 {
@@ -704,6 +2324,216 @@ impl Settings {
             errors.push("REDIS_URL must be a valid Redis connection string".to_string());
         }
 
+        // A typo in the admin allowlist must fail loudly rather than
+        // silently locking admins out (or, worse, being ignored).
+        if let Err(e) = parse_ip_networks(&self.security.admin_ip_allowlist) {
+            errors.push(format!("ADMIN_IP_ALLOWLIST: {}", e));
+        }
+        if let Err(e) = parse_ip_networks(&self.security.trusted_proxies) {
+            errors.push(format!("TRUSTED_PROXIES: {}", e));
+        }
+        if let Some(entry) = self
+            .security
+            .csrf_protected_paths
+            .split(',')
+            .map(str::trim)
+            .find(|entry| !entry.is_empty() && !entry.starts_with('/'))
+        {
+            errors.push(format!(
+                "CSRF_PROTECTED_PATHS: '{}' must be a path starting with '/'",
+                entry
+            ));
+        }
+        if let Err(e) = self.oauth.redirect_origins() {
+            errors.push(format!("OAUTH_ALLOWED_REDIRECT_ORIGINS: {}", e));
+        }
+
+        let allowed_currencies = self.coupons.allowed_currencies();
+        if let Some(bad) = allowed_currencies
+            .iter()
+            .find(|c| c.len() != 3 || !c.chars().all(|ch| ch.is_ascii_uppercase()))
+        {
+            errors.push(format!(
+                "COUPON_ALLOWED_CURRENCIES: '{}' is not a 3-letter currency code",
+                bad
+            ));
+        }
+        if !self
+            .coupons
+            .is_allowed_currency(&self.coupons.default_currency)
+        {
+            errors.push(format!(
+                "COUPON_DEFAULT_CURRENCY '{}' is not in COUPON_ALLOWED_CURRENCIES",
+                self.coupons.default_currency
+            ));
+        }
+
+        // Auto-generated codes must pass the same format check as typed
+        // ones and fit `coupons.code` (VARCHAR(20)).
+        if !self
+            .coupons
+            .code_prefix
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_' || c == '-')
+        {
+            errors.push(
+                "COUPON_CODE_PREFIX may only contain uppercase letters, numbers, underscores and hyphens"
+                    .to_string(),
+            );
+        }
+        if self.coupons.code_length < MIN_COUPON_CODE_LENGTH
+            || self.coupons.code_prefix.len() + self.coupons.code_length > MAX_COUPON_CODE_LENGTH
+        {
+            errors.push(format!(
+                "COUPON_CODE_LENGTH must be at least {} and, with COUPON_CODE_PREFIX, at most {}",
+                MIN_COUPON_CODE_LENGTH, MAX_COUPON_CODE_LENGTH
+            ));
+        }
+        if self.coupons.code_charset().len() < MIN_COUPON_CODE_CHARSET {
+            errors.push(format!(
+                "COUPON_CODE_CHARSET needs at least {} distinct unambiguous letters or digits",
+                MIN_COUPON_CODE_CHARSET
+            ));
+        }
+
+        if let Err(e) = self.coupons.local_offset() {
+            errors.push(format!("COUPON_UTC_OFFSET: {}", e));
+        }
+
+        if self.transaction_history.max_age_days == 0 || self.transaction_history.max_rows == 0 {
+            errors.push(
+                "TRANSACTION_HISTORY_MAX_AGE_DAYS and TRANSACTION_HISTORY_MAX_ROWS must be positive"
+                    .to_string(),
+            );
+        }
+
+        let redemption_rate = self.loyalty.points_redemption_rate;
+        if !redemption_rate.is_finite() || redemption_rate <= 0.0 {
+            errors.push(format!(
+                "LOYALTY_POINTS_REDEMPTION_RATE must be positive (got {})",
+                redemption_rate
+            ));
+        }
+
+        if self.loyalty.tier_recalc_batch_size == 0 {
+            errors.push("LOYALTY_TIER_RECALC_BATCH_SIZE must be positive".to_string());
+        }
+
+        if self.security.rate_limit_window_ms == 0 {
+            errors.push("RATE_LIMIT_WINDOW_MS must be positive".to_string());
+        }
+        if self.security.rate_limit_max_requests == 0 {
+            errors.push("RATE_LIMIT_MAX_REQUESTS must be positive".to_string());
+        }
+
+        if let Err(e) = self.points_conversion.parsed_rates() {
+            errors.push(format!("POINTS_CONVERSION_RATES: {}", e));
+        }
+
+        if let Err(e) = self.http_cache.parsed_response_cache_ttls() {
+            errors.push(format!("RESPONSE_CACHE_TTLS: {}", e));
+        }
+
+        if let Err(e) = self.notification_throttle.parsed_limits() {
+            errors.push(format!("NOTIFICATION_THROTTLE_LIMITS: {}", e));
+        }
+
+        if self.webhooks.is_configured() {
+            let url = self.webhooks.url.as_deref().unwrap_or_default();
+            if !(url.starts_with("https://") || url.starts_with("http://")) {
+                errors.push("WEBHOOK_URL must be an http(s) URL".to_string());
+            }
+            if self.webhooks.secret.as_deref().map_or(0, str::len) < 32 {
+                errors.push(
+                    "WEBHOOK_SECRET must be at least 32 characters when WEBHOOK_URL is set"
+                        .to_string(),
+                );
+            }
+            if self.webhooks.max_attempts == 0 || self.webhooks.poll_interval_secs == 0 {
+                errors.push(
+                    "WEBHOOK_MAX_ATTEMPTS and WEBHOOK_POLL_INTERVAL_SECS must be positive"
+                        .to_string(),
+                );
+            }
+        }
+
+        if self.loyalty.tier_downgrade_grace_days > 3650 {
+            errors.push("LOYALTY_TIER_DOWNGRADE_GRACE_DAYS must be at most 3650".to_string());
+        }
+        if self.loyalty.pending_points_clear_days > 3650 {
+            errors.push("LOYALTY_PENDING_POINTS_CLEAR_DAYS must be at most 3650".to_string());
+        }
+
+        if self.member_export.batch_size == 0 || self.member_export.batch_size > 10_000 {
+            errors.push("MEMBER_EXPORT_BATCH_SIZE must be between 1 and 10000".to_string());
+        }
+
+        if self.search.min_length == 0 {
+            errors.push("SEARCH_MIN_LENGTH must be positive".to_string());
+        }
+
+        if self.survey_reminders.delay_hours > 0 && self.survey_reminders.interval_secs == 0 {
+            errors.push(
+                "SURVEY_REMINDER_INTERVAL_SECS must be positive when reminders are enabled"
+                    .to_string(),
+            );
+        }
+
+        if self.analytics.pseudonymize_user_ids
+            && self.analytics.user_id_salt.len() < MIN_ANALYTICS_SALT_LENGTH
+        {
+            errors.push(format!(
+                "ANALYTICS_USER_ID_SALT must be at least {} characters when ANALYTICS_PSEUDONYMIZE_USER_IDS is set",
+                MIN_ANALYTICS_SALT_LENGTH
+            ));
+        }
+
+        if self.points_statements.enabled && self.points_statements.interval_secs == 0 {
+            errors.push(
+                "POINTS_STATEMENT_INTERVAL_SECS must be positive when statements are enabled"
+                    .to_string(),
+            );
+        }
+
+        if self.notification_throttle.window_secs == 0 {
+            errors.push("NOTIFICATION_THROTTLE_WINDOW_SECS must be positive".to_string());
+        }
+
+        if self.notification_quiet_hours.interval_secs == 0 {
+            errors.push("NOTIFICATION_QUIET_HOURS_INTERVAL_SECS must be positive".to_string());
+        }
+
+        if self
+            .notification_quiet_hours
+            .default_timezone
+            .trim()
+            .is_empty()
+        {
+            errors.push("NOTIFICATION_QUIET_HOURS_DEFAULT_TIMEZONE must not be empty".to_string());
+        }
+
+        if self.auth.admin_access_token_expiry_secs == 0
+            || self.auth.admin_refresh_token_expiry_secs == 0
+            || self.auth.reauth_max_age_secs == 0
+        {
+            errors.push(
+                "ADMIN_ACCESS_TOKEN_EXPIRY_SECS, ADMIN_REFRESH_TOKEN_EXPIRY_SECS and REAUTH_MAX_AGE_SECS must be positive"
+                    .to_string(),
+            );
+        }
+
+        if self.auth.magic_link_ttl_secs == 0 || self.auth.magic_link_ttl_secs > 3600 {
+            errors.push("MAGIC_LINK_TTL_SECS must be between 1 and 3600".to_string());
+        }
+
+        if self.server.shutdown_timeout_secs == 0 {
+            errors.push("SHUTDOWN_TIMEOUT_SECS must be positive".to_string());
+        }
+
+        if self.auth.verification_resend_cooldown_secs == 0 {
+            errors.push("VERIFICATION_RESEND_COOLDOWN_SECS must be positive".to_string());
+        }
+
         if !errors.is_empty() {
             return Err(ConfigurationError::ValidationError(errors.join("; ")));
         }
@@ -816,6 +2646,7 @@ mod tests {
         assert_eq!(settings.environment, Environment::Development);
         assert_eq!(settings.server.port, 4001);
         assert_eq!(settings.database.max_connections, 10);
+        assert!(settings.database.run_migrations_on_startup);
         assert_eq!(settings.redis.pool_size, 5);
     }
 
@@ -946,4 +2777,275 @@ mod tests {
             .validate()
             .expect("strong, non-placeholder production secrets should pass validation");
     }
+
+    #[test]
+    fn test_parse_ip_networks() {
+        let networks = parse_ip_networks(" 10.8.0.0/24, 203.0.113.7 ,,::1").unwrap();
+        assert_eq!(networks.len(), 3);
+        assert!(networks[0].contains(&"10.8.0.42".parse::<std::net::IpAddr>().unwrap()));
+        assert!(networks[1].contains(&"203.0.113.7".parse::<std::net::IpAddr>().unwrap()));
+        assert!(!networks[1].contains(&"203.0.113.8".parse::<std::net::IpAddr>().unwrap()));
+
+        assert!(parse_ip_networks("").unwrap().is_empty());
+        assert!(parse_ip_networks("10.0.0.0/33").is_err());
+        assert!(parse_ip_networks("office-vpn").is_err());
+    }
+
+    #[test]
+    fn test_parse_origins() {
+        let origins =
+            parse_origins(" https://App.Example.com ,,http://localhost:3000/,https://x.io:443")
+                .unwrap();
+        assert_eq!(
+            origins,
+            vec![
+                "https://app.example.com".to_string(),
+                "http://localhost:3000".to_string(),
+                "https://x.io".to_string(),
+            ]
+        );
+        assert!(parse_origins("").unwrap().is_empty());
+        assert!(parse_origins("app.example.com").is_err());
+        assert!(parse_origins("https://app.example.com/callback").is_err());
+        assert!(parse_origins("https://user@app.example.com").is_err());
+        assert!(parse_origins("ftp://files.example.com").is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_admin_ip_allowlist() {
+        let mut settings = production_settings_with_strong_secrets();
+        settings.security.admin_ip_allowlist = "10.0.0.0/8,not-an-ip".to_string();
+
+        let err = settings
+            .validate()
+            .expect_err("malformed ADMIN_IP_ALLOWLIST must be rejected");
+        assert!(err.to_string().contains("ADMIN_IP_ALLOWLIST"));
+    }
+
+    #[test]
+    fn test_log_format_parse_and_default() {
+        assert_eq!("json".parse::<LogFormat>(), Ok(LogFormat::Json));
+        assert_eq!(" Pretty ".parse::<LogFormat>(), Ok(LogFormat::Pretty));
+        assert!("yaml".parse::<LogFormat>().is_err());
+
+        assert_eq!(LogFormat::resolve(None, true), LogFormat::Pretty);
+        assert_eq!(LogFormat::resolve(None, false), LogFormat::Json);
+        assert_eq!(
+            LogFormat::resolve(Some(LogFormat::Json), true),
+            LogFormat::Json
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_email_capture_mode_in_production() {
+        let mut settings = production_settings_with_strong_secrets();
+        settings.email.capture_mode = true;
+
+        let err = settings
+            .validate()
+            .expect_err("EMAIL_CAPTURE_MODE must be rejected in production");
+        assert!(err.to_string().contains("EMAIL_CAPTURE_MODE"));
+    }
+
+    #[test]
+    fn test_coupon_config_allowed_currencies() {
+        let config = CouponConfig {
+            default_currency: "THB".to_string(),
+            allowed_currencies: " thb, USD ,,".to_string(),
+            stacking: CouponStacking::None,
+            ..Default::default()
+        };
+        assert_eq!(config.allowed_currencies(), vec!["THB", "USD"]);
+        assert!(config.is_allowed_currency("usd"));
+        assert!(!config.is_allowed_currency("EUR"));
+    }
+
+    #[test]
+    fn test_validate_rejects_default_currency_not_allowed() {
+        let mut settings = production_settings_with_strong_secrets();
+        settings.coupons.default_currency = "EUR".to_string();
+
+        let err = settings
+            .validate()
+            .expect_err("a default currency outside the allowed list must be rejected");
+        assert!(err.to_string().contains("COUPON_DEFAULT_CURRENCY"));
+    }
+
+    #[test]
+    fn test_validate_requires_analytics_salt_when_pseudonymizing() {
+        let mut settings = production_settings_with_strong_secrets();
+        settings.analytics.pseudonymize_user_ids = true;
+        settings.analytics.user_id_salt = "too-short".to_string();
+
+        let err = settings
+            .validate()
+            .expect_err("a short analytics salt must be rejected");
+        assert!(err.to_string().contains("ANALYTICS_USER_ID_SALT"));
+
+        settings.analytics.user_id_salt = "s".repeat(MIN_ANALYTICS_SALT_LENGTH);
+        if let Err(err) = settings.validate() {
+            assert!(!err.to_string().contains("ANALYTICS_USER_ID_SALT"));
+        }
+    }
+
+    #[test]
+    fn test_points_conversion_rates() {
+        let config = PointsConversionConfig {
+            rates: " Siam_Rewards=0.5, partner_x = 2 ,".to_string(),
+        };
+        assert_eq!(
+            config.rate_for("siam_rewards"),
+            Some(rust_decimal::Decimal::new(5, 1))
+        );
+        assert_eq!(
+            config.rate_for("PARTNER_X"),
+            Some(rust_decimal::Decimal::from(2))
+        );
+        assert_eq!(config.rate_for("unknown"), None);
+
+        for bad in ["partner_x", "=1", "partner_x=abc", "partner_x=0"] {
+            let config = PointsConversionConfig {
+                rates: bad.to_string(),
+            };
+            assert!(config.parsed_rates().is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_response_cache_ttls() {
+        let config = HttpCacheConfig {
+            response_cache_ttls: " Tiers=60, surveys = 0 ,".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.parsed_response_cache_ttls().unwrap(),
+            vec![("tiers".to_string(), 60), ("surveys".to_string(), 0)]
+        );
+        assert!(HttpCacheConfig::default()
+            .parsed_response_cache_ttls()
+            .unwrap()
+            .is_empty());
+
+        for bad in ["tiers", "=60", "tiers=soon", "tiers=-1"] {
+            let config = HttpCacheConfig {
+                response_cache_ttls: bad.to_string(),
+                ..Default::default()
+            };
+            assert!(
+                config.parsed_response_cache_ttls().is_err(),
+                "{bad} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_coupon_code_charset_drops_ambiguous_characters() {
+        let config = CouponConfig {
+            code_charset: "abc0O1iIlL2-b".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(config.code_charset(), b"ABC2".to_vec());
+
+        let default = CouponConfig::default().code_charset();
+        assert!(default.iter().all(|c| !b"0O1IL".contains(c)));
+        assert!(default.len() >= MIN_COUPON_CODE_CHARSET);
+    }
+
+    #[test]
+    fn test_coupon_code_settings_validation() {
+        let mut settings = production_settings_with_strong_secrets();
+        settings.coupons.code_prefix = "PROMO-".to_string();
+        settings.coupons.code_length = 15;
+
+        let err = settings
+            .validate()
+            .expect_err("a code longer than the column must be rejected");
+        assert!(err.to_string().contains("COUPON_CODE_LENGTH"));
+
+        settings.coupons.code_length = 8;
+        settings.coupons.code_prefix = "promo-".to_string();
+        let err = settings
+            .validate()
+            .expect_err("a lowercase prefix must be rejected");
+        assert!(err.to_string().contains("COUPON_CODE_PREFIX"));
+    }
+
+    #[test]
+    fn test_coupon_local_offset() {
+        let offset = |value: &str| {
+            CouponConfig {
+                utc_offset: value.to_string(),
+                ..Default::default()
+            }
+            .local_offset()
+        };
+
+        assert_eq!(
+            CouponConfig::default().local_offset(),
+            Ok(chrono::FixedOffset::east_opt(7 * 3600).unwrap())
+        );
+        assert_eq!(
+            offset("-05:30"),
+            Ok(chrono::FixedOffset::west_opt(5 * 3600 + 30 * 60).unwrap())
+        );
+        for bad in ["07:00", "+7", "+15:00", "+07:60", "Asia/Bangkok", ""] {
+            assert!(offset(bad).is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_notification_throttle_limits() {
+        let config = NotificationThrottleConfig {
+            limits: " Coupon=3, points = 0 ,".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(config.limit_for("coupon"), 3);
+        assert_eq!(config.limit_for("POINTS"), 0);
+        assert_eq!(config.limit_for("survey"), 10);
+
+        for bad in ["coupon", "=1", "coupon=-1", "coupon=many"] {
+            let config = NotificationThrottleConfig {
+                limits: bad.to_string(),
+                ..Default::default()
+            };
+            assert!(config.parsed_limits().is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_admin_session_lifetimes() {
+        let config = AuthConfig::default();
+
+        assert_eq!(config.access_token_expiry_for("customer", 900), 900);
+        assert_eq!(config.access_token_expiry_for("admin", 900), 600);
+        assert_eq!(config.access_token_expiry_for("super_admin", 900), 600);
+
+        // "Remember me" stretches customer sessions but not admin ones
+        assert_eq!(config.access_token_expiry_for("customer", 7200), 7200);
+        assert_eq!(config.access_token_expiry_for("admin", 7200), 600);
+        assert_eq!(
+            config.refresh_token_expiry_for("customer", 2_592_000),
+            2_592_000
+        );
+        assert_eq!(config.refresh_token_expiry_for("admin", 2_592_000), 28_800);
+
+        // The admin lifetime never exceeds the general one
+        assert_eq!(config.access_token_expiry_for("admin", 300), 300);
+    }
+
+    #[test]
+    fn test_password_max_age_by_role() {
+        let mut config = AuthConfig::default();
+        assert_eq!(config.password_max_age_days_for("admin"), 0);
+        assert_eq!(config.password_max_age_days_for("customer"), 0);
+
+        // Enabling rotation for admins leaves customers alone
+        config.admin_password_max_age_days = 90;
+        assert_eq!(config.password_max_age_days_for("admin"), 90);
+        assert_eq!(config.password_max_age_days_for("super_admin"), 90);
+        assert_eq!(config.password_max_age_days_for("customer"), 0);
+
+        config.customer_password_max_age_days = 365;
+        assert_eq!(config.password_max_age_days_for("customer"), 365);
+    }
 }
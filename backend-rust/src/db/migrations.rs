@@ -373,6 +373,35 @@ pub async fn has_pending_migrations(pool: &PgPool) -> Result<bool> {
     Ok(applied.len() < total)
 }
 
+/// Check that every embedded migration is applied with a matching checksum,
+/// without applying anything
+///
+/// Used at startup when the server isn't allowed to migrate
+/// (`RUN_MIGRATIONS_ON_STARTUP=false`) and by `migrate --check`, so a
+/// server never runs against a schema older than its code.
+///
+/// # Arguments
+/// * `pool` - A reference to the PostgreSQL connection pool
+///
+/// # Errors
+/// * Returns an error naming the pending or mismatched versions
+pub async fn ensure_migrations_applied(pool: &PgPool) -> Result<()> {
+    let validation = validate_migrations(pool)
+        .await
+        .context("Failed to check applied migrations")?;
+    if validation.is_valid && validation.pending_versions.is_empty() {
+        return Ok(());
+    }
+
+    let mismatched: Vec<i64> = validation.mismatches.iter().map(|m| m.version).collect();
+    Err(anyhow::anyhow!(
+        "Database schema is not up to date: pending {:?}, checksum mismatch {:?}. \
+         Run the `migrate` binary against this database first.",
+        validation.pending_versions,
+        mismatched,
+    ))
+}
+
 /// Validate that all migrations have correct checksums
 ///
 /// This checks that the embedded migrations match what was applied to the database.
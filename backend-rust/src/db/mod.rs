@@ -8,8 +8,18 @@ pub mod seed;
 
 use anyhow::{Context, Result};
 use sqlx::postgres::{PgPool, PgPoolOptions};
-use std::time::Duration;
-use tracing::info;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Default slow-query threshold in milliseconds
+pub const DEFAULT_SLOW_QUERY_THRESHOLD_MS: u64 = 500;
+
+/// Queries wrapped in [`timed_query`] that take at least this many
+/// milliseconds are logged at WARN; 0 turns the logging off. Set once at
+/// startup from `SLOW_QUERY_THRESHOLD_MS`.
+static SLOW_QUERY_THRESHOLD_MS: AtomicU64 = AtomicU64::new(DEFAULT_SLOW_QUERY_THRESHOLD_MS);
 
 /// Database configuration options
 #[derive(Debug, Clone)]
@@ -74,6 +84,11 @@ pub struct Database {
 }
 
 impl Database {
+    /// Wrap an existing pool, e.g. the one held in `AppState`
+    pub fn from_pool(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
     /// Get a reference to the underlying connection pool
     pub fn pool(&self) -> &PgPool {
         &self.pool
@@ -167,6 +182,49 @@ pub async fn init_pool_with_url(database_url: &str, config: Option<DbConfig>) ->
     Ok(Database { pool })
 }
 
+/// Set the slow-query threshold used by [`timed_query`] (0 disables it)
+pub fn set_slow_query_threshold_ms(threshold_ms: u64) {
+    SLOW_QUERY_THRESHOLD_MS.store(threshold_ms, Ordering::Relaxed);
+}
+
+/// Whether a query that took `elapsed` counts as slow
+fn is_slow_query(elapsed: Duration, threshold_ms: u64) -> bool {
+    threshold_ms > 0 && elapsed >= Duration::from_millis(threshold_ms)
+}
+
+/// Await a database query, logging it at WARN if it takes longer than the
+/// slow-query threshold
+///
+/// `label` names the query in the log (e.g. `"loyalty.status"`). It is a
+/// `&'static str` so it can't carry user data; put nothing else about the
+/// query in the log. Under the threshold the cost is one clock read and an
+/// atomic load.
+///
+/// # Example
+/// ```rust,ignore
+/// let row = timed_query("coupons.validate", query.fetch_optional(pool)).await?;
+/// ```
+pub async fn timed_query<F, T>(label: &'static str, query: F) -> T
+where
+    F: Future<Output = T>,
+{
+    let started = Instant::now();
+    let output = query.await;
+
+    let threshold_ms = SLOW_QUERY_THRESHOLD_MS.load(Ordering::Relaxed);
+    let elapsed = started.elapsed();
+    if is_slow_query(elapsed, threshold_ms) {
+        warn!(
+            query = label,
+            duration_ms = elapsed.as_millis() as u64,
+            threshold_ms,
+            "Slow database query"
+        );
+    }
+
+    output
+}
+
 /// Error types specific to database operations
 #[derive(Debug, thiserror::Error)]
 pub enum DbError {
@@ -205,6 +263,21 @@ impl DbError {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_slow_query() {
+        assert!(is_slow_query(Duration::from_millis(500), 500));
+        assert!(is_slow_query(Duration::from_secs(2), 500));
+        assert!(!is_slow_query(Duration::from_millis(499), 500));
+        // 0 disables slow-query logging
+        assert!(!is_slow_query(Duration::from_secs(60), 0));
+    }
+
+    #[tokio::test]
+    async fn test_timed_query_returns_output() {
+        let output = timed_query("test.query", async { 42 }).await;
+        assert_eq!(output, 42);
+    }
+
     #[test]
     fn test_db_config_default() {
         let config = DbConfig::default();
@@ -3,32 +3,230 @@
 //! This module provides functions for seeding the database with essential
 //! and sample data. Essential data is seeded in all environments, while
 //! sample data is only seeded in development.
+//!
+//! Sample data defaults to the built-in fixtures below. Setting `SEED_FILE`
+//! to a JSON (`.json`) or YAML (`.yaml` / `.yml`) file replaces them, so demo
+//! environments can be customised without touching Rust code:
+//!
+//! ```yaml
+//! tiers:
+//!   - { name: Diamond, min_nights: 50, color: "#B9F2FF", sort_order: 5 }
+//! coupons:
+//!   - { code: DEMO10, name: "Demo 10% off", type: percentage, value: 10 }
+//! users:
+//!   - { email: demo@example.com, password: "demo-password", first_name: Demo, last_name: User }
+//! ```
+//!
+//! Every insert is keyed on a natural identifier (tier name, coupon code,
+//! user email, survey id) and skipped when the row already exists, so the
+//! same file can be applied on every startup.
 
-use anyhow::{Context, Result};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use rust_decimal::Decimal;
+use serde::Deserialize;
 use serde_json::json;
 use sqlx::PgPool;
 use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::models::coupon::CouponType;
+use crate::models::tier::TierBenefits;
+
+/// Sample data to seed, either built in or loaded from `SEED_FILE`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeedData {
+    #[serde(default)]
+    tiers: Vec<SeedTier>,
+    #[serde(default)]
+    coupons: Vec<SeedCoupon>,
+    #[serde(default)]
+    users: Vec<SeedUser>,
+    #[serde(default)]
+    surveys: Vec<SeedSurvey>,
+}
+
 /// Tier data for seeding
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
 struct SeedTier {
-    name: &'static str,
+    name: String,
+    #[serde(default)]
     min_points: i32,
     min_nights: i32,
+    #[serde(default = "default_tier_benefits")]
     benefits: serde_json::Value,
-    color: &'static str,
+    color: String,
     sort_order: i32,
+    #[serde(default = "default_points_multiplier")]
     points_multiplier: f64,
 }
 
+fn default_tier_benefits() -> serde_json::Value {
+    json!({})
+}
+
+fn default_points_multiplier() -> f64 {
+    1.0
+}
+
+/// Sample coupon data for seeding
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SeedCoupon {
+    code: String,
+    name: String,
+    description: Option<String>,
+    #[serde(rename = "type")]
+    coupon_type: CouponType,
+    value: Option<Decimal>,
+    currency: Option<String>,
+    minimum_spend: Option<Decimal>,
+    maximum_discount: Option<Decimal>,
+    usage_limit: Option<i32>,
+    #[serde(default = "default_usage_limit_per_user")]
+    usage_limit_per_user: i32,
+}
+
+fn default_usage_limit_per_user() -> i32 {
+    1
+}
+
+/// Sample user data for seeding
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SeedUser {
+    email: String,
+    password: String,
+    first_name: String,
+    last_name: String,
+    phone: Option<String>,
+    #[serde(default)]
+    current_points: i32,
+    #[serde(default)]
+    total_nights: i32,
+}
+
 /// Sample survey data for seeding
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
 struct SeedSurvey {
     id: Uuid,
-    title: &'static str,
-    description: &'static str,
+    title: String,
+    description: String,
     questions: serde_json::Value,
-    access_type: &'static str,
-    status: &'static str,
+    #[serde(default = "default_survey_access_type")]
+    access_type: String,
+    #[serde(default = "default_survey_status")]
+    status: String,
+}
+
+fn default_survey_access_type() -> String {
+    "public".to_string()
+}
+
+fn default_survey_status() -> String {
+    "active".to_string()
+}
+
+impl SeedData {
+    /// Built-in sample data used when no seed file is configured
+    pub fn builtin() -> Self {
+        Self {
+            tiers: get_sample_tiers(),
+            coupons: Vec::new(),
+            users: Vec::new(),
+            surveys: get_sample_surveys(),
+        }
+    }
+
+    /// Load seed data from a JSON or YAML file, chosen by extension
+    ///
+    /// Fails with the file path and the parser's line/column on malformed
+    /// input, and with a field-level message on semantically invalid rows,
+    /// so a typo in a demo file is caught at startup rather than half-applied.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read seed file {}", path.display()))?;
+
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+
+        let data: SeedData = match extension.as_deref() {
+            Some("json") => serde_json::from_str(&contents)
+                .with_context(|| format!("Malformed JSON in seed file {}", path.display()))?,
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&contents)
+                .with_context(|| format!("Malformed YAML in seed file {}", path.display()))?,
+            _ => bail!(
+                "Unsupported seed file extension for {} (expected .json, .yaml or .yml)",
+                path.display()
+            ),
+        };
+
+        data.validate()
+            .with_context(|| format!("Invalid seed file {}", path.display()))?;
+
+        Ok(data)
+    }
+
+    /// Check constraints the schema would otherwise reject mid-insert
+    fn validate(&self) -> Result<()> {
+        for tier in &self.tiers {
+            if tier.name.trim().is_empty() || tier.name.len() > 50 {
+                bail!("tier name must be 1-50 characters (got {:?})", tier.name);
+            }
+            if tier.min_nights < 0 {
+                bail!("tier {} has negative min_nights", tier.name);
+            }
+            if !is_hex_color(&tier.color) {
+                bail!(
+                    "tier {} color must be a #RRGGBB hex value (got {:?})",
+                    tier.name,
+                    tier.color
+                );
+            }
+        }
+
+        for coupon in &self.coupons {
+            if coupon.code.is_empty() || coupon.code.len() > 20 {
+                bail!(
+                    "coupon code must be 1-20 characters (got {:?})",
+                    coupon.code
+                );
+            }
+            if coupon.currency.as_ref().is_some_and(|c| c.len() != 3) {
+                bail!("coupon {} currency must be a 3-letter code", coupon.code);
+            }
+            if coupon.usage_limit_per_user < 1 {
+                bail!(
+                    "coupon {} usage_limit_per_user must be at least 1",
+                    coupon.code
+                );
+            }
+        }
+
+        for user in &self.users {
+            if !user.email.contains('@') {
+                bail!("user email {:?} is not a valid address", user.email);
+            }
+            if user.password.len() < 8 {
+                bail!("user {} password must be at least 8 characters", user.email);
+            }
+            if user.current_points < 0 || user.total_nights < 0 {
+                bail!("user {} points and nights must be non-negative", user.email);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value.len() == 7 && value.starts_with('#') && value[1..].chars().all(|c| c.is_ascii_hexdigit())
 }
 
 /// Get default tiers for the loyalty program
@@ -41,50 +239,50 @@ struct SeedSurvey {
 fn get_sample_tiers() -> Vec<SeedTier> {
     vec![
         SeedTier {
-            name: "Bronze",
+            name: "Bronze".to_string(),
             min_points: 0,
             min_nights: 0,
             benefits: json!({
                 "description": "ระดับต้อนรับสำหรับสมาชิกใหม่",
                 "perks": ["ราคาพิเศษสำหรับสมาชิก", "บริการแต่งห้องวันเกิด", "ได้รับคะแนนเพิ่ม"]
             }),
-            color: "#CD7F32",
+            color: "#CD7F32".to_string(),
             sort_order: 1,
             points_multiplier: 1.0,
         },
         SeedTier {
-            name: "Silver",
+            name: "Silver".to_string(),
             min_points: 0,
             min_nights: 1,
             benefits: json!({
                 "description": "สิทธิพิเศษระดับกลางสำหรับสมาชิกที่ใช้บริการ",
                 "perks": ["ส่วนลดเครื่องดื่ม 10%", "ได้รับคะแนนเพิ่ม"]
             }),
-            color: "#C0C0C0",
+            color: "#C0C0C0".to_string(),
             sort_order: 2,
             points_multiplier: 1.25,
         },
         SeedTier {
-            name: "Gold",
+            name: "Gold".to_string(),
             min_points: 0,
             min_nights: 10,
             benefits: json!({
                 "description": "สิทธิพิเศษระดับพรีเมียมสำหรับสมาชิกที่มีค่า",
                 "perks": ["อัพเกรดห้องฟรี", "ได้รับคะแนนเพิ่ม"]
             }),
-            color: "#D4AF37",
+            color: "#D4AF37".to_string(),
             sort_order: 3,
             points_multiplier: 1.5,
         },
         SeedTier {
-            name: "Platinum",
+            name: "Platinum".to_string(),
             min_points: 0,
             min_nights: 20,
             benefits: json!({
                 "description": "สิทธิพิเศษสุดพิเศษสำหรับสมาชิกระดับสูงสุด",
                 "perks": ["ส่วนลดพิเศษสำหรับสมาชิกขั้นสูงสุด"]
             }),
-            color: "#6B7280",
+            color: "#6B7280".to_string(),
             sort_order: 4,
             points_multiplier: 2.0,
         },
@@ -96,10 +294,10 @@ fn get_sample_surveys() -> Vec<SeedSurvey> {
     vec![
         SeedSurvey {
             id: Uuid::parse_str("b5cbde95-7faf-4268-b3e3-7047a1e4e17b").unwrap(),
-            title: "Public Test Survey",
-            description: "This is a public survey for testing the surveys page",
-            access_type: "public",
-            status: "active",
+            title: "Public Test Survey".to_string(),
+            description: "This is a public survey for testing the surveys page".to_string(),
+            access_type: "public".to_string(),
+            status: "active".to_string(),
             questions: json!([
                 {
                     "id": "q_1",
@@ -126,10 +324,10 @@ fn get_sample_surveys() -> Vec<SeedSurvey> {
         },
         SeedSurvey {
             id: Uuid::parse_str("5eb4165b-7e38-439c-9936-db47b454a7e5").unwrap(),
-            title: "Customer Satisfaction Survey",
-            description: "Tell us about your experience with our hotel services",
-            access_type: "public",
-            status: "active",
+            title: "Customer Satisfaction Survey".to_string(),
+            description: "Tell us about your experience with our hotel services".to_string(),
+            access_type: "public".to_string(),
+            status: "active".to_string(),
             questions: json!([
                 {
                     "id": "q_rating",
@@ -149,10 +347,10 @@ fn get_sample_surveys() -> Vec<SeedSurvey> {
         },
         SeedSurvey {
             id: Uuid::parse_str("c5824262-bcba-489e-ab48-5c720ff3dbb4").unwrap(),
-            title: "Service Quality Assessment",
-            description: "Help us improve our service quality",
-            access_type: "public",
-            status: "active",
+            title: "Service Quality Assessment".to_string(),
+            description: "Help us improve our service quality".to_string(),
+            access_type: "public".to_string(),
+            status: "active".to_string(),
             questions: json!([
                 {
                     "id": "q_service_rating",
@@ -180,7 +378,7 @@ pub async fn seed_essential_data(db: &PgPool) -> Result<()> {
     seed_membership_sequence(db).await?;
 
     // Seed tiers
-    seed_tiers(db).await?;
+    seed_tiers(db, &get_sample_tiers()).await?;
 
     info!("Essential data seeding completed");
     Ok(())
@@ -188,15 +386,30 @@ pub async fn seed_essential_data(db: &PgPool) -> Result<()> {
 
 /// Seed sample data for development/testing
 ///
-/// This function seeds:
+/// This function seeds, from `data`:
+/// - Additional tiers
+/// - Sample coupons
+/// - Sample users (with profile and loyalty records)
 /// - Sample surveys for testing
 ///
+/// Pass [`SeedData::builtin`] for the defaults or [`SeedData::from_path`]
+/// for a customised demo environment.
+///
 /// This should only be called in development environments.
-pub async fn seed_sample_data(db: &PgPool) -> Result<()> {
+pub async fn seed_sample_data(db: &PgPool, data: &SeedData) -> Result<()> {
     info!("Starting sample data seeding (development only)...");
 
+    // Seed tiers first so sample users can be placed into them
+    seed_tiers(db, &data.tiers).await?;
+
+    // Seed sample coupons
+    seed_coupons(db, &data.coupons).await?;
+
+    // Seed sample users
+    seed_users(db, &data.users).await?;
+
     // Seed sample surveys
-    seed_surveys(db).await?;
+    seed_surveys(db, &data.surveys).await?;
 
     info!("Sample data seeding completed");
     Ok(())
@@ -259,7 +472,7 @@ async fn seed_membership_sequence(db: &PgPool) -> Result<()> {
 /// Seed default tiers for the loyalty program
 ///
 /// Tiers define membership levels based on total nights stayed.
-async fn seed_tiers(db: &PgPool) -> Result<()> {
+async fn seed_tiers(db: &PgPool, tiers: &[SeedTier]) -> Result<()> {
     info!("Checking tiers...");
 
     // Check if the tiers table exists
@@ -281,9 +494,7 @@ async fn seed_tiers(db: &PgPool) -> Result<()> {
         return Ok(());
     }
 
-    let tiers = get_sample_tiers();
-
-    for tier in &tiers {
+    for tier in tiers {
         // Check if tier already exists
         let existing: Option<Uuid> =
             sqlx::query_scalar!("SELECT id FROM tiers WHERE name = $1", tier.name)
@@ -296,7 +507,8 @@ async fn seed_tiers(db: &PgPool) -> Result<()> {
             continue;
         }
 
-        // Insert the tier
+        // Insert the tier, with its benefits in the structured shape
+        let benefits = TierBenefits::from_json(&tier.benefits).to_json();
         sqlx::query!(
             r#"
             INSERT INTO tiers (name, min_points, min_nights, benefits, color, sort_order, is_active, created_at, updated_at)
@@ -305,7 +517,7 @@ async fn seed_tiers(db: &PgPool) -> Result<()> {
             tier.name,
             tier.min_points,
             tier.min_nights,
-            &tier.benefits,
+            &benefits,
             tier.color,
             tier.sort_order
         )
@@ -322,8 +534,150 @@ async fn seed_tiers(db: &PgPool) -> Result<()> {
     Ok(())
 }
 
+/// Seed sample coupons, keyed on coupon code
+///
+/// Seeded coupons are created `active` so they can be assigned straight away.
+async fn seed_coupons(db: &PgPool, coupons: &[SeedCoupon]) -> Result<()> {
+    if coupons.is_empty() {
+        return Ok(());
+    }
+
+    info!("Checking coupons...");
+
+    for coupon in coupons {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO coupons (
+                code, name, description, type, value, currency, minimum_spend,
+                maximum_discount, usage_limit, usage_limit_per_user, status
+            ) VALUES ($1, $2, $3, $4, $5, COALESCE($6, 'THB'), $7, $8, $9, $10, 'active')
+            ON CONFLICT (code) DO NOTHING
+            "#,
+        )
+        .bind(&coupon.code)
+        .bind(&coupon.name)
+        .bind(&coupon.description)
+        .bind(coupon.coupon_type)
+        .bind(coupon.value)
+        .bind(&coupon.currency)
+        .bind(coupon.minimum_spend)
+        .bind(coupon.maximum_discount)
+        .bind(coupon.usage_limit)
+        .bind(coupon.usage_limit_per_user)
+        .execute(db)
+        .await
+        .with_context(|| format!("Failed to insert coupon {}", coupon.code))?;
+
+        if result.rows_affected() == 0 {
+            info!("Coupon {} already exists, skipping", coupon.code);
+        } else {
+            info!("Seeded: coupon {} ({})", coupon.code, coupon.name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Seed sample users, keyed on email
+///
+/// Each user gets a profile with a fresh membership ID and a loyalty record
+/// placed into the tier their `total_nights` qualifies for.
+async fn seed_users(db: &PgPool, users: &[SeedUser]) -> Result<()> {
+    if users.is_empty() {
+        return Ok(());
+    }
+
+    info!("Checking users...");
+
+    for user in users {
+        let existing: Option<Uuid> = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+            .bind(&user.email)
+            .fetch_optional(db)
+            .await
+            .context("Failed to check existing user")?;
+
+        if existing.is_some() {
+            info!("User {} already exists, skipping", user.email);
+            continue;
+        }
+
+        let password_hash = hash_seed_password(&user.password)?;
+        let membership_id = crate::routes::auth::generate_membership_id(db)
+            .await
+            .context("Failed to generate membership ID for seed user")?;
+
+        let mut tx = db.begin().await.context("Failed to begin transaction")?;
+
+        let user_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO users (email, password_hash, email_verified)
+            VALUES ($1, $2, true)
+            RETURNING id
+            "#,
+        )
+        .bind(&user.email)
+        .bind(&password_hash)
+        .fetch_one(&mut *tx)
+        .await
+        .with_context(|| format!("Failed to insert user {}", user.email))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_profiles (user_id, first_name, last_name, phone, membership_id)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(user_id)
+        .bind(&user.first_name)
+        .bind(&user.last_name)
+        .bind(&user.phone)
+        .bind(&membership_id)
+        .execute(&mut *tx)
+        .await
+        .with_context(|| format!("Failed to insert profile for user {}", user.email))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_loyalty (user_id, current_points, total_nights, tier_id)
+            SELECT $1, $2, $3, (
+                SELECT id FROM tiers
+                WHERE is_active = true AND min_nights <= $3
+                ORDER BY min_nights DESC
+                LIMIT 1
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(user.current_points)
+        .bind(user.total_nights)
+        .execute(&mut *tx)
+        .await
+        .with_context(|| format!("Failed to insert loyalty record for user {}", user.email))?;
+
+        tx.commit().await.context("Failed to commit seed user")?;
+
+        info!("Seeded: user {} ({})", user.email, membership_id);
+    }
+
+    Ok(())
+}
+
+/// Hash a seed user's password with the same Argon2 defaults as registration
+fn hash_seed_password(password: &str) -> Result<String> {
+    use argon2::{
+        password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
+        Argon2,
+    };
+
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| anyhow::anyhow!("Failed to hash seed user password: {}", e))
+}
+
 /// Seed sample surveys for development testing
-async fn seed_surveys(db: &PgPool) -> Result<()> {
+async fn seed_surveys(db: &PgPool, surveys: &[SeedSurvey]) -> Result<()> {
     info!("Checking surveys...");
 
     // Check if the surveys table exists
@@ -345,8 +699,6 @@ async fn seed_surveys(db: &PgPool) -> Result<()> {
         return Ok(());
     }
 
-    let surveys = get_sample_surveys();
-
     for survey in surveys {
         // Check if survey already exists
         let existing: Option<Uuid> =
@@ -455,4 +807,106 @@ mod tests {
         ids.dedup();
         assert_eq!(ids.len(), surveys.len(), "Survey IDs must be unique");
     }
+
+    fn write_seed_file(extension: &str, contents: &str) -> tempfile::NamedTempFile {
+        use std::io::Write;
+
+        let mut file = tempfile::Builder::new()
+            .suffix(extension)
+            .tempfile()
+            .expect("create temp seed file");
+        file.write_all(contents.as_bytes())
+            .expect("write seed file");
+        file
+    }
+
+    #[test]
+    fn test_builtin_seed_data_matches_defaults() {
+        let data = SeedData::builtin();
+        assert_eq!(data.tiers.len(), 4);
+        assert_eq!(data.surveys.len(), 3);
+        assert!(data.coupons.is_empty());
+        assert!(data.users.is_empty());
+    }
+
+    #[test]
+    fn test_seed_file_json() {
+        let file = write_seed_file(
+            ".json",
+            r##"{
+                "tiers": [{ "name": "Diamond", "min_nights": 50, "color": "#B9F2FF", "sort_order": 5 }],
+                "coupons": [{ "code": "DEMO10", "name": "Demo", "type": "percentage", "value": 10 }],
+                "users": [{ "email": "demo@example.com", "password": "demo-password",
+                            "first_name": "Demo", "last_name": "User", "total_nights": 3 }]
+            }"##,
+        );
+
+        let data = SeedData::from_path(file.path()).expect("valid JSON seed file");
+        assert_eq!(data.tiers[0].name, "Diamond");
+        assert_eq!(data.tiers[0].points_multiplier, 1.0);
+        assert_eq!(data.coupons[0].coupon_type, CouponType::Percentage);
+        assert_eq!(data.coupons[0].usage_limit_per_user, 1);
+        assert_eq!(data.users[0].total_nights, 3);
+        assert!(data.surveys.is_empty());
+    }
+
+    #[test]
+    fn test_seed_file_yaml() {
+        let file = write_seed_file(
+            ".yaml",
+            "coupons:\n  - code: FREEUP\n    name: Free upgrade\n    type: free_upgrade\n",
+        );
+
+        let data = SeedData::from_path(file.path()).expect("valid YAML seed file");
+        assert_eq!(data.coupons.len(), 1);
+        assert_eq!(data.coupons[0].coupon_type, CouponType::FreeUpgrade);
+        assert!(data.coupons[0].value.is_none());
+    }
+
+    #[test]
+    fn test_seed_file_malformed_reports_path() {
+        let file = write_seed_file(".json", r#"{ "tiers": [ { "name": "Broken" "#);
+
+        let err = SeedData::from_path(file.path()).expect_err("malformed JSON must fail");
+        let message = format!("{:#}", err);
+        assert!(message.contains("Malformed JSON"), "got: {message}");
+        assert!(
+            message.contains(&file.path().display().to_string()),
+            "got: {message}"
+        );
+    }
+
+    #[test]
+    fn test_seed_file_rejects_unknown_fields() {
+        let file = write_seed_file(".yml", "coupon:\n  - code: TYPO\n");
+
+        let err = SeedData::from_path(file.path()).expect_err("unknown key must fail");
+        assert!(format!("{:#}", err).contains("unknown field"));
+    }
+
+    #[test]
+    fn test_seed_file_rejects_unsupported_extension() {
+        let file = write_seed_file(".toml", "");
+
+        let err = SeedData::from_path(file.path()).expect_err("unsupported extension");
+        assert!(err.to_string().contains("Unsupported seed file extension"));
+    }
+
+    #[test]
+    fn test_seed_file_rejects_invalid_tier_color() {
+        let file = write_seed_file(
+            ".yaml",
+            "tiers:\n  - { name: Diamond, min_nights: 50, color: blue, sort_order: 5 }\n",
+        );
+
+        let err = SeedData::from_path(file.path()).expect_err("invalid color must fail");
+        assert!(format!("{:#}", err).contains("#RRGGBB"));
+    }
+
+    #[test]
+    fn test_seed_file_missing() {
+        let err = SeedData::from_path(Path::new("/nonexistent/seed.json"))
+            .expect_err("missing file must fail");
+        assert!(err.to_string().contains("Failed to read seed file"));
+    }
 }
@@ -4,7 +4,7 @@
 //! All errors are converted to appropriate HTTP responses with consistent JSON format.
 
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -93,6 +93,18 @@ pub enum AppError {
     #[error("Session expired")]
     SessionExpired,
 
+    /// A sensitive action needs the password entered again
+    /// (`POST /api/auth/reauth`). 403 rather than 401 so clients don't
+    /// treat it as an expired token and just refresh it.
+    #[error("Re-authentication required")]
+    ReauthRequired,
+
+    /// The password is past its maximum age (`ADMIN_PASSWORD_MAX_AGE_DAYS`
+    /// / `CUSTOMER_PASSWORD_MAX_AGE_DAYS`); no tokens are issued until it
+    /// is changed through `POST /api/auth/password/expired`.
+    #[error("Password expired")]
+    PasswordExpired,
+
     // Authorization errors
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
@@ -133,6 +145,11 @@ pub enum AppError {
     #[error("Bad request: {0}")]
     BadRequest(String),
 
+    /// A coupon redemption refused because the coupon (or the user's share
+    /// of it) has no uses left
+    #[error("Coupon usage limit reached: {0}")]
+    CouponLimitReached(String),
+
     #[error("Missing required field: {0}")]
     MissingField(String),
 
@@ -216,6 +233,8 @@ impl AppError {
             Self::AccountLocked(_) => "account_locked",
             Self::AccountNotVerified => "account_not_verified",
             Self::SessionExpired => "session_expired",
+            Self::ReauthRequired => "reauth_required",
+            Self::PasswordExpired => "password_expired",
 
             // Authorization errors
             Self::Unauthorized(_) => "unauthorized",
@@ -235,6 +254,7 @@ impl AppError {
 
             // Request errors
             Self::BadRequest(_) => "bad_request",
+            Self::CouponLimitReached(_) => "coupon_usage_limit_reached",
             Self::MissingField(_) => "missing_field",
             Self::InvalidFormat(_) => "invalid_format",
             Self::PayloadTooLarge => "payload_too_large",
@@ -289,6 +309,8 @@ impl AppError {
             Self::AccountLocked(_) => StatusCode::UNAUTHORIZED,
             Self::AccountNotVerified => StatusCode::UNAUTHORIZED,
             Self::SessionExpired => StatusCode::UNAUTHORIZED,
+            Self::ReauthRequired => StatusCode::FORBIDDEN,
+            Self::PasswordExpired => StatusCode::FORBIDDEN,
 
             // Authorization errors - 401/403
             Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
@@ -308,6 +330,7 @@ impl AppError {
 
             // Request errors - 400
             Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::CouponLimitReached(_) => StatusCode::BAD_REQUEST,
             Self::MissingField(_) => StatusCode::BAD_REQUEST,
             Self::InvalidFormat(_) => StatusCode::BAD_REQUEST,
             Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
@@ -359,6 +382,10 @@ impl AppError {
             Self::AccountLocked(reason) => format!("Account locked: {}", reason),
             Self::AccountNotVerified => "Please verify your email address".to_string(),
             Self::SessionExpired => "Your session has expired, please log in again".to_string(),
+            Self::ReauthRequired => "Please confirm your password to continue".to_string(),
+            Self::PasswordExpired => {
+                "Your password has expired. Please choose a new one.".to_string()
+            },
 
             // Authorization - safe to expose
             Self::Unauthorized(msg) => msg.clone(),
@@ -378,6 +405,7 @@ impl AppError {
 
             // Request errors - safe to expose
             Self::BadRequest(msg) => msg.clone(),
+            Self::CouponLimitReached(msg) => msg.clone(),
             Self::MissingField(field) => format!("Missing required field: {}", field),
             Self::InvalidFormat(msg) => msg.clone(),
             Self::PayloadTooLarge => "Request payload is too large".to_string(),
@@ -452,6 +480,16 @@ impl IntoResponse for AppError {
             _ => ErrorResponse::new(error_code, message),
         };
 
+        // Tell well-behaved clients when to come back
+        if let AppError::TooManyRequests(seconds) = &self {
+            return (
+                status,
+                [(header::RETRY_AFTER, seconds.to_string())],
+                Json(body),
+            )
+                .into_response();
+        }
+
         (status, Json(body)).into_response()
     }
 }
@@ -547,6 +585,10 @@ mod tests {
             AppError::BadRequest("test".to_string()).status_code(),
             StatusCode::BAD_REQUEST
         );
+        assert_eq!(
+            AppError::CouponLimitReached("test".to_string()).status_code(),
+            StatusCode::BAD_REQUEST
+        );
         assert_eq!(
             AppError::RateLimitExceeded.status_code(),
             StatusCode::TOO_MANY_REQUESTS
@@ -568,6 +610,10 @@ mod tests {
             AppError::NotFound("test".to_string()).error_code(),
             "not_found"
         );
+        assert_eq!(
+            AppError::CouponLimitReached("test".to_string()).error_code(),
+            "coupon_usage_limit_reached"
+        );
         assert_eq!(
             AppError::RateLimitExceeded.error_code(),
             "rate_limit_exceeded"
@@ -585,6 +631,16 @@ mod tests {
         assert_eq!(not_found.user_message(), "User not found");
     }
 
+    #[test]
+    fn test_too_many_requests_sets_retry_after() {
+        let response = AppError::TooManyRequests(42).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "42");
+
+        let response = AppError::RateLimitExceeded.into_response();
+        assert!(response.headers().get(header::RETRY_AFTER).is_none());
+    }
+
     #[test]
     fn test_option_ext_ok_or_not_found() {
         let some_value: Option<i32> = Some(42);
@@ -1,23 +1,28 @@
 //! Admin Authorization Middleware
 //!
 //! Checks if the authenticated user has admin privileges by verifying
-//! their email against the configured admin list in admins.json.
+//! their email against the configured admin list in admins.json, and
+//! optionally restricts admin endpoints to an allowlist of client networks.
 
 use axum::{
-    extract::Request,
+    extract::{Request, State},
     http::StatusCode,
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
+use ipnet::IpNet;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 use std::fs;
+use std::net::IpAddr;
 use std::path::Path;
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 
+use crate::config::{parse_ip_networks, SecurityConfig};
 use crate::error::ErrorResponse;
 use crate::middleware::auth::AuthUser;
+use crate::middleware::rate_limit::get_client_ip;
 
 /// Admin configuration loaded from admins.json
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -190,6 +195,7 @@ pub enum AdminAuthError {
     NotAuthenticated,
     NotAdmin,
     NotSuperAdmin,
+    IpNotAllowed,
 }
 
 impl IntoResponse for AdminAuthError {
@@ -208,6 +214,11 @@ impl IntoResponse for AdminAuthError {
                 "forbidden",
                 "Super admin access required",
             ),
+            AdminAuthError::IpNotAllowed => (
+                StatusCode::FORBIDDEN,
+                "forbidden",
+                "Admin access is not permitted from this network",
+            ),
         };
 
         let body = Json(ErrorResponse {
@@ -296,6 +307,117 @@ pub async fn super_admin_middleware(
     Ok(next.run(request).await)
 }
 
+/// Network allowlist for admin endpoints
+///
+/// Built from `SecurityConfig::admin_ip_allowlist` and
+/// `SecurityConfig::trusted_proxies`. An empty allowlist means "no
+/// restriction", so deployments that don't set `ADMIN_IP_ALLOWLIST` behave
+/// exactly as before.
+#[derive(Debug, Clone, Default)]
+pub struct AdminIpAllowlist {
+    allowed: Arc<Vec<IpNet>>,
+    trusted_proxies: Arc<Vec<IpNet>>,
+}
+
+impl AdminIpAllowlist {
+    /// Build the allowlist from security settings.
+    ///
+    /// Both lists are already checked by `Settings::validate`, so an error
+    /// here means the settings were constructed without validation.
+    pub fn from_config(security: &SecurityConfig) -> Result<Self, String> {
+        Ok(Self {
+            allowed: Arc::new(parse_ip_networks(&security.admin_ip_allowlist)?),
+            trusted_proxies: Arc::new(parse_ip_networks(&security.trusted_proxies)?),
+        })
+    }
+
+    /// Whether any restriction is configured
+    pub fn is_enabled(&self) -> bool {
+        !self.allowed.is_empty()
+    }
+
+    /// Whether `ip` may reach admin endpoints
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        !self.is_enabled() || self.allowed.iter().any(|net| net.contains(&ip))
+    }
+
+    /// Resolve the real client IP for a request.
+    ///
+    /// Starts from the TCP peer (`ConnectInfo`, same source as the rate
+    /// limiter — see `get_client_ip`). Only when that peer is a configured
+    /// trusted proxy do we consult `X-Forwarded-For`, walking it right to
+    /// left and returning the first hop that is not itself a trusted
+    /// proxy. Anything to the left of that hop was supplied by the client
+    /// and is ignored, so a spoofed header can't talk its way past the
+    /// allowlist.
+    pub fn resolve_client_ip(&self, request: &Request) -> IpAddr {
+        let peer = get_client_ip(request);
+        if !self.is_trusted_proxy(peer) {
+            return peer;
+        }
+
+        let forwarded = request
+            .headers()
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+
+        for hop in forwarded.rsplit(',') {
+            match hop.trim().parse::<IpAddr>() {
+                Ok(ip) if self.is_trusted_proxy(ip) => continue,
+                Ok(ip) => return ip,
+                // A malformed hop means we can't trust anything further
+                // left; fall back to the proxy itself.
+                Err(_) => break,
+            }
+        }
+
+        peer
+    }
+
+    fn is_trusted_proxy(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(&ip))
+    }
+}
+
+/// Whether `path` is an admin endpoint.
+///
+/// Admin handlers live under `/api/admin` and under `/admin/` sub-paths of
+/// other route groups (e.g. `/api/loyalty/admin/award-points`,
+/// `/api/notifications/admin/cleanup`).
+fn is_admin_path(path: &str) -> bool {
+    path == "/api/admin" || path.starts_with("/api/admin/") || path.contains("/admin/")
+}
+
+/// Admin IP allowlist middleware
+///
+/// Rejects requests to admin endpoints with 403 when the resolved client IP
+/// is outside the configured allowlist. Non-admin paths and deployments
+/// without an allowlist pass straight through. Layered over the whole API
+/// router in `routes::create_router` so it runs before authentication —
+/// a disallowed network never gets as far as validating a token.
+pub async fn admin_ip_allowlist_middleware(
+    State(allowlist): State<AdminIpAllowlist>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AdminAuthError> {
+    if !allowlist.is_enabled() || !is_admin_path(request.uri().path()) {
+        return Ok(next.run(request).await);
+    }
+
+    let client_ip = allowlist.resolve_client_ip(&request);
+    if !allowlist.allows(client_ip) {
+        tracing::warn!(
+            client_ip = %crate::utils::sanitize_ip(Some(&client_ip.to_string())),
+            path = %request.uri().path(),
+            "Admin access denied: client IP not in allowlist"
+        );
+        return Err(AdminAuthError::IpNotAllowed);
+    }
+
+    Ok(next.run(request).await)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -361,18 +483,24 @@ mod tests {
             id: "user-1".to_string(),
             email: None,
             role: "admin".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
 
         let super_admin_user = AuthUser {
             id: "user-2".to_string(),
             email: None,
             role: "super_admin".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
 
         let customer_user = AuthUser {
             id: "user-3".to_string(),
             email: None,
             role: "customer".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
 
         assert!(is_admin(&admin_user));
@@ -386,12 +514,16 @@ mod tests {
             id: "user-1".to_string(),
             email: None,
             role: "admin".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
 
         let super_admin_user = AuthUser {
             id: "user-2".to_string(),
             email: None,
             role: "super_admin".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
 
         assert!(!is_super_admin(&admin_user));
@@ -435,4 +567,85 @@ mod tests {
         assert!(config.super_admin_emails.is_empty());
         assert!(config.description.is_empty());
     }
+
+    fn allowlist(allowed: &str, trusted_proxies: &str) -> AdminIpAllowlist {
+        AdminIpAllowlist::from_config(&SecurityConfig {
+            admin_ip_allowlist: allowed.to_string(),
+            trusted_proxies: trusted_proxies.to_string(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn request_from(peer: &str, forwarded_for: Option<&str>) -> Request {
+        let mut builder = Request::builder().uri("/api/admin/users");
+        if let Some(value) = forwarded_for {
+            builder = builder.header("x-forwarded-for", value);
+        }
+        let mut req = builder.body(axum::body::Body::empty()).unwrap();
+        let addr: std::net::SocketAddr = format!("{}:40000", peer).parse().unwrap();
+        req.extensions_mut()
+            .insert(axum::extract::ConnectInfo(addr));
+        req
+    }
+
+    #[test]
+    fn test_empty_allowlist_allows_everyone() {
+        let list = AdminIpAllowlist::default();
+        assert!(!list.is_enabled());
+        assert!(list.allows("198.51.100.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_allowlist_matches_cidr_and_single_ip() {
+        let list = allowlist("10.8.0.0/24,203.0.113.7", "");
+        assert!(list.allows("10.8.0.200".parse().unwrap()));
+        assert!(list.allows("203.0.113.7".parse().unwrap()));
+        assert!(!list.allows("10.8.1.1".parse().unwrap()));
+        assert!(!list.allows("203.0.113.8".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_resolve_client_ip_ignores_forwarded_for_from_untrusted_peer() {
+        let list = allowlist("10.8.0.0/24", "");
+        let req = request_from("198.51.100.9", Some("10.8.0.5"));
+        assert_eq!(
+            list.resolve_client_ip(&req),
+            "198.51.100.9".parse::<IpAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_resolve_client_ip_uses_forwarded_for_behind_trusted_proxy() {
+        let list = allowlist("10.8.0.0/24", "172.18.0.0/16");
+        // Client spoofs a leading allowlisted hop; the proxy appends the
+        // real address, which is what we must judge.
+        let req = request_from("172.18.0.2", Some("10.8.0.5, 198.51.100.9"));
+        let ip = list.resolve_client_ip(&req);
+        assert_eq!(ip, "198.51.100.9".parse::<IpAddr>().unwrap());
+        assert!(!list.allows(ip));
+
+        let req = request_from("172.18.0.2", Some("10.8.0.5"));
+        assert!(list.allows(list.resolve_client_ip(&req)));
+    }
+
+    #[test]
+    fn test_resolve_client_ip_falls_back_to_proxy_without_header() {
+        let list = allowlist("10.8.0.0/24", "172.18.0.0/16");
+        let req = request_from("172.18.0.2", None);
+        assert_eq!(
+            list.resolve_client_ip(&req),
+            "172.18.0.2".parse::<IpAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_is_admin_path() {
+        assert!(is_admin_path("/api/admin"));
+        assert!(is_admin_path("/api/admin/users"));
+        assert!(is_admin_path("/api/loyalty/admin/award-points"));
+        assert!(is_admin_path("/api/notifications/admin/cleanup"));
+        assert!(!is_admin_path("/api/loyalty/status"));
+        assert!(!is_admin_path("/api/administrator"));
+    }
 }
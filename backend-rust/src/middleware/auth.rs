@@ -14,7 +14,7 @@ use axum_extra::extract::cookie::Cookie;
 use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
 
-use crate::error::ErrorResponse;
+use crate::error::{AppError, ErrorResponse};
 
 // ============================================================================
 // Refresh-token Cookie Helpers (Phase 1 of HttpOnly cookie migration)
@@ -107,6 +107,85 @@ pub fn build_clear_refresh_cookie() -> Cookie<'static> {
 #[derive(Clone)]
 pub struct JwtSecret(pub String);
 
+/// Redis key prefix for per-user session revocation timestamps
+pub const SESSIONS_REVOKED_KEY_PREFIX: &str = "auth:sessions_revoked_at:";
+
+/// Redis key prefix for single revoked sessions, keyed by session id
+pub const SESSION_REVOKED_KEY_PREFIX: &str = "auth:session_revoked:";
+
+/// Per-user "every session up to now is revoked" markers, injected as an
+/// extension by `create_router`.
+///
+/// Access tokens are stateless JWTs, so deleting refresh tokens alone
+/// leaves an already-issued access token usable until it expires.
+/// [`SessionRevocations::revoke_all`] records the revocation time in Redis
+/// and `auth_middleware` rejects tokens issued at or before it. The marker
+/// only needs to outlive the longest-lived access token, so it expires
+/// after `ttl_secs`. Routers built without the extension (tests) skip the
+/// check, and a Redis error fails open like the rate limiter.
+#[derive(Clone)]
+pub struct SessionRevocations(pub redis::aio::ConnectionManager);
+
+impl SessionRevocations {
+    /// Revoke every token issued to `user_id` so far
+    pub async fn revoke_all(&self, user_id: &str, ttl_secs: u64) -> Result<(), redis::RedisError> {
+        let mut redis = self.0.clone();
+        redis::AsyncCommands::set_ex::<_, _, ()>(
+            &mut redis,
+            format!("{}{}", SESSIONS_REVOKED_KEY_PREFIX, user_id),
+            chrono::Utc::now().timestamp(),
+            ttl_secs.max(1),
+        )
+        .await
+    }
+
+    /// Revoke the access tokens of one session (see [`Claims::sid`])
+    pub async fn revoke_session(
+        &self,
+        session_id: &str,
+        ttl_secs: u64,
+    ) -> Result<(), redis::RedisError> {
+        let mut redis = self.0.clone();
+        redis::AsyncCommands::set_ex::<_, _, ()>(
+            &mut redis,
+            format!("{}{}", SESSION_REVOKED_KEY_PREFIX, session_id),
+            chrono::Utc::now().timestamp(),
+            ttl_secs.max(1),
+        )
+        .await
+    }
+
+    /// Whether the session `session_id` was revoked, if within the TTL
+    async fn is_session_revoked(&self, session_id: &str) -> Result<bool, redis::RedisError> {
+        let mut redis = self.0.clone();
+        redis::AsyncCommands::exists(
+            &mut redis,
+            format!("{}{}", SESSION_REVOKED_KEY_PREFIX, session_id),
+        )
+        .await
+    }
+
+    /// When `user_id`'s sessions were last revoked, if within the TTL
+    async fn revoked_at(&self, user_id: &str) -> Result<Option<i64>, redis::RedisError> {
+        let mut redis = self.0.clone();
+        redis::AsyncCommands::get(
+            &mut redis,
+            format!("{}{}", SESSIONS_REVOKED_KEY_PREFIX, user_id),
+        )
+        .await
+    }
+}
+
+/// Whether a token issued at `iat` predates a revocation at `revoked_at`.
+/// Tokens without `iat` can't prove they are newer, so they are revoked.
+fn is_session_revoked(iat: Option<i64>, revoked_at: Option<i64>) -> bool {
+    match (iat, revoked_at) {
+        (_, None) => false,
+        (None, Some(_)) => true,
+        (Some(iat), Some(revoked_at)) => iat <= revoked_at,
+    }
+}
+
 /// JWT claims structure matching the Node.js backend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
@@ -120,6 +199,15 @@ pub struct Claims {
     pub iat: Option<i64>,
     /// Expiration timestamp
     pub exp: i64,
+    /// When the user last entered their credentials (login or re-auth).
+    /// Carried over unchanged when the token is refreshed.
+    #[serde(default)]
+    pub last_auth_at: Option<i64>,
+    /// The login session the token belongs to, listed by
+    /// `GET /api/auth/sessions`. Tokens minted outside a session (OAuth,
+    /// tests) have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
 }
 
 /// User context extracted from JWT, available in request extensions
@@ -128,6 +216,10 @@ pub struct AuthUser {
     pub id: String,
     pub email: Option<String>,
     pub role: String,
+    /// See [`Claims::last_auth_at`]
+    pub last_auth_at: Option<i64>,
+    /// See [`Claims::sid`]
+    pub session_id: Option<String>,
 }
 
 impl From<Claims> for AuthUser {
@@ -136,10 +228,40 @@ impl From<Claims> for AuthUser {
             id: claims.id,
             email: claims.email,
             role: claims.role,
+            last_auth_at: claims.last_auth_at,
+            session_id: claims.sid,
         }
     }
 }
 
+/// Whether credentials entered at `last_auth_at` are recent enough at
+/// `now`. Tokens issued before `last_auth_at` existed have none, so they
+/// always need a re-auth.
+fn is_recent_auth(last_auth_at: Option<i64>, now: i64, max_age_secs: u64) -> bool {
+    match last_auth_at {
+        Some(at) => now.saturating_sub(at) <= max_age_secs as i64,
+        None => false,
+    }
+}
+
+/// Require that `user` entered their credentials within `max_age_secs`.
+///
+/// Called by sensitive admin handlers (role changes, deletions) only, so
+/// ordinary reads never trigger it. A stale session gets
+/// [`AppError::ReauthRequired`]; the client confirms the password via
+/// `POST /api/auth/reauth` and retries with the token it returns.
+pub fn require_recent_auth(user: &AuthUser, max_age_secs: u64) -> Result<(), AppError> {
+    if is_recent_auth(
+        user.last_auth_at,
+        chrono::Utc::now().timestamp(),
+        max_age_secs,
+    ) {
+        Ok(())
+    } else {
+        Err(AppError::ReauthRequired)
+    }
+}
+
 /// Authentication error types
 #[derive(Debug)]
 pub enum AuthError {
@@ -147,6 +269,7 @@ pub enum AuthError {
     InvalidToken,
     ExpiredToken,
     MalformedHeader,
+    SessionRevoked,
 }
 
 impl IntoResponse for AuthError {
@@ -172,6 +295,11 @@ impl IntoResponse for AuthError {
                 "No token provided",
                 "Malformed authorization header",
             ),
+            AuthError::SessionRevoked => (
+                StatusCode::UNAUTHORIZED,
+                "Session revoked",
+                "Your session has been revoked. Please sign in again.",
+            ),
         };
 
         let body = Json(ErrorResponse {
@@ -269,6 +397,31 @@ pub async fn auth_middleware(mut request: Request, next: Next) -> Result<Respons
     let token = extract_bearer_token(auth_header)?;
     let claims = validate_token(token, &jwt_secret)?;
 
+    // Reject tokens issued before the user's sessions were revoked
+    // (account deletion, logout everywhere), or belonging to a single
+    // session that was ended.
+    if let Some(revocations) = request.extensions().get::<SessionRevocations>().cloned() {
+        match revocations.revoked_at(&claims.id).await {
+            Ok(revoked_at) if is_session_revoked(claims.iat, revoked_at) => {
+                return Err(AuthError::SessionRevoked);
+            },
+            Ok(_) => {},
+            Err(e) => {
+                tracing::warn!(error = %e, "Session revocation check unavailable; allowing token");
+            },
+        }
+
+        if let Some(session_id) = claims.sid.as_deref() {
+            match revocations.is_session_revoked(session_id).await {
+                Ok(true) => return Err(AuthError::SessionRevoked),
+                Ok(false) => {},
+                Err(e) => {
+                    tracing::warn!(error = %e, "Session revocation check unavailable; allowing token");
+                },
+            }
+        }
+    }
+
     // Add user info to request extensions
     let auth_user = AuthUser::from(claims);
     request.extensions_mut().insert(auth_user);
@@ -311,6 +464,27 @@ pub async fn optional_auth_middleware(mut request: Request, next: Next) -> Respo
     next.run(request).await
 }
 
+/// The authenticated user's id for `request`, for middleware that runs
+/// outside the per-router auth layers (the rate limiter).
+///
+/// Uses the [`AuthUser`] extension when an auth layer already ran,
+/// otherwise validates the bearer token against the injected
+/// [`JwtSecret`]. Session revocation is not checked — a revoked token
+/// still gets a 401 from `auth_middleware`. Any failure means `None`, and
+/// the caller treats the request as anonymous.
+pub(crate) fn request_user_id(request: &Request) -> Option<String> {
+    if let Some(user) = request.extensions().get::<AuthUser>() {
+        return Some(user.id.clone());
+    }
+
+    let jwt_secret = request.extensions().get::<JwtSecret>()?;
+    let auth_header = request.headers().get(AUTHORIZATION)?.to_str().ok()?;
+    let token = extract_bearer_token(auth_header).ok()?;
+    validate_token(token, &jwt_secret.0)
+        .ok()
+        .map(|claims| claims.id)
+}
+
 /// Role-based authorization check
 ///
 /// Returns true if the user has the required role or higher privilege level.
@@ -414,6 +588,8 @@ mod tests {
             role: "customer".to_string(),
             iat: Some(Utc::now().timestamp()),
             exp: Utc::now().timestamp() + 3600,
+            last_auth_at: None,
+            sid: None,
         };
 
         let token = create_test_token(&claims, secret);
@@ -433,6 +609,8 @@ mod tests {
             role: "customer".to_string(),
             iat: Some(Utc::now().timestamp() - 7200),
             exp: Utc::now().timestamp() - 3600, // Expired 1 hour ago
+            last_auth_at: None,
+            sid: None,
         };
 
         let token = create_test_token(&claims, secret);
@@ -449,6 +627,8 @@ mod tests {
             role: "customer".to_string(),
             iat: Some(Utc::now().timestamp()),
             exp: Utc::now().timestamp() + 3600,
+            last_auth_at: None,
+            sid: None,
         };
 
         let token = create_test_token(&claims, "secret1");
@@ -463,6 +643,8 @@ mod tests {
             id: "1".to_string(),
             email: None,
             role: "customer".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
 
         assert!(has_role(&user, "customer"));
@@ -476,6 +658,8 @@ mod tests {
             id: "1".to_string(),
             email: None,
             role: "admin".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
 
         assert!(has_role(&user, "customer"));
@@ -489,6 +673,8 @@ mod tests {
             id: "1".to_string(),
             email: None,
             role: "super_admin".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
 
         assert!(has_role(&user, "customer"));
@@ -565,4 +751,29 @@ mod tests {
         assert!(serialised.contains("SameSite=Strict"));
         assert!(serialised.contains("Path=/api/auth"));
     }
+
+    #[test]
+    fn test_is_session_revoked() {
+        // No revocation recorded: every token passes
+        assert!(!is_session_revoked(Some(1_000), None));
+        assert!(!is_session_revoked(None, None));
+
+        // Tokens issued at or before the revocation are rejected
+        assert!(is_session_revoked(Some(1_000), Some(1_000)));
+        assert!(is_session_revoked(Some(999), Some(1_000)));
+        assert!(!is_session_revoked(Some(1_001), Some(1_000)));
+
+        // A token without `iat` can't show it is newer
+        assert!(is_session_revoked(None, Some(1_000)));
+    }
+
+    #[test]
+    fn test_is_recent_auth() {
+        assert!(is_recent_auth(Some(1_000), 1_000, 300));
+        assert!(is_recent_auth(Some(1_000), 1_300, 300));
+        assert!(!is_recent_auth(Some(1_000), 1_301, 300));
+
+        // Tokens without the claim always need a re-auth
+        assert!(!is_recent_auth(None, 1_000, 300));
+    }
 }
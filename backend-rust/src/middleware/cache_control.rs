@@ -0,0 +1,97 @@
+//! `Cache-Control` for API responses
+//!
+//! Every API response is uncacheable unless its handler says otherwise:
+//! [`cache_control_middleware`] adds `Cache-Control: no-store` to any
+//! response that doesn't already carry a `Cache-Control` header. Handlers
+//! serving genuinely public data (identical for every caller, with or
+//! without a token) opt in by returning a [`CachePolicy`] alongside the
+//! body:
+//!
+//! ```ignore
+//! let max_age = state.config().http_cache.tiers_max_age_secs;
+//! Ok((CachePolicy::public(max_age), Json(tiers)))
+//! ```
+//!
+//! Authenticated, user-specific handlers never return a policy, so they
+//! always get `no-store`. Because a policy is only attached on the `Ok`
+//! path, error responses from a cacheable handler are `no-store` too.
+//!
+//! Endpoints that don't need auth but whose answer changes on a write are
+//! *not* public in this sense: `GET /api/coupons/validate/:qrCode` reports
+//! whether a coupon is still redeemable, so a cached copy could show a
+//! redeemed coupon as valid.
+
+use axum::{
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponseParts, Response, ResponseParts},
+};
+use std::convert::Infallible;
+
+/// `Cache-Control` value for responses that must not be stored anywhere
+pub const NO_STORE: &str = "no-store";
+
+/// How a handler's response may be cached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Not stored by browsers or shared caches (the default)
+    NoStore,
+    /// Cacheable by browsers and CDNs for `max_age` seconds
+    Public { max_age: u32 },
+}
+
+impl CachePolicy {
+    /// Public caching for `max_age` seconds; `0` means [`CachePolicy::NoStore`]
+    pub fn public(max_age: u32) -> Self {
+        if max_age == 0 {
+            Self::NoStore
+        } else {
+            Self::Public { max_age }
+        }
+    }
+
+    /// The `Cache-Control` header value
+    pub fn header_value(&self) -> String {
+        match self {
+            Self::NoStore => NO_STORE.to_string(),
+            Self::Public { max_age } => format!("public, max-age={}", max_age),
+        }
+    }
+}
+
+impl IntoResponseParts for CachePolicy {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        if let Ok(value) = HeaderValue::from_str(&self.header_value()) {
+            res.headers_mut().insert(header::CACHE_CONTROL, value);
+        }
+        Ok(res)
+    }
+}
+
+/// Default every response without a `Cache-Control` header to `no-store`
+pub async fn cache_control_middleware(request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    response
+        .headers_mut()
+        .entry(header::CACHE_CONTROL)
+        .or_insert(HeaderValue::from_static(NO_STORE));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_policy_header_value() {
+        assert_eq!(CachePolicy::NoStore.header_value(), "no-store");
+        assert_eq!(
+            CachePolicy::public(300).header_value(),
+            "public, max-age=300"
+        );
+        assert_eq!(CachePolicy::public(0), CachePolicy::NoStore);
+    }
+}
@@ -0,0 +1,296 @@
+//! CSRF protection for cookie-authenticated requests
+//!
+//! Double-submit cookie pattern: `GET /api/auth/csrf-token` sets a random
+//! token in the [`CSRF_COOKIE_NAME`] cookie and returns it in the body, and
+//! the browser client echoes it in the `X-CSRF-Token` header on every
+//! state-changing request. Another site can make the browser send the
+//! cookie but can't read it, so it can't produce a matching header.
+//!
+//! Only requests that a browser could have been tricked into sending are
+//! checked:
+//! - POST/PUT/PATCH/DELETE to a path under one of the configured
+//!   `CSRF_PROTECTED_PATHS` prefixes; empty (the default) turns the check
+//!   off
+//! - that carry cookies; a request with none has no ambient credentials
+//!   to abuse
+//! - that don't authenticate with an `Authorization: Bearer` header, which
+//!   a cross-site form or fetch can't attach, so API clients are exempt
+//!
+//! OAuth callbacks and inbound webhooks arrive from third parties that
+//! never saw the token, so they are exempt whatever the configuration.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::extract::cookie::Cookie;
+
+use crate::config::SecurityConfig;
+use crate::error::ErrorResponse;
+
+/// Cookie holding the CSRF token. Readable by JavaScript on purpose: the
+/// frontend copies it into [`CSRF_HEADER_NAME`].
+pub const CSRF_COOKIE_NAME: &str = "csrf_token";
+
+/// Header a protected request must repeat the cookie's token in
+pub const CSRF_HEADER_NAME: &str = "x-csrf-token";
+
+/// Paths never checked: OAuth provider callbacks and inbound webhooks
+const EXEMPT_PREFIXES: &[&str] = &["/api/oauth/", "/api/webhooks/"];
+
+/// Generate a new random CSRF token (32 bytes, URL-safe base64)
+pub fn generate_csrf_token() -> String {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use rand::RngCore;
+
+    let mut token_bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut token_bytes);
+    URL_SAFE_NO_PAD.encode(token_bytes)
+}
+
+/// Whether `token` looks like one [`generate_csrf_token`] made, so a
+/// client-supplied cookie can be handed back as-is
+pub fn is_well_formed_csrf_token(token: &str) -> bool {
+    token.len() == 43
+        && token
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+/// Build the `Set-Cookie` value for the CSRF token.
+///
+/// Unlike the refresh cookie this one is not `HttpOnly` — the frontend has
+/// to read it — and is scoped to `/` so it can be echoed on any API call.
+/// `SameSite=Strict` and `Secure` match the refresh cookie.
+pub fn build_csrf_cookie_header(token: &str) -> String {
+    format!(
+        "{name}={value}; Path=/; Secure; SameSite=Strict",
+        name = CSRF_COOKIE_NAME,
+        value = token,
+    )
+}
+
+/// Build an `axum_extra::Cookie` for the CSRF token, for a `CookieJar`
+pub fn build_csrf_cookie(token: &str) -> Cookie<'static> {
+    Cookie::parse_encoded(build_csrf_cookie_header(token))
+        .expect("csrf cookie header is well-formed by construction")
+        .into_owned()
+}
+
+/// Middleware state: the path prefixes the check applies to
+#[derive(Debug, Clone, Default)]
+pub struct CsrfProtection {
+    protected_paths: Vec<String>,
+}
+
+impl CsrfProtection {
+    /// Read `CSRF_PROTECTED_PATHS` (comma-separated path prefixes)
+    pub fn from_config(security: &SecurityConfig) -> Self {
+        Self::new(&security.csrf_protected_paths)
+    }
+
+    fn new(list: &str) -> Self {
+        let protected_paths = list
+            .split(',')
+            .map(|entry| entry.trim().trim_end_matches('/'))
+            .filter(|entry| !entry.is_empty())
+            .map(str::to_string)
+            .collect();
+        Self { protected_paths }
+    }
+
+    /// Whether any path is protected at all
+    pub fn is_enabled(&self) -> bool {
+        !self.protected_paths.is_empty()
+    }
+
+    /// Whether `path` falls under a protected prefix (on a segment
+    /// boundary, so `/api/auth` doesn't cover `/api/authz`)
+    fn covers(&self, path: &str) -> bool {
+        self.protected_paths.iter().any(|prefix| {
+            path == prefix
+                || path
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+
+    /// Whether a request must carry a matching CSRF token
+    fn requires_token(&self, method: &Method, path: &str, headers: &HeaderMap) -> bool {
+        let state_changing = matches!(
+            *method,
+            Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+        );
+        if !state_changing
+            || !self.covers(path)
+            || EXEMPT_PREFIXES.iter().any(|p| path.starts_with(p))
+        {
+            return false;
+        }
+
+        let bearer = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("Bearer "));
+        !bearer && headers.contains_key(header::COOKIE)
+    }
+}
+
+/// The value of cookie `name` from the request's `Cookie` headers
+fn request_cookie<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Compare two tokens without returning early on the first difference
+fn tokens_match(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+/// Check the double-submitted token, returning why it failed if it did
+fn check_token(headers: &HeaderMap) -> Result<(), &'static str> {
+    let cookie = request_cookie(headers, CSRF_COOKIE_NAME)
+        .filter(|token| !token.is_empty())
+        .ok_or("CSRF cookie missing; fetch /api/auth/csrf-token first")?;
+    let header = headers
+        .get(CSRF_HEADER_NAME)
+        .and_then(|v| v.to_str().ok())
+        .ok_or("X-CSRF-Token header missing")?;
+
+    if tokens_match(cookie, header) {
+        Ok(())
+    } else {
+        Err("X-CSRF-Token header does not match the CSRF cookie")
+    }
+}
+
+/// The 403 body returned for a rejected request
+fn csrf_failed_response(message: &str) -> Response {
+    let body = Json(ErrorResponse {
+        error: "csrf_failed".to_string(),
+        message: message.to_string(),
+        details: None,
+    });
+
+    (StatusCode::FORBIDDEN, body).into_response()
+}
+
+/// CSRF middleware
+///
+/// Layered over the whole API router in `routes::create_router`. Requests
+/// outside the configured scope pass straight through.
+pub async fn csrf_middleware(
+    State(protection): State<CsrfProtection>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !protection.requires_token(request.method(), request.uri().path(), request.headers()) {
+        return next.run(request).await;
+    }
+
+    match check_token(request.headers()) {
+        Ok(()) => next.run(request).await,
+        Err(reason) => {
+            tracing::debug!(
+                method = %request.method(),
+                path = %request.uri().path(),
+                reason,
+                "Request rejected: CSRF check failed"
+            );
+            csrf_failed_response(reason)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_scope_matches_on_segment_boundaries() {
+        let protection = CsrfProtection::new(" /api/auth/ , /api/users");
+        assert!(protection.is_enabled());
+        assert!(protection.covers("/api/auth"));
+        assert!(protection.covers("/api/auth/refresh"));
+        assert!(protection.covers("/api/users/profile"));
+        assert!(!protection.covers("/api/authz"));
+        assert!(!protection.covers("/api/bookings"));
+        assert!(!CsrfProtection::new("").is_enabled());
+    }
+
+    #[test]
+    fn test_only_cookie_authenticated_writes_need_a_token() {
+        let protection = CsrfProtection::new("/api");
+        let cookie = headers(&[("cookie", "refresh_token=abc")]);
+
+        assert!(protection.requires_token(&Method::POST, "/api/auth/refresh", &cookie));
+        assert!(protection.requires_token(&Method::DELETE, "/api/users/1", &cookie));
+        assert!(!protection.requires_token(&Method::GET, "/api/auth/me", &cookie));
+        assert!(!protection.requires_token(&Method::POST, "/api/auth/login", &HeaderMap::new()));
+
+        let bearer = headers(&[
+            ("cookie", "refresh_token=abc"),
+            ("authorization", "Bearer token"),
+        ]);
+        assert!(!protection.requires_token(&Method::POST, "/api/users/profile", &bearer));
+    }
+
+    #[test]
+    fn test_oauth_callbacks_and_webhooks_are_exempt() {
+        let protection = CsrfProtection::new("/api");
+        let cookie = headers(&[("cookie", "refresh_token=abc")]);
+
+        assert!(!protection.requires_token(&Method::POST, "/api/oauth/line/callback", &cookie));
+        assert!(!protection.requires_token(&Method::POST, "/api/webhooks/pms", &cookie));
+    }
+
+    #[test]
+    fn test_check_token() {
+        let token = generate_csrf_token();
+        assert!(is_well_formed_csrf_token(&token));
+
+        let cookie = format!("refresh_token=abc; {}={}", CSRF_COOKIE_NAME, token);
+        assert!(check_token(&headers(&[
+            ("cookie", cookie.as_str()),
+            (CSRF_HEADER_NAME, token.as_str())
+        ]))
+        .is_ok());
+        assert!(check_token(&headers(&[("cookie", cookie.as_str())])).is_err());
+        assert!(check_token(&headers(&[
+            ("cookie", cookie.as_str()),
+            (CSRF_HEADER_NAME, "other")
+        ]))
+        .is_err());
+        assert!(check_token(&headers(&[(CSRF_HEADER_NAME, token.as_str())])).is_err());
+    }
+
+    #[test]
+    fn test_csrf_cookie_is_readable_by_scripts() {
+        let header = build_csrf_cookie_header("tok");
+        assert!(header.starts_with("csrf_token=tok;"));
+        assert!(header.contains("SameSite=Strict"));
+        assert!(!header.contains("HttpOnly"));
+    }
+}
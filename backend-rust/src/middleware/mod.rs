@@ -1,25 +1,39 @@
 //! Custom middleware module
 //!
 //! Contains middleware for authentication, CORS, rate limiting, admin authorization,
-//! and request processing.
+//! read-only mode, CSRF checks, response caching headers, the shared response cache,
+//! request-scoped transactions and request processing.
 
 pub mod admin;
 pub mod auth;
+pub mod cache_control;
 pub mod cors;
+pub mod csrf;
 pub mod rate_limit;
+pub mod read_only;
+pub mod response_cache;
+pub mod transaction;
 
 // Re-export commonly used items for convenience
 pub use admin::{
-    admin_middleware, get_admin_config, is_admin, is_super_admin, reload_admin_config,
-    super_admin_middleware, AdminAuthError, AdminConfig,
+    admin_ip_allowlist_middleware, admin_middleware, get_admin_config, is_admin, is_super_admin,
+    reload_admin_config, super_admin_middleware, AdminAuthError, AdminConfig, AdminIpAllowlist,
 };
 pub use auth::{
     auth_middleware, build_clear_refresh_cookie, build_clear_refresh_cookie_header,
     build_refresh_cookie, build_refresh_cookie_header, optional_auth_middleware, AuthUser, Claims,
     REFRESH_COOKIE_NAME, REFRESH_COOKIE_PATH,
 };
+pub use cache_control::{cache_control_middleware, CachePolicy};
 pub use cors::{cors_layer, cors_layer_permissive};
+pub use csrf::{csrf_middleware, CsrfProtection, CSRF_COOKIE_NAME, CSRF_HEADER_NAME};
 pub use rate_limit::{
     default_rate_limit_layer, rate_limit_middleware, strict_rate_limit_layer, RateLimitConfig,
     RateLimiter,
 };
+pub use read_only::{read_only_middleware, ReadOnlyMode, ReadOnlyState};
+pub use response_cache::{
+    invalidate_response_cache, response_cache_middleware, CacheScope, ResponseCacheStore,
+    SharedResponse,
+};
+pub use transaction::{transaction_layer, Tx};
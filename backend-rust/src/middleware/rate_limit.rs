@@ -1,12 +1,14 @@
 //! Rate Limiting Middleware
 //!
 //! Provides rate limiting functionality to protect the API from abuse.
-//! This is a placeholder implementation that can be extended with Redis-backed
-//! distributed rate limiting.
+//! [`RedisRateLimiter`] keeps fixed-window counters in Redis so limits are
+//! enforced cluster-wide, and falls back to a per-instance [`RateLimiter`]
+//! while Redis is unreachable. Both count requests per client IP, or per
+//! authenticated user with [`RateLimitKeyStrategy::User`].
 
 use axum::{
     extract::{ConnectInfo, Request},
-    http::StatusCode,
+    http::{HeaderMap, HeaderValue, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
@@ -17,7 +19,10 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
+pub use crate::config::RateLimitKeyStrategy;
+use crate::config::SecurityConfig;
 use crate::error::ErrorResponse;
+use crate::middleware::auth::request_user_id;
 
 /// Rate limit configuration
 #[derive(Debug, Clone)]
@@ -26,6 +31,8 @@ pub struct RateLimitConfig {
     pub max_requests: u32,
     /// Time window duration
     pub window: Duration,
+    /// What requests are counted against (client IP by default)
+    pub key_strategy: RateLimitKeyStrategy,
 }
 
 impl Default for RateLimitConfig {
@@ -33,6 +40,7 @@ impl Default for RateLimitConfig {
         Self {
             max_requests: 100,
             window: Duration::from_secs(60),
+            key_strategy: RateLimitKeyStrategy::Ip,
         }
     }
 }
@@ -43,27 +51,82 @@ impl RateLimitConfig {
         Self {
             max_requests,
             window: Duration::from_secs(window_secs),
+            ..Self::default()
         }
     }
 
     /// Strict rate limit for sensitive endpoints (e.g., login)
     pub fn strict() -> Self {
-        Self {
-            max_requests: 5,
-            window: Duration::from_secs(60),
-        }
+        Self::new(5, 60)
     }
 
     /// Relaxed rate limit for read-heavy endpoints
     pub fn relaxed() -> Self {
+        Self::new(1000, 60)
+    }
+
+    /// The API-wide limit from `RATE_LIMIT_MAX_REQUESTS` per
+    /// `RATE_LIMIT_WINDOW_MS`, keyed per `RATE_LIMIT_KEY`
+    pub fn from_security(security: &SecurityConfig) -> Self {
         Self {
-            max_requests: 1000,
-            window: Duration::from_secs(60),
+            max_requests: security.rate_limit_max_requests,
+            window: Duration::from_millis(security.rate_limit_window_ms),
+            key_strategy: security.rate_limit_key,
+        }
+    }
+
+    /// Count requests against `key_strategy` instead of the client IP
+    pub fn with_key_strategy(mut self, key_strategy: RateLimitKeyStrategy) -> Self {
+        self.key_strategy = key_strategy;
+        self
+    }
+}
+
+/// `X-RateLimit-Limit`: requests allowed per window
+pub const X_RATELIMIT_LIMIT: &str = "x-ratelimit-limit";
+/// `X-RateLimit-Remaining`: requests left in the current window
+pub const X_RATELIMIT_REMAINING: &str = "x-ratelimit-remaining";
+/// `X-RateLimit-Reset`: seconds until the current window resets
+pub const X_RATELIMIT_RESET: &str = "x-ratelimit-reset";
+
+/// Quota snapshot for one client after a rate-limit check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Requests allowed per window
+    pub limit: u32,
+    /// Requests left in the current window (0 once the limit is hit)
+    pub remaining: u32,
+    /// Seconds until the window resets (delta, like `Retry-After`)
+    pub reset_secs: u64,
+}
+
+impl RateLimitStatus {
+    /// Write the `X-RateLimit-*` headers onto a response.
+    ///
+    /// Routes can sit under more than one limiter (auth routes get the
+    /// strict limiter inside the global one). The outer layer runs last on
+    /// the way out, so rather than overwrite blindly we keep whichever
+    /// snapshot has fewer requests remaining — the client sees the quota
+    /// that will actually bind first.
+    pub fn apply_headers(&self, headers: &mut HeaderMap) {
+        let existing_remaining = headers
+            .get(X_RATELIMIT_REMAINING)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u32>().ok());
+        if matches!(existing_remaining, Some(existing) if existing <= self.remaining) {
+            return;
         }
+
+        headers.insert(X_RATELIMIT_LIMIT, HeaderValue::from(self.limit));
+        headers.insert(X_RATELIMIT_REMAINING, HeaderValue::from(self.remaining));
+        headers.insert(X_RATELIMIT_RESET, HeaderValue::from(self.reset_secs));
     }
 }
 
-/// Track request counts per IP
+/// Clients tracked by a [`RateLimiter`] before expired entries are pruned
+const TRACKED_CLIENTS_CLEANUP_THRESHOLD: usize = 10_000;
+
+/// Track request counts per client key
 #[derive(Debug)]
 struct RequestTracker {
     count: u32,
@@ -77,7 +140,7 @@ struct RequestTracker {
 #[derive(Debug, Clone)]
 pub struct RateLimiter {
     config: RateLimitConfig,
-    requests: Arc<RwLock<HashMap<IpAddr, RequestTracker>>>,
+    requests: Arc<RwLock<HashMap<String, RequestTracker>>>,
 }
 
 impl RateLimiter {
@@ -91,10 +154,31 @@ impl RateLimiter {
 
     /// Check if a request from the given IP should be allowed
     pub async fn check(&self, ip: IpAddr) -> Result<(), RateLimitError> {
+        self.check_key(&ip.to_string()).await
+    }
+
+    /// Check if a request counted against `key` (see [`rate_limit_key`])
+    /// should be allowed
+    pub async fn check_key(&self, key: &str) -> Result<(), RateLimitError> {
+        self.check_key_with_status(key).await.map(|_| ())
+    }
+
+    /// Like [`check_key`](Self::check_key), but also report the client's
+    /// quota
+    pub async fn check_key_with_status(
+        &self,
+        key: &str,
+    ) -> Result<RateLimitStatus, RateLimitError> {
         let mut requests = self.requests.write().await;
         let now = Instant::now();
 
-        let tracker = requests.entry(ip).or_insert(RequestTracker {
+        // Nothing else prunes the map, so keep it bounded here
+        if requests.len() >= TRACKED_CLIENTS_CLEANUP_THRESHOLD {
+            let window = self.config.window;
+            requests.retain(|_, tracker| now.duration_since(tracker.window_start) < window);
+        }
+
+        let tracker = requests.entry(key.to_string()).or_insert(RequestTracker {
             count: 0,
             window_start: now,
         });
@@ -105,18 +189,29 @@ impl RateLimiter {
             tracker.window_start = now;
         }
 
+        // Round up, like the Redis limiter, so waiting `reset_secs` always
+        // lands in a new window
+        let reset_ms = self
+            .config
+            .window
+            .saturating_sub(now.duration_since(tracker.window_start))
+            .as_millis();
+        let reset_secs = u64::try_from(reset_ms.div_ceil(1000)).unwrap_or(u64::MAX);
+
         // Check if limit exceeded
         if tracker.count >= self.config.max_requests {
-            let retry_after =
-                self.config.window.as_secs() - now.duration_since(tracker.window_start).as_secs();
             return Err(RateLimitError::TooManyRequests {
-                retry_after: retry_after as u32,
+                retry_after: u32::try_from(reset_secs).unwrap_or(u32::MAX),
             });
         }
 
         // Increment counter
         tracker.count += 1;
-        Ok(())
+        Ok(RateLimitStatus {
+            limit: self.config.max_requests,
+            remaining: self.config.max_requests - tracker.count,
+            reset_secs,
+        })
     }
 
     /// Clean up expired entries to prevent memory growth
@@ -129,9 +224,14 @@ impl RateLimiter {
     }
 }
 
+/// Machine-readable `error` code of a limiter's 429 body
+pub const RATE_LIMITED_ERROR: &str = "rate_limited";
+
 /// Rate limit error
 #[derive(Debug)]
 pub enum RateLimitError {
+    /// `retry_after` is the number of seconds until the client's window
+    /// resets
     TooManyRequests { retry_after: u32 },
 }
 
@@ -139,14 +239,16 @@ impl IntoResponse for RateLimitError {
     fn into_response(self) -> Response {
         match self {
             RateLimitError::TooManyRequests { retry_after } => {
-                let body = Json(ErrorResponse {
-                    error: "rate_limit_exceeded".to_string(),
-                    message: format!(
+                // `Retry-After: 0` would invite an immediate retry into the
+                // same window
+                let retry_after = retry_after.max(1);
+                let body = Json(ErrorResponse::new(
+                    RATE_LIMITED_ERROR,
+                    format!(
                         "Too many requests. Please try again in {} seconds.",
                         retry_after
                     ),
-                    details: None,
-                });
+                ));
 
                 (
                     StatusCode::TOO_MANY_REQUESTS,
@@ -176,7 +278,7 @@ impl IntoResponse for RateLimitError {
 /// reason the extension is missing (test harnesses, misconfiguration),
 /// we fall back to `127.0.0.1` — that places every such request in a
 /// single shared bucket, which is the safe-by-default behaviour.
-fn get_client_ip(request: &Request) -> IpAddr {
+pub(crate) fn get_client_ip(request: &Request) -> IpAddr {
     request
         .extensions()
         .get::<ConnectInfo<SocketAddr>>()
@@ -188,8 +290,27 @@ fn get_client_ip(request: &Request) -> IpAddr {
         })
 }
 
+/// The bucket a request is counted in under `strategy`.
+///
+/// With [`RateLimitKeyStrategy::User`], a request carrying a valid access
+/// token is keyed `user:{id}` — only a signed token counts, so a client
+/// can't mint fresh buckets by inventing user ids. Everything else is
+/// keyed by [`get_client_ip`], the same bucket the IP strategy uses.
+pub(crate) fn rate_limit_key(strategy: RateLimitKeyStrategy, request: &Request) -> String {
+    if strategy == RateLimitKeyStrategy::User {
+        if let Some(user_id) = request_user_id(request) {
+            return format!("user:{}", user_id);
+        }
+    }
+    get_client_ip(request).to_string()
+}
+
 /// Rate limiting middleware
 ///
+/// Rejected requests get a 429 with `Retry-After` set to the seconds left
+/// in the client's window and an [`ErrorResponse`] body whose `error` is
+/// [`RATE_LIMITED_ERROR`].
+///
 /// # Usage
 ///
 /// ```rust,ignore
@@ -210,8 +331,8 @@ pub async fn rate_limit_middleware(
     request: Request,
     next: Next,
 ) -> Result<Response, RateLimitError> {
-    let ip = get_client_ip(&request);
-    limiter.check(ip).await?;
+    let key = rate_limit_key(limiter.config.key_strategy, &request);
+    limiter.check_key(&key).await?;
     Ok(next.run(request).await)
 }
 
@@ -229,12 +350,20 @@ pub fn strict_rate_limit_layer() -> RateLimiter {
 
 /// Redis-backed rate limiter for distributed deployments
 ///
-/// Uses atomic Redis operations (INCR with EXPIRE) to track request counts
-/// across multiple server instances. This is the recommended approach for
+/// Uses atomic Redis operations (INCR with PEXPIRE) to track request
+/// counts across multiple server instances: a fixed window per client
+/// starts with its first request, so every replica behind the load
+/// balancer adds to the same counter. This is the recommended approach for
 /// production deployments with load balancing.
 ///
+/// While Redis is unreachable each instance enforces the same limit on its
+/// own with an in-memory [`RateLimiter`], so an outage loosens the limit
+/// (by up to the number of replicas) instead of dropping it.
+///
 /// # Key Format
-/// Keys are stored as: `rate_limit:{prefix}:{ip}`
+/// Keys are stored as `rate_limit:{prefix}:{ip}`, or
+/// `rate_limit:{prefix}:user:{user_id}` for authenticated requests under
+/// [`RateLimitKeyStrategy::User`].
 ///
 /// # Example
 /// ```rust,ignore
@@ -255,6 +384,10 @@ pub struct RedisRateLimiter {
     config: RateLimitConfig,
     /// Key prefix for namespacing rate limit keys
     key_prefix: String,
+    /// Whether the middleware adds `X-RateLimit-*` headers to responses
+    emit_headers: bool,
+    /// Per-instance limiter used while Redis is unavailable
+    fallback: RateLimiter,
 }
 
 impl RedisRateLimiter {
@@ -271,11 +404,19 @@ impl RedisRateLimiter {
     ) -> Self {
         Self {
             redis,
+            fallback: RateLimiter::new(config.clone()),
             config,
             key_prefix: key_prefix.into(),
+            emit_headers: true,
         }
     }
 
+    /// Enable or disable the `X-RateLimit-*` response headers (on by default)
+    pub fn with_headers(mut self, enabled: bool) -> Self {
+        self.emit_headers = enabled;
+        self
+    }
+
     /// Create a rate limiter with default configuration
     pub fn with_defaults(
         redis: redis::aio::ConnectionManager,
@@ -289,60 +430,111 @@ impl RedisRateLimiter {
         Self::new(redis, RateLimitConfig::strict(), key_prefix)
     }
 
+    /// Count requests against `key_strategy` instead of the client IP
+    pub fn with_key_strategy(mut self, key_strategy: RateLimitKeyStrategy) -> Self {
+        self.config.key_strategy = key_strategy;
+        self
+    }
+
     /// Check if a request from the given IP should be allowed
     ///
-    /// Uses Redis INCR with EXPIRE for atomic rate limiting.
-    /// The expiration is only set on the first request in a window (NX flag).
+    /// Uses Redis INCR with PEXPIRE for atomic rate limiting.
+    /// The expiration is only set on the first request in a window.
     ///
     /// # Returns
     /// - `Ok(())` if the request is allowed
     /// - `Err(RateLimitError::TooManyRequests)` if the limit is exceeded
-    /// - `Err(RateLimitError::RedisError)` if Redis communication fails
+    ///   (on Redis errors, the in-memory fallback decides)
     pub async fn check(&self, ip: IpAddr) -> Result<(), RateLimitError> {
-        let key = format!("rate_limit:{}:{}", self.key_prefix, ip);
-        let window_secs = self.config.window.as_secs() as i64;
+        self.check_with_status(ip).await.map(|_| ())
+    }
+
+    /// Like [`check`](Self::check), but also report the client's quota.
+    ///
+    /// The snapshot is computed from the count and TTL the Lua script
+    /// returns for *this* request's `INCR`, so it is exact across
+    /// instances sharing the Redis counter — no separate read that another
+    /// instance could race. While Redis is unavailable it comes from the
+    /// in-memory fallback and covers this instance only.
+    pub async fn check_with_status(&self, ip: IpAddr) -> Result<RateLimitStatus, RateLimitError> {
+        self.check_key_with_status(&ip.to_string()).await
+    }
+
+    /// [`check_with_status`](Self::check_with_status) for a request counted
+    /// against `client` (see [`rate_limit_key`]) rather than a bare IP
+    pub async fn check_key_with_status(
+        &self,
+        client: &str,
+    ) -> Result<RateLimitStatus, RateLimitError> {
+        let key = format!("rate_limit:{}:{}", self.key_prefix, client);
+        let window_ms = self.window_ms();
         let mut conn = self.redis.clone();
 
         // Atomic increment and get current count
-        // Uses a Lua script to ensure atomicity of INCR + EXPIRE NX
+        // Uses a Lua script to ensure atomicity of INCR + PEXPIRE
         let script = redis::Script::new(
             r#"
             local current = redis.call('INCR', KEYS[1])
             if current == 1 then
-                redis.call('EXPIRE', KEYS[1], ARGV[1])
+                redis.call('PEXPIRE', KEYS[1], ARGV[1])
             end
-            local ttl = redis.call('TTL', KEYS[1])
+            local ttl = redis.call('PTTL', KEYS[1])
             return {current, ttl}
             "#,
         );
 
         let result: Result<(i64, i64), redis::RedisError> = script
             .key(&key)
-            .arg(window_secs)
+            .arg(window_ms)
             .invoke_async(&mut conn)
             .await;
 
         match result {
-            Ok((count, ttl)) => {
-                if count > self.config.max_requests as i64 {
-                    let retry_after = if ttl > 0 {
-                        ttl as u32
-                    } else {
-                        window_secs as u32
-                    };
-                    return Err(RateLimitError::TooManyRequests { retry_after });
+            Ok((count, ttl_ms)) => {
+                let status = self.status_from_counter(count, ttl_ms);
+                if count > i64::from(self.config.max_requests) {
+                    return Err(RateLimitError::TooManyRequests {
+                        retry_after: u32::try_from(status.reset_secs).unwrap_or(u32::MAX),
+                    });
                 }
-                Ok(())
+                Ok(status)
             },
             Err(e) => {
-                // Log the error but fail open to prevent blocking legitimate requests
-                // when Redis is temporarily unavailable
-                tracing::warn!("Redis rate limit check failed: {}. Allowing request.", e);
-                Ok(())
+                // Keep limiting, per instance, rather than letting every
+                // request through while Redis is down
+                tracing::warn!(
+                    "Redis rate limit check failed: {}. Using in-memory limiter.",
+                    e
+                );
+                self.fallback.check_key_with_status(client).await
             },
         }
     }
 
+    /// The window in whole milliseconds (at least 1, as `PEXPIRE` needs)
+    fn window_ms(&self) -> i64 {
+        i64::try_from(self.config.window.as_millis())
+            .unwrap_or(i64::MAX)
+            .max(1)
+    }
+
+    /// Build a quota snapshot from the script's `(count, ttl_ms)` reply.
+    ///
+    /// The reset is rounded up to whole seconds, so a client that waits
+    /// that long always lands in a new window. A TTL of -1/-2 (no expiry /
+    /// key gone) only happens in the instant around window rollover; report
+    /// a full window in that case.
+    fn status_from_counter(&self, count: i64, ttl_ms: i64) -> RateLimitStatus {
+        let limit = self.config.max_requests;
+        let used = u32::try_from(count.max(0)).unwrap_or(u32::MAX);
+        let ttl_ms = if ttl_ms > 0 { ttl_ms } else { self.window_ms() };
+        RateLimitStatus {
+            limit,
+            remaining: limit.saturating_sub(used),
+            reset_secs: (ttl_ms as u64).div_ceil(1000),
+        }
+    }
+
     /// Get the current request count for an IP without incrementing
     ///
     /// Useful for debugging and monitoring.
@@ -402,14 +594,36 @@ impl RedisRateLimiter {
 ///         redis_rate_limit_middleware,
 ///     ));
 /// ```
+///
+/// Unless disabled with [`RedisRateLimiter::with_headers`], responses carry
+/// `X-RateLimit-Limit`, `X-RateLimit-Remaining` and `X-RateLimit-Reset`
+/// (seconds) — on successful responses as well as on 429s.
 pub async fn redis_rate_limit_middleware(
     axum::extract::State(limiter): axum::extract::State<RedisRateLimiter>,
     request: Request,
     next: Next,
 ) -> Result<Response, RateLimitError> {
-    let ip = get_client_ip(&request);
-    limiter.check(ip).await?;
-    Ok(next.run(request).await)
+    let key = rate_limit_key(limiter.config.key_strategy, &request);
+    let status = match limiter.check_key_with_status(&key).await {
+        Ok(status) => status,
+        Err(RateLimitError::TooManyRequests { retry_after }) if limiter.emit_headers => {
+            let mut response = RateLimitError::TooManyRequests { retry_after }.into_response();
+            RateLimitStatus {
+                limit: limiter.config.max_requests,
+                remaining: 0,
+                reset_secs: u64::from(retry_after),
+            }
+            .apply_headers(response.headers_mut());
+            return Ok(response);
+        },
+        Err(e) => return Err(e),
+    };
+
+    let mut response = next.run(request).await;
+    if limiter.emit_headers {
+        status.apply_headers(response.headers_mut());
+    }
+    Ok(response)
 }
 
 #[cfg(test)]
@@ -492,6 +706,43 @@ mod tests {
 
         let relaxed = RateLimitConfig::relaxed();
         assert_eq!(relaxed.max_requests, 1000);
+
+        assert_eq!(strict.key_strategy, RateLimitKeyStrategy::Ip);
+        let per_user = RateLimitConfig::strict().with_key_strategy(RateLimitKeyStrategy::User);
+        assert_eq!(per_user.key_strategy, RateLimitKeyStrategy::User);
+        assert_eq!(per_user.max_requests, 5);
+    }
+
+    #[test]
+    fn test_config_from_security() {
+        let security = SecurityConfig {
+            rate_limit_window_ms: 1_500,
+            rate_limit_max_requests: 42,
+            rate_limit_key: RateLimitKeyStrategy::User,
+            ..SecurityConfig::default()
+        };
+        let config = RateLimitConfig::from_security(&security);
+        assert_eq!(config.max_requests, 42);
+        assert_eq!(config.window, Duration::from_millis(1_500));
+        assert_eq!(config.key_strategy, RateLimitKeyStrategy::User);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_reports_status() {
+        let limiter = RateLimiter::new(RateLimitConfig::new(2, 60));
+
+        let status = limiter.check_key_with_status("client").await.unwrap();
+        assert_eq!(status.limit, 2);
+        assert_eq!(status.remaining, 1);
+        assert!(status.reset_secs <= 60);
+
+        let status = limiter.check_key_with_status("client").await.unwrap();
+        assert_eq!(status.remaining, 0);
+
+        match limiter.check_key_with_status("client").await {
+            Err(RateLimitError::TooManyRequests { retry_after }) => assert!(retry_after <= 60),
+            other => panic!("expected TooManyRequests, got {:?}", other),
+        }
     }
 
     // ----------------------------------------------------------------
@@ -555,6 +806,140 @@ mod tests {
         assert_eq!(ip, "127.0.0.1".parse::<IpAddr>().unwrap());
     }
 
+    // ----------------------------------------------------------------
+    // rate_limit_key — per-user vs per-IP buckets
+    // ----------------------------------------------------------------
+
+    const TEST_SECRET: &str = "rate-limit-test-secret";
+
+    fn token_for(user_id: &str, secret: &str) -> String {
+        use crate::middleware::auth::Claims;
+        use jsonwebtoken::{encode, EncodingKey, Header};
+
+        let now = chrono::Utc::now().timestamp();
+        let claims = Claims {
+            id: user_id.to_string(),
+            email: None,
+            role: "customer".to_string(),
+            iat: Some(now),
+            exp: now + 3600,
+            last_auth_at: Some(now),
+            sid: None,
+        };
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    /// A request from `peer`, with a bearer token when given
+    fn request_from(peer: &str, token: Option<&str>) -> Request {
+        let mut builder = axum::http::Request::builder().uri("/api/loyalty/status");
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        let mut req = builder.body(Body::empty()).unwrap();
+        req.extensions_mut()
+            .insert(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+        req.extensions_mut()
+            .insert(crate::middleware::auth::JwtSecret(TEST_SECRET.to_string()));
+        req
+    }
+
+    #[test]
+    fn rate_limit_key_per_user_falls_back_to_ip() {
+        let alice = token_for("alice", TEST_SECRET);
+        let forged = token_for("mallory", "some-other-secret");
+
+        let user = RateLimitKeyStrategy::User;
+        assert_eq!(
+            rate_limit_key(user, &request_from("203.0.113.9:1000", Some(&alice))),
+            "user:alice"
+        );
+        // Anonymous and badly signed requests are counted by IP
+        assert_eq!(
+            rate_limit_key(user, &request_from("203.0.113.9:1000", None)),
+            "203.0.113.9"
+        );
+        assert_eq!(
+            rate_limit_key(user, &request_from("203.0.113.9:1000", Some(&forged))),
+            "203.0.113.9"
+        );
+        // The IP strategy ignores the token entirely
+        assert_eq!(
+            rate_limit_key(
+                RateLimitKeyStrategy::Ip,
+                &request_from("203.0.113.9:1000", Some(&alice))
+            ),
+            "203.0.113.9"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_per_user_buckets_behind_shared_ip() {
+        let config = RateLimitConfig::new(2, 60).with_key_strategy(RateLimitKeyStrategy::User);
+        let limiter = RateLimiter::new(config.clone());
+        let alice = token_for("alice", TEST_SECRET);
+        let bob = token_for("bob", TEST_SECRET);
+        let nat = "198.51.100.7:4000";
+
+        for token in [&alice, &alice, &bob, &bob] {
+            let key = rate_limit_key(config.key_strategy, &request_from(nat, Some(token)));
+            assert!(limiter.check_key(&key).await.is_ok());
+        }
+        let key = rate_limit_key(config.key_strategy, &request_from(nat, Some(&alice)));
+        assert!(limiter.check_key(&key).await.is_err());
+
+        // Anonymous traffic from the same address still has its own quota
+        let key = rate_limit_key(config.key_strategy, &request_from(nat, None));
+        assert!(limiter.check_key(&key).await.is_ok());
+    }
+
+    #[test]
+    fn rate_limit_status_sets_headers() {
+        let mut headers = HeaderMap::new();
+        RateLimitStatus {
+            limit: 100,
+            remaining: 42,
+            reset_secs: 17,
+        }
+        .apply_headers(&mut headers);
+
+        assert_eq!(headers[X_RATELIMIT_LIMIT], "100");
+        assert_eq!(headers[X_RATELIMIT_REMAINING], "42");
+        assert_eq!(headers[X_RATELIMIT_RESET], "17");
+    }
+
+    #[test]
+    fn rate_limit_status_keeps_most_restrictive_snapshot() {
+        let strict = RateLimitStatus {
+            limit: 5,
+            remaining: 3,
+            reset_secs: 40,
+        };
+        let global = RateLimitStatus {
+            limit: 100,
+            remaining: 90,
+            reset_secs: 55,
+        };
+
+        // Inner (strict) layer writes first, outer (global) must not clobber it
+        let mut headers = HeaderMap::new();
+        strict.apply_headers(&mut headers);
+        global.apply_headers(&mut headers);
+        assert_eq!(headers[X_RATELIMIT_LIMIT], "5");
+        assert_eq!(headers[X_RATELIMIT_REMAINING], "3");
+
+        // ...and a tighter outer snapshot does replace a looser inner one
+        let mut headers = HeaderMap::new();
+        global.apply_headers(&mut headers);
+        strict.apply_headers(&mut headers);
+        assert_eq!(headers[X_RATELIMIT_REMAINING], "3");
+        assert_eq!(headers[X_RATELIMIT_RESET], "40");
+    }
+
     // Redis rate limiter tests require a running Redis instance
     // These are integration tests that should be run with:
     // cargo test -- --ignored
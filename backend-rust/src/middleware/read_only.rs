@@ -0,0 +1,202 @@
+//! Global read-only mode
+//!
+//! During data migrations the API can be switched into read-only mode:
+//! reads keep working, state-changing requests get a 503 explaining why.
+//! The flag lives in Redis (`system:read_only`) so every instance sees the
+//! same state, and a super admin flips it at runtime through
+//! `PUT /api/admin/read-only` (see `routes::admin_maintenance`).
+//!
+//! Some writes must keep working while the flag is on, or nobody could
+//! turn it off again:
+//! - the read-only toggle itself
+//! - signing in, refreshing and signing out (an admin whose access token
+//!   expired mid-migration still needs a session)
+//! - health checks, which load balancers probe regardless of method
+//!
+//! Like the rate limiter, the check fails open: if Redis can't be read,
+//! requests proceed rather than taking the whole API down.
+
+use axum::{
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ErrorResponse;
+
+/// Redis key holding the serialized [`ReadOnlyState`]; absent means off
+pub const READ_ONLY_KEY: &str = "system:read_only";
+
+/// Path of the admin toggle, exempt so read-only mode can be turned off
+pub const READ_ONLY_TOGGLE_PATH: &str = "/api/admin/read-only";
+
+/// Write endpoints that stay available in read-only mode
+const EXEMPT_PATHS: &[&str] = &[
+    READ_ONLY_TOGGLE_PATH,
+    "/api/auth/login",
+    "/api/auth/refresh",
+    "/api/auth/logout",
+];
+
+/// Current read-only mode, as stored in Redis
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadOnlyState {
+    pub enabled: bool,
+    /// Shown to clients in the 503 message (e.g. "Migrating bookings")
+    pub reason: Option<String>,
+    /// Email of the super admin who enabled it
+    pub enabled_by: Option<String>,
+    pub enabled_at: Option<DateTime<Utc>>,
+}
+
+/// Read the current read-only state. A missing key means disabled.
+pub async fn get_read_only_state(
+    redis: &mut ConnectionManager,
+) -> Result<ReadOnlyState, redis::RedisError> {
+    let raw: Option<String> = redis.get(READ_ONLY_KEY).await?;
+    Ok(raw
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default())
+}
+
+/// Store `state`; disabling deletes the key rather than storing `false`.
+pub async fn set_read_only_state(
+    redis: &mut ConnectionManager,
+    state: &ReadOnlyState,
+) -> Result<(), redis::RedisError> {
+    if state.enabled {
+        let json = serde_json::to_string(state).unwrap_or_default();
+        redis.set::<_, _, ()>(READ_ONLY_KEY, json).await
+    } else {
+        redis.del::<_, ()>(READ_ONLY_KEY).await
+    }
+}
+
+/// Middleware state: the Redis connection the flag is read from
+#[derive(Clone)]
+pub struct ReadOnlyMode {
+    redis: ConnectionManager,
+}
+
+impl ReadOnlyMode {
+    pub fn new(redis: ConnectionManager) -> Self {
+        Self { redis }
+    }
+}
+
+/// Whether a request is allowed through regardless of read-only mode
+fn is_exempt(method: &Method, path: &str) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+        || path == "/api/health"
+        || path.starts_with("/api/health/")
+        || EXEMPT_PATHS.contains(&path)
+}
+
+/// The 503 body returned for a rejected write
+fn read_only_response(state: &ReadOnlyState) -> Response {
+    let message = match state.reason.as_deref().map(str::trim) {
+        Some(reason) if !reason.is_empty() => format!(
+            "The service is temporarily read-only ({}). Please try again later.",
+            reason
+        ),
+        _ => "The service is temporarily read-only for maintenance. Please try again later."
+            .to_string(),
+    };
+
+    let body = Json(ErrorResponse {
+        error: "read_only_mode".to_string(),
+        message,
+        details: None,
+    });
+
+    (StatusCode::SERVICE_UNAVAILABLE, body).into_response()
+}
+
+/// Read-only mode middleware
+///
+/// Layered over the whole API router in `routes::create_router`. Safe
+/// methods and the exempt paths above skip the Redis lookup entirely, so
+/// reads cost nothing extra.
+pub async fn read_only_middleware(
+    State(mode): State<ReadOnlyMode>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if is_exempt(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let mut redis = mode.redis.clone();
+    match get_read_only_state(&mut redis).await {
+        Ok(state) if state.enabled => {
+            tracing::debug!(
+                method = %request.method(),
+                path = %request.uri().path(),
+                "Write rejected: read-only mode"
+            );
+            read_only_response(&state)
+        },
+        Ok(_) => next.run(request).await,
+        Err(e) => {
+            tracing::warn!(error = %e, "Read-only flag unavailable; allowing request");
+            next.run(request).await
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_are_exempt() {
+        assert!(is_exempt(&Method::GET, "/api/bookings"));
+        assert!(is_exempt(&Method::HEAD, "/api/bookings"));
+        assert!(is_exempt(&Method::OPTIONS, "/api/bookings"));
+        assert!(!is_exempt(&Method::POST, "/api/bookings"));
+        assert!(!is_exempt(&Method::DELETE, "/api/admin/users/1"));
+    }
+
+    #[test]
+    fn test_override_auth_and_health_are_exempt() {
+        assert!(is_exempt(&Method::PUT, READ_ONLY_TOGGLE_PATH));
+        assert!(is_exempt(&Method::POST, "/api/auth/login"));
+        assert!(is_exempt(&Method::POST, "/api/auth/refresh"));
+        assert!(is_exempt(&Method::POST, "/api/health"));
+        assert!(is_exempt(&Method::POST, "/api/health/db"));
+        assert!(!is_exempt(&Method::POST, "/api/auth/register"));
+        assert!(!is_exempt(&Method::POST, "/api/healthcheck"));
+    }
+
+    #[test]
+    fn test_read_only_response_is_503_with_reason() {
+        let state = ReadOnlyState {
+            enabled: true,
+            reason: Some("Migrating bookings".to_string()),
+            ..Default::default()
+        };
+        let response = read_only_response(&state);
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_read_only_state_round_trip() {
+        let state = ReadOnlyState {
+            enabled: true,
+            reason: Some("Migration".to_string()),
+            enabled_by: Some("root@example.com".to_string()),
+            enabled_at: Some(Utc::now()),
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(json.contains("\"enabledBy\""));
+        let parsed: ReadOnlyState = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, state);
+    }
+}
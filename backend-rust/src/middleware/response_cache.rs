@@ -0,0 +1,473 @@
+//! Shared Redis cache for GET responses
+//!
+//! A route opts in by layering [`response_cache_middleware`] with the
+//! [`CacheScope`] its data belongs to:
+//!
+//! ```ignore
+//! Router::new()
+//!     .route("/tiers", get(get_tiers))
+//!     .route_layer(middleware::from_fn_with_state(
+//!         CacheScope("tiers"),
+//!         response_cache_middleware,
+//!     ))
+//! ```
+//!
+//! The scope's TTL comes from `RESPONSE_CACHE_TTLS` (`tiers=60,...`); a
+//! scope without one isn't cached, so nothing changes until an operator
+//! turns it on. On an authenticated route the layer must sit inside
+//! `auth_middleware` so it can see the caller's role.
+//!
+//! Entries are keyed by method, path, query and the caller's role — never
+//! the user. Only responses the handler marked as shareable are stored: a
+//! `Cache-Control: public` response (see [`super::cache_control`]) or one
+//! carrying [`SharedResponse`]. Everything else, including errors and
+//! responses that set cookies, passes through untouched, so a
+//! user-specific response can't land in the shared cache even on a cached
+//! route.
+//!
+//! Concurrent misses for the same entry are collapsed (single-flight): one
+//! request runs the handler while the others wait for it and then read
+//! what it stored, so an expired popular entry doesn't send a burst of
+//! identical queries to the database. This is per instance.
+//!
+//! A successful write (any non-GET method) through a route layered with a
+//! scope drops that scope's entries once the handler has returned; writes
+//! elsewhere call [`invalidate_response_cache`]. Like the rate limiter,
+//! the cache fails open: a Redis error means the handler just runs.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, IntoResponseParts, Response, ResponseParts},
+};
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use tokio::sync::watch;
+
+use crate::config::HttpCacheConfig;
+use crate::middleware::auth::AuthUser;
+
+/// Redis key prefix; each scope's entries live in one hash under it
+pub const RESPONSE_CACHE_KEY_PREFIX: &str = "response_cache:";
+
+/// Response header reporting `HIT` or `MISS` for cached routes
+pub const X_CACHE_HEADER: &str = "x-cache";
+
+/// Role used in the key for requests without an authenticated user
+const ANONYMOUS_ROLE: &str = "anonymous";
+
+/// Larger bodies are passed through without being stored
+const MAX_CACHED_BODY_BYTES: u64 = 256 * 1024;
+
+/// How long a request waits for a concurrent identical one before running
+/// the handler itself
+const SINGLE_FLIGHT_WAIT: Duration = Duration::from_secs(5);
+
+/// The cache scope a route belongs to, passed as the middleware state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheScope(pub &'static str);
+
+/// Marks a response as identical for every caller with the same role.
+///
+/// Returned alongside the body by handlers whose response isn't public
+/// (so it can't carry a [`super::cache_control::CachePolicy`]) but may
+/// still be shared server-side, e.g. a list every admin sees the same way.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SharedResponse;
+
+impl IntoResponseParts for SharedResponse {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        res.extensions_mut().insert(SharedResponse);
+        Ok(res)
+    }
+}
+
+/// A stored response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedResponse {
+    status: u16,
+    content_type: Option<String>,
+    cache_control: Option<String>,
+    body: String,
+    /// Unix timestamp the entry is stale after. Entries share their
+    /// scope's hash, so each carries its own expiry.
+    expires_at: i64,
+}
+
+impl CachedResponse {
+    fn into_response(self) -> Response {
+        let mut response = Response::new(Body::from(self.body));
+        *response.status_mut() = StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK);
+
+        let headers = response.headers_mut();
+        for (name, value) in [
+            (header::CONTENT_TYPE, self.content_type),
+            (header::CACHE_CONTROL, self.cache_control),
+        ] {
+            if let Some(value) = value.and_then(|v| HeaderValue::from_str(&v).ok()) {
+                headers.insert(name, value);
+            }
+        }
+        headers.insert(X_CACHE_HEADER, HeaderValue::from_static("HIT"));
+        response
+    }
+}
+
+/// Requests currently running the handler for an entry, by entry key
+type InFlight = Arc<Mutex<HashMap<String, watch::Receiver<()>>>>;
+
+/// Redis connection, per-scope TTLs and single-flight bookkeeping,
+/// injected as an extension by `create_router`
+#[derive(Clone)]
+pub struct ResponseCacheStore {
+    redis: ConnectionManager,
+    ttls: Arc<HashMap<String, u64>>,
+    in_flight: InFlight,
+}
+
+impl ResponseCacheStore {
+    /// Build the store from `RESPONSE_CACHE_TTLS`, already checked by
+    /// `Settings::validate`
+    pub fn new(redis: ConnectionManager, config: &HttpCacheConfig) -> Self {
+        let ttls = config
+            .parsed_response_cache_ttls()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+
+        Self {
+            redis,
+            ttls: Arc::new(ttls),
+            in_flight: InFlight::default(),
+        }
+    }
+
+    /// The TTL of `scope`, if it is cached at all
+    fn ttl_for(&self, scope: &str) -> Option<u64> {
+        self.ttls.get(scope).copied().filter(|ttl| *ttl > 0)
+    }
+
+    /// The fresh entry for `variant`, if any
+    async fn load(&self, scope: &str, variant: &str) -> Option<CachedResponse> {
+        let mut redis = self.redis.clone();
+        let raw: Option<String> = match redis.hget(scope_key(scope), variant).await {
+            Ok(raw) => raw,
+            Err(e) => {
+                tracing::warn!(error = %e, scope, "Response cache unavailable; running handler");
+                return None;
+            },
+        };
+
+        raw.and_then(|json| serde_json::from_str::<CachedResponse>(&json).ok())
+            .filter(|entry| entry.expires_at > chrono::Utc::now().timestamp())
+    }
+
+    async fn save(&self, scope: &str, variant: &str, entry: &CachedResponse, ttl_secs: u64) {
+        let Ok(json) = serde_json::to_string(entry) else {
+            return;
+        };
+
+        // Stale entries are skipped by `load`; the hash itself goes once
+        // nothing has been stored in it for a TTL
+        let mut redis = self.redis.clone();
+        let result: Result<(), redis::RedisError> = redis::pipe()
+            .hset(scope_key(scope), variant, json)
+            .ignore()
+            .expire(scope_key(scope), ttl_secs as i64)
+            .ignore()
+            .query_async(&mut redis)
+            .await;
+        if let Err(e) = result {
+            tracing::warn!(error = %e, scope, "Failed to store cached response");
+        }
+    }
+}
+
+/// Become the request that runs the handler for `key`, or wait on the one
+/// that already is
+fn join_flight(in_flight: &InFlight, key: String) -> Flight {
+    let mut running = in_flight.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(done) = running.get(&key) {
+        return Flight::Follower(done.clone());
+    }
+
+    let (done_tx, done_rx) = watch::channel(());
+    running.insert(key.clone(), done_rx);
+    Flight::Leader(FlightGuard {
+        key,
+        in_flight: in_flight.clone(),
+        _done: done_tx,
+    })
+}
+
+/// This request's part in a single-flight group
+enum Flight {
+    /// Runs the handler; the others are released when the guard drops
+    Leader(FlightGuard),
+    /// Waits until the leader is done (the sender is dropped)
+    Follower(watch::Receiver<()>),
+}
+
+/// Held by the leader; dropping it (even on cancellation) ends the flight
+struct FlightGuard {
+    key: String,
+    in_flight: InFlight,
+    _done: watch::Sender<()>,
+}
+
+impl Drop for FlightGuard {
+    fn drop(&mut self) {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.key);
+    }
+}
+
+/// Redis hash holding `scope`'s entries
+fn scope_key(scope: &str) -> String {
+    format!("{}{}", RESPONSE_CACHE_KEY_PREFIX, scope)
+}
+
+/// Entry key within a scope: role, method, path and query. The role is
+/// the only thing taken from the caller.
+fn cache_variant(request: &Request) -> String {
+    let role = request
+        .extensions()
+        .get::<AuthUser>()
+        .map(|user| user.role.as_str())
+        .unwrap_or(ANONYMOUS_ROLE);
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or_else(|| request.uri().path());
+
+    format!("{}:{}:{}", role, request.method(), path_and_query)
+}
+
+/// Whether the handler said `response` may be shared with other callers
+fn is_shareable(response: &Response) -> bool {
+    if response.status() != StatusCode::OK || response.headers().contains_key(header::SET_COOKIE) {
+        return false;
+    }
+
+    response.extensions().get::<SharedResponse>().is_some()
+        || response
+            .headers()
+            .get(header::CACHE_CONTROL)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.split(',').any(|d| d.trim() == "public"))
+}
+
+/// Store `response` if it is shareable, returning it either way
+async fn store_response(
+    store: &ResponseCacheStore,
+    scope: &str,
+    variant: &str,
+    ttl_secs: u64,
+    response: Response,
+) -> Response {
+    let fits = response
+        .body()
+        .size_hint()
+        .exact()
+        .is_some_and(|len| len <= MAX_CACHED_BODY_BYTES);
+    if !fits || !is_shareable(&response) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_CACHED_BODY_BYTES as usize).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!(error = %e, scope, "Failed to read response body for caching");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        },
+    };
+
+    if let Ok(text) = std::str::from_utf8(&bytes) {
+        let header_string = |name: header::HeaderName| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from)
+        };
+        let entry = CachedResponse {
+            status: parts.status.as_u16(),
+            content_type: header_string(header::CONTENT_TYPE),
+            cache_control: header_string(header::CACHE_CONTROL),
+            body: text.to_string(),
+            expires_at: chrono::Utc::now().timestamp() + ttl_secs as i64,
+        };
+        store.save(scope, variant, &entry, ttl_secs).await;
+        parts
+            .headers
+            .insert(X_CACHE_HEADER, HeaderValue::from_static("MISS"));
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// Drop every cached response of `scope`, so the next read runs the
+/// handler.
+///
+/// A Redis failure is logged rather than returned: the write has already
+/// committed, and the entries still expire on their own.
+pub async fn invalidate_response_cache(mut redis: ConnectionManager, scope: &str) {
+    if let Err(e) = redis.del::<_, ()>(scope_key(scope)).await {
+        tracing::warn!(error = %e, scope, "Failed to invalidate the response cache");
+    }
+}
+
+/// Response cache middleware (see the module docs)
+pub async fn response_cache_middleware(
+    State(CacheScope(scope)): State<CacheScope>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(store) = request.extensions().get::<ResponseCacheStore>().cloned() else {
+        return next.run(request).await;
+    };
+
+    if request.method() != Method::GET {
+        let is_write = !matches!(*request.method(), Method::HEAD | Method::OPTIONS);
+        let response = next.run(request).await;
+        if is_write && response.status().is_success() {
+            invalidate_response_cache(store.redis.clone(), scope).await;
+        }
+        return response;
+    }
+
+    let Some(ttl_secs) = store.ttl_for(scope) else {
+        return next.run(request).await;
+    };
+
+    let variant = cache_variant(&request);
+    if let Some(entry) = store.load(scope, &variant).await {
+        return entry.into_response();
+    }
+
+    match join_flight(&store.in_flight, format!("{}:{}", scope, variant)) {
+        Flight::Leader(guard) => {
+            let response = next.run(request).await;
+            let response = store_response(&store, scope, &variant, ttl_secs, response).await;
+            // Stored before the followers are released
+            drop(guard);
+            response
+        },
+        Flight::Follower(mut done) => {
+            let _ = tokio::time::timeout(SINGLE_FLIGHT_WAIT, done.changed()).await;
+            if let Some(entry) = store.load(scope, &variant).await {
+                return entry.into_response();
+            }
+            // The leader's response wasn't shareable, or it took too long
+            let response = next.run(request).await;
+            store_response(&store, scope, &variant, ttl_secs, response).await
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::cache_control::CachePolicy;
+    use axum::Json;
+
+    fn request(uri: &str) -> Request {
+        Request::builder().uri(uri).body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_variant_uses_role_not_user() {
+        let mut req = request("/api/loyalty/tiers?lang=th");
+        assert_eq!(
+            cache_variant(&req),
+            "anonymous:GET:/api/loyalty/tiers?lang=th"
+        );
+
+        req.extensions_mut().insert(AuthUser {
+            id: "5d3c1f0e-0000-0000-0000-000000000001".to_string(),
+            email: Some("guest@example.com".to_string()),
+            role: "customer".to_string(),
+            last_auth_at: None,
+            session_id: None,
+        });
+        let variant = cache_variant(&req);
+        assert_eq!(variant, "customer:GET:/api/loyalty/tiers?lang=th");
+        assert!(!variant.contains("guest@example.com"));
+        assert!(!variant.contains("5d3c1f0e"));
+    }
+
+    #[test]
+    fn test_only_marked_responses_are_shareable() {
+        let plain = Json(serde_json::json!({ "points": 10 })).into_response();
+        assert!(!is_shareable(&plain));
+
+        let public = (CachePolicy::public(60), Json(serde_json::json!([]))).into_response();
+        assert!(is_shareable(&public));
+
+        let shared = (SharedResponse, Json(serde_json::json!([]))).into_response();
+        assert!(is_shareable(&shared));
+
+        let error = (StatusCode::NOT_FOUND, SharedResponse, "missing").into_response();
+        assert!(!is_shareable(&error));
+
+        let with_cookie = (
+            SharedResponse,
+            [(header::SET_COOKIE, "a=b")],
+            Json(serde_json::json!([])),
+        )
+            .into_response();
+        assert!(!is_shareable(&with_cookie));
+    }
+
+    #[test]
+    fn test_cached_response_restores_headers() {
+        let response = CachedResponse {
+            status: 200,
+            content_type: Some("application/json".to_string()),
+            cache_control: Some("public, max-age=60".to_string()),
+            body: "[]".to_string(),
+            expires_at: 0,
+        }
+        .into_response();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=60"
+        );
+        assert_eq!(response.headers()[X_CACHE_HEADER], "HIT");
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_releases_followers() {
+        let in_flight = InFlight::default();
+        let join = |key: &str| join_flight(&in_flight, key.to_string());
+
+        let Flight::Leader(guard) = join("tiers:a") else {
+            panic!("the first request leads");
+        };
+        let Flight::Follower(mut done) = join("tiers:a") else {
+            panic!("a concurrent request follows");
+        };
+        assert!(matches!(join("tiers:b"), Flight::Leader(_)));
+
+        drop(guard);
+        assert!(done.changed().await.is_err(), "the follower is released");
+        assert!(matches!(join("tiers:a"), Flight::Leader(_)));
+    }
+}
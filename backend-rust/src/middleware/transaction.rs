@@ -0,0 +1,143 @@
+//! Request-scoped database transactions
+//!
+//! A handler that writes takes a [`Tx`] extractor instead of calling
+//! `begin()`/`commit()` itself. Its routes must be wrapped in
+//! [`transaction_layer`], which commits the transaction when the handler's
+//! response is a success and rolls it back when it is a 4xx/5xx — so every
+//! `AppError` return path, `?` included, undoes the handler's writes.
+//! Handlers that don't take a `Tx` never open a transaction, and routes
+//! without the layer don't pay for it.
+//!
+//! A handler with work that must follow the commit (notifications, cache
+//! invalidation, reads through the pool that need to see its writes) calls
+//! [`Tx::commit`] itself; the layer then has nothing left to do.
+//!
+//! ```rust,ignore
+//! async fn handler(mut tx: Tx, Json(body): Json<Body>) -> AppResult<Json<Out>> {
+//!     sqlx::query("UPDATE ...").execute(&mut *tx).await?;
+//!     validate(&body)?; // rolls back the UPDATE
+//!     Ok(Json(out)) // committed by the layer
+//! }
+//!
+//! Router::new()
+//!     .route("/", post(handler))
+//!     .layer(middleware::from_fn(transaction_layer));
+//! ```
+
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::{
+    extract::{FromRequestParts, Request},
+    http::request::Parts,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx::{PgConnection, Postgres, Transaction};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// The request's transaction, once a [`Tx`] has opened it
+type TxSlot = Arc<Mutex<Option<Transaction<'static, Postgres>>>>;
+
+/// Request extension shared by [`transaction_layer`] and [`Tx`]
+#[derive(Clone, Default)]
+struct RequestTransaction(TxSlot);
+
+/// Commit or roll back the transaction a handler opened through [`Tx`].
+///
+/// 1xx-3xx responses commit; 4xx and 5xx roll back. A failed commit
+/// replaces the handler's response with a 500, so a client is never told
+/// a write succeeded when it didn't.
+pub async fn transaction_layer(mut request: Request, next: Next) -> Response {
+    let slot = TxSlot::default();
+    request
+        .extensions_mut()
+        .insert(RequestTransaction(slot.clone()));
+
+    let response = next.run(request).await;
+
+    // The handler (and with it the `Tx` guard) has finished by now
+    let Some(tx) = slot.lock().await.take() else {
+        return response;
+    };
+
+    let status = response.status();
+    if status.is_client_error() || status.is_server_error() {
+        if let Err(e) = tx.rollback().await {
+            // Dropping the connection's transaction rolls it back anyway
+            tracing::warn!(error = %e, "Failed to roll back request transaction");
+        }
+        return response;
+    }
+
+    match tx.commit().await {
+        Ok(()) => response,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to commit request transaction");
+            AppError::Database(e).into_response()
+        },
+    }
+}
+
+/// A transaction scoped to the current request (see the module docs).
+///
+/// Derefs to the connection, so queries run on it with
+/// `.execute(&mut *tx)` exactly as on a `sqlx::Transaction`.
+pub struct Tx(OwnedMutexGuard<Option<Transaction<'static, Postgres>>>);
+
+impl Tx {
+    /// Commit now instead of when the response is ready
+    pub async fn commit(mut self) -> Result<(), AppError> {
+        match self.0.take() {
+            Some(tx) => Ok(tx.commit().await?),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Deref for Tx {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &PgConnection {
+        // Only `commit(self)` empties the slot, and it consumes the `Tx`
+        self.0.as_deref().expect("request transaction is open")
+    }
+}
+
+impl DerefMut for Tx {
+    fn deref_mut(&mut self) -> &mut PgConnection {
+        self.0.as_deref_mut().expect("request transaction is open")
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for Tx {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        // Without the layer nothing would commit, and every write would be
+        // silently rolled back when the `Tx` is dropped
+        let RequestTransaction(slot) = parts
+            .extensions
+            .get::<RequestTransaction>()
+            .cloned()
+            .ok_or_else(|| {
+                AppError::Internal("Tx used on a route without transaction_layer".to_string())
+            })?;
+
+        let mut guard = slot
+            .try_lock_owned()
+            .map_err(|_| AppError::Internal("Request transaction is already in use".to_string()))?;
+        if guard.is_none() {
+            *guard = Some(state.db().begin().await?);
+        }
+        Ok(Tx(guard))
+    }
+}
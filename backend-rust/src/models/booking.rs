@@ -21,6 +21,192 @@ pub enum BookingStatus {
     NoShow,
 }
 
+/// Payment status of a booking, driven by the slips attached to it
+///
+/// Transitions only move forward (`unpaid` → `pending_verification` →
+/// `paid`); use [`PaymentStatus::advance_to`] rather than assigning a new
+/// value directly so a late or failed verification can never walk a paid
+/// booking back.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentStatus {
+    /// No slip has been attached yet
+    #[default]
+    Unpaid,
+    /// A slip is attached but has not been verified
+    PendingVerification,
+    /// A slip referencing this booking was verified
+    Paid,
+}
+
+impl PaymentStatus {
+    /// Column value as stored in `bookings.payment_status`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentStatus::Unpaid => "unpaid",
+            PaymentStatus::PendingVerification => "pending_verification",
+            PaymentStatus::Paid => "paid",
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            PaymentStatus::Unpaid => 0,
+            PaymentStatus::PendingVerification => 1,
+            PaymentStatus::Paid => 2,
+        }
+    }
+
+    /// Return the status after moving towards `next`, never moving backwards
+    pub fn advance_to(self, next: PaymentStatus) -> PaymentStatus {
+        if next.rank() > self.rank() {
+            next
+        } else {
+            self
+        }
+    }
+}
+
+impl std::fmt::Display for PaymentStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for PaymentStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "unpaid" => Ok(PaymentStatus::Unpaid),
+            "pending_verification" => Ok(PaymentStatus::PendingVerification),
+            "paid" => Ok(PaymentStatus::Paid),
+            _ => Err(format!("Invalid payment status: {}", s)),
+        }
+    }
+}
+
+/// Who sold the stay, stored in `bookings.source`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookingSource {
+    /// Booked with the hotel itself
+    #[default]
+    Direct,
+    /// Online travel agency (Booking.com, Agoda, ...)
+    Ota,
+    /// Offline travel agent
+    TravelAgent,
+    /// Corporate account / negotiated rate
+    Corporate,
+}
+
+impl BookingSource {
+    /// Every accepted value, for validation messages and reporting
+    pub const ALL: [BookingSource; 4] = [
+        BookingSource::Direct,
+        BookingSource::Ota,
+        BookingSource::TravelAgent,
+        BookingSource::Corporate,
+    ];
+
+    /// Column value as stored in `bookings.source`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BookingSource::Direct => "direct",
+            BookingSource::Ota => "ota",
+            BookingSource::TravelAgent => "travel_agent",
+            BookingSource::Corporate => "corporate",
+        }
+    }
+}
+
+impl std::fmt::Display for BookingSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for BookingSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "direct" => Ok(BookingSource::Direct),
+            "ota" => Ok(BookingSource::Ota),
+            "travel_agent" => Ok(BookingSource::TravelAgent),
+            "corporate" => Ok(BookingSource::Corporate),
+            _ => Err(format!("Invalid booking source: {}", s)),
+        }
+    }
+}
+
+/// How the booking reached us, stored in `bookings.channel`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookingChannel {
+    /// Our website
+    #[default]
+    Web,
+    /// Our mobile app
+    App,
+    /// Taken over the phone by staff
+    Phone,
+    /// Taken at the front desk
+    WalkIn,
+    /// Taken by email by staff
+    Email,
+}
+
+impl BookingChannel {
+    /// Every accepted value, for validation messages and reporting
+    pub const ALL: [BookingChannel; 5] = [
+        BookingChannel::Web,
+        BookingChannel::App,
+        BookingChannel::Phone,
+        BookingChannel::WalkIn,
+        BookingChannel::Email,
+    ];
+
+    /// Column value as stored in `bookings.channel`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BookingChannel::Web => "web",
+            BookingChannel::App => "app",
+            BookingChannel::Phone => "phone",
+            BookingChannel::WalkIn => "walk_in",
+            BookingChannel::Email => "email",
+        }
+    }
+
+    /// Channels a guest can book through themselves; the rest are
+    /// staff-entered
+    pub fn is_self_service(&self) -> bool {
+        matches!(self, BookingChannel::Web | BookingChannel::App)
+    }
+}
+
+impl std::fmt::Display for BookingChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for BookingChannel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "web" => Ok(BookingChannel::Web),
+            "app" => Ok(BookingChannel::App),
+            "phone" => Ok(BookingChannel::Phone),
+            "walk_in" => Ok(BookingChannel::WalkIn),
+            "email" => Ok(BookingChannel::Email),
+            _ => Err(format!("Invalid booking channel: {}", s)),
+        }
+    }
+}
+
 /// Room type enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -40,6 +226,9 @@ pub struct Booking {
     pub user_id: Uuid,
     pub booking_reference: String,
     pub status: BookingStatus,
+    pub payment_status: PaymentStatus,
+    pub source: BookingSource,
+    pub channel: BookingChannel,
     pub check_in_date: NaiveDate,
     pub check_out_date: NaiveDate,
     pub nights_count: i32,
@@ -47,7 +236,10 @@ pub struct Booking {
     pub room_number: Option<String>,
     pub total_amount: rust_decimal::Decimal,
     pub currency: String,
+    /// Total guests (`adults + children`)
     pub guest_count: Option<i32>,
+    pub adults: Option<i32>,
+    pub children: Option<i32>,
     pub special_requests: Option<String>,
     pub confirmation_number: Option<String>,
     pub external_booking_id: Option<String>,
@@ -71,6 +263,10 @@ pub struct CreateBookingRequest {
     pub total_amount: rust_decimal::Decimal,
     pub currency: Option<String>,
     pub external_booking_id: Option<String>,
+    #[serde(default)]
+    pub source: BookingSource,
+    #[serde(default)]
+    pub channel: BookingChannel,
 }
 
 /// Update booking request DTO
@@ -94,14 +290,23 @@ pub struct BookingResponse {
     pub user_id: Uuid,
     pub booking_reference: String,
     pub status: BookingStatus,
+    pub payment_status: PaymentStatus,
+    pub source: BookingSource,
+    pub channel: BookingChannel,
     pub check_in_date: NaiveDate,
     pub check_out_date: NaiveDate,
     pub nights_count: i32,
     pub room_type: Option<RoomType>,
     pub room_number: Option<String>,
     pub total_amount: rust_decimal::Decimal,
+    /// `total_amount` formatted for display (e.g. `฿4,500.00`); the numeric
+    /// field stays authoritative
+    pub total_amount_display: String,
     pub currency: String,
+    /// Total guests (`adults + children`)
     pub guest_count: Option<i32>,
+    pub adults: Option<i32>,
+    pub children: Option<i32>,
     pub special_requests: Option<String>,
     pub confirmation_number: Option<String>,
     pub points_earned: Option<i32>,
@@ -127,14 +332,23 @@ impl From<Booking> for BookingResponse {
             user_id: booking.user_id,
             booking_reference: booking.booking_reference,
             status: booking.status,
+            payment_status: booking.payment_status,
+            source: booking.source,
+            channel: booking.channel,
             check_in_date: booking.check_in_date,
             check_out_date: booking.check_out_date,
             nights_count: booking.nights_count,
             room_type: booking.room_type,
             room_number: booking.room_number,
             total_amount: booking.total_amount,
+            total_amount_display: crate::utils::format_money(
+                booking.total_amount,
+                &booking.currency,
+            ),
             currency: booking.currency,
             guest_count: booking.guest_count,
+            adults: booking.adults,
+            children: booking.children,
             special_requests: booking.special_requests,
             confirmation_number: booking.confirmation_number,
             points_earned: booking.points_earned,
@@ -165,3 +379,70 @@ impl Booking {
         matches!(self.status, BookingStatus::CheckedOut)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payment_status_advances_forward() {
+        assert_eq!(
+            PaymentStatus::Unpaid.advance_to(PaymentStatus::PendingVerification),
+            PaymentStatus::PendingVerification
+        );
+        assert_eq!(
+            PaymentStatus::PendingVerification.advance_to(PaymentStatus::Paid),
+            PaymentStatus::Paid
+        );
+        assert_eq!(
+            PaymentStatus::Unpaid.advance_to(PaymentStatus::Paid),
+            PaymentStatus::Paid
+        );
+    }
+
+    #[test]
+    fn test_payment_status_never_moves_backwards() {
+        assert_eq!(
+            PaymentStatus::Paid.advance_to(PaymentStatus::PendingVerification),
+            PaymentStatus::Paid
+        );
+        assert_eq!(
+            PaymentStatus::PendingVerification.advance_to(PaymentStatus::Unpaid),
+            PaymentStatus::PendingVerification
+        );
+    }
+
+    #[test]
+    fn test_payment_status_round_trip() {
+        for status in [
+            PaymentStatus::Unpaid,
+            PaymentStatus::PendingVerification,
+            PaymentStatus::Paid,
+        ] {
+            assert_eq!(status.to_string().parse::<PaymentStatus>(), Ok(status));
+        }
+        assert!("refunded".parse::<PaymentStatus>().is_err());
+    }
+
+    #[test]
+    fn test_booking_source_and_channel_round_trip() {
+        assert_eq!(BookingSource::default(), BookingSource::Direct);
+        assert_eq!(BookingChannel::default(), BookingChannel::Web);
+        for source in BookingSource::ALL {
+            assert_eq!(source.as_str().parse::<BookingSource>(), Ok(source));
+        }
+        for channel in BookingChannel::ALL {
+            assert_eq!(channel.as_str().parse::<BookingChannel>(), Ok(channel));
+        }
+        assert!("expedia".parse::<BookingSource>().is_err());
+        assert!("fax".parse::<BookingChannel>().is_err());
+    }
+
+    #[test]
+    fn test_booking_channel_self_service() {
+        assert!(BookingChannel::Web.is_self_service());
+        assert!(BookingChannel::App.is_self_service());
+        assert!(!BookingChannel::Phone.is_self_service());
+        assert!(!BookingChannel::WalkIn.is_self_service());
+    }
+}
@@ -0,0 +1,149 @@
+//! Booking note models
+//!
+//! Notes staff attach to a booking, each with a [`NoteVisibility`] scope
+//! deciding whether the guest who owns the booking may read it.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Who may read a booking note, stored in `booking_notes.visibility`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteVisibility {
+    /// Staff only
+    #[default]
+    Internal,
+    /// Staff and the guest who owns the booking
+    Guest,
+}
+
+impl NoteVisibility {
+    /// Column value as stored in `booking_notes.visibility`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NoteVisibility::Internal => "internal",
+            NoteVisibility::Guest => "guest",
+        }
+    }
+
+    /// Whether a note with this visibility may be shown to the requester
+    pub fn is_visible_to(&self, is_staff: bool) -> bool {
+        is_staff || *self == NoteVisibility::Guest
+    }
+}
+
+impl std::fmt::Display for NoteVisibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for NoteVisibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "internal" => Ok(NoteVisibility::Internal),
+            "guest" => Ok(NoteVisibility::Guest),
+            _ => Err(format!("Invalid note visibility: {}", s)),
+        }
+    }
+}
+
+/// A row of `booking_notes`
+#[derive(Debug, Clone, FromRow)]
+pub struct BookingNote {
+    pub id: Uuid,
+    pub booking_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub visibility: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request body for `POST /api/bookings/:id/notes`
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBookingNoteRequest {
+    /// The note text, 1-2000 characters
+    #[validate(length(min = 1, max = 2000, message = "Note must be 1-2000 characters"))]
+    pub body: String,
+    /// Defaults to `internal`, so a note is only shown to the guest when
+    /// staff ask for it
+    #[serde(default)]
+    pub visibility: NoteVisibility,
+}
+
+/// A booking note as returned by the API
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookingNoteResponse {
+    pub id: Uuid,
+    pub booking_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub visibility: NoteVisibility,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<BookingNote> for BookingNoteResponse {
+    fn from(note: BookingNote) -> Self {
+        Self {
+            id: note.id,
+            booking_id: note.booking_id,
+            author_id: note.author_id,
+            // The column's CHECK constraint only admits the two scopes;
+            // anything else is treated as the more restrictive one
+            visibility: note.visibility.parse().unwrap_or_default(),
+            body: note.body,
+            created_at: note.created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visibility_round_trip() {
+        for visibility in [NoteVisibility::Internal, NoteVisibility::Guest] {
+            assert_eq!(
+                visibility.as_str().parse::<NoteVisibility>().unwrap(),
+                visibility
+            );
+        }
+        assert!("public".parse::<NoteVisibility>().is_err());
+    }
+
+    #[test]
+    fn test_guests_only_see_guest_notes() {
+        assert!(NoteVisibility::Guest.is_visible_to(false));
+        assert!(!NoteVisibility::Internal.is_visible_to(false));
+        assert!(NoteVisibility::Guest.is_visible_to(true));
+        assert!(NoteVisibility::Internal.is_visible_to(true));
+    }
+
+    #[test]
+    fn test_create_request_defaults_to_internal() {
+        let request: CreateBookingNoteRequest =
+            serde_json::from_str(r#"{"body": "VIP, upgrade if possible"}"#).unwrap();
+        assert_eq!(request.visibility, NoteVisibility::Internal);
+    }
+
+    #[test]
+    fn test_unknown_stored_visibility_is_internal() {
+        let response = BookingNoteResponse::from(BookingNote {
+            id: Uuid::new_v4(),
+            booking_id: Uuid::new_v4(),
+            author_id: None,
+            visibility: "bogus".to_string(),
+            body: "note".to_string(),
+            created_at: Utc::now(),
+        });
+        assert_eq!(response.visibility, NoteVisibility::Internal);
+    }
+}
@@ -2,13 +2,13 @@
 //!
 //! Contains structs for coupon management and user coupon assignments.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::Type;
 use uuid::Uuid;
 
 /// Coupon type enum
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
 #[sqlx(type_name = "coupon_type", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum CouponType {
@@ -95,6 +95,9 @@ pub struct UserCoupon {
 /// Create coupon request DTO
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateCouponRequest {
+    /// Coupon code; a unique one is generated when empty or omitted
+    /// (`COUPON_CODE_PREFIX` / `COUPON_CODE_LENGTH` / `COUPON_CODE_CHARSET`)
+    #[serde(default)]
     pub code: String,
     pub name: String,
     pub description: Option<String>,
@@ -106,6 +109,13 @@ pub struct CreateCouponRequest {
     pub maximum_discount: Option<rust_decimal::Decimal>,
     pub valid_from: Option<DateTime<Utc>>,
     pub valid_until: Option<DateTime<Utc>>,
+    /// First valid day in the hotel's local time (`COUPON_UTC_OFFSET`);
+    /// an alternative to `valid_from`
+    pub valid_from_date: Option<NaiveDate>,
+    /// Last valid day in the hotel's local time, inclusive: the coupon
+    /// expires at the following local midnight. An alternative to
+    /// `valid_until`
+    pub valid_until_date: Option<NaiveDate>,
     pub usage_limit: Option<i32>,
     pub usage_limit_per_user: Option<i32>,
     pub tier_restrictions: Option<serde_json::Value>,
@@ -125,6 +135,13 @@ pub struct UpdateCouponRequest {
     pub maximum_discount: Option<rust_decimal::Decimal>,
     pub valid_from: Option<DateTime<Utc>>,
     pub valid_until: Option<DateTime<Utc>>,
+    /// First valid day in the hotel's local time (`COUPON_UTC_OFFSET`);
+    /// an alternative to `valid_from`
+    pub valid_from_date: Option<NaiveDate>,
+    /// Last valid day in the hotel's local time, inclusive: the coupon
+    /// expires at the following local midnight. An alternative to
+    /// `valid_until`
+    pub valid_until_date: Option<NaiveDate>,
     pub usage_limit: Option<i32>,
     pub usage_limit_per_user: Option<i32>,
     pub tier_restrictions: Option<serde_json::Value>,
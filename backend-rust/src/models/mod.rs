@@ -5,6 +5,7 @@
 //! (with sqlx::FromRow) and DTOs for request/response handling.
 
 pub mod booking;
+pub mod booking_note;
 pub mod coupon;
 pub mod notification;
 pub mod password_reset;
@@ -37,8 +38,8 @@ pub use user_loyalty::{
 
 // Tier models
 pub use tier::{
-    CreateTierRequest, Tier, TierProgression, TierResponse, TierSummary, TierWithStats,
-    UpdateTierRequest,
+    CreateTierRequest, Tier, TierBenefits, TierProgression, TierResponse, TierSummary,
+    TierWithStats, UpdateTierRequest,
 };
 
 // Points transaction models
@@ -63,15 +64,21 @@ pub use survey::{
 
 // Booking models
 pub use booking::{
-    Booking, BookingResponse, BookingStatus, BookingSummary, CreateBookingRequest, RoomType,
-    UpdateBookingRequest,
+    Booking, BookingChannel, BookingResponse, BookingSource, BookingStatus, BookingSummary,
+    CreateBookingRequest, PaymentStatus, RoomType, UpdateBookingRequest,
+};
+
+// Booking note models
+pub use booking_note::{
+    BookingNote, BookingNoteResponse, CreateBookingNoteRequest, NoteVisibility,
 };
 
 // Notification models
 pub use notification::{
-    CreateNotificationRequest, Notification, NotificationCountResponse, NotificationPreference,
-    NotificationPreferenceResponse, NotificationResponse, NotificationType,
-    PaginatedNotificationsResponse, UpdateNotificationPreferenceRequest, UpdateNotificationRequest,
+    CreateNotificationRequest, Notification, NotificationCountResponse, NotificationDispatch,
+    NotificationPreference, NotificationPreferenceResponse, NotificationPriority,
+    NotificationResponse, NotificationType, PaginatedNotificationsResponse,
+    UpdateNotificationPreferenceRequest, UpdateNotificationRequest,
 };
 
 // Password reset models
@@ -8,7 +8,14 @@ use sqlx::Type;
 use uuid::Uuid;
 
 /// Notification type enum
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+///
+/// Stored as text in `notifications.type`, limited by the
+/// `chk_notification_type` constraint to exactly these values. Adding a
+/// variant needs a migration extending that constraint, and [`dispatch`]
+/// makes the compiler ask how the new type is delivered.
+///
+/// [`dispatch`]: NotificationType::dispatch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
 #[sqlx(type_name = "notification_type", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum NotificationType {
@@ -26,6 +33,169 @@ pub enum NotificationType {
     Points,
 }
 
+/// How notifications of one [`NotificationType`] are delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotificationDispatch {
+    /// Pushed live to the member's SSE streams (it lands in the inbox
+    /// either way)
+    pub push: bool,
+    /// May be throttled or held for quiet hours; `false` always delivers
+    /// at once
+    pub deferrable: bool,
+    /// Lowest priority the type is sent at; senders may raise it
+    pub min_priority: NotificationPriority,
+}
+
+impl NotificationType {
+    /// Every type, in declaration order
+    pub const ALL: [NotificationType; 11] = [
+        NotificationType::Info,
+        NotificationType::Success,
+        NotificationType::Warning,
+        NotificationType::Error,
+        NotificationType::System,
+        NotificationType::Reward,
+        NotificationType::Coupon,
+        NotificationType::Survey,
+        NotificationType::Profile,
+        NotificationType::TierChange,
+        NotificationType::Points,
+    ];
+
+    /// Column / wire value for this type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationType::Info => "info",
+            NotificationType::Success => "success",
+            NotificationType::Warning => "warning",
+            NotificationType::Error => "error",
+            NotificationType::System => "system",
+            NotificationType::Reward => "reward",
+            NotificationType::Coupon => "coupon",
+            NotificationType::Survey => "survey",
+            NotificationType::Profile => "profile",
+            NotificationType::TierChange => "tier_change",
+            NotificationType::Points => "points",
+        }
+    }
+
+    /// Parse a stored `notifications.type` value. Rows written outside the
+    /// service (manual SQL, older releases) may hold anything; an unknown
+    /// value is logged and treated as `info` rather than failing the read.
+    pub fn from_stored(value: &str) -> Self {
+        value.parse().unwrap_or_else(|_| {
+            tracing::warn!(
+                notification_type = value,
+                "Unknown stored notification type, treating as info"
+            );
+            NotificationType::Info
+        })
+    }
+
+    /// Delivery rules for this type. Errors and system notices reach the
+    /// member straight away; everything else is routine.
+    pub fn dispatch(&self) -> NotificationDispatch {
+        match self {
+            NotificationType::Error | NotificationType::System => NotificationDispatch {
+                push: true,
+                deferrable: false,
+                min_priority: NotificationPriority::High,
+            },
+            NotificationType::Info
+            | NotificationType::Success
+            | NotificationType::Warning
+            | NotificationType::Reward
+            | NotificationType::Coupon
+            | NotificationType::Survey
+            | NotificationType::Profile
+            | NotificationType::TierChange
+            | NotificationType::Points => NotificationDispatch {
+                push: true,
+                deferrable: true,
+                min_priority: NotificationPriority::Low,
+            },
+        }
+    }
+
+    /// The priority a notification of this type is stored with when its
+    /// sender asked for `requested`
+    pub fn priority(&self, requested: NotificationPriority) -> NotificationPriority {
+        requested.max(self.dispatch().min_priority)
+    }
+}
+
+impl std::fmt::Display for NotificationType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for NotificationType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        NotificationType::ALL
+            .into_iter()
+            .find(|kind| kind.as_str().eq_ignore_ascii_case(value))
+            .ok_or_else(|| format!("Invalid notification type: {}", s))
+    }
+}
+
+/// Notification priority
+///
+/// Lets push clients tell security alerts apart from routine updates.
+/// Stored in `notifications.priority`; rows created before the column
+/// existed (and any caller that doesn't say otherwise) are `normal`.
+/// Ordered from `low` to `high`.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl NotificationPriority {
+    /// Column / wire value for this priority
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationPriority::Low => "low",
+            NotificationPriority::Normal => "normal",
+            NotificationPriority::High => "high",
+        }
+    }
+
+    /// Whether the notification must be delivered immediately rather than
+    /// being held for a digest. Only `high` bypasses batching.
+    pub fn bypasses_digest(&self) -> bool {
+        matches!(self, NotificationPriority::High)
+    }
+}
+
+impl std::fmt::Display for NotificationPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for NotificationPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(NotificationPriority::Low),
+            "normal" => Ok(NotificationPriority::Normal),
+            "high" => Ok(NotificationPriority::High),
+            _ => Err(format!("Invalid notification priority: {}", s)),
+        }
+    }
+}
+
 /// Notification database entity
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Notification {
@@ -35,6 +205,10 @@ pub struct Notification {
     pub message: String,
     #[sqlx(rename = "type")]
     pub notification_type: String,
+    #[sqlx(default)]
+    pub priority: NotificationPriority,
+    #[sqlx(default)]
+    pub category: Option<String>,
     pub data: Option<serde_json::Value>,
     pub read_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
@@ -61,6 +235,9 @@ pub struct CreateNotificationRequest {
     pub title: String,
     pub message: String,
     pub notification_type: Option<NotificationType>,
+    #[serde(default)]
+    pub priority: NotificationPriority,
+    pub category: Option<String>,
     pub data: Option<serde_json::Value>,
     pub expires_at: Option<DateTime<Utc>>,
 }
@@ -79,6 +256,8 @@ pub struct NotificationResponse {
     pub title: String,
     pub message: String,
     pub notification_type: String,
+    pub priority: NotificationPriority,
+    pub category: Option<String>,
     pub data: Option<serde_json::Value>,
     pub read_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
@@ -127,6 +306,8 @@ impl From<Notification> for NotificationResponse {
             title: notification.title,
             message: notification.message,
             notification_type: notification.notification_type,
+            priority: notification.priority,
+            category: notification.category,
             data: notification.data,
             read_at: notification.read_at,
             created_at: notification.created_at,
@@ -147,6 +328,11 @@ impl From<NotificationPreference> for NotificationPreferenceResponse {
 }
 
 impl Notification {
+    /// The notification's type; unknown stored values read as `info`
+    pub fn kind(&self) -> NotificationType {
+        NotificationType::from_stored(&self.notification_type)
+    }
+
     /// Check if the notification is read
     pub fn is_read(&self) -> bool {
         self.read_at.is_some()
@@ -160,3 +346,62 @@ impl Notification {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_type_matches_stored_values() {
+        // The values allowed by `chk_notification_type`
+        let stored = [
+            "info",
+            "success",
+            "warning",
+            "error",
+            "system",
+            "reward",
+            "coupon",
+            "survey",
+            "profile",
+            "tier_change",
+            "points",
+        ];
+        assert_eq!(NotificationType::ALL.len(), stored.len());
+        for (kind, value) in NotificationType::ALL.into_iter().zip(stored) {
+            assert_eq!(kind.as_str(), value);
+            assert_eq!(serde_json::to_value(kind).unwrap(), value);
+            assert_eq!(value.parse::<NotificationType>(), Ok(kind));
+            assert_eq!(NotificationType::from_stored(value), kind);
+        }
+        assert_eq!(
+            "Tier_Change".parse::<NotificationType>(),
+            Ok(NotificationType::TierChange)
+        );
+    }
+
+    #[test]
+    fn test_unknown_stored_type_reads_as_info() {
+        assert!("survey_reminder".parse::<NotificationType>().is_err());
+        assert_eq!(
+            NotificationType::from_stored("survey_reminder"),
+            NotificationType::Info
+        );
+        assert_eq!(NotificationType::from_stored(""), NotificationType::Info);
+    }
+
+    #[test]
+    fn test_notification_type_priority_floor() {
+        use NotificationPriority::*;
+
+        assert_eq!(NotificationType::Points.priority(Low), Low);
+        assert_eq!(NotificationType::Points.priority(High), High);
+        assert_eq!(NotificationType::Error.priority(Normal), High);
+        assert_eq!(NotificationType::System.priority(Low), High);
+        for kind in NotificationType::ALL {
+            let dispatch = kind.dispatch();
+            // A type that can't wait must also outrank quiet hours
+            assert!(dispatch.deferrable || dispatch.min_priority.bypasses_digest());
+        }
+    }
+}
@@ -10,10 +10,16 @@ use sqlx::FromRow;
 use uuid::Uuid;
 use validator::Validate;
 
-/// Points transaction type enumeration matching the PostgreSQL enum
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
-#[sqlx(type_name = "points_transaction_type", rename_all = "snake_case")]
-#[serde(rename_all = "snake_case")]
+/// Points transaction type enumeration matching the PostgreSQL
+/// `points_transaction_type` enum.
+///
+/// Serializes (JSON and SQL) to the same snake_case strings the database
+/// stores. A value this build doesn't know about (e.g. one added by a newer
+/// migration) decodes to [`PointsTransactionType::Unknown`] instead of
+/// failing the whole query; it is logged, serializes as `"unknown"`, and is
+/// refused when written back to the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "&'static str", from = "String")]
 pub enum PointsTransactionType {
     /// Points earned from a hotel stay
     EarnedStay,
@@ -35,19 +41,106 @@ pub enum PointsTransactionType {
 
     /// Points deducted by admin
     AdminDeduction,
+
+    /// Points converted from a partner loyalty program
+    Converted,
+
+    /// Points taken back after a booking was partially refunded
+    RefundAdjustment,
+
+    /// A value not known to this build
+    Unknown,
+}
+
+impl PointsTransactionType {
+    /// Every known type, in database enum order
+    pub const ALL: [PointsTransactionType; 9] = [
+        PointsTransactionType::EarnedStay,
+        PointsTransactionType::EarnedBonus,
+        PointsTransactionType::Redeemed,
+        PointsTransactionType::Expired,
+        PointsTransactionType::AdminAdjustment,
+        PointsTransactionType::AdminAward,
+        PointsTransactionType::AdminDeduction,
+        PointsTransactionType::Converted,
+        PointsTransactionType::RefundAdjustment,
+    ];
+
+    /// The database / API string for this type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PointsTransactionType::EarnedStay => "earned_stay",
+            PointsTransactionType::EarnedBonus => "earned_bonus",
+            PointsTransactionType::Redeemed => "redeemed",
+            PointsTransactionType::Expired => "expired",
+            PointsTransactionType::AdminAdjustment => "admin_adjustment",
+            PointsTransactionType::AdminAward => "admin_award",
+            PointsTransactionType::AdminDeduction => "admin_deduction",
+            PointsTransactionType::Converted => "converted",
+            PointsTransactionType::RefundAdjustment => "refund_adjustment",
+            PointsTransactionType::Unknown => "unknown",
+        }
+    }
+
+    /// Parse a database / API string; anything unrecognised is `Unknown`
+    pub fn from_db(value: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.as_str() == value)
+            .unwrap_or(PointsTransactionType::Unknown)
+    }
 }
 
 impl std::fmt::Display for PointsTransactionType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            PointsTransactionType::EarnedStay => write!(f, "earned_stay"),
-            PointsTransactionType::EarnedBonus => write!(f, "earned_bonus"),
-            PointsTransactionType::Redeemed => write!(f, "redeemed"),
-            PointsTransactionType::Expired => write!(f, "expired"),
-            PointsTransactionType::AdminAdjustment => write!(f, "admin_adjustment"),
-            PointsTransactionType::AdminAward => write!(f, "admin_award"),
-            PointsTransactionType::AdminDeduction => write!(f, "admin_deduction"),
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<PointsTransactionType> for &'static str {
+    fn from(value: PointsTransactionType) -> Self {
+        value.as_str()
+    }
+}
+
+impl From<String> for PointsTransactionType {
+    fn from(value: String) -> Self {
+        Self::from_db(&value)
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for PointsTransactionType {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("points_transaction_type")
+    }
+
+    /// Also accept the enum cast to text (`type::text`), which several
+    /// queries select.
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        *ty == Self::type_info() || <&str as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for PointsTransactionType {
+    fn decode(value: sqlx::postgres::PgValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let raw = <&str as sqlx::Decode<sqlx::Postgres>>::decode(value)?;
+        let parsed = Self::from_db(raw);
+        if parsed == PointsTransactionType::Unknown {
+            tracing::warn!(value = raw, "Unknown points_transaction_type value");
         }
+        Ok(parsed)
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for PointsTransactionType {
+    fn encode_by_ref(
+        &self,
+        buf: &mut sqlx::postgres::PgArgumentBuffer,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        if *self == PointsTransactionType::Unknown {
+            return Err("cannot write an unknown points transaction type".into());
+        }
+        <&str as sqlx::Encode<sqlx::Postgres>>::encode_by_ref(&self.as_str(), buf)
     }
 }
 
@@ -59,6 +152,7 @@ impl PointsTransactionType {
             PointsTransactionType::EarnedStay
                 | PointsTransactionType::EarnedBonus
                 | PointsTransactionType::AdminAward
+                | PointsTransactionType::Converted
         )
     }
 
@@ -69,6 +163,7 @@ impl PointsTransactionType {
             PointsTransactionType::Redeemed
                 | PointsTransactionType::Expired
                 | PointsTransactionType::AdminDeduction
+                | PointsTransactionType::RefundAdjustment
         )
     }
 
@@ -79,6 +174,8 @@ impl PointsTransactionType {
             PointsTransactionType::AdminAdjustment
                 | PointsTransactionType::AdminAward
                 | PointsTransactionType::AdminDeduction
+                | PointsTransactionType::Converted
+                | PointsTransactionType::RefundAdjustment
         )
     }
 }
@@ -342,12 +439,42 @@ mod tests {
         assert!(PointsTransactionType::Redeemed.is_debit());
         assert!(PointsTransactionType::Expired.is_debit());
         assert!(PointsTransactionType::AdminDeduction.is_debit());
+        assert!(PointsTransactionType::RefundAdjustment.is_debit());
 
         // AdminAdjustment can be either
         assert!(!PointsTransactionType::AdminAdjustment.is_credit());
         assert!(!PointsTransactionType::AdminAdjustment.is_debit());
     }
 
+    #[test]
+    fn test_transaction_type_strings_round_trip() {
+        for t in PointsTransactionType::ALL {
+            assert_eq!(PointsTransactionType::from_db(t.as_str()), t);
+            let json = serde_json::to_string(&t).unwrap();
+            assert_eq!(json, format!("\"{}\"", t.as_str()));
+            assert_eq!(
+                serde_json::from_str::<PointsTransactionType>(&json).unwrap(),
+                t
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&PointsTransactionType::AdminAward).unwrap(),
+            "\"admin_award\""
+        );
+    }
+
+    #[test]
+    fn test_transaction_type_unknown_value() {
+        assert_eq!(
+            PointsTransactionType::from_db("loyalty_transfer"),
+            PointsTransactionType::Unknown
+        );
+        let parsed: PointsTransactionType = serde_json::from_str("\"loyalty_transfer\"").unwrap();
+        assert_eq!(parsed, PointsTransactionType::Unknown);
+        assert!(!parsed.is_credit());
+        assert!(!parsed.is_debit());
+    }
+
     #[test]
     fn test_transaction_type_admin_action() {
         assert!(PointsTransactionType::AdminAward.is_admin_action());
@@ -143,6 +143,9 @@ pub struct SurveyResponseDto {
     pub scheduled_start: Option<NaiveDateTime>,
     pub scheduled_end: Option<NaiveDateTime>,
     pub access_type: String,
+    /// Member targeting (`tierIds`, `minNights`, `lastStayWithinDays`);
+    /// `{}` targets everyone. See `services::survey::SurveyTargeting`.
+    pub target_segment: Option<serde_json::Value>,
     pub created_at: Option<NaiveDateTime>,
 }
 
@@ -182,6 +185,7 @@ impl From<Survey> for SurveyResponseDto {
             scheduled_start: survey.scheduled_start,
             scheduled_end: survey.scheduled_end,
             access_type: survey.access_type,
+            target_segment: survey.target_segment,
             created_at: survey.created_at,
         }
     }
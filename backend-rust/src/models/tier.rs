@@ -5,6 +5,7 @@
 //! nights stayed (NOT points).
 
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use sqlx::FromRow;
@@ -35,8 +36,8 @@ pub struct Tier {
     /// Minimum nights required to achieve this tier
     pub min_nights: i32,
 
-    /// JSON object containing tier benefits
-    /// Example: { "discount": 10, "free_breakfast": true, "late_checkout": true }
+    /// JSON object containing tier benefits; read it through
+    /// [`Tier::benefits`], which gives the structured [`TierBenefits`]
     pub benefits: Option<JsonValue>,
 
     /// Hex color code for UI display (e.g., "#CD7F32" for Bronze)
@@ -66,12 +67,199 @@ impl Tier {
         self.benefits.clone().unwrap_or(serde_json::json!({}))
     }
 
+    /// Get the structured benefits, coercing legacy JSON
+    pub fn benefits(&self) -> TierBenefits {
+        self.benefits
+            .as_ref()
+            .map(TierBenefits::from_json)
+            .unwrap_or_default()
+    }
+
     /// Check if a user qualifies for this tier based on nights stayed
     pub fn qualifies(&self, nights: i32) -> bool {
         nights >= self.min_nights
     }
 }
 
+/// Largest tier discount, in percent
+pub const MAX_TIER_DISCOUNT_PERCENT: f64 = 100.0;
+
+/// Structured benefits of a tier, stored in `tiers.benefits`
+///
+/// The named fields are what the application reads (e.g.
+/// [`TierBenefits::apply_discount`]); anything else a property wants to
+/// show goes in `extra`, which is passed through untouched. Requests must
+/// use this shape. Stored JSON that predates it is read through
+/// [`TierBenefits::from_json`], which coerces the legacy keys.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct TierBenefits {
+    /// Short description shown with the tier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(max = 500, message = "Description must be at most 500 characters"))]
+    pub description: Option<String>,
+
+    /// Discount on room rates, in percent (0-100)
+    #[serde(default)]
+    #[validate(range(min = 0.0, max = 100.0, message = "Discount must be between 0 and 100"))]
+    pub discount_percent: f64,
+
+    /// Complimentary nights per membership year
+    #[serde(default)]
+    #[validate(range(max = 365, message = "Free nights must be at most 365"))]
+    pub free_nights: u32,
+
+    /// Whether late checkout is included
+    #[serde(default)]
+    pub late_checkout: bool,
+
+    /// Currency value of one point when redeemed by members of the tier.
+    /// Unset falls back to the global `LOYALTY_POINTS_REDEMPTION_RATE`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(
+        exclusive_min = 0.0,
+        max = 1000.0,
+        message = "Redemption rate must be above 0 and at most 1000"
+    ))]
+    pub points_redemption_rate: Option<f64>,
+
+    /// Perks listed for the tier, in display order
+    #[serde(default)]
+    #[validate(
+        length(max = 20, message = "At most 20 perks are allowed"),
+        custom(function = "validate_perks")
+    )]
+    pub perks: Vec<String>,
+
+    /// Free-form additions not covered by the fields above
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extra: serde_json::Map<String, JsonValue>,
+}
+
+impl TierBenefits {
+    /// Read stored benefits, coercing JSON written before the structured
+    /// shape existed. Never fails:
+    ///
+    /// - `discount` is read as `discount_percent`; numbers may be strings
+    ///   and are clamped to 0-100
+    /// - `perks` may be a single string; a bare array is taken as the perks
+    ///   and a bare string as the description
+    /// - `late_checkout` may be a string (`"true"`)
+    /// - `redemption_rate` is read as `points_redemption_rate`; a rate that
+    ///   isn't positive is dropped, leaving the global default
+    /// - any other key is kept in `extra`
+    pub fn from_json(value: &JsonValue) -> Self {
+        let mut benefits = Self::default();
+        let object = match value {
+            JsonValue::Object(object) => object,
+            JsonValue::Array(items) => {
+                benefits.perks = items.iter().filter_map(perk_text).collect();
+                return benefits;
+            },
+            JsonValue::String(text) if !text.trim().is_empty() => {
+                benefits.description = Some(text.clone());
+                return benefits;
+            },
+            _ => return benefits,
+        };
+
+        for (key, value) in object {
+            match key.as_str() {
+                "description" => benefits.description = value.as_str().map(str::to_string),
+                "discount_percent" | "discount" => {
+                    if let Some(percent) = json_number(value) {
+                        benefits.discount_percent = percent.clamp(0.0, MAX_TIER_DISCOUNT_PERCENT);
+                    }
+                },
+                "free_nights" => {
+                    if let Some(nights) = json_number(value) {
+                        benefits.free_nights = nights.max(0.0) as u32;
+                    }
+                },
+                "late_checkout" => {
+                    benefits.late_checkout = match value {
+                        JsonValue::Bool(flag) => *flag,
+                        JsonValue::String(text) => text.eq_ignore_ascii_case("true"),
+                        _ => false,
+                    }
+                },
+                "points_redemption_rate" | "redemption_rate" => {
+                    benefits.points_redemption_rate =
+                        json_number(value).filter(|rate| rate.is_finite() && *rate > 0.0);
+                },
+                "perks" => {
+                    benefits.perks = match value {
+                        JsonValue::Array(items) => items.iter().filter_map(perk_text).collect(),
+                        other => perk_text(other).into_iter().collect(),
+                    }
+                },
+                "extra" => {
+                    if let JsonValue::Object(extra) = value {
+                        benefits.extra.extend(extra.clone());
+                    }
+                },
+                _ => {
+                    benefits.extra.insert(key.clone(), value.clone());
+                },
+            }
+        }
+
+        benefits
+    }
+
+    /// The structured form as stored in `tiers.benefits`
+    pub fn to_json(&self) -> JsonValue {
+        serde_json::to_value(self).unwrap_or_else(|_| serde_json::json!({}))
+    }
+
+    /// `amount` after the tier discount, rounded to 2 decimal places
+    pub fn apply_discount(&self, amount: Decimal) -> Decimal {
+        let percent = Decimal::try_from(self.discount_percent).unwrap_or(Decimal::ZERO);
+        (amount - amount * percent / Decimal::ONE_HUNDRED).round_dp(2)
+    }
+
+    /// The tier's points redemption rate, or `default_rate` when it has none
+    pub fn redemption_rate(&self, default_rate: Decimal) -> Decimal {
+        self.points_redemption_rate
+            .and_then(|rate| Decimal::try_from(rate).ok())
+            .unwrap_or(default_rate)
+    }
+
+    /// Currency value of `points` at [`Self::redemption_rate`], rounded to
+    /// 2 decimal places. Every points redemption is priced through this.
+    pub fn redemption_value(&self, points: i32, default_rate: Decimal) -> Decimal {
+        (Decimal::from(points) * self.redemption_rate(default_rate)).round_dp(2)
+    }
+}
+
+/// Perks must be non-blank and at most 200 characters each
+fn validate_perks(perks: &[String]) -> Result<(), validator::ValidationError> {
+    if perks
+        .iter()
+        .any(|perk| perk.trim().is_empty() || perk.len() > 200)
+    {
+        let mut error = validator::ValidationError::new("invalid_perk");
+        error.message = Some("Perks must be non-empty and at most 200 characters".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn json_number(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Number(number) => number.as_f64(),
+        JsonValue::String(text) => text.trim().trim_end_matches('%').parse().ok(),
+        _ => None,
+    }
+}
+
+fn perk_text(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::String(text) if !text.trim().is_empty() => Some(text.clone()),
+        _ => None,
+    }
+}
+
 /// Tier response DTO
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TierResponse {
@@ -88,7 +276,7 @@ pub struct TierResponse {
     pub min_nights: i32,
 
     /// Tier benefits
-    pub benefits: JsonValue,
+    pub benefits: TierBenefits,
 
     /// Display color (hex)
     pub color: String,
@@ -102,12 +290,13 @@ pub struct TierResponse {
 
 impl From<Tier> for TierResponse {
     fn from(tier: Tier) -> Self {
+        let benefits = tier.benefits();
         TierResponse {
             id: tier.id,
             name: tier.name,
             min_points: tier.min_points,
             min_nights: tier.min_nights,
-            benefits: tier.benefits.unwrap_or(serde_json::json!({})),
+            benefits,
             color: tier.color,
             sort_order: tier.sort_order,
             is_active: tier.is_active.unwrap_or(true),
@@ -129,8 +318,9 @@ pub struct CreateTierRequest {
     #[validate(range(min = 0, message = "Minimum nights cannot be negative"))]
     pub min_nights: i32,
 
-    /// Tier benefits as JSON
-    pub benefits: Option<JsonValue>,
+    /// Tier benefits
+    #[validate(nested)]
+    pub benefits: Option<TierBenefits>,
 
     /// Display color (hex format)
     #[validate(length(equal = 7, message = "Color must be 7 characters (e.g., #FFFFFF)"))]
@@ -158,7 +348,8 @@ pub struct UpdateTierRequest {
     pub min_nights: Option<i32>,
 
     /// Updated benefits
-    pub benefits: Option<JsonValue>,
+    #[validate(nested)]
+    pub benefits: Option<TierBenefits>,
 
     /// Updated color
     #[validate(length(equal = 7, message = "Color must be 7 characters (e.g., #FFFFFF)"))]
@@ -277,4 +468,125 @@ mod tests {
         assert_eq!(response.min_nights, 10);
         assert!(response.is_active);
     }
+
+    #[test]
+    fn test_tier_benefits_coerces_legacy_json() {
+        let benefits = TierBenefits::from_json(&serde_json::json!({
+            "description": "Premium",
+            "discount": "15%",
+            "late_checkout": "true",
+            "perks": "Free upgrade",
+            "free_breakfast": true
+        }));
+
+        assert_eq!(benefits.description.as_deref(), Some("Premium"));
+        assert_eq!(benefits.discount_percent, 15.0);
+        assert!(benefits.late_checkout);
+        assert_eq!(benefits.perks, vec!["Free upgrade".to_string()]);
+        assert_eq!(benefits.extra["free_breakfast"], serde_json::json!(true));
+
+        let perks_only = TierBenefits::from_json(&serde_json::json!(["A", "", 3]));
+        assert_eq!(perks_only.perks, vec!["A".to_string()]);
+
+        let clamped = TierBenefits::from_json(&serde_json::json!({ "discount_percent": 250 }));
+        assert_eq!(clamped.discount_percent, 100.0);
+    }
+
+    #[test]
+    fn test_tier_benefits_round_trip_is_stable() {
+        let benefits = TierBenefits::from_json(&serde_json::json!({
+            "discount": 10,
+            "free_nights": 2,
+            "perks": ["Late checkout"],
+            "extra": { "lounge": "Sky Bar" },
+            "spa": "20% off"
+        }));
+        let stored = benefits.to_json();
+
+        assert_eq!(stored["discount_percent"], serde_json::json!(10.0));
+        assert_eq!(stored["extra"]["lounge"], "Sky Bar");
+        assert_eq!(stored["extra"]["spa"], "20% off");
+        assert_eq!(TierBenefits::from_json(&stored), benefits);
+        assert_eq!(
+            serde_json::from_value::<TierBenefits>(stored).unwrap(),
+            benefits
+        );
+    }
+
+    #[test]
+    fn test_tier_benefits_validation() {
+        let valid = TierBenefits {
+            discount_percent: 10.0,
+            perks: vec!["Free breakfast".to_string()],
+            ..Default::default()
+        };
+        assert!(valid.validate().is_ok());
+
+        let too_much = TierBenefits {
+            discount_percent: 120.0,
+            ..Default::default()
+        };
+        assert!(too_much.validate().is_err());
+
+        let blank_perk = TierBenefits {
+            perks: vec!["  ".to_string()],
+            ..Default::default()
+        };
+        assert!(blank_perk.validate().is_err());
+
+        // Unknown keys belong in `extra`
+        assert!(
+            serde_json::from_value::<TierBenefits>(serde_json::json!({ "spa": true })).is_err()
+        );
+    }
+
+    #[test]
+    fn test_tier_benefits_redemption_value() {
+        let platinum = TierBenefits {
+            points_redemption_rate: Some(0.25),
+            ..Default::default()
+        };
+        assert_eq!(
+            platinum.redemption_value(1000, Decimal::new(1, 1)),
+            Decimal::new(25000, 2)
+        );
+        // No tier rate: the global default applies
+        assert_eq!(
+            TierBenefits::default().redemption_value(1000, Decimal::new(1, 1)),
+            Decimal::new(10000, 2)
+        );
+
+        assert_eq!(
+            TierBenefits::from_json(&serde_json::json!({ "redemption_rate": "0.2" }))
+                .points_redemption_rate,
+            Some(0.2)
+        );
+        assert_eq!(
+            TierBenefits::from_json(&serde_json::json!({ "points_redemption_rate": 0 }))
+                .points_redemption_rate,
+            None
+        );
+    }
+
+    #[test]
+    fn test_tier_benefits_apply_discount() {
+        let benefits = TierBenefits {
+            discount_percent: 12.5,
+            ..Default::default()
+        };
+        assert_eq!(
+            benefits.apply_discount(Decimal::new(100000, 2)),
+            Decimal::new(87500, 2)
+        );
+        assert_eq!(
+            TierBenefits::default().apply_discount(Decimal::new(4999, 2)),
+            Decimal::new(4999, 2)
+        );
+    }
+
+    #[test]
+    fn test_tier_response_reads_structured_benefits() {
+        let response: TierResponse = create_test_tier().into();
+        assert_eq!(response.benefits.discount_percent, 15.0);
+    }
 }
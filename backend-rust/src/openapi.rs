@@ -77,13 +77,24 @@ impl Modify for SecurityAddon {
         crate::openapi::paths::health_check_db,
         crate::openapi::paths::health_check_redis,
         crate::openapi::paths::health_check_full,
+        crate::openapi::paths::health_live,
+        crate::openapi::paths::health_ready,
         // Auth endpoints
         crate::openapi::paths::auth_register,
         crate::openapi::paths::auth_login,
         crate::openapi::paths::auth_logout,
+        crate::openapi::paths::auth_logout_all,
+        crate::openapi::paths::auth_list_sessions,
+        crate::openapi::paths::auth_revoke_session,
         crate::openapi::paths::auth_refresh,
+        crate::openapi::paths::auth_reauth,
         crate::openapi::paths::auth_forgot_password,
         crate::openapi::paths::auth_reset_password,
+        crate::openapi::paths::auth_change_expired_password,
+        crate::openapi::paths::auth_request_magic_link,
+        crate::openapi::paths::auth_verify_magic_link,
+        crate::openapi::paths::auth_resend_verification,
+        crate::openapi::paths::auth_csrf_token,
         crate::openapi::paths::auth_me,
         // User endpoints
         crate::openapi::paths::get_current_user,
@@ -107,7 +118,10 @@ impl Modify for SecurityAddon {
         crate::openapi::paths::delete_coupon,
         crate::openapi::paths::get_user_coupons,
         crate::openapi::paths::assign_coupon,
+        crate::openapi::paths::assign_coupon_by_segment,
         crate::openapi::paths::redeem_coupon,
+        crate::openapi::paths::calculate_coupon,
+        crate::openapi::paths::redeem_multiple_coupons,
         crate::openapi::paths::validate_coupon,
         crate::openapi::paths::get_coupon_stats,
         // Survey endpoints
@@ -117,6 +131,9 @@ impl Modify for SecurityAddon {
         crate::openapi::paths::update_survey,
         crate::openapi::paths::submit_survey_response,
         crate::openapi::paths::get_survey_responses,
+        crate::openapi::paths::send_survey_invitations,
+        crate::openapi::paths::get_invited_survey,
+        crate::openapi::paths::submit_invited_response,
         // SSE endpoints
         crate::openapi::paths::sse_events,
         crate::openapi::paths::sse_info,
@@ -129,20 +146,28 @@ impl Modify for SecurityAddon {
             schemas::HealthResponse,
             schemas::DbHealthResponse,
             schemas::RedisHealthResponse,
+            schemas::ReadinessResponse,
             schemas::SystemHealthResponse,
             // Auth schemas
             schemas::RegisterRequest,
             schemas::LoginRequest,
+            schemas::ReauthRequest,
+            schemas::ChangeExpiredPasswordRequest,
             // LogoutRequest / RefreshTokenRequest removed in Phase 3 —
             // both endpoints now take an empty body and read the refresh
             // token from the HttpOnly cookie.
             schemas::ForgotPasswordRequest,
             schemas::ResetPasswordRequest,
+            schemas::MagicLinkRequest,
+            schemas::ResendVerificationRequest,
+            schemas::CsrfTokenResponse,
             schemas::AuthResponse,
             schemas::AuthTokens,
             schemas::MeResponse,
             schemas::MessageResponse,
             schemas::TokenRefreshResponse,
+            schemas::SessionResponse,
+            schemas::SessionListResponse,
             // User schemas
             schemas::UserResponse,
             schemas::UserRole,
@@ -154,6 +179,7 @@ impl Modify for SecurityAddon {
             schemas::LoyaltyStatusResponseUser,
             schemas::TierInfoUser,
             // Loyalty schemas
+            schemas::TierBenefits,
             schemas::TierResponse,
             schemas::LoyaltyStatusResponse,
             schemas::TierInfo,
@@ -173,8 +199,16 @@ impl Modify for SecurityAddon {
             schemas::CreateCouponRequest,
             schemas::UpdateCouponRequest,
             schemas::AssignCouponRequest,
+            schemas::AssignCouponBySegmentRequest,
+            schemas::SegmentAssignmentResult,
             schemas::RedeemCouponRequest,
             schemas::RedemptionResult,
+            schemas::CalculateCouponRequest,
+            schemas::LineItem,
+            schemas::CouponCalculation,
+            schemas::RedeemMultipleCouponsRequest,
+            schemas::StackedDiscount,
+            schemas::MultiRedemptionResult,
             schemas::CouponValidationResponse,
             schemas::CouponValidationData,
             schemas::CouponStats,
@@ -191,10 +225,22 @@ impl Modify for SecurityAddon {
             schemas::SurveyAnswerDto,
             schemas::PaginatedSurveysResponse,
             schemas::PaginatedSurveyAnswersResponse,
+            schemas::SendSurveyInvitationsRequest,
+            schemas::SentSurveyInvitation,
+            schemas::SendSurveyInvitationsResponse,
+            schemas::InvitedSurveyResponse,
+            schemas::SubmitInvitationResponseRequest,
+            schemas::SubmitResponseResult,
             // SSE schemas
             schemas::SseInfoResponse,
             // Generic schemas
             schemas::SuccessResponse,
+            schemas::ResponsePagination,
+            schemas::TiersApiResponse,
+            schemas::LoyaltyStatusApiResponse,
+            schemas::TransactionsApiResponse,
+            schemas::AwardPointsApiResponse,
+            schemas::RecalculateTierApiResponse,
         )
     ),
     modifiers(&SecurityAddon)
@@ -258,6 +304,22 @@ pub mod schemas {
         pub timestamp: String,
     }
 
+    /// Readiness probe response
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct ReadinessResponse {
+        /// `ready` or `not_ready`
+        #[schema(example = "ready")]
+        pub status: String,
+        /// Database connection status
+        #[schema(example = "connected")]
+        pub database: String,
+        /// Redis connection status
+        #[schema(example = "connected")]
+        pub redis: String,
+        /// Current timestamp
+        pub timestamp: String,
+    }
+
     /// Redis health check response
     #[derive(Debug, Serialize, Deserialize, ToSchema)]
     pub struct RedisHealthResponse {
@@ -288,6 +350,15 @@ pub mod schemas {
         /// Redis connection status
         #[schema(example = "connected")]
         pub redis: String,
+        /// Connections currently open in the database pool
+        #[schema(example = 5)]
+        pub db_pool_size: u32,
+        /// Open pool connections not in use
+        #[schema(example = 4)]
+        pub db_pool_idle: usize,
+        /// Redis `PING` round trip in milliseconds; null when Redis is down
+        #[schema(example = 0.42)]
+        pub redis_latency_ms: Option<f64>,
     }
 
     // ============================================================================
@@ -314,6 +385,10 @@ pub mod schemas {
         /// Optional phone number
         #[schema(example = "+66812345678")]
         pub phone: Option<String>,
+        /// Optional referral code (the referring member's membership ID)
+        #[serde(rename = "referralCode")]
+        #[schema(example = "AB12CD34")]
+        pub referral_code: Option<String>,
     }
 
     /// Login request
@@ -330,6 +405,29 @@ pub mod schemas {
         pub remember_me: bool,
     }
 
+    /// Re-authentication request
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct ReauthRequest {
+        /// The signed-in user's password
+        #[schema(example = "securePassword123")]
+        pub password: String,
+    }
+
+    /// Expired password change request
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ChangeExpiredPasswordRequest {
+        /// User's email address
+        #[schema(example = "admin@example.com")]
+        pub email: String,
+        /// The expired password
+        #[schema(example = "securePassword123")]
+        pub current_password: String,
+        /// The replacement (at least 8 characters, different from the current one)
+        #[schema(example = "newSecurePassword456")]
+        pub new_password: String,
+    }
+
     // Phase 3: `LogoutRequest` and `RefreshTokenRequest` are gone. Both
     // endpoints take an empty JSON body and read the refresh token from
     // the `refresh_token` HttpOnly cookie. See `routes::auth` for the
@@ -343,6 +441,22 @@ pub mod schemas {
         pub email: String,
     }
 
+    /// Magic-link sign-in request
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct MagicLinkRequest {
+        /// Email address to send the sign-in link to
+        #[schema(example = "user@example.com")]
+        pub email: String,
+    }
+
+    /// Verification email resend request
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct ResendVerificationRequest {
+        /// Email address of the unverified account
+        #[schema(example = "user@example.com")]
+        pub email: String,
+    }
+
     /// Reset password request
     #[derive(Debug, Serialize, Deserialize, ToSchema)]
     pub struct ResetPasswordRequest {
@@ -382,6 +496,14 @@ pub mod schemas {
         pub user: UserResponse,
     }
 
+    /// CSRF token for the double-submit check
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CsrfTokenResponse {
+        /// Echo in the `X-CSRF-Token` header on state-changing requests
+        pub csrf_token: String,
+    }
+
     /// Generic message response
     #[derive(Debug, Serialize, Deserialize, ToSchema)]
     pub struct MessageResponse {
@@ -397,6 +519,32 @@ pub mod schemas {
         pub tokens: AuthTokens,
     }
 
+    /// One of the user's active sessions
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SessionResponse {
+        /// Session ID, used to revoke it
+        pub id: Uuid,
+        /// Client IP the session was started from
+        #[schema(example = "203.0.113.7")]
+        pub ip_address: Option<String>,
+        /// User agent the session was started from
+        pub user_agent: Option<String>,
+        /// When the user signed in
+        pub created_at: DateTime<Utc>,
+        /// Last login or token refresh
+        pub last_activity: DateTime<Utc>,
+        /// Whether this is the session making the request
+        pub current: bool,
+    }
+
+    /// Active sessions response
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct SessionListResponse {
+        /// Sessions, most recently active first
+        pub sessions: Vec<SessionResponse>,
+    }
+
     // ============================================================================
     // User Schemas
     // ============================================================================
@@ -469,6 +617,8 @@ pub mod schemas {
         pub date_of_birth: Option<NaiveDate>,
         /// User preferences as JSON
         pub preferences: Option<serde_json::Value>,
+        /// Opt in to (or out of) the loyalty leaderboard
+        pub leaderboard_opt_in: Option<bool>,
     }
 
     /// Change password request
@@ -549,6 +699,10 @@ pub mod schemas {
         /// Current points balance
         #[schema(example = 1500)]
         pub current_points: i32,
+        /// Current points balance formatted for display in the caller's
+        /// locale; never parse it, use `current_points`
+        #[schema(example = "1,500")]
+        pub current_points_display: String,
         /// Total nights stayed
         #[schema(example = 12)]
         pub total_nights: i32,
@@ -575,13 +729,38 @@ pub mod schemas {
         #[schema(example = 10)]
         pub min_nights: i32,
         /// Tier benefits
-        pub benefits: serde_json::Value,
+        pub benefits: TierBenefits,
     }
 
     // ============================================================================
     // Loyalty Schemas
     // ============================================================================
 
+    /// Structured tier benefits
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct TierBenefits {
+        /// Short description shown with the tier
+        #[schema(example = "Premium benefits for valued members")]
+        pub description: Option<String>,
+        /// Discount on room rates, in percent (0-100)
+        #[schema(example = 10.0)]
+        pub discount_percent: f64,
+        /// Complimentary nights per membership year
+        #[schema(example = 1)]
+        pub free_nights: u32,
+        /// Whether late checkout is included
+        pub late_checkout: bool,
+        /// Currency value of one point when redeemed; unset uses the
+        /// global default
+        #[schema(example = 0.25)]
+        pub points_redemption_rate: Option<f64>,
+        /// Perks listed for the tier, in display order
+        #[schema(example = json!(["Free room upgrade", "Bonus points"]))]
+        pub perks: Vec<String>,
+        /// Free-form additions not covered by the fields above
+        pub extra: Option<serde_json::Value>,
+    }
+
     /// Tier response
     #[derive(Debug, Serialize, Deserialize, ToSchema)]
     pub struct TierResponse {
@@ -596,8 +775,8 @@ pub mod schemas {
         /// Minimum nights required
         #[schema(example = 10)]
         pub min_nights: i32,
-        /// Tier benefits as JSON
-        pub benefits: serde_json::Value,
+        /// Tier benefits
+        pub benefits: TierBenefits,
         /// Display color (hex)
         #[schema(example = "#FFD700")]
         pub color: String,
@@ -616,6 +795,17 @@ pub mod schemas {
         /// Current points balance
         #[schema(example = 1500)]
         pub current_points: i32,
+        /// Current points balance formatted for display in the caller's
+        /// locale; never parse it, use `current_points`
+        #[schema(example = "1,500")]
+        pub current_points_display: String,
+        /// Redeemable points; the same as `current_points`
+        #[schema(example = 1500)]
+        pub available_points: i32,
+        /// Stay points waiting for payment slip verification; not included
+        /// in `current_points` and not redeemable yet
+        #[schema(example = 300)]
+        pub pending_points: i32,
         /// Total nights stayed
         #[schema(example = 12)]
         pub total_nights: i32,
@@ -641,7 +831,7 @@ pub mod schemas {
         #[schema(example = "#FFD700")]
         pub color: String,
         /// Tier benefits
-        pub benefits: serde_json::Value,
+        pub benefits: TierBenefits,
         /// Minimum nights required
         #[schema(example = 10)]
         pub min_nights: i32,
@@ -712,6 +902,13 @@ pub mod schemas {
         /// Total pages
         #[schema(example = 3)]
         pub total_pages: i32,
+        /// True when older transactions exist beyond the history window
+        /// (download them from the CSV export)
+        pub truncated: bool,
+        /// The history window applied: max_age_days, max_rows, since, export_path
+        pub history_limit: Option<serde_json::Value>,
+        /// Opaque cursor for the next page; absent on the last page
+        pub next_cursor: Option<String>,
     }
 
     /// Award points request
@@ -732,6 +929,9 @@ pub mod schemas {
         /// Description
         #[schema(example = "Bonus points for referral")]
         pub description: Option<String>,
+        /// Required to exceed LOYALTY_MAX_POINTS_PER_AWARD / LOYALTY_MAX_NIGHTS_PER_AWARD (super_admins are exempt)
+        #[schema(example = false)]
+        pub confirm_large: Option<bool>,
     }
 
     /// Award points result
@@ -772,6 +972,8 @@ pub mod schemas {
         /// Current total nights
         #[schema(example = 12)]
         pub total_nights: i32,
+        /// When a downgrade held back by LOYALTY_TIER_DOWNGRADE_GRACE_DAYS takes effect; absent if none is pending
+        pub downgrade_eligible_at: Option<DateTime<Utc>>,
     }
 
     // ============================================================================
@@ -931,9 +1133,10 @@ pub mod schemas {
     /// Create coupon request
     #[derive(Debug, Serialize, Deserialize, ToSchema)]
     pub struct CreateCouponRequest {
-        /// Unique coupon code (uppercase letters, numbers, underscores, hyphens)
+        /// Unique coupon code (uppercase letters, numbers, underscores, hyphens).
+        /// Omit to have a unique code generated.
         #[schema(example = "SUMMER2024")]
-        pub code: String,
+        pub code: Option<String>,
         /// Coupon name
         #[schema(example = "Summer Sale Discount")]
         pub name: String,
@@ -956,6 +1159,12 @@ pub mod schemas {
         pub valid_from: Option<DateTime<Utc>>,
         /// Valid until date
         pub valid_until: Option<DateTime<Utc>>,
+        /// First valid day in hotel-local time (instead of `valid_from`)
+        #[schema(example = "2026-03-01")]
+        pub valid_from_date: Option<NaiveDate>,
+        /// Last valid day in hotel-local time, inclusive (instead of `valid_until`)
+        #[schema(example = "2026-03-31")]
+        pub valid_until_date: Option<NaiveDate>,
         /// Total usage limit
         pub usage_limit: Option<i32>,
         /// Usage limit per user
@@ -989,6 +1198,10 @@ pub mod schemas {
         pub valid_from: Option<DateTime<Utc>>,
         /// Updated valid until
         pub valid_until: Option<DateTime<Utc>>,
+        /// Updated first valid day in hotel-local time
+        pub valid_from_date: Option<NaiveDate>,
+        /// Updated last valid day in hotel-local time, inclusive
+        pub valid_until_date: Option<NaiveDate>,
         /// Updated usage limit
         pub usage_limit: Option<i32>,
         /// Updated usage limit per user
@@ -1016,6 +1229,40 @@ pub mod schemas {
         /// Custom expiry date
         #[serde(rename = "customExpiry")]
         pub custom_expiry: Option<DateTime<Utc>>,
+        /// Notify recipients in-app and by email (default: false)
+        #[serde(rename = "notifyUsers", default)]
+        pub notify_users: bool,
+    }
+
+    /// Assign a coupon to a tier or segment
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct AssignCouponBySegmentRequest {
+        /// Coupon ID to assign
+        #[serde(rename = "couponId")]
+        pub coupon_id: Uuid,
+        /// Tier names to target (e.g. `["Gold"]`); empty targets any tier
+        #[serde(default)]
+        pub tiers: Vec<String>,
+        /// Further criteria: `minNights`, `lastStayWithinDays`, `tiers`
+        pub segment: Option<serde_json::Value>,
+        /// Reason for assignment
+        #[serde(rename = "assignedReason")]
+        pub assigned_reason: Option<String>,
+        /// Custom expiry date
+        #[serde(rename = "customExpiry")]
+        pub custom_expiry: Option<DateTime<Utc>>,
+        /// Notify recipients in-app and by email (default: false)
+        #[serde(rename = "notifyUsers", default)]
+        pub notify_users: bool,
+    }
+
+    /// Outcome of a segment assignment
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct SegmentAssignmentResult {
+        /// Members who received the coupon
+        pub assigned: usize,
+        /// Members in the segment who already held it
+        pub skipped: usize,
     }
 
     /// Redeem coupon request
@@ -1034,6 +1281,67 @@ pub mod schemas {
         pub location: Option<String>,
         /// Additional metadata
         pub metadata: Option<serde_json::Value>,
+        /// The order's items; required for BOGO coupons
+        #[serde(rename = "lineItems")]
+        pub line_items: Option<Vec<LineItem>>,
+    }
+
+    /// One line of an order
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct LineItem {
+        /// What was bought
+        pub name: Option<String>,
+        /// Price of one unit
+        #[serde(rename = "unitPrice")]
+        pub unit_price: Decimal,
+        /// Number of units (default 1, max 1000)
+        pub quantity: Option<u32>,
+    }
+
+    /// Coupon discount preview request
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct CalculateCouponRequest {
+        /// QR code of the user coupon
+        #[serde(rename = "qrCode")]
+        pub qr_code: String,
+        /// Order total before discount
+        #[serde(rename = "originalAmount")]
+        pub original_amount: Decimal,
+        /// The order's items; required for BOGO coupons
+        #[serde(rename = "lineItems")]
+        pub line_items: Option<Vec<LineItem>>,
+    }
+
+    /// What redeeming a coupon on an order would do
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct CouponCalculation {
+        /// Whether the coupon could be redeemed on this order right now
+        pub applicable: bool,
+        /// Why not, when it isn't
+        #[schema(example = "Minimum spend of 1000 THB is required")]
+        pub reason: Option<String>,
+        /// Original amount
+        #[serde(rename = "originalAmount")]
+        pub original_amount: Decimal,
+        /// Discount the redemption would apply (zero when not applicable)
+        #[serde(rename = "discountAmount")]
+        pub discount_amount: Decimal,
+        /// Final amount after discount
+        #[serde(rename = "finalAmount")]
+        pub final_amount: Decimal,
+        /// Original amount formatted for display
+        #[serde(rename = "originalAmountDisplay")]
+        pub original_amount_display: String,
+        /// Discount amount formatted for display
+        #[serde(rename = "discountAmountDisplay")]
+        pub discount_amount_display: String,
+        /// Final amount formatted for display
+        #[serde(rename = "finalAmountDisplay")]
+        pub final_amount_display: String,
+        /// What a free upgrade/service coupon gives, or how many items a
+        /// BOGO coupon made free
+        #[serde(rename = "benefitDescription")]
+        pub benefit_description: Option<String>,
     }
 
     /// Redemption result
@@ -1053,6 +1361,83 @@ pub mod schemas {
         /// Final amount after discount
         #[serde(rename = "finalAmount")]
         pub final_amount: Decimal,
+        /// Original amount formatted for display (e.g. `฿1,500.00`)
+        #[serde(rename = "originalAmountDisplay")]
+        #[schema(example = "฿1,500.00")]
+        pub original_amount_display: String,
+        /// Discount amount formatted for display
+        #[serde(rename = "discountAmountDisplay")]
+        #[schema(example = "฿150.00")]
+        pub discount_amount_display: String,
+        /// Final amount formatted for display
+        #[serde(rename = "finalAmountDisplay")]
+        #[schema(example = "฿1,350.00")]
+        pub final_amount_display: String,
+        /// What a free upgrade/service coupon gives, or how many items a
+        /// BOGO coupon made free; omitted for percentage and fixed coupons
+        #[serde(rename = "benefitDescription")]
+        #[schema(example = "Free upgrade: Deluxe to Junior Suite")]
+        pub benefit_description: Option<String>,
+    }
+
+    /// Request to redeem several coupons on one transaction
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct RedeemMultipleCouponsRequest {
+        /// QR codes of the user coupons to combine (1-10)
+        #[serde(rename = "qrCodes")]
+        pub qr_codes: Vec<String>,
+        /// Original amount before discount
+        #[serde(rename = "originalAmount")]
+        pub original_amount: Decimal,
+        /// Transaction reference
+        #[serde(rename = "transactionReference")]
+        pub transaction_reference: Option<String>,
+        /// Location of redemption
+        pub location: Option<String>,
+        /// Additional metadata
+        pub metadata: Option<serde_json::Value>,
+    }
+
+    /// One coupon's share of a stacked discount
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct StackedDiscount {
+        #[serde(rename = "userCouponId")]
+        pub user_coupon_id: Uuid,
+        #[serde(rename = "couponId")]
+        pub coupon_id: Uuid,
+        #[serde(rename = "qrCode")]
+        pub qr_code: String,
+        #[serde(rename = "type")]
+        pub coupon_type: CouponType,
+        /// The amount this coupon was applied to (after earlier coupons)
+        #[serde(rename = "amountBefore")]
+        pub amount_before: Decimal,
+        /// This coupon's discount, after its own maximum_discount cap
+        #[serde(rename = "discountAmount")]
+        pub discount_amount: Decimal,
+    }
+
+    /// Result of redeeming several coupons together
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct MultiRedemptionResult {
+        pub success: bool,
+        #[schema(example = "2 coupons redeemed successfully")]
+        pub message: String,
+        #[serde(rename = "originalAmount")]
+        pub original_amount: Decimal,
+        /// Sum of every coupon's discount
+        #[serde(rename = "discountAmount")]
+        pub discount_amount: Decimal,
+        #[serde(rename = "finalAmount")]
+        pub final_amount: Decimal,
+        #[serde(rename = "originalAmountDisplay")]
+        pub original_amount_display: String,
+        #[serde(rename = "discountAmountDisplay")]
+        pub discount_amount_display: String,
+        #[serde(rename = "finalAmountDisplay")]
+        pub final_amount_display: String,
+        /// Each coupon's discount, in the order it was applied
+        pub coupons: Vec<StackedDiscount>,
     }
 
     /// Coupon validation response
@@ -1235,6 +1620,8 @@ pub mod schemas {
         /// Access type (public, invited)
         #[schema(example = "public")]
         pub access_type: String,
+        /// Member targeting (tierIds, minNights, lastStayWithinDays); `{}` targets everyone
+        pub target_segment: Option<serde_json::Value>,
         /// Creation timestamp
         pub created_at: Option<NaiveDateTime>,
     }
@@ -1295,6 +1682,78 @@ pub mod schemas {
         pub is_completed: bool,
     }
 
+    /// Send survey invitation links request
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SendSurveyInvitationsRequest {
+        /// Members to invite; the link goes to their account email
+        #[serde(default)]
+        pub user_ids: Vec<Uuid>,
+        /// Guests without an account to invite
+        #[serde(default)]
+        #[schema(example = json!(["guest@example.com"]))]
+        pub emails: Vec<String>,
+        /// How long the links stay valid in days (default 14, at most 90)
+        #[schema(example = 14)]
+        pub expires_in_days: Option<u32>,
+    }
+
+    /// One invitation link that was sent
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SentSurveyInvitation {
+        /// Invitation ID
+        pub id: Uuid,
+        /// Invited member, if the invitation is for one
+        pub user_id: Option<Uuid>,
+        /// Invited guest's email, if the invitation is for a guest
+        pub email: Option<String>,
+        /// The single-use link
+        pub link: String,
+        /// When the link stops working
+        pub expires_at: Option<NaiveDateTime>,
+    }
+
+    /// Send survey invitation links result
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct SendSurveyInvitationsResponse {
+        /// Number of invitations created
+        pub sent: usize,
+        /// The invitations created
+        pub invitations: Vec<SentSurveyInvitation>,
+    }
+
+    /// A survey opened through an invitation link
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    #[serde(rename_all = "camelCase")]
+    pub struct InvitedSurveyResponse {
+        /// The survey
+        pub survey: SurveyResponseDto,
+        /// When the link stops working
+        pub expires_at: Option<NaiveDateTime>,
+    }
+
+    /// Submit through an invitation link request; always a final submission
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct SubmitInvitationResponseRequest {
+        /// Answers as JSON (question_id -> answer)
+        pub answers: serde_json::Value,
+    }
+
+    /// Survey response submission result
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SubmitResponseResult {
+        /// Response ID
+        pub id: Uuid,
+        /// Survey ID
+        pub survey_id: Uuid,
+        /// Whether the response is completed
+        pub is_completed: bool,
+        /// Human-readable result
+        pub message: String,
+    }
+
     /// Survey answer DTO
     #[derive(Debug, Serialize, Deserialize, ToSchema)]
     pub struct SurveyAnswerDto {
@@ -1382,16 +1841,44 @@ pub mod schemas {
         pub data: Option<serde_json::Value>,
     }
 
-    /// Generic API response wrapper
+    /// Generic API response wrapper, mirroring `crate::types::ApiResponse`
     #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    #[aliases(
+        TiersApiResponse = ApiResponse<Vec<TierResponse>>,
+        LoyaltyStatusApiResponse = ApiResponse<LoyaltyStatusResponse>,
+        TransactionsApiResponse = ApiResponse<PaginatedTransactionsResponse>,
+        AwardPointsApiResponse = ApiResponse<AwardPointsResult>,
+        RecalculateTierApiResponse = ApiResponse<RecalculateTierResult>
+    )]
     pub struct ApiResponse<T> {
         /// Success flag
         pub success: bool,
-        /// Response data
-        pub data: T,
+        /// Response data (present on success)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub data: Option<T>,
+        /// Error message (present on failure)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub error: Option<String>,
         /// Optional message
         #[serde(skip_serializing_if = "Option::is_none")]
         pub message: Option<String>,
+        /// Pagination metadata (list endpoints only)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub pagination: Option<ResponsePagination>,
+    }
+
+    /// Pagination metadata carried by `ApiResponse`
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct ResponsePagination {
+        /// Current page number (1-indexed)
+        #[schema(example = 1)]
+        pub page: u32,
+        /// Items per page
+        #[schema(example = 20)]
+        pub limit: u32,
+        /// Total number of items
+        #[schema(example = 100)]
+        pub total: u64,
     }
 }
 
@@ -1451,6 +1938,29 @@ pub mod paths {
     )]
     pub async fn health_check_full() {}
 
+    /// Liveness probe; checks no dependencies
+    #[utoipa::path(
+        get,
+        path = "/health/live",
+        tag = "health",
+        responses(
+            (status = 200, description = "The process is serving requests", body = HealthResponse)
+        )
+    )]
+    pub async fn health_live() {}
+
+    /// Readiness probe; checks the database and Redis
+    #[utoipa::path(
+        get,
+        path = "/health/ready",
+        tag = "health",
+        responses(
+            (status = 200, description = "Database and Redis are reachable", body = ReadinessResponse),
+            (status = 503, description = "The database or Redis is unreachable", body = ReadinessResponse)
+        )
+    )]
+    pub async fn health_ready() {}
+
     // ============================================================================
     // Auth Endpoints
     // ============================================================================
@@ -1478,7 +1988,7 @@ pub mod paths {
         responses(
             (status = 200, description = "Login successful", body = AuthResponse),
             (status = 401, description = "Invalid credentials", body = ErrorResponse),
-            (status = 403, description = "Account disabled", body = ErrorResponse)
+            (status = 403, description = "Account disabled, or `password_expired` (change it via /auth/password/expired)", body = ErrorResponse)
         )
     )]
     pub async fn auth_login() {}
@@ -1501,6 +2011,60 @@ pub mod paths {
     )]
     pub async fn auth_logout() {}
 
+    /// Log out of all devices.
+    ///
+    /// Deletes every refresh token and session of the user and revokes
+    /// all access tokens issued so far, including the one sent with this
+    /// request. Clears the `refresh_token` cookie like `/auth/logout`.
+    #[utoipa::path(
+        post,
+        path = "/auth/logout-all",
+        tag = "auth",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Every session ended", body = MessageResponse),
+            (status = 401, description = "Not authenticated", body = ErrorResponse)
+        )
+    )]
+    pub async fn auth_logout_all() {}
+
+    /// List active sessions.
+    ///
+    /// Returns the user's signed-in sessions with the IP address and user
+    /// agent they were started from. The one this request was made with is
+    /// flagged `current`.
+    #[utoipa::path(
+        get,
+        path = "/auth/sessions",
+        tag = "auth",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Active sessions", body = SessionListResponse),
+            (status = 401, description = "Not authenticated", body = ErrorResponse)
+        )
+    )]
+    pub async fn auth_list_sessions() {}
+
+    /// Revoke a session.
+    ///
+    /// Ends one session: its refresh token is deleted and its access
+    /// tokens stop working. Other sessions are unaffected.
+    #[utoipa::path(
+        delete,
+        path = "/auth/sessions/{id}",
+        tag = "auth",
+        params(
+            ("id" = uuid::Uuid, Path, description = "Session ID")
+        ),
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Session revoked", body = MessageResponse),
+            (status = 401, description = "Not authenticated", body = ErrorResponse),
+            (status = 404, description = "No such session for this user", body = ErrorResponse)
+        )
+    )]
+    pub async fn auth_revoke_session() {}
+
     /// Refresh access token.
     ///
     /// Phase 3: takes no JSON body. The refresh token is read exclusively
@@ -1518,6 +2082,25 @@ pub mod paths {
     )]
     pub async fn auth_refresh() {}
 
+    /// Confirm the password for sensitive admin actions.
+    ///
+    /// Role changes and deletions return `403` with error
+    /// `reauth_required` when the password was last entered more than
+    /// `REAUTH_MAX_AGE_SECS` ago. Call this, then retry with the returned
+    /// access token.
+    #[utoipa::path(
+        post,
+        path = "/auth/reauth",
+        tag = "auth",
+        security(("bearer_auth" = [])),
+        request_body = ReauthRequest,
+        responses(
+            (status = 200, description = "Password confirmed; fresh access token", body = TokenRefreshResponse),
+            (status = 401, description = "Not authenticated or wrong password", body = ErrorResponse)
+        )
+    )]
+    pub async fn auth_reauth() {}
+
     /// Request password reset
     #[utoipa::path(
         post,
@@ -1543,6 +2126,79 @@ pub mod paths {
     )]
     pub async fn auth_reset_password() {}
 
+    /// Change a password past its maximum age and sign in.
+    ///
+    /// Login returns `403` with error `password_expired` once the password
+    /// is older than `ADMIN_PASSWORD_MAX_AGE_DAYS` (or
+    /// `CUSTOMER_PASSWORD_MAX_AGE_DAYS`); no tokens are issued until it is
+    /// changed here. Other sessions are signed out.
+    #[utoipa::path(
+        post,
+        path = "/auth/password/expired",
+        tag = "auth",
+        request_body = ChangeExpiredPasswordRequest,
+        responses(
+            (status = 200, description = "Password changed; signed in", body = AuthResponse),
+            (status = 400, description = "New password same as the current one", body = ErrorResponse),
+            (status = 401, description = "Invalid credentials", body = ErrorResponse)
+        )
+    )]
+    pub async fn auth_change_expired_password() {}
+
+    /// Email a single-use passwordless sign-in link
+    #[utoipa::path(
+        post,
+        path = "/auth/magic-link",
+        tag = "auth",
+        request_body = MagicLinkRequest,
+        responses(
+            (status = 200, description = "Sign-in link sent if the account exists", body = MessageResponse)
+        )
+    )]
+    pub async fn auth_request_magic_link() {}
+
+    /// Sign in with a magic-link token. The token works once.
+    #[utoipa::path(
+        get,
+        path = "/auth/magic-link/verify",
+        tag = "auth",
+        params(
+            ("token" = String, Query, description = "Token from the emailed link")
+        ),
+        responses(
+            (status = 200, description = "Signed in", body = AuthResponse),
+            (status = 400, description = "Invalid, expired or already used link", body = ErrorResponse),
+            (status = 403, description = "Account disabled or password expired", body = ErrorResponse)
+        )
+    )]
+    pub async fn auth_verify_magic_link() {}
+
+    /// Email a fresh verification code to an unverified account
+    #[utoipa::path(
+        post,
+        path = "/auth/resend-verification",
+        tag = "auth",
+        request_body = ResendVerificationRequest,
+        responses(
+            (status = 200, description = "Code sent if the account needs verifying", body = MessageResponse),
+            (status = 429, description = "Asked again within the cooldown; see Retry-After", body = ErrorResponse)
+        )
+    )]
+    pub async fn auth_resend_verification() {}
+
+    /// Get the CSRF token, also set as the `csrf_token` cookie. Cookie-
+    /// authenticated writes under `CSRF_PROTECTED_PATHS` must echo it in
+    /// `X-CSRF-Token`.
+    #[utoipa::path(
+        get,
+        path = "/auth/csrf-token",
+        tag = "auth",
+        responses(
+            (status = 200, description = "Current CSRF token", body = CsrfTokenResponse)
+        )
+    )]
+    pub async fn auth_csrf_token() {}
+
     /// Get current authenticated user
     #[utoipa::path(
         get,
@@ -1676,7 +2332,7 @@ pub mod paths {
         path = "/loyalty/tiers",
         tag = "loyalty",
         responses(
-            (status = 200, description = "List of loyalty tiers", body = [TierResponse])
+            (status = 200, description = "List of loyalty tiers", body = TiersApiResponse)
         )
     )]
     pub async fn get_tiers() {}
@@ -1688,7 +2344,7 @@ pub mod paths {
         tag = "loyalty",
         security(("bearer_auth" = [])),
         responses(
-            (status = 200, description = "Loyalty status", body = LoyaltyStatusResponse),
+            (status = 200, description = "Loyalty status", body = LoyaltyStatusApiResponse),
             (status = 401, description = "Not authenticated", body = ErrorResponse),
             (status = 404, description = "Loyalty status not found", body = ErrorResponse)
         )
@@ -1702,11 +2358,13 @@ pub mod paths {
         tag = "loyalty",
         params(
             ("page" = Option<i32>, Query, description = "Page number (default: 1)"),
-            ("limit" = Option<i32>, Query, description = "Items per page (default: 20, max: 100)")
+            ("limit" = Option<i32>, Query, description = "Items per page (default: 20, max: 100)"),
+            ("cursor" = Option<String>, Query, description = "`next_cursor` from the previous page; replaces `page`")
         ),
         security(("bearer_auth" = [])),
         responses(
-            (status = 200, description = "Transaction history", body = PaginatedTransactionsResponse),
+            (status = 200, description = "Transaction history", body = TransactionsApiResponse),
+            (status = 400, description = "Invalid cursor", body = ErrorResponse),
             (status = 401, description = "Not authenticated", body = ErrorResponse)
         )
     )]
@@ -1720,7 +2378,7 @@ pub mod paths {
         request_body = AwardPointsRequest,
         security(("bearer_auth" = [])),
         responses(
-            (status = 200, description = "Points awarded", body = AwardPointsResult),
+            (status = 200, description = "Points awarded", body = AwardPointsApiResponse),
             (status = 401, description = "Not authenticated", body = ErrorResponse),
             (status = 403, description = "Admin access required", body = ErrorResponse),
             (status = 404, description = "User loyalty record not found", body = ErrorResponse),
@@ -1739,7 +2397,7 @@ pub mod paths {
         ),
         security(("bearer_auth" = [])),
         responses(
-            (status = 200, description = "Tier recalculated", body = RecalculateTierResult),
+            (status = 200, description = "Tier recalculated", body = RecalculateTierApiResponse),
             (status = 401, description = "Not authenticated", body = ErrorResponse),
             (status = 403, description = "Admin access required", body = ErrorResponse),
             (status = 404, description = "User loyalty record not found", body = ErrorResponse)
@@ -1879,6 +2537,27 @@ pub mod paths {
     )]
     pub async fn assign_coupon() {}
 
+    /// Assign a coupon to every member of a tier or segment (admin only)
+    ///
+    /// Targets members matching both the request and the coupon's own
+    /// customer segment and tier restrictions; members who already hold
+    /// the coupon are skipped.
+    #[utoipa::path(
+        post,
+        path = "/coupons/assign-by-segment",
+        tag = "coupons",
+        request_body = AssignCouponBySegmentRequest,
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Assigned and skipped counts", body = SegmentAssignmentResult),
+            (status = 401, description = "Not authenticated", body = ErrorResponse),
+            (status = 403, description = "Admin access required", body = ErrorResponse),
+            (status = 404, description = "Coupon not found", body = ErrorResponse),
+            (status = 400, description = "No criteria, unknown tier or inactive coupon", body = ErrorResponse)
+        )
+    )]
+    pub async fn assign_coupon_by_segment() {}
+
     /// Redeem a coupon
     #[utoipa::path(
         post,
@@ -1888,13 +2567,55 @@ pub mod paths {
         security(("bearer_auth" = [])),
         responses(
             (status = 200, description = "Coupon redeemed", body = RedemptionResult),
-            (status = 400, description = "Coupon not available or minimum spend not met", body = ErrorResponse),
+            (status = 400, description = "Coupon not available, minimum spend not met, or a BOGO coupon without lineItems", body = ErrorResponse),
             (status = 401, description = "Not authenticated", body = ErrorResponse),
             (status = 404, description = "Invalid QR code", body = ErrorResponse)
         )
     )]
     pub async fn redeem_coupon() {}
 
+    /// Preview a coupon's discount on an order without redeeming it
+    ///
+    /// Same checks and math as redemption; a coupon that fails one is
+    /// reported with `applicable: false` and a reason.
+    #[utoipa::path(
+        post,
+        path = "/coupons/calculate",
+        tag = "coupons",
+        request_body = CalculateCouponRequest,
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Discount preview", body = CouponCalculation),
+            (status = 400, description = "Missing QR code or non-positive amount", body = ErrorResponse),
+            (status = 401, description = "Not authenticated", body = ErrorResponse),
+            (status = 404, description = "Invalid QR code", body = ErrorResponse)
+        )
+    )]
+    pub async fn calculate_coupon() {}
+
+    /// Redeem several coupons on one transaction.
+    ///
+    /// `COUPON_STACKING` decides which combinations are allowed (`none`,
+    /// `one_per_type`, `unlimited`). Discounts apply in a fixed order:
+    /// fixed_amount (largest first), then percentage (highest first, of
+    /// the amount left), then item coupons; ties by user coupon ID. Each
+    /// coupon's `maximum_discount` caps its own discount. All coupons are
+    /// redeemed or none is.
+    #[utoipa::path(
+        post,
+        path = "/coupons/redeem-multiple",
+        tag = "coupons",
+        request_body = RedeemMultipleCouponsRequest,
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Coupons redeemed", body = MultiRedemptionResult),
+            (status = 400, description = "Combination not allowed, coupon unavailable or minimum spend not met", body = ErrorResponse),
+            (status = 401, description = "Not authenticated", body = ErrorResponse),
+            (status = 404, description = "Invalid QR code", body = ErrorResponse)
+        )
+    )]
+    pub async fn redeem_multiple_coupons() {}
+
     /// Validate coupon by QR code
     #[utoipa::path(
         get,
@@ -2009,6 +2730,7 @@ pub mod paths {
         security(("bearer_auth" = [])),
         responses(
             (status = 201, description = "Response submitted", body = SurveyAnswerDto),
+            (status = 400, description = "Answers break question rules; details are keyed by question ID", body = ErrorResponse),
             (status = 401, description = "Not authenticated", body = ErrorResponse),
             (status = 403, description = "Not authorized to respond", body = ErrorResponse),
             (status = 404, description = "Survey not found", body = ErrorResponse)
@@ -2016,6 +2738,61 @@ pub mod paths {
     )]
     pub async fn submit_survey_response() {}
 
+    /// Send single-use survey invitation links (admin only)
+    #[utoipa::path(
+        post,
+        path = "/surveys/{id}/invitations/send",
+        tag = "surveys",
+        params(
+            ("id" = uuid::Uuid, Path, description = "Survey ID")
+        ),
+        request_body = SendSurveyInvitationsRequest,
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 201, description = "Invitations created and emailed", body = SendSurveyInvitationsResponse),
+            (status = 400, description = "No or invalid recipients, bad expiry, or survey not active", body = ErrorResponse),
+            (status = 401, description = "Not authenticated", body = ErrorResponse),
+            (status = 403, description = "Admin access required", body = ErrorResponse),
+            (status = 404, description = "Survey not found", body = ErrorResponse)
+        )
+    )]
+    pub async fn send_survey_invitations() {}
+
+    /// Get the survey behind an invitation link (no login needed)
+    #[utoipa::path(
+        get,
+        path = "/surveys/invite/{token}",
+        tag = "surveys",
+        params(
+            ("token" = String, Path, description = "Invitation link token")
+        ),
+        responses(
+            (status = 200, description = "The invited survey", body = InvitedSurveyResponse),
+            (status = 400, description = "Invitation expired or survey not active", body = ErrorResponse),
+            (status = 404, description = "Unknown invitation", body = ErrorResponse),
+            (status = 409, description = "Invitation already used", body = ErrorResponse)
+        )
+    )]
+    pub async fn get_invited_survey() {}
+
+    /// Submit a response through an invitation link (no login needed)
+    #[utoipa::path(
+        post,
+        path = "/surveys/invite/{token}/submit",
+        tag = "surveys",
+        params(
+            ("token" = String, Path, description = "Invitation link token")
+        ),
+        request_body = SubmitInvitationResponseRequest,
+        responses(
+            (status = 200, description = "Response recorded; the link can't be used again", body = SubmitResponseResult),
+            (status = 400, description = "Invalid answers, invitation expired or survey not active", body = ErrorResponse),
+            (status = 404, description = "Unknown invitation", body = ErrorResponse),
+            (status = 409, description = "Invitation already used", body = ErrorResponse)
+        )
+    )]
+    pub async fn submit_invited_response() {}
+
     /// Get survey responses (admin only)
     #[utoipa::path(
         get,
@@ -2165,6 +2942,10 @@ mod tests {
         let security_schemes = &components.security_schemes;
         assert!(security_schemes.contains_key("bearer_auth"));
 
+        // Loyalty responses are documented inside the shared envelope
+        assert!(components.schemas.contains_key("TransactionsApiResponse"));
+        assert!(components.schemas.contains_key("ResponsePagination"));
+
         // Verify paths exist
         let paths = &spec.paths;
         assert!(paths.paths.contains_key("/health"));
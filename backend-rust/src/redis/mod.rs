@@ -15,6 +15,9 @@ const DEFAULT_SESSION_TTL_SECS: u64 = 86400;
 /// Session key prefix
 const SESSION_PREFIX: &str = "session:";
 
+/// Prefix of the per-user set of session ids, maintained by `set_session`
+const USER_SESSIONS_PREFIX: &str = "user_sessions:";
+
 /// Redis connection manager wrapper with automatic reconnection
 #[derive(Clone)]
 pub struct RedisManager {
@@ -57,6 +60,15 @@ impl RedisManager {
         })
     }
 
+    /// Wrap an existing connection, e.g. the one held in `AppState`, to
+    /// use the helpers below without opening a new connection
+    pub fn from_connection(connection: ConnectionManager) -> Self {
+        Self {
+            connection,
+            redis_url_sanitized: String::from("<shared connection>"),
+        }
+    }
+
     /// Initialize from REDIS_URL environment variable
     ///
     /// Falls back to "redis://localhost:6379" if not set
@@ -79,6 +91,21 @@ impl RedisManager {
         url.to_string()
     }
 
+    /// Flush and close the connection at shutdown
+    ///
+    /// Commands on the shared connection are answered in order, so once a
+    /// final `PING` comes back every write sent before it has reached the
+    /// server. Dropping this handle then releases the connection, once the
+    /// other clones (`AppState`) are gone too.
+    pub async fn close(mut self) -> Result<()> {
+        info!("Flushing Redis connection");
+        redis::cmd("PING")
+            .query_async::<_, String>(self.conn())
+            .await
+            .context("Redis flush failed")?;
+        Ok(())
+    }
+
     /// Get a mutable connection reference
     fn conn(&mut self) -> &mut ConnectionManager {
         &mut self.connection
@@ -126,6 +153,30 @@ impl RedisManager {
         }
     }
 
+    /// Get a string value and delete its key in one step
+    ///
+    /// GET and DEL run in a MULTI block, so when several callers race for
+    /// the same key only one of them gets the value.
+    ///
+    /// # Arguments
+    /// * `key` - The key to take
+    ///
+    /// # Returns
+    /// * `Result<Option<String>>` - The value if found, None if key doesn't exist
+    pub async fn take(&mut self, key: &str) -> Result<Option<String>> {
+        let (result,): (Option<String>,) = redis::pipe()
+            .atomic()
+            .get(key)
+            .del(key)
+            .ignore()
+            .query_async(self.conn())
+            .await
+            .context("Failed to take key from Redis")?;
+
+        debug!("Redis GETDEL {}: {:?}", key, result.is_some());
+        Ok(result)
+    }
+
     /// Set a string value
     ///
     /// # Arguments
@@ -256,6 +307,48 @@ impl RedisManager {
         Ok(result)
     }
 
+    /// Add a member to a set
+    ///
+    /// # Returns
+    /// * `Result<bool>` - True if the member was not in the set yet
+    pub async fn set_add(&mut self, key: &str, member: &str) -> Result<bool> {
+        let added: i32 = self
+            .conn()
+            .sadd(key, member)
+            .await
+            .context("Failed to add set member in Redis")?;
+
+        debug!("Redis SADD {} {}: {}", key, member, added > 0);
+        Ok(added > 0)
+    }
+
+    /// Remove a member from a set
+    ///
+    /// # Returns
+    /// * `Result<bool>` - True if the member was in the set
+    pub async fn set_remove(&mut self, key: &str, member: &str) -> Result<bool> {
+        let removed: i32 = self
+            .conn()
+            .srem(key, member)
+            .await
+            .context("Failed to remove set member in Redis")?;
+
+        debug!("Redis SREM {} {}: {}", key, member, removed > 0);
+        Ok(removed > 0)
+    }
+
+    /// Get all members of a set (empty if the key doesn't exist)
+    pub async fn set_members(&mut self, key: &str) -> Result<Vec<String>> {
+        let members: Vec<String> = self
+            .conn()
+            .smembers(key)
+            .await
+            .context("Failed to get set members from Redis")?;
+
+        debug!("Redis SMEMBERS {}: {} members", key, members.len());
+        Ok(members)
+    }
+
     /// Get the remaining TTL of a key
     ///
     /// # Arguments
@@ -282,13 +375,19 @@ impl RedisManager {
 
     /// Store a user session
     ///
+    /// The session id is also recorded in the user's `user_sessions:` set
+    /// so [`Self::delete_all_user_sessions`] can find it. Ids in that set
+    /// whose session has expired are pruned here.
+    ///
     /// # Arguments
     /// * `session_id` - Unique session identifier
+    /// * `user_id` - The user the session belongs to
     /// * `session_data` - Session data to store (must be serializable)
     /// * `ttl_secs` - Optional TTL in seconds (defaults to 24 hours)
     pub async fn set_session<T: Serialize>(
         &mut self,
         session_id: &str,
+        user_id: uuid::Uuid,
         session_data: &T,
         ttl_secs: Option<u64>,
     ) -> Result<()> {
@@ -296,6 +395,15 @@ impl RedisManager {
         let ttl = ttl_secs.unwrap_or(DEFAULT_SESSION_TTL_SECS);
 
         self.set_json(&key, session_data, Some(ttl)).await?;
+
+        let index_key = format!("{}{}", USER_SESSIONS_PREFIX, user_id);
+        for other in self.set_members(&index_key).await? {
+            if other != session_id && !self.session_exists(&other).await? {
+                self.set_remove(&index_key, &other).await?;
+            }
+        }
+        self.set_add(&index_key, session_id).await?;
+
         info!("Session stored: {} (TTL: {}s)", session_id, ttl);
         Ok(())
     }
@@ -341,6 +449,69 @@ impl RedisManager {
         Ok(deleted)
     }
 
+    /// Delete every session of a user
+    ///
+    /// Only sessions stored through [`Self::set_session`] are found.
+    ///
+    /// # Returns
+    /// * `Result<i32>` - Number of sessions deleted
+    pub async fn delete_all_user_sessions(&mut self, user_id: uuid::Uuid) -> Result<i32> {
+        let index_key = format!("{}{}", USER_SESSIONS_PREFIX, user_id);
+        let keys: Vec<String> = self
+            .set_members(&index_key)
+            .await?
+            .iter()
+            .map(|session_id| format!("{}{}", SESSION_PREFIX, session_id))
+            .collect();
+
+        let key_refs: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let deleted = self.delete_many(&key_refs).await?;
+        self.delete(&index_key).await?;
+
+        info!("Deleted {} sessions of user {}", deleted, user_id);
+        Ok(deleted)
+    }
+
+    /// List the live sessions of a user
+    ///
+    /// Only sessions stored through [`Self::set_session`] are found. Ids in
+    /// the user's index whose session has expired are pruned.
+    ///
+    /// # Returns
+    /// * `Result<Vec<(String, UserSession)>>` - Session ids with their data
+    pub async fn list_user_sessions(
+        &mut self,
+        user_id: uuid::Uuid,
+    ) -> Result<Vec<(String, UserSession)>> {
+        let index_key = format!("{}{}", USER_SESSIONS_PREFIX, user_id);
+        let mut sessions = Vec::new();
+
+        for session_id in self.set_members(&index_key).await? {
+            match self.get_session::<UserSession>(&session_id).await? {
+                Some(session) => sessions.push((session_id, session)),
+                None => {
+                    self.set_remove(&index_key, &session_id).await?;
+                },
+            }
+        }
+
+        Ok(sessions)
+    }
+
+    /// Delete one session of a user and drop it from their index
+    ///
+    /// # Returns
+    /// * `Result<bool>` - True if the session was deleted
+    pub async fn delete_user_session(
+        &mut self,
+        user_id: uuid::Uuid,
+        session_id: &str,
+    ) -> Result<bool> {
+        let index_key = format!("{}{}", USER_SESSIONS_PREFIX, user_id);
+        self.set_remove(&index_key, session_id).await?;
+        self.delete_session(session_id).await
+    }
+
     /// Refresh session TTL (extend expiration)
     ///
     /// # Arguments
@@ -483,6 +654,11 @@ mod tests {
         assert_eq!(SESSION_PREFIX, "session:");
     }
 
+    #[test]
+    fn test_user_sessions_key_prefix() {
+        assert_eq!(USER_SESSIONS_PREFIX, "user_sessions:");
+    }
+
     #[test]
     fn test_default_session_ttl() {
         assert_eq!(DEFAULT_SESSION_TTL_SECS, 86400); // 24 hours
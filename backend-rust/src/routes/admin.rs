@@ -18,12 +18,19 @@ use uuid::Uuid;
 use validator::Validate;
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::auth::{auth_middleware, has_role, AuthUser};
-use crate::models::notification::NotificationType;
+use crate::middleware::auth::{
+    auth_middleware, has_role, require_recent_auth, AuthUser, SessionRevocations,
+};
+use crate::models::notification::{NotificationPriority, NotificationType};
 use crate::models::user::UserRole;
 use crate::models::user_loyalty::UserLoyaltyResponse;
 use crate::models::user_profile::UserProfileResponse;
+use crate::routes::coupons::notify_coupon_assignment;
+use crate::services::account_merge::{merge_user_accounts, AccountMergeSummary};
+use crate::services::storage::StorageService;
+use crate::services::user_deletion::{delete_user_account, UserDeletionSummary};
 use crate::state::AppState;
+use crate::utils::{csv_field, search_pattern};
 
 // ============================================================================
 // Request/Response DTOs
@@ -256,6 +263,41 @@ pub struct UpdateUserResponse {
 pub struct DeleteUserResponse {
     pub success: bool,
     pub message: String,
+    pub summary: UserDeletionSummary,
+}
+
+/// Request to merge a duplicate account into another
+#[derive(Debug, Clone, Deserialize)]
+pub struct MergeUsersRequest {
+    /// Account that is merged away and deleted
+    pub source_user_id: Uuid,
+    /// Account that receives the source's points, nights, coupons and
+    /// bookings
+    pub target_user_id: Uuid,
+    /// Client-chosen identifier of this merge; repeating a request with the
+    /// same reference returns the recorded result. Defaults to one derived
+    /// from the two account ids.
+    pub merge_reference: Option<String>,
+}
+
+impl MergeUsersRequest {
+    /// The merge reference, defaulting to `MERGE-<source>-<target>`
+    fn reference(&self) -> String {
+        self.merge_reference
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("MERGE-{}-{}", self.source_user_id, self.target_user_id))
+    }
+}
+
+/// Merge users response
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeUsersResponse {
+    pub success: bool,
+    pub message: String,
+    pub summary: AccountMergeSummary,
 }
 
 /// Dashboard statistics response
@@ -356,6 +398,18 @@ fn default_active_only() -> bool {
     true
 }
 
+/// Query parameters for `GET /api/admin/members/export`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberExportQuery {
+    /// Only members currently in this tier
+    pub tier_id: Option<Uuid>,
+    /// Only members who joined on or after this date (UTC)
+    pub joined_from: Option<NaiveDate>,
+    /// Only members who joined on or before this date (UTC)
+    pub joined_to: Option<NaiveDate>,
+}
+
 /// Response for broadcast notification
 #[derive(Debug, Clone, Serialize)]
 pub struct BroadcastNotificationResponse {
@@ -407,6 +461,20 @@ fn deny_deactivation_if_super_admin(existing_role: &UserRole) -> AppResult<()> {
 // Handlers
 // ============================================================================
 
+/// `list_users` search: `u.id` of users whose email, first or last name or
+/// membership ID contains the `$1` pattern (case-insensitive).
+///
+/// Matching ids are collected per table so each side can use its trigram
+/// indexes; an OR across the joined tables would scan both.
+const USER_SEARCH_PREDICATE: &str = r#"
+    u.id IN (
+        SELECT id FROM users WHERE email ILIKE $1
+        UNION
+        SELECT user_id FROM user_profiles
+        WHERE first_name ILIKE $1 OR last_name ILIKE $1 OR membership_id ILIKE $1
+    )
+"#;
+
 /// GET /api/admin/users
 /// List all users with pagination and search
 async fn list_users(
@@ -431,26 +499,17 @@ async fn list_users(
     let sort_by = query.sort_by.to_sql_column();
     let sort_order = query.sort_order.to_sql();
 
-    // Build search condition
-    let search_pattern = query
-        .search
-        .as_ref()
-        .map(|s| format!("%{}%", s.to_lowercase()));
+    // Build search condition. Terms shorter than SEARCH_MIN_LENGTH are
+    // rejected; longer ones are answered from the trigram indexes.
+    let search_pattern = search_pattern(query.search.as_deref(), state.config().search.min_length)?;
 
     // Get total count
     let total: i64 = if let Some(ref pattern) = search_pattern {
-        sqlx::query_scalar!(
-            r#"
-            SELECT COUNT(*) as "count!: i64"
-            FROM users u
-            LEFT JOIN user_profiles up ON u.id = up.user_id
-            WHERE LOWER(COALESCE(u.email, '')) LIKE $1
-               OR LOWER(COALESCE(up.first_name, '')) LIKE $1
-               OR LOWER(COALESCE(up.last_name, '')) LIKE $1
-               OR LOWER(COALESCE(up.membership_id, '')) LIKE $1
-            "#,
-            pattern,
-        )
+        sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM users u WHERE {}",
+            USER_SEARCH_PREDICATE
+        ))
+        .bind(pattern)
         .fetch_one(state.db())
         .await?
     } else {
@@ -479,14 +538,11 @@ async fn list_users(
             FROM users u
             LEFT JOIN user_profiles up ON u.id = up.user_id
             LEFT JOIN user_loyalty ul ON u.id = ul.user_id
-            WHERE LOWER(COALESCE(u.email, '')) LIKE $1
-               OR LOWER(COALESCE(up.first_name, '')) LIKE $1
-               OR LOWER(COALESCE(up.last_name, '')) LIKE $1
-               OR LOWER(COALESCE(up.membership_id, '')) LIKE $1
+            WHERE {}
             {}
             LIMIT $2 OFFSET $3
             "#,
-            order_clause
+            USER_SEARCH_PREDICATE, order_clause
         );
 
         sqlx::query_as::<_, UserRow>(&query_str)
@@ -735,6 +791,7 @@ async fn delete_user(
     Path(user_id): Path<Uuid>,
 ) -> AppResult<Json<DeleteUserResponse>> {
     require_super_admin(&user)?;
+    require_recent_auth(&user, state.config().auth.reauth_max_age_secs)?;
 
     // Prevent self-deletion
     if user_id == Uuid::parse_str(&user.id).unwrap_or_default() {
@@ -759,21 +816,126 @@ async fn delete_user(
     // you cannot get around it by deactivating instead of demoting.
     deny_deactivation_if_super_admin(&existing_role)?;
 
-    // Soft delete: deactivate the user instead of hard delete
-    sqlx::query!(
-        r#"
-        UPDATE users
-        SET is_active = false, updated_at = NOW()
-        WHERE id = $1
-        "#,
+    // Anonymize the account and apply the configured cascade to related
+    // records in one transaction (see `services::user_deletion`).
+    let deleted_by = Uuid::parse_str(&user.id).unwrap_or_default();
+    let mut tx = state.db().begin().await?;
+    let summary = delete_user_account(
+        &mut tx,
         user_id,
+        deleted_by,
+        state.config().user_deletion.related_records,
     )
-    .execute(state.db())
     .await?;
 
+    // Access tokens are stateless, so mark every token issued so far as
+    // revoked before committing. If Redis is down the deletion rolls back
+    // rather than leaving live sessions on a deleted account.
+    SessionRevocations(state.redis())
+        .revoke_all(
+            &user_id.to_string(),
+            state.config().auth.access_token_expiry_secs,
+        )
+        .await?;
+
+    tx.commit().await?;
+
+    // Best effort: the profile no longer references the file either way.
+    if let Err(e) = StorageService::new()
+        .delete_user_avatar(&user_id.to_string())
+        .await
+    {
+        tracing::warn!(user_id = %user_id, error = %e, "Failed to remove deleted user's avatar");
+    }
+
     Ok(Json(DeleteUserResponse {
         success: true,
-        message: "User deactivated successfully".to_string(),
+        message: "User deleted and personal data anonymized".to_string(),
+        summary,
+    }))
+}
+
+/// POST /api/admin/users/merge
+/// Merge a duplicate account into another (super_admin only)
+///
+/// Moves the source account's points transactions, coupons, bookings and
+/// nights to the target, recalculates the target's tier and soft-deletes
+/// the source, all in one transaction (see `services::account_merge`).
+/// Gated like `delete_user`, since the source account is deleted.
+async fn merge_users(
+    Extension(user): Extension<AuthUser>,
+    State(state): State<AppState>,
+    Json(request): Json<MergeUsersRequest>,
+) -> AppResult<Json<MergeUsersResponse>> {
+    require_super_admin(&user)?;
+    require_recent_auth(&user, state.config().auth.reauth_max_age_secs)?;
+
+    if !state.config().account_merge.enabled {
+        return Err(AppError::Forbidden(
+            "Account merging is disabled".to_string(),
+        ));
+    }
+
+    let merge_reference = request.reference();
+    if merge_reference.len() > 100 {
+        return Err(AppError::Validation(
+            "merge_reference must be at most 100 characters".to_string(),
+        ));
+    }
+
+    let merged_by = Uuid::parse_str(&user.id).unwrap_or_default();
+    let mut tx = state.db().begin().await?;
+    let outcome = merge_user_accounts(
+        &mut tx,
+        &merge_reference,
+        request.source_user_id,
+        request.target_user_id,
+        merged_by,
+        state.config().user_deletion.related_records,
+    )
+    .await?;
+
+    // As for `delete_user`: the merged-away account's sessions end with
+    // the merge, or the merge rolls back.
+    if outcome.newly_merged {
+        SessionRevocations(state.redis())
+            .revoke_all(
+                &request.source_user_id.to_string(),
+                state.config().auth.access_token_expiry_secs,
+            )
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    if outcome.newly_merged {
+        if let Some(grant) = outcome.upgrade_grant {
+            notify_coupon_assignment(&state, grant.coupon_id, &[request.target_user_id]).await;
+        }
+
+        // Best effort: the deleted profile no longer references the file.
+        if let Err(e) = StorageService::new()
+            .delete_user_avatar(&request.source_user_id.to_string())
+            .await
+        {
+            tracing::warn!(
+                user_id = %request.source_user_id,
+                error = %e,
+                "Failed to remove merged account's avatar"
+            );
+        }
+    }
+
+    let message = if outcome.newly_merged {
+        "Accounts merged"
+    } else {
+        "Accounts were already merged by this request"
+    };
+
+    Ok(Json(MergeUsersResponse {
+        success: true,
+        message: message.to_string(),
+        summary: outcome.summary,
     }))
 }
 
@@ -1129,7 +1291,7 @@ async fn broadcast_notification(
     // Insert notifications for all target users
     // Note: Uses runtime query because $5::notification_type enum cast
     // is not supported by compile-time macros
-    let notification_type_str = format!("{:?}", notification_type).to_lowercase();
+    let priority = notification_type.priority(NotificationPriority::default());
     let data_json = payload.data.unwrap_or(serde_json::json!({}));
 
     let mut count: i64 = 0;
@@ -1137,15 +1299,16 @@ async fn broadcast_notification(
         let notification_id = Uuid::new_v4();
         sqlx::query(
             r#"
-            INSERT INTO notifications (id, user_id, title, message, type, data, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5::notification_type, $6, NOW(), NOW())
+            INSERT INTO notifications (id, user_id, title, message, type, priority, data, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5::notification_type, $6, $7, NOW(), NOW())
             "#,
         )
         .bind(notification_id)
         .bind(user_id)
         .bind(&payload.title)
         .bind(&payload.message)
-        .bind(&notification_type_str)
+        .bind(notification_type.as_str())
+        .bind(priority)
         .bind(&data_json)
         .execute(state.db())
         .await?;
@@ -1188,6 +1351,7 @@ async fn update_user_role(
     Json(payload): Json<UpdateUserRoleRequest>,
 ) -> AppResult<Json<serde_json::Value>> {
     require_admin(&user)?;
+    require_recent_auth(&user, state.config().auth.reauth_max_age_secs)?;
 
     // Fetch the target user's current role to gate transitions that touch
     // super_admin (see HIGH-1 in security-2026-05-13.md). A 404 here matches
@@ -1318,10 +1482,235 @@ async fn get_coupon_status(
     }
 }
 
+/// Window for the per-admin member export limit
+const MEMBER_EXPORT_WINDOW_SECS: i64 = 3600;
+
+/// Count one member export against the admin's hourly limit and return
+/// `(exports in the current window, seconds until the window resets)`.
+///
+/// Fails open like `admin_email::increment_test_email_quota`: a Redis
+/// outage shouldn't block finance's export, and every export is still
+/// audited.
+async fn increment_member_export_count(state: &AppState, admin_id: &str) -> (u32, u64) {
+    let key = format!("member_export:{}", admin_id);
+    let mut conn = state.redis();
+
+    let script = redis::Script::new(
+        r#"
+        local count = redis.call('INCR', KEYS[1])
+        if count == 1 then
+            redis.call('EXPIRE', KEYS[1], ARGV[1])
+        end
+        return {count, redis.call('TTL', KEYS[1])}
+        "#,
+    );
+
+    match script
+        .key(&key)
+        .arg(MEMBER_EXPORT_WINDOW_SECS)
+        .invoke_async::<_, (i64, i64)>(&mut conn)
+        .await
+    {
+        Ok((count, ttl)) => (count.max(0) as u32, ttl.max(1) as u64),
+        Err(e) => {
+            tracing::warn!(
+                admin_id = %admin_id,
+                error = %e,
+                "Redis member export INCR failed — failing open"
+            );
+            (0, 0)
+        },
+    }
+}
+
+/// GET /api/admin/members/export
+/// Stream every member as CSV for reporting
+///
+/// Includes profile, tier, points, nights and join date, optionally
+/// filtered by tier and join date. Members are read in keyset-paginated
+/// batches of `MEMBER_EXPORT_BATCH_SIZE` and written out as they arrive,
+/// so memory use doesn't grow with the member count. This is a bulk PII
+/// export: each admin may start `MEMBER_EXPORT_MAX_PER_HOUR` exports an
+/// hour, and every export is recorded in `user_audit_log`.
+async fn export_members(
+    Extension(user): Extension<AuthUser>,
+    State(state): State<AppState>,
+    Query(query): Query<MemberExportQuery>,
+) -> AppResult<axum::response::Response> {
+    require_admin(&user)?;
+
+    let config = state.config().member_export.clone();
+    if config.max_per_hour == 0 {
+        return Err(AppError::Forbidden("Member export is disabled".to_string()));
+    }
+
+    if let (Some(from), Some(to)) = (query.joined_from, query.joined_to) {
+        if from > to {
+            return Err(AppError::BadRequest(
+                "joinedFrom must not be after joinedTo".to_string(),
+            ));
+        }
+    }
+
+    let admin_id = Uuid::parse_str(&user.id)
+        .map_err(|_| AppError::BadRequest("Invalid admin user ID".to_string()))?;
+
+    let (count, retry_after) = increment_member_export_count(&state, &user.id).await;
+    if count > config.max_per_hour {
+        tracing::warn!(
+            admin_id = %admin_id,
+            count,
+            limit = config.max_per_hour,
+            "Member export rate limit exceeded"
+        );
+        return Err(AppError::TooManyRequests(retry_after));
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_audit_log (user_id, action, details)
+        VALUES ($1, 'members_exported', $2)
+        "#,
+    )
+    .bind(admin_id)
+    .bind(serde_json::json!({
+        "tierId": query.tier_id,
+        "joinedFrom": query.joined_from,
+        "joinedTo": query.joined_to,
+    }))
+    .execute(state.db())
+    .await?;
+
+    let pool = state.db().clone();
+    let batch_size = i64::from(config.batch_size);
+    let (sender, receiver) = tokio::sync::mpsc::channel::<Result<String, std::io::Error>>(32);
+
+    tokio::spawn(async move {
+        if sender
+            .send(Ok(MemberExportRow::CSV_HEADER.to_string()))
+            .await
+            .is_err()
+        {
+            return;
+        }
+
+        let mut last_id: Option<Uuid> = None;
+        let mut exported = 0usize;
+
+        loop {
+            let batch = sqlx::query_as::<_, MemberExportRow>(
+                r#"
+                SELECT u.id, u.email, up.membership_id, up.first_name, up.last_name,
+                       up.phone, t.name AS tier_name,
+                       COALESCE(ul.current_points, 0) AS current_points,
+                       COALESCE(ul.total_nights, 0) AS total_nights,
+                       u.created_at
+                FROM users u
+                LEFT JOIN user_profiles up ON up.user_id = u.id
+                LEFT JOIN user_loyalty ul ON ul.user_id = u.id
+                LEFT JOIN tiers t ON t.id = ul.tier_id
+                WHERE u.role = 'customer'
+                  AND u.deleted_at IS NULL
+                  AND ($1::uuid IS NULL OR u.id > $1)
+                  AND ($2::uuid IS NULL OR ul.tier_id = $2)
+                  AND ($3::date IS NULL OR u.created_at >= $3::date::timestamptz)
+                  AND ($4::date IS NULL OR u.created_at < ($4::date + 1)::timestamptz)
+                ORDER BY u.id
+                LIMIT $5
+                "#,
+            )
+            .bind(last_id)
+            .bind(query.tier_id)
+            .bind(query.joined_from)
+            .bind(query.joined_to)
+            .bind(batch_size)
+            .fetch_all(&pool)
+            .await;
+
+            let batch = match batch {
+                Ok(batch) => batch,
+                Err(e) => {
+                    // The client sees a truncated body.
+                    tracing::error!(admin_id = %admin_id, error = %e, "Member export failed");
+                    let _ = sender.send(Err(std::io::Error::other(e.to_string()))).await;
+                    return;
+                },
+            };
+
+            let Some(last) = batch.last() else {
+                break;
+            };
+            last_id = Some(last.id);
+            let done = (batch.len() as i64) < batch_size;
+
+            let chunk: String = batch.iter().map(MemberExportRow::to_csv_line).collect();
+            exported += batch.len();
+            if sender.send(Ok(chunk)).await.is_err() {
+                tracing::info!(admin_id = %admin_id, exported, "Member export aborted by client");
+                return;
+            }
+            if done {
+                break;
+            }
+        }
+
+        tracing::info!(admin_id = %admin_id, exported, "Member export completed");
+    });
+
+    let filename = format!("members-{}.csv", Utc::now().format("%Y-%m-%d"));
+    axum::response::Response::builder()
+        .status(axum::http::StatusCode::OK)
+        .header(axum::http::header::CONTENT_TYPE, "text/csv; charset=utf-8")
+        .header(
+            axum::http::header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )
+        .body(axum::body::Body::from_stream(
+            tokio_stream::wrappers::ReceiverStream::new(receiver),
+        ))
+        .map_err(|e| AppError::Internal(format!("Failed to build export response: {}", e)))
+}
+
 // ============================================================================
 // Row Types for Complex Queries
 // ============================================================================
 
+/// One line of the member export
+#[derive(Debug, sqlx::FromRow)]
+struct MemberExportRow {
+    id: Uuid,
+    email: Option<String>,
+    membership_id: Option<String>,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    phone: Option<String>,
+    tier_name: Option<String>,
+    current_points: i32,
+    total_nights: i32,
+    created_at: Option<DateTime<Utc>>,
+}
+
+impl MemberExportRow {
+    const CSV_HEADER: &'static str = "user_id,email,membership_id,first_name,last_name,phone,tier,current_points,total_nights,joined_at\n";
+
+    fn to_csv_line(&self) -> String {
+        let text = |value: &Option<String>| csv_field(value.as_deref().unwrap_or(""));
+        format!(
+            "{},{},{},{},{},{},{},{},{},{}\n",
+            self.id,
+            text(&self.email),
+            text(&self.membership_id),
+            text(&self.first_name),
+            text(&self.last_name),
+            text(&self.phone),
+            text(&self.tier_name),
+            self.current_points,
+            self.total_nights,
+            self.created_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        )
+    }
+}
+
 /// Row type for joined user queries (used by both dynamic and static queries)
 #[derive(Debug, sqlx::FromRow)]
 struct UserRow {
@@ -1429,6 +1818,7 @@ impl From<UserRow> for AdminUserResponse {
 /// - `GET /admin/stats` - Dashboard statistics
 /// - `GET /admin/analytics` - Analytics data
 /// - `POST /admin/notifications/broadcast` - Send notification to all users
+/// - `GET /admin/members/export` - Stream all members as CSV
 ///
 /// # Example
 ///
@@ -1444,6 +1834,7 @@ pub fn router() -> Router<AppState> {
     Router::new()
         // User management
         .route("/users", get(list_users))
+        .route("/users/merge", post(merge_users))
         .route("/users/:id", get(get_user))
         .route("/users/:id", put(update_user))
         .route("/users/:id", delete(delete_user))
@@ -1455,6 +1846,8 @@ pub fn router() -> Router<AppState> {
         .route("/analytics", get(get_analytics))
         // Notifications
         .route("/notifications/broadcast", post(broadcast_notification))
+        // Reporting
+        .route("/members/export", get(export_members))
         // Coupon settings
         .route("/new-member-coupon-settings", get(get_new_member_coupon_settings))
         .route("/new-member-coupon-settings", put(update_new_member_coupon_settings))
@@ -1474,6 +1867,8 @@ pub fn router() -> Router<AppState> {
         // viewer sidebar. Conceptually unrelated to room inventory, so
         // it lives in its own module.
         .merge(crate::routes::admin_slips::router())
+        // Maintenance switches (read-only mode).
+        .merge(crate::routes::admin_maintenance::router())
         // Apply auth middleware to all routes
         .layer(middleware::from_fn(auth_middleware))
 }
@@ -1490,6 +1885,24 @@ pub fn routes() -> Router<AppState> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_merge_users_request_reference() {
+        let source = Uuid::new_v4();
+        let target = Uuid::new_v4();
+        let mut request = MergeUsersRequest {
+            source_user_id: source,
+            target_user_id: target,
+            merge_reference: None,
+        };
+        assert_eq!(request.reference(), format!("MERGE-{}-{}", source, target));
+
+        request.merge_reference = Some("  ".to_string());
+        assert_eq!(request.reference(), format!("MERGE-{}-{}", source, target));
+
+        request.merge_reference = Some(" ticket-42 ".to_string());
+        assert_eq!(request.reference(), "ticket-42");
+    }
+
     #[test]
     fn test_list_users_query_defaults() {
         let query = ListUsersQuery {
@@ -1634,12 +2047,16 @@ mod tests {
             id: "123".to_string(),
             email: Some("admin@example.com".to_string()),
             role: "admin".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
 
         let customer_user = AuthUser {
             id: "456".to_string(),
             email: Some("customer@example.com".to_string()),
             role: "customer".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
 
         assert!(require_admin(&admin_user).is_ok());
@@ -1652,12 +2069,16 @@ mod tests {
             id: "123".to_string(),
             email: Some("superadmin@example.com".to_string()),
             role: "super_admin".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
 
         let admin_user = AuthUser {
             id: "456".to_string(),
             email: Some("admin@example.com".to_string()),
             role: "admin".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
 
         assert!(require_super_admin(&super_admin).is_ok());
@@ -1684,6 +2105,8 @@ mod tests {
             id: "admin-id".to_string(),
             email: Some("admin@example.com".to_string()),
             role: "admin".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
         assert!(require_super_admin(&admin).is_err());
 
@@ -1693,6 +2116,8 @@ mod tests {
             id: "super-id".to_string(),
             email: Some("super@example.com".to_string()),
             role: "super_admin".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
         assert!(require_super_admin(&super_admin).is_ok());
     }
@@ -1718,4 +2143,34 @@ mod tests {
         assert!(deny_deactivation_if_super_admin(&UserRole::Admin).is_ok());
         assert!(deny_deactivation_if_super_admin(&UserRole::Customer).is_ok());
     }
+
+    #[test]
+    fn test_member_export_row_csv_line() {
+        let id = Uuid::new_v4();
+        let row = MemberExportRow {
+            id,
+            email: Some("guest@example.com".to_string()),
+            membership_id: Some("26900001".to_string()),
+            first_name: Some("=cmd".to_string()),
+            last_name: Some("Smith, Jr.".to_string()),
+            phone: None,
+            tier_name: Some("Gold".to_string()),
+            current_points: 1200,
+            total_nights: 7,
+            created_at: None,
+        };
+
+        let line = row.to_csv_line();
+        assert_eq!(
+            line,
+            format!(
+                "{},guest@example.com,26900001,'=cmd,\"Smith, Jr.\",,Gold,1200,7,\n",
+                id
+            )
+        );
+        assert_eq!(
+            line.matches(',').count() - 1,
+            MemberExportRow::CSV_HEADER.matches(',').count()
+        );
+    }
 }
@@ -4,6 +4,7 @@
 //!
 //! - `GET    /api/admin/bookings`               — paginated list + status counts
 //! - `GET    /api/admin/bookings/room-types`    — dropdown source for the edit modal
+//! - `GET    /api/admin/bookings/sources`       — counts/revenue by source + channel
 //! - `GET    /api/admin/bookings/:id`           — full booking detail + slip + audit
 //! - `PUT    /api/admin/bookings/:id`           — partial update (whitelisted fields)
 //! - `POST   /api/admin/bookings/:id/discount`  — apply or update a discount
@@ -57,6 +58,7 @@ use validator::Validate;
 
 use crate::error::{AppError, AppResult};
 use crate::middleware::auth::{has_role, AuthUser};
+use crate::services::booking::GuestCount;
 use crate::state::AppState;
 
 // ============================================================================
@@ -165,6 +167,29 @@ pub struct ListBookingsResponse {
     pub status_counts: StatusCounts,
 }
 
+/// Query parameters for `GET /api/admin/bookings/sources`. Both bounds
+/// apply to `check_in_date` and are inclusive; omit either for an open
+/// range.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceReportQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+/// One `(source, channel)` bucket of the source report. Cancelled and
+/// no-show bookings are counted but excluded from `nights` / `revenue`.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceReportRow {
+    pub source: String,
+    pub channel: String,
+    pub bookings: i64,
+    pub cancelled: i64,
+    pub nights: i64,
+    pub revenue: Decimal,
+}
+
 // ============================================================================
 // DTOs — Item / Detail
 // ============================================================================
@@ -661,6 +686,51 @@ async fn list_room_types_for_modal(
     ))
 }
 
+/// `GET /api/admin/bookings/sources`
+///
+/// Booking volume grouped by `(source, channel)` for the reporting view,
+/// largest bucket first.
+async fn booking_source_report(
+    Extension(user): Extension<AuthUser>,
+    State(state): State<AppState>,
+    Query(query): Query<SourceReportQuery>,
+) -> AppResult<Json<Vec<SourceReportRow>>> {
+    require_admin(&user)?;
+
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return Err(AppError::Validation(
+                "from must be on or before to".to_string(),
+            ));
+        }
+    }
+
+    let rows: Vec<SourceReportRow> = sqlx::query_as(
+        r#"
+        SELECT
+            b.source,
+            b.channel,
+            COUNT(*) AS bookings,
+            COUNT(*) FILTER (WHERE b.status IN ('cancelled', 'no_show')) AS cancelled,
+            COALESCE(SUM(b.check_out_date - b.check_in_date)
+                FILTER (WHERE b.status NOT IN ('cancelled', 'no_show')), 0)::bigint AS nights,
+            COALESCE(SUM(b.total_price)
+                FILTER (WHERE b.status NOT IN ('cancelled', 'no_show')), 0) AS revenue
+          FROM bookings b
+         WHERE ($1::date IS NULL OR b.check_in_date >= $1)
+           AND ($2::date IS NULL OR b.check_in_date <= $2)
+         GROUP BY b.source, b.channel
+         ORDER BY bookings DESC, b.source, b.channel
+        "#,
+    )
+    .bind(query.from)
+    .bind(query.to)
+    .fetch_all(state.db())
+    .await?;
+
+    Ok(Json(rows))
+}
+
 /// `GET /api/admin/bookings/:id`
 ///
 /// Full detail = list-item shape + the booking's audit history (newest
@@ -906,6 +976,28 @@ async fn update_booking(
         ));
     }
 
+    // The admin form edits the total only; re-split it into adults and
+    // children and check it against the (possibly new) room type.
+    let guests = if payload.number_of_guests.is_some() || payload.room_type_id.is_some() {
+        let (children, room_type_name, max_guests): (i32, String, i32) = sqlx::query_as(
+            r#"
+            SELECT b.children, rt.name, rt.max_guests
+              FROM bookings b, room_types rt
+             WHERE b.id = $1 AND rt.id = $2
+            "#,
+        )
+        .bind(booking_id)
+        .bind(new_room_type)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let guests = GuestCount::from_total(new_guests, children)?;
+        guests.ensure_fits(&room_type_name, max_guests)?;
+        Some(guests)
+    } else {
+        None
+    };
+
     // Apply the update. We touch every editable column with COALESCE so a
     // single static query handles partial payloads.
     sqlx::query!(
@@ -937,6 +1029,15 @@ async fn update_booking(
     .execute(&mut *tx)
     .await?;
 
+    if let Some(guests) = guests {
+        sqlx::query("UPDATE bookings SET adults = $2, children = $3 WHERE id = $1")
+            .bind(booking_id)
+            .bind(guests.adults)
+            .bind(guests.children)
+            .execute(&mut *tx)
+            .await?;
+    }
+
     // Build the before/after JSON snapshots — only fields whose value
     // actually changed are included, so an audit row never lies about
     // touching something it didn't.
@@ -1332,6 +1433,7 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .route("/bookings", get(list_bookings))
         .route("/bookings/room-types", get(list_room_types_for_modal))
+        .route("/bookings/sources", get(booking_source_report))
         .route("/bookings/:id", get(get_booking_detail))
         .route("/bookings/:id", put(update_booking))
         .route("/bookings/:id/discount", post(apply_discount))
@@ -10,6 +10,8 @@
 //!   state. Returns `EmailStatus`.
 //! - `POST /api/admin/email/test` — sends a real test email to the
 //!   authenticated admin's own address. Returns `TestResult`.
+//! - `GET  /api/admin/email/captured` — emails stored by test-mode capture
+//!   (`EMAIL_CAPTURE_MODE`), newest first. Returns `CapturedEmailsResponse`.
 //!
 //! ## What we honestly report vs. what we don't
 //!
@@ -33,11 +35,11 @@
 //! ## sqlx note
 //!
 //! No DB writes in these handlers — they call the existing `EmailService`
-//! trait. No new compile-time queries; nothing for `regen-sqlx-cache.sh` to
-//! cache here.
+//! trait. The captured-email listing is a runtime `sqlx::query_as` read, so
+//! there is nothing for `regen-sqlx-cache.sh` to cache here.
 
 use axum::{
-    extract::{Extension, State},
+    extract::{Extension, Query, State},
     http::StatusCode,
     response::IntoResponse,
     routing::{get, post},
@@ -63,6 +65,10 @@ use crate::utils::hash_email;
 /// UUID + UTC date, so the count resets at 00:00 UTC.
 const TEST_EMAIL_DAILY_QUOTA: u32 = 10;
 
+/// Default and maximum page size for `GET /api/admin/email/captured`.
+const CAPTURED_EMAILS_DEFAULT_LIMIT: i64 = 50;
+const CAPTURED_EMAILS_MAX_LIMIT: i64 = 200;
+
 // ============================================================================
 // DTOs
 // ============================================================================
@@ -151,6 +157,40 @@ pub struct TestEmailResponse {
     pub recipient: String,
 }
 
+/// Query parameters for `GET /api/admin/email/captured`.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CapturedEmailsQuery {
+    /// Page size; defaults to 50, capped at 200.
+    pub limit: Option<i64>,
+    /// Only return messages sent to this address (case-insensitive).
+    pub recipient: Option<String>,
+}
+
+/// One row of `captured_emails`.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedEmail {
+    pub id: Uuid,
+    /// `email` today; `sms` is reserved for when an SMS sender exists.
+    pub channel: String,
+    pub recipient: String,
+    pub subject: Option<String>,
+    pub html_body: Option<String>,
+    pub text_body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Response for `GET /api/admin/email/captured`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedEmailsResponse {
+    /// Whether capture mode is active right now. Rows can exist with this
+    /// `false` if capture was switched off after they were stored.
+    pub capture_mode: bool,
+    pub emails: Vec<CapturedEmail>,
+}
+
 // ============================================================================
 // Helpers
 // ============================================================================
@@ -472,6 +512,50 @@ async fn send_test_email(
     }
 }
 
+/// `GET /api/admin/email/captured`
+///
+/// Lists emails stored by [`CapturingEmailService`] instead of being sent,
+/// so staging testers can read password-reset links and verification codes
+/// without a real inbox.
+///
+/// [`CapturingEmailService`]: crate::services::email::CapturingEmailService
+async fn list_captured_emails(
+    Extension(user): Extension<AuthUser>,
+    State(state): State<AppState>,
+    Query(query): Query<CapturedEmailsQuery>,
+) -> AppResult<Json<CapturedEmailsResponse>> {
+    require_admin(&user)?;
+
+    let limit = query
+        .limit
+        .unwrap_or(CAPTURED_EMAILS_DEFAULT_LIMIT)
+        .clamp(1, CAPTURED_EMAILS_MAX_LIMIT);
+    let recipient = query
+        .recipient
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+
+    let emails: Vec<CapturedEmail> = sqlx::query_as(
+        r#"
+        SELECT id, channel, recipient, subject, html_body, text_body, created_at
+        FROM captured_emails
+        WHERE ($1::text IS NULL OR LOWER(recipient) = LOWER($1))
+        ORDER BY created_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(recipient)
+    .bind(limit)
+    .fetch_all(state.db())
+    .await?;
+
+    Ok(Json(CapturedEmailsResponse {
+        capture_mode: state.config().email.capture_mode,
+        emails,
+    }))
+}
+
 // ============================================================================
 // Router
 // ============================================================================
@@ -485,6 +569,7 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .route("/email/status", get(get_email_status))
         .route("/email/test", post(send_test_email))
+        .route("/email/captured", get(list_captured_emails))
 }
 
 #[cfg(test)]
@@ -497,6 +582,8 @@ mod tests {
             id: Uuid::new_v4().to_string(),
             email: Some("admin@test".to_string()),
             role: "admin".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
         assert!(require_admin(&admin).is_ok());
     }
@@ -507,10 +594,19 @@ mod tests {
             id: Uuid::new_v4().to_string(),
             email: Some("user@test".to_string()),
             role: "customer".to_string(),
+            last_auth_at: None,
+            session_id: None,
         };
         assert!(require_admin(&customer).is_err());
     }
 
+    #[test]
+    fn captured_emails_query_defaults_when_empty() {
+        let query: CapturedEmailsQuery = serde_json::from_str("{}").unwrap();
+        assert!(query.limit.is_none());
+        assert!(query.recipient.is_none());
+    }
+
     #[test]
     fn send_test_email_request_default_is_empty() {
         // HIGH-4: the `to` field has been removed from the request DTO.
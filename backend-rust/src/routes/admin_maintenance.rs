@@ -0,0 +1,118 @@
+//! Admin maintenance routes
+//!
+//! ## Endpoints
+//!
+//! - `GET /api/admin/read-only` — current read-only mode (admin).
+//!   Returns `ReadOnlyState`.
+//! - `PUT /api/admin/read-only` — turn read-only mode on or off
+//!   (super admin). Body: `{ "enabled": bool, "reason"?: string }`.
+//!
+//! The PUT path is exempt from the read-only middleware itself
+//! (`middleware::read_only::READ_ONLY_TOGGLE_PATH`), so the mode can always
+//! be turned off again.
+
+use axum::{
+    extract::{Extension, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::{has_role, AuthUser};
+use crate::middleware::read_only::{get_read_only_state, set_read_only_state, ReadOnlyState};
+use crate::state::AppState;
+
+/// Longest reason accepted; it is echoed in every 503 while the mode is on
+const MAX_REASON_LEN: usize = 200;
+
+/// Request body for `PUT /api/admin/read-only`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetReadOnlyRequest {
+    pub enabled: bool,
+    pub reason: Option<String>,
+}
+
+/// GET /api/admin/read-only
+async fn get_read_only(
+    Extension(user): Extension<AuthUser>,
+    State(state): State<AppState>,
+) -> AppResult<Json<ReadOnlyState>> {
+    if !has_role(&user, "admin") {
+        return Err(AppError::Forbidden("Admin access required".to_string()));
+    }
+
+    let mut redis = state.redis();
+    let current = get_read_only_state(&mut redis).await?;
+
+    Ok(Json(current))
+}
+
+/// PUT /api/admin/read-only
+async fn set_read_only(
+    Extension(user): Extension<AuthUser>,
+    State(state): State<AppState>,
+    Json(request): Json<SetReadOnlyRequest>,
+) -> AppResult<Json<ReadOnlyState>> {
+    if !has_role(&user, "super_admin") {
+        return Err(AppError::Forbidden(
+            "Super admin access required".to_string(),
+        ));
+    }
+
+    let reason = request
+        .reason
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+    if reason.as_ref().is_some_and(|r| r.len() > MAX_REASON_LEN) {
+        return Err(AppError::Validation(format!(
+            "Reason must be at most {} characters",
+            MAX_REASON_LEN
+        )));
+    }
+
+    let new_state = if request.enabled {
+        ReadOnlyState {
+            enabled: true,
+            reason,
+            enabled_by: user.email.clone(),
+            enabled_at: Some(Utc::now()),
+        }
+    } else {
+        ReadOnlyState::default()
+    };
+
+    let mut redis = state.redis();
+    set_read_only_state(&mut redis, &new_state).await?;
+
+    tracing::warn!(
+        admin_id = %user.id,
+        enabled = new_state.enabled,
+        reason = ?new_state.reason,
+        "Read-only mode changed"
+    );
+
+    Ok(Json(new_state))
+}
+
+/// Build the admin maintenance sub-router.
+///
+/// `.merge`d into the parent admin router so its `auth_middleware` layer
+/// covers these routes too. Mounted at `/api/admin/...`.
+pub fn router() -> Router<AppState> {
+    Router::new().route("/read-only", get(get_read_only).put(set_read_only))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_read_only_request_reason_is_optional() {
+        let request: SetReadOnlyRequest = serde_json::from_str(r#"{"enabled":false}"#).unwrap();
+        assert!(!request.enabled);
+        assert!(request.reason.is_none());
+    }
+}
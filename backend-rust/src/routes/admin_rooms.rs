@@ -39,7 +39,7 @@ use uuid::Uuid;
 use validator::Validate;
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::auth::{has_role, AuthUser};
+use crate::middleware::auth::{has_role, require_recent_auth, AuthUser};
 use crate::state::AppState;
 
 // ============================================================================
@@ -506,6 +506,7 @@ async fn delete_room_type(
     Path(id): Path<Uuid>,
 ) -> AppResult<axum::response::Response> {
     require_admin(&user)?;
+    require_recent_auth(&user, state.config().auth.reauth_max_age_secs)?;
 
     // Check for attached rooms first so we can return a structured 409 body.
     // Run inside a transaction so the count + delete are consistent if
@@ -762,6 +763,7 @@ async fn delete_room(
     Path(id): Path<Uuid>,
 ) -> AppResult<axum::response::Response> {
     require_admin(&user)?;
+    require_recent_auth(&user, state.config().auth.reauth_max_age_secs)?;
 
     // Run inside a transaction so the count + delete stay consistent
     // if another writer is racing us. Same pattern as `delete_room_type`.
@@ -13,6 +13,7 @@
 //!
 //! - `POST /api/admin/bookings/slips/:slip_id/verify`       — admin verify
 //! - `POST /api/admin/bookings/slips/:slip_id/needs-action` — admin reject
+//! - `POST /api/admin/bookings/slips/:slip_id/reverify`     — re-run SlipOK
 //!
 //! Note the mount path: nested under `/bookings/slips/...` to match the
 //! frontend's `verifySlipByIdMutation` URL in `SlipViewerSidebar.tsx:130-158`
@@ -50,6 +51,11 @@ use validator::Validate;
 
 use crate::error::{AppError, AppResult};
 use crate::middleware::auth::{has_role, AuthUser};
+use crate::models::booking::PaymentStatus;
+use crate::routes::slips::slip_file_path;
+use crate::services::booking::verify_booking_slip;
+use crate::services::loyalty::release_pending_points_for_booking;
+use crate::services::slipok::SlipOKService;
 use crate::state::AppState;
 
 // ============================================================================
@@ -104,6 +110,18 @@ pub struct AdminSlipResponse {
     pub slipok_verified_at: Option<DateTime<Utc>>,
 }
 
+/// Response for the force-reverify action: the fresh SlipOK outcome and
+/// where it left the booking.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReverifySlipResponse {
+    pub slip_id: Uuid,
+    pub booking_id: Uuid,
+    /// `verified`, `failed` or `quota_exceeded`
+    pub slipok_status: Option<String>,
+    pub payment_status: PaymentStatus,
+}
+
 // ============================================================================
 // Helpers
 // ============================================================================
@@ -140,6 +158,9 @@ fn admin_user_id(user: &AuthUser) -> AppResult<Uuid> {
 /// `docs/audits/correctness-2026-05-13.md` (MED #3) for the audit
 /// write-up.
 ///
+/// Points held as pending for the slip's booking are released in the
+/// same transaction.
+///
 /// Returns 200 with the updated slip row; 404 if the slip doesn't exist.
 async fn verify_slip(
     Extension(user): Extension<AuthUser>,
@@ -225,6 +246,9 @@ async fn verify_slip(
     )
     .await?;
 
+    // A verified slip makes any points held for the stay available
+    release_pending_points_for_booking(&mut tx, row.booking_id).await?;
+
     tx.commit().await?;
 
     tracing::info!(
@@ -368,6 +392,92 @@ async fn mark_slip_needs_action(
     }))
 }
 
+/// `POST /api/admin/bookings/slips/:slip_id/reverify`
+///
+/// Runs SlipOK verification for the slip again, bypassing the cached
+/// result (see `services::slipok`), and applies the outcome to the booking
+/// exactly as the automatic verification on upload does. Useful after a
+/// quota error, or when an admin doubts a cached verdict. Unlike the
+/// upload path this waits for SlipOK, so the admin sees the outcome.
+///
+/// Returns 404 if the slip or its image file is gone, and 503 when SlipOK
+/// isn't configured.
+async fn reverify_slip(
+    Extension(user): Extension<AuthUser>,
+    State(state): State<AppState>,
+    Path(slip_id): Path<Uuid>,
+) -> AppResult<Json<ReverifySlipResponse>> {
+    require_admin(&user)?;
+    let admin_id = admin_user_id(&user)?;
+
+    let slipok = SlipOKService::from_env()
+        .with_result_cache(state.redis(), state.config().slipok.result_cache_ttl_secs);
+    if !slipok.is_configured() {
+        return Err(AppError::ExternalServiceUnavailable("SlipOK".to_string()));
+    }
+
+    let (booking_id, slip_url): (Uuid, String) =
+        sqlx::query_as("SELECT booking_id, slip_url FROM booking_slips WHERE id = $1")
+            .bind(slip_id)
+            .fetch_optional(state.db())
+            .await?
+            .ok_or_else(|| AppError::NotFound("Slip".to_string()))?;
+
+    let path =
+        slip_file_path(&slip_url).ok_or_else(|| AppError::NotFound("Slip image".to_string()))?;
+    let image = tokio::fs::read(&path).await.map_err(|e| {
+        tracing::warn!(slip_id = %slip_id, error = %e, "Could not read slip file for reverify");
+        AppError::NotFound("Slip image".to_string())
+    })?;
+
+    let payment_status = verify_booking_slip(
+        state.db(),
+        &slipok,
+        slip_id,
+        booking_id,
+        bytes::Bytes::from(image),
+        &state.config().webhooks,
+        true,
+    )
+    .await?;
+
+    let slipok_status: Option<String> =
+        sqlx::query_scalar("SELECT slipok_status FROM booking_slips WHERE id = $1")
+            .bind(slip_id)
+            .fetch_one(state.db())
+            .await?;
+
+    insert_slip_audit_row(
+        state.db(),
+        booking_id,
+        admin_id,
+        "slip_reverified",
+        None,
+        Some(json!({
+            "slipId": slip_id,
+            "slipokStatus": slipok_status,
+            "paymentStatus": payment_status.as_str(),
+        })),
+        None,
+    )
+    .await?;
+
+    tracing::info!(
+        slip_id = %slip_id,
+        booking_id = %booking_id,
+        admin_id = %admin_id,
+        slipok_status = ?slipok_status,
+        "Admin re-ran SlipOK verification"
+    );
+
+    Ok(Json(ReverifySlipResponse {
+        slip_id,
+        booking_id,
+        slipok_status,
+        payment_status,
+    }))
+}
+
 /// Insert a single `booking_audit_log` row from inside the caller's
 /// transaction. Mirrors `routes::admin_bookings::insert_audit_row`
 /// (kept local rather than `pub`-ing the original to avoid coupling
@@ -419,6 +529,7 @@ pub fn router() -> Router<AppState> {
             "/bookings/slips/:slip_id/needs-action",
             post(mark_slip_needs_action),
         )
+        .route("/bookings/slips/:slip_id/reverify", post(reverify_slip))
 }
 
 #[cfg(test)]
@@ -15,6 +15,10 @@
 //! - `GET /user-engagement` - Get user engagement metrics
 //! - `GET /dashboard` - Get analytics dashboard summary
 //! - `POST /update-daily` - Update daily analytics (typically called by cron)
+//!
+//! With `ANALYTICS_PSEUDONYMIZE_USER_IDS`, events are stored under a salted
+//! pseudonym of the user ID (see `services::analytics`); the `userId`
+//! filter takes the real ID and the reported user IDs are pseudonyms.
 
 use axum::{
     extract::{Extension, Query, State},
@@ -31,6 +35,7 @@ use uuid::Uuid;
 
 use crate::error::AppError;
 use crate::middleware::auth::{auth_middleware, has_role, AuthUser};
+use crate::services::analytics::analytics_user_id;
 use crate::state::AppState;
 
 // ============================================================================
@@ -271,6 +276,8 @@ async fn track_coupon_usage(
         .transpose()
         .map_err(|_| AppError::BadRequest("Invalid user coupon ID format".to_string()))?;
 
+    let user_id = analytics_user_id(&state.config().analytics, user_id);
+
     // Insert analytics record
     sqlx::query(
         r#"
@@ -320,6 +327,8 @@ async fn track_profile_change(
         )));
     }
 
+    let user_id = analytics_user_id(&state.config().analytics, user_id);
+
     // Insert analytics record
     sqlx::query(
         r#"
@@ -399,7 +408,8 @@ async fn get_coupon_usage_analytics(
         params
             .user_id
             .as_ref()
-            .and_then(|id| Uuid::parse_str(id).ok()),
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .map(|id| analytics_user_id(&state.config().analytics, id)),
     )
     .fetch_one(state.db())
     .await
@@ -517,7 +527,8 @@ async fn get_profile_change_analytics(
         params
             .user_id
             .as_ref()
-            .and_then(|id| Uuid::parse_str(id).ok()),
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .map(|id| analytics_user_id(&state.config().analytics, id)),
     )
     .fetch_one(state.db())
     .await
@@ -1,12 +1,20 @@
 //! Authentication routes
 //!
 //! Provides endpoints for user authentication including login, registration,
-//! logout, token refresh, and password reset.
+//! logout, token refresh, password reset, changing an expired password,
+//! resending the verification email, passwordless sign-in with an emailed
+//! magic link, and issuing the CSRF token for cookie-authenticated requests.
+
+use std::collections::HashMap;
 
 use axum::{
-    extract::{Extension, State},
+    extract::{Extension, Path, Query, State},
+    http::{
+        header::{AUTHORIZATION, USER_AGENT},
+        HeaderMap,
+    },
     middleware,
-    routing::{get, post},
+    routing::{delete, get, post},
     Json, Router,
 };
 use axum_extra::extract::cookie::CookieJar;
@@ -15,17 +23,31 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::config::is_admin_role;
 use crate::error::AppError;
 use crate::middleware::auth::{
     auth_middleware, build_clear_refresh_cookie, build_refresh_cookie, AuthUser,
-    REFRESH_COOKIE_NAME,
+    SessionRevocations, REFRESH_COOKIE_NAME,
+};
+use crate::middleware::csrf::{
+    build_csrf_cookie, generate_csrf_token, is_well_formed_csrf_token, CSRF_COOKIE_NAME,
 };
-use crate::services::email::{EmailService, EmailServiceImpl};
+use crate::redis::{RedisManager, UserSession};
+use crate::services::auth::{
+    consume_magic_link, issue_email_verification, issue_magic_link, password_change_required,
+    refresh_token_status, revoke_refresh_family, revoke_user_refresh_families,
+    rotate_refresh_family, start_refresh_family, validate_password,
+    verification_resend_retry_after, verify_refresh_grace, RefreshTokenStatus,
+};
+use crate::services::email::{email_service_for, EmailService};
 
 /// Application state type alias for auth routes
 /// Uses the main state from crate::state or a compatible state type
 pub use crate::state::AppState;
 
+/// Access token lifetime for "remember me" logins, in seconds (2 hours)
+const REMEMBER_ME_ACCESS_TOKEN_SECS: u64 = 7200;
+
 // ============================================================================
 // Request/Response Types
 // ============================================================================
@@ -37,7 +59,7 @@ pub struct RegisterRequest {
     #[validate(email(message = "Invalid email format"))]
     pub email: String,
 
-    /// User's password (minimum 8 characters)
+    /// User's password; must also pass `validate_password`
     #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
     pub password: String,
 
@@ -53,6 +75,11 @@ pub struct RegisterRequest {
 
     /// Optional phone number
     pub phone: Option<String>,
+
+    /// Optional referral code (the referring member's membership ID)
+    #[validate(length(max = 20, message = "Referral code is too long"))]
+    #[serde(rename = "referralCode")]
+    pub referral_code: Option<String>,
 }
 
 /// Login request payload
@@ -71,6 +98,31 @@ pub struct LoginRequest {
     pub remember_me: bool,
 }
 
+/// Expired password change request payload
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeExpiredPasswordRequest {
+    /// User's email address
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+
+    /// The expired password
+    #[validate(length(min = 1, message = "Current password is required"))]
+    pub current_password: String,
+
+    /// The replacement password
+    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    pub new_password: String,
+}
+
+/// Re-authentication request payload
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct ReauthRequest {
+    /// The signed-in user's password
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+}
+
 // Phase 3 (PR follows #208): the JSON-body refresh-token contract has been
 // removed. Logout and refresh both source the refresh token exclusively from
 // the `refresh_token` HttpOnly cookie. `LogoutRequest` and
@@ -84,6 +136,29 @@ pub struct ForgotPasswordRequest {
     pub email: String,
 }
 
+/// Magic-link request payload
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct MagicLinkRequest {
+    /// Email address to send the sign-in link to
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+}
+
+/// Verification email resend request payload
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct ResendVerificationRequest {
+    /// The address to send a new verification code to
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+}
+
+/// Query of `GET /api/auth/magic-link/verify`
+#[derive(Debug, Clone, Deserialize)]
+pub struct MagicLinkVerifyQuery {
+    /// The token from the emailed link
+    pub token: String,
+}
+
 /// Reset password request payload
 #[derive(Debug, Clone, Deserialize, Validate)]
 pub struct ResetPasswordRequest {
@@ -165,12 +240,39 @@ pub struct MessageResponse {
     pub message: String,
 }
 
+/// CSRF token response
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsrfTokenResponse {
+    /// Send back in the `X-CSRF-Token` header on state-changing requests
+    pub csrf_token: String,
+}
+
 /// Current user response
 #[derive(Debug, Clone, Serialize)]
 pub struct MeResponse {
     pub user: UserResponse,
 }
 
+/// One of the user's active sessions
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionResponse {
+    pub id: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    /// Whether this is the session the request was made with
+    pub current: bool,
+}
+
+/// Active sessions response
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionListResponse {
+    pub sessions: Vec<SessionResponse>,
+}
+
 // ============================================================================
 // Database Row Types
 // ============================================================================
@@ -241,12 +343,49 @@ struct Claims {
     exp: i64,
     /// Issued at timestamp
     iat: i64,
+    /// When the user last entered their credentials
+    last_auth_at: i64,
+    /// The login session the token belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sid: Option<String>,
 }
 
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+/// Validate a request that sets a new password
+///
+/// Field errors and password policy failures are reported together in the
+/// error's `details`: one key per field, and one per failed password
+/// requirement (`min_length`, `uppercase`, ...) in place of the password
+/// field's own entry.
+fn validate_new_password<T: Validate>(
+    payload: &T,
+    password_field: &str,
+    password: &str,
+) -> Result<(), AppError> {
+    let mut details = match payload.validate().map_err(AppError::from) {
+        Ok(()) => HashMap::new(),
+        Err(AppError::ValidationWithDetails { details, .. }) => details,
+        Err(e) => return Err(e),
+    };
+
+    if let Err(errors) = validate_password(password) {
+        details.remove(password_field);
+        details.extend(errors.details());
+    }
+
+    if details.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::ValidationWithDetails {
+            message: "Validation failed".to_string(),
+            details,
+        })
+    }
+}
+
 /// Hash a password using Argon2
 async fn hash_password(password: &str) -> Result<String, AppError> {
     use argon2::{
@@ -285,6 +424,8 @@ fn generate_access_token(
     role: &str,
     jwt_secret: &str,
     expiration_secs: i64,
+    last_auth_at: DateTime<Utc>,
+    session_id: Option<Uuid>,
 ) -> Result<String, AppError> {
     use jsonwebtoken::{encode, EncodingKey, Header};
 
@@ -295,6 +436,8 @@ fn generate_access_token(
         role: role.to_string(),
         exp: (now + Duration::seconds(expiration_secs)).timestamp(),
         iat: now.timestamp(),
+        last_auth_at: last_auth_at.timestamp(),
+        sid: session_id.map(|id| id.to_string()),
     };
 
     encode(
@@ -305,6 +448,99 @@ fn generate_access_token(
     .map_err(|e| AppError::Internal(format!("Failed to generate access token: {}", e)))
 }
 
+/// Client IP and user agent of a request, as recorded on its session.
+///
+/// nginx replaces `X-Forwarded-For` with the peer address, so its first
+/// hop is the client. The values are only shown back to the user, never
+/// used for access decisions.
+fn session_client(headers: &HeaderMap) -> (Option<String>, Option<String>) {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+
+    let ip_address = header("x-forwarded-for")
+        .and_then(|v| v.split(',').next())
+        .map(str::trim)
+        .or_else(|| header("x-real-ip"))
+        .map(String::from);
+    let user_agent = headers
+        .get(USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+
+    (ip_address, user_agent)
+}
+
+/// Record a login session in Redis so `GET /api/auth/sessions` lists it
+///
+/// A Redis failure only loses the listing entry, so it doesn't fail the
+/// login.
+async fn store_session(
+    state: &AppState,
+    session_id: Uuid,
+    user_row: &UserRow,
+    role: &str,
+    headers: &HeaderMap,
+    ttl_secs: u64,
+) {
+    let (ip_address, user_agent) = session_client(headers);
+    let session = UserSession::new(
+        user_row.id,
+        user_row.email.clone().unwrap_or_default(),
+        role.to_string(),
+        ip_address,
+        user_agent,
+    );
+
+    if let Err(e) = RedisManager::from_connection(state.redis())
+        .set_session(
+            &session_id.to_string(),
+            user_row.id,
+            &session,
+            Some(ttl_secs.max(1)),
+        )
+        .await
+    {
+        tracing::warn!(error = %e, user_id = %user_row.id, "Failed to store session");
+    }
+}
+
+/// Mark a session active and extend it to `ttl_secs`, e.g. on refresh
+///
+/// A session missing from Redis (expired, or from before sessions were
+/// tracked) is recorded afresh from this request. Fails open like
+/// [`store_session`].
+async fn touch_session(
+    state: &AppState,
+    session_id: Uuid,
+    user_row: &UserRow,
+    role: &str,
+    headers: &HeaderMap,
+    ttl_secs: u64,
+) {
+    let mut redis = RedisManager::from_connection(state.redis());
+    let key = session_id.to_string();
+    match redis.get_session::<UserSession>(&key).await {
+        Ok(Some(mut session)) => {
+            session.touch();
+            if let Err(e) = redis
+                .set_session(&key, user_row.id, &session, Some(ttl_secs.max(1)))
+                .await
+            {
+                tracing::warn!(error = %e, user_id = %user_row.id, "Failed to update session");
+            }
+        },
+        Ok(None) => store_session(state, session_id, user_row, role, headers, ttl_secs).await,
+        Err(e) => {
+            tracing::warn!(error = %e, user_id = %user_row.id, "Failed to load session");
+        },
+    }
+}
+
 /// Generate a random refresh token
 fn generate_refresh_token_string() -> String {
     use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
@@ -399,13 +635,12 @@ async fn get_user_profile(db: &sqlx::PgPool, user_id: &Uuid) -> Result<UserRespo
 #[axum::debug_handler]
 async fn register(
     State(state): State<AppState>,
+    headers: HeaderMap,
     jar: CookieJar,
     Json(payload): Json<RegisterRequest>,
 ) -> Result<(CookieJar, Json<AuthResponse>), AppError> {
     // Validate request
-    payload
-        .validate()
-        .map_err(|e| AppError::Validation(e.to_string()))?;
+    validate_new_password(&payload, "password", &payload.password)?;
 
     let db = state.db();
 
@@ -468,12 +703,15 @@ async fn register(
     // Generate tokens
     let role_str = format!("{:?}", user_row.role.unwrap_or_default()).to_lowercase();
     let config = state.config();
+    let session_id = Uuid::new_v4();
     let access_token = generate_access_token(
         &user_row.id,
         user_row.email.as_deref(),
         &role_str,
         &config.auth.jwt_secret,
         config.auth.access_token_expiry_secs as i64,
+        Utc::now(),
+        Some(session_id),
     )?;
 
     let refresh_token = generate_refresh_token_string();
@@ -482,30 +720,32 @@ async fn register(
     // Store refresh token
     sqlx::query(
         r#"
-        INSERT INTO refresh_tokens (user_id, token, expires_at)
-        VALUES ($1, $2, $3)
+        INSERT INTO refresh_tokens (user_id, token, expires_at, session_id)
+        VALUES ($1, $2, $3, $4)
         "#,
     )
     .bind(&user_row.id)
     .bind(&refresh_token)
     .bind(&refresh_expires_at)
+    .bind(&session_id)
     .execute(&mut *tx)
     .await
     .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
 
-    // Create initial loyalty record (Bronze tier, 0 points, 0 nights)
-    sqlx::query(
-        r#"
-        INSERT INTO user_loyalty (user_id, current_points, total_nights, tier_id)
-        SELECT $1, 0, 0, id
-        FROM tiers
-        WHERE name = 'Bronze'
-        "#,
-    )
-    .bind(&user_row.id)
-    .execute(&mut *tx)
-    .await
-    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+    // Create initial loyalty record (entry tier, 0 points, 0 nights)
+    crate::services::loyalty::ensure_user_loyalty(&mut *tx, user_row.id).await?;
+
+    // Record the referral, if any. An invalid code fails the whole
+    // registration so the user can correct it rather than silently
+    // losing the referral; bonuses are only awarded later, when the
+    // referee completes their first stay.
+    if let Some(code) = payload
+        .referral_code
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+    {
+        crate::services::referral::record_referral(&mut *tx, user_row.id, code).await?;
+    }
 
     // Log registration action
     sqlx::query(
@@ -525,6 +765,17 @@ async fn register(
         .await
         .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
 
+    let refresh_max_age_secs = (refresh_expires_at - Utc::now()).num_seconds().max(0);
+    store_session(
+        &state,
+        session_id,
+        &user_row,
+        &role_str,
+        &headers,
+        refresh_max_age_secs as u64,
+    )
+    .await;
+
     // Build response
     let user_response = UserResponse {
         id: user_row.id.to_string(),
@@ -546,7 +797,6 @@ async fn register(
     // Phase 3: deliver the refresh token via HttpOnly cookie only.
     // Max-Age aligns with the DB row's expiry so the browser drops the
     // cookie when the server-side token expires.
-    let refresh_max_age_secs = (refresh_expires_at - Utc::now()).num_seconds().max(0);
     let jar = jar.add(build_refresh_cookie(refresh_token, refresh_max_age_secs));
 
     // Note: Returns 200 OK instead of 201 CREATED for compatibility
@@ -569,6 +819,7 @@ async fn register(
 /// the cookie attributes and rationale.
 async fn login(
     State(state): State<AppState>,
+    headers: HeaderMap,
     jar: CookieJar,
     Json(payload): Json<LoginRequest>,
 ) -> Result<(CookieJar, Json<AuthResponse>), AppError> {
@@ -617,71 +868,235 @@ async fn login(
         ));
     }
 
-    // Generate tokens
+    // An expired password gets no tokens at all, so the forced change
+    // can't be skipped by using the session first
+    let role_str = format!("{:?}", user_row.role.unwrap_or_default()).to_lowercase();
+    if password_change_required(db, &state.config().auth, user_row.id, &role_str).await? {
+        tracing::info!(user_id = %user_row.id, "Login refused: password expired");
+        return Err(AppError::PasswordExpired);
+    }
+
+    // Log login action
+    sqlx::query(
+        r#"
+        INSERT INTO user_audit_log (user_id, action, details)
+        VALUES ($1, 'login', $2)
+        "#,
+    )
+    .bind(&user_row.id)
+    .bind(serde_json::json!({ "email": payload.email }))
+    .execute(db)
+    .await
+    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    let (jar, response) =
+        start_session(&state, &headers, jar, &user_row, payload.remember_me).await?;
+
+    tracing::info!("User logged in: {}", response.user.id);
+
+    Ok((jar, Json(response)))
+}
+
+/// POST /api/auth/password/expired
+/// Changes a password that is past its maximum age and signs the user in.
+///
+/// Login answers `403 password_expired` (and issues nothing) once a
+/// password is older than `ADMIN_PASSWORD_MAX_AGE_DAYS` /
+/// `CUSTOMER_PASSWORD_MAX_AGE_DAYS`. This takes the same credentials plus
+/// a new password, so it needs no token and can't be reached with a
+/// session that skipped the change. The new password must differ from the
+/// old one; the user's other sessions are signed out.
+async fn change_expired_password(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    jar: CookieJar,
+    Json(payload): Json<ChangeExpiredPasswordRequest>,
+) -> Result<(CookieJar, Json<AuthResponse>), AppError> {
+    validate_new_password(&payload, "new_password", &payload.new_password)?;
+
+    let db = state.db();
+
+    let user_row: Option<UserRow> = sqlx::query_as(
+        r#"
+        SELECT id, email, password_hash, role, is_active, email_verified, created_at, updated_at
+        FROM users
+        WHERE email = $1
+        "#,
+    )
+    .bind(&payload.email)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    let user_row =
+        user_row.ok_or_else(|| AppError::Unauthorized("Invalid email or password".to_string()))?;
+
+    if !user_row.is_active.unwrap_or(true) {
+        return Err(AppError::Forbidden(
+            "Account is disabled. Please contact support.".to_string(),
+        ));
+    }
+
+    let password_hash = user_row
+        .password_hash
+        .as_ref()
+        .filter(|hash| !hash.is_empty())
+        .ok_or_else(|| AppError::Unauthorized("Invalid email or password".to_string()))?;
+
+    if !verify_password(&payload.current_password, password_hash).await? {
+        return Err(AppError::Unauthorized(
+            "Invalid email or password".to_string(),
+        ));
+    }
+
+    if verify_password(&payload.new_password, password_hash).await? {
+        return Err(AppError::BadRequest(
+            "New password must be different from the current one".to_string(),
+        ));
+    }
+
+    let new_password_hash = hash_password(&payload.new_password).await?;
+
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET password_hash = $2, password_changed_at = NOW(), updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(&user_row.id)
+    .bind(&new_password_hash)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    sqlx::query("DELETE FROM refresh_tokens WHERE user_id = $1")
+        .bind(&user_row.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_audit_log (user_id, action, details)
+        VALUES ($1, 'password_rotated', '{}')
+        "#,
+    )
+    .bind(&user_row.id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    let (jar, response) = start_session(&state, &headers, jar, &user_row, false).await?;
+
+    tracing::info!("Expired password changed for user: {}", response.user.id);
+
+    Ok((jar, Json(response)))
+}
+
+/// Issue an access token and a refresh-token cookie to a user who has
+/// just entered their credentials
+///
+/// Phase 3: the refresh token is delivered only via the HttpOnly cookie.
+/// Cookie's Max-Age aligns with the DB row expiry so the browser drops
+/// the cookie when the server-side token expires.
+///
+/// Each call starts a new session (see `GET /api/auth/sessions`): its id
+/// is carried by the access token and the refresh-token row.
+async fn start_session(
+    state: &AppState,
+    headers: &HeaderMap,
+    jar: CookieJar,
+    user_row: &UserRow,
+    remember_me: bool,
+) -> Result<(CookieJar, AuthResponse), AppError> {
+    let db = state.db();
     let role_str = format!("{:?}", user_row.role.unwrap_or_default()).to_lowercase();
     let config = state.config();
-    let access_expiration = if payload.remember_me {
-        7200 // 2 hours
+    // Admin sessions are shorter-lived, and "remember me" doesn't extend them
+    let access_expiration = if remember_me {
+        REMEMBER_ME_ACCESS_TOKEN_SECS
     } else {
-        config.auth.access_token_expiry_secs as i64
+        config.auth.access_token_expiry_secs
     };
+    let access_expiration = config
+        .auth
+        .access_token_expiry_for(&role_str, access_expiration);
 
+    let authenticated_at = Utc::now();
+    let session_id = Uuid::new_v4();
     let access_token = generate_access_token(
         &user_row.id,
         user_row.email.as_deref(),
         &role_str,
         &config.auth.jwt_secret,
-        access_expiration,
+        access_expiration as i64,
+        authenticated_at,
+        Some(session_id),
     )?;
 
     let refresh_token = generate_refresh_token_string();
-    let refresh_expires_days = if payload.remember_me { 30 } else { 7 };
-    let refresh_expires_at = Utc::now() + Duration::days(refresh_expires_days);
+    let refresh_expires_days: u64 = if remember_me { 30 } else { 7 };
+    let refresh_expires_secs = config
+        .auth
+        .refresh_token_expiry_for(&role_str, refresh_expires_days * 86_400);
+    let refresh_expires_at = authenticated_at + Duration::seconds(refresh_expires_secs as i64);
 
     // Store refresh token
     sqlx::query(
         r#"
-        INSERT INTO refresh_tokens (user_id, token, expires_at)
-        VALUES ($1, $2, $3)
+        INSERT INTO refresh_tokens (user_id, token, expires_at, authenticated_at, session_id)
+        VALUES ($1, $2, $3, $4, $5)
         "#,
     )
     .bind(&user_row.id)
     .bind(&refresh_token)
     .bind(&refresh_expires_at)
+    .bind(&authenticated_at)
+    .bind(&session_id)
     .execute(db)
     .await
     .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
 
-    // Log login action
-    sqlx::query(
-        r#"
-        INSERT INTO user_audit_log (user_id, action, details)
-        VALUES ($1, 'login', $2)
-        "#,
+    // Every login starts a new token family for reuse detection
+    start_refresh_family(
+        state.redis(),
+        user_row.id,
+        &refresh_token,
+        refresh_expires_secs,
     )
-    .bind(&user_row.id)
-    .bind(serde_json::json!({ "email": payload.email }))
-    .execute(db)
-    .await
-    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+    .await;
+    store_session(
+        state,
+        session_id,
+        user_row,
+        &role_str,
+        headers,
+        refresh_expires_secs,
+    )
+    .await;
 
     // Get full user profile
     let user_response = get_user_profile(db, &user_row.id).await?;
 
-    tracing::info!("User logged in: {}", user_response.id);
-
-    // Phase 3: the refresh token is delivered only via the HttpOnly cookie.
-    // Cookie's Max-Age aligns with the DB row expiry so the browser drops
-    // the cookie when the server-side token expires.
     let refresh_max_age_secs = (refresh_expires_at - Utc::now()).num_seconds().max(0);
     let jar = jar.add(build_refresh_cookie(refresh_token, refresh_max_age_secs));
 
     Ok((
         jar,
-        Json(AuthResponse {
+        AuthResponse {
             user: user_response,
             tokens: AuthTokens { access_token },
-        }),
+        },
     ))
 }
 
@@ -714,6 +1129,22 @@ async fn logout(
             .execute(db)
             .await
             .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+        if let RefreshTokenStatus::Current(family) =
+            refresh_token_status(state.redis(), &cookie_token).await
+        {
+            revoke_refresh_family(state.redis(), &family.id).await;
+        }
+    }
+
+    // The session no longer shows up in the listing
+    if let Some(session_id) = auth_user.session_id.as_deref() {
+        if let Err(e) = RedisManager::from_connection(state.redis())
+            .delete_user_session(user_id, session_id)
+            .await
+        {
+            tracing::warn!(error = %e, user_id = %user_id, "Failed to delete session");
+        }
     }
 
     // Log logout action
@@ -741,52 +1172,297 @@ async fn logout(
     ))
 }
 
-/// POST /api/auth/refresh
-/// Issues a new access token using a refresh token.
+/// POST /api/auth/logout-all
+/// Ends every session of the user, on all devices.
 ///
-/// Phase 3: the refresh token is read EXCLUSIVELY from the `refresh_token`
-/// HttpOnly cookie. The JSON-body `refreshToken` field accepted in Phase 1
-/// is no longer supported — clients that previously sent it must rely on
-/// the browser-managed cookie (axios `withCredentials: true`). Missing or
-/// empty cookies return 401.
-async fn refresh(
+/// Deletes all of the user's refresh tokens, their token families and
+/// Redis sessions, and marks every access token issued so far as revoked
+/// (see `SessionRevocations`) — including the one this request carried.
+/// Like `/auth/logout`, clears the refresh-token cookie.
+async fn logout_all(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     jar: CookieJar,
-) -> Result<(CookieJar, Json<TokenRefreshResponse>), AppError> {
+) -> Result<(CookieJar, Json<MessageResponse>), AppError> {
     let db = state.db();
 
-    // Phase 3: cookie-only. An empty cookie value is treated as missing so
-    // a stale `Set-Cookie: refresh_token=` (e.g., from a prior logout race)
-    // still produces a clean 401.
-    let supplied_token = jar
-        .get(REFRESH_COOKIE_NAME)
-        .map(|c| c.value().to_string())
-        .filter(|t| !t.is_empty())
-        .ok_or_else(|| AppError::Unauthorized("Missing refresh token cookie".to_string()))?;
+    let user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::Unauthorized("Invalid user ID".to_string()))?;
 
-    // Find valid refresh token
-    let token_row: Option<(Uuid,)> = sqlx::query_as(
+    sqlx::query("DELETE FROM refresh_tokens WHERE user_id = $1")
+        .bind(&user_id)
+        .execute(db)
+        .await
+        .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    // Unlike a single logout, a failure here is reported: the user asked
+    // for every session to end because they suspect a compromise.
+    revoke_user_refresh_families(state.redis(), user_id).await?;
+    let sessions = RedisManager::from_connection(state.redis())
+        .delete_all_user_sessions(user_id)
+        .await?;
+
+    // The revocation marker must outlive the longest access token a login
+    // can issue
+    let longest_access_token_secs = state
+        .config()
+        .auth
+        .access_token_expiry_secs
+        .max(REMEMBER_ME_ACCESS_TOKEN_SECS);
+    SessionRevocations(state.redis())
+        .revoke_all(&auth_user.id, longest_access_token_secs)
+        .await?;
+
+    sqlx::query(
         r#"
-        SELECT user_id
-        FROM refresh_tokens
-        WHERE token = $1 AND expires_at > NOW()
+        INSERT INTO user_audit_log (user_id, action, details)
+        VALUES ($1, 'logout_all', $2)
         "#,
     )
-    .bind(&supplied_token)
-    .fetch_optional(db)
+    .bind(&user_id)
+    .bind(serde_json::json!({ "sessions": sessions }))
+    .execute(db)
     .await
     .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
 
-    let (user_id,) = token_row
-        .ok_or_else(|| AppError::Unauthorized("Invalid or expired refresh token".to_string()))?;
+    tracing::info!(user_id = %user_id, "User logged out of all devices");
 
-    // Get user
-    let user_row: Option<UserRow> = sqlx::query_as(
-        r#"
-        SELECT id, email, password_hash, role, is_active, email_verified, created_at, updated_at
-        FROM users
-        WHERE id = $1 AND is_active = true
-        "#,
+    let jar = jar.add(build_clear_refresh_cookie());
+
+    Ok((
+        jar,
+        Json(MessageResponse {
+            message: "Logged out of all devices".to_string(),
+        }),
+    ))
+}
+
+/// GET /api/auth/sessions
+/// Lists the user's active sessions, newest activity first.
+///
+/// The session the request was made with is flagged `current`. Sessions
+/// are recorded at login and registration and kept alive by refreshes, so
+/// one ends when its refresh token would.
+async fn list_sessions(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<SessionListResponse>, AppError> {
+    let user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::Unauthorized("Invalid user ID".to_string()))?;
+
+    let mut sessions: Vec<SessionResponse> = RedisManager::from_connection(state.redis())
+        .list_user_sessions(user_id)
+        .await?
+        .into_iter()
+        .map(|(id, session)| SessionResponse {
+            current: auth_user.session_id.as_deref() == Some(id.as_str()),
+            id,
+            ip_address: session.ip_address,
+            user_agent: session.user_agent,
+            created_at: session.created_at,
+            last_activity: session.last_activity,
+        })
+        .collect();
+    sessions.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
+
+    Ok(Json(SessionListResponse { sessions }))
+}
+
+/// DELETE /api/auth/sessions/:id
+/// Ends one of the user's sessions, e.g. a device they no longer use.
+///
+/// Deletes the session's refresh token and marks its access tokens as
+/// revoked (see `SessionRevocations::revoke_session`). The user's other
+/// sessions are untouched. Sessions of other users answer 404.
+async fn revoke_session(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<MessageResponse>, AppError> {
+    let db = state.db();
+
+    let user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::Unauthorized("Invalid user ID".to_string()))?;
+
+    let mut redis = RedisManager::from_connection(state.redis());
+    let key = session_id.to_string();
+    let owned = redis
+        .get_session::<UserSession>(&key)
+        .await?
+        .is_some_and(|session| session.user_id == user_id);
+    if !owned {
+        return Err(AppError::NotFound("Session not found".to_string()));
+    }
+
+    sqlx::query("DELETE FROM refresh_tokens WHERE user_id = $1 AND session_id = $2")
+        .bind(&user_id)
+        .bind(&session_id)
+        .execute(db)
+        .await
+        .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    redis.delete_user_session(user_id, &key).await?;
+
+    // Like `logout_all`, the marker must outlive the longest access token
+    // the session could hold
+    let longest_access_token_secs = state
+        .config()
+        .auth
+        .access_token_expiry_secs
+        .max(REMEMBER_ME_ACCESS_TOKEN_SECS);
+    SessionRevocations(state.redis())
+        .revoke_session(&key, longest_access_token_secs)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_audit_log (user_id, action, details)
+        VALUES ($1, 'session_revoked', $2)
+        "#,
+    )
+    .bind(&user_id)
+    .bind(serde_json::json!({ "session_id": session_id }))
+    .execute(db)
+    .await
+    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    tracing::info!(user_id = %user_id, session_id = %session_id, "Session revoked");
+
+    Ok(Json(MessageResponse {
+        message: "Session revoked".to_string(),
+    }))
+}
+
+/// Revoke a refresh token family after reuse was detected
+///
+/// The family's live token is the only one with a database row left (its
+/// predecessors were deleted as they were rotated out); the row is found
+/// by the token id Redis tracks it under.
+async fn revoke_token_family(
+    state: &AppState,
+    family_id: &str,
+    user_id: Uuid,
+    current_token_id: &str,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        DELETE FROM refresh_tokens
+        WHERE user_id = $1
+          AND encode(sha256(convert_to(token, 'UTF8')), 'hex') = $2
+        "#,
+    )
+    .bind(&user_id)
+    .bind(current_token_id)
+    .execute(state.db())
+    .await
+    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    revoke_refresh_family(state.redis(), family_id).await;
+    Ok(())
+}
+
+/// POST /api/auth/refresh
+/// Issues a new access token using a refresh token.
+///
+/// Phase 3: the refresh token is read EXCLUSIVELY from the `refresh_token`
+/// HttpOnly cookie. The JSON-body `refreshToken` field accepted in Phase 1
+/// is no longer supported — clients that previously sent it must rely on
+/// the browser-managed cookie (axios `withCredentials: true`). Missing or
+/// empty cookies return 401.
+///
+/// An access token in the `Authorization` header is optional, but when
+/// present it must belong to the same user and have expired within
+/// `REFRESH_ACCESS_TOKEN_GRACE_SECS` (see `verify_refresh_grace`).
+///
+/// Each refresh rotates the token within its family (see
+/// `services::auth::RefreshFamily`). Presenting a token that was already
+/// rotated out means it was copied, so the whole family is revoked: the
+/// attacker's and the victim's copies both stop working and the user has
+/// to log in again.
+async fn refresh(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    jar: CookieJar,
+) -> Result<(CookieJar, Json<TokenRefreshResponse>), AppError> {
+    let db = state.db();
+
+    // Phase 3: cookie-only. An empty cookie value is treated as missing so
+    // a stale `Set-Cookie: refresh_token=` (e.g., from a prior logout race)
+    // still produces a clean 401.
+    let supplied_token = jar
+        .get(REFRESH_COOKIE_NAME)
+        .map(|c| c.value().to_string())
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| AppError::Unauthorized("Missing refresh token cookie".to_string()))?;
+
+    // Checked before the database: a rotated-out token no longer has a row
+    let family_status = refresh_token_status(state.redis(), &supplied_token).await;
+    if let RefreshTokenStatus::Reused(family) = family_status {
+        revoke_token_family(&state, &family.id, family.user_id, &family.current).await?;
+        tracing::warn!(
+            user_id = %family.user_id,
+            family_id = %family.id,
+            "Refresh token reuse detected; token family revoked"
+        );
+        return Err(AppError::Unauthorized(
+            "Refresh token has already been used".to_string(),
+        ));
+    }
+
+    // Find valid refresh token
+    let token_row: Option<(Uuid, DateTime<Utc>, Option<Uuid>)> = sqlx::query_as(
+        r#"
+        SELECT user_id, authenticated_at, session_id
+        FROM refresh_tokens
+        WHERE token = $1 AND expires_at > NOW()
+        "#,
+    )
+    .bind(&supplied_token)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    let (user_id, authenticated_at, session_id) = token_row
+        .ok_or_else(|| AppError::Unauthorized("Invalid or expired refresh token".to_string()))?;
+    // Tokens from before sessions were tracked join one on their first
+    // refresh
+    let session_id = session_id.unwrap_or_else(Uuid::new_v4);
+
+    // An access token sent along must have expired only recently
+    // (`REFRESH_ACCESS_TOKEN_GRACE_SECS`); without one the refresh token
+    // alone is enough.
+    let presented_access_token = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| {
+            v.strip_prefix("Bearer ")
+                .or_else(|| v.strip_prefix("bearer "))
+        })
+        .map(str::trim)
+        .filter(|t| !t.is_empty());
+    if let Some(access_token) = presented_access_token {
+        if let Err(e) = verify_refresh_grace(
+            access_token,
+            &state.config().auth.jwt_secret,
+            &user_id.to_string(),
+            state.config().auth.refresh_grace_secs,
+            Utc::now().timestamp(),
+        ) {
+            tracing::warn!(
+                user_id = %user_id,
+                "Refresh rejected: presented access token outside the grace window"
+            );
+            return Err(e);
+        }
+    }
+
+    // Get user
+    let user_row: Option<UserRow> = sqlx::query_as(
+        r#"
+        SELECT id, email, password_hash, role, is_active, email_verified, created_at, updated_at
+        FROM users
+        WHERE id = $1 AND is_active = true
+        "#,
     )
     .bind(&user_id)
     .fetch_optional(db)
@@ -796,19 +1472,48 @@ async fn refresh(
     let user_row =
         user_row.ok_or_else(|| AppError::Unauthorized("User not found or inactive".to_string()))?;
 
-    // Generate new tokens
+    // A session from before the password expired doesn't outlive it: the
+    // refresh token is dropped and the user has to go through the change.
     let role_str = format!("{:?}", user_row.role.unwrap_or_default()).to_lowercase();
     let config = state.config();
+    if password_change_required(db, &config.auth, user_row.id, &role_str).await? {
+        sqlx::query("DELETE FROM refresh_tokens WHERE token = $1")
+            .bind(&supplied_token)
+            .execute(db)
+            .await
+            .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+        if let RefreshTokenStatus::Current(family) = family_status {
+            revoke_refresh_family(state.redis(), &family.id).await;
+        }
+        tracing::info!(user_id = %user_row.id, "Refresh refused: password expired");
+        return Err(AppError::PasswordExpired);
+    }
+
+    // Generate new tokens. Refreshing is not re-authenticating, so the
+    // session's `authenticated_at` carries over unchanged.
     let access_token = generate_access_token(
         &user_row.id,
         user_row.email.as_deref(),
         &role_str,
         &config.auth.jwt_secret,
-        config.auth.access_token_expiry_secs as i64,
+        config
+            .auth
+            .access_token_expiry_for(&role_str, config.auth.access_token_expiry_secs)
+            as i64,
+        authenticated_at,
+        Some(session_id),
     )?;
 
+    // Customer sessions slide with each refresh; admin sessions end a
+    // fixed time after the last login or re-auth however often they are
+    // refreshed.
     let new_refresh_token = generate_refresh_token_string();
-    let refresh_expires_at = Utc::now() + Duration::days(7);
+    let refresh_expires_at = if is_admin_role(&role_str) {
+        authenticated_at
+            + Duration::seconds(config.auth.refresh_token_expiry_for(&role_str, 7 * 86_400) as i64)
+    } else {
+        Utc::now() + Duration::days(7)
+    };
 
     // Delete old refresh token and insert new one
     sqlx::query("DELETE FROM refresh_tokens WHERE token = $1")
@@ -819,13 +1524,15 @@ async fn refresh(
 
     sqlx::query(
         r#"
-        INSERT INTO refresh_tokens (user_id, token, expires_at)
-        VALUES ($1, $2, $3)
+        INSERT INTO refresh_tokens (user_id, token, expires_at, authenticated_at, session_id)
+        VALUES ($1, $2, $3, $4, $5)
         "#,
     )
     .bind(&user_row.id)
     .bind(&new_refresh_token)
     .bind(&refresh_expires_at)
+    .bind(&authenticated_at)
+    .bind(&session_id)
     .execute(db)
     .await
     .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
@@ -836,6 +1543,32 @@ async fn refresh(
     // Set-Cookie header — never in the JSON body. Max-Age matches the new
     // DB row's expiry.
     let refresh_max_age_secs = (refresh_expires_at - Utc::now()).num_seconds().max(0);
+
+    // Tokens from before families existed join one on their first refresh
+    let family_ttl_secs = refresh_max_age_secs.max(1) as u64;
+    match family_status {
+        RefreshTokenStatus::Current(family) => {
+            rotate_refresh_family(state.redis(), family, &new_refresh_token, family_ttl_secs).await;
+        },
+        _ => {
+            start_refresh_family(
+                state.redis(),
+                user_row.id,
+                &new_refresh_token,
+                family_ttl_secs,
+            )
+            .await;
+        },
+    }
+    touch_session(
+        &state,
+        session_id,
+        &user_row,
+        &role_str,
+        &headers,
+        family_ttl_secs,
+    )
+    .await;
     let jar = jar.add(build_refresh_cookie(
         new_refresh_token,
         refresh_max_age_secs,
@@ -849,6 +1582,103 @@ async fn refresh(
     ))
 }
 
+/// POST /api/auth/reauth
+/// Confirms the signed-in user's password for sensitive actions.
+///
+/// Role changes and deletions answer `403 reauth_required` when the
+/// session's credentials were entered more than `REAUTH_MAX_AGE_SECS` ago
+/// (see `crate::middleware::auth::require_recent_auth`). The client calls
+/// this with the password and retries with the returned access token. The
+/// refresh-token row behind the cookie is stamped too, so tokens refreshed
+/// afterwards stay fresh.
+async fn reauth(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    jar: CookieJar,
+    Json(payload): Json<ReauthRequest>,
+) -> Result<Json<TokenRefreshResponse>, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let db = state.db();
+    let user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::Unauthorized("Invalid user ID".to_string()))?;
+
+    let user_row: Option<UserRow> = sqlx::query_as(
+        r#"
+        SELECT id, email, password_hash, role, is_active, email_verified, created_at, updated_at
+        FROM users
+        WHERE id = $1 AND is_active = true
+        "#,
+    )
+    .bind(&user_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    let user_row =
+        user_row.ok_or_else(|| AppError::Unauthorized("User not found or inactive".to_string()))?;
+
+    // OAuth-only accounts have no password to confirm; signing in again
+    // through the provider issues a fresh session instead.
+    let password_hash = user_row.password_hash.as_ref().ok_or_else(|| {
+        AppError::BadRequest(
+            "This account uses social login. Please sign in again with Google/LINE.".to_string(),
+        )
+    })?;
+
+    if !verify_password(&payload.password, password_hash).await? {
+        return Err(AppError::Unauthorized("Invalid password".to_string()));
+    }
+
+    let authenticated_at = Utc::now();
+    if let Some(cookie_token) = jar.get(REFRESH_COOKIE_NAME).map(|c| c.value().to_string()) {
+        sqlx::query(
+            "UPDATE refresh_tokens SET authenticated_at = $3 WHERE user_id = $1 AND token = $2",
+        )
+        .bind(&user_id)
+        .bind(&cookie_token)
+        .bind(&authenticated_at)
+        .execute(db)
+        .await
+        .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+    }
+
+    let role_str = format!("{:?}", user_row.role.unwrap_or_default()).to_lowercase();
+    let config = state.config();
+    let access_token = generate_access_token(
+        &user_row.id,
+        user_row.email.as_deref(),
+        &role_str,
+        &config.auth.jwt_secret,
+        config
+            .auth
+            .access_token_expiry_for(&role_str, config.auth.access_token_expiry_secs)
+            as i64,
+        authenticated_at,
+        auth_user
+            .session_id
+            .as_deref()
+            .and_then(|id| Uuid::parse_str(id).ok()),
+    )?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_audit_log (user_id, action, details)
+        VALUES ($1, 'reauth', '{}')
+        "#,
+    )
+    .bind(&user_id)
+    .execute(db)
+    .await
+    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    Ok(Json(TokenRefreshResponse {
+        tokens: AuthTokens { access_token },
+    }))
+}
+
 /// POST /api/auth/forgot-password (or /api/auth/reset-password/request)
 /// Initiates the password reset process
 async fn forgot_password(
@@ -931,10 +1761,7 @@ async fn forgot_password(
             .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
 
             // Send email with reset link containing reset_token
-            let email_service = EmailServiceImpl::from_smtp_config(
-                &state.config().email.smtp,
-                &state.config().server.frontend_url,
-            );
+            let email_service = email_service_for(state.config(), state.db());
 
             if email_service.is_configured() {
                 if let Err(e) = email_service
@@ -968,6 +1795,209 @@ async fn forgot_password(
     }))
 }
 
+/// POST /api/auth/magic-link
+/// Emails a single-use sign-in link
+///
+/// The token lives in Redis for `MAGIC_LINK_TTL_SECS`. Like
+/// `forgot_password`, the response is the same whether or not the email
+/// belongs to an active account.
+async fn request_magic_link(
+    State(state): State<AppState>,
+    Json(payload): Json<MagicLinkRequest>,
+) -> Result<Json<MessageResponse>, AppError> {
+    payload.validate()?;
+
+    let db = state.db();
+
+    let user_row: Option<(Uuid, String)> = sqlx::query_as(
+        "SELECT id, email FROM users WHERE email = $1 AND COALESCE(is_active, true)",
+    )
+    .bind(&payload.email)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    if let Some((user_id, email)) = user_row {
+        let ttl_secs = state.config().auth.magic_link_ttl_secs;
+        let token = issue_magic_link(state.redis(), user_id, ttl_secs).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_audit_log (user_id, action, details)
+            VALUES ($1, 'magic_link_request', '{}')
+            "#,
+        )
+        .bind(&user_id)
+        .execute(db)
+        .await
+        .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+        let email_service = email_service_for(state.config(), state.db());
+        if email_service.is_configured() {
+            if let Err(e) = email_service
+                .send_magic_link_email(&email, &token, ttl_secs.div_ceil(60))
+                .await
+            {
+                // Still a generic success, so the response can't be used
+                // to find out which emails have accounts
+                tracing::error!("Failed to send magic link email: {}", e);
+            }
+        } else {
+            tracing::warn!(
+                "SMTP not configured, skipping magic link email for user: {}",
+                user_id
+            );
+        }
+
+        tracing::info!("Magic link requested for user: {}", user_id);
+    } else {
+        tracing::info!("Magic link requested for unknown or inactive email");
+    }
+
+    Ok(Json(MessageResponse {
+        message: "If the email exists, a sign-in link has been sent".to_string(),
+    }))
+}
+
+/// POST /api/auth/resend-verification
+/// Emails a fresh verification code to an unverified account
+///
+/// Each address may ask once per `VERIFICATION_RESEND_COOLDOWN_SECS`;
+/// sooner gets a 429 with `Retry-After`. The cooldown and the response
+/// are the same for unknown, already-verified and unverified addresses,
+/// so neither says whether the email is registered. Only an active,
+/// unverified account actually gets a new code, which replaces any
+/// earlier one.
+async fn resend_verification(
+    State(state): State<AppState>,
+    Json(payload): Json<ResendVerificationRequest>,
+) -> Result<Json<MessageResponse>, AppError> {
+    payload.validate()?;
+
+    let cooldown_secs = state.config().auth.verification_resend_cooldown_secs;
+    if let Some(retry_after) =
+        verification_resend_retry_after(state.redis(), &payload.email, cooldown_secs).await
+    {
+        return Err(AppError::TooManyRequests(retry_after));
+    }
+
+    let db = state.db();
+
+    let user_row: Option<(Uuid, String, Option<bool>)> = sqlx::query_as(
+        r#"
+        SELECT id, email, email_verified
+        FROM users
+        WHERE email = $1 AND COALESCE(is_active, true)
+        "#,
+    )
+    .bind(&payload.email)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    match user_row {
+        Some((user_id, _, Some(true))) => {
+            tracing::info!("Verification resend skipped, already verified: {}", user_id);
+        },
+        Some((user_id, email, _)) => {
+            let email_service = email_service_for(state.config(), state.db());
+            let code = email_service.generate_verification_code();
+            issue_email_verification(db, user_id, &email, &code).await?;
+
+            if email_service.is_configured() {
+                if let Err(e) = email_service
+                    .send_registration_verification_email(&email, &code)
+                    .await
+                {
+                    tracing::error!("Failed to send verification email: {}", e);
+                }
+            } else {
+                tracing::warn!(
+                    "SMTP not configured, skipping verification email for user: {}",
+                    user_id
+                );
+            }
+
+            tracing::info!("Verification email resent for user: {}", user_id);
+        },
+        None => {
+            tracing::info!("Verification resend requested for unknown or inactive email");
+        },
+    }
+
+    Ok(Json(MessageResponse {
+        message: "If the email needs verifying, a new verification email has been sent".to_string(),
+    }))
+}
+
+/// GET /api/auth/magic-link/verify?token=
+/// Signs in with a magic-link token and issues the usual tokens
+///
+/// The token is used up by the first attempt, successful or not. Unknown,
+/// expired and already-used tokens all get the same 400, so the answer
+/// says nothing about the account. An account disabled or with an expired
+/// password since the link was sent is refused like a password login.
+async fn verify_magic_link(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    jar: CookieJar,
+    Query(query): Query<MagicLinkVerifyQuery>,
+) -> Result<(CookieJar, Json<AuthResponse>), AppError> {
+    let invalid = || AppError::BadRequest("Invalid or expired sign-in link".to_string());
+
+    if query.token.trim().is_empty() {
+        return Err(invalid());
+    }
+    let user_id = consume_magic_link(state.redis(), query.token.trim())
+        .await?
+        .ok_or_else(invalid)?;
+
+    let db = state.db();
+
+    let user_row: UserRow = sqlx::query_as(
+        r#"
+        SELECT id, email, password_hash, role, is_active, email_verified, created_at, updated_at
+        FROM users
+        WHERE id = $1
+        "#,
+    )
+    .bind(&user_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?
+    .ok_or_else(invalid)?;
+
+    if !user_row.is_active.unwrap_or(true) {
+        return Err(AppError::Forbidden(
+            "Account is disabled. Please contact support.".to_string(),
+        ));
+    }
+
+    let role_str = format!("{:?}", user_row.role.unwrap_or_default()).to_lowercase();
+    if password_change_required(db, &state.config().auth, user_row.id, &role_str).await? {
+        tracing::info!(user_id = %user_row.id, "Magic link login refused: password expired");
+        return Err(AppError::PasswordExpired);
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_audit_log (user_id, action, details)
+        VALUES ($1, 'login', $2)
+        "#,
+    )
+    .bind(&user_row.id)
+    .bind(serde_json::json!({ "email": user_row.email, "method": "magic_link" }))
+    .execute(db)
+    .await
+    .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+    let (jar, response) = start_session(&state, &headers, jar, &user_row, false).await?;
+
+    tracing::info!("User logged in with magic link: {}", response.user.id);
+
+    Ok((jar, Json(response)))
+}
+
 /// POST /api/auth/reset-password
 /// Resets the user's password using a reset token
 async fn reset_password(
@@ -975,9 +2005,7 @@ async fn reset_password(
     Json(payload): Json<ResetPasswordRequest>,
 ) -> Result<Json<MessageResponse>, AppError> {
     // Validate request
-    payload
-        .validate()
-        .map_err(|e| AppError::Validation(e.to_string()))?;
+    validate_new_password(&payload, "password", &payload.password)?;
 
     let db = state.db();
 
@@ -1010,8 +2038,10 @@ async fn reset_password(
     let new_password_hash = hash_password(&payload.password).await?;
 
     // Update password
-    sqlx::query("UPDATE users SET password_hash = $1, updated_at = NOW() WHERE id = $2")
-        .bind(&new_password_hash)
+    sqlx::query(
+        "UPDATE users SET password_hash = $1, password_changed_at = NOW(), updated_at = NOW() WHERE id = $2",
+    )
+    .bind(&new_password_hash)
         .bind(&user_id)
         .execute(db)
         .await
@@ -1043,6 +2073,23 @@ async fn reset_password(
     }))
 }
 
+/// GET /api/auth/csrf-token
+/// Returns the CSRF token for the double-submit check (see
+/// `middleware::csrf`), setting it as the `csrf_token` cookie
+///
+/// A browser that already holds a token gets the same one back, so tabs
+/// fetching it concurrently don't invalidate each other's copy.
+async fn csrf_token(jar: CookieJar) -> (CookieJar, Json<CsrfTokenResponse>) {
+    let token = jar
+        .get(CSRF_COOKIE_NAME)
+        .map(|cookie| cookie.value().to_string())
+        .filter(|token| is_well_formed_csrf_token(token))
+        .unwrap_or_else(generate_csrf_token);
+
+    let jar = jar.add(build_csrf_cookie(&token));
+    (jar, Json(CsrfTokenResponse { csrf_token: token }))
+}
+
 /// GET /api/auth/me
 /// Returns the authenticated user's profile
 async fn me(
@@ -1075,6 +2122,10 @@ pub fn routes() -> Router<AppState> {
     // Protected routes (require authentication)
     let protected_routes = Router::<AppState>::new()
         .route("/logout", post(logout))
+        .route("/logout-all", post(logout_all))
+        .route("/sessions", get(list_sessions))
+        .route("/sessions/:id", delete(revoke_session))
+        .route("/reauth", post(reauth))
         .route("/me", get(me))
         .layer(middleware::from_fn(auth_middleware));
 
@@ -1085,7 +2136,12 @@ pub fn routes() -> Router<AppState> {
         .route("/refresh", post(refresh))
         .route("/reset-password/request", post(forgot_password))
         .route("/forgot-password", post(forgot_password))
-        .route("/reset-password", post(reset_password));
+        .route("/reset-password", post(reset_password))
+        .route("/password/expired", post(change_expired_password))
+        .route("/magic-link", post(request_magic_link))
+        .route("/magic-link/verify", get(verify_magic_link))
+        .route("/resend-verification", post(resend_verification))
+        .route("/csrf-token", get(csrf_token));
 
     Router::<AppState>::new()
         .merge(protected_routes)
@@ -1097,6 +2153,10 @@ pub fn routes_with_state(state: AppState) -> Router {
     // Protected routes (require authentication)
     let protected_routes = Router::<AppState>::new()
         .route("/auth/logout", post(logout))
+        .route("/auth/logout-all", post(logout_all))
+        .route("/auth/sessions", get(list_sessions))
+        .route("/auth/sessions/:id", delete(revoke_session))
+        .route("/auth/reauth", post(reauth))
         .route("/auth/me", get(me))
         .layer(middleware::from_fn(auth_middleware));
 
@@ -1107,7 +2167,12 @@ pub fn routes_with_state(state: AppState) -> Router {
         .route("/auth/refresh", post(refresh))
         .route("/auth/reset-password/request", post(forgot_password))
         .route("/auth/forgot-password", post(forgot_password))
-        .route("/auth/reset-password", post(reset_password));
+        .route("/auth/reset-password", post(reset_password))
+        .route("/auth/password/expired", post(change_expired_password))
+        .route("/auth/magic-link", post(request_magic_link))
+        .route("/auth/magic-link/verify", get(verify_magic_link))
+        .route("/auth/resend-verification", post(resend_verification))
+        .route("/auth/csrf-token", get(csrf_token));
 
     Router::<AppState>::new()
         .merge(protected_routes)
@@ -1132,6 +2197,7 @@ mod tests {
             first_name: "John".to_string(),
             last_name: "Doe".to_string(),
             phone: None,
+            referral_code: None,
         };
         assert!(valid_request.validate().is_ok());
 
@@ -1141,6 +2207,7 @@ mod tests {
             first_name: "John".to_string(),
             last_name: "Doe".to_string(),
             phone: None,
+            referral_code: None,
         };
         assert!(invalid_email.validate().is_err());
 
@@ -1150,8 +2217,48 @@ mod tests {
             first_name: "John".to_string(),
             last_name: "Doe".to_string(),
             phone: None,
+            referral_code: None,
         };
         assert!(short_password.validate().is_err());
+
+        let long_referral_code = RegisterRequest {
+            email: "test@example.com".to_string(),
+            password: "password123".to_string(),
+            first_name: "John".to_string(),
+            last_name: "Doe".to_string(),
+            phone: None,
+            referral_code: Some("X".repeat(21)),
+        };
+        assert!(long_referral_code.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_new_password_reports_fields_and_requirements() {
+        let request = RegisterRequest {
+            email: "invalid-email".to_string(),
+            password: "short".to_string(),
+            first_name: "John".to_string(),
+            last_name: "Doe".to_string(),
+            phone: None,
+            referral_code: None,
+        };
+
+        match validate_new_password(&request, "password", &request.password) {
+            Err(AppError::ValidationWithDetails { details, .. }) => {
+                assert!(details.contains_key("email"));
+                assert!(details.contains_key("min_length"));
+                assert!(details.contains_key("special_char"));
+                assert!(!details.contains_key("password"));
+            },
+            other => panic!("expected ValidationWithDetails, got {:?}", other),
+        }
+
+        let strong = RegisterRequest {
+            email: "test@example.com".to_string(),
+            password: "SecurePass123!".to_string(),
+            ..request
+        };
+        assert!(validate_new_password(&strong, "password", &strong.password).is_ok());
     }
 
     #[test]
@@ -1184,6 +2291,47 @@ mod tests {
         assert_ne!(token1, token2);
     }
 
+    #[test]
+    fn test_session_client_reads_first_forwarded_hop() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "203.0.113.7, 10.0.0.2".parse().unwrap());
+        headers.insert("x-real-ip", "10.0.0.2".parse().unwrap());
+        headers.insert(USER_AGENT, "Mozilla/5.0".parse().unwrap());
+
+        let (ip_address, user_agent) = session_client(&headers);
+        assert_eq!(ip_address.as_deref(), Some("203.0.113.7"));
+        assert_eq!(user_agent.as_deref(), Some("Mozilla/5.0"));
+
+        assert_eq!(session_client(&HeaderMap::new()), (None, None));
+    }
+
+    #[test]
+    fn test_access_token_carries_session_id() {
+        use jsonwebtoken::{decode, DecodingKey, Validation};
+
+        let secret = "test-secret-for-session-ids-min-32-bytes-long";
+        let session_id = Uuid::new_v4();
+        let token = generate_access_token(
+            &Uuid::new_v4(),
+            None,
+            "customer",
+            secret,
+            3600,
+            Utc::now(),
+            Some(session_id),
+        )
+        .unwrap();
+
+        let claims = decode::<Claims>(
+            &token,
+            &DecodingKey::from_secret(secret.as_bytes()),
+            &Validation::default(),
+        )
+        .unwrap()
+        .claims;
+        assert_eq!(claims.sid, Some(session_id.to_string()));
+    }
+
     #[test]
     fn test_auth_tokens_serialization_omits_refresh_token() {
         // Phase 3 contract: the AuthTokens body struct must only contain
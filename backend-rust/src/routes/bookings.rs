@@ -19,7 +19,16 @@ use validator::Validate;
 
 use crate::error::{AppError, AppResult};
 use crate::middleware::auth::{auth_middleware, has_role, AuthUser};
-use crate::models::booking::{BookingResponse, BookingStatus, RoomType};
+use crate::models::booking::{
+    BookingChannel, BookingResponse, BookingSource, BookingStatus, PaymentStatus, RoomType,
+};
+use crate::models::booking_note::{BookingNote, BookingNoteResponse, CreateBookingNoteRequest};
+use crate::routes::coupons::notify_coupon_assignment;
+use crate::services::booking::{has_verified_payment, GuestCount};
+use crate::services::loyalty::{
+    grant_tier_upgrade_coupon, hold_pending_points, lock_current_tier, notify_stay_milestone,
+    record_stay_milestone, StayMilestone, TierUpgradeGrant,
+};
 use crate::state::AppState;
 
 // ==================== REQUEST/RESPONSE TYPES ====================
@@ -32,6 +41,10 @@ pub struct PaginationQuery {
     #[serde(default = "default_limit")]
     pub limit: i32,
     pub status: Option<String>,
+    /// Filter by booking source (`direct`, `ota`, `travel_agent`, `corporate`)
+    pub source: Option<String>,
+    /// Filter by booking channel (`web`, `app`, `phone`, `walk_in`, `email`)
+    pub channel: Option<String>,
 }
 
 impl Default for PaginationQuery {
@@ -40,6 +53,8 @@ impl Default for PaginationQuery {
             page: default_page(),
             limit: default_limit(),
             status: None,
+            source: None,
+            channel: None,
         }
     }
 }
@@ -52,12 +67,16 @@ fn default_limit() -> i32 {
     20
 }
 
-/// Availability check query parameters
+/// Availability check query parameters. The snake_case spellings
+/// (`check_in`, `check_out`, `room_type`) are accepted too.
 #[derive(Debug, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct AvailabilityQuery {
+    #[serde(alias = "check_in")]
     pub check_in: NaiveDate,
+    #[serde(alias = "check_out")]
     pub check_out: NaiveDate,
+    #[serde(alias = "room_type")]
     pub room_type: Option<String>,
 }
 
@@ -68,9 +87,22 @@ pub struct CreateBookingRequest {
     pub check_in: NaiveDate,
     pub check_out: NaiveDate,
     pub room_type: Option<String>,
+    /// Number of adults (at least 1)
+    #[validate(range(min = 1, message = "At least 1 adult is required"))]
+    pub adults: Option<i32>,
+    /// Number of children (default: 0)
+    #[validate(range(min = 0, message = "Number of children cannot be negative"))]
+    pub children: Option<i32>,
+    /// Legacy total guest count, read as `adults` when `adults` is absent
     #[validate(range(min = 1, message = "At least 1 guest required"))]
-    pub guests: i32,
+    pub guests: Option<i32>,
     pub special_requests: Option<String>,
+    /// Who sold the stay (default: `direct`). Only staff can record
+    /// non-direct bookings.
+    pub source: Option<String>,
+    /// How the booking reached us (default: `web`). Guests can only use
+    /// `web` or `app`; the other channels are staff-entered.
+    pub channel: Option<String>,
 }
 
 /// Update booking request
@@ -80,6 +112,9 @@ pub struct UpdateBookingRequest {
     pub check_in: Option<NaiveDate>,
     pub check_out: Option<NaiveDate>,
     pub room_type: Option<String>,
+    pub adults: Option<i32>,
+    pub children: Option<i32>,
+    /// Legacy total guest count, read as `adults` when `adults` is absent
     pub guests: Option<i32>,
     pub special_requests: Option<String>,
 }
@@ -173,6 +208,8 @@ pub struct SuccessResponse {
 /// - page: Page number (default: 1)
 /// - limit: Items per page (default: 20, max: 100)
 /// - status: Filter by status (confirmed, cancelled, completed)
+/// - source: Filter by source (direct, ota, travel_agent, corporate)
+/// - channel: Filter by channel (web, app, phone, walk_in, email)
 async fn list_bookings(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
@@ -193,6 +230,17 @@ async fn list_bookings(
         }
     }
 
+    let source = params
+        .source
+        .as_deref()
+        .map(parse_booking_source)
+        .transpose()?;
+    let channel = params
+        .channel
+        .as_deref()
+        .map(parse_booking_channel)
+        .transpose()?;
+
     // Admin can see all bookings, regular users only see their own
     let is_admin = has_role(&auth_user, "admin");
     let user_id_filter = if is_admin {
@@ -205,14 +253,13 @@ async fn list_bookings(
     };
 
     // Query bookings from database
-    let (bookings, total) = query_bookings(
-        state.db(),
-        user_id_filter,
-        params.status.as_deref(),
-        limit,
-        offset,
-    )
-    .await?;
+    let filters = BookingListFilters {
+        user_id: user_id_filter,
+        status: params.status.as_deref(),
+        source,
+        channel,
+    };
+    let (bookings, total) = query_bookings(state.db(), &filters, limit, offset).await?;
 
     let total_pages = ((total as f64) / (limit as f64)).ceil() as i32;
 
@@ -255,8 +302,12 @@ async fn get_booking(
 /// - checkIn: Check-in date (YYYY-MM-DD)
 /// - checkOut: Check-out date (YYYY-MM-DD)
 /// - roomType: Room type (standard, deluxe, suite, etc.)
-/// - guests: Number of guests
+/// - adults: Number of adults (at least 1)
+/// - children: Optional number of children (default 0)
+/// - guests: Legacy total guest count, used as `adults` if `adults` is absent
 /// - specialRequests: Optional special requests
+/// - source: Optional booking source (staff only beyond `direct`)
+/// - channel: Optional booking channel (staff only beyond `web`/`app`)
 async fn create_booking(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
@@ -286,6 +337,35 @@ async fn create_booking(
     // Parse room type if provided
     let room_type = req.room_type.as_deref().map(parse_room_type).transpose()?;
 
+    let guests = GuestCount::new(
+        req.adults.or(req.guests).unwrap_or(0),
+        req.children.unwrap_or(0),
+    )?;
+
+    let source = req
+        .source
+        .as_deref()
+        .map(parse_booking_source)
+        .transpose()?
+        .unwrap_or_default();
+    let channel = req
+        .channel
+        .as_deref()
+        .map(parse_booking_channel)
+        .transpose()?
+        .unwrap_or_default();
+
+    // Guests book for themselves through the site or app; anything else
+    // is staff recording a stay sold elsewhere and would skew reporting
+    // if a guest could set it.
+    if !has_role(&auth_user, "admin")
+        && (source != BookingSource::Direct || !channel.is_self_service())
+    {
+        return Err(AppError::Forbidden(
+            "Only staff can record bookings from other sources or channels".to_string(),
+        ));
+    }
+
     // Create the booking
     let booking = insert_booking(
         state.db(),
@@ -293,8 +373,10 @@ async fn create_booking(
         req.check_in,
         req.check_out,
         room_type,
-        req.guests,
+        guests,
         req.special_requests,
+        source,
+        channel,
     )
     .await?;
 
@@ -357,6 +439,11 @@ async fn update_booking(
         None => existing.room_type,
     };
 
+    let guests = GuestCount::new(
+        req.adults.or(req.guests).or(existing.adults).unwrap_or(1),
+        req.children.or(existing.children).unwrap_or(0),
+    )?;
+
     // Update the booking
     let updated = update_booking_in_db(
         state.db(),
@@ -364,7 +451,7 @@ async fn update_booking(
         check_in,
         check_out,
         room_type,
-        req.guests.unwrap_or(existing.guest_count.unwrap_or(1)),
+        guests,
         req.special_requests.or(existing.special_requests),
     )
     .await?;
@@ -470,23 +557,63 @@ async fn complete_booking(
     // Mark as completed and award points
     let completed = complete_booking_in_db(state.db(), booking_id).await?;
 
-    // Award loyalty points (10 points per THB spent)
-    let points_to_award = (completed
+    // Award loyalty points (10 points per THB spent). Stays shorter than
+    // the configured minimum still get their nights credited, with zero
+    // points.
+    let spend_points = (completed
         .total_amount
         .to_string()
         .parse::<f64>()
         .unwrap_or(0.0)
         * 10.0) as i32;
+    let points_to_award = crate::services::loyalty::apply_min_nights_for_points(
+        spend_points,
+        completed.nights_count,
+        state.config().loyalty.min_nights_for_points,
+    );
+
+    // With pending points on, a stay whose payment isn't verified yet
+    // earns its nights now and its points once the slip is verified.
+    let loyalty_config = &state.config().loyalty;
+    let hold_points_days = if loyalty_config.pending_points_enabled
+        && !has_verified_payment(state.db(), booking_id).await?
+    {
+        Some(loyalty_config.pending_points_clear_days)
+    } else {
+        None
+    };
 
-    if points_to_award > 0 {
-        award_loyalty_points(
+    if spend_points > 0 {
+        let (upgrade_grant, stay_milestone) = award_loyalty_points(
             state.db(),
             completed.user_id,
             points_to_award,
             completed.nights_count,
             booking_id,
+            loyalty_config.win_back_after_days,
+            hold_points_days,
         )
         .await?;
+
+        if let Some(grant) = upgrade_grant {
+            notify_coupon_assignment(&state, grant.coupon_id, &[completed.user_id]).await;
+        }
+        if let Some(milestone) = stay_milestone {
+            notify_stay_milestone(state.db(), completed.user_id, milestone).await;
+        }
+    }
+
+    // A completed stay is the qualifying action for a pending referral.
+    // Best-effort: the booking is already completed, so a failure here is
+    // logged rather than turned into an error response.
+    if completed.nights_count > 0 {
+        if let Err(e) = qualify_referral_for_booking(&state, completed.user_id, booking_id).await {
+            tracing::warn!(
+                error = %e,
+                booking_id = %booking_id,
+                "Failed to qualify referral for completed booking"
+            );
+        }
     }
 
     tracing::info!(
@@ -621,6 +748,10 @@ async fn add_booking_slip(
 
     let slip = insert_booking_slip_tx(&mut tx, booking_id, &slip_url, auth_user_id).await?;
 
+    // An attached slip moves the booking from `unpaid` to
+    // `pending_verification`; SlipOK (below) or an admin takes it further.
+    crate::services::booking::mark_payment_pending_verification(&mut *tx, booking_id).await?;
+
     let response_body = serde_json::to_vec(&slip).map_err(|e| {
         AppError::Internal(format!("Failed to serialize booking slip response: {e}"))
     })?;
@@ -645,6 +776,8 @@ async fn add_booking_slip(
         "Booking slip added"
     );
 
+    spawn_slip_verification(&state, slip.id, booking_id, &slip_url);
+
     Ok((StatusCode::CREATED, Json(slip)))
 }
 
@@ -700,6 +833,12 @@ async fn delete_booking_slip(
 /// - checkIn: Check-in date (YYYY-MM-DD)
 /// - checkOut: Check-out date (YYYY-MM-DD)
 /// - roomType: Optional room type filter
+///
+/// A room type's inventory is its active rooms, as managed by admins. A
+/// room is taken when a booking that isn't cancelled overlaps the stay
+/// (`check_in < existing.check_out AND check_out > existing.check_in`,
+/// so a stay may start on the day another ends) or when it's blocked on
+/// any night of the stay.
 async fn check_availability(
     State(state): State<AppState>,
     Query(params): Query<AvailabilityQuery>,
@@ -725,6 +864,75 @@ async fn check_availability(
     Ok(Json(availability))
 }
 
+/// GET /api/bookings/:id/notes - List the notes on a booking
+///
+/// Staff see every note; the guest who owns the booking sees only notes
+/// with `guest` visibility. The filter is applied in the query, so an
+/// internal note is never loaded for a guest. Other users get 403.
+async fn list_booking_notes(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(booking_id): Path<Uuid>,
+) -> AppResult<Json<Vec<BookingNoteResponse>>> {
+    let booking = query_booking_by_id(state.db(), booking_id).await?;
+
+    let user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::InvalidToken("Invalid user ID in token".to_string()))?;
+    let is_staff = has_role(&auth_user, "admin");
+    if booking.user_id != user_id && !is_staff {
+        return Err(AppError::Forbidden(
+            "You can only view notes on your own bookings".to_string(),
+        ));
+    }
+
+    let notes = query_booking_notes(state.db(), booking_id, is_staff).await?;
+
+    Ok(Json(notes.into_iter().map(Into::into).collect()))
+}
+
+/// POST /api/bookings/:id/notes - Add a note to a booking (staff only)
+///
+/// Request body:
+/// - body: The note text (1-2000 characters)
+/// - visibility: `internal` (default, staff only) or `guest` (also shown
+///   to the booking's guest)
+///
+/// Returns 201 with the created note.
+async fn add_booking_note(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(booking_id): Path<Uuid>,
+    Json(req): Json<CreateBookingNoteRequest>,
+) -> AppResult<(StatusCode, Json<BookingNoteResponse>)> {
+    if !has_role(&auth_user, "admin") {
+        return Err(AppError::Forbidden(
+            "Only staff can add booking notes".to_string(),
+        ));
+    }
+
+    req.validate()?;
+    let body = req.body.trim();
+    if body.is_empty() {
+        return Err(AppError::Validation("Note cannot be empty".to_string()));
+    }
+
+    // Surfaces a 404 when the booking doesn't exist
+    query_booking_by_id(state.db(), booking_id).await?;
+
+    let author_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::InvalidToken("Invalid user ID in token".to_string()))?;
+    let note = insert_booking_note(state.db(), booking_id, author_id, &req, body).await?;
+
+    tracing::info!(
+        booking_id = %booking_id,
+        note_id = %note.id,
+        visibility = %req.visibility,
+        "Booking note added"
+    );
+
+    Ok((StatusCode::CREATED, Json(note.into())))
+}
+
 // ==================== DATABASE ROW TYPES ====================
 
 /// Database row for booking with room info
@@ -738,9 +946,14 @@ struct BookingRow {
     pub check_in_date: NaiveDate,
     pub check_out_date: NaiveDate,
     pub num_guests: i32,
+    pub adults: i32,
+    pub children: i32,
     pub total_price: Decimal,
     pub points_earned: Option<i32>,
     pub status: String,
+    pub payment_status: String,
+    pub source: String,
+    pub channel: String,
     pub cancelled_at: Option<DateTime<Utc>>,
     pub cancellation_reason: Option<String>,
     pub notes: Option<String>,
@@ -776,19 +989,32 @@ impl BookingRow {
             _ => BookingStatus::Confirmed,
         };
 
+        let payment_status = self
+            .payment_status
+            .parse::<PaymentStatus>()
+            .unwrap_or_default();
+        let source = self.source.parse::<BookingSource>().unwrap_or_default();
+        let channel = self.channel.parse::<BookingChannel>().unwrap_or_default();
+
         BookingResponse {
             id: self.id,
             user_id: self.user_id,
             booking_reference: format!("BK{}", self.id.to_string()[..8].to_uppercase()),
             status,
+            payment_status,
+            source,
+            channel,
             check_in_date: self.check_in_date,
             check_out_date: self.check_out_date,
             nights_count: nights,
             room_type,
             room_number: self.room_number,
             total_amount: self.total_price,
+            total_amount_display: crate::utils::format_money(self.total_price, "THB"),
             currency: "THB".to_string(),
             guest_count: Some(self.num_guests),
+            adults: Some(self.adults),
+            children: Some(self.children),
             special_requests: self.notes,
             confirmation_number: Some(format!("CNF{}", self.id.to_string()[..12].to_uppercase())),
             points_earned: self.points_earned,
@@ -804,148 +1030,76 @@ struct RoomTypeRow {
     pub id: Uuid,
     pub name: String,
     pub price_per_night: Decimal,
+    pub max_guests: i32,
 }
 
 // ==================== DATABASE OPERATIONS ====================
 
+/// Filters for [`query_bookings`]; `None` means "don't filter"
+struct BookingListFilters<'a> {
+    user_id: Option<Uuid>,
+    status: Option<&'a str>,
+    source: Option<BookingSource>,
+    channel: Option<BookingChannel>,
+}
+
 async fn query_bookings(
     db: &PgPool,
-    user_id: Option<Uuid>,
-    status: Option<&str>,
+    filters: &BookingListFilters<'_>,
     limit: i32,
     offset: i32,
 ) -> AppResult<(Vec<BookingResponse>, i64)> {
-    // Build query dynamically based on filters
-    let (bookings, total) = if let Some(uid) = user_id {
-        // User-specific query
-        if let Some(st) = status {
-            let total: (i64,) =
-                sqlx::query_as("SELECT COUNT(*) FROM bookings WHERE user_id = $1 AND status = $2")
-                    .bind(uid)
-                    .bind(st)
-                    .fetch_one(db)
-                    .await?;
-
-            let rows: Vec<BookingRow> = sqlx::query_as(
-                r#"
-                SELECT
-                    b.id, b.user_id, b.room_id, b.room_type_id,
-                    b.check_in_date, b.check_out_date, b.num_guests,
-                    b.total_price, b.points_earned, b.status,
-                    b.cancelled_at, b.cancellation_reason, b.notes,
-                    b.created_at, b.updated_at,
-                    r.room_number, rt.name as room_type_name
-                FROM bookings b
-                LEFT JOIN rooms r ON b.room_id = r.id
-                LEFT JOIN room_types rt ON b.room_type_id = rt.id
-                WHERE b.user_id = $1 AND b.status = $2
-                ORDER BY b.created_at DESC
-                LIMIT $3 OFFSET $4
-                "#,
-            )
-            .bind(uid)
-            .bind(st)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(db)
-            .await?;
-
-            (rows, total.0)
-        } else {
-            let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM bookings WHERE user_id = $1")
-                .bind(uid)
-                .fetch_one(db)
-                .await?;
-
-            let rows: Vec<BookingRow> = sqlx::query_as(
-                r#"
-                SELECT
-                    b.id, b.user_id, b.room_id, b.room_type_id,
-                    b.check_in_date, b.check_out_date, b.num_guests,
-                    b.total_price, b.points_earned, b.status,
-                    b.cancelled_at, b.cancellation_reason, b.notes,
-                    b.created_at, b.updated_at,
-                    r.room_number, rt.name as room_type_name
-                FROM bookings b
-                LEFT JOIN rooms r ON b.room_id = r.id
-                LEFT JOIN room_types rt ON b.room_type_id = rt.id
-                WHERE b.user_id = $1
-                ORDER BY b.created_at DESC
-                LIMIT $2 OFFSET $3
-                "#,
-            )
-            .bind(uid)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(db)
-            .await?;
-
-            (rows, total.0)
-        }
-    } else {
-        // Admin query - all bookings
-        if let Some(st) = status {
-            let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM bookings WHERE status = $1")
-                .bind(st)
-                .fetch_one(db)
-                .await?;
-
-            let rows: Vec<BookingRow> = sqlx::query_as(
-                r#"
-                SELECT
-                    b.id, b.user_id, b.room_id, b.room_type_id,
-                    b.check_in_date, b.check_out_date, b.num_guests,
-                    b.total_price, b.points_earned, b.status,
-                    b.cancelled_at, b.cancellation_reason, b.notes,
-                    b.created_at, b.updated_at,
-                    r.room_number, rt.name as room_type_name
-                FROM bookings b
-                LEFT JOIN rooms r ON b.room_id = r.id
-                LEFT JOIN room_types rt ON b.room_type_id = rt.id
-                WHERE b.status = $1
-                ORDER BY b.created_at DESC
-                LIMIT $2 OFFSET $3
-                "#,
-            )
-            .bind(st)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(db)
-            .await?;
-
-            (rows, total.0)
-        } else {
-            let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM bookings")
-                .fetch_one(db)
-                .await?;
-
-            let rows: Vec<BookingRow> = sqlx::query_as(
-                r#"
-                SELECT
-                    b.id, b.user_id, b.room_id, b.room_type_id,
-                    b.check_in_date, b.check_out_date, b.num_guests,
-                    b.total_price, b.points_earned, b.status,
-                    b.cancelled_at, b.cancellation_reason, b.notes,
-                    b.created_at, b.updated_at,
-                    r.room_number, rt.name as room_type_name
-                FROM bookings b
-                LEFT JOIN rooms r ON b.room_id = r.id
-                LEFT JOIN room_types rt ON b.room_type_id = rt.id
-                ORDER BY b.created_at DESC
-                LIMIT $1 OFFSET $2
-                "#,
-            )
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(db)
+    // Every filter is a `$N IS NULL OR ...` guard so one static query
+    // covers all combinations.
+    const WHERE_CLAUSE: &str = r#"
+        WHERE ($1::uuid IS NULL OR b.user_id = $1)
+          AND ($2::text IS NULL OR b.status = $2)
+          AND ($3::text IS NULL OR b.source = $3)
+          AND ($4::text IS NULL OR b.channel = $4)
+    "#;
+
+    let source = filters.source.map(|s| s.as_str());
+    let channel = filters.channel.map(|c| c.as_str());
+
+    let total: (i64,) =
+        sqlx::query_as(&format!("SELECT COUNT(*) FROM bookings b {}", WHERE_CLAUSE))
+            .bind(filters.user_id)
+            .bind(filters.status)
+            .bind(source)
+            .bind(channel)
+            .fetch_one(db)
             .await?;
 
-            (rows, total.0)
-        }
-    };
+    let rows: Vec<BookingRow> = sqlx::query_as(&format!(
+        r#"
+        SELECT
+            b.id, b.user_id, b.room_id, b.room_type_id,
+            b.check_in_date, b.check_out_date, b.num_guests, b.adults, b.children,
+            b.total_price, b.points_earned, b.status, b.payment_status,
+            b.source, b.channel,
+            b.cancelled_at, b.cancellation_reason, b.notes,
+            b.created_at, b.updated_at,
+            r.room_number, rt.name as room_type_name
+        FROM bookings b
+        LEFT JOIN rooms r ON b.room_id = r.id
+        LEFT JOIN room_types rt ON b.room_type_id = rt.id
+        {}
+        ORDER BY b.created_at DESC
+        LIMIT $5 OFFSET $6
+        "#,
+        WHERE_CLAUSE
+    ))
+    .bind(filters.user_id)
+    .bind(filters.status)
+    .bind(source)
+    .bind(channel)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?;
 
-    let responses: Vec<BookingResponse> = bookings.into_iter().map(|r| r.into_response()).collect();
-    Ok((responses, total))
+    let responses: Vec<BookingResponse> = rows.into_iter().map(|r| r.into_response()).collect();
+    Ok((responses, total.0))
 }
 
 async fn query_booking_by_id(db: &PgPool, booking_id: Uuid) -> AppResult<BookingResponse> {
@@ -953,8 +1107,9 @@ async fn query_booking_by_id(db: &PgPool, booking_id: Uuid) -> AppResult<Booking
         r#"
         SELECT
             b.id, b.user_id, b.room_id, b.room_type_id,
-            b.check_in_date, b.check_out_date, b.num_guests,
-            b.total_price, b.points_earned, b.status,
+            b.check_in_date, b.check_out_date, b.num_guests, b.adults, b.children,
+            b.total_price, b.points_earned, b.status, b.payment_status,
+            b.source, b.channel,
             b.cancelled_at, b.cancellation_reason, b.notes,
             b.created_at, b.updated_at,
             r.room_number, rt.name as room_type_name
@@ -983,14 +1138,17 @@ async fn query_booking_by_id(db: &PgPool, booking_id: Uuid) -> AppResult<Booking
 /// takes `SELECT ... FOR UPDATE` on the room row, so the *common* case
 /// hands the loser a clean 409 Conflict rather than relying on the
 /// constraint violation to bubble up.
+#[allow(clippy::too_many_arguments)]
 async fn insert_booking(
     db: &PgPool,
     user_id: Uuid,
     check_in: NaiveDate,
     check_out: NaiveDate,
     room_type: Option<RoomType>,
-    guests: i32,
+    guests: GuestCount,
     special_requests: Option<String>,
+    source: BookingSource,
+    channel: BookingChannel,
 ) -> AppResult<BookingResponse> {
     // Get room type info and find an available room
     let room_type_name = room_type
@@ -1000,7 +1158,7 @@ async fn insert_booking(
     // Find the room type (read-only, safe to do outside the transaction)
     let room_type_row: RoomTypeRow = sqlx::query_as(
         r#"
-        SELECT id, name, price_per_night
+        SELECT id, name, price_per_night, max_guests
         FROM room_types
         WHERE LOWER(name) = LOWER($1) AND is_active = true
         "#,
@@ -1010,6 +1168,8 @@ async fn insert_booking(
     .await?
     .ok_or_else(|| AppError::BadRequest(format!("Room type '{}' not found", room_type_name)))?;
 
+    guests.ensure_fits(&room_type_row.name, room_type_row.max_guests)?;
+
     let mut tx = db.begin().await?;
 
     // Find and lock an available room of this type. The `FOR UPDATE` on
@@ -1067,11 +1227,12 @@ async fn insert_booking(
     // it to a 409 Conflict so the client can retry.
     let row: Result<BookingRow, sqlx::Error> = sqlx::query_as(
         r#"
-        INSERT INTO bookings (user_id, room_id, room_type_id, check_in_date, check_out_date, num_guests, total_price, notes, status)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'confirmed')
+        INSERT INTO bookings (user_id, room_id, room_type_id, check_in_date, check_out_date, num_guests, adults, children, total_price, notes, status, source, channel)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, 'confirmed', $11, $12)
         RETURNING
             id, user_id, room_id, room_type_id, check_in_date, check_out_date,
-            num_guests, total_price, points_earned, status, cancelled_at,
+            num_guests, adults, children, total_price, points_earned, status, payment_status,
+            source, channel, cancelled_at,
             cancellation_reason, notes, created_at, updated_at,
             NULL::varchar as room_number, NULL::varchar as room_type_name
         "#
@@ -1081,9 +1242,13 @@ async fn insert_booking(
     .bind(room_type_row.id)
     .bind(check_in)
     .bind(check_out)
-    .bind(guests)
+    .bind(guests.total())
+    .bind(guests.adults)
+    .bind(guests.children)
     .bind(total_price)
     .bind(&special_requests)
+    .bind(source.as_str())
+    .bind(channel.as_str())
     .fetch_one(&mut *tx)
     .await;
 
@@ -1115,7 +1280,7 @@ async fn update_booking_in_db(
     check_in: NaiveDate,
     check_out: NaiveDate,
     room_type: Option<RoomType>,
-    guests: i32,
+    guests: GuestCount,
     special_requests: Option<String>,
 ) -> AppResult<BookingResponse> {
     // Get current booking
@@ -1129,7 +1294,7 @@ async fn update_booking_in_db(
 
         let room_type_row: RoomTypeRow = sqlx::query_as(
             r#"
-            SELECT id, name, price_per_night
+            SELECT id, name, price_per_night, max_guests
             FROM room_types
             WHERE LOWER(name) = LOWER($1) AND is_active = true
             "#,
@@ -1139,6 +1304,8 @@ async fn update_booking_in_db(
         .await?
         .ok_or_else(|| AppError::BadRequest(format!("Room type '{}' not found", room_type_name)))?;
 
+        guests.ensure_fits(&room_type_row.name, room_type_row.max_guests)?;
+
         // Find available room (excluding current booking)
         let room_id: Option<(Uuid,)> = sqlx::query_as(
             r#"
@@ -1176,9 +1343,9 @@ async fn update_booking_in_db(
         (room_id, room_type_row.id, total)
     } else {
         // Recalculate price with current room type
-        let room_info: (Uuid, Uuid, Decimal) = sqlx::query_as(
+        let room_info: (Uuid, Uuid, Decimal, String, i32) = sqlx::query_as(
             r#"
-            SELECT b.room_id, b.room_type_id, rt.price_per_night
+            SELECT b.room_id, b.room_type_id, rt.price_per_night, rt.name, rt.max_guests
             FROM bookings b
             JOIN room_types rt ON b.room_type_id = rt.id
             WHERE b.id = $1
@@ -1188,6 +1355,8 @@ async fn update_booking_in_db(
         .fetch_one(db)
         .await?;
 
+        guests.ensure_fits(&room_info.3, room_info.4)?;
+
         let nights = (check_out - check_in).num_days() as i32;
         let total = room_info.2 * Decimal::from(nights);
 
@@ -1199,7 +1368,8 @@ async fn update_booking_in_db(
         r#"
         UPDATE bookings
         SET room_id = $2, room_type_id = $3, check_in_date = $4, check_out_date = $5,
-            num_guests = $6, total_price = $7, notes = $8, updated_at = NOW()
+            num_guests = $6, adults = $7, children = $8, total_price = $9, notes = $10,
+            updated_at = NOW()
         WHERE id = $1
         "#,
     )
@@ -1208,7 +1378,9 @@ async fn update_booking_in_db(
     .bind(room_type_id)
     .bind(check_in)
     .bind(check_out)
-    .bind(guests)
+    .bind(guests.total())
+    .bind(guests.adults)
+    .bind(guests.children)
     .bind(total_price)
     .bind(&special_requests)
     .execute(db)
@@ -1267,7 +1439,7 @@ async fn check_room_availability(
         // Get room type info
         let room_type_info: Option<RoomTypeRow> = sqlx::query_as(
             r#"
-            SELECT id, name, price_per_night
+            SELECT id, name, price_per_night, max_guests
             FROM room_types
             WHERE LOWER(name) = LOWER($1) AND is_active = true
             "#,
@@ -1373,29 +1545,82 @@ async fn check_room_availability(
     }
 }
 
+/// Qualify the booking owner's pending referral (if any) in its own
+/// transaction, so the status flip and both bonus awards land together.
+async fn qualify_referral_for_booking(
+    state: &AppState,
+    user_id: Uuid,
+    booking_id: Uuid,
+) -> AppResult<()> {
+    let mut tx = state.db().begin().await?;
+    crate::services::referral::qualify_referral(
+        &mut tx,
+        user_id,
+        &format!("BOOKING-{}", booking_id),
+        &state.config().loyalty,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Credit a completed booking's points and nights in one transaction.
+///
+/// With `hold_points_days` the nights are credited but the points are held
+/// as pending (see [`hold_pending_points`]), auto-released after that many
+/// days. Returns the tier upgrade coupon granted if the nights moved the
+/// member up a tier, and the stay milestone (first stay / win-back) if
+/// any, so the caller can notify them once the award has committed.
 async fn award_loyalty_points(
     db: &PgPool,
     user_id: Uuid,
     points: i32,
     nights: i32,
     booking_id: Uuid,
-) -> AppResult<()> {
+    win_back_after_days: u32,
+    hold_points_days: Option<u32>,
+) -> AppResult<(Option<TierUpgradeGrant>, Option<StayMilestone>)> {
     let reference_id = format!("BOOKING-{}", booking_id);
+    let earned_points = points;
+
+    let mut tx = db.begin().await?;
+    let old_tier_id = lock_current_tier(&mut tx, user_id).await?;
+
+    // Held points go into `pending_points` instead of the balance
+    let points = match hold_points_days {
+        Some(clear_after_days) => {
+            hold_pending_points(
+                &mut *tx,
+                user_id,
+                booking_id,
+                points,
+                &reference_id,
+                clear_after_days,
+            )
+            .await?;
+            0
+        },
+        None => points,
+    };
 
     // Insert points transaction directly (avoids stored procedure type resolution issues)
-    sqlx::query(
+    let transaction_id: Uuid = sqlx::query_scalar(
         r#"
         INSERT INTO points_transactions (user_id, points, type, description, reference_id, nights_stayed)
         VALUES ($1, $2, 'earned_stay'::text::points_transaction_type, 'Points earned from booking', $3, $4)
+        RETURNING id
         "#,
     )
     .bind(user_id)
     .bind(points)
     .bind(&reference_id)
     .bind(nights)
-    .execute(db)
+    .fetch_one(&mut *tx)
     .await?;
 
+    let stay_milestone =
+        record_stay_milestone(&mut tx, user_id, transaction_id, win_back_after_days).await?;
+
     // Update user loyalty totals
     sqlx::query(
         r#"
@@ -1410,25 +1635,29 @@ async fn award_loyalty_points(
     .bind(user_id)
     .bind(points)
     .bind(nights)
-    .execute(db)
+    .execute(&mut *tx)
     .await?;
 
     // Recalculate tier if nights were awarded
     if nights > 0 {
         sqlx::query("SELECT * FROM recalculate_user_tier_by_nights($1)")
             .bind(user_id)
-            .execute(db)
+            .execute(&mut *tx)
             .await?;
     }
 
-    // Update the booking with points earned
+    // Update the booking with points earned, held or not
     sqlx::query("UPDATE bookings SET points_earned = $2 WHERE id = $1")
         .bind(booking_id)
-        .bind(points)
-        .execute(db)
+        .bind(earned_points)
+        .execute(&mut *tx)
         .await?;
 
-    Ok(())
+    let upgrade_grant = grant_tier_upgrade_coupon(&mut tx, user_id, old_tier_id).await?;
+
+    tx.commit().await?;
+
+    Ok((upgrade_grant, stay_milestone))
 }
 
 /// Raw `booking_slips` row used by slip insert/query/delete helpers.
@@ -1537,8 +1766,105 @@ async fn delete_booking_slip_by_id(db: &PgPool, slip_id: Uuid) -> AppResult<()>
     Ok(())
 }
 
+/// Fetch a booking's notes, oldest first. Internal notes are only included
+/// when `include_internal` is set, i.e. for staff.
+async fn query_booking_notes(
+    db: &PgPool,
+    booking_id: Uuid,
+    include_internal: bool,
+) -> AppResult<Vec<BookingNote>> {
+    let notes = sqlx::query_as::<_, BookingNote>(
+        r#"
+        SELECT id, booking_id, author_id, visibility, body, created_at
+        FROM booking_notes
+        WHERE booking_id = $1
+          AND ($2 OR visibility = 'guest')
+        ORDER BY created_at, id
+        "#,
+    )
+    .bind(booking_id)
+    .bind(include_internal)
+    .fetch_all(db)
+    .await?;
+
+    Ok(notes)
+}
+
+/// Insert a booking note; `body` is the trimmed note text.
+async fn insert_booking_note(
+    db: &PgPool,
+    booking_id: Uuid,
+    author_id: Uuid,
+    req: &CreateBookingNoteRequest,
+    body: &str,
+) -> AppResult<BookingNote> {
+    let note = sqlx::query_as::<_, BookingNote>(
+        r#"
+        INSERT INTO booking_notes (booking_id, author_id, visibility, body)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, booking_id, author_id, visibility, body, created_at
+        "#,
+    )
+    .bind(booking_id)
+    .bind(author_id)
+    .bind(req.visibility.as_str())
+    .bind(body)
+    .fetch_one(db)
+    .await?;
+
+    Ok(note)
+}
+
 // ==================== HELPER FUNCTIONS ====================
 
+/// Run SlipOK verification for a freshly attached slip in the background.
+///
+/// No-op when SlipOK isn't configured or the slip file can't be resolved;
+/// the booking then stays `pending_verification` until an admin reviews it.
+/// Failures are logged rather than surfaced — the slip was already saved
+/// and the client has its 201.
+fn spawn_slip_verification(state: &AppState, slip_id: Uuid, booking_id: Uuid, slip_url: &str) {
+    let slipok = crate::services::slipok::SlipOKService::from_env()
+        .with_result_cache(state.redis(), state.config().slipok.result_cache_ttl_secs);
+    if !slipok.is_configured() {
+        return;
+    }
+
+    let db = state.db().clone();
+    let webhooks = state.config().webhooks.clone();
+
+    let Some(path) = crate::routes::slips::slip_file_path(slip_url) else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let image = match tokio::fs::read(&path).await {
+            Ok(data) => bytes::Bytes::from(data),
+            Err(e) => {
+                tracing::warn!(
+                    slip_id = %slip_id,
+                    error = %e,
+                    "Could not read slip file for SlipOK verification"
+                );
+                return;
+            },
+        };
+
+        if let Err(e) = crate::services::booking::verify_booking_slip(
+            &db, &slipok, slip_id, booking_id, image, &webhooks, false,
+        )
+        .await
+        {
+            tracing::warn!(
+                slip_id = %slip_id,
+                booking_id = %booking_id,
+                error = %e,
+                "SlipOK verification failed"
+            );
+        }
+    });
+}
+
 /// Parse room type string to enum
 fn parse_room_type(room_type: &str) -> AppResult<RoomType> {
     match room_type.to_lowercase().as_str() {
@@ -1554,6 +1880,26 @@ fn parse_room_type(room_type: &str) -> AppResult<RoomType> {
     }
 }
 
+fn parse_booking_source(source: &str) -> AppResult<BookingSource> {
+    source.parse().map_err(|_| {
+        AppError::Validation(format!(
+            "Invalid source '{}'. Valid sources: {}",
+            source,
+            BookingSource::ALL.map(|s| s.as_str()).join(", ")
+        ))
+    })
+}
+
+fn parse_booking_channel(channel: &str) -> AppResult<BookingChannel> {
+    channel.parse().map_err(|_| {
+        AppError::Validation(format!(
+            "Invalid channel '{}'. Valid channels: {}",
+            channel,
+            BookingChannel::ALL.map(|c| c.as_str()).join(", ")
+        ))
+    })
+}
+
 // ==================== ROUTER ====================
 
 /// Create booking routes
@@ -1574,6 +1920,8 @@ pub fn routes() -> Router<AppState> {
 /// - POST /api/bookings/:id/cancel - Cancel a booking
 /// - POST /api/bookings/:id/slips - Attach a payment slip URL to a booking
 /// - DELETE /api/bookings/slips/:slip_id - Remove a payment slip
+/// - GET /api/bookings/:id/notes - List booking notes (guests: guest-visible only)
+/// - POST /api/bookings/:id/notes - Add a booking note (staff only)
 /// - POST /api/bookings/:id/complete - Mark booking as completed (admin only)
 /// - GET /api/bookings/availability - Check room availability
 ///
@@ -1593,7 +1941,9 @@ pub fn router() -> Router<AppState> {
         // Slip routes are flat (not nested under booking id) to match the
         // contract the frontend already calls: `DELETE /api/bookings/slips/:id`.
         .route("/slips/:slip_id", delete(delete_booking_slip))
+        .route("/:id/notes", get(list_booking_notes))
         // Admin routes
+        .route("/:id/notes", post(add_booking_note))
         .route("/:id/complete", post(complete_booking))
         // Apply authentication middleware to all routes
         .layer(middleware::from_fn(auth_middleware))
@@ -1620,6 +1970,8 @@ pub fn router_without_auth() -> Router<AppState> {
         .route("/:id/cancel", post(cancel_booking))
         .route("/:id/slips", post(add_booking_slip))
         .route("/slips/:slip_id", delete(delete_booking_slip))
+        .route("/:id/notes", get(list_booking_notes))
+        .route("/:id/notes", post(add_booking_note))
         .route("/:id/complete", post(complete_booking))
 }
 
@@ -1657,5 +2009,27 @@ mod tests {
         assert_eq!(query.page, 1);
         assert_eq!(query.limit, 20);
         assert!(query.status.is_none());
+        assert!(query.source.is_none());
+        assert!(query.channel.is_none());
+    }
+
+    #[test]
+    fn test_parse_booking_source_and_channel() {
+        assert!(matches!(
+            parse_booking_source("OTA"),
+            Ok(BookingSource::Ota)
+        ));
+        assert!(matches!(
+            parse_booking_channel("walk_in"),
+            Ok(BookingChannel::WalkIn)
+        ));
+        assert!(matches!(
+            parse_booking_source("expedia"),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            parse_booking_channel("fax"),
+            Err(AppError::Validation(_))
+        ));
     }
 }
@@ -5,7 +5,7 @@
 
 use axum::{
     extract::{Extension, Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
     middleware,
     routing::{delete, get, post, put},
     Json, Router,
@@ -16,13 +16,29 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::db::timed_query;
 use crate::error::{AppError, AppResult};
-use crate::middleware::auth::{auth_middleware, require_role, AuthUser};
+use crate::middleware::auth::{
+    auth_middleware, has_role, require_recent_auth, require_role, AuthUser,
+};
+use crate::middleware::transaction::{transaction_layer, Tx};
 use crate::models::coupon::{
     CouponResponse, CouponStatus, CouponType, CreateCouponRequest, UpdateCouponRequest,
     UserCouponResponse, UserCouponStatus,
 };
+use crate::models::notification::{NotificationPriority, NotificationType};
+use crate::services::coupon::{
+    apply_coupon_stack, coupon_expired, find_segment_members, generate_unique_coupon_code,
+    increment_coupon_usage, render_qr_png, resolve_coupon_window, validate_coupon_stack,
+    validate_coupon_terms, CouponSegment, CouponTerms, StackedCoupon, StackedDiscount,
+};
+use crate::services::email::{email_service_for, templates as email_templates};
+use crate::services::notification::{
+    email_recipients, is_bulk_send, spawn_notification_emails, CreateNotificationDto,
+    NotificationEmail, NotificationService, NotificationServiceImpl, NotificationThrottle,
+};
 use crate::state::AppState;
+use crate::utils::{format_money, search_pattern};
 
 // ============================================================================
 // Helper functions for parsing enum strings from compile-time macros
@@ -100,6 +116,13 @@ pub struct ListUserCouponsQuery {
     pub user_id: Option<String>,
 }
 
+/// Query parameters for a user coupon QR image
+#[derive(Debug, Deserialize, Default)]
+pub struct QrImageQuery {
+    /// Width and height in pixels (default 256, clamped to 64-1024)
+    pub size: Option<u32>,
+}
+
 /// Query parameters for analytics data
 #[derive(Debug, Deserialize, Default)]
 pub struct AnalyticsDataQuery {
@@ -127,6 +150,41 @@ pub struct AssignCouponRequest {
     /// Custom expiry date
     #[serde(rename = "customExpiry")]
     pub custom_expiry: Option<DateTime<Utc>>,
+    /// Notify recipients ("You've received a coupon") in-app and by email
+    #[serde(rename = "notifyUsers", default)]
+    pub notify_users: bool,
+}
+
+/// Request to assign a coupon to every member of a tier or segment
+#[derive(Debug, Deserialize)]
+pub struct AssignCouponBySegmentRequest {
+    /// Coupon ID to assign
+    #[serde(rename = "couponId")]
+    pub coupon_id: Uuid,
+    /// Tier names to target (e.g. `["Gold"]`); empty targets any tier
+    #[serde(default)]
+    pub tiers: Vec<String>,
+    /// Further criteria (`minNights`, `lastStayWithinDays`, `tiers`), see
+    /// `CouponSegment`
+    pub segment: Option<serde_json::Value>,
+    /// Reason for assignment
+    #[serde(rename = "assignedReason")]
+    pub assigned_reason: Option<String>,
+    /// Custom expiry date
+    #[serde(rename = "customExpiry")]
+    pub custom_expiry: Option<DateTime<Utc>>,
+    /// Notify recipients ("You've received a coupon") in-app and by email
+    #[serde(rename = "notifyUsers", default)]
+    pub notify_users: bool,
+}
+
+/// Outcome of a segment assignment
+#[derive(Debug, Serialize)]
+pub struct SegmentAssignmentResult {
+    /// Members who received the coupon
+    pub assigned: usize,
+    /// Members in the segment who already held it
+    pub skipped: usize,
 }
 
 /// Request to redeem a coupon
@@ -147,9 +205,80 @@ pub struct RedeemCouponRequest {
     pub location: Option<String>,
     /// Additional metadata
     pub metadata: Option<serde_json::Value>,
+    /// The order's items; required for BOGO coupons, which make the
+    /// cheaper item of each pair free
+    #[serde(rename = "lineItems")]
+    #[validate(nested)]
+    pub line_items: Option<Vec<LineItem>>,
+}
+
+/// One line of an order, for coupons whose discount depends on the items
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct LineItem {
+    /// What was bought, kept in the redemption details
+    pub name: Option<String>,
+    /// Price of one unit
+    #[serde(rename = "unitPrice")]
+    #[validate(custom(function = "validate_non_negative_decimal"))]
+    pub unit_price: Decimal,
+    /// Number of units (default 1)
+    #[serde(default = "default_line_item_quantity")]
+    #[validate(range(min = 1, max = 1000, message = "Quantity must be between 1 and 1000"))]
+    pub quantity: u32,
+}
+
+fn default_line_item_quantity() -> u32 {
+    1
+}
+
+/// Request to preview a coupon's discount without redeeming it
+#[derive(Debug, Deserialize, Validate)]
+pub struct CalculateCouponRequest {
+    /// QR code of the user coupon
+    #[serde(rename = "qrCode")]
+    #[validate(length(min = 1, message = "QR code is required"))]
+    pub qr_code: String,
+    /// Order total before discount
+    #[serde(rename = "originalAmount")]
+    #[validate(custom(function = "validate_positive_decimal"))]
+    pub original_amount: Decimal,
+    /// The order's items; required for BOGO coupons
+    #[serde(rename = "lineItems")]
+    #[validate(nested)]
+    pub line_items: Option<Vec<LineItem>>,
+}
+
+/// Request to redeem several coupons on one transaction
+#[derive(Debug, Deserialize, Validate)]
+pub struct RedeemMultipleCouponsRequest {
+    /// QR codes of the user coupons to combine
+    #[serde(rename = "qrCodes")]
+    #[validate(length(min = 1, max = 10, message = "Must specify between 1 and 10 QR codes"))]
+    pub qr_codes: Vec<String>,
+    /// Original amount before discount
+    #[serde(rename = "originalAmount")]
+    #[validate(custom(function = "validate_positive_decimal"))]
+    pub original_amount: Decimal,
+    /// Transaction reference
+    #[serde(rename = "transactionReference")]
+    pub transaction_reference: Option<String>,
+    /// Location of redemption
+    pub location: Option<String>,
+    /// Additional metadata
+    pub metadata: Option<serde_json::Value>,
 }
 
 /// Custom validator for positive Decimal values
+fn validate_non_negative_decimal(value: &Decimal) -> Result<(), validator::ValidationError> {
+    if *value >= Decimal::ZERO {
+        Ok(())
+    } else {
+        let mut err = validator::ValidationError::new("range");
+        err.message = Some(std::borrow::Cow::Borrowed("Unit price cannot be negative"));
+        Err(err)
+    }
+}
+
 fn validate_positive_decimal(value: &Decimal) -> Result<(), validator::ValidationError> {
     let min = Decimal::new(1, 2); // 0.01
     if *value >= min {
@@ -246,6 +375,65 @@ pub struct RedemptionResult {
     pub discount_amount: Decimal,
     #[serde(rename = "finalAmount")]
     pub final_amount: Decimal,
+    /// Display strings for the amounts above in the coupon's currency.
+    /// Additive only — the numeric fields remain the source of truth.
+    #[serde(rename = "originalAmountDisplay")]
+    pub original_amount_display: String,
+    #[serde(rename = "discountAmountDisplay")]
+    pub discount_amount_display: String,
+    #[serde(rename = "finalAmountDisplay")]
+    pub final_amount_display: String,
+    /// What a non-monetary coupon gives (a free upgrade or service), or
+    /// how many items a BOGO coupon made free
+    #[serde(rename = "benefitDescription", skip_serializing_if = "Option::is_none")]
+    pub benefit_description: Option<String>,
+}
+
+/// What redeeming a coupon on an order would do, from `POST /coupons/calculate`
+#[derive(Debug, Serialize)]
+pub struct CouponCalculation {
+    /// Whether the coupon could be redeemed on this order right now
+    pub applicable: bool,
+    /// Why not, when it isn't
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(rename = "originalAmount")]
+    pub original_amount: Decimal,
+    /// Zero when the coupon isn't applicable
+    #[serde(rename = "discountAmount")]
+    pub discount_amount: Decimal,
+    #[serde(rename = "finalAmount")]
+    pub final_amount: Decimal,
+    #[serde(rename = "originalAmountDisplay")]
+    pub original_amount_display: String,
+    #[serde(rename = "discountAmountDisplay")]
+    pub discount_amount_display: String,
+    #[serde(rename = "finalAmountDisplay")]
+    pub final_amount_display: String,
+    /// Same as [`RedemptionResult::benefit_description`]
+    #[serde(rename = "benefitDescription", skip_serializing_if = "Option::is_none")]
+    pub benefit_description: Option<String>,
+}
+
+/// Result of redeeming several coupons together
+#[derive(Debug, Serialize)]
+pub struct MultiRedemptionResult {
+    pub success: bool,
+    pub message: String,
+    #[serde(rename = "originalAmount")]
+    pub original_amount: Decimal,
+    #[serde(rename = "discountAmount")]
+    pub discount_amount: Decimal,
+    #[serde(rename = "finalAmount")]
+    pub final_amount: Decimal,
+    #[serde(rename = "originalAmountDisplay")]
+    pub original_amount_display: String,
+    #[serde(rename = "discountAmountDisplay")]
+    pub discount_amount_display: String,
+    #[serde(rename = "finalAmountDisplay")]
+    pub final_amount_display: String,
+    /// Each coupon's discount, in the order it was applied
+    pub coupons: Vec<StackedDiscount>,
 }
 
 /// Coupon statistics response
@@ -307,12 +495,14 @@ async fn list_coupons(
 
     let is_admin = user.role == "admin" || user.role == "super_admin";
 
+    // Code/name search; short terms are rejected (SEARCH_MIN_LENGTH)
+    let search_filter = search_pattern(query.search.as_deref(), state.config().search.min_length)?;
+
     // Build query based on role
     let (coupons, total): (Vec<CouponResponse>, i64) = if is_admin {
         // Admin can see all coupons and filter by status
         let status_filter = query.status.as_deref();
         let type_filter = query.coupon_type.as_deref();
-        let search_filter = query.search.as_deref().map(|s| format!("%{}%", s));
 
         let rows = sqlx::query!(
             r#"
@@ -394,7 +584,6 @@ async fn list_coupons(
     } else {
         // Regular users can only see active coupons
         let type_filter = query.coupon_type.as_deref();
-        let search_filter = query.search.as_deref().map(|s| format!("%{}%", s));
 
         let rows = sqlx::query!(
             r#"
@@ -512,8 +701,10 @@ async fn get_user_coupons(
 
     let status_filter = query.status.as_deref();
 
-    let rows = sqlx::query!(
-        r#"
+    let rows = timed_query(
+        "coupons.user_coupons",
+        sqlx::query!(
+            r#"
         SELECT
             uc.id,
             uc.user_id,
@@ -540,12 +731,13 @@ async fn get_user_coupons(
         ORDER BY uc.created_at DESC
         LIMIT $3 OFFSET $4
         "#,
-        user_uuid,
-        status_filter,
-        limit as i64,
-        offset,
+            user_uuid,
+            status_filter,
+            limit as i64,
+            offset,
+        )
+        .fetch_all(state.db()),
     )
-    .fetch_all(state.db())
     .await?;
 
     let user_coupons: Vec<UserCouponWithDetails> = rows
@@ -669,13 +861,16 @@ async fn get_coupon(
 async fn create_coupon(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
-    Json(request): Json<CreateCouponRequest>,
+    Json(mut request): Json<CreateCouponRequest>,
 ) -> AppResult<(StatusCode, Json<SuccessResponse<CouponResponse>>)> {
     // Validate required fields
-    if request.code.is_empty() || request.name.is_empty() {
-        return Err(AppError::Validation(
-            "Code and name are required".to_string(),
-        ));
+    if request.name.is_empty() {
+        return Err(AppError::Validation("Name is required".to_string()));
+    }
+
+    // No code supplied: generate a unique one
+    if request.code.trim().is_empty() {
+        request.code = generate_unique_coupon_code(state.db(), &state.config().coupons).await?;
     }
 
     // Validate code format (uppercase letters, numbers, underscores, hyphens only)
@@ -690,46 +885,40 @@ async fn create_coupon(
         ));
     }
 
-    // Validate value for percentage and fixed_amount types
-    if matches!(
-        request.coupon_type,
-        CouponType::Percentage | CouponType::FixedAmount
-    ) {
-        match request.value {
-            None => {
-                return Err(AppError::Validation(
-                    "Value is required for percentage and fixed_amount coupons".to_string(),
-                ));
-            },
-            Some(v) if v <= Decimal::ZERO => {
-                return Err(AppError::Validation(
-                    "Value must be positive for percentage and fixed_amount coupons".to_string(),
-                ));
-            },
-            _ => {},
-        }
-    }
-
-    // Validate percentage value
-    if request.coupon_type == CouponType::Percentage {
-        if let Some(value) = request.value {
-            if value > Decimal::from(100) {
-                return Err(AppError::Validation(
-                    "Percentage value cannot exceed 100".to_string(),
-                ));
-            }
-        }
-    }
+    // Per-type value rules, allowed currencies, non-negative amounts and
+    // date ordering, reported per field. Local dates are converted with the
+    // hotel's UTC offset first.
+    let coupon_config = &state.config().coupons;
+    let (valid_from, valid_until) = resolve_coupon_window(
+        request.valid_from,
+        request.valid_from_date,
+        request.valid_until,
+        request.valid_until_date,
+        coupon_config.local_offset().map_err(AppError::Internal)?,
+    )?;
+    let currency = request
+        .currency
+        .clone()
+        .unwrap_or_else(|| coupon_config.default_currency.clone())
+        .to_ascii_uppercase();
+    validate_coupon_terms(
+        &CouponTerms {
+            coupon_type: request.coupon_type,
+            value: request.value,
+            currency: Some(&currency),
+            minimum_spend: request.minimum_spend,
+            maximum_discount: request.maximum_discount,
+            valid_from,
+            valid_until,
+        },
+        coupon_config,
+    )?;
 
     let user_uuid = Uuid::parse_str(&user.id)
         .map_err(|_| AppError::InvalidInput("Invalid user ID format".to_string()))?;
 
     let coupon_id = Uuid::new_v4();
     let status = request.status.unwrap_or(CouponStatus::Draft);
-    let currency = request
-        .currency
-        .clone()
-        .unwrap_or_else(|| "THB".to_string());
 
     // Note: Uses runtime query because bind params with enum type casts
     // ($6::coupon_type, $17::coupon_status) are not supported by compile-time macros
@@ -776,8 +965,8 @@ async fn create_coupon(
     .bind(&currency)
     .bind(request.minimum_spend)
     .bind(request.maximum_discount)
-    .bind(request.valid_from)
-    .bind(request.valid_until)
+    .bind(valid_from)
+    .bind(valid_until)
     .bind(request.usage_limit)
     .bind(request.usage_limit_per_user.unwrap_or(1))
     .bind(&request.tier_restrictions)
@@ -832,6 +1021,37 @@ async fn update_coupon(
         }
     }
 
+    let (valid_from, valid_until) = resolve_coupon_window(
+        request.valid_from,
+        request.valid_from_date,
+        request.valid_until,
+        request.valid_until_date,
+        state
+            .config()
+            .coupons
+            .local_offset()
+            .map_err(AppError::Internal)?,
+    )?;
+
+    // A new bound must still leave the window non-empty against the stored one
+    if valid_from.is_some() || valid_until.is_some() {
+        let (current_from, current_until): (Option<DateTime<Utc>>, Option<DateTime<Utc>>) =
+            sqlx::query_as("SELECT valid_from, valid_until FROM coupons WHERE id = $1")
+                .bind(coupon_id)
+                .fetch_optional(state.db())
+                .await?
+                .ok_or_else(|| AppError::NotFound("Coupon".to_string()))?;
+        if let (Some(from), Some(until)) =
+            (valid_from.or(current_from), valid_until.or(current_until))
+        {
+            if from >= until {
+                return Err(AppError::Validation(
+                    "Valid until must be after valid from".to_string(),
+                ));
+            }
+        }
+    }
+
     // Note: Uses runtime query because bind param $15 is Option<CouponStatus> (enum type)
     // which is not supported by compile-time macros
     let coupon = sqlx::query_as::<_, CouponResponse>(
@@ -882,8 +1102,8 @@ async fn update_coupon(
     .bind(&request.currency)
     .bind(request.minimum_spend)
     .bind(request.maximum_discount)
-    .bind(request.valid_from)
-    .bind(request.valid_until)
+    .bind(valid_from)
+    .bind(valid_until)
     .bind(request.usage_limit)
     .bind(request.usage_limit_per_user)
     .bind(&request.tier_restrictions)
@@ -904,9 +1124,11 @@ async fn update_coupon(
 /// DELETE /api/coupons/:id
 async fn delete_coupon(
     State(state): State<AppState>,
-    Extension(_user): Extension<AuthUser>,
+    Extension(user): Extension<AuthUser>,
     Path(coupon_id): Path<Uuid>,
 ) -> AppResult<Json<SuccessResponse<()>>> {
+    require_recent_auth(&user, state.config().auth.reauth_max_age_secs)?;
+
     let result = sqlx::query!("DELETE FROM coupons WHERE id = $1", coupon_id)
         .execute(state.db())
         .await?;
@@ -1017,6 +1239,11 @@ async fn assign_coupon(
         });
     }
 
+    if request.notify_users {
+        let recipients: Vec<Uuid> = assigned_coupons.iter().map(|uc| uc.user_id).collect();
+        notify_coupon_assignment(&state, request.coupon_id, &recipients).await;
+    }
+
     Ok(Json(SuccessResponse::with_message(
         assigned_coupons,
         format!(
@@ -1026,104 +1253,541 @@ async fn assign_coupon(
     )))
 }
 
-/// Redeem a coupon
+/// Default side of a QR image from `get_user_coupon_qr`, in pixels
+const QR_IMAGE_DEFAULT_SIZE: u32 = 256;
+
+/// Smallest and largest QR image sides a client may ask for
+const QR_IMAGE_SIZE_RANGE: std::ops::RangeInclusive<u32> = 64..=1024;
+
+/// Get a user coupon's QR code as a PNG image (owner or admin)
 ///
-/// POST /api/coupons/redeem
-async fn redeem_coupon(
+/// GET /api/coupons/user/:userCouponId/qr.png?size=256
+///
+/// The image encodes the stored `qr_code`, the same value staff scan for
+/// `POST /coupons/redeem`.
+async fn get_user_coupon_qr(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
-    Json(request): Json<RedeemCouponRequest>,
-) -> AppResult<Json<SuccessResponse<RedemptionResult>>> {
-    if request.qr_code.is_empty() {
-        return Err(AppError::Validation("QR code is required".to_string()));
+    Path(user_coupon_id): Path<Uuid>,
+    Query(query): Query<QrImageQuery>,
+) -> AppResult<axum::response::Response> {
+    let (owner_id, qr_code): (Uuid, String) =
+        sqlx::query_as("SELECT user_id, qr_code FROM user_coupons WHERE id = $1")
+            .bind(user_coupon_id)
+            .fetch_optional(state.db())
+            .await?
+            .ok_or_else(|| AppError::NotFound("User coupon".to_string()))?;
+
+    let user_uuid = Uuid::parse_str(&user.id)
+        .map_err(|_| AppError::InvalidToken("Invalid user ID in token".to_string()))?;
+    if owner_id != user_uuid && !has_role(&user, "admin") {
+        return Err(AppError::Forbidden(
+            "You can only view your own coupons".to_string(),
+        ));
     }
 
-    if request.original_amount <= Decimal::ZERO {
+    let size = query
+        .size
+        .unwrap_or(QR_IMAGE_DEFAULT_SIZE)
+        .clamp(*QR_IMAGE_SIZE_RANGE.start(), *QR_IMAGE_SIZE_RANGE.end());
+    let png = render_qr_png(&qr_code, size)?;
+
+    axum::response::Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "image/png")
+        // The code redeems the coupon, so shared caches must not keep it
+        .header(header::CACHE_CONTROL, "private, max-age=3600")
+        .body(axum::body::Body::from(png))
+        .map_err(|e| AppError::Internal(format!("Failed to build QR response: {}", e)))
+}
+
+/// Rows inserted per statement by `assign_coupon_by_segment`
+const SEGMENT_ASSIGN_BATCH_SIZE: usize = 500;
+
+/// Assign a coupon to every member of a tier or segment (admin only)
+///
+/// POST /api/coupons/assign-by-segment
+///
+/// The members are the ones matching both the request and the coupon's own
+/// `customer_segment`/`tier_restrictions`. Members who already hold the
+/// coupon are skipped, so repeating a campaign only reaches newcomers. All
+/// batches are inserted in one transaction.
+async fn assign_coupon_by_segment(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(request): Json<AssignCouponBySegmentRequest>,
+) -> AppResult<Json<SuccessResponse<SegmentAssignmentResult>>> {
+    let admin_uuid = Uuid::parse_str(&user.id)
+        .map_err(|_| AppError::InvalidInput("Invalid admin user ID format".to_string()))?;
+
+    let requested = CouponSegment::parse(request.segment.as_ref())?
+        .narrow(CouponSegment::default().with_tiers(request.tiers.clone()));
+    if requested.is_unrestricted() {
         return Err(AppError::Validation(
-            "Original amount must be positive".to_string(),
+            "Specify at least one tier or segment criterion".to_string(),
         ));
     }
 
-    let redeemer_uuid = Uuid::parse_str(&user.id)
-        .map_err(|_| AppError::InvalidInput("Invalid user ID format".to_string()))?;
+    if let Some(tiers) = &requested.tiers {
+        let known: Vec<String> = sqlx::query_scalar("SELECT name FROM tiers WHERE name = ANY($1)")
+            .bind(tiers)
+            .fetch_all(state.db())
+            .await?;
+        if let Some(unknown) = tiers.iter().find(|tier| !known.contains(tier)) {
+            return Err(AppError::Validation(format!("Unknown tier: {}", unknown)));
+        }
+    }
 
-    // Get user coupon by QR code with coupon details
-    let user_coupon = sqlx::query!(
+    let (status, valid_until, tier_restrictions, customer_segment): (
+        String,
+        Option<DateTime<Utc>>,
+        Option<serde_json::Value>,
+        Option<serde_json::Value>,
+    ) = sqlx::query_as(
         r#"
-        SELECT
-            uc.id,
-            uc.user_id,
-            uc.status::text as "status!",
-            uc.expires_at,
-            c.type::text as "coupon_type!",
-            c.value,
-            c.minimum_spend,
-            c.maximum_discount,
-            c.currency
-        FROM user_coupons uc
-        JOIN coupons c ON uc.coupon_id = c.id
-        WHERE uc.qr_code = $1
+        SELECT status::text, valid_until, tier_restrictions, customer_segment
+        FROM coupons
+        WHERE id = $1
         "#,
-        &request.qr_code,
     )
+    .bind(request.coupon_id)
     .fetch_optional(state.db())
     .await?
-    .ok_or_else(|| AppError::NotFound("Invalid QR code".to_string()))?;
-
-    // Check if coupon is available
-    if user_coupon.status != "available" {
-        return Err(AppError::Validation(format!(
-            "Coupon is not available for use (status: {})",
-            user_coupon.status
-        )));
+    .ok_or_else(|| AppError::NotFound("Coupon".to_string()))?;
+
+    if status != "active" {
+        return Err(AppError::Validation(
+            "Cannot assign inactive coupon".to_string(),
+        ));
+    }
+
+    let segment = requested.narrow(CouponSegment::for_coupon(
+        customer_segment.as_ref(),
+        tier_restrictions.as_ref(),
+    ));
+    let expires_at = request.custom_expiry.or(valid_until);
+
+    let mut tx = state.db().begin().await?;
+    let members = find_segment_members(&mut *tx, &segment).await?;
+
+    let mut assigned: Vec<Uuid> = Vec::with_capacity(members.len());
+    for batch in members.chunks(SEGMENT_ASSIGN_BATCH_SIZE) {
+        let ids: Vec<Uuid> = batch.iter().map(|_| Uuid::new_v4()).collect();
+        let qr_codes: Vec<String> = ids
+            .iter()
+            .map(|id| format!("QR-{}-{}", request.coupon_id, id))
+            .collect();
+
+        let inserted: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO user_coupons (
+                id, user_id, coupon_id, status, qr_code,
+                assigned_by, assigned_reason, expires_at, created_at, updated_at
+            )
+            SELECT m.id, m.user_id, $4, 'available', m.qr_code, $5, $6, $7, NOW(), NOW()
+            FROM UNNEST($1::uuid[], $2::uuid[], $3::text[]) AS m(id, user_id, qr_code)
+            WHERE NOT EXISTS (
+                SELECT 1 FROM user_coupons uc
+                WHERE uc.user_id = m.user_id AND uc.coupon_id = $4
+            )
+            RETURNING user_id
+            "#,
+        )
+        .bind(&ids)
+        .bind(batch)
+        .bind(&qr_codes)
+        .bind(request.coupon_id)
+        .bind(admin_uuid)
+        .bind(request.assigned_reason.as_deref())
+        .bind(expires_at)
+        .fetch_all(&mut *tx)
+        .await?;
+        assigned.extend(inserted);
     }
 
-    // Check if coupon has expired
-    if let Some(expires_at) = user_coupon.expires_at {
-        if expires_at < Utc::now() {
-            return Err(AppError::Validation("Coupon has expired".to_string()));
+    tx.commit().await?;
+
+    let result = SegmentAssignmentResult {
+        assigned: assigned.len(),
+        skipped: members.len() - assigned.len(),
+    };
+    tracing::info!(
+        coupon_id = %request.coupon_id,
+        assigned = result.assigned,
+        skipped = result.skipped,
+        "Coupon assigned by segment"
+    );
+
+    if request.notify_users && !assigned.is_empty() {
+        notify_coupon_assignment(&state, request.coupon_id, &assigned).await;
+    }
+
+    let message = format!(
+        "Coupon assigned to {} users ({} already had it)",
+        result.assigned, result.skipped
+    );
+    Ok(Json(SuccessResponse::with_message(result, message)))
+}
+
+/// Tell users a coupon was assigned to them ("You've received a coupon: X").
+///
+/// A single recipient gets an immediate notification and email. A bulk
+/// assignment (see `is_bulk_send`) writes all in-app notifications in one
+/// INSERT and hands the emails to a single paced background sender, so
+/// assigning to 100 users doesn't fire 100 SMTP sends at once.
+///
+/// Best-effort: the coupons are already assigned, so failures are logged
+/// rather than failing the request.
+pub(crate) async fn notify_coupon_assignment(state: &AppState, coupon_id: Uuid, user_ids: &[Uuid]) {
+    if user_ids.is_empty() {
+        return;
+    }
+
+    let coupon_name: String = match sqlx::query_scalar("SELECT name FROM coupons WHERE id = $1")
+        .bind(coupon_id)
+        .fetch_one(state.db())
+        .await
+    {
+        Ok(name) => name,
+        Err(e) => {
+            tracing::warn!(error = %e, coupon_id = %coupon_id, "Failed to load coupon for assignment notification");
+            return;
+        },
+    };
+
+    let message = format!("You've received a coupon: {}", coupon_name);
+    let mut items: Vec<CreateNotificationDto> = user_ids
+        .iter()
+        .map(|&user_id| CreateNotificationDto {
+            user_id,
+            title: "You've received a coupon".to_string(),
+            message: message.clone(),
+            notification_type: Some(NotificationType::Coupon),
+            priority: NotificationPriority::Normal,
+            category: Some("coupon".to_string()),
+            data: Some(serde_json::json!({ "couponId": coupon_id })),
+            expires_at: None,
+        })
+        .collect();
+
+    let service = NotificationServiceImpl::new(state.db().clone()).with_throttle(
+        NotificationThrottle::new(state.redis(), state.config().notification_throttle.clone()),
+    );
+    let created = if is_bulk_send(items.len()) {
+        service.create_notifications_batch(items).await.map(|_| ())
+    } else {
+        match items.pop() {
+            Some(item) => service.create_notification(item).await.map(|_| ()),
+            None => Ok(()),
         }
+    };
+    // A throttled notification has already been counted and logged
+    if let Err(e) = created.or_else(|e| match e {
+        AppError::RateLimitExceeded => Ok(()),
+        e => Err(e),
+    }) {
+        tracing::warn!(error = %e, coupon_id = %coupon_id, "Failed to create coupon assignment notifications");
     }
 
-    // Check minimum spend
-    if let Some(min_spend) = user_coupon.minimum_spend {
-        if request.original_amount < min_spend {
-            return Err(AppError::Validation(format!(
-                "Minimum spend of {} {} is required",
-                min_spend,
-                user_coupon.currency.as_deref().unwrap_or("THB")
-            )));
+    let recipients = match email_recipients(state.db(), user_ids).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to load coupon assignment email recipients");
+            return;
+        },
+    };
+
+    let email_service = email_service_for(state.config(), state.db());
+    let html_body = email_templates::coupon_assigned_template(
+        &coupon_name,
+        &state.config().server.frontend_url,
+    );
+    let emails = recipients
+        .into_iter()
+        .map(|(_, to)| NotificationEmail {
+            to,
+            subject: message.clone(),
+            html_body: html_body.clone(),
+        })
+        .collect();
+
+    spawn_notification_emails(email_service, emails);
+}
+
+/// A user coupon and the coupon terms redeeming it depends on
+#[derive(Debug, sqlx::FromRow)]
+struct RedeemableCoupon {
+    id: Uuid,
+    coupon_id: Uuid,
+    status: String,
+    expires_at: Option<DateTime<Utc>>,
+    coupon_type: String,
+    name: String,
+    description: Option<String>,
+    value: Option<Decimal>,
+    minimum_spend: Option<Decimal>,
+    maximum_discount: Option<Decimal>,
+    currency: Option<String>,
+}
+
+impl RedeemableCoupon {
+    fn currency(&self) -> &str {
+        self.currency.as_deref().unwrap_or("THB")
+    }
+
+    /// Why the coupon can't be redeemed on an order of `original_amount`,
+    /// if it can't
+    fn unusable_reason(&self, original_amount: Decimal) -> Option<String> {
+        if self.status != "available" {
+            return Some(format!(
+                "Coupon is not available for use (status: {})",
+                self.status
+            ));
+        }
+        if coupon_expired(self.expires_at, Utc::now()) {
+            return Some("Coupon has expired".to_string());
         }
+        self.minimum_spend
+            .filter(|min_spend| original_amount < *min_spend)
+            .map(|min_spend| {
+                format!(
+                    "Minimum spend of {} {} is required",
+                    min_spend,
+                    self.currency()
+                )
+            })
     }
+}
+
+/// Look up the user coupon with QR code `qr_code` for redemption
+async fn find_redeemable_coupon(state: &AppState, qr_code: &str) -> AppResult<RedeemableCoupon> {
+    timed_query(
+        "coupons.redeem_lookup",
+        sqlx::query_as::<_, RedeemableCoupon>(
+            r#"
+            SELECT
+                uc.id,
+                uc.coupon_id,
+                uc.status::text AS status,
+                uc.expires_at,
+                c.type::text AS coupon_type,
+                c.name,
+                c.description,
+                c.value,
+                c.minimum_spend,
+                c.maximum_discount,
+                c.currency
+            FROM user_coupons uc
+            JOIN coupons c ON uc.coupon_id = c.id
+            WHERE uc.qr_code = $1
+            "#,
+        )
+        .bind(qr_code)
+        .fetch_optional(state.db()),
+    )
+    .await?
+    .ok_or_else(|| AppError::NotFound("Invalid QR code".to_string()))
+}
+
+/// What redeeming one coupon on an order gives
+#[derive(Debug, Clone, PartialEq)]
+struct CouponBenefit {
+    discount_amount: Decimal,
+    final_amount: Decimal,
+    benefit_description: Option<String>,
+}
 
-    // Calculate discount
-    let discount_amount = match user_coupon.coupon_type.as_str() {
+/// Work out what `coupon` takes off an order of `original_amount`.
+///
+/// - Percentage: that share of the order, capped at `maximum_discount`
+/// - Fixed amount: the coupon's value
+/// - BOGO: the cheaper unit of each pair in `line_items` is free (see
+///   [`bogo_free_units`]), capped at `maximum_discount`; the line items are
+///   required
+/// - Free upgrade / free service: no money off, described in
+///   `benefit_description` from the coupon's description (or name)
+///
+/// The final amount never goes below zero.
+fn coupon_benefit(
+    coupon: &RedeemableCoupon,
+    original_amount: Decimal,
+    line_items: Option<&[LineItem]>,
+) -> AppResult<CouponBenefit> {
+    let cap = |discount: Decimal| match coupon.maximum_discount {
+        Some(max_discount) => discount.min(max_discount),
+        None => discount,
+    };
+    let described = || {
+        coupon
+            .description
+            .clone()
+            .unwrap_or_else(|| coupon.name.clone())
+    };
+
+    let (discount_amount, benefit_description) = match coupon.coupon_type.as_str() {
         "percentage" => {
-            let percentage = user_coupon.value.unwrap_or(Decimal::ZERO);
-            let discount = request.original_amount * percentage / Decimal::from(100);
-            // Apply maximum discount cap
-            if let Some(max_discount) = user_coupon.maximum_discount {
-                discount.min(max_discount)
-            } else {
-                discount
-            }
+            let percentage = coupon.value.unwrap_or(Decimal::ZERO);
+            (cap(original_amount * percentage / Decimal::from(100)), None)
         },
-        "fixed_amount" => user_coupon.value.unwrap_or(Decimal::ZERO),
-        _ => Decimal::ZERO, // BOGO, free_upgrade, free_service don't have numeric discounts
+        "fixed_amount" => (coupon.value.unwrap_or(Decimal::ZERO), None),
+        "bogo" => {
+            let line_items = line_items
+                .filter(|items| !items.is_empty())
+                .ok_or_else(|| {
+                    AppError::Validation(
+                        "lineItems are required to redeem a buy-one-get-one coupon".to_string(),
+                    )
+                })?;
+            let (free_units, free_value) = bogo_free_units(line_items);
+            (
+                cap(free_value).min(original_amount),
+                Some(format!(
+                    "Buy one, get one free: {} free item{}",
+                    free_units,
+                    if free_units == 1 { "" } else { "s" }
+                )),
+            )
+        },
+        "free_upgrade" => (
+            Decimal::ZERO,
+            Some(format!("Free upgrade: {}", described())),
+        ),
+        "free_service" => (
+            Decimal::ZERO,
+            Some(format!("Free service: {}", described())),
+        ),
+        _ => (Decimal::ZERO, None),
     };
 
-    let final_amount = (request.original_amount - discount_amount).max(Decimal::ZERO);
+    Ok(CouponBenefit {
+        discount_amount,
+        final_amount: (original_amount - discount_amount).max(Decimal::ZERO),
+        benefit_description,
+    })
+}
+
+/// The units a buy-one-get-one coupon makes free: with every unit in
+/// `line_items` ordered from dearest to cheapest and paired off, the
+/// second of each pair. Returns how many units are free and their value.
+fn bogo_free_units(line_items: &[LineItem]) -> (u64, Decimal) {
+    let mut items: Vec<&LineItem> = line_items.iter().collect();
+    items.sort_by(|a, b| b.unit_price.cmp(&a.unit_price));
+
+    let mut position: u64 = 0;
+    let mut free_units: u64 = 0;
+    let mut free_value = Decimal::ZERO;
+    for item in items {
+        let quantity = u64::from(item.quantity);
+        // Units at odd positions (0-based) are the second of their pair
+        let free = (position + quantity) / 2 - position / 2;
+        free_units += free;
+        free_value += item.unit_price * Decimal::from(free);
+        position += quantity;
+    }
+    (free_units, free_value)
+}
+
+/// Preview a coupon's discount on an order without redeeming it
+///
+/// POST /api/coupons/calculate
+///
+/// Applies the same checks and math as `POST /coupons/redeem`, but a coupon
+/// that fails a check (used, expired, below minimum spend) is reported as
+/// not applicable instead of rejected, so the POS can show why. Nothing is
+/// written.
+async fn calculate_coupon(
+    State(state): State<AppState>,
+    Json(request): Json<CalculateCouponRequest>,
+) -> AppResult<Json<SuccessResponse<CouponCalculation>>> {
+    request.validate()?;
+
+    let user_coupon = find_redeemable_coupon(&state, &request.qr_code).await?;
+    let currency = user_coupon.currency();
+
+    let reason = user_coupon.unusable_reason(request.original_amount);
+    let benefit = match reason {
+        Some(_) => CouponBenefit {
+            discount_amount: Decimal::ZERO,
+            final_amount: request.original_amount,
+            benefit_description: None,
+        },
+        None => coupon_benefit(
+            &user_coupon,
+            request.original_amount,
+            request.line_items.as_deref(),
+        )?,
+    };
+
+    Ok(Json(SuccessResponse::new(CouponCalculation {
+        applicable: reason.is_none(),
+        reason,
+        original_amount: request.original_amount,
+        discount_amount: benefit.discount_amount,
+        final_amount: benefit.final_amount,
+        original_amount_display: format_money(request.original_amount, currency),
+        discount_amount_display: format_money(benefit.discount_amount, currency),
+        final_amount_display: format_money(benefit.final_amount, currency),
+        benefit_description: benefit.benefit_description,
+    })))
+}
+
+/// Redeem a coupon
+///
+/// POST /api/coupons/redeem
+async fn redeem_coupon(
+    State(state): State<AppState>,
+    mut tx: Tx,
+    Extension(user): Extension<AuthUser>,
+    Json(request): Json<RedeemCouponRequest>,
+) -> AppResult<Json<SuccessResponse<RedemptionResult>>> {
+    if request.qr_code.is_empty() {
+        return Err(AppError::Validation("QR code is required".to_string()));
+    }
+
+    if request.original_amount <= Decimal::ZERO {
+        return Err(AppError::Validation(
+            "Original amount must be positive".to_string(),
+        ));
+    }
+
+    let redeemer_uuid = Uuid::parse_str(&user.id)
+        .map_err(|_| AppError::InvalidInput("Invalid user ID format".to_string()))?;
+
+    if let Some(line_items) = &request.line_items {
+        for item in line_items {
+            item.validate()?;
+        }
+    }
+
+    let user_coupon = find_redeemable_coupon(&state, &request.qr_code).await?;
+    if let Some(reason) = user_coupon.unusable_reason(request.original_amount) {
+        return Err(AppError::Validation(reason));
+    }
+
+    let CouponBenefit {
+        discount_amount,
+        final_amount,
+        benefit_description,
+    } = coupon_benefit(
+        &user_coupon,
+        request.original_amount,
+        request.line_items.as_deref(),
+    )?;
 
     // Update user coupon as used
     let redemption_details = serde_json::json!({
         "originalAmount": request.original_amount,
         "discountAmount": discount_amount,
         "finalAmount": final_amount,
+        "benefitDescription": benefit_description,
+        "lineItems": request.line_items,
         "transactionReference": request.transaction_reference,
         "location": request.location,
         "metadata": request.metadata
     });
 
+    // The user coupon is only marked used if the coupon still has a use
+    // left (see `increment_coupon_usage`)
     sqlx::query!(
         r#"
         UPDATE user_coupons
@@ -1141,27 +1805,170 @@ async fn redeem_coupon(
         request.location.as_deref(),
         &redemption_details,
     )
-    .execute(state.db())
+    .execute(&mut *tx)
     .await?;
 
-    // Increment used_count on coupon
-    sqlx::query!(
-        r#"
-        UPDATE coupons
-        SET used_count = COALESCE(used_count, 0) + 1, updated_at = NOW()
-        WHERE id = (SELECT coupon_id FROM user_coupons WHERE id = $1)
-        "#,
-        user_coupon.id,
+    increment_coupon_usage(
+        &mut *tx,
+        user_coupon.coupon_id,
+        state.config().coupons.exhaust_at_limit,
     )
-    .execute(state.db())
     .await?;
 
+    let currency = user_coupon.currency();
+
     Ok(Json(SuccessResponse::new(RedemptionResult {
         success: true,
         message: "Coupon redeemed successfully".to_string(),
         original_amount: request.original_amount,
         discount_amount,
         final_amount,
+        original_amount_display: format_money(request.original_amount, currency),
+        discount_amount_display: format_money(discount_amount, currency),
+        final_amount_display: format_money(final_amount, currency),
+        benefit_description,
+    })))
+}
+
+/// Redeem several coupons on one transaction
+///
+/// POST /api/coupons/redeem-multiple
+///
+/// The combination must be allowed by `COUPON_STACKING` (see
+/// `services::coupon::validate_coupon_stack`); discounts are applied in
+/// the fixed order documented on `services::coupon::apply_coupon_stack`.
+/// Either every coupon is redeemed or none is.
+async fn redeem_multiple_coupons(
+    State(state): State<AppState>,
+    mut tx: Tx,
+    Extension(user): Extension<AuthUser>,
+    Json(request): Json<RedeemMultipleCouponsRequest>,
+) -> AppResult<Json<SuccessResponse<MultiRedemptionResult>>> {
+    request
+        .validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let mut unique_codes = std::collections::HashSet::new();
+    if !request
+        .qr_codes
+        .iter()
+        .all(|c| unique_codes.insert(c.as_str()))
+    {
+        return Err(AppError::Validation(
+            "The same coupon was given more than once".to_string(),
+        ));
+    }
+
+    let redeemer_uuid = Uuid::parse_str(&user.id)
+        .map_err(|_| AppError::InvalidInput("Invalid user ID format".to_string()))?;
+
+    // Lock the user coupons so a concurrent redemption of any of them
+    // waits for this one to finish.
+    let coupons: Vec<StackedCoupon> = sqlx::query_as(
+        r#"
+        SELECT
+            uc.id AS user_coupon_id,
+            uc.user_id,
+            uc.coupon_id,
+            uc.qr_code,
+            uc.status,
+            uc.expires_at,
+            c.type AS coupon_type,
+            c.value,
+            c.currency,
+            c.minimum_spend,
+            c.maximum_discount
+        FROM user_coupons uc
+        JOIN coupons c ON uc.coupon_id = c.id
+        WHERE uc.qr_code = ANY($1)
+        ORDER BY uc.id
+        FOR UPDATE OF uc
+        "#,
+    )
+    .bind(&request.qr_codes)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if coupons.len() != request.qr_codes.len() {
+        return Err(AppError::NotFound("Invalid QR code".to_string()));
+    }
+
+    validate_coupon_stack(
+        &coupons,
+        request.original_amount,
+        state.config().coupons.stacking,
+        Utc::now(),
+    )?;
+
+    let applied = apply_coupon_stack(request.original_amount, &coupons);
+    let discount_amount: Decimal = applied.iter().map(|d| d.discount_amount).sum();
+    let final_amount = (request.original_amount - discount_amount).max(Decimal::ZERO);
+
+    for discount in &applied {
+        let redemption_details = serde_json::json!({
+            "originalAmount": request.original_amount,
+            "amountBefore": discount.amount_before,
+            "discountAmount": discount.discount_amount,
+            "finalAmount": final_amount,
+            "stackedWith": applied
+                .iter()
+                .filter(|d| d.user_coupon_id != discount.user_coupon_id)
+                .map(|d| d.qr_code.as_str())
+                .collect::<Vec<_>>(),
+            "transactionReference": request.transaction_reference,
+            "location": request.location,
+            "metadata": request.metadata
+        });
+
+        sqlx::query(
+            r#"
+            UPDATE user_coupons
+            SET
+                status = 'used',
+                used_at = NOW(),
+                used_by_admin = $2,
+                redemption_location = $3,
+                redemption_details = $4,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(discount.user_coupon_id)
+        .bind(redeemer_uuid)
+        .bind(request.location.as_deref())
+        .bind(&redemption_details)
+        .execute(&mut *tx)
+        .await?;
+
+        increment_coupon_usage(
+            &mut *tx,
+            discount.coupon_id,
+            state.config().coupons.exhaust_at_limit,
+        )
+        .await?;
+    }
+
+    let currency = coupons
+        .iter()
+        .filter(|c| {
+            matches!(
+                c.coupon_type,
+                CouponType::FixedAmount | CouponType::Percentage
+            )
+        })
+        .find_map(|c| c.currency.as_deref())
+        .unwrap_or("THB");
+
+    Ok(Json(SuccessResponse::new(MultiRedemptionResult {
+        success: true,
+        message: format!("{} coupons redeemed successfully", applied.len()),
+        original_amount: request.original_amount,
+        discount_amount,
+        final_amount,
+        original_amount_display: format_money(request.original_amount, currency),
+        discount_amount_display: format_money(discount_amount, currency),
+        final_amount_display: format_money(final_amount, currency),
+        coupons: applied,
     })))
 }
 
@@ -1176,8 +1983,10 @@ async fn validate_coupon(
         return Err(AppError::Validation("QR code is required".to_string()));
     }
 
-    let user_coupon = sqlx::query!(
-        r#"
+    let user_coupon = timed_query(
+        "coupons.validate",
+        sqlx::query!(
+            r#"
         SELECT
             uc.status::text as "status!",
             uc.expires_at,
@@ -1193,16 +2002,17 @@ async fn validate_coupon(
         JOIN coupons c ON uc.coupon_id = c.id
         WHERE uc.qr_code = $1
         "#,
-        &qr_code,
+            &qr_code,
+        )
+        .fetch_optional(state.db()),
     )
-    .fetch_optional(state.db())
     .await?;
 
     let response = match user_coupon {
         Some(uc) => {
             let effective_expiry = uc.expires_at.or(uc.valid_until);
             let is_valid =
-                uc.status == "available" && effective_expiry.map_or(true, |exp| exp > Utc::now());
+                uc.status == "available" && !coupon_expired(effective_expiry, Utc::now());
 
             CouponValidationResponse {
                 valid: is_valid,
@@ -1503,13 +2313,17 @@ async fn get_coupon_analytics_data(
 /// Routes:
 /// - GET / - List available coupons
 /// - GET /my-coupons - Get user's assigned coupons
+/// - GET /user/:userCouponId/qr.png - QR code image of a user coupon
 /// - GET /:couponId - Get coupon by ID
 /// - GET /validate/:qrCode - Validate coupon by QR code (public)
 /// - POST / - Create a new coupon (admin)
 /// - PUT /:couponId - Update a coupon (admin)
 /// - DELETE /:couponId - Delete a coupon (admin)
 /// - POST /assign - Assign coupon to users (admin)
+/// - POST /assign-by-segment - Assign coupon to a tier/segment (admin)
 /// - POST /redeem - Redeem a coupon
+/// - POST /calculate - Preview a coupon's discount without redeeming it
+/// - POST /redeem-multiple - Redeem several coupons on one transaction
 /// - POST /user-coupons/:userCouponId/revoke - Revoke a user coupon (admin)
 /// - GET /analytics/stats - Get coupon statistics (admin)
 /// - GET /:couponId/redemptions - Get coupon redemptions (admin)
@@ -1523,7 +2337,11 @@ pub fn routes() -> Router<AppState> {
         .route("/", get(list_coupons))
         .route("/my-coupons", get(get_user_coupons))
         .route("/redeem", post(redeem_coupon))
+        .route("/calculate", post(calculate_coupon))
+        .route("/redeem-multiple", post(redeem_multiple_coupons))
+        .route("/user/:userCouponId/qr.png", get(get_user_coupon_qr))
         .route("/:couponId", get(get_coupon))
+        .layer(middleware::from_fn(transaction_layer))
         .layer(middleware::from_fn(auth_middleware));
 
     // Admin routes (require admin role)
@@ -1532,6 +2350,7 @@ pub fn routes() -> Router<AppState> {
         .route("/:couponId", put(update_coupon))
         .route("/:couponId", delete(delete_coupon))
         .route("/assign", post(assign_coupon))
+        .route("/assign-by-segment", post(assign_coupon_by_segment))
         .route(
             "/user-coupons/:userCouponId/revoke",
             post(revoke_user_coupon),
@@ -1,13 +1,26 @@
 //! Health check routes
 //!
 //! Provides endpoints for health monitoring and readiness checks.
+//!
+//! For Kubernetes-style probes, `/live` answers as long as the process
+//! can serve requests at all, while `/ready` also requires the database
+//! and Redis to be reachable, so a pod that lost a dependency is taken
+//! out of the load balancer without being restarted.
+
+use std::time::{Duration, Instant};
 
 use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
 use chrono::Utc;
 use serde::Serialize;
 
+use crate::db::Database;
+use crate::redis::RedisManager;
 use crate::state::AppState;
 
+/// How long each readiness dependency check may take before the
+/// dependency counts as down
+const READINESS_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
 /// Health check response
 #[derive(Serialize)]
 pub struct HealthResponse {
@@ -32,6 +45,16 @@ pub struct RedisHealthResponse {
     pub timestamp: String,
 }
 
+/// Readiness probe response
+#[derive(Serialize)]
+pub struct ReadinessResponse {
+    /// `ready` or `not_ready`
+    pub status: String,
+    pub database: String,
+    pub redis: String,
+    pub timestamp: String,
+}
+
 /// Services health status
 #[derive(Serialize)]
 pub struct ServicesHealth {
@@ -58,6 +81,12 @@ pub struct SystemHealthResponse {
     pub services: ServicesHealth,
     pub uptime: u64,
     pub memory: MemoryInfo,
+    /// Connections currently open in the database pool, idle or in use
+    pub db_pool_size: u32,
+    /// Open pool connections not checked out by a request
+    pub db_pool_idle: usize,
+    /// Round-trip time of the Redis `PING`; `null` when it failed
+    pub redis_latency_ms: Option<f64>,
 }
 
 /// Basic health check handler
@@ -70,6 +99,51 @@ async fn health_check() -> Json<HealthResponse> {
     })
 }
 
+/// Liveness probe handler
+/// Answers 200 whenever the process is serving; checks no dependencies,
+/// so a database or Redis outage never gets the pod restarted
+async fn health_live() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "alive".to_string(),
+        timestamp: Utc::now().to_rfc3339(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    })
+}
+
+/// Readiness probe handler
+/// 200 when both the database and Redis answer within
+/// `READINESS_CHECK_TIMEOUT`, 503 otherwise
+async fn health_ready(State(state): State<AppState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let db = Database::from_pool(state.db().clone());
+    let mut redis = RedisManager::from_connection(state.redis());
+
+    let (db_result, redis_result) = tokio::join!(
+        tokio::time::timeout(READINESS_CHECK_TIMEOUT, db.health_check()),
+        tokio::time::timeout(READINESS_CHECK_TIMEOUT, redis.health_check()),
+    );
+    let db_ok = matches!(db_result, Ok(Ok(())));
+    let redis_ok = matches!(redis_result, Ok(Ok(())));
+
+    let connection = |ok: bool| if ok { "connected" } else { "disconnected" }.to_string();
+    let ready = db_ok && redis_ok;
+    if !ready {
+        tracing::warn!(database = db_ok, redis = redis_ok, "Readiness check failed");
+    }
+
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let body = ReadinessResponse {
+        status: if ready { "ready" } else { "not_ready" }.to_string(),
+        database: connection(db_ok),
+        redis: connection(redis_ok),
+        timestamp: Utc::now().to_rfc3339(),
+    };
+    (status, Json(body))
+}
+
 /// Database health check handler
 /// Checks database connectivity
 async fn health_check_db(
@@ -136,14 +210,18 @@ async fn health_check_full(
         Err(_) => "unhealthy".to_string(),
     };
 
-    // Check Redis
+    // Check Redis, timing the round trip
     let mut redis_conn = state.redis();
-    let redis_status = match redis::cmd("PING")
+    let ping_started = Instant::now();
+    let (redis_status, redis_latency_ms) = match redis::cmd("PING")
         .query_async::<_, String>(&mut redis_conn)
         .await
     {
-        Ok(_) => "healthy".to_string(),
-        Err(_) => "unhealthy".to_string(),
+        Ok(_) => (
+            "healthy".to_string(),
+            Some(ping_started.elapsed().as_secs_f64() * 1000.0),
+        ),
+        Err(_) => ("unhealthy".to_string(), None),
     };
 
     let db_healthy = db_status == "healthy";
@@ -184,6 +262,9 @@ async fn health_check_full(
         services,
         uptime: 0, // Would need to track start time for actual uptime
         memory,
+        db_pool_size: state.db().size(),
+        db_pool_idle: state.db().num_idle(),
+        redis_latency_ms,
     };
 
     if all_healthy {
@@ -202,6 +283,8 @@ pub fn routes() -> Router<AppState> {
         .route("/db", get(health_check_db))
         .route("/redis", get(health_check_redis))
         .route("/full", get(health_check_full))
+        .route("/live", get(health_live))
+        .route("/ready", get(health_ready))
 }
 
 #[cfg(test)]
@@ -215,4 +298,10 @@ mod tests {
         assert!(!response.timestamp.is_empty());
         assert!(!response.version.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_health_live_needs_no_state() {
+        let response = health_live().await;
+        assert_eq!(response.status, "alive");
+    }
 }
@@ -7,32 +7,62 @@
 //!
 //! - `GET /tiers` - Get all available loyalty tiers (public)
 //! - `GET /status` - Get current user's loyalty status (authenticated)
-//! - `GET /transactions` - Get user's transaction history (authenticated)
+//! - `GET /transactions` - Get user's recent transaction history (authenticated)
+//! - `GET /transactions/export` - Full transaction history as CSV (authenticated)
+//! - `GET /leaderboard` - Top opted-in members by nights or points (authenticated)
+//! - `POST /redeem` - Redeem points at the member's tier rate (authenticated)
 //! - `POST /award` - Award points to a user (admin only)
 //! - `POST /recalculate/:user_id` - Recalculate user's tier (admin only)
+//! - `POST /admin/recalculate-all` - Recalculate every member's tier in batches (admin only)
 
 use axum::{
     extract::{Extension, Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
     middleware,
-    routing::{get, post},
+    routing::{get, post, put},
     Json, Router,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use sqlx::PgPool;
+use sqlx::{Connection, PgPool};
 use uuid::Uuid;
+use validator::Validate;
 
-use crate::db::Database;
+use crate::config::{LoyaltyConfig, PointsDisplayConfig, TransactionHistoryConfig};
+use crate::db::{timed_query, Database};
 use crate::error::AppError;
 use crate::middleware::auth::{auth_middleware, has_role, AuthUser};
+use crate::middleware::cache_control::CachePolicy;
+use crate::middleware::response_cache::{response_cache_middleware, CacheScope};
+use crate::middleware::transaction::{transaction_layer, Tx};
+use crate::models::points_transaction::RedeemPointsRequest;
+use crate::models::tier::{CreateTierRequest, TierBenefits, UpdateTierRequest};
+use crate::redis::RedisManager;
+use crate::routes::coupons::notify_coupon_assignment;
+use crate::services::idempotency::{self, ReplayOutcome};
+use crate::services::loyalty::{
+    check_tier_ladder, count_tier_recalculation_remaining, deactivate_tier, ensure_user_loyalty,
+    expire_points, fetch_leaderboard, grant_tier_upgrade_coupon, invalidate_tiers_cache,
+    lock_current_tier, lock_tier_ladder, map_balance_violation, mask_display_name,
+    notify_points_expired, notify_stay_milestone, pending_points_balance, preview_points_expiry,
+    recalculate_tier_batch, recalculate_tier_with_grace, record_stay_milestone, redeem_points,
+    try_lock_tier_recalculation, unlock_tier_recalculation, LeaderboardMetric, LoyaltyService,
+    LoyaltyServiceImpl, PointsAdjustmentParams, PointsRedemption, PointsTransactionType,
+    StayMilestone, Tier, TierUpgradeGrant,
+};
+use crate::services::sse::{get_sse_service, SseEvent, SseEventType};
 use crate::state::AppState;
+use crate::types::{ApiResponse, Pagination};
+use crate::utils::{csv_field, display_points, search_pattern};
 
 // ============================================================================
 // State (Legacy - for backwards compatibility)
 // ============================================================================
 
+/// Response cache scope of the public tier list; tier writes invalidate it
+const TIERS_CACHE_SCOPE: CacheScope = CacheScope("tiers");
+
 /// Application state for loyalty routes (uses Database wrapper)
 /// This is kept for backwards compatibility with older route configurations.
 #[derive(Clone)]
@@ -52,7 +82,7 @@ impl LoyaltyState {
 // ============================================================================
 
 /// Tier row from database
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, sqlx::FromRow)]
 pub struct TierRow {
     pub id: Uuid,
     pub name: String,
@@ -87,12 +117,12 @@ pub struct UserLoyaltyWithTierRow {
 }
 
 /// Points transaction row from database
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, sqlx::FromRow)]
 pub struct PointsTransactionRow {
     pub id: Uuid,
     pub user_id: Uuid,
     pub points: i32,
-    pub transaction_type: String,
+    pub transaction_type: PointsTransactionType,
     pub description: Option<String>,
     pub reference_id: Option<String>,
     pub admin_user_id: Option<Uuid>,
@@ -106,31 +136,46 @@ pub struct PointsTransactionRow {
 // Response Types
 // ============================================================================
 
-/// API success response wrapper
+/// A referral made by the current user
 #[derive(Debug, Serialize)]
-pub struct ApiResponse<T: Serialize> {
-    pub success: bool,
-    pub data: T,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub message: Option<String>,
+#[serde(rename_all = "camelCase")]
+pub struct ReferralSummary {
+    pub id: Uuid,
+    pub status: String,
+    /// Bonus points this user earned from the referral (0 while pending)
+    pub points_earned: i32,
+    pub created_at: DateTime<Utc>,
+    pub qualified_at: Option<DateTime<Utc>>,
 }
 
-impl<T: Serialize> ApiResponse<T> {
-    pub fn success(data: T) -> Self {
-        Self {
-            success: true,
-            data,
-            message: None,
-        }
-    }
+/// Current user's referral code and referrals
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferralsResponse {
+    /// Code to share (the user's membership ID)
+    pub referral_code: Option<String>,
+    pub referrals: Vec<ReferralSummary>,
+}
 
-    pub fn with_message(data: T, message: impl Into<String>) -> Self {
-        Self {
-            success: true,
-            data,
-            message: Some(message.into()),
-        }
-    }
+/// One ranked member on the leaderboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardEntry {
+    pub rank: i64,
+    /// First name plus last initial
+    pub display_name: String,
+    pub tier_name: Option<String>,
+    pub tier_color: Option<String>,
+    /// Nights or points, per the requested metric
+    pub value: i32,
+}
+
+/// Leaderboard for one metric
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardResponse {
+    pub metric: LeaderboardMetric,
+    pub entries: Vec<LeaderboardEntry>,
 }
 
 /// Tier response DTO
@@ -140,12 +185,29 @@ pub struct TierResponse {
     pub name: String,
     pub min_points: i32,
     pub min_nights: i32,
-    pub benefits: JsonValue,
+    pub benefits: TierBenefits,
     pub color: String,
     pub sort_order: i32,
     pub is_active: bool,
 }
 
+impl From<Tier> for TierResponse {
+    fn from(tier: Tier) -> Self {
+        Self::from(TierRow {
+            id: tier.id,
+            name: tier.name,
+            min_points: tier.min_points,
+            min_nights: tier.min_nights,
+            benefits: tier.benefits,
+            color: tier.color,
+            sort_order: tier.sort_order,
+            is_active: tier.is_active,
+            created_at: tier.created_at,
+            updated_at: tier.updated_at,
+        })
+    }
+}
+
 impl From<TierRow> for TierResponse {
     fn from(row: TierRow) -> Self {
         Self {
@@ -153,7 +215,11 @@ impl From<TierRow> for TierResponse {
             name: row.name,
             min_points: row.min_points,
             min_nights: row.min_nights,
-            benefits: row.benefits.unwrap_or(serde_json::json!({})),
+            benefits: row
+                .benefits
+                .as_ref()
+                .map(TierBenefits::from_json)
+                .unwrap_or_default(),
             color: row.color,
             sort_order: row.sort_order,
             is_active: row.is_active.unwrap_or(true),
@@ -162,10 +228,19 @@ impl From<TierRow> for TierResponse {
 }
 
 /// User loyalty status response
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoyaltyStatusResponse {
     pub user_id: Uuid,
+    /// Exact balance; `current_points_display` is for display only
     pub current_points: i32,
+    /// `current_points` formatted per `POINTS_DISPLAY_STYLE` in the
+    /// caller's locale (e.g. `12,345` or `12.3K`)
+    pub current_points_display: String,
+    /// Points that can be redeemed; the same as `current_points`
+    pub available_points: i32,
+    /// Stay points waiting for the booking's payment slip to be verified;
+    /// not part of `current_points` and not redeemable yet
+    pub pending_points: i32,
     pub total_nights: i32,
     pub tier: Option<TierInfo>,
     pub tier_updated_at: Option<DateTime<Utc>>,
@@ -174,17 +249,17 @@ pub struct LoyaltyStatusResponse {
 }
 
 /// Tier info for loyalty status
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TierInfo {
     pub id: Uuid,
     pub name: String,
     pub color: String,
-    pub benefits: JsonValue,
+    pub benefits: TierBenefits,
     pub min_nights: i32,
 }
 
 /// Next tier progress info
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NextTierInfo {
     pub name: String,
     pub min_nights: i32,
@@ -199,7 +274,7 @@ pub struct PointsTransactionResponse {
     pub user_id: Uuid,
     pub points: i32,
     #[serde(rename = "type")]
-    pub transaction_type: String,
+    pub transaction_type: PointsTransactionType,
     pub description: Option<String>,
     pub reference_id: Option<String>,
     pub admin_user_id: Option<Uuid>,
@@ -231,10 +306,130 @@ impl From<PointsTransactionRow> for PointsTransactionResponse {
 #[derive(Debug, Clone, Serialize)]
 pub struct PaginatedTransactionsResponse {
     pub transactions: Vec<PointsTransactionResponse>,
+    /// Transactions reachable through pagination (at most
+    /// `history_limit.max_rows` for member history)
     pub total: i64,
     pub page: i32,
     pub limit: i32,
     pub total_pages: i32,
+    /// True when older transactions exist beyond `history_limit`; they are
+    /// only available through the CSV export
+    pub truncated: bool,
+    /// The history window applied (member history only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history_limit: Option<TransactionHistoryLimit>,
+    /// Pass as `?cursor=` to fetch the transactions after this page;
+    /// absent on the last page (member history only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl PaginatedTransactionsResponse {
+    /// Pagination metadata for the response envelope
+    fn pagination(&self) -> Pagination {
+        offset_pagination((self.page.max(1) - 1) * self.limit, self.limit, self.total)
+    }
+}
+
+/// Pagination metadata for a `limit`/`offset` admin listing
+fn offset_pagination(offset: i32, limit: i32, total: i64) -> Pagination {
+    let limit = limit.max(1);
+    Pagination::new(
+        (offset.max(0) / limit + 1) as u32,
+        limit as u32,
+        total.max(0) as u64,
+    )
+}
+
+/// Position in a member's history for cursor pagination: the
+/// `(created_at, id)` of the last transaction already returned
+///
+/// History is ordered by `created_at DESC, id DESC`, so transactions
+/// sharing a timestamp still have a fixed order and the next page starts
+/// strictly after the cursor. Encoded as URL-safe base64 of
+/// `<RFC 3339 timestamp>|<uuid>`; clients treat it as opaque.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl TransactionCursor {
+    pub fn encode(&self) -> String {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+        URL_SAFE_NO_PAD.encode(format!(
+            "{}|{}",
+            self.created_at
+                .to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+            self.id
+        ))
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, AppError> {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+        let invalid = || AppError::BadRequest("Invalid transactions cursor".to_string());
+        let raw = URL_SAFE_NO_PAD
+            .decode(cursor.trim())
+            .map_err(|_| invalid())?;
+        let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+        let (created_at, id) = raw.split_once('|').ok_or_else(invalid)?;
+
+        Ok(Self {
+            created_at: DateTime::parse_from_rfc3339(created_at)
+                .map_err(|_| invalid())?
+                .with_timezone(&Utc),
+            id: Uuid::parse_str(id).map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// The window a member's paginated history is limited to
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionHistoryLimit {
+    pub max_age_days: u32,
+    pub max_rows: u32,
+    /// Transactions before this moment are not paginated
+    pub since: DateTime<Utc>,
+    /// Where the full history can be downloaded
+    pub export_path: &'static str,
+}
+
+/// Path of the full-history CSV export
+const TRANSACTIONS_EXPORT_PATH: &str = "/api/loyalty/transactions/export";
+
+/// One line of the transactions CSV export
+#[derive(Debug, sqlx::FromRow)]
+struct TransactionExportRow {
+    id: Uuid,
+    created_at: Option<DateTime<Utc>>,
+    transaction_type: PointsTransactionType,
+    points: i32,
+    nights_stayed: Option<i32>,
+    description: Option<String>,
+    reference_id: Option<String>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl TransactionExportRow {
+    const CSV_HEADER: &'static str =
+        "id,created_at,type,points,nights_stayed,description,reference_id,expires_at\n";
+
+    fn to_csv_line(&self) -> String {
+        let timestamp = |t: Option<DateTime<Utc>>| t.map(|t| t.to_rfc3339()).unwrap_or_default();
+        format!(
+            "{},{},{},{},{},{},{},{}\n",
+            self.id,
+            timestamp(self.created_at),
+            self.transaction_type,
+            self.points,
+            self.nights_stayed.unwrap_or(0),
+            csv_field(self.description.as_deref().unwrap_or("")),
+            csv_field(self.reference_id.as_deref().unwrap_or("")),
+            timestamp(self.expires_at),
+        )
+    }
 }
 
 /// Award points result.
@@ -262,6 +457,9 @@ pub struct RecalculateTierResult {
     pub new_tier: String,
     pub tier_changed: bool,
     pub total_nights: i32,
+    /// When a downgrade held back by the grace period takes effect
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downgrade_eligible_at: Option<DateTime<Utc>>,
 }
 
 // ============================================================================
@@ -275,6 +473,9 @@ pub struct TransactionsQuery {
     pub page: i32,
     #[serde(default = "default_limit")]
     pub limit: i32,
+    /// `next_cursor` from the previous page; replaces `page` when given
+    #[serde(default)]
+    pub cursor: Option<String>,
 }
 
 fn default_page() -> i32 {
@@ -289,6 +490,19 @@ fn default_offset() -> i32 {
     0
 }
 
+/// Query params for the leaderboard endpoint
+#[derive(Debug, Deserialize)]
+pub struct LeaderboardQuery {
+    #[serde(default)]
+    pub metric: LeaderboardMetric,
+    #[serde(default = "default_leaderboard_limit")]
+    pub limit: i64,
+}
+
+fn default_leaderboard_limit() -> i64 {
+    10
+}
+
 /// Award points request body
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -299,6 +513,9 @@ pub struct AwardPointsRequest {
     pub nights: i32,
     pub source: Option<String>,
     pub description: Option<String>,
+    /// Needed to exceed the per-award caps (see `check_award_caps`)
+    #[serde(default, alias = "confirm_large")]
+    pub confirm_large: bool,
 }
 
 // ============================================================================
@@ -332,6 +549,40 @@ pub struct AdminAwardPointsRequest {
     pub points: i32,
     pub description: Option<String>,
     pub reference_id: Option<String>,
+    /// Needed to exceed the per-award caps (see `check_award_caps`)
+    #[serde(default, alias = "confirm_large")]
+    pub confirm_large: bool,
+}
+
+/// Admin convert points request
+///
+/// `external_reference` identifies the partner balance being converted
+/// (e.g. the partner's statement or transfer ID); a reference can only be
+/// converted once per `source_program`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminConvertPointsRequest {
+    pub user_id: Uuid,
+    pub source_program: String,
+    pub external_reference: String,
+    pub external_points: i64,
+}
+
+/// Admin partial refund adjustment request
+///
+/// The refunded share is either `refunded_fraction` (0 < f <= 1) or
+/// `refunded_amount` out of `original_amount`. `refund_reference`
+/// identifies the refund (e.g. the payment provider's refund ID); each
+/// refund can only be adjusted once.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminPartialAdjustRequest {
+    pub original_transaction_id: Uuid,
+    pub refund_reference: String,
+    pub refunded_fraction: Option<rust_decimal::Decimal>,
+    pub refunded_amount: Option<rust_decimal::Decimal>,
+    pub original_amount: Option<rust_decimal::Decimal>,
+    pub reason: Option<String>,
 }
 
 /// Admin deduct points request
@@ -341,6 +592,9 @@ pub struct AdminDeductPointsRequest {
     pub user_id: Uuid,
     pub points: i32,
     pub reason: String,
+    /// Needed to exceed the per-award caps (see `check_award_caps`)
+    #[serde(default, alias = "confirm_large")]
+    pub confirm_large: bool,
 }
 
 /// Admin award spending with nights request
@@ -354,6 +608,9 @@ pub struct AdminAwardSpendingWithNightsRequest {
     pub nights_stayed: i32,
     pub reference_id: Option<String>,
     pub description: Option<String>,
+    /// Needed to exceed the per-award caps (see `check_award_caps`)
+    #[serde(default, alias = "confirm_large")]
+    pub confirm_large: bool,
 }
 
 /// Admin award nights request
@@ -364,6 +621,9 @@ pub struct AdminAwardNightsRequest {
     pub nights: i32,
     pub reason: String,
     pub reference_id: Option<String>,
+    /// Needed to exceed the per-award caps (see `check_award_caps`)
+    #[serde(default, alias = "confirm_large")]
+    pub confirm_large: bool,
 }
 
 /// Admin deduct nights request
@@ -374,6 +634,46 @@ pub struct AdminDeductNightsRequest {
     pub nights: i32,
     pub reason: String,
     pub reference_id: Option<String>,
+    /// Needed to exceed the per-award caps (see `check_award_caps`)
+    #[serde(default, alias = "confirm_large")]
+    pub confirm_large: bool,
+}
+
+/// Most members a single bulk award may target
+const MAX_BULK_AWARD_USERS: usize = 500;
+
+/// Admin bulk award request: the same points and/or nights for every
+/// member in `userIds`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminBulkAwardRequest {
+    pub user_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub points: i32,
+    #[serde(default)]
+    pub nights: i32,
+    pub description: String,
+    /// Needed to exceed the per-award caps (see `check_award_caps`)
+    #[serde(default, alias = "confirm_large")]
+    pub confirm_large: bool,
+}
+
+/// Admin bulk tier recalculation request; an empty body starts a new run
+/// with the configured batch size
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRecalculateAllRequest {
+    /// Members per transaction (default `LOYALTY_TIER_RECALC_BATCH_SIZE`)
+    pub batch_size: Option<u32>,
+    /// Carry on a failed or interrupted job after its `lastUserId`
+    pub resume_job_id: Option<Uuid>,
+}
+
+/// Admin set tier upgrade coupon request (`couponId: null` clears it)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminSetTierUpgradeCouponRequest {
+    pub coupon_id: Option<Uuid>,
 }
 
 // ============================================================================
@@ -381,7 +681,7 @@ pub struct AdminDeductNightsRequest {
 // ============================================================================
 
 /// User loyalty status for admin list
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
 pub struct AdminUserLoyaltyRow {
     pub user_id: Uuid,
     pub current_points: Option<i32>,
@@ -456,32 +756,395 @@ pub struct PointsEarningRuleRow {
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+/// Query params for the admin expire-points endpoint
+#[derive(Debug, Deserialize)]
+pub struct ExpirePointsQuery {
+    /// Report what would expire without expiring anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
 /// Expire points result
 #[derive(Debug, Clone, Serialize)]
 pub struct ExpirePointsResult {
     pub expired_count: i64,
+    /// Present only for a dry run
+    #[serde(flatten)]
+    pub preview: Option<ExpirePointsPreview>,
 }
 
-/// Admin award/deduct result
+/// What a dry-run expiration would have expired
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpirePointsPreview {
+    pub dry_run: bool,
+    pub points_to_expire: i64,
+    pub users: Vec<ExpirePointsPreviewUser>,
+}
+
+/// One member's share of a dry-run expiration
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpirePointsPreviewUser {
+    pub user_id: Uuid,
+    pub points_to_expire: i32,
+    pub transactions: i64,
+}
+
+/// Tier upgrade coupon configuration result
 #[derive(Debug, Clone, Serialize)]
+pub struct TierUpgradeCouponResult {
+    pub tier_id: Uuid,
+    pub tier_name: String,
+    pub upgrade_coupon_id: Option<Uuid>,
+}
+
+/// Admin tier update/deactivation result
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminTierUpdateResult {
+    pub tier: TierResponse,
+    /// Members moved off the tier because it was deactivated
+    pub reassigned_members: i64,
+}
+
+/// Admin award/deduct result
+///
+/// `Deserialize` is needed to replay the result cached for an
+/// `Idempotency-Key` (see `run_idempotent`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AdminOperationResult {
     pub transaction_id: Uuid,
     pub loyalty_status: Option<LoyaltyStatusResponse>,
 }
 
+/// Admin points conversion result
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointsConversionResult {
+    pub conversion_id: Uuid,
+    pub transaction_id: Option<Uuid>,
+    pub source_program: String,
+    pub external_reference: String,
+    pub external_points: i64,
+    pub rate: rust_decimal::Decimal,
+    pub points_awarded: i32,
+    /// True when this reference had already been converted and nothing new
+    /// was awarded
+    pub already_converted: bool,
+    pub loyalty_status: Option<LoyaltyStatusResponse>,
+}
+
+/// A `points_conversions` row
+#[derive(Debug, sqlx::FromRow)]
+struct PointsConversionRow {
+    id: Uuid,
+    user_id: Uuid,
+    transaction_id: Option<Uuid>,
+    source_program: String,
+    external_reference: String,
+    external_points: i64,
+    rate: rust_decimal::Decimal,
+    points_awarded: i32,
+}
+
+/// Admin partial refund adjustment result
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialRefundAdjustmentResult {
+    pub adjustment_id: Uuid,
+    /// The `refund_adjustment` transaction, absent when the refund took
+    /// back nothing (rounded down to zero, or the award was already fully
+    /// adjusted)
+    pub transaction_id: Option<Uuid>,
+    pub original_transaction_id: Uuid,
+    pub refund_reference: String,
+    pub refunded_fraction: rust_decimal::Decimal,
+    pub points_deducted: i32,
+    pub nights_deducted: i32,
+    /// True when this refund had already been adjusted and nothing new
+    /// was deducted
+    pub already_adjusted: bool,
+    pub loyalty_status: Option<LoyaltyStatusResponse>,
+}
+
+/// A `points_refund_adjustments` row
+#[derive(Debug, sqlx::FromRow)]
+struct PointsRefundAdjustmentRow {
+    id: Uuid,
+    user_id: Uuid,
+    original_transaction_id: Uuid,
+    transaction_id: Option<Uuid>,
+    refunded_fraction: rust_decimal::Decimal,
+    points_deducted: i32,
+    nights_deducted: i32,
+}
+
+/// The award a partial refund adjusts
+#[derive(Debug, sqlx::FromRow)]
+struct RefundedAwardRow {
+    user_id: Uuid,
+    points: i32,
+    transaction_type: PointsTransactionType,
+    nights_stayed: i32,
+}
+
+/// The transaction type for `POST /award`'s optional `source`, which
+/// defaults to `admin_award`. Only credit types may be used there.
+fn award_transaction_type(source: Option<&str>) -> Result<PointsTransactionType, AppError> {
+    let Some(source) = source else {
+        return Ok(PointsTransactionType::AdminAward);
+    };
+    let transaction_type = PointsTransactionType::from_db(source);
+    if !transaction_type.is_credit() {
+        return Err(AppError::Validation(format!(
+            "Invalid points source '{}'",
+            source
+        )));
+    }
+    Ok(transaction_type)
+}
+
+/// The per-award caps (`LoyaltyConfig::max_points_per_award` /
+/// `max_nights_per_award`) that a change of `points` and `nights` exceeds,
+/// described for the error message; empty when it's within both.
+/// Deductions are measured by size, like awards.
+fn exceeded_award_caps(config: &LoyaltyConfig, points: i64, nights: i64) -> Vec<String> {
+    let mut exceeded = Vec::new();
+    let max_points = i64::from(config.max_points_per_award);
+    if max_points > 0 && points.abs() > max_points {
+        exceeded.push(format!("{} points (limit {})", points.abs(), max_points));
+    }
+    let max_nights = i64::from(config.max_nights_per_award);
+    if max_nights > 0 && nights.abs() > max_nights {
+        exceeded.push(format!("{} nights (limit {})", nights.abs(), max_nights));
+    }
+    exceeded
+}
+
+/// Guard an admin award or deduction against typos
+///
+/// A change over the per-award caps only goes ahead with `confirm_large`
+/// or from a super_admin. Every over-cap attempt, allowed or not, is
+/// written to the member's audit log. The audit row goes through the pool
+/// rather than the operation's transaction so a rejected or rolled-back
+/// attempt is still recorded.
+async fn check_award_caps(
+    state: &AppState,
+    auth_user: &AuthUser,
+    user_id: Uuid,
+    operation: &str,
+    points: i64,
+    nights: i64,
+    confirm_large: bool,
+) -> Result<(), AppError> {
+    let config = &state.config().loyalty;
+    let exceeded = exceeded_award_caps(config, points, nights);
+    if exceeded.is_empty() {
+        return Ok(());
+    }
+
+    let super_admin = has_role(auth_user, "super_admin");
+    let allowed = confirm_large || super_admin;
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_audit_log (user_id, action, details)
+        VALUES ($1, 'large_award_attempted', $2)
+        "#,
+    )
+    .bind(user_id)
+    .bind(serde_json::json!({
+        "operation": operation,
+        "points": points,
+        "nights": nights,
+        "maxPointsPerAward": config.max_points_per_award,
+        "maxNightsPerAward": config.max_nights_per_award,
+        "confirmLarge": confirm_large,
+        "superAdmin": super_admin,
+        "allowed": allowed,
+        "adminUserId": auth_user.id,
+    }))
+    .execute(state.db())
+    .await?;
+
+    if !allowed {
+        tracing::warn!(
+            admin_user_id = %auth_user.id,
+            user_id = %user_id,
+            operation,
+            points,
+            nights,
+            "Rejected admin award over the per-award cap"
+        );
+        return Err(AppError::Validation(format!(
+            "{} exceeds the per-award limit; resend with confirmLarge: true if this is intended",
+            exceeded.join(" and ")
+        )));
+    }
+
+    Ok(())
+}
+
+/// The trimmed `Idempotency-Key` header, if one was sent
+fn idempotency_key(headers: &axum::http::HeaderMap) -> Option<String> {
+    headers
+        .get("idempotency-key")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Run an admin points adjustment at most once per `Idempotency-Key`
+///
+/// Without the header `apply` just runs. With it, the key is scoped to
+/// `endpoint` and the member and reserved in Redis first: a repeat within
+/// 24 hours gets the original result back (flagged `true`) instead of
+/// creating a second transaction, and a repeat while the first request
+/// is still running is a 409. See `services/idempotency.rs`.
+async fn run_idempotent<T, F, Fut>(
+    state: &AppState,
+    headers: &axum::http::HeaderMap,
+    endpoint: &str,
+    user_id: Uuid,
+    apply: F,
+) -> Result<(T, bool), AppError>
+where
+    T: Serialize + serde::de::DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<T, AppError>>,
+{
+    let Some(key) = idempotency_key(headers) else {
+        return Ok((apply().await?, false));
+    };
+
+    let redis_key = idempotency::replay_key(endpoint, user_id, &key);
+    let mut redis = state.redis();
+    match idempotency::reserve_replay::<T>(&mut redis, &redis_key).await? {
+        ReplayOutcome::Fresh => {},
+        ReplayOutcome::Replay(result) => return Ok((result, true)),
+        ReplayOutcome::InFlight => {
+            return Err(AppError::Conflict(
+                "A concurrent request with the same Idempotency-Key is in flight".to_string(),
+            ));
+        },
+    }
+
+    let result = match apply().await {
+        Ok(result) => result,
+        Err(e) => {
+            if let Err(release_err) = idempotency::release_replay(&mut redis, &redis_key).await {
+                tracing::warn!(error = %release_err, endpoint, "Failed to release idempotency key");
+            }
+            return Err(e);
+        },
+    };
+
+    // The adjustment has committed; failing to cache it must not turn the
+    // response into an error. The reservation then lapses on its own.
+    match serde_json::to_string(&result) {
+        Ok(body) => {
+            if let Err(e) = idempotency::store_replay(&mut redis, &redis_key, &body).await {
+                tracing::warn!(error = %e, endpoint, "Failed to cache idempotent result");
+            }
+        },
+        Err(e) => tracing::warn!(error = %e, endpoint, "Failed to serialize idempotent result"),
+    }
+
+    Ok((result, false))
+}
+
+/// Success message, marked when the result was replayed for a repeated
+/// `Idempotency-Key`
+fn replay_message(message: &str, replayed: bool) -> String {
+    if replayed {
+        format!("{} (replayed)", message)
+    } else {
+        message.to_string()
+    }
+}
+
+/// Points awarded for `external_points` at `rate`, rounded down
+fn converted_points(external_points: i64, rate: rust_decimal::Decimal) -> Option<i32> {
+    use rust_decimal::prelude::ToPrimitive;
+
+    (rust_decimal::Decimal::from(external_points).checked_mul(rate)?)
+        .floor()
+        .to_i32()
+}
+
+/// The refunded share of a booking, from either an explicit fraction or a
+/// refunded amount out of the original amount. Truncated to the 6 decimal
+/// places `points_refund_adjustments` stores, and always in (0, 1].
+fn refunded_fraction(
+    fraction: Option<rust_decimal::Decimal>,
+    refunded_amount: Option<rust_decimal::Decimal>,
+    original_amount: Option<rust_decimal::Decimal>,
+) -> Result<rust_decimal::Decimal, AppError> {
+    use rust_decimal::{Decimal, RoundingStrategy};
+
+    let fraction = match (fraction, refunded_amount, original_amount) {
+        (Some(fraction), None, None) => fraction,
+        (None, Some(refunded), Some(original)) => {
+            if original <= Decimal::ZERO {
+                return Err(AppError::Validation(
+                    "originalAmount must be greater than 0".to_string(),
+                ));
+            }
+            refunded
+                .checked_div(original)
+                .ok_or_else(|| AppError::Validation("Refund amount is out of range".to_string()))?
+        },
+        _ => {
+            return Err(AppError::Validation(
+                "Provide either refundedFraction or both refundedAmount and originalAmount"
+                    .to_string(),
+            ))
+        },
+    };
+    let fraction = fraction.round_dp_with_strategy(6, RoundingStrategy::ToZero);
+    if fraction <= Decimal::ZERO || fraction > Decimal::ONE {
+        return Err(AppError::Validation(
+            "Refunded share must be greater than 0 and at most the original amount".to_string(),
+        ));
+    }
+    Ok(fraction)
+}
+
+/// How much of `original` a refund of `fraction` takes back: the
+/// proportional share rounded down, capped at what earlier adjustments
+/// (`already_adjusted`) left of it, so the adjustments for one award can
+/// never add up to more than the award itself
+fn refund_adjustment(original: i32, already_adjusted: i32, fraction: rust_decimal::Decimal) -> i32 {
+    use rust_decimal::prelude::ToPrimitive;
+
+    let remaining = original.saturating_sub(already_adjusted).max(0);
+    (rust_decimal::Decimal::from(original) * fraction)
+        .floor()
+        .to_i32()
+        .unwrap_or(remaining)
+        .clamp(0, remaining)
+}
+
 /// Admin nights operation result
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AdminNightsOperationResult {
     pub transaction_id: Uuid,
+    /// Points credited alongside the nights by the points-per-night rule
+    /// (always 0 for deductions)
+    pub points_awarded: i32,
     pub new_total_nights: i32,
     pub new_tier_name: String,
     pub loyalty_status: Option<LoyaltyStatusResponse>,
+    /// Set when this award was the member's first stay or a win-back
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stay_milestone: Option<StayMilestone>,
 }
 
 /// Admin spending with nights result
-#[derive(Debug, Clone, Serialize)]
+///
+/// `Deserialize` is needed to replay the result cached for an
+/// `Idempotency-Key` (see `run_idempotent`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AdminSpendingWithNightsResult {
     pub transaction_id: Uuid,
@@ -489,6 +1152,59 @@ pub struct AdminSpendingWithNightsResult {
     pub new_total_nights: i32,
     pub new_tier_name: String,
     pub loyalty_status: Option<LoyaltyStatusResponse>,
+    /// Set when this award was the member's first stay or a win-back
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stay_milestone: Option<StayMilestone>,
+}
+
+/// Projected outcome of an award-spending-with-nights, from
+/// `POST /admin/award-preview`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminAwardPreviewResult {
+    pub user_id: Uuid,
+    pub points_earned: i32,
+    pub current_points: i32,
+    pub projected_points: i32,
+    pub current_total_nights: i32,
+    pub projected_total_nights: i32,
+    /// `None` for a member who hasn't been placed in a tier yet
+    pub current_tier_name: Option<String>,
+    pub projected_tier_name: String,
+    pub tier_changed: bool,
+    /// The per-award caps the award exceeds; submitting it then needs
+    /// `confirmLarge` (see `check_award_caps`)
+    pub exceeded_caps: Vec<String>,
+}
+
+/// Outcome of a bulk award for one member
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkAwardUserResult {
+    pub user_id: Uuid,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_points: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_nights: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tier_name: Option<String>,
+    /// Why the award was skipped for this member
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Admin bulk award result
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminBulkAwardResult {
+    /// `reference_id` shared by every transaction in the batch
+    pub reference_id: String,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkAwardUserResult>,
 }
 
 // ============================================================================
@@ -522,6 +1238,7 @@ async fn get_tiers(
 async fn get_status(
     State(state): State<LoyaltyState>,
     Extension(auth_user): Extension<AuthUser>,
+    headers: axum::http::HeaderMap,
 ) -> Result<Json<ApiResponse<LoyaltyStatusResponse>>, AppError> {
     let user_id = Uuid::parse_str(&auth_user.id)
         .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
@@ -569,8 +1286,9 @@ async fn get_status(
                     .unwrap_or_else(|| "#CD7F32".to_string()),
                 benefits: loyalty
                     .tier_benefits
-                    .clone()
-                    .unwrap_or(serde_json::json!({})),
+                    .as_ref()
+                    .map(TierBenefits::from_json)
+                    .unwrap_or_default(),
                 min_nights: loyalty.tier_min_nights.unwrap_or(0),
             })
         } else {
@@ -581,9 +1299,20 @@ async fn get_status(
     let current_nights = loyalty.total_nights.unwrap_or(0);
     let next_tier_info = get_next_tier_info(state.db.pool(), current_nights).await?;
 
+    let current_points = loyalty.current_points.unwrap_or(0);
+    let pending_points = pending_points_balance(state.db.pool(), loyalty.user_id).await?;
     let response = LoyaltyStatusResponse {
         user_id: loyalty.user_id,
-        current_points: loyalty.current_points.unwrap_or(0),
+        current_points,
+        current_points_display: display_points(
+            current_points.into(),
+            &PointsDisplayConfig::default(),
+            headers
+                .get(header::ACCEPT_LANGUAGE)
+                .and_then(|v| v.to_str().ok()),
+        ),
+        available_points: current_points,
+        pending_points,
         total_nights: current_nights,
         tier: tier_info,
         tier_updated_at: loyalty.tier_updated_at,
@@ -640,53 +1369,28 @@ async fn get_transactions(
     let user_id = Uuid::parse_str(&auth_user.id)
         .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
 
-    let page = params.page.max(1);
-    let limit = params.limit.clamp(1, 100);
-    let offset = (page - 1) * limit;
+    // This legacy state carries no settings, so it uses the default window.
+    let cursor = params
+        .cursor
+        .as_deref()
+        .filter(|cursor| !cursor.trim().is_empty())
+        .map(TransactionCursor::decode)
+        .transpose()?;
 
-    // Get total count
-    let total = sqlx::query_scalar!(
-        r#"SELECT COUNT(*) as "count!: i64" FROM points_transactions WHERE user_id = $1"#,
+    let response = query_transaction_history(
+        state.db.pool(),
         user_id,
+        params.page,
+        params.limit,
+        cursor,
+        &TransactionHistoryConfig::default(),
     )
-    .fetch_one(state.db.pool())
     .await?;
 
-    // Get transactions
-    let transactions: Vec<PointsTransactionRow> = sqlx::query_as!(
-        PointsTransactionRow,
-        r#"
-        SELECT
-            id, user_id, points, type::text as "transaction_type!", description, reference_id,
-            admin_user_id, admin_reason, expires_at, created_at, nights_stayed
-        FROM points_transactions
-        WHERE user_id = $1
-        ORDER BY created_at DESC
-        LIMIT $2 OFFSET $3
-        "#,
-        user_id,
-        limit as i64,
-        offset as i64,
-    )
-    .fetch_all(state.db.pool())
-    .await?;
-
-    let transaction_responses: Vec<PointsTransactionResponse> = transactions
-        .into_iter()
-        .map(PointsTransactionResponse::from)
-        .collect();
-
-    let total_pages = ((total as f64) / (limit as f64)).ceil() as i32;
-
-    let response = PaginatedTransactionsResponse {
-        transactions: transaction_responses,
-        total,
-        page,
-        limit,
-        total_pages,
-    };
-
-    Ok(Json(ApiResponse::success(response)))
+    let pagination = response.pagination();
+    Ok(Json(ApiResponse::success_with_pagination(
+        response, pagination,
+    )))
 }
 
 /// POST /loyalty/award
@@ -720,6 +1424,8 @@ async fn award_points(
     // the same transaction once invoked.
     let mut tx = state.db.pool().begin().await?;
 
+    ensure_user_loyalty(&mut *tx, payload.user_id).await?;
+
     let current_loyalty = sqlx::query!(
         "SELECT tier_id FROM user_loyalty WHERE user_id = $1 FOR UPDATE",
         payload.user_id,
@@ -730,7 +1436,7 @@ async fn award_points(
 
     let old_tier_id = current_loyalty.tier_id;
 
-    let transaction_type = payload.source.as_deref().unwrap_or("admin_award");
+    let transaction_type = award_transaction_type(payload.source.as_deref())?.as_str();
     let description = payload
         .description
         .clone()
@@ -778,6 +1484,12 @@ async fn award_points(
     let tier_changed = old_tier_id != updated.tier_id;
     let new_tier_name = updated.tier_name;
 
+    // Legacy state has no notification service; the coupon is still
+    // granted and shows up in the member's wallet.
+    if tier_changed {
+        grant_tier_upgrade_coupon(&mut tx, payload.user_id, old_tier_id).await?;
+    }
+
     tx.commit().await?;
 
     let result = AwardPointsResult {
@@ -864,6 +1576,8 @@ async fn recalculate_tier(
         )
         .execute(&mut *tx)
         .await?;
+
+        grant_tier_upgrade_coupon(&mut tx, user_id, old_tier_id).await?;
     }
 
     tx.commit().await?;
@@ -874,6 +1588,7 @@ async fn recalculate_tier(
         new_tier: new_tier.name,
         tier_changed,
         total_nights,
+        downgrade_eligible_at: None,
     };
 
     Ok(Json(ApiResponse::with_message(
@@ -903,6 +1618,9 @@ async fn recalculate_tier(
 /// ### Authenticated Routes
 /// - `GET /status` - Get current user's loyalty status (authenticated)
 /// - `GET /transactions` - Get user's transaction history (authenticated)
+/// - `GET /referrals` - Get user's referral code and referrals (authenticated)
+/// - `GET /leaderboard` - Top opted-in members, `?metric=nights|points&limit=N` (max 100)
+/// - `POST /redeem` - Redeem points for their value at the member's tier rate
 /// - `POST /award` - Award points to a user (admin only)
 /// - `POST /recalculate/:user_id` - Recalculate user's tier (admin only)
 ///
@@ -910,21 +1628,41 @@ async fn recalculate_tier(
 /// - `GET /admin/users` - List all users' loyalty status with pagination
 /// - `POST /admin/award-points` - Award points to user
 /// - `POST /admin/deduct-points` - Deduct points from user
+/// - `POST /admin/partial-adjust` - Take back points for a partial refund
 /// - `GET /admin/transactions` - Get all admin transactions with pagination
 /// - `GET /admin/user/:userId/history` - Get specific user's history
 /// - `GET /admin/earning-rules` - Get earning rules config
-/// - `POST /admin/expire-points` - Trigger points expiration
+/// - `POST /admin/expire-points` - Trigger points expiration (`?dry_run=true` to preview)
 /// - `POST /admin/award-spending-with-nights` - Award based on spending + nights
-/// - `POST /admin/award-nights` - Award nights only
+/// - `POST /admin/award-preview` - Project an award-spending-with-nights without writing it
+/// - `POST /admin/award-nights` - Award nights (plus points-per-night, if configured)
 /// - `POST /admin/deduct-nights` - Deduct nights only
+/// - `POST /admin/bulk-award` - Award points/nights to up to 500 members at once
+/// - `POST /admin/recalculate-all` - Recalculate every member's tier in batches, in the background
+/// - `GET /admin/recalculate-all/:jobId` - Progress of a bulk tier recalculation
+/// - `GET /tiers/:tierId` - Get one tier, including inactive ones
+/// - `POST /admin/tiers` - Create a tier
+/// - `PUT /admin/tiers/:tierId` - Update a tier (`is_active: false` deactivates it)
+/// - `DELETE /admin/tiers/:tierId` - Deactivate a tier, moving its members to the nearest active one
+/// - `PUT /admin/tiers/:tierId/upgrade-coupon` - Set the coupon granted on tier upgrade
 pub fn routes() -> Router<AppState> {
     // Public routes (no auth required) - tiers can be viewed by anyone
-    let public_routes = Router::new().route("/tiers", get(get_tiers_full));
+    let public_routes = Router::new().route(
+        "/tiers",
+        get(get_tiers_full).layer(middleware::from_fn_with_state(
+            TIERS_CACHE_SCOPE,
+            response_cache_middleware,
+        )),
+    );
 
     // Authenticated routes - require valid JWT token
     let auth_routes = Router::new()
         .route("/status", get(get_status_full))
         .route("/transactions", get(get_transactions_full))
+        .route("/transactions/export", get(export_transactions_full))
+        .route("/referrals", get(get_referrals_full))
+        .route("/leaderboard", get(get_leaderboard_full))
+        .route("/redeem", post(redeem_points_full))
         .route("/award", post(award_points_full))
         .route("/recalculate/:user_id", post(recalculate_tier_full))
         .layer(middleware::from_fn(auth_middleware));
@@ -934,6 +1672,8 @@ pub fn routes() -> Router<AppState> {
         .route("/admin/users", get(admin_get_users))
         .route("/admin/award-points", post(admin_award_points))
         .route("/admin/deduct-points", post(admin_deduct_points))
+        .route("/admin/convert-points", post(admin_convert_points))
+        .route("/admin/partial-adjust", post(admin_partial_adjust))
         .route("/admin/transactions", get(admin_get_transactions))
         .route("/admin/user/:userId/history", get(admin_get_user_history))
         .route("/admin/earning-rules", get(admin_get_earning_rules))
@@ -942,8 +1682,37 @@ pub fn routes() -> Router<AppState> {
             "/admin/award-spending-with-nights",
             post(admin_award_spending_with_nights),
         )
+        .route("/admin/award-preview", post(admin_award_preview))
         .route("/admin/award-nights", post(admin_award_nights))
         .route("/admin/deduct-nights", post(admin_deduct_nights))
+        .route("/admin/bulk-award", post(admin_bulk_award))
+        .route("/admin/recalculate-all", post(admin_recalculate_all_tiers))
+        .route(
+            "/admin/recalculate-all/:jobId",
+            get(admin_get_tier_recalculation),
+        )
+        .route("/tiers/:tierId", get(admin_get_tier))
+        .route(
+            "/admin/tiers",
+            post(admin_create_tier).layer(middleware::from_fn_with_state(
+                TIERS_CACHE_SCOPE,
+                response_cache_middleware,
+            )),
+        )
+        .route(
+            "/admin/tiers/:tierId",
+            put(admin_update_tier).delete(admin_deactivate_tier).layer(
+                middleware::from_fn_with_state(TIERS_CACHE_SCOPE, response_cache_middleware),
+            ),
+        )
+        .route(
+            "/admin/tiers/:tierId/upgrade-coupon",
+            put(admin_set_tier_upgrade_coupon).layer(middleware::from_fn_with_state(
+                TIERS_CACHE_SCOPE,
+                response_cache_middleware,
+            )),
+        )
+        .layer(middleware::from_fn(transaction_layer))
         .layer(middleware::from_fn(auth_middleware));
 
     public_routes.merge(auth_routes).merge(admin_routes)
@@ -987,30 +1756,126 @@ pub fn routes_with_state(state: LoyaltyState) -> Router {
 // ============================================================================
 
 /// GET /tiers - using AppState
+///
+/// Public and identical for every caller, so it is sent with a cacheable
+/// `Cache-Control` (`CACHE_TIERS_MAX_AGE_SECS`), read through the Redis
+/// tiers cache (`LOYALTY_TIERS_CACHE_TTL_SECS`) and, when the `tiers` scope
+/// is configured, served from the shared response cache.
 async fn get_tiers_full(
     State(state): State<AppState>,
-) -> Result<Json<ApiResponse<Vec<TierResponse>>>, AppError> {
-    let tiers: Vec<TierRow> = sqlx::query_as!(
-        TierRow,
-        r#"
-        SELECT id, name, min_points, min_nights, benefits, color, sort_order, is_active, created_at, updated_at
-        FROM tiers
-        WHERE is_active = true
-        ORDER BY sort_order ASC
-        "#,
-    )
-    .fetch_all(state.db())
-    .await?;
+) -> Result<(CachePolicy, Json<ApiResponse<Vec<TierResponse>>>), AppError> {
+    let tiers = LoyaltyServiceImpl::new(state.db().clone())
+        .with_tiers_cache(state.redis(), state.config().loyalty.tiers_cache_ttl_secs)
+        .get_tiers_cached()
+        .await?;
 
     let tier_responses: Vec<TierResponse> = tiers.into_iter().map(TierResponse::from).collect();
+    let cache = CachePolicy::public(state.config().http_cache.tiers_max_age_secs);
 
-    Ok(Json(ApiResponse::success(tier_responses)))
+    Ok((cache, Json(ApiResponse::success(tier_responses))))
+}
+
+/// GET /loyalty/referrals - Current user's referral code and referrals
+async fn get_referrals_full(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<ApiResponse<ReferralsResponse>>, AppError> {
+    let user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
+
+    let referral_code: Option<String> =
+        sqlx::query_scalar("SELECT membership_id FROM user_profiles WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(state.db())
+            .await?
+            .flatten();
+
+    let referrals = crate::services::referral::list_referrals(state.db(), user_id)
+        .await?
+        .into_iter()
+        .map(|r| ReferralSummary {
+            id: r.id,
+            status: r.status,
+            points_earned: r.referrer_points,
+            created_at: r.created_at,
+            qualified_at: r.qualified_at,
+        })
+        .collect();
+
+    Ok(Json(ApiResponse::success(ReferralsResponse {
+        referral_code,
+        referrals,
+    })))
+}
+
+/// GET /loyalty/leaderboard - top opted-in members by nights or points
+async fn get_leaderboard_full(
+    State(state): State<AppState>,
+    Query(params): Query<LeaderboardQuery>,
+) -> Result<Json<ApiResponse<LeaderboardResponse>>, AppError> {
+    let entries = fetch_leaderboard(state.db(), params.metric, params.limit)
+        .await?
+        .into_iter()
+        .map(|row| LeaderboardEntry {
+            rank: row.rank,
+            display_name: mask_display_name(row.first_name.as_deref(), row.last_name.as_deref()),
+            tier_name: row.tier_name,
+            tier_color: row.tier_color,
+            value: match params.metric {
+                LeaderboardMetric::Nights => row.total_nights,
+                LeaderboardMetric::Points => row.current_points,
+            },
+        })
+        .collect();
+
+    Ok(Json(ApiResponse::success(LeaderboardResponse {
+        metric: params.metric,
+        entries,
+    })))
+}
+
+/// POST /loyalty/redeem - Redeem the current user's points
+///
+/// Priced at the member's tier `points_redemption_rate`, or
+/// `LOYALTY_POINTS_REDEMPTION_RATE` when the tier has none (see
+/// `services::loyalty::redeem_points`).
+async fn redeem_points_full(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(payload): Json<RedeemPointsRequest>,
+) -> Result<Json<ApiResponse<PointsRedemption>>, AppError> {
+    payload.validate().map_err(AppError::from)?;
+    let user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
+
+    let reference_id = payload.reference_id.trim();
+    if reference_id.is_empty() {
+        return Err(AppError::Validation(
+            "Redemption reference is required".to_string(),
+        ));
+    }
+
+    let redemption = redeem_points(
+        state.db(),
+        user_id,
+        payload.points,
+        reference_id,
+        payload.description.as_deref(),
+        state.config().loyalty.default_redemption_rate(),
+    )
+    .await?;
+
+    Ok(Json(ApiResponse::with_message(
+        redemption,
+        "Points redeemed",
+    )))
 }
 
 /// GET /loyalty/status - using FullAppState
 async fn get_status_full(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
+    headers: axum::http::HeaderMap,
 ) -> Result<Json<ApiResponse<LoyaltyStatusResponse>>, AppError> {
     let user_id = Uuid::parse_str(&auth_user.id)
         .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
@@ -1047,8 +1912,9 @@ async fn get_status_full(
                     .unwrap_or_else(|| "#CD7F32".to_string()),
                 benefits: loyalty
                     .tier_benefits
-                    .clone()
-                    .unwrap_or(serde_json::json!({})),
+                    .as_ref()
+                    .map(TierBenefits::from_json)
+                    .unwrap_or_default(),
                 min_nights: loyalty.tier_min_nights.unwrap_or(0),
             })
         } else {
@@ -1058,9 +1924,20 @@ async fn get_status_full(
     let current_nights = loyalty.total_nights.unwrap_or(0);
     let next_tier_info = get_next_tier_info(state.db(), current_nights).await?;
 
+    let current_points = loyalty.current_points.unwrap_or(0);
+    let pending_points = pending_points_balance(state.db(), loyalty.user_id).await?;
     let response = LoyaltyStatusResponse {
         user_id: loyalty.user_id,
-        current_points: loyalty.current_points.unwrap_or(0),
+        current_points,
+        current_points_display: display_points(
+            current_points.into(),
+            &state.config().points_display,
+            headers
+                .get(header::ACCEPT_LANGUAGE)
+                .and_then(|v| v.to_str().ok()),
+        ),
+        available_points: current_points,
+        pending_points,
         total_nights: current_nights,
         tier: tier_info,
         tier_updated_at: loyalty.tier_updated_at,
@@ -1080,50 +1957,93 @@ async fn get_transactions_full(
     let user_id = Uuid::parse_str(&auth_user.id)
         .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
 
-    let page = params.page.max(1);
-    let limit = params.limit.clamp(1, 100);
-    let offset = (page - 1) * limit;
+    let cursor = params
+        .cursor
+        .as_deref()
+        .filter(|cursor| !cursor.trim().is_empty())
+        .map(TransactionCursor::decode)
+        .transpose()?;
 
-    let total = sqlx::query_scalar!(
-        r#"SELECT COUNT(*) as "count!: i64" FROM points_transactions WHERE user_id = $1"#,
+    let response = query_transaction_history(
+        state.db(),
         user_id,
+        params.page,
+        params.limit,
+        cursor,
+        &state.config().transaction_history,
     )
-    .fetch_one(state.db())
     .await?;
 
-    let transactions: Vec<PointsTransactionRow> = sqlx::query_as!(
-        PointsTransactionRow,
-        r#"
-        SELECT id, user_id, points, type::text as "transaction_type!", description, reference_id,
-               admin_user_id, admin_reason, expires_at, created_at, nights_stayed
-        FROM points_transactions
-        WHERE user_id = $1
-        ORDER BY created_at DESC
-        LIMIT $2 OFFSET $3
-        "#,
-        user_id,
-        limit as i64,
-        offset as i64,
-    )
-    .fetch_all(state.db())
-    .await?;
+    let pagination = response.pagination();
+    Ok(Json(ApiResponse::success_with_pagination(
+        response, pagination,
+    )))
+}
 
-    let transaction_responses: Vec<PointsTransactionResponse> = transactions
-        .into_iter()
-        .map(PointsTransactionResponse::from)
-        .collect();
+/// GET /loyalty/transactions/export - The current user's full points
+/// history as CSV, newest first
+///
+/// Unlike `GET /transactions` this is not limited to the history window.
+/// Rows are streamed straight from the database, so memory use doesn't
+/// grow with the length of the history.
+async fn export_transactions_full(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<axum::response::Response, AppError> {
+    use futures::StreamExt;
 
-    let total_pages = ((total as f64) / (limit as f64)).ceil() as i32;
+    let user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
 
-    let response = PaginatedTransactionsResponse {
-        transactions: transaction_responses,
-        total,
-        page,
-        limit,
-        total_pages,
-    };
+    let pool = state.db().clone();
+    let (sender, receiver) = tokio::sync::mpsc::channel::<Result<String, std::io::Error>>(32);
 
-    Ok(Json(ApiResponse::success(response)))
+    tokio::spawn(async move {
+        if sender
+            .send(Ok(TransactionExportRow::CSV_HEADER.to_string()))
+            .await
+            .is_err()
+        {
+            return;
+        }
+
+        let mut rows = sqlx::query_as::<_, TransactionExportRow>(
+            r#"
+            SELECT id, created_at, type AS transaction_type, points, nights_stayed,
+                   description, reference_id, expires_at
+            FROM points_transactions
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch(&pool);
+
+        while let Some(row) = rows.next().await {
+            let line = row.map(|row| row.to_csv_line()).map_err(|e| {
+                tracing::error!(user_id = %user_id, error = %e, "Transaction export failed");
+                std::io::Error::other(e.to_string())
+            });
+            let failed = line.is_err();
+            // Stop on a database error (the client sees a truncated body)
+            // or when the client has gone away.
+            if sender.send(line).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    axum::response::Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/csv; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"points-transactions.csv\"",
+        )
+        .body(axum::body::Body::from_stream(
+            tokio_stream::wrappers::ReceiverStream::new(receiver),
+        ))
+        .map_err(|e| AppError::Internal(format!("Failed to build export response: {}", e)))
 }
 
 /// POST /loyalty/award - using FullAppState
@@ -1160,13 +2080,20 @@ async fn award_points_full(
         ));
     }
 
+    check_award_caps(
+        &state,
+        &auth_user,
+        payload.user_id,
+        "award",
+        payload.points.into(),
+        payload.nights.into(),
+        payload.confirm_large,
+    )
+    .await?;
+
     // Parse the optional `Idempotency-Key` header. See
     // `services/idempotency.rs` for the full contract.
-    let idempotency_key = headers
-        .get("idempotency-key")
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty());
+    let idempotency_key = idempotency_key(&headers);
 
     // Pre-check for a previously-cached response. Has to happen against
     // the pool (not our own transaction) because the original write
@@ -1213,6 +2140,8 @@ async fn award_points_full(
     // against the same user — without it, two parallel awards could
     // both read the same `old_points` and report stale deltas (even
     // though the SP would still arrive at a correct total).
+    ensure_user_loyalty(&mut *tx, payload.user_id).await?;
+
     let current_loyalty = sqlx::query!(
         "SELECT current_points, total_nights, tier_id FROM user_loyalty WHERE user_id = $1 FOR UPDATE",
         payload.user_id,
@@ -1223,7 +2152,7 @@ async fn award_points_full(
 
     let old_tier_id = current_loyalty.tier_id;
 
-    let transaction_type = payload.source.as_deref().unwrap_or("admin_award");
+    let transaction_type = award_transaction_type(payload.source.as_deref())?.as_str();
     let description = payload
         .description
         .clone()
@@ -1284,6 +2213,12 @@ async fn award_points_full(
     let new_total_nights = updated.total_nights.unwrap_or(0);
     let tier_changed = old_tier_id != updated.tier_id;
 
+    let upgrade_grant = if tier_changed {
+        grant_tier_upgrade_coupon(&mut tx, payload.user_id, old_tier_id).await?
+    } else {
+        None
+    };
+
     let result = AwardPointsResult {
         transaction_id,
         points_awarded: payload.points,
@@ -1310,6 +2245,10 @@ async fn award_points_full(
 
     tx.commit().await?;
 
+    if let Some(grant) = upgrade_grant {
+        notify_coupon_assignment(&state, grant.coupon_id, &[payload.user_id]).await;
+    }
+
     Ok(Json(ApiResponse::with_message(
         result,
         "Points awarded successfully",
@@ -1347,43 +2286,36 @@ async fn recalculate_tier_full(
     let old_tier_id = current.tier_id;
     let old_tier_name = current.tier_name;
 
-    let new_tier: Option<TierRow> = sqlx::query_as!(
-        TierRow,
-        r#"
-        SELECT id, name, min_points, min_nights, benefits, color, sort_order, is_active, created_at, updated_at
-        FROM tiers
-        WHERE min_nights <= $1 AND is_active = true
-        ORDER BY min_nights DESC
-        LIMIT 1
-        "#,
-        total_nights,
+    // Downgrades wait out `LOYALTY_TIER_DOWNGRADE_GRACE_DAYS`.
+    let recalculation = recalculate_tier_with_grace(
+        &mut tx,
+        user_id,
+        state.config().loyalty.tier_downgrade_grace_days,
     )
-    .fetch_optional(&mut *tx)
     .await?;
+    let tier_changed = recalculation.tier_changed;
 
-    let new_tier = new_tier
-        .ok_or_else(|| AppError::Internal("No tier found for user's night count".to_string()))?;
+    let upgrade_grant = if tier_changed {
+        grant_tier_upgrade_coupon(&mut tx, user_id, old_tier_id).await?
+    } else {
+        None
+    };
 
-    let tier_changed = old_tier_id != Some(new_tier.id);
+    tx.commit().await?;
 
-    if tier_changed {
-        sqlx::query!(
-            "UPDATE user_loyalty SET tier_id = $1, tier_updated_at = NOW(), updated_at = NOW() WHERE user_id = $2",
-            new_tier.id,
-            user_id,
-        )
-        .execute(&mut *tx)
-        .await?;
+    if let Some(grant) = upgrade_grant {
+        notify_coupon_assignment(&state, grant.coupon_id, &[user_id]).await;
     }
 
-    tx.commit().await?;
-
     let result = RecalculateTierResult {
         user_id,
         previous_tier: Some(old_tier_name),
-        new_tier: new_tier.name,
+        new_tier: recalculation.tier_name.ok_or_else(|| {
+            AppError::Internal("No tier found for user's night count".to_string())
+        })?,
         tier_changed,
         total_nights,
+        downgrade_eligible_at: recalculation.downgrade_eligible_at,
     };
 
     Ok(Json(ApiResponse::with_message(
@@ -1400,14 +2332,197 @@ async fn recalculate_tier_full(
 // Admin Handlers (AppState)
 // ============================================================================
 
+/// One page of a member's points history, limited to the configured
+/// window (`TRANSACTION_HISTORY_MAX_AGE_DAYS` / `TRANSACTION_HISTORY_MAX_ROWS`).
+///
+/// Only the newest `max_rows` transactions since `now - max_age_days` can be
+/// paged through, and the count stops at `max_rows + 1`, so the cost is
+/// bounded by the window rather than the member's lifetime history.
+///
+/// With a `cursor` the page starts right after it (keyset pagination) and
+/// `page` is ignored; otherwise `page` selects it by offset. Both walk the
+/// same `created_at DESC, id DESC` order and return `next_cursor`, so a
+/// client can switch to cursors after the first page.
+async fn query_transaction_history(
+    pool: &PgPool,
+    user_id: Uuid,
+    page: i32,
+    limit: i32,
+    cursor: Option<TransactionCursor>,
+    history: &TransactionHistoryConfig,
+) -> Result<PaginatedTransactionsResponse, AppError> {
+    let page = page.max(1);
+    let limit = limit.clamp(1, 100);
+    let max_rows = i64::from(history.max_rows);
+    let since = Utc::now() - chrono::Duration::days(i64::from(history.max_age_days));
+
+    let in_window: i64 = timed_query(
+        "loyalty.transaction_history.count",
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM (
+                SELECT 1 FROM points_transactions
+                WHERE user_id = $1 AND created_at >= $2
+                ORDER BY created_at DESC
+                LIMIT $3
+            ) recent
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .bind(max_rows + 1)
+        .fetch_one(pool),
+    )
+    .await?;
+
+    let truncated = in_window > max_rows
+        || timed_query(
+            "loyalty.transaction_history.truncated",
+            sqlx::query_scalar::<_, bool>(
+                r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM points_transactions
+                    WHERE user_id = $1 AND (created_at < $2 OR created_at IS NULL)
+                )
+                "#,
+            )
+            .bind(user_id)
+            .bind(since)
+            .fetch_one(pool),
+        )
+        .await?;
+    let total = in_window.min(max_rows);
+
+    // Rows of the window before this page: the offset, or for a cursor the
+    // rows at or before it (bounded by the window like the count above).
+    let skipped: i64 = match cursor {
+        None => (i64::from(page) - 1) * i64::from(limit),
+        Some(cursor) => {
+            timed_query(
+                "loyalty.transaction_history.cursor_position",
+                sqlx::query_scalar(
+                    r#"
+                    SELECT COUNT(*) FROM (
+                        SELECT 1 FROM points_transactions
+                        WHERE user_id = $1 AND created_at >= $2
+                          AND (created_at, id) >= ($3, $4)
+                        ORDER BY created_at DESC, id DESC
+                        LIMIT $5
+                    ) seen
+                    "#,
+                )
+                .bind(user_id)
+                .bind(since)
+                .bind(cursor.created_at)
+                .bind(cursor.id)
+                .bind(max_rows + 1)
+                .fetch_one(pool),
+            )
+            .await?
+        },
+    };
+
+    let rows: Vec<PointsTransactionRow> = if skipped >= total {
+        Vec::new()
+    } else {
+        let page_size = i64::from(limit).min(total - skipped);
+        match cursor {
+            None => {
+                timed_query(
+                    "loyalty.transaction_history.page",
+                    sqlx::query_as::<_, PointsTransactionRow>(
+                        r#"
+                        SELECT id, user_id, points, type AS transaction_type, description, reference_id,
+                               admin_user_id, admin_reason, expires_at, created_at, nights_stayed
+                        FROM points_transactions
+                        WHERE user_id = $1 AND created_at >= $2
+                        ORDER BY created_at DESC, id DESC
+                        LIMIT $3 OFFSET $4
+                        "#,
+                    )
+                    .bind(user_id)
+                    .bind(since)
+                    .bind(page_size)
+                    .bind(skipped)
+                    .fetch_all(pool),
+                )
+                .await?
+            },
+            Some(cursor) => {
+                timed_query(
+                    "loyalty.transaction_history.page_after_cursor",
+                    sqlx::query_as::<_, PointsTransactionRow>(
+                        r#"
+                        SELECT id, user_id, points, type AS transaction_type, description, reference_id,
+                               admin_user_id, admin_reason, expires_at, created_at, nights_stayed
+                        FROM points_transactions
+                        WHERE user_id = $1 AND created_at >= $2
+                          AND (created_at, id) < ($3, $4)
+                        ORDER BY created_at DESC, id DESC
+                        LIMIT $5
+                        "#,
+                    )
+                    .bind(user_id)
+                    .bind(since)
+                    .bind(cursor.created_at)
+                    .bind(cursor.id)
+                    .bind(page_size)
+                    .fetch_all(pool),
+                )
+                .await?
+            },
+        }
+    };
+
+    let next_cursor = if skipped + (rows.len() as i64) < total {
+        rows.last().and_then(|row| {
+            row.created_at.map(|created_at| {
+                TransactionCursor {
+                    created_at,
+                    id: row.id,
+                }
+                .encode()
+            })
+        })
+    } else {
+        None
+    };
+
+    let transactions: Vec<PointsTransactionResponse> = rows
+        .into_iter()
+        .map(PointsTransactionResponse::from)
+        .collect();
+
+    let total_pages = ((total as f64) / (limit as f64)).ceil() as i32;
+
+    Ok(PaginatedTransactionsResponse {
+        transactions,
+        total,
+        page,
+        limit,
+        total_pages,
+        truncated,
+        history_limit: Some(TransactionHistoryLimit {
+            max_age_days: history.max_age_days,
+            max_rows: history.max_rows,
+            since,
+            export_path: TRANSACTIONS_EXPORT_PATH,
+        }),
+        next_cursor,
+    })
+}
+
 /// Helper function to get user's loyalty status
 async fn get_user_loyalty_status_internal(
     pool: &PgPool,
     user_id: Uuid,
+    points_display: &PointsDisplayConfig,
 ) -> Result<Option<LoyaltyStatusResponse>, AppError> {
-    let loyalty: Option<UserLoyaltyWithTierRow> = sqlx::query_as!(
-        UserLoyaltyWithTierRow,
-        r#"
+    let loyalty: Option<UserLoyaltyWithTierRow> = timed_query(
+        "loyalty.status",
+        sqlx::query_as!(
+            UserLoyaltyWithTierRow,
+            r#"
         SELECT
             ul.user_id, ul.current_points, ul.total_nights, ul.tier_id,
             ul.tier_updated_at, ul.points_updated_at, ul.created_at, ul.updated_at,
@@ -1418,9 +2533,10 @@ async fn get_user_loyalty_status_internal(
         LEFT JOIN tiers t ON ul.tier_id = t.id
         WHERE ul.user_id = $1
         "#,
-        user_id,
+            user_id,
+        )
+        .fetch_optional(pool),
     )
-    .fetch_optional(pool)
     .await?;
 
     match loyalty {
@@ -1437,8 +2553,9 @@ async fn get_user_loyalty_status_internal(
                         .unwrap_or_else(|| "#CD7F32".to_string()),
                     benefits: loyalty
                         .tier_benefits
-                        .clone()
-                        .unwrap_or(serde_json::json!({})),
+                        .as_ref()
+                        .map(TierBenefits::from_json)
+                        .unwrap_or_default(),
                     min_nights: loyalty.tier_min_nights.unwrap_or(0),
                 })
             } else {
@@ -1448,9 +2565,14 @@ async fn get_user_loyalty_status_internal(
             let current_nights = loyalty.total_nights.unwrap_or(0);
             let next_tier_info = get_next_tier_info(pool, current_nights).await?;
 
+            let current_points = loyalty.current_points.unwrap_or(0);
+            let pending_points = pending_points_balance(pool, loyalty.user_id).await?;
             Ok(Some(LoyaltyStatusResponse {
                 user_id: loyalty.user_id,
-                current_points: loyalty.current_points.unwrap_or(0),
+                current_points,
+                current_points_display: display_points(current_points.into(), points_display, None),
+                available_points: current_points,
+                pending_points,
                 total_nights: current_nights,
                 tier: tier_info,
                 tier_updated_at: loyalty.tier_updated_at,
@@ -1462,6 +2584,64 @@ async fn get_user_loyalty_status_internal(
     }
 }
 
+/// `admin_get_users` search: `u.id` of members whose email, UUID, first or
+/// last name, membership ID or phone contains the `$1` pattern
+/// (case-insensitive).
+///
+/// Matching ids are collected per table so each side can use its trigram
+/// indexes; an OR across the joined tables would scan both.
+const LOYALTY_USER_SEARCH_PREDICATE: &str = r#"
+    u.id IN (
+        SELECT id FROM users WHERE email ILIKE $1 OR id::text ILIKE $1
+        UNION
+        SELECT user_id FROM user_profiles
+        WHERE first_name ILIKE $1 OR last_name ILIKE $1
+           OR membership_id ILIKE $1 OR phone ILIKE $1
+    )
+"#;
+
+/// `admin_get_users` rows, before filtering and paging.
+///
+/// Progress toward the next tier is clamped to 0-100 (members can hold
+/// more nights than the next tier needs while a downgrade grace period
+/// runs, or after a tier's threshold is lowered), and a next tier that
+/// needs 0 nights counts as reached rather than dividing by zero.
+const ADMIN_USER_LOYALTY_SELECT: &str = r#"
+    SELECT
+        ul.user_id,
+        ul.current_points,
+        ul.total_nights,
+        t.name as tier_name,
+        t.color as tier_color,
+        t.benefits as tier_benefits,
+        t.sort_order as tier_level,
+        CASE
+            WHEN next_tier.min_nights IS NOT NULL AND next_tier.min_nights > 0
+            THEN LEAST(GREATEST(ROUND((COALESCE(ul.total_nights, 0)::numeric / next_tier.min_nights) * 100), 0), 100)::float8
+            ELSE 100.0
+        END as progress_percentage,
+        next_tier.min_nights as next_tier_nights,
+        next_tier.name as next_tier_name,
+        CASE
+            WHEN next_tier.min_nights IS NOT NULL
+            THEN GREATEST(next_tier.min_nights - COALESCE(ul.total_nights, 0), 0)
+            ELSE NULL
+        END as nights_to_next_tier,
+        up.first_name,
+        up.last_name,
+        up.phone,
+        up.membership_id,
+        u.email,
+        u.oauth_provider,
+        u.oauth_provider_id,
+        u.created_at as user_created_at
+    FROM user_loyalty ul
+    JOIN tiers t ON ul.tier_id = t.id
+    LEFT JOIN tiers next_tier ON next_tier.sort_order = t.sort_order + 1 AND next_tier.is_active = true
+    JOIN users u ON ul.user_id = u.id
+    LEFT JOIN user_profiles up ON u.id = up.user_id
+"#;
+
 /// GET /loyalty/admin/users - Get all users' loyalty status (admin only)
 async fn admin_get_users(
     State(state): State<AppState>,
@@ -1475,115 +2655,52 @@ async fn admin_get_users(
     let limit = params.limit.clamp(1, 100);
     let offset = params.offset.max(0);
 
-    // Build query based on search term
-    let (users, total) = if let Some(ref search) = params.search {
-        let search_pattern = format!("%{}%", search);
-        let users: Vec<AdminUserLoyaltyRow> = sqlx::query_as!(
-            AdminUserLoyaltyRow,
+    // Build query based on search term. Terms shorter than
+    // SEARCH_MIN_LENGTH are rejected; longer ones are answered from the
+    // trigram indexes.
+    let search_pattern =
+        search_pattern(params.search.as_deref(), state.config().search.min_length)?;
+    let (users, total) = if let Some(ref search_pattern) = search_pattern {
+        let users: Vec<AdminUserLoyaltyRow> = sqlx::query_as(&format!(
             r#"
-            SELECT
-                ul.user_id,
-                ul.current_points,
-                ul.total_nights,
-                t.name as tier_name,
-                t.color as tier_color,
-                t.benefits as tier_benefits,
-                t.sort_order as tier_level,
-                CASE
-                    WHEN next_tier.min_nights IS NOT NULL
-                    THEN ROUND((ul.total_nights::numeric / next_tier.min_nights) * 100)::float8
-                    ELSE 100.0
-                END as progress_percentage,
-                next_tier.min_nights as next_tier_nights,
-                next_tier.name as next_tier_name,
-                CASE
-                    WHEN next_tier.min_nights IS NOT NULL
-                    THEN (next_tier.min_nights - ul.total_nights)
-                    ELSE NULL
-                END as nights_to_next_tier,
-                up.first_name,
-                up.last_name,
-                up.phone,
-                up.membership_id,
-                u.email,
-                u.oauth_provider,
-                u.oauth_provider_id,
-                u.created_at as user_created_at
-            FROM user_loyalty ul
-            JOIN tiers t ON ul.tier_id = t.id
-            LEFT JOIN tiers next_tier ON next_tier.sort_order = t.sort_order + 1 AND next_tier.is_active = true
-            JOIN users u ON ul.user_id = u.id
-            LEFT JOIN user_profiles up ON u.id = up.user_id
-            WHERE (u.email ILIKE $1 OR up.first_name ILIKE $1 OR up.last_name ILIKE $1
-                   OR u.id::text ILIKE $1 OR up.membership_id ILIKE $1 OR up.phone ILIKE $1)
+            {}
+            WHERE {}
             ORDER BY ul.total_nights DESC, ul.current_points DESC
             LIMIT $2 OFFSET $3
             "#,
-            &search_pattern,
-            limit as i64,
-            offset as i64,
-        )
+            ADMIN_USER_LOYALTY_SELECT, LOYALTY_USER_SEARCH_PREDICATE
+        ))
+        .bind(search_pattern)
+        .bind(limit as i64)
+        .bind(offset as i64)
         .fetch_all(state.db())
         .await?;
 
-        let total = sqlx::query_scalar!(
+        let total: i64 = sqlx::query_scalar(&format!(
             r#"
-            SELECT COUNT(*) as "count!: i64"
+            SELECT COUNT(*)
             FROM user_loyalty ul
             JOIN users u ON ul.user_id = u.id
-            LEFT JOIN user_profiles up ON u.id = up.user_id
-            WHERE (u.email ILIKE $1 OR up.first_name ILIKE $1 OR up.last_name ILIKE $1
-                   OR u.id::text ILIKE $1 OR up.membership_id ILIKE $1 OR up.phone ILIKE $1)
+            WHERE {}
             "#,
-            &search_pattern,
-        )
+            LOYALTY_USER_SEARCH_PREDICATE
+        ))
+        .bind(search_pattern)
         .fetch_one(state.db())
         .await?;
 
         (users, total)
     } else {
-        let users: Vec<AdminUserLoyaltyRow> = sqlx::query_as!(
-            AdminUserLoyaltyRow,
+        let users: Vec<AdminUserLoyaltyRow> = sqlx::query_as(&format!(
             r#"
-            SELECT
-                ul.user_id,
-                ul.current_points,
-                ul.total_nights,
-                t.name as tier_name,
-                t.color as tier_color,
-                t.benefits as tier_benefits,
-                t.sort_order as tier_level,
-                CASE
-                    WHEN next_tier.min_nights IS NOT NULL
-                    THEN ROUND((ul.total_nights::numeric / next_tier.min_nights) * 100)::float8
-                    ELSE 100.0
-                END as progress_percentage,
-                next_tier.min_nights as next_tier_nights,
-                next_tier.name as next_tier_name,
-                CASE
-                    WHEN next_tier.min_nights IS NOT NULL
-                    THEN (next_tier.min_nights - ul.total_nights)
-                    ELSE NULL
-                END as nights_to_next_tier,
-                up.first_name,
-                up.last_name,
-                up.phone,
-                up.membership_id,
-                u.email,
-                u.oauth_provider,
-                u.oauth_provider_id,
-                u.created_at as user_created_at
-            FROM user_loyalty ul
-            JOIN tiers t ON ul.tier_id = t.id
-            LEFT JOIN tiers next_tier ON next_tier.sort_order = t.sort_order + 1 AND next_tier.is_active = true
-            JOIN users u ON ul.user_id = u.id
-            LEFT JOIN user_profiles up ON u.id = up.user_id
+            {}
             ORDER BY ul.total_nights DESC, ul.current_points DESC
             LIMIT $1 OFFSET $2
             "#,
-            limit as i64,
-            offset as i64,
-        )
+            ADMIN_USER_LOYALTY_SELECT
+        ))
+        .bind(limit as i64)
+        .bind(offset as i64)
         .fetch_all(state.db())
         .await?;
 
@@ -1594,9 +2711,12 @@ async fn admin_get_users(
         (users, total)
     };
 
+    let pagination = offset_pagination(offset, limit, total);
     let response = AdminUsersResponse { users, total };
 
-    Ok(Json(ApiResponse::success(response)))
+    Ok(Json(ApiResponse::success_with_pagination(
+        response, pagination,
+    )))
 }
 
 /// POST /loyalty/admin/award-points - Award points to a user (admin only)
@@ -1605,16 +2725,36 @@ async fn admin_get_users(
 /// hand-rolling the points_transactions insert + user_loyalty update
 /// (CLAUDE.md rule + Correctness HIGH #4). Nights default to 0 — this
 /// endpoint is points-only; nights-aware admin awards go through
-/// `admin_award_nights` or `admin_award_spending_with_nights`.
+/// `admin_award_nights` or `admin_award_spending_with_nights`. Accepts an
+/// `Idempotency-Key` header; see `run_idempotent`.
 async fn admin_award_points(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
+    headers: axum::http::HeaderMap,
     Json(payload): Json<AdminAwardPointsRequest>,
 ) -> Result<Json<ApiResponse<AdminOperationResult>>, AppError> {
     if !has_role(&auth_user, "admin") {
         return Err(AppError::Forbidden("Admin access required".to_string()));
     }
 
+    let (result, replayed) =
+        run_idempotent(&state, &headers, "award-points", payload.user_id, || {
+            apply_award_points(&state, &auth_user, &payload)
+        })
+        .await?;
+
+    Ok(Json(ApiResponse::with_message(
+        result,
+        replay_message("Points awarded successfully", replayed),
+    )))
+}
+
+/// The award behind `admin_award_points`, run once per idempotency key
+async fn apply_award_points(
+    state: &AppState,
+    auth_user: &AuthUser,
+    payload: &AdminAwardPointsRequest,
+) -> Result<AdminOperationResult, AppError> {
     let admin_user_id = Uuid::parse_str(&auth_user.id)
         .map_err(|_| AppError::BadRequest("Invalid admin user ID".to_string()))?;
 
@@ -1624,27 +2764,201 @@ async fn admin_award_points(
         ));
     }
 
-    let description = payload
+    check_award_caps(
+        state,
+        auth_user,
+        payload.user_id,
+        "admin_award_points",
+        payload.points.into(),
+        0,
+        payload.confirm_large,
+    )
+    .await?;
+
+    let description = payload
         .description
         .clone()
         .unwrap_or_else(|| "Points awarded by admin".to_string());
 
     let admin_reason = format!("Points awarded by admin user {}", admin_user_id);
 
-    let sp_result: JsonValue = sqlx::query_scalar!(
+    let adjusted = LoyaltyServiceImpl::new(state.db().clone())
+        .award_points_atomic(PointsAdjustmentParams {
+            user_id: payload.user_id,
+            points: payload.points,
+            transaction_type: PointsTransactionType::AdminAward,
+            description,
+            reference_id: payload.reference_id.clone(),
+            admin_user_id: Some(admin_user_id),
+            admin_reason: Some(admin_reason),
+        })
+        .await?;
+    let transaction_id = adjusted.transaction_id;
+
+    // Get updated loyalty status
+    let loyalty_status = get_user_loyalty_status_internal(
+        state.db(),
+        payload.user_id,
+        &state.config().points_display,
+    )
+    .await?;
+
+    let result = AdminOperationResult {
+        transaction_id,
+        loyalty_status,
+    };
+
+    Ok(result)
+}
+
+/// POST /loyalty/admin/convert-points - Convert a partner program balance
+/// into points (admin only)
+///
+/// The rate comes from `POINTS_CONVERSION_RATES`; the result is rounded
+/// down and credited as a `converted` transaction referencing
+/// `<program>:<external reference>`. Idempotent per external reference:
+/// repeating a conversion returns the original result with
+/// `alreadyConverted: true`, while reusing a reference for a different
+/// member or balance is a 409. Every conversion is recorded in
+/// `points_conversions` and the member's audit log.
+async fn admin_convert_points(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(payload): Json<AdminConvertPointsRequest>,
+) -> Result<Json<ApiResponse<PointsConversionResult>>, AppError> {
+    if !has_role(&auth_user, "admin") {
+        return Err(AppError::Forbidden("Admin access required".to_string()));
+    }
+
+    let admin_user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::BadRequest("Invalid admin user ID".to_string()))?;
+
+    let source_program = payload.source_program.trim().to_ascii_lowercase();
+    let external_reference = payload.external_reference.trim().to_string();
+    if external_reference.is_empty() || external_reference.len() > 100 {
+        return Err(AppError::Validation(
+            "externalReference must be 1-100 characters".to_string(),
+        ));
+    }
+    if payload.external_points <= 0 {
+        return Err(AppError::Validation(
+            "External points must be greater than 0".to_string(),
+        ));
+    }
+    let rate = state
+        .config()
+        .points_conversion
+        .rate_for(&source_program)
+        .ok_or_else(|| {
+            AppError::Validation(format!(
+                "No conversion rate is configured for program '{}'",
+                source_program
+            ))
+        })?;
+    let points = converted_points(payload.external_points, rate)
+        .ok_or_else(|| AppError::Validation("Converted points are out of range".to_string()))?;
+    if points <= 0 {
+        return Err(AppError::Validation(
+            "External balance converts to less than 1 point".to_string(),
+        ));
+    }
+
+    ensure_user_loyalty(state.db(), payload.user_id).await?;
+
+    let mut tx = state.db().begin().await?;
+
+    // Claim the reference first. A concurrent request for the same
+    // reference blocks here until this transaction finishes, then sees the
+    // committed row and replays it instead of awarding again.
+    let claimed: Option<Uuid> = sqlx::query_scalar(
         r#"
-        SELECT award_points($1, $2, 'admin_award'::varchar, $3, $4, $5, $6, 0) AS "result!"
+        INSERT INTO points_conversions
+            (user_id, source_program, external_reference, external_points, rate,
+             points_awarded, converted_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (source_program, external_reference) DO NOTHING
+        RETURNING id
         "#,
-        payload.user_id,
-        payload.points,
-        &description,
-        payload.reference_id.as_deref(),
-        admin_user_id,
-        &admin_reason,
     )
-    .fetch_one(state.db())
+    .bind(payload.user_id)
+    .bind(&source_program)
+    .bind(&external_reference)
+    .bind(payload.external_points)
+    .bind(rate)
+    .bind(points)
+    .bind(admin_user_id)
+    .fetch_optional(&mut *tx)
     .await?;
 
+    let Some(conversion_id) = claimed else {
+        tx.rollback().await?;
+
+        let existing: PointsConversionRow = sqlx::query_as(
+            r#"
+            SELECT id, user_id, transaction_id, source_program, external_reference,
+                   external_points, rate, points_awarded
+            FROM points_conversions
+            WHERE source_program = $1 AND external_reference = $2
+            "#,
+        )
+        .bind(&source_program)
+        .bind(&external_reference)
+        .fetch_one(state.db())
+        .await?;
+
+        if existing.user_id != payload.user_id
+            || existing.external_points != payload.external_points
+        {
+            return Err(AppError::Conflict(format!(
+                "Reference '{}' from '{}' was already converted for a different member or balance",
+                external_reference, source_program
+            )));
+        }
+
+        let loyalty_status = get_user_loyalty_status_internal(
+            state.db(),
+            existing.user_id,
+            &state.config().points_display,
+        )
+        .await?;
+
+        return Ok(Json(ApiResponse::with_message(
+            PointsConversionResult {
+                conversion_id: existing.id,
+                transaction_id: existing.transaction_id,
+                source_program: existing.source_program,
+                external_reference: existing.external_reference,
+                external_points: existing.external_points,
+                rate: existing.rate,
+                points_awarded: existing.points_awarded,
+                already_converted: true,
+                loyalty_status,
+            },
+            "Points were already converted for this reference",
+        )));
+    };
+
+    let description = format!(
+        "Converted {} {} points",
+        payload.external_points, source_program
+    );
+    let reference_id = format!("{}:{}", source_program, external_reference);
+    let admin_reason = format!(
+        "Points conversion from {} at rate {} by admin user {}",
+        source_program, rate, admin_user_id
+    );
+
+    let sp_result: JsonValue =
+        sqlx::query_scalar("SELECT award_points($1, $2, 'converted'::varchar, $3, $4, $5, $6, 0)")
+            .bind(payload.user_id)
+            .bind(points)
+            .bind(&description)
+            .bind(&reference_id)
+            .bind(admin_user_id)
+            .bind(&admin_reason)
+            .fetch_one(&mut *tx)
+            .await?;
+
     let transaction_id = sp_result
         .get("transaction_id")
         .and_then(|v| v.as_str())
@@ -1653,26 +2967,82 @@ async fn admin_award_points(
             AppError::Internal("award_points SP did not return a transaction_id".to_string())
         })?;
 
-    // Get updated loyalty status
-    let loyalty_status = get_user_loyalty_status_internal(state.db(), payload.user_id).await?;
+    sqlx::query("UPDATE points_conversions SET transaction_id = $2 WHERE id = $1")
+        .bind(conversion_id)
+        .bind(transaction_id)
+        .execute(&mut *tx)
+        .await?;
 
-    let result = AdminOperationResult {
-        transaction_id,
-        loyalty_status,
-    };
+    sqlx::query(
+        r#"
+        INSERT INTO user_audit_log (user_id, action, details)
+        VALUES ($1, 'points_converted', $2)
+        "#,
+    )
+    .bind(payload.user_id)
+    .bind(serde_json::json!({
+        "conversionId": conversion_id,
+        "transactionId": transaction_id,
+        "sourceProgram": source_program,
+        "externalReference": external_reference,
+        "externalPoints": payload.external_points,
+        "rate": rate,
+        "pointsAwarded": points,
+        "convertedBy": admin_user_id,
+    }))
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::info!(
+        user_id = %payload.user_id,
+        admin_user_id = %admin_user_id,
+        source_program = %source_program,
+        external_reference = %external_reference,
+        points,
+        "Partner points converted"
+    );
+
+    let loyalty_status = get_user_loyalty_status_internal(
+        state.db(),
+        payload.user_id,
+        &state.config().points_display,
+    )
+    .await?;
 
     Ok(Json(ApiResponse::with_message(
-        result,
-        "Points awarded successfully",
+        PointsConversionResult {
+            conversion_id,
+            transaction_id: Some(transaction_id),
+            source_program,
+            external_reference,
+            external_points: payload.external_points,
+            rate,
+            points_awarded: points,
+            already_converted: false,
+            loyalty_status,
+        },
+        "Points converted successfully",
     )))
 }
 
-/// POST /loyalty/admin/deduct-points - Deduct points from a user (admin only)
-async fn admin_deduct_points(
+/// POST /loyalty/admin/partial-adjust - Take back points for a partially
+/// refunded booking (admin only)
+///
+/// Deducts the refunded fraction of the original transaction's points,
+/// rounded down, as a `refund_adjustment` transaction. With
+/// `POINTS_REFUND_ADJUST_NIGHTS` the same fraction of its nights goes too
+/// and the member's tier is recalculated. Adjustments against one award
+/// never add up to more than it. Idempotent per refund reference:
+/// repeating an adjustment returns the original result with
+/// `alreadyAdjusted: true`, while reusing a reference for a different
+/// transaction or fraction is a 409.
+async fn admin_partial_adjust(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
-    Json(payload): Json<AdminDeductPointsRequest>,
-) -> Result<Json<ApiResponse<AdminOperationResult>>, AppError> {
+    Json(payload): Json<AdminPartialAdjustRequest>,
+) -> Result<Json<ApiResponse<PartialRefundAdjustmentResult>>, AppError> {
     if !has_role(&auth_user, "admin") {
         return Err(AppError::Forbidden("Admin access required".to_string()));
     }
@@ -1680,76 +3050,327 @@ async fn admin_deduct_points(
     let admin_user_id = Uuid::parse_str(&auth_user.id)
         .map_err(|_| AppError::BadRequest("Invalid admin user ID".to_string()))?;
 
-    if payload.points <= 0 {
+    let refund_reference = payload.refund_reference.trim().to_string();
+    if refund_reference.is_empty() || refund_reference.len() > 100 {
         return Err(AppError::Validation(
-            "Points must be greater than 0".to_string(),
+            "refundReference must be 1-100 characters".to_string(),
         ));
     }
+    let fraction = refunded_fraction(
+        payload.refunded_fraction,
+        payload.refunded_amount,
+        payload.original_amount,
+    )?;
 
-    // Check if user has enough points
-    let current = sqlx::query!(
-        "SELECT current_points FROM user_loyalty WHERE user_id = $1",
-        payload.user_id,
+    let mut tx = state.db().begin().await?;
+
+    // Lock the original award first. Adjustments against it - including a
+    // retry of this same refund - queue here, so the "already adjusted"
+    // total below can't change before this one is recorded.
+    let original: RefundedAwardRow = sqlx::query_as(
+        r#"
+        SELECT user_id, points, type AS transaction_type,
+               COALESCE(nights_stayed, 0) AS nights_stayed
+        FROM points_transactions
+        WHERE id = $1
+        FOR UPDATE
+        "#,
     )
-    .fetch_optional(state.db())
+    .bind(payload.original_transaction_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Original transaction not found".to_string()))?;
+
+    let existing: Option<PointsRefundAdjustmentRow> = sqlx::query_as(
+        r#"
+        SELECT id, user_id, original_transaction_id, transaction_id,
+               refunded_fraction, points_deducted, nights_deducted
+        FROM points_refund_adjustments
+        WHERE refund_reference = $1
+        "#,
+    )
+    .bind(&refund_reference)
+    .fetch_optional(&mut *tx)
     .await?;
 
-    let current_points = current
-        .ok_or_else(|| AppError::NotFound("User loyalty record not found".to_string()))?
-        .current_points
-        .unwrap_or(0);
+    if let Some(existing) = existing {
+        tx.rollback().await?;
 
-    if current_points < payload.points {
-        return Err(AppError::Validation(
-            "Insufficient points for deduction".to_string(),
-        ));
+        if existing.original_transaction_id != payload.original_transaction_id
+            || existing.refunded_fraction != fraction
+        {
+            return Err(AppError::Conflict(format!(
+                "Refund '{}' was already adjusted for a different transaction or amount",
+                refund_reference
+            )));
+        }
+
+        let loyalty_status = get_user_loyalty_status_internal(
+            state.db(),
+            existing.user_id,
+            &state.config().points_display,
+        )
+        .await?;
+
+        return Ok(Json(ApiResponse::with_message(
+            PartialRefundAdjustmentResult {
+                adjustment_id: existing.id,
+                transaction_id: existing.transaction_id,
+                original_transaction_id: existing.original_transaction_id,
+                refund_reference,
+                refunded_fraction: existing.refunded_fraction,
+                points_deducted: existing.points_deducted,
+                nights_deducted: existing.nights_deducted,
+                already_adjusted: true,
+                loyalty_status,
+            },
+            "Points were already adjusted for this refund",
+        )));
     }
 
-    let description = format!("Points deducted by admin: {}", payload.reason);
+    if !original.transaction_type.is_credit() {
+        return Err(AppError::Validation(format!(
+            "Only earned or awarded points can be adjusted for a refund, not '{}'",
+            original.transaction_type
+        )));
+    }
 
-    // Create points transaction with negative points
-    let transaction_id = sqlx::query_scalar!(
+    let (points_adjusted, nights_adjusted): (i32, i32) = sqlx::query_as(
         r#"
-        INSERT INTO points_transactions (user_id, points, type, description, admin_user_id, admin_reason)
-        VALUES ($1, $2, 'admin_deduction'::points_transaction_type, $3, $4, $5)
-        RETURNING id
+        SELECT COALESCE(SUM(points_deducted), 0)::INT,
+               COALESCE(SUM(nights_deducted), 0)::INT
+        FROM points_refund_adjustments
+        WHERE original_transaction_id = $1
         "#,
-        payload.user_id,
-        -payload.points, // Negative for deduction
-        &description,
-        admin_user_id,
-        &payload.reason,
     )
-    .fetch_one(state.db())
+    .bind(payload.original_transaction_id)
+    .fetch_one(&mut *tx)
     .await?;
 
-    // Update user loyalty points
-    sqlx::query!(
+    let points = refund_adjustment(original.points, points_adjusted, fraction);
+    let nights = if state.config().points_refund.adjust_nights {
+        refund_adjustment(original.nights_stayed, nights_adjusted, fraction)
+    } else {
+        0
+    };
+
+    // The lock above serialises adjustments of this award, but the same
+    // reference could still be claimed concurrently against another one.
+    let adjustment_id: Uuid = sqlx::query_scalar(
         r#"
-        UPDATE user_loyalty
-        SET current_points = current_points - $1,
-            points_updated_at = NOW(),
-            updated_at = NOW()
-        WHERE user_id = $2
+        INSERT INTO points_refund_adjustments
+            (user_id, original_transaction_id, refund_reference, refunded_fraction,
+             points_deducted, nights_deducted, adjusted_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (refund_reference) DO NOTHING
+        RETURNING id
         "#,
-        payload.points,
+    )
+    .bind(original.user_id)
+    .bind(payload.original_transaction_id)
+    .bind(&refund_reference)
+    .bind(fraction)
+    .bind(points)
+    .bind(nights)
+    .bind(admin_user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| {
+        AppError::Conflict(format!(
+            "Refund '{}' was already adjusted for a different transaction",
+            refund_reference
+        ))
+    })?;
+
+    let transaction_id = if points > 0 || nights > 0 {
+        let description = format!(
+            "Partial refund adjustment ({}% refunded)",
+            (fraction * rust_decimal::Decimal::ONE_HUNDRED).normalize()
+        );
+        let admin_reason = payload
+            .reason
+            .clone()
+            .unwrap_or_else(|| format!("Partial refund {}", refund_reference));
+
+        // The SP only recalculates the tier for positive nights, so a
+        // nights reduction recalculates it explicitly below.
+        let sp_result: JsonValue = sqlx::query_scalar(
+            "SELECT award_points($1, $2, 'refund_adjustment'::varchar, $3, $4, $5, $6, $7)",
+        )
+        .bind(original.user_id)
+        .bind(-points)
+        .bind(&description)
+        .bind(&refund_reference)
+        .bind(admin_user_id)
+        .bind(&admin_reason)
+        .bind(-nights)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_balance_violation)?;
+
+        let transaction_id = sp_result
+            .get("transaction_id")
+            .and_then(|v| v.as_str())
+            .and_then(|s| Uuid::parse_str(s).ok())
+            .ok_or_else(|| {
+                AppError::Internal("award_points SP did not return a transaction_id".to_string())
+            })?;
+
+        if nights > 0 {
+            sqlx::query("SELECT * FROM recalculate_user_tier_by_nights($1)")
+                .bind(original.user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("UPDATE points_refund_adjustments SET transaction_id = $2 WHERE id = $1")
+            .bind(adjustment_id)
+            .bind(transaction_id)
+            .execute(&mut *tx)
+            .await?;
+
+        Some(transaction_id)
+    } else {
+        None
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_audit_log (user_id, action, details)
+        VALUES ($1, 'points_refund_adjusted', $2)
+        "#,
+    )
+    .bind(original.user_id)
+    .bind(serde_json::json!({
+        "adjustmentId": adjustment_id,
+        "transactionId": transaction_id,
+        "originalTransactionId": payload.original_transaction_id,
+        "refundReference": refund_reference,
+        "refundedFraction": fraction,
+        "pointsDeducted": points,
+        "nightsDeducted": nights,
+        "adjustedBy": admin_user_id,
+    }))
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::info!(
+        user_id = %original.user_id,
+        admin_user_id = %admin_user_id,
+        original_transaction_id = %payload.original_transaction_id,
+        refund_reference = %refund_reference,
+        points,
+        nights,
+        "Points adjusted for partial refund"
+    );
+
+    let loyalty_status = get_user_loyalty_status_internal(
+        state.db(),
+        original.user_id,
+        &state.config().points_display,
+    )
+    .await?;
+
+    Ok(Json(ApiResponse::with_message(
+        PartialRefundAdjustmentResult {
+            adjustment_id,
+            transaction_id,
+            original_transaction_id: payload.original_transaction_id,
+            refund_reference,
+            refunded_fraction: fraction,
+            points_deducted: points,
+            nights_deducted: nights,
+            already_adjusted: false,
+            loyalty_status,
+        },
+        "Points adjusted for refund",
+    )))
+}
+
+/// POST /loyalty/admin/deduct-points - Deduct points from a user (admin only)
+///
+/// Accepts an `Idempotency-Key` header; see `run_idempotent`.
+async fn admin_deduct_points(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<AdminDeductPointsRequest>,
+) -> Result<Json<ApiResponse<AdminOperationResult>>, AppError> {
+    if !has_role(&auth_user, "admin") {
+        return Err(AppError::Forbidden("Admin access required".to_string()));
+    }
+
+    let (result, replayed) =
+        run_idempotent(&state, &headers, "deduct-points", payload.user_id, || {
+            apply_deduct_points(&state, &auth_user, &payload)
+        })
+        .await?;
+
+    Ok(Json(ApiResponse::with_message(
+        result,
+        replay_message("Points deducted successfully", replayed),
+    )))
+}
+
+/// The deduction behind `admin_deduct_points`, run once per idempotency key
+async fn apply_deduct_points(
+    state: &AppState,
+    auth_user: &AuthUser,
+    payload: &AdminDeductPointsRequest,
+) -> Result<AdminOperationResult, AppError> {
+    let admin_user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::BadRequest("Invalid admin user ID".to_string()))?;
+
+    if payload.points <= 0 {
+        return Err(AppError::Validation(
+            "Points must be greater than 0".to_string(),
+        ));
+    }
+
+    check_award_caps(
+        state,
+        auth_user,
         payload.user_id,
+        "admin_deduct_points",
+        -i64::from(payload.points),
+        0,
+        payload.confirm_large,
     )
-    .execute(state.db())
     .await?;
 
+    let description = format!("Points deducted by admin: {}", payload.reason);
+
+    // Checked and applied in one UPDATE, so concurrent deductions can't
+    // overdraw the balance between a read and the write.
+    let adjusted = LoyaltyServiceImpl::new(state.db().clone())
+        .award_points_atomic(PointsAdjustmentParams {
+            user_id: payload.user_id,
+            points: -payload.points,
+            transaction_type: PointsTransactionType::AdminDeduction,
+            description,
+            reference_id: None,
+            admin_user_id: Some(admin_user_id),
+            admin_reason: Some(payload.reason.clone()),
+        })
+        .await?;
+    let transaction_id = adjusted.transaction_id;
+
     // Get updated loyalty status
-    let loyalty_status = get_user_loyalty_status_internal(state.db(), payload.user_id).await?;
+    let loyalty_status = get_user_loyalty_status_internal(
+        state.db(),
+        payload.user_id,
+        &state.config().points_display,
+    )
+    .await?;
 
     let result = AdminOperationResult {
         transaction_id,
         loyalty_status,
     };
 
-    Ok(Json(ApiResponse::with_message(
-        result,
-        "Points deducted successfully",
-    )))
+    Ok(result)
 }
 
 /// GET /loyalty/admin/transactions - Get all admin transactions (admin only)
@@ -1813,12 +3434,15 @@ async fn admin_get_transactions(
     .fetch_one(state.db())
     .await?;
 
+    let pagination = offset_pagination(offset, limit, total);
     let response = AdminTransactionsResponse {
         transactions,
         total,
     };
 
-    Ok(Json(ApiResponse::success(response)))
+    Ok(Json(ApiResponse::success_with_pagination(
+        response, pagination,
+    )))
 }
 
 /// GET /loyalty/admin/user/:userId/history - Get user's points history (admin only)
@@ -1842,20 +3466,19 @@ async fn admin_get_user_history(
     .fetch_one(state.db())
     .await?;
 
-    let transactions: Vec<PointsTransactionRow> = sqlx::query_as!(
-        PointsTransactionRow,
+    let transactions: Vec<PointsTransactionRow> = sqlx::query_as(
         r#"
-        SELECT id, user_id, points, type::text as "transaction_type!", description, reference_id,
+        SELECT id, user_id, points, type AS transaction_type, description, reference_id,
                admin_user_id, admin_reason, expires_at, created_at, nights_stayed
         FROM points_transactions
         WHERE user_id = $1
         ORDER BY created_at DESC
         LIMIT $2 OFFSET $3
         "#,
-        user_id,
-        limit as i64,
-        offset as i64,
     )
+    .bind(user_id)
+    .bind(limit as i64)
+    .bind(offset as i64)
     .fetch_all(state.db())
     .await?;
 
@@ -1874,9 +3497,15 @@ async fn admin_get_user_history(
         page,
         limit,
         total_pages,
+        truncated: false,
+        history_limit: None,
+        next_cursor: None,
     };
 
-    Ok(Json(ApiResponse::success(response)))
+    let pagination = response.pagination();
+    Ok(Json(ApiResponse::success_with_pagination(
+        response, pagination,
+    )))
 }
 
 /// GET /loyalty/admin/earning-rules - Get points earning rules (admin only)
@@ -1905,69 +3534,281 @@ async fn admin_get_earning_rules(
 }
 
 /// POST /loyalty/admin/expire-points - Trigger points expiration (admin only)
+///
+/// With `?dry_run=true` nothing is expired; the response adds the points
+/// that would expire and a per-member breakdown.
 async fn admin_expire_points(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
+    Query(params): Query<ExpirePointsQuery>,
 ) -> Result<Json<ApiResponse<ExpirePointsResult>>, AppError> {
     if !has_role(&auth_user, "admin") {
         return Err(AppError::Forbidden("Admin access required".to_string()));
     }
 
-    // Expire points and create negative transactions
-    let result = sqlx::query!(
-        r#"
-        INSERT INTO points_transactions (user_id, points, type, description, created_at)
-        SELECT
-            user_id,
-            -points,
-            'expired'::points_transaction_type,
-            'Points expired automatically',
-            NOW()
-        FROM points_transactions
-        WHERE expires_at <= NOW()
-        AND points > 0
-        AND id NOT IN (
-            SELECT reference_id::UUID
-            FROM points_transactions
-            WHERE type = 'expired'
-            AND reference_id IS NOT NULL
-        )
-        "#,
-    )
-    .execute(state.db())
-    .await?;
+    if params.dry_run {
+        let balances = preview_points_expiry(state.db()).await?;
+        let expired_count: i64 = balances.iter().map(|b| b.expired_transactions).sum();
+        let points_to_expire: i64 = balances.iter().map(|b| i64::from(b.expired_points)).sum();
+
+        return Ok(Json(ApiResponse::with_message(
+            ExpirePointsResult {
+                expired_count,
+                preview: Some(ExpirePointsPreview {
+                    dry_run: true,
+                    points_to_expire,
+                    users: balances
+                        .into_iter()
+                        .map(|b| ExpirePointsPreviewUser {
+                            user_id: b.user_id,
+                            points_to_expire: b.expired_points,
+                            transactions: b.expired_transactions,
+                        })
+                        .collect(),
+                }),
+            },
+            format!(
+                "Dry run: {} points in {} transactions would expire",
+                points_to_expire, expired_count
+            ),
+        )));
+    }
 
-    let expired_count = result.rows_affected() as i64;
+    // Same run as the scheduled worker; the advisory lock inside makes a
+    // back-to-back admin and scheduled run expire each earning once.
+    let balances = expire_points(state.db()).await?;
+    notify_points_expired(&balances).await;
+
+    let expired_count: i64 = balances.iter().map(|b| b.expired_transactions).sum();
 
     Ok(Json(ApiResponse::with_message(
-        ExpirePointsResult { expired_count },
+        ExpirePointsResult {
+            expired_count,
+            preview: None,
+        },
         format!("Expired points for {} transactions", expired_count),
     )))
 }
 
 /// POST /loyalty/admin/award-spending-with-nights - Award spending points with nights (admin only)
+///
+/// Accepts an `Idempotency-Key` header so PMS retries don't award twice;
+/// see `run_idempotent`.
 async fn admin_award_spending_with_nights(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
+    headers: axum::http::HeaderMap,
     Json(payload): Json<AdminAwardSpendingWithNightsRequest>,
 ) -> Result<Json<ApiResponse<AdminSpendingWithNightsResult>>, AppError> {
     if !has_role(&auth_user, "admin") {
         return Err(AppError::Forbidden("Admin access required".to_string()));
     }
 
-    let admin_user_id = Uuid::parse_str(&auth_user.id)
-        .map_err(|_| AppError::BadRequest("Invalid admin user ID".to_string()))?;
+    let (result, replayed) = run_idempotent(
+        &state,
+        &headers,
+        "award-spending-with-nights",
+        payload.user_id,
+        || apply_award_spending_with_nights(&state, &auth_user, &payload),
+    )
+    .await?;
 
-    if payload.amount_spent == 0.0 && payload.nights_stayed == 0 {
-        return Err(AppError::Validation(
-            "At least one of amount spent or nights stayed must be provided".to_string(),
-        ));
-    }
+    Ok(Json(ApiResponse::with_message(
+        result,
+        replay_message("Spending points and nights awarded successfully", replayed),
+    )))
+}
 
-    // Calculate points (10 points per 1 THB spent)
-    let points_earned = (payload.amount_spent * 10.0).floor() as i32;
+/// The award behind `admin_award_spending_with_nights`, run once per
+/// idempotency key
+async fn apply_award_spending_with_nights(
+    state: &AppState,
+    auth_user: &AuthUser,
+    payload: &AdminAwardSpendingWithNightsRequest,
+) -> Result<AdminSpendingWithNightsResult, AppError> {
+    let points_earned = spending_award_points(state, payload).await?;
+
+    check_award_caps(
+        state,
+        auth_user,
+        payload.user_id,
+        "admin_award_spending_with_nights",
+        points_earned.into(),
+        payload.nights_stayed.into(),
+        payload.confirm_large,
+    )
+    .await?;
 
-    let description = payload.description.clone().unwrap_or_else(|| {
+    let mut tx = state.db().begin().await?;
+    let award = write_spending_award(&mut tx, auth_user, payload, points_earned).await?;
+
+    let stay_milestone = if award.transaction_type == PointsTransactionType::EarnedStay {
+        record_stay_milestone(
+            &mut tx,
+            payload.user_id,
+            award.transaction_id,
+            state.config().loyalty.win_back_after_days,
+        )
+        .await?
+    } else {
+        None
+    };
+
+    let upgrade_grant =
+        grant_tier_upgrade_coupon(&mut tx, payload.user_id, award.old_tier_id).await?;
+
+    tx.commit().await?;
+
+    if let Some(grant) = upgrade_grant {
+        notify_coupon_assignment(state, grant.coupon_id, &[payload.user_id]).await;
+    }
+    if let Some(milestone) = stay_milestone {
+        notify_stay_milestone(state.db(), payload.user_id, milestone).await;
+    }
+
+    // Get updated loyalty status
+    let loyalty_status = get_user_loyalty_status_internal(
+        state.db(),
+        payload.user_id,
+        &state.config().points_display,
+    )
+    .await?;
+
+    let result = AdminSpendingWithNightsResult {
+        transaction_id: award.transaction_id,
+        points_earned,
+        new_total_nights: award.new_total_nights,
+        new_tier_name: award.new_tier_name,
+        loyalty_status,
+        stay_milestone,
+    };
+
+    Ok(result)
+}
+
+/// POST /loyalty/admin/award-preview - Project an award-spending-with-nights (admin only)
+///
+/// Takes the same body as `/admin/award-spending-with-nights` and runs the
+/// award's own calculation and `award_points` SP in a transaction that is
+/// rolled back, so the projected points, nights and tier are exactly what
+/// submitting it would give. The per-award caps are reported rather than
+/// enforced; nothing is written, audit log included.
+async fn admin_award_preview(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(payload): Json<AdminAwardSpendingWithNightsRequest>,
+) -> Result<Json<ApiResponse<AdminAwardPreviewResult>>, AppError> {
+    if !has_role(&auth_user, "admin") {
+        return Err(AppError::Forbidden("Admin access required".to_string()));
+    }
+
+    let points_earned = spending_award_points(&state, &payload).await?;
+    let exceeded_caps = exceeded_award_caps(
+        &state.config().loyalty,
+        points_earned.into(),
+        payload.nights_stayed.into(),
+    );
+
+    let mut tx = state.db().begin().await?;
+    let before = member_standing(&mut tx, payload.user_id).await?;
+    write_spending_award(&mut tx, &auth_user, &payload, points_earned).await?;
+    let after = member_standing(&mut tx, payload.user_id).await?;
+    tx.rollback().await?;
+
+    let (current_points, current_total_nights, current_tier_name) = before.unwrap_or_default();
+    let (projected_points, projected_total_nights, projected_tier_name) = after.unwrap_or_default();
+    let projected_tier_name = projected_tier_name.unwrap_or_else(|| "Bronze".to_string());
+
+    Ok(Json(ApiResponse::success(AdminAwardPreviewResult {
+        user_id: payload.user_id,
+        points_earned,
+        current_points,
+        projected_points,
+        current_total_nights,
+        projected_total_nights,
+        tier_changed: current_tier_name.as_deref() != Some(projected_tier_name.as_str()),
+        current_tier_name,
+        projected_tier_name,
+        exceeded_caps,
+    })))
+}
+
+/// A member's points, nights and tier name as `conn` sees them; `None`
+/// before they have a `user_loyalty` row
+async fn member_standing(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+) -> Result<Option<(i32, i32, Option<String>)>, AppError> {
+    let standing = sqlx::query_as(
+        r#"
+        SELECT COALESCE(ul.current_points, 0), COALESCE(ul.total_nights, 0), t.name
+        FROM user_loyalty ul
+        LEFT JOIN tiers t ON ul.tier_id = t.id
+        WHERE ul.user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(standing)
+}
+
+/// Points an award-spending-with-nights of `payload` earns.
+///
+/// Spending is converted with the active spend earning rule and the
+/// member's tier multiplier, then the points are zeroed for stays shorter
+/// than the configured minimum — the nights still count. Shared by the
+/// award and its preview so the two can't disagree.
+async fn spending_award_points(
+    state: &AppState,
+    payload: &AdminAwardSpendingWithNightsRequest,
+) -> Result<i32, AppError> {
+    if payload.amount_spent == 0.0 && payload.nights_stayed == 0 {
+        return Err(AppError::Validation(
+            "At least one of amount spent or nights stayed must be provided".to_string(),
+        ));
+    }
+
+    let spend_points = crate::services::loyalty::calculate_spend_points(
+        state.db(),
+        payload.user_id,
+        payload.amount_spent,
+    )
+    .await?;
+
+    Ok(crate::services::loyalty::apply_min_nights_for_points(
+        spend_points,
+        payload.nights_stayed,
+        state.config().loyalty.min_nights_for_points,
+    ))
+}
+
+/// What [`write_spending_award`] wrote
+struct WrittenSpendingAward {
+    transaction_id: Uuid,
+    transaction_type: PointsTransactionType,
+    /// The member's tier before the award, for `grant_tier_upgrade_coupon`
+    old_tier_id: Option<Uuid>,
+    new_total_nights: i32,
+    new_tier_name: String,
+}
+
+/// Write an award of `points_earned` and `payload.nights_stayed` through
+/// the `award_points` SP on `conn`'s transaction, and read back the
+/// member's nights and tier.
+///
+/// The caller commits (the award) or rolls back (its preview).
+async fn write_spending_award(
+    conn: &mut sqlx::PgConnection,
+    auth_user: &AuthUser,
+    payload: &AdminAwardSpendingWithNightsRequest,
+    points_earned: i32,
+) -> Result<WrittenSpendingAward, AppError> {
+    let admin_user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::BadRequest("Invalid admin user ID".to_string()))?;
+
+    let description = payload.description.clone().unwrap_or_else(|| {
         format!(
             "Hotel stay: {} night(s), {:.2} THB spent",
             payload.nights_stayed, payload.amount_spent
@@ -1988,28 +3829,16 @@ async fn admin_award_spending_with_nights(
 
     // Determine transaction type based on whether it's adding or removing
     let transaction_type = if payload.nights_stayed < 0 || points_earned < 0 {
-        "admin_deduction"
+        PointsTransactionType::AdminDeduction
     } else {
-        "earned_stay"
+        PointsTransactionType::EarnedStay
     };
 
-    let mut tx = state.db().begin().await?;
-
     // Ensure user has loyalty status before invoking the SP (the SP
-    // assumes the row exists; we keep this seed insert because legacy
-    // accounts may pre-date the loyalty enrollment hook).
-    sqlx::query!(
-        r#"
-        INSERT INTO user_loyalty (user_id, tier_id, current_points, total_nights)
-        SELECT $1, t.id, 0, 0
-        FROM tiers t
-        WHERE t.name = 'Bronze'
-        ON CONFLICT (user_id) DO NOTHING
-        "#,
-        payload.user_id,
-    )
-    .execute(&mut *tx)
-    .await?;
+    // assumes the row exists; legacy accounts may pre-date the loyalty
+    // enrollment hook).
+    ensure_user_loyalty(&mut *conn, payload.user_id).await?;
+    let old_tier_id = lock_current_tier(conn, payload.user_id).await?;
 
     // Delegate to `award_points` SP — it inserts the
     // points_transactions row, bumps current_points + total_nights,
@@ -2021,13 +3850,118 @@ async fn admin_award_spending_with_nights(
         "#,
         payload.user_id,
         points_earned,
-        transaction_type,
+        transaction_type.as_str(),
         &description,
         Some(&reference_id),
         admin_user_id,
         &admin_reason,
         payload.nights_stayed,
     )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let transaction_id = sp_result
+        .get("transaction_id")
+        .and_then(|v| v.as_str())
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .ok_or_else(|| {
+            AppError::Internal("award_points SP did not return a transaction_id".to_string())
+        })?;
+
+    // Re-read total nights + tier for the response. Both were just
+    // written by the SP — this is a confirmation read.
+    let updated = sqlx::query!(
+        r#"
+        SELECT ul.total_nights, t.name AS "tier_name?"
+        FROM user_loyalty ul
+        LEFT JOIN tiers t ON ul.tier_id = t.id
+        WHERE ul.user_id = $1
+        "#,
+        payload.user_id,
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(WrittenSpendingAward {
+        transaction_id,
+        transaction_type,
+        old_tier_id,
+        new_total_nights: updated.total_nights.unwrap_or(0),
+        new_tier_name: updated.tier_name.unwrap_or_else(|| "Bronze".to_string()),
+    })
+}
+
+/// POST /loyalty/admin/award-nights - Award nights plus any per-night points (admin only)
+async fn admin_award_nights(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(payload): Json<AdminAwardNightsRequest>,
+) -> Result<Json<ApiResponse<AdminNightsOperationResult>>, AppError> {
+    if !has_role(&auth_user, "admin") {
+        return Err(AppError::Forbidden("Admin access required".to_string()));
+    }
+
+    let admin_user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::BadRequest("Invalid admin user ID".to_string()))?;
+
+    if payload.nights <= 0 {
+        return Err(AppError::Validation(
+            "Nights must be greater than 0".to_string(),
+        ));
+    }
+
+    // Opt-in points-per-night rule (`LOYALTY_POINTS_PER_NIGHT`, default 0).
+    let loyalty_config = &state.config().loyalty;
+    let points = crate::services::loyalty::apply_min_nights_for_points(
+        crate::services::loyalty::points_for_nights(
+            payload.nights,
+            loyalty_config.points_per_night,
+        ),
+        payload.nights,
+        loyalty_config.min_nights_for_points,
+    );
+
+    check_award_caps(
+        &state,
+        &auth_user,
+        payload.user_id,
+        "admin_award_nights",
+        points.into(),
+        payload.nights.into(),
+        payload.confirm_large,
+    )
+    .await?;
+
+    let description = if points > 0 {
+        format!(
+            "Admin awarded {} night(s) and {} point(s)",
+            payload.nights, points
+        )
+    } else {
+        format!("Admin awarded {} night(s)", payload.nights)
+    };
+
+    let mut tx = state.db().begin().await?;
+
+    // Ensure user has loyalty status before invoking the SP.
+    ensure_user_loyalty(&mut *tx, payload.user_id).await?;
+    let old_tier_id = lock_current_tier(&mut tx, payload.user_id).await?;
+
+    // Delegate to the SP. Nights and any per-night points go through a
+    // single call, so they share one transaction row; nights trigger the
+    // tier recalculation hook inside the SP.
+    let sp_result: JsonValue = sqlx::query_scalar(
+        r#"
+        SELECT award_points($1, $2, 'earned_stay'::varchar, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(payload.user_id)
+    .bind(points)
+    .bind(&description)
+    .bind(payload.reference_id.as_deref())
+    .bind(admin_user_id)
+    .bind(&payload.reason)
+    .bind(payload.nights)
     .fetch_one(&mut *tx)
     .await?;
 
@@ -2039,235 +3973,1083 @@ async fn admin_award_spending_with_nights(
             AppError::Internal("award_points SP did not return a transaction_id".to_string())
         })?;
 
-    // Re-read total nights + tier for the response. Both were just
-    // written by the SP — this is a confirmation read.
-    let updated = sqlx::query!(
+    let updated = sqlx::query!(
+        r#"
+        SELECT ul.total_nights, t.name AS "tier_name?"
+        FROM user_loyalty ul
+        LEFT JOIN tiers t ON ul.tier_id = t.id
+        WHERE ul.user_id = $1
+        "#,
+        payload.user_id,
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let new_total_nights = updated.total_nights.unwrap_or(0);
+    let new_tier_name = updated.tier_name.unwrap_or_else(|| "Bronze".to_string());
+
+    let stay_milestone = record_stay_milestone(
+        &mut tx,
+        payload.user_id,
+        transaction_id,
+        state.config().loyalty.win_back_after_days,
+    )
+    .await?;
+
+    let upgrade_grant = grant_tier_upgrade_coupon(&mut tx, payload.user_id, old_tier_id).await?;
+
+    tx.commit().await?;
+
+    if let Some(grant) = upgrade_grant {
+        notify_coupon_assignment(&state, grant.coupon_id, &[payload.user_id]).await;
+    }
+    if let Some(milestone) = stay_milestone {
+        notify_stay_milestone(state.db(), payload.user_id, milestone).await;
+    }
+
+    // Get updated loyalty status
+    let loyalty_status = get_user_loyalty_status_internal(
+        state.db(),
+        payload.user_id,
+        &state.config().points_display,
+    )
+    .await?;
+
+    let result = AdminNightsOperationResult {
+        transaction_id,
+        points_awarded: points,
+        new_total_nights,
+        new_tier_name,
+        loyalty_status,
+        stay_milestone,
+    };
+
+    Ok(Json(ApiResponse::with_message(
+        result,
+        "Nights awarded successfully",
+    )))
+}
+
+/// POST /loyalty/admin/deduct-nights - Deduct nights only (admin only)
+async fn admin_deduct_nights(
+    State(state): State<AppState>,
+    mut tx: Tx,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(payload): Json<AdminDeductNightsRequest>,
+) -> Result<Json<ApiResponse<AdminNightsOperationResult>>, AppError> {
+    if !has_role(&auth_user, "admin") {
+        return Err(AppError::Forbidden("Admin access required".to_string()));
+    }
+
+    let admin_user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::BadRequest("Invalid admin user ID".to_string()))?;
+
+    if payload.nights <= 0 {
+        return Err(AppError::Validation(
+            "Nights must be greater than 0".to_string(),
+        ));
+    }
+
+    check_award_caps(
+        &state,
+        &auth_user,
+        payload.user_id,
+        "admin_deduct_nights",
+        0,
+        -i64::from(payload.nights),
+        payload.confirm_large,
+    )
+    .await?;
+
+    // Enroll first so a never-enrolled user gets a clean "insufficient
+    // nights" rather than a missing-record error.
+    ensure_user_loyalty(state.db(), payload.user_id).await?;
+
+    // Check if user has enough nights
+    let current = sqlx::query!(
+        "SELECT total_nights FROM user_loyalty WHERE user_id = $1",
+        payload.user_id,
+    )
+    .fetch_optional(state.db())
+    .await?;
+
+    let current_nights = current
+        .ok_or_else(|| AppError::NotFound("User loyalty record not found".to_string()))?
+        .total_nights
+        .unwrap_or(0);
+
+    if current_nights < payload.nights {
+        return Err(AppError::Validation(
+            "Insufficient nights for deduction".to_string(),
+        ));
+    }
+
+    let description = format!("Admin deducted {} night(s)", payload.nights);
+
+    // Delegate to `award_points` SP with negative nights so the SP
+    // handles the points_transactions insert, balance update, and tier
+    // recalculation in one place. CLAUDE.md rule + Correctness HIGH #4.
+    let neg_nights = -payload.nights;
+    let sp_result: JsonValue = sqlx::query_scalar!(
+        r#"
+        SELECT award_points($1, 0, 'admin_deduction'::varchar, $2, $3, $4, $5, $6) AS "result!"
+        "#,
+        payload.user_id,
+        &description,
+        payload.reference_id.as_deref(),
+        admin_user_id,
+        &payload.reason,
+        neg_nights,
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(map_balance_violation)?;
+
+    let transaction_id = sp_result
+        .get("transaction_id")
+        .and_then(|v| v.as_str())
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .ok_or_else(|| {
+            AppError::Internal("award_points SP did not return a transaction_id".to_string())
+        })?;
+
+    // The SP only recalculates the tier when nights are added, so lower it
+    // here, held back for the configured grace period.
+    recalculate_tier_with_grace(
+        &mut tx,
+        payload.user_id,
+        state.config().loyalty.tier_downgrade_grace_days,
+    )
+    .await?;
+
+    let updated = sqlx::query!(
+        r#"
+        SELECT ul.total_nights, t.name AS "tier_name?"
+        FROM user_loyalty ul
+        LEFT JOIN tiers t ON ul.tier_id = t.id
+        WHERE ul.user_id = $1
+        "#,
+        payload.user_id,
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let new_total_nights = updated.total_nights.unwrap_or(0);
+    let new_tier_name = updated.tier_name.unwrap_or_else(|| "Bronze".to_string());
+
+    tx.commit().await?;
+
+    // Get updated loyalty status
+    let loyalty_status = get_user_loyalty_status_internal(
+        state.db(),
+        payload.user_id,
+        &state.config().points_display,
+    )
+    .await?;
+
+    let result = AdminNightsOperationResult {
+        transaction_id,
+        points_awarded: 0,
+        new_total_nights,
+        new_tier_name,
+        loyalty_status,
+        stay_milestone: None,
+    };
+
+    Ok(Json(ApiResponse::with_message(
+        result,
+        "Nights deducted successfully",
+    )))
+}
+
+/// POST /loyalty/admin/bulk-award - Award points and/or nights to many members (admin only)
+///
+/// Up to 500 members (duplicates are awarded once) in one transaction.
+/// Each member is awarded in a savepoint, so one that can't be awarded
+/// (e.g. no loyalty record) is reported in its result and skipped rather
+/// than failing the batch. Every transaction in the batch shares the
+/// returned `referenceId`; members whose award went through get a
+/// loyalty update over SSE once the batch commits.
+async fn admin_bulk_award(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(payload): Json<AdminBulkAwardRequest>,
+) -> Result<Json<ApiResponse<AdminBulkAwardResult>>, AppError> {
+    if !has_role(&auth_user, "admin") {
+        return Err(AppError::Forbidden("Admin access required".to_string()));
+    }
+
+    let admin_user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::BadRequest("Invalid admin user ID".to_string()))?;
+
+    let mut seen = std::collections::HashSet::new();
+    let user_ids: Vec<Uuid> = payload
+        .user_ids
+        .iter()
+        .copied()
+        .filter(|user_id| seen.insert(*user_id))
+        .collect();
+
+    if user_ids.is_empty() {
+        return Err(AppError::BadRequest(
+            "userIds must contain at least one user".to_string(),
+        ));
+    }
+    if user_ids.len() > MAX_BULK_AWARD_USERS {
+        return Err(AppError::BadRequest(format!(
+            "A bulk award can target at most {} users; {} given",
+            MAX_BULK_AWARD_USERS,
+            user_ids.len()
+        )));
+    }
+    if payload.points < 0 || payload.nights < 0 {
+        return Err(AppError::Validation(
+            "Points and nights cannot be negative".to_string(),
+        ));
+    }
+    if payload.points == 0 && payload.nights == 0 {
+        return Err(AppError::Validation(
+            "At least one of points or nights must be greater than 0".to_string(),
+        ));
+    }
+    let description = payload.description.trim();
+    if description.is_empty() {
+        return Err(AppError::Validation("Description is required".to_string()));
+    }
+
+    for &user_id in &user_ids {
+        check_award_caps(
+            &state,
+            &auth_user,
+            user_id,
+            "admin_bulk_award",
+            payload.points.into(),
+            payload.nights.into(),
+            payload.confirm_large,
+        )
+        .await?;
+    }
+
+    let reference_id = format!("BULK-{}", Uuid::new_v4());
+    let admin_reason = format!(
+        "Bulk award by admin {} ({} members)",
+        auth_user.email.as_deref().unwrap_or("unknown"),
+        user_ids.len()
+    );
+    let award = BulkAward {
+        points: payload.points,
+        nights: payload.nights,
+        description,
+        reference_id: &reference_id,
+        admin_user_id,
+        admin_reason: &admin_reason,
+        grace_days: state.config().loyalty.tier_downgrade_grace_days,
+    };
+
+    let mut tx = state.db().begin().await?;
+    let mut results = Vec::with_capacity(user_ids.len());
+    let mut upgrade_grants = Vec::new();
+
+    for &user_id in &user_ids {
+        match bulk_award_one(&mut tx, user_id, &award).await? {
+            Ok((result, grant)) => {
+                results.push(result);
+                upgrade_grants.extend(grant.map(|grant| (user_id, grant)));
+            },
+            Err(error) => {
+                tracing::warn!(
+                    user_id = %user_id,
+                    reference_id = %reference_id,
+                    error = %error,
+                    "Bulk award skipped member"
+                );
+                results.push(BulkAwardUserResult {
+                    user_id,
+                    success: false,
+                    transaction_id: None,
+                    current_points: None,
+                    total_nights: None,
+                    tier_name: None,
+                    error: Some(error.user_message()),
+                });
+            },
+        }
+    }
+
+    tx.commit().await?;
+
+    for (user_id, grant) in upgrade_grants {
+        notify_coupon_assignment(&state, grant.coupon_id, &[user_id]).await;
+    }
+    for result in results.iter().filter(|result| result.success) {
+        crate::services::sse::helpers::send_loyalty_update(
+            &result.user_id.to_string(),
+            result.current_points.unwrap_or(0),
+            result.tier_name.as_deref().unwrap_or("Bronze"),
+            result.total_nights.unwrap_or(0),
+        )
+        .await;
+    }
+
+    let succeeded = results.iter().filter(|result| result.success).count();
+    let failed = results.len() - succeeded;
+    tracing::info!(
+        admin_user_id = %admin_user_id,
+        reference_id = %reference_id,
+        succeeded,
+        failed,
+        points = payload.points,
+        nights = payload.nights,
+        "Bulk award applied"
+    );
+
+    Ok(Json(ApiResponse::with_message(
+        AdminBulkAwardResult {
+            reference_id,
+            succeeded,
+            failed,
+            results,
+        },
+        format!("Awarded {} of {} members", succeeded, succeeded + failed),
+    )))
+}
+
+/// Largest `batchSize` a bulk tier recalculation accepts
+const MAX_TIER_RECALC_BATCH_SIZE: u32 = 5000;
+
+/// Redis key prefix for bulk tier recalculation jobs (`{prefix}{job_id}`)
+const TIER_RECALC_JOB_KEY_PREFIX: &str = "loyalty:tier_recalc:";
+
+/// How long a job's progress stays available for polling and resuming
+const TIER_RECALC_JOB_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Where a bulk tier recalculation job is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TierRecalculationStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Progress of a bulk tier recalculation, kept in Redis and pushed to the
+/// admin who started it as `tier_recalculation` SSE events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TierRecalculationJob {
+    pub job_id: Uuid,
+    pub status: TierRecalculationStatus,
+    pub batch_size: u32,
+    /// Members to go through, counted when the job (or its last resume)
+    /// started
+    pub total: i64,
+    pub processed: i64,
+    /// Members whose tier changed
+    pub changed: i64,
+    /// Last member recalculated; a resumed job carries on after it
+    pub last_user_id: Option<Uuid>,
+    pub started_by: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+fn tier_recalc_job_key(job_id: Uuid) -> String {
+    format!("{}{}", TIER_RECALC_JOB_KEY_PREFIX, job_id)
+}
+
+async fn load_tier_recalc_job(
+    state: &AppState,
+    job_id: Uuid,
+) -> Result<TierRecalculationJob, AppError> {
+    RedisManager::from_connection(state.redis())
+        .get_json(&tier_recalc_job_key(job_id))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to load tier recalculation job: {}", e)))?
+        .ok_or_else(|| AppError::NotFound("Tier recalculation job not found".to_string()))
+}
+
+async fn save_tier_recalc_job(
+    state: &AppState,
+    job: &TierRecalculationJob,
+) -> Result<(), AppError> {
+    RedisManager::from_connection(state.redis())
+        .set_json(
+            &tier_recalc_job_key(job.job_id),
+            job,
+            Some(TIER_RECALC_JOB_TTL_SECS),
+        )
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to save tier recalculation job: {}", e)))
+}
+
+/// POST /loyalty/admin/recalculate-all - Recalculate every member's tier in the background (admin only)
+///
+/// Walks `user_loyalty` in `user_id` order, `batchSize` members per
+/// transaction, through the same recalculation as
+/// `POST /recalculate/:user_id` (grace period and upgrade coupon
+/// included). Returns `202` with the job straight away; progress goes to
+/// the caller as `tier_recalculation` SSE events and can be polled at
+/// `GET /admin/recalculate-all/:jobId`.
+///
+/// One run at a time across all instances: a second request gets `409`
+/// while the Postgres advisory lock is held. A failed or interrupted job
+/// is resumed by passing its id as `resumeJobId`; committed batches are
+/// not redone, and resuming a completed job just returns it.
+async fn admin_recalculate_all_tiers(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    payload: Option<Json<AdminRecalculateAllRequest>>,
+) -> Result<(StatusCode, Json<ApiResponse<TierRecalculationJob>>), AppError> {
+    if !has_role(&auth_user, "admin") {
+        return Err(AppError::Forbidden("Admin access required".to_string()));
+    }
+
+    let admin_user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::BadRequest("Invalid admin user ID".to_string()))?;
+
+    let payload = payload.map(|Json(payload)| payload).unwrap_or_default();
+    let batch_size = payload
+        .batch_size
+        .unwrap_or(state.config().loyalty.tier_recalc_batch_size);
+    if batch_size == 0 || batch_size > MAX_TIER_RECALC_BATCH_SIZE {
+        return Err(AppError::Validation(format!(
+            "batchSize must be between 1 and {}",
+            MAX_TIER_RECALC_BATCH_SIZE
+        )));
+    }
+
+    let previous = match payload.resume_job_id {
+        Some(job_id) => {
+            let job = load_tier_recalc_job(&state, job_id).await?;
+            if job.status == TierRecalculationStatus::Completed {
+                return Ok((
+                    StatusCode::OK,
+                    Json(ApiResponse::with_message(
+                        job,
+                        "Tier recalculation already completed",
+                    )),
+                ));
+            }
+            Some(job)
+        },
+        None => None,
+    };
+
+    let resume_after = previous.as_ref().and_then(|job| job.last_user_id);
+    let remaining = count_tier_recalculation_remaining(state.db(), resume_after).await?;
+    let now = Utc::now();
+    let job = match previous {
+        Some(job) => TierRecalculationJob {
+            status: TierRecalculationStatus::Running,
+            batch_size,
+            total: job.processed + remaining,
+            updated_at: now,
+            finished_at: None,
+            error: None,
+            ..job
+        },
+        None => TierRecalculationJob {
+            job_id: Uuid::new_v4(),
+            status: TierRecalculationStatus::Running,
+            batch_size,
+            total: remaining,
+            processed: 0,
+            changed: 0,
+            last_user_id: None,
+            started_by: admin_user_id,
+            started_at: now,
+            updated_at: now,
+            finished_at: None,
+            error: None,
+        },
+    };
+
+    let mut lock_conn = state.db().acquire().await?;
+    if !try_lock_tier_recalculation(&mut lock_conn).await? {
+        return Err(AppError::Conflict(
+            "A tier recalculation is already running".to_string(),
+        ));
+    }
+    if let Err(e) = save_tier_recalc_job(&state, &job).await {
+        unlock_tier_recalculation(&mut lock_conn).await.ok();
+        return Err(e);
+    }
+
+    tracing::info!(
+        admin_user_id = %admin_user_id,
+        job_id = %job.job_id,
+        total = job.total,
+        batch_size,
+        resumed = resume_after.is_some(),
+        "Bulk tier recalculation started"
+    );
+    tokio::spawn(run_tier_recalculation(
+        state.clone(),
+        lock_conn,
+        job.clone(),
+        auth_user.id.clone(),
+    ));
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ApiResponse::with_message(job, "Tier recalculation started")),
+    ))
+}
+
+/// GET /loyalty/admin/recalculate-all/:jobId - Progress of a bulk tier recalculation (admin only)
+async fn admin_get_tier_recalculation(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<TierRecalculationJob>>, AppError> {
+    if !has_role(&auth_user, "admin") {
+        return Err(AppError::Forbidden("Admin access required".to_string()));
+    }
+
+    Ok(Json(ApiResponse::success(
+        load_tier_recalc_job(&state, job_id).await?,
+    )))
+}
+
+/// Work through `job` batch by batch, holding the recalculation lock on
+/// `lock_conn` until it ends.
+///
+/// After every committed batch the job is saved and pushed to `admin_id`,
+/// so its `lastUserId` is always a safe place to resume from. Members
+/// granted an upgrade coupon are notified batch by batch, as a single
+/// recalculation would.
+async fn run_tier_recalculation(
+    state: AppState,
+    mut lock_conn: sqlx::pool::PoolConnection<sqlx::Postgres>,
+    mut job: TierRecalculationJob,
+    admin_id: String,
+) {
+    let grace_days = state.config().loyalty.tier_downgrade_grace_days;
+
+    let outcome = loop {
+        let batch = match recalculate_tier_batch(
+            state.db(),
+            job.last_user_id,
+            i64::from(job.batch_size),
+            grace_days,
+        )
+        .await
+        {
+            Ok(batch) => batch,
+            Err(e) => break Err(e),
+        };
+
+        job.processed += batch.processed;
+        job.changed += batch.changed;
+        job.last_user_id = batch.last_user_id.or(job.last_user_id);
+        job.updated_at = Utc::now();
+
+        for (user_id, grant) in &batch.grants {
+            notify_coupon_assignment(&state, grant.coupon_id, &[*user_id]).await;
+        }
+
+        if batch.processed < i64::from(job.batch_size) {
+            break Ok(());
+        }
+        publish_tier_recalculation(&state, &job, &admin_id).await;
+    };
+
+    let now = Utc::now();
+    job.updated_at = now;
+    job.finished_at = Some(now);
+    match outcome {
+        Ok(()) => {
+            job.status = TierRecalculationStatus::Completed;
+            tracing::info!(
+                job_id = %job.job_id,
+                processed = job.processed,
+                changed = job.changed,
+                "Bulk tier recalculation completed"
+            );
+        },
+        Err(e) => {
+            job.status = TierRecalculationStatus::Failed;
+            job.error = Some(e.user_message());
+            tracing::error!(
+                job_id = %job.job_id,
+                processed = job.processed,
+                error = %e,
+                "Bulk tier recalculation failed; resume with its job id"
+            );
+        },
+    }
+    publish_tier_recalculation(&state, &job, &admin_id).await;
+
+    // A connection returned to the pool still holding the session lock
+    // would block every later run, so close it if the unlock fails.
+    if let Err(e) = unlock_tier_recalculation(&mut lock_conn).await {
+        tracing::warn!(error = %e, "Failed to release tier recalculation lock; closing connection");
+        drop(lock_conn.detach());
+    }
+}
+
+/// Save `job` and send it to `admin_id` as a `tier_recalculation` event
+async fn publish_tier_recalculation(state: &AppState, job: &TierRecalculationJob, admin_id: &str) {
+    if let Err(e) = save_tier_recalc_job(state, job).await {
+        tracing::warn!(job_id = %job.job_id, error = %e, "Failed to save tier recalculation progress");
+    }
+    get_sse_service()
+        .send_to_user(
+            admin_id,
+            SseEvent::new(
+                SseEventType::TierRecalculation,
+                serde_json::to_value(job).unwrap_or_default(),
+            ),
+        )
+        .await;
+}
+
+/// What every member in a bulk award receives
+struct BulkAward<'a> {
+    points: i32,
+    nights: i32,
+    description: &'a str,
+    reference_id: &'a str,
+    admin_user_id: Uuid,
+    admin_reason: &'a str,
+    grace_days: u32,
+}
+
+/// A member's bulk award result, plus the upgrade coupon it earned
+type BulkAwardOutcome = (BulkAwardUserResult, Option<TierUpgradeGrant>);
+
+/// Apply a bulk award to one member inside a savepoint of `conn`.
+///
+/// The outer `Result` is for failures that leave the batch transaction
+/// unusable; the inner one is this member's outcome, already rolled back
+/// to the savepoint when it's an error.
+async fn bulk_award_one(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+    award: &BulkAward<'_>,
+) -> Result<Result<BulkAwardOutcome, AppError>, AppError> {
+    let mut savepoint = conn.begin().await?;
+
+    match bulk_award_member(&mut savepoint, user_id, award).await {
+        Ok(outcome) => {
+            savepoint.commit().await?;
+            Ok(Ok(outcome))
+        },
+        Err(error) => {
+            savepoint.rollback().await?;
+            Ok(Err(error))
+        },
+    }
+}
+
+async fn bulk_award_member(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+    award: &BulkAward<'_>,
+) -> Result<BulkAwardOutcome, AppError> {
+    // Unlike the single-member endpoints this doesn't enroll anyone: a
+    // promotion list naming a member without a loyalty record is reported
+    // back instead.
+    let old_tier_id: Option<Option<Uuid>> =
+        sqlx::query_scalar("SELECT tier_id FROM user_loyalty WHERE user_id = $1 FOR UPDATE")
+            .bind(user_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+    let Some(old_tier_id) = old_tier_id else {
+        return Err(AppError::NotFound("User loyalty record".to_string()));
+    };
+
+    let sp_result: JsonValue = sqlx::query_scalar(
+        r#"
+        SELECT award_points($1, $2, $3::varchar, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(user_id)
+    .bind(award.points)
+    .bind(PointsTransactionType::AdminAward.as_str())
+    .bind(award.description)
+    .bind(award.reference_id)
+    .bind(award.admin_user_id)
+    .bind(award.admin_reason)
+    .bind(award.nights)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(map_balance_violation)?;
+
+    let transaction_id = sp_result
+        .get("transaction_id")
+        .and_then(|v| v.as_str())
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .ok_or_else(|| {
+            AppError::Internal("award_points SP did not return a transaction_id".to_string())
+        })?;
+
+    let recalculation = recalculate_tier_with_grace(&mut *conn, user_id, award.grace_days).await?;
+    let upgrade_grant = grant_tier_upgrade_coupon(&mut *conn, user_id, old_tier_id).await?;
+
+    let (current_points, total_nights): (Option<i32>, Option<i32>) =
+        sqlx::query_as("SELECT current_points, total_nights FROM user_loyalty WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&mut *conn)
+            .await?;
+
+    Ok((
+        BulkAwardUserResult {
+            user_id,
+            success: true,
+            transaction_id: Some(transaction_id),
+            current_points: Some(current_points.unwrap_or(0)),
+            total_nights: Some(total_nights.unwrap_or(0)),
+            tier_name: Some(
+                recalculation
+                    .tier_name
+                    .unwrap_or_else(|| "Bronze".to_string()),
+            ),
+            error: None,
+        },
+        upgrade_grant,
+    ))
+}
+
+/// PUT /loyalty/admin/tiers/:tierId/upgrade-coupon - Set the coupon granted on upgrade into a tier (admin only)
+///
+/// Members moving up into the tier receive the coupon once (see
+/// `grant_tier_upgrade_coupon`). Changing the coupon later doesn't re-grant
+/// to members who already received the old one for this tier.
+async fn admin_set_tier_upgrade_coupon(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(tier_id): Path<Uuid>,
+    Json(payload): Json<AdminSetTierUpgradeCouponRequest>,
+) -> Result<Json<ApiResponse<TierUpgradeCouponResult>>, AppError> {
+    if !has_role(&auth_user, "admin") {
+        return Err(AppError::Forbidden("Admin access required".to_string()));
+    }
+
+    if let Some(coupon_id) = payload.coupon_id {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM coupons WHERE id = $1)")
+                .bind(coupon_id)
+                .fetch_one(state.db())
+                .await?;
+        if !exists {
+            return Err(AppError::NotFound("Coupon not found".to_string()));
+        }
+    }
+
+    let updated: Option<(String, Option<Uuid>)> = sqlx::query_as(
         r#"
-        SELECT ul.total_nights, t.name AS "tier_name?"
-        FROM user_loyalty ul
-        LEFT JOIN tiers t ON ul.tier_id = t.id
-        WHERE ul.user_id = $1
+        UPDATE tiers
+        SET upgrade_coupon_id = $2, updated_at = NOW()
+        WHERE id = $1
+        RETURNING name, upgrade_coupon_id
         "#,
-        payload.user_id,
     )
-    .fetch_one(&mut *tx)
+    .bind(tier_id)
+    .bind(payload.coupon_id)
+    .fetch_optional(state.db())
     .await?;
 
-    let new_total_nights = updated.total_nights.unwrap_or(0);
-    let new_tier_name = updated.tier_name.unwrap_or_else(|| "Bronze".to_string());
-
-    tx.commit().await?;
-
-    // Get updated loyalty status
-    let loyalty_status = get_user_loyalty_status_internal(state.db(), payload.user_id).await?;
+    let (tier_name, upgrade_coupon_id) =
+        updated.ok_or_else(|| AppError::NotFound("Tier not found".to_string()))?;
 
-    let result = AdminSpendingWithNightsResult {
-        transaction_id,
-        points_earned,
-        new_total_nights,
-        new_tier_name,
-        loyalty_status,
-    };
+    tracing::info!(
+        admin_id = %auth_user.id,
+        tier_id = %tier_id,
+        coupon_id = ?upgrade_coupon_id,
+        "Tier upgrade coupon updated"
+    );
 
     Ok(Json(ApiResponse::with_message(
-        result,
-        "Spending points and nights awarded successfully",
+        TierUpgradeCouponResult {
+            tier_id,
+            tier_name,
+            upgrade_coupon_id,
+        },
+        "Tier upgrade coupon updated",
     )))
 }
 
-/// POST /loyalty/admin/award-nights - Award nights only (admin only)
-async fn admin_award_nights(
+/// GET /loyalty/tiers/:tierId - One tier, active or not (admin only, since
+/// the public list hides inactive tiers)
+async fn admin_get_tier(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
-    Json(payload): Json<AdminAwardNightsRequest>,
-) -> Result<Json<ApiResponse<AdminNightsOperationResult>>, AppError> {
+    Path(tier_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<TierResponse>>, AppError> {
     if !has_role(&auth_user, "admin") {
         return Err(AppError::Forbidden("Admin access required".to_string()));
     }
 
-    let admin_user_id = Uuid::parse_str(&auth_user.id)
-        .map_err(|_| AppError::BadRequest("Invalid admin user ID".to_string()))?;
+    let tier = fetch_tier(state.db(), tier_id, false)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Tier not found".to_string()))?;
 
-    if payload.nights <= 0 {
-        return Err(AppError::Validation(
-            "Nights must be greater than 0".to_string(),
-        ));
+    Ok(Json(ApiResponse::success(TierResponse::from(tier))))
+}
+
+/// POST /loyalty/admin/tiers - Create a tier (admin only)
+///
+/// An active tier has to fit the ladder (see `check_tier_ladder`), so new
+/// tiers go above the top or below the bottom one. Existing members are
+/// moved onto it as their tier is next recalculated.
+async fn admin_create_tier(
+    State(state): State<AppState>,
+    mut tx: Tx,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(payload): Json<CreateTierRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<TierResponse>>), AppError> {
+    if !has_role(&auth_user, "admin") {
+        return Err(AppError::Forbidden("Admin access required".to_string()));
     }
+    payload.validate().map_err(AppError::from)?;
 
-    let description = format!("Admin awarded {} night(s)", payload.nights);
+    let is_active = payload.is_active.unwrap_or(true);
+    lock_tier_ladder(&mut tx).await?;
 
-    let mut tx = state.db().begin().await?;
+    if is_active {
+        let mut ladder = active_tier_ladder(&mut *tx, None).await?;
+        ladder.push((payload.sort_order, payload.min_nights));
+        check_tier_ladder(&ladder).map_err(AppError::Conflict)?;
+    }
 
-    // Ensure user has loyalty status before invoking the SP.
-    sqlx::query!(
+    let tier: TierRow = sqlx::query_as(
         r#"
-        INSERT INTO user_loyalty (user_id, tier_id, current_points, total_nights)
-        SELECT $1, t.id, 0, 0
-        FROM tiers t
-        WHERE t.name = 'Bronze'
-        ON CONFLICT (user_id) DO NOTHING
+        INSERT INTO tiers (name, min_points, min_nights, benefits, color, sort_order, is_active)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, name, min_points, min_nights, benefits, color, sort_order, is_active, created_at, updated_at
         "#,
-        payload.user_id,
     )
-    .execute(&mut *tx)
-    .await?;
-
-    // Delegate to the SP. Points = 0; nights = payload.nights triggers
-    // the tier recalculation hook inside the SP.
-    let sp_result: JsonValue = sqlx::query_scalar!(
-        r#"
-        SELECT award_points($1, 0, 'earned_stay'::varchar, $2, $3, $4, $5, $6) AS "result!"
-        "#,
-        payload.user_id,
-        &description,
-        payload.reference_id.as_deref(),
-        admin_user_id,
-        &payload.reason,
-        payload.nights,
+    .bind(payload.name.trim())
+    .bind(payload.min_points.unwrap_or(0))
+    .bind(payload.min_nights)
+    .bind(
+        payload
+            .benefits
+            .as_ref()
+            .map(TierBenefits::to_json)
+            .unwrap_or_else(|| serde_json::json!({})),
     )
+    .bind(&payload.color)
+    .bind(payload.sort_order)
+    .bind(is_active)
     .fetch_one(&mut *tx)
-    .await?;
+    .await
+    .map_err(map_tier_conflict)?;
 
-    let transaction_id = sp_result
-        .get("transaction_id")
-        .and_then(|v| v.as_str())
-        .and_then(|s| Uuid::parse_str(s).ok())
-        .ok_or_else(|| {
-            AppError::Internal("award_points SP did not return a transaction_id".to_string())
-        })?;
+    tx.commit().await?;
+    invalidate_tiers_cache(state.redis()).await;
 
-    let updated = sqlx::query!(
+    tracing::info!(
+        admin_id = %auth_user.id,
+        tier_id = %tier.id,
+        tier_name = %tier.name,
+        "Tier created"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::with_message(
+            TierResponse::from(tier),
+            "Tier created",
+        )),
+    ))
+}
+
+/// PUT /loyalty/admin/tiers/:tierId - Update a tier (admin only)
+///
+/// Omitted fields are left as they are. The result has to fit the ladder
+/// if the tier is (or becomes) active; `is_active: false` deactivates it
+/// exactly as `DELETE` does.
+async fn admin_update_tier(
+    State(state): State<AppState>,
+    mut tx: Tx,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(tier_id): Path<Uuid>,
+    Json(payload): Json<UpdateTierRequest>,
+) -> Result<Json<ApiResponse<AdminTierUpdateResult>>, AppError> {
+    if !has_role(&auth_user, "admin") {
+        return Err(AppError::Forbidden("Admin access required".to_string()));
+    }
+    payload.validate().map_err(AppError::from)?;
+
+    lock_tier_ladder(&mut tx).await?;
+
+    let current = fetch_tier(&mut *tx, tier_id, true)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Tier not found".to_string()))?;
+    let was_active = current.is_active.unwrap_or(true);
+    let is_active = payload.is_active.unwrap_or(was_active);
+    let sort_order = payload.sort_order.unwrap_or(current.sort_order);
+    let min_nights = payload.min_nights.unwrap_or(current.min_nights);
+
+    if is_active {
+        let mut ladder = active_tier_ladder(&mut *tx, Some(tier_id)).await?;
+        ladder.push((sort_order, min_nights));
+        check_tier_ladder(&ladder).map_err(AppError::Conflict)?;
+    }
+
+    sqlx::query(
         r#"
-        SELECT ul.total_nights, t.name AS "tier_name?"
-        FROM user_loyalty ul
-        LEFT JOIN tiers t ON ul.tier_id = t.id
-        WHERE ul.user_id = $1
+        UPDATE tiers
+        SET name = COALESCE($2, name),
+            min_points = COALESCE($3, min_points),
+            min_nights = $4,
+            benefits = COALESCE($5, benefits),
+            color = COALESCE($6, color),
+            sort_order = $7,
+            is_active = $8,
+            updated_at = NOW()
+        WHERE id = $1
         "#,
-        payload.user_id,
     )
-    .fetch_one(&mut *tx)
-    .await?;
+    .bind(tier_id)
+    .bind(payload.name.as_deref().map(str::trim))
+    .bind(payload.min_points)
+    .bind(min_nights)
+    .bind(payload.benefits.as_ref().map(TierBenefits::to_json))
+    .bind(payload.color.as_deref())
+    .bind(sort_order)
+    .bind(is_active)
+    .execute(&mut *tx)
+    .await
+    .map_err(map_tier_conflict)?;
 
-    let new_total_nights = updated.total_nights.unwrap_or(0);
-    let new_tier_name = updated.tier_name.unwrap_or_else(|| "Bronze".to_string());
+    // The gap is closed from the slot the tier held while it was active
+    let reassigned_members = if was_active && !is_active {
+        deactivate_tier(&mut tx, tier_id, current.sort_order).await?
+    } else {
+        0
+    };
 
+    let tier = fetch_tier(&mut *tx, tier_id, false)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Tier not found".to_string()))?;
     tx.commit().await?;
+    invalidate_tiers_cache(state.redis()).await;
 
-    // Get updated loyalty status
-    let loyalty_status = get_user_loyalty_status_internal(state.db(), payload.user_id).await?;
-
-    let result = AdminNightsOperationResult {
-        transaction_id,
-        new_total_nights,
-        new_tier_name,
-        loyalty_status,
-    };
+    tracing::info!(
+        admin_id = %auth_user.id,
+        tier_id = %tier_id,
+        reassigned_members,
+        "Tier updated"
+    );
 
     Ok(Json(ApiResponse::with_message(
-        result,
-        "Nights awarded successfully",
+        AdminTierUpdateResult {
+            tier: TierResponse::from(tier),
+            reassigned_members,
+        },
+        "Tier updated",
     )))
 }
 
-/// POST /loyalty/admin/deduct-nights - Deduct nights only (admin only)
-async fn admin_deduct_nights(
+/// DELETE /loyalty/admin/tiers/:tierId - Deactivate a tier (admin only)
+///
+/// A soft delete: the row stays (with `is_active = false`) so history that
+/// references it still resolves. Its members move to the nearest active
+/// tier for their nights (see `deactivate_tier`). Deactivating an inactive
+/// tier is a no-op.
+async fn admin_deactivate_tier(
     State(state): State<AppState>,
+    mut tx: Tx,
     Extension(auth_user): Extension<AuthUser>,
-    Json(payload): Json<AdminDeductNightsRequest>,
-) -> Result<Json<ApiResponse<AdminNightsOperationResult>>, AppError> {
+    Path(tier_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<AdminTierUpdateResult>>, AppError> {
     if !has_role(&auth_user, "admin") {
         return Err(AppError::Forbidden("Admin access required".to_string()));
     }
 
-    let admin_user_id = Uuid::parse_str(&auth_user.id)
-        .map_err(|_| AppError::BadRequest("Invalid admin user ID".to_string()))?;
-
-    if payload.nights <= 0 {
-        return Err(AppError::Validation(
-            "Nights must be greater than 0".to_string(),
-        ));
-    }
+    lock_tier_ladder(&mut tx).await?;
 
-    // Check if user has enough nights
-    let current = sqlx::query!(
-        "SELECT total_nights FROM user_loyalty WHERE user_id = $1",
-        payload.user_id,
-    )
-    .fetch_optional(state.db())
-    .await?;
+    let current = fetch_tier(&mut *tx, tier_id, true)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Tier not found".to_string()))?;
 
-    let current_nights = current
-        .ok_or_else(|| AppError::NotFound("User loyalty record not found".to_string()))?
-        .total_nights
-        .unwrap_or(0);
+    let reassigned_members = if current.is_active.unwrap_or(true) {
+        deactivate_tier(&mut tx, tier_id, current.sort_order).await?
+    } else {
+        0
+    };
 
-    if current_nights < payload.nights {
-        return Err(AppError::Validation(
-            "Insufficient nights for deduction".to_string(),
-        ));
-    }
+    let tier = fetch_tier(&mut *tx, tier_id, false)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Tier not found".to_string()))?;
+    tx.commit().await?;
+    invalidate_tiers_cache(state.redis()).await;
 
-    let description = format!("Admin deducted {} night(s)", payload.nights);
+    tracing::info!(
+        admin_id = %auth_user.id,
+        tier_id = %tier_id,
+        reassigned_members,
+        "Tier deactivation requested"
+    );
 
-    let mut tx = state.db().begin().await?;
+    Ok(Json(ApiResponse::with_message(
+        AdminTierUpdateResult {
+            tier: TierResponse::from(tier),
+            reassigned_members,
+        },
+        "Tier deactivated",
+    )))
+}
 
-    // Delegate to `award_points` SP with negative nights so the SP
-    // handles the points_transactions insert, balance update, and tier
-    // recalculation in one place. CLAUDE.md rule + Correctness HIGH #4.
-    let neg_nights = -payload.nights;
-    let sp_result: JsonValue = sqlx::query_scalar!(
+/// One tier by id, active or not; `for_update` locks the row
+async fn fetch_tier<'c, E>(
+    executor: E,
+    tier_id: Uuid,
+    for_update: bool,
+) -> Result<Option<TierRow>, AppError>
+where
+    E: sqlx::PgExecutor<'c>,
+{
+    let sql = format!(
         r#"
-        SELECT award_points($1, 0, 'admin_deduction'::varchar, $2, $3, $4, $5, $6) AS "result!"
+        SELECT id, name, min_points, min_nights, benefits, color, sort_order, is_active, created_at, updated_at
+        FROM tiers
+        WHERE id = $1
+        {}
         "#,
-        payload.user_id,
-        &description,
-        payload.reference_id.as_deref(),
-        admin_user_id,
-        &payload.reason,
-        neg_nights,
-    )
-    .fetch_one(&mut *tx)
-    .await?;
+        if for_update { "FOR UPDATE" } else { "" }
+    );
 
-    let transaction_id = sp_result
-        .get("transaction_id")
-        .and_then(|v| v.as_str())
-        .and_then(|s| Uuid::parse_str(s).ok())
-        .ok_or_else(|| {
-            AppError::Internal("award_points SP did not return a transaction_id".to_string())
-        })?;
+    Ok(sqlx::query_as(&sql)
+        .bind(tier_id)
+        .fetch_optional(executor)
+        .await?)
+}
 
-    let updated = sqlx::query!(
+/// Active tiers other than `exclude`, as `(sort_order, min_nights)` for
+/// `check_tier_ladder`
+async fn active_tier_ladder<'c, E>(
+    executor: E,
+    exclude: Option<Uuid>,
+) -> Result<Vec<(i32, i32)>, AppError>
+where
+    E: sqlx::PgExecutor<'c>,
+{
+    Ok(sqlx::query_as(
         r#"
-        SELECT ul.total_nights, t.name AS "tier_name?"
-        FROM user_loyalty ul
-        LEFT JOIN tiers t ON ul.tier_id = t.id
-        WHERE ul.user_id = $1
+        SELECT sort_order, min_nights
+        FROM tiers
+        WHERE is_active = true AND ($1::uuid IS NULL OR id <> $1)
         "#,
-        payload.user_id,
     )
-    .fetch_one(&mut *tx)
-    .await?;
-
-    let new_total_nights = updated.total_nights.unwrap_or(0);
-    let new_tier_name = updated.tier_name.unwrap_or_else(|| "Bronze".to_string());
-
-    tx.commit().await?;
-
-    // Get updated loyalty status
-    let loyalty_status = get_user_loyalty_status_internal(state.db(), payload.user_id).await?;
-
-    let result = AdminNightsOperationResult {
-        transaction_id,
-        new_total_nights,
-        new_tier_name,
-        loyalty_status,
-    };
+    .bind(exclude)
+    .fetch_all(executor)
+    .await?)
+}
 
-    Ok(Json(ApiResponse::with_message(
-        result,
-        "Nights deducted successfully",
-    )))
+/// Rewrite the tier name unique-violation (`tiers_name_key`) as a 409
+fn map_tier_conflict(err: sqlx::Error) -> AppError {
+    if let sqlx::Error::Database(ref db_err) = err {
+        if db_err.code().as_deref() == Some("23505") {
+            return AppError::Conflict("A tier with this name already exists".to_string());
+        }
+    }
+    AppError::from(err)
 }
 
 /// Create loyalty routes with explicit AppState (for backwards compatibility)
@@ -2277,13 +5059,22 @@ async fn admin_deduct_nights(
 pub fn routes_with_app_state(state: AppState) -> Router {
     // Public routes (no auth required)
     let public_routes = Router::new()
-        .route("/tiers", get(get_tiers_full))
+        .route(
+            "/tiers",
+            get(get_tiers_full).layer(middleware::from_fn_with_state(
+                TIERS_CACHE_SCOPE,
+                response_cache_middleware,
+            )),
+        )
         .with_state(state.clone());
 
     // Authenticated routes
     let auth_routes = Router::new()
         .route("/status", get(get_status_full))
         .route("/transactions", get(get_transactions_full))
+        .route("/transactions/export", get(export_transactions_full))
+        .route("/referrals", get(get_referrals_full))
+        .route("/leaderboard", get(get_leaderboard_full))
         .route("/award", post(award_points_full))
         .route("/recalculate/:user_id", post(recalculate_tier_full))
         .layer(middleware::from_fn(auth_middleware))
@@ -2294,6 +5085,8 @@ pub fn routes_with_app_state(state: AppState) -> Router {
         .route("/admin/users", get(admin_get_users))
         .route("/admin/award-points", post(admin_award_points))
         .route("/admin/deduct-points", post(admin_deduct_points))
+        .route("/admin/convert-points", post(admin_convert_points))
+        .route("/admin/partial-adjust", post(admin_partial_adjust))
         .route("/admin/transactions", get(admin_get_transactions))
         .route("/admin/user/:userId/history", get(admin_get_user_history))
         .route("/admin/earning-rules", get(admin_get_earning_rules))
@@ -2302,8 +5095,37 @@ pub fn routes_with_app_state(state: AppState) -> Router {
             "/admin/award-spending-with-nights",
             post(admin_award_spending_with_nights),
         )
+        .route("/admin/award-preview", post(admin_award_preview))
         .route("/admin/award-nights", post(admin_award_nights))
         .route("/admin/deduct-nights", post(admin_deduct_nights))
+        .route("/admin/bulk-award", post(admin_bulk_award))
+        .route("/admin/recalculate-all", post(admin_recalculate_all_tiers))
+        .route(
+            "/admin/recalculate-all/:jobId",
+            get(admin_get_tier_recalculation),
+        )
+        .route("/tiers/:tierId", get(admin_get_tier))
+        .route(
+            "/admin/tiers",
+            post(admin_create_tier).layer(middleware::from_fn_with_state(
+                TIERS_CACHE_SCOPE,
+                response_cache_middleware,
+            )),
+        )
+        .route(
+            "/admin/tiers/:tierId",
+            put(admin_update_tier).delete(admin_deactivate_tier).layer(
+                middleware::from_fn_with_state(TIERS_CACHE_SCOPE, response_cache_middleware),
+            ),
+        )
+        .route(
+            "/admin/tiers/:tierId/upgrade-coupon",
+            put(admin_set_tier_upgrade_coupon).layer(middleware::from_fn_with_state(
+                TIERS_CACHE_SCOPE,
+                response_cache_middleware,
+            )),
+        )
+        .layer(middleware::from_fn(transaction_layer))
         .layer(middleware::from_fn(auth_middleware))
         .with_state(state);
 
@@ -2368,6 +5190,7 @@ mod tests {
         let query = TransactionsQuery {
             page: default_page(),
             limit: default_limit(),
+            cursor: None,
         };
         assert_eq!(query.page, 1);
         assert_eq!(query.limit, 20);
@@ -2391,6 +5214,7 @@ mod tests {
         let response: TierResponse = row.into();
         assert_eq!(response.name, "Gold");
         assert_eq!(response.min_nights, 10);
+        assert_eq!(response.benefits.discount_percent, 15.0);
         assert!(response.is_active);
     }
 
@@ -2410,7 +5234,7 @@ mod tests {
         };
 
         let response: TierResponse = row.into();
-        assert_eq!(response.benefits, serde_json::json!({}));
+        assert_eq!(response.benefits, TierBenefits::default());
         assert!(response.is_active); // Defaults to true
     }
 
@@ -2428,6 +5252,18 @@ mod tests {
         assert_eq!(response.message, Some("Operation completed".to_string()));
     }
 
+    #[test]
+    fn test_offset_pagination() {
+        let pagination = offset_pagination(40, 20, 95);
+        assert_eq!(pagination.page, 3);
+        assert_eq!(pagination.limit, 20);
+        assert_eq!(pagination.total, 95);
+        assert_eq!(pagination.total_pages(), 5);
+
+        let first = offset_pagination(-5, 0, -1);
+        assert_eq!((first.page, first.limit, first.total), (1, 1, 0));
+    }
+
     #[test]
     fn test_award_points_request_deserialization() {
         let json = r#"{
@@ -2450,7 +5286,7 @@ mod tests {
             id: Uuid::new_v4(),
             user_id: Uuid::new_v4(),
             points: 500,
-            transaction_type: "earned_stay".to_string(),
+            transaction_type: PointsTransactionType::EarnedStay,
             description: Some("Hotel stay".to_string()),
             reference_id: Some("STAY-001".to_string()),
             admin_user_id: None,
@@ -2462,8 +5298,11 @@ mod tests {
 
         let response: PointsTransactionResponse = row.into();
         assert_eq!(response.points, 500);
-        assert_eq!(response.transaction_type, "earned_stay");
+        assert_eq!(response.transaction_type, PointsTransactionType::EarnedStay);
         assert_eq!(response.nights_stayed, 3);
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["type"], "earned_stay");
     }
 
     // ============================================================================
@@ -2571,12 +5410,202 @@ mod tests {
     fn test_admin_nights_operation_result_serialization() {
         let result = AdminNightsOperationResult {
             transaction_id: Uuid::new_v4(),
+            points_awarded: 0,
             new_total_nights: 15,
             new_tier_name: "Silver".to_string(),
             loyalty_status: None,
+            stay_milestone: None,
         };
         let json = serde_json::to_string(&result).unwrap();
         assert!(json.contains("newTotalNights"));
         assert!(json.contains("Silver"));
+        assert!(!json.contains("stayMilestone"));
+
+        let result = AdminNightsOperationResult {
+            stay_milestone: Some(StayMilestone::FirstStay),
+            ..result
+        };
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["stayMilestone"], "first_stay");
+    }
+
+    #[test]
+    fn test_referrals_response_serialization() {
+        let response = ReferralsResponse {
+            referral_code: Some("AB12CD34".to_string()),
+            referrals: vec![ReferralSummary {
+                id: Uuid::new_v4(),
+                status: "pending".to_string(),
+                points_earned: 0,
+                created_at: Utc::now(),
+                qualified_at: None,
+            }],
+        };
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"referralCode\":\"AB12CD34\""));
+        assert!(json.contains("pointsEarned"));
+        assert!(json.contains("qualifiedAt"));
+    }
+
+    #[test]
+    fn test_award_transaction_type() {
+        assert_eq!(
+            award_transaction_type(None).unwrap(),
+            PointsTransactionType::AdminAward
+        );
+        assert_eq!(
+            award_transaction_type(Some("earned_stay")).unwrap(),
+            PointsTransactionType::EarnedStay
+        );
+        assert!(award_transaction_type(Some("admin_deduction")).is_err());
+        assert!(award_transaction_type(Some("bogus")).is_err());
+    }
+
+    #[test]
+    fn test_exceeded_award_caps() {
+        let config = LoyaltyConfig {
+            max_points_per_award: 10_000,
+            max_nights_per_award: 30,
+            ..LoyaltyConfig::default()
+        };
+
+        assert!(exceeded_award_caps(&config, 10_000, 30).is_empty());
+        assert_eq!(
+            exceeded_award_caps(&config, 10_001, 0),
+            vec!["10001 points (limit 10000)".to_string()]
+        );
+        // Deductions are capped by size too
+        assert_eq!(exceeded_award_caps(&config, 0, -100_000).len(), 1);
+        assert_eq!(exceeded_award_caps(&config, -20_000, 31).len(), 2);
+
+        let uncapped = LoyaltyConfig {
+            max_points_per_award: 0,
+            max_nights_per_award: 0,
+            ..LoyaltyConfig::default()
+        };
+        assert!(exceeded_award_caps(&uncapped, i64::from(i32::MAX), 100_000).is_empty());
+    }
+
+    #[test]
+    fn test_converted_points_rounds_down() {
+        use rust_decimal::Decimal;
+
+        assert_eq!(converted_points(1_000, Decimal::new(5, 1)), Some(500));
+        assert_eq!(converted_points(999, Decimal::new(5, 1)), Some(499));
+        assert_eq!(converted_points(3, Decimal::new(25, 2)), Some(0));
+        assert_eq!(converted_points(i64::MAX, Decimal::from(2)), None);
+    }
+
+    #[test]
+    fn test_refunded_fraction() {
+        use rust_decimal::Decimal;
+
+        assert_eq!(
+            refunded_fraction(Some(Decimal::new(25, 2)), None, None).unwrap(),
+            Decimal::new(25, 2)
+        );
+        assert_eq!(
+            refunded_fraction(None, Some(Decimal::from(1_000)), Some(Decimal::from(3_000)))
+                .unwrap(),
+            Decimal::new(333_333, 6)
+        );
+        assert_eq!(
+            refunded_fraction(None, Some(Decimal::from(500)), Some(Decimal::from(500))).unwrap(),
+            Decimal::ONE
+        );
+
+        assert!(refunded_fraction(None, None, None).is_err());
+        assert!(refunded_fraction(Some(Decimal::ONE), Some(Decimal::ONE), None).is_err());
+        assert!(refunded_fraction(Some(Decimal::ZERO), None, None).is_err());
+        assert!(refunded_fraction(Some(Decimal::new(11, 1)), None, None).is_err());
+        assert!(refunded_fraction(Some(Decimal::new(1, 9)), None, None).is_err());
+        assert!(refunded_fraction(None, Some(Decimal::ONE), Some(Decimal::ZERO)).is_err());
+        assert!(
+            refunded_fraction(None, Some(Decimal::from(600)), Some(Decimal::from(500))).is_err()
+        );
+    }
+
+    #[test]
+    fn test_refund_adjustment_never_exceeds_award() {
+        use rust_decimal::Decimal;
+
+        let third = Decimal::new(333_333, 6);
+        assert_eq!(refund_adjustment(1_000, 0, Decimal::new(25, 2)), 250);
+        assert_eq!(refund_adjustment(1_000, 0, third), 333);
+        assert_eq!(refund_adjustment(999, 0, Decimal::new(5, 1)), 499);
+        assert_eq!(refund_adjustment(3, 0, Decimal::new(1, 1)), 0);
+
+        // Three one-third refunds, then a full one: never more than 1000
+        assert_eq!(refund_adjustment(1_000, 333, third), 333);
+        assert_eq!(refund_adjustment(1_000, 666, third), 333);
+        assert_eq!(refund_adjustment(1_000, 999, Decimal::ONE), 1);
+        assert_eq!(refund_adjustment(1_000, 1_000, Decimal::ONE), 0);
+        assert_eq!(refund_adjustment(1_000, 800, Decimal::new(5, 1)), 200);
+        assert_eq!(refund_adjustment(0, 0, Decimal::ONE), 0);
+    }
+
+    #[test]
+    fn test_expire_points_result_shape() {
+        let applied = serde_json::to_value(ExpirePointsResult {
+            expired_count: 3,
+            preview: None,
+        })
+        .unwrap();
+        assert_eq!(applied, serde_json::json!({ "expired_count": 3 }));
+
+        let user_id = Uuid::new_v4();
+        let preview = serde_json::to_value(ExpirePointsResult {
+            expired_count: 3,
+            preview: Some(ExpirePointsPreview {
+                dry_run: true,
+                points_to_expire: 700,
+                users: vec![ExpirePointsPreviewUser {
+                    user_id,
+                    points_to_expire: 700,
+                    transactions: 3,
+                }],
+            }),
+        })
+        .unwrap();
+        assert_eq!(preview["expired_count"], 3);
+        assert_eq!(preview["dry_run"], true);
+        assert_eq!(preview["users"][0]["user_id"], user_id.to_string());
+        assert_eq!(preview["users"][0]["points_to_expire"], 700);
+    }
+
+    #[test]
+    fn test_transaction_cursor_round_trip() {
+        let cursor = TransactionCursor {
+            created_at: DateTime::parse_from_rfc3339("2026-05-16T10:30:00.123456Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            id: Uuid::new_v4(),
+        };
+
+        let encoded = cursor.encode();
+        assert!(encoded
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(TransactionCursor::decode(&encoded).unwrap(), cursor);
+    }
+
+    #[test]
+    fn test_transaction_cursor_rejects_garbage() {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+        for bad in [
+            "not base64!".to_string(),
+            URL_SAFE_NO_PAD.encode("2026-05-16T10:30:00Z"),
+            URL_SAFE_NO_PAD.encode("yesterday|00000000-0000-0000-0000-000000000000"),
+            URL_SAFE_NO_PAD.encode("2026-05-16T10:30:00Z|not-a-uuid"),
+        ] {
+            assert!(
+                matches!(
+                    TransactionCursor::decode(&bad),
+                    Err(AppError::BadRequest(_))
+                ),
+                "{bad} should be rejected"
+            );
+        }
     }
 }
@@ -5,8 +5,9 @@
 //!
 //! ## Endpoints
 //!
-//! - `GET /my-id` - Get current user's membership ID (authenticated)
-//! - `GET /lookup/:membershipId` - Look up user by membership ID (admin only)
+//! - `GET /my-id` - Get current user's membership ID and code (authenticated)
+//! - `POST /regenerate-code` - Replace the current user's membership code (authenticated)
+//! - `GET /lookup/:membershipId` - Look up user by membership ID or code (admin only)
 //! - `GET /stats` - Get membership ID statistics (admin only)
 //! - `POST /regenerate/:userId` - Regenerate membership ID for user (super_admin only)
 
@@ -23,39 +24,16 @@ use uuid::Uuid;
 
 use crate::error::AppError;
 use crate::middleware::auth::{auth_middleware, has_role, AuthUser};
+use crate::services::membership_id::{
+    membership_code, rotate_membership_code, verify_membership_code,
+};
 use crate::state::AppState;
+use crate::types::ApiResponse;
 
 // ============================================================================
 // Response Types
 // ============================================================================
 
-/// API success response wrapper
-#[derive(Debug, Serialize)]
-pub struct ApiResponse<T: Serialize> {
-    pub success: bool,
-    pub data: T,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub message: Option<String>,
-}
-
-impl<T: Serialize> ApiResponse<T> {
-    pub fn success(data: T) -> Self {
-        Self {
-            success: true,
-            data,
-            message: None,
-        }
-    }
-
-    pub fn with_message(data: T, message: impl Into<String>) -> Self {
-        Self {
-            success: true,
-            data,
-            message: Some(message.into()),
-        }
-    }
-}
-
 /// User info returned from membership lookup
 #[derive(Debug, Clone, Serialize, FromRow)]
 pub struct MembershipUserInfo {
@@ -83,6 +61,10 @@ pub struct MembershipUserInfo {
 pub struct MyMembershipIdResponse {
     #[serde(rename = "membershipId")]
     pub membership_id: String,
+    /// Signed code for the membership card / QR code. Changes on
+    /// regeneration; `membershipId` never does.
+    #[serde(rename = "membershipCode")]
+    pub membership_code: String,
 }
 
 /// Membership ID statistics
@@ -136,23 +118,66 @@ async fn get_my_membership_id(
     let user_id = Uuid::parse_str(&auth_user.id)
         .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
 
-    let result: Option<(String,)> =
-        sqlx::query_as("SELECT membership_id FROM user_profiles WHERE user_id = $1")
-            .bind(user_id)
-            .fetch_optional(state.db())
-            .await?;
+    let result: Option<(String, i32)> = sqlx::query_as(
+        "SELECT membership_id, membership_code_version FROM user_profiles WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_optional(state.db())
+    .await?;
 
-    let membership_id = result
-        .ok_or_else(|| AppError::NotFound("User profile not found".to_string()))?
-        .0;
+    let (membership_id, version) =
+        result.ok_or_else(|| AppError::NotFound("User profile not found".to_string()))?;
 
     Ok(Json(ApiResponse::success(MyMembershipIdResponse {
+        membership_code: membership_code(state.jwt_secret(), &membership_id, version),
         membership_id,
     })))
 }
 
+/// POST /membership/regenerate-code
+/// Replace the current user's membership code (e.g. after losing their card).
+///
+/// The membership ID stays the same; only the signed code rotates, and the
+/// previous code is rejected by `GET /lookup/:membershipId` from then on.
+/// Limited to one regeneration per hour.
+async fn regenerate_my_membership_code(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<ApiResponse<MyMembershipIdResponse>>, AppError> {
+    let user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
+
+    let (membership_id, version) = rotate_membership_code(state.db(), user_id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_audit_log (user_id, action, details)
+        VALUES ($1, 'membership_code_regenerated', $2)
+        "#,
+    )
+    .bind(user_id)
+    .bind(serde_json::json!({ "codeVersion": version }))
+    .execute(state.db())
+    .await?;
+
+    tracing::info!(user_id = %user_id, code_version = version, "Membership code regenerated");
+
+    Ok(Json(ApiResponse::with_message(
+        MyMembershipIdResponse {
+            membership_code: membership_code(state.jwt_secret(), &membership_id, version),
+            membership_id,
+        },
+        "Membership code regenerated successfully",
+    )))
+}
+
 /// GET /membership/lookup/:membershipId
 /// Look up user information by membership ID (admin only)
+///
+/// Accepts either the membership ID itself (typed in by staff) or a
+/// scanned membership code. A code must carry a valid signature and the
+/// profile's current code version, so codes replaced through
+/// `POST /regenerate-code` are rejected.
 async fn lookup_membership(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
@@ -170,11 +195,37 @@ async fn lookup_membership(
         ));
     }
 
-    if !validate_membership_id(&membership_id) {
+    let membership_id = if validate_membership_id(&membership_id) {
+        membership_id
+    } else if let Some((code_membership_id, code_version)) =
+        verify_membership_code(state.jwt_secret(), &membership_id)
+    {
+        let current_version: Option<i32> = sqlx::query_scalar(
+            "SELECT membership_code_version FROM user_profiles WHERE membership_id = $1",
+        )
+        .bind(&code_membership_id)
+        .fetch_optional(state.db())
+        .await?;
+
+        match current_version {
+            Some(version) if version == code_version => code_membership_id,
+            Some(_) => {
+                return Err(AppError::BadRequest(
+                    "This membership code has been replaced. Ask the member for their current code."
+                        .to_string(),
+                ));
+            },
+            None => {
+                return Err(AppError::NotFound(
+                    "User not found with this membership ID".to_string(),
+                ));
+            },
+        }
+    } else {
         return Err(AppError::BadRequest(
             "Invalid membership ID format. Must be 8 digits starting with 269.".to_string(),
         ));
-    }
+    };
 
     // Look up user by membership ID
     let user_info: Option<MembershipUserInfo> = sqlx::query_as(
@@ -374,13 +425,15 @@ async fn generate_membership_id(db: &sqlx::PgPool) -> Result<String, AppError> {
 ///
 /// ## Endpoints
 ///
-/// - `GET /my-id` - Get current user's membership ID (authenticated)
-/// - `GET /lookup/:membershipId` - Look up user by membership ID (admin only)
+/// - `GET /my-id` - Get current user's membership ID and code (authenticated)
+/// - `POST /regenerate-code` - Replace the current user's membership code (authenticated)
+/// - `GET /lookup/:membershipId` - Look up user by membership ID or code (admin only)
 /// - `GET /stats` - Get membership ID statistics (admin only)
 /// - `POST /regenerate/:userId` - Regenerate membership ID for user (super_admin only)
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/my-id", get(get_my_membership_id))
+        .route("/regenerate-code", post(regenerate_my_membership_code))
         .route("/lookup/:membershipId", get(lookup_membership))
         .route("/stats", get(get_membership_stats))
         .route("/:userId/regenerate", post(regenerate_membership_id))
@@ -411,6 +464,7 @@ mod tests {
     fn test_api_response_success() {
         let response = ApiResponse::success(MyMembershipIdResponse {
             membership_id: "26900001".to_string(),
+            membership_code: membership_code("secret", "26900001", 1),
         });
         assert!(response.success);
         assert!(response.message.is_none());
@@ -6,6 +6,7 @@
 pub mod admin;
 pub mod admin_bookings;
 pub mod admin_email;
+pub mod admin_maintenance;
 pub mod admin_rooms;
 pub mod admin_slips;
 pub mod analytics;
@@ -29,8 +30,15 @@ use axum::{middleware, Extension, Router};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
-use crate::middleware::auth::JwtSecret;
-use crate::middleware::rate_limit::{redis_rate_limit_middleware, RedisRateLimiter};
+use crate::middleware::admin::{admin_ip_allowlist_middleware, AdminIpAllowlist};
+use crate::middleware::auth::{JwtSecret, SessionRevocations};
+use crate::middleware::cache_control::cache_control_middleware;
+use crate::middleware::csrf::{csrf_middleware, CsrfProtection};
+use crate::middleware::rate_limit::{
+    redis_rate_limit_middleware, RateLimitConfig, RedisRateLimiter,
+};
+use crate::middleware::read_only::{read_only_middleware, ReadOnlyMode};
+use crate::middleware::response_cache::ResponseCacheStore;
 use crate::openapi::ApiDoc;
 use crate::state::AppState;
 
@@ -69,22 +77,51 @@ use crate::state::AppState;
 pub fn create_router(state: AppState) -> Router {
     // Extract JWT secret from config to inject as Extension for auth middleware
     let jwt_secret = JwtSecret(state.config().auth.jwt_secret.clone());
+    // Per-user session revocation markers, checked by auth_middleware
+    let session_revocations = SessionRevocations(state.redis());
+    // Shared response cache for routes layered with a `CacheScope`
+    let response_cache = ResponseCacheStore::new(state.redis(), &state.config().http_cache);
+
+    // Optional admin network allowlist (`ADMIN_IP_ALLOWLIST`). The lists
+    // were validated when settings loaded; an empty allowlist is a no-op.
+    let admin_allowlist =
+        AdminIpAllowlist::from_config(&state.config().security).unwrap_or_else(|e| {
+            tracing::error!(error = %e, "Invalid admin IP allowlist; admin endpoints unrestricted");
+            AdminIpAllowlist::default()
+        });
 
     // Rate limiters are only attached in production. In development and test
     // we disable them so iterative testing (login retries, integration suites
     // hitting the same endpoints from 127.0.0.1) doesn't trip the strict
-    // 5/min threshold. The limiter itself fails open if Redis is unavailable,
-    // so leaving it on in dev would still mostly work — but disabling is
-    // simpler and avoids flaky tests.
+    // 5/min threshold. The limiter itself falls back to per-instance counting
+    // if Redis is unavailable, so leaving it on in dev would still mostly
+    // work — but disabling is simpler and avoids flaky tests.
     let rate_limiters = if state.is_production() {
+        let headers = state.config().security.rate_limit_headers;
+        let key_strategy = state.config().security.rate_limit_key;
         Some((
-            RedisRateLimiter::with_defaults(state.redis(), "api"),
-            RedisRateLimiter::strict(state.redis(), "auth"),
+            RedisRateLimiter::new(
+                state.redis(),
+                RateLimitConfig::from_security(&state.config().security),
+                "api",
+            )
+            .with_headers(headers),
+            RedisRateLimiter::strict(state.redis(), "auth")
+                .with_headers(headers)
+                .with_key_strategy(key_strategy),
         ))
     } else {
         None
     };
 
+    // Global read-only switch (`PUT /api/admin/read-only`), checked on
+    // every state-changing request.
+    let read_only = ReadOnlyMode::new(state.redis());
+
+    // Double-submit CSRF check for cookie-authenticated writes under
+    // `CSRF_PROTECTED_PATHS`; a no-op while that is empty.
+    let csrf = CsrfProtection::from_config(&state.config().security);
+
     // Storage routes use a different state type, so mount separately.
     //
     // MED-6 (security-2026-05-13.md): the slip-serving handler needs DB
@@ -141,7 +178,32 @@ pub fn create_router(state: AppState) -> Router {
         None => app,
     };
 
+    // Read-only mode rejects writes before they reach any handler; it sits
+    // inside the allowlist so a blocked network still gets its 403.
+    let app = app.layer(middleware::from_fn_with_state(
+        read_only,
+        read_only_middleware,
+    ));
+
+    // A forged cross-site write is turned away before the read-only
+    // lookup or any handler runs.
+    let app = app.layer(middleware::from_fn_with_state(csrf, csrf_middleware));
+
+    // Admin IP allowlist sits outside the per-router auth layers so a
+    // request from a disallowed network is rejected before any handler or
+    // token check runs.
+    let app = app.layer(middleware::from_fn_with_state(
+        admin_allowlist,
+        admin_ip_allowlist_middleware,
+    ));
+
+    // Responses are `no-store` unless the handler returned a `CachePolicy`,
+    // so user-specific data is never cached by browsers or CDNs.
+    let app = app.layer(middleware::from_fn(cache_control_middleware));
+
     app.layer(Extension(jwt_secret))
+        .layer(Extension(session_revocations))
+        .layer(Extension(response_cache))
 }
 
 #[cfg(test)]
@@ -5,6 +5,7 @@
 //! - Getting unread count
 //! - Marking notifications as read (single and all)
 //! - Deleting notifications
+//! - Quiet hours for non-critical notification pushes
 
 use axum::{
     extract::{Extension, Path, Query, State},
@@ -13,13 +14,14 @@ use axum::{
     routing::{delete, get, post, put},
     Json, Router,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
 use crate::middleware::auth::{auth_middleware, has_role, AuthUser};
+use crate::models::notification::NotificationPriority;
 use crate::state::AppState;
 
 // ==================== REQUEST/RESPONSE TYPES ====================
@@ -44,6 +46,10 @@ pub struct NotificationDto {
     pub message: String,
     #[sqlx(rename = "type")]
     pub notification_type: String,
+    #[sqlx(default)]
+    pub priority: NotificationPriority,
+    #[sqlx(default)]
+    pub category: Option<String>,
     pub data: Option<serde_json::Value>,
     pub read_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
@@ -60,6 +66,8 @@ pub struct NotificationResponse {
     pub message: String,
     #[serde(rename = "type")]
     pub notification_type: String,
+    pub priority: NotificationPriority,
+    pub category: Option<String>,
     pub data: Option<serde_json::Value>,
     pub read_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
@@ -76,6 +84,8 @@ impl From<NotificationDto> for NotificationResponse {
             title: n.title,
             message: n.message,
             notification_type: n.notification_type,
+            priority: n.priority,
+            category: n.category,
             data: n.data,
             read_at: n.read_at,
             created_at: n.created_at,
@@ -157,6 +167,46 @@ pub struct UpdatePreferencesRequest {
     pub preferences: Vec<NotificationPreference>,
 }
 
+/// A member's quiet hours; times are `HH:MM` in `timezone`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuietHours {
+    pub enabled: bool,
+    pub start: String,
+    pub end: String,
+    pub timezone: String,
+}
+
+/// Quiet hours response; `quiet_hours` is null until the member sets them
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuietHoursResponse {
+    pub user_id: Uuid,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+/// Set quiet hours request
+#[derive(Debug, Deserialize)]
+pub struct UpdateQuietHoursRequest {
+    #[serde(default = "default_quiet_hours_enabled")]
+    pub enabled: bool,
+    /// Window start, `HH:MM`
+    pub start: String,
+    /// Window end, `HH:MM`; earlier than `start` to span midnight
+    pub end: String,
+    /// IANA time zone (e.g. `Asia/Bangkok`); the configured default when
+    /// omitted
+    pub timezone: Option<String>,
+}
+
+fn default_quiet_hours_enabled() -> bool {
+    true
+}
+
+/// Parse an `HH:MM` quiet-hours time
+fn parse_quiet_time(value: &str, field: &str) -> Result<NaiveTime, AppError> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| AppError::Validation(format!("{} must be a time like 22:00", field)))
+}
+
 /// Cleanup response
 #[derive(Debug, Serialize)]
 pub struct CleanupResponse {
@@ -236,6 +286,8 @@ async fn list_notifications(
                 title,
                 message,
                 type,
+                priority,
+                category,
                 data,
                 read_at,
                 created_at,
@@ -262,6 +314,8 @@ async fn list_notifications(
                 title,
                 message,
                 type,
+                priority,
+                category,
                 data,
                 read_at,
                 created_at,
@@ -379,6 +433,8 @@ async fn mark_notification_read(
             title,
             message,
             type,
+            priority,
+            category,
             data,
             read_at,
             created_at,
@@ -556,6 +612,106 @@ async fn update_notification_preferences(
     }))
 }
 
+/// Load a member's quiet hours, if set
+async fn fetch_quiet_hours(
+    pool: &sqlx::PgPool,
+    user_id: Uuid,
+) -> Result<Option<QuietHours>, AppError> {
+    let row: Option<(bool, NaiveTime, NaiveTime, String)> = sqlx::query_as(
+        "SELECT enabled, start_time, end_time, timezone FROM notification_quiet_hours WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|(enabled, start, end, timezone)| QuietHours {
+        enabled,
+        start: start.format("%H:%M").to_string(),
+        end: end.format("%H:%M").to_string(),
+        timezone,
+    }))
+}
+
+/// GET /api/notifications/quiet-hours
+async fn get_quiet_hours(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> AppResult<Json<QuietHoursResponse>> {
+    let user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::InvalidInput("Invalid user ID".to_string()))?;
+
+    Ok(Json(QuietHoursResponse {
+        user_id,
+        quiet_hours: fetch_quiet_hours(state.db(), user_id).await?,
+    }))
+}
+
+/// PUT /api/notifications/quiet-hours
+///
+/// Non-critical notifications created inside the window are stored but
+/// pushed only when it ends (see
+/// `services::notification::release_held_notifications`).
+async fn update_quiet_hours(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(payload): Json<UpdateQuietHoursRequest>,
+) -> AppResult<Json<QuietHoursResponse>> {
+    let user_id = Uuid::parse_str(&auth_user.id)
+        .map_err(|_| AppError::InvalidInput("Invalid user ID".to_string()))?;
+
+    let start = parse_quiet_time(&payload.start, "start")?;
+    let end = parse_quiet_time(&payload.end, "end")?;
+    if start == end {
+        return Err(AppError::Validation(
+            "start and end must be different times".to_string(),
+        ));
+    }
+
+    let timezone = payload
+        .timezone
+        .as_deref()
+        .map(str::trim)
+        .filter(|tz| !tz.is_empty())
+        .unwrap_or(&state.config().notification_quiet_hours.default_timezone)
+        .to_string();
+    let known_zone: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM pg_timezone_names WHERE name = $1)")
+            .bind(&timezone)
+            .fetch_one(state.db())
+            .await?;
+    if !known_zone {
+        return Err(AppError::Validation(format!(
+            "Unknown time zone '{}'",
+            timezone
+        )));
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO notification_quiet_hours (user_id, enabled, start_time, end_time, timezone)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (user_id) DO UPDATE SET
+            enabled = $2,
+            start_time = $3,
+            end_time = $4,
+            timezone = $5,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(user_id)
+    .bind(payload.enabled)
+    .bind(start)
+    .bind(end)
+    .bind(&timezone)
+    .execute(state.db())
+    .await?;
+
+    Ok(Json(QuietHoursResponse {
+        user_id,
+        quiet_hours: fetch_quiet_hours(state.db(), user_id).await?,
+    }))
+}
+
 /// POST /api/notifications/admin/cleanup
 async fn cleanup_notifications(
     State(state): State<AppState>,
@@ -590,6 +746,7 @@ async fn cleanup_notifications(
 /// - PUT /:id/read - Mark as read
 /// - PUT /read-all - Mark all as read
 /// - DELETE /:id - Delete notification
+/// - GET/PUT /quiet-hours - Get or set quiet hours
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/", get(list_notifications))
@@ -599,6 +756,8 @@ pub fn router() -> Router<AppState> {
         .route("/:id", delete(delete_notification))
         .route("/preferences", get(get_notification_preferences))
         .route("/preferences", put(update_notification_preferences))
+        .route("/quiet-hours", get(get_quiet_hours))
+        .route("/quiet-hours", put(update_quiet_hours))
         .route("/admin/cleanup", post(cleanup_notifications))
         .layer(middleware::from_fn(auth_middleware))
 }
@@ -746,6 +905,8 @@ mod tests {
             title: "Test Title".to_string(),
             message: "Test Message".to_string(),
             notification_type: "info".to_string(),
+            priority: NotificationPriority::default(),
+            category: None,
             data: Some(serde_json::json!({"key": "value"})),
             read_at: None,
             created_at: Utc::now(),
@@ -775,6 +936,8 @@ mod tests {
             title: "Test".to_string(),
             message: "Test".to_string(),
             notification_type: "reward".to_string(),
+            priority: NotificationPriority::Normal,
+            category: None,
             data: None,
             read_at: None,
             created_at: Utc::now(),
@@ -787,4 +950,26 @@ mod tests {
         assert!(json.contains("\"type\":\"reward\""));
         assert!(!json.contains("\"notification_type\""));
     }
+
+    #[test]
+    fn test_notification_response_priority_serialization() {
+        let response = NotificationResponse {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            title: "New sign-in".to_string(),
+            message: "Your account was accessed from a new device".to_string(),
+            notification_type: "warning".to_string(),
+            priority: NotificationPriority::High,
+            category: Some("security".to_string()),
+            data: None,
+            read_at: None,
+            created_at: Utc::now(),
+            expires_at: None,
+            is_read: false,
+        };
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"priority\":\"high\""));
+        assert!(json.contains("\"category\":\"security\""));
+    }
 }
@@ -16,8 +16,10 @@ use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::config::Settings;
 use crate::error::{AppError, AppResult};
 use crate::middleware::auth::{auth_middleware, build_refresh_cookie_header, AuthUser};
+use crate::services::auth::password_change_required;
 use crate::state::AppState;
 
 // =============================================================================
@@ -178,22 +180,29 @@ pub struct TokenResponse {
 // =============================================================================
 
 /// Validates and sanitizes OAuth return URLs to prevent open redirect attacks.
-/// Only allows redirects to the same origin as the configured frontend URL.
-fn validate_return_url(input_url: Option<&str>, frontend_url: &str) -> String {
+/// Only allows redirects to the frontend URL's origin or one of the
+/// configured `OAUTH_ALLOWED_REDIRECT_ORIGINS`; anything else falls back to
+/// the frontend URL.
+fn validate_return_url(
+    input_url: Option<&str>,
+    frontend_url: &str,
+    allowed_origins: &[String],
+) -> String {
     let default_url = frontend_url.to_string();
 
     let Some(url) = input_url else {
         return default_url;
     };
 
-    // Extract origin from frontend URL for comparison
-    let frontend_origin = extract_origin(frontend_url);
-    let input_origin = extract_origin(url);
-
-    // Only allow redirects to the same origin
-    if let (Some(fo), Some(io)) = (frontend_origin, input_origin) {
-        if fo == io {
-            return url.to_string();
+    // Compare parsed origins rather than string prefixes so tricks like
+    // `https://app.example.com@evil.com` resolve to the real host.
+    if let Ok(parsed) = reqwest::Url::parse(url) {
+        let has_credentials = !parsed.username().is_empty() || parsed.password().is_some();
+        if let (false, Some(origin)) = (has_credentials, extract_origin(url)) {
+            let same_origin = extract_origin(frontend_url).as_deref() == Some(origin.as_str());
+            if same_origin || allowed_origins.contains(&origin) {
+                return parsed.to_string();
+            }
         }
     }
 
@@ -205,29 +214,18 @@ fn validate_return_url(input_url: Option<&str>, frontend_url: &str) -> String {
     default_url
 }
 
-/// Extract origin (scheme + host + port) from a URL string
-fn extract_origin(url: &str) -> Option<String> {
-    // Handle URLs starting with http:// or https://
-    let rest = if url.starts_with("https://") {
-        &url[8..]
-    } else if url.starts_with("http://") {
-        &url[7..]
-    } else {
-        return None;
-    };
-
-    // Find the end of the host (first / or end of string)
-    let host_end = rest.find('/').unwrap_or(rest.len());
-    let host_port = &rest[..host_end];
-
-    // Reconstruct origin
-    let scheme = if url.starts_with("https://") {
-        "https://"
-    } else {
-        "http://"
-    };
+/// Return URL for a login flow, checked against the configured frontend URL
+/// and redirect allowlist.
+fn safe_return_url(config: &Settings, input_url: Option<&str>) -> String {
+    // `validate()` rejects a malformed allowlist at startup, so an error
+    // here can only mean "nothing extra is allowed".
+    let allowed_origins = config.oauth.redirect_origins().unwrap_or_default();
+    validate_return_url(input_url, &config.server.frontend_url, &allowed_origins)
+}
 
-    Some(format!("{}{}", scheme, host_port))
+/// Extract origin (scheme + host + port) from an http(s) URL string
+fn extract_origin(url: &str) -> Option<String> {
+    crate::config::url_origin(url)
 }
 
 /// Validates if the error query parameter is a legitimate OAuth error code.
@@ -388,6 +386,28 @@ fn is_mobile_safari(user_agent: &str) -> bool {
     is_mobile && is_safari
 }
 
+/// Refuse to sign in a user whose password is past its maximum age
+///
+/// Linked accounts that also have a password could otherwise use the
+/// provider to skip the change that password login enforces.
+async fn ensure_password_current(state: &AppState, user: &UserResponse) -> AppResult<()> {
+    let user_id =
+        Uuid::parse_str(&user.id).map_err(|_| AppError::Internal("Invalid user ID".to_string()))?;
+    if password_change_required(state.db(), &state.config().auth, user_id, &user.role).await? {
+        tracing::info!(user_id = %user_id, "[OAuth] Sign-in refused: password expired");
+        return Err(AppError::PasswordExpired);
+    }
+    Ok(())
+}
+
+/// Error code for the login page when processing a sign-in fails
+fn oauth_error_code(error: &AppError) -> &'static str {
+    match error {
+        AppError::PasswordExpired => "password_expired",
+        _ => "oauth_processing_failed",
+    }
+}
+
 /// Generate JWT tokens for a user
 async fn generate_tokens(
     state: &AppState,
@@ -398,16 +418,20 @@ async fn generate_tokens(
     use jsonwebtoken::{encode, EncodingKey, Header};
 
     let now = Utc::now();
-    let access_exp = now.timestamp() + state.jwt_expiration();
+    let auth_config = &state.config().auth;
+    let access_exp = now.timestamp()
+        + auth_config.access_token_expiry_for(role, auth_config.access_token_expiry_secs) as i64;
     let refresh_exp = now.timestamp() + (7 * 24 * 3600); // 7 days
 
-    // Access token claims
+    // Access token claims. Signing in through the provider counts as
+    // entering credentials, so `last_auth_at` is now.
     let access_claims = serde_json::json!({
         "id": user_id,
         "email": email,
         "role": role,
         "iat": now.timestamp(),
         "exp": access_exp,
+        "last_auth_at": now.timestamp(),
     });
 
     let access_token = encode(
@@ -467,7 +491,7 @@ async fn google_oauth_init(
         .and_then(|v| v.to_str().ok())
         .unwrap_or("");
 
-    let return_url = validate_return_url(query.return_url.as_deref(), frontend_url);
+    let return_url = safe_return_url(config, query.return_url.as_deref());
     let is_pwa = query.pwa.as_deref() == Some("true");
     let is_standalone = query.standalone.as_deref() == Some("true");
     let platform = query.platform.clone().unwrap_or_else(|| "web".to_string());
@@ -584,7 +608,7 @@ async fn google_oauth_callback(
         Err(e) => {
             tracing::error!(error = ?e, "[OAuth] Google token exchange failed");
             return build_error_redirect(
-                &validate_return_url(Some(&state_data.return_url), frontend_url),
+                &safe_return_url(config, Some(&state_data.return_url)),
                 "oauth_token_failed",
                 user_agent,
             );
@@ -597,7 +621,7 @@ async fn google_oauth_callback(
         Err(e) => {
             tracing::error!(error = ?e, "[OAuth] Failed to get Google user info");
             return build_error_redirect(
-                &validate_return_url(Some(&state_data.return_url), frontend_url),
+                &safe_return_url(config, Some(&state_data.return_url)),
                 "oauth_profile_failed",
                 user_agent,
             );
@@ -610,8 +634,8 @@ async fn google_oauth_callback(
         Err(e) => {
             tracing::error!(error = ?e, "[OAuth] Google auth processing failed");
             return build_error_redirect(
-                &validate_return_url(Some(&state_data.return_url), frontend_url),
-                "oauth_processing_failed",
+                &safe_return_url(config, Some(&state_data.return_url)),
+                oauth_error_code(&e),
                 user_agent,
             );
         },
@@ -631,7 +655,7 @@ async fn google_oauth_callback(
     // upstream access logs, proxy logs, or Referer headers — it stays
     // confined to the SPA's runtime memory. The frontend handler reads
     // `window.location.hash` instead of `useSearchParams`.
-    let return_url = validate_return_url(Some(&state_data.return_url), frontend_url);
+    let return_url = safe_return_url(config, Some(&state_data.return_url));
     let success_url =
         build_oauth_success_url(&return_url, &result.tokens.access_token, result.is_new_user);
 
@@ -918,6 +942,10 @@ async fn process_google_auth(
         .await
         .map_err(AppError::Database)?;
 
+    // An expired password has to be changed first, whichever way the
+    // user signs in
+    ensure_password_current(state, &user).await?;
+
     // Generate JWT tokens
     let tokens = generate_tokens(state, &user.id, user.email.as_deref(), &user.role).await?;
 
@@ -954,7 +982,7 @@ async fn line_oauth_init(
         .and_then(|v| v.to_str().ok())
         .unwrap_or("");
 
-    let return_url = validate_return_url(query.return_url.as_deref(), frontend_url);
+    let return_url = safe_return_url(config, query.return_url.as_deref());
     let is_pwa = query.pwa.as_deref() == Some("true");
     let is_standalone = query.standalone.as_deref() == Some("true");
     let platform = query.platform.clone().unwrap_or_else(|| "web".to_string());
@@ -1071,7 +1099,7 @@ async fn line_oauth_callback(
         Err(e) => {
             tracing::error!(error = ?e, "[OAuth] LINE token exchange failed");
             return build_error_redirect(
-                &validate_return_url(Some(&state_data.return_url), frontend_url),
+                &safe_return_url(config, Some(&state_data.return_url)),
                 "oauth_token_failed",
                 user_agent,
             );
@@ -1084,7 +1112,7 @@ async fn line_oauth_callback(
         Err(e) => {
             tracing::error!(error = ?e, "[OAuth] Failed to get LINE profile");
             return build_error_redirect(
-                &validate_return_url(Some(&state_data.return_url), frontend_url),
+                &safe_return_url(config, Some(&state_data.return_url)),
                 "oauth_profile_failed",
                 user_agent,
             );
@@ -1097,8 +1125,8 @@ async fn line_oauth_callback(
         Err(e) => {
             tracing::error!(error = ?e, "[OAuth] LINE auth processing failed");
             return build_error_redirect(
-                &validate_return_url(Some(&state_data.return_url), frontend_url),
-                "oauth_processing_failed",
+                &safe_return_url(config, Some(&state_data.return_url)),
+                oauth_error_code(&e),
                 user_agent,
             );
         },
@@ -1110,7 +1138,7 @@ async fn line_oauth_callback(
     // Phase 3 + LOW-2: same fragment-based handoff as the Google
     // callback — see the comment in `google_oauth_callback` for the full
     // rationale. The refresh token lives only in the HttpOnly cookie.
-    let return_url = validate_return_url(Some(&state_data.return_url), frontend_url);
+    let return_url = safe_return_url(config, Some(&state_data.return_url));
     let success_url =
         build_oauth_success_url(&return_url, &result.tokens.access_token, result.is_new_user);
 
@@ -1344,6 +1372,10 @@ async fn process_line_auth(state: &AppState, profile: LineProfile) -> AppResult<
         .await
         .map_err(AppError::Database)?;
 
+    // An expired password has to be changed first, whichever way the
+    // user signs in
+    ensure_password_current(state, &user).await?;
+
     // Generate JWT tokens
     let tokens = generate_tokens(state, &user.id, user.email.as_deref(), &user.role).await?;
 
@@ -1526,34 +1558,8 @@ async fn create_default_notification_preferences(
 }
 
 /// Ensure user is enrolled in loyalty program
-async fn ensure_loyalty_enrollment(db: &sqlx::PgPool, user_id: Uuid) -> Result<(), sqlx::Error> {
-    // Check if already enrolled
-    let existing: Option<(i32,)> = sqlx::query_as("SELECT 1 FROM user_loyalty WHERE user_id = $1")
-        .bind(user_id)
-        .fetch_optional(db)
-        .await?;
-
-    if existing.is_none() {
-        // Get default tier (Bronze)
-        let tier: Option<(i32,)> =
-            sqlx::query_as("SELECT id FROM tiers WHERE name = 'Bronze' LIMIT 1")
-                .fetch_optional(db)
-                .await?;
-
-        let tier_id = tier.map(|t| t.0).unwrap_or(1);
-
-        sqlx::query(
-            r#"INSERT INTO user_loyalty (user_id, tier_id, current_points, total_nights)
-               VALUES ($1, $2, 0, 0)
-               ON CONFLICT (user_id) DO NOTHING"#,
-        )
-        .bind(user_id)
-        .bind(tier_id)
-        .execute(db)
-        .await?;
-    }
-
-    Ok(())
+async fn ensure_loyalty_enrollment(db: &sqlx::PgPool, user_id: Uuid) -> Result<(), AppError> {
+    crate::services::loyalty::ensure_user_loyalty(db, user_id).await
 }
 
 /// Append the refresh-token HttpOnly cookie to an OAuth redirect response.
@@ -1658,28 +1664,60 @@ mod tests {
     #[test]
     fn test_validate_return_url_valid_same_origin() {
         let frontend = "https://app.example.com";
-        let result = validate_return_url(Some("https://app.example.com/dashboard"), frontend);
+        let result = validate_return_url(Some("https://app.example.com/dashboard"), frontend, &[]);
         assert_eq!(result, "https://app.example.com/dashboard");
     }
 
     #[test]
     fn test_validate_return_url_blocks_different_origin() {
         let frontend = "https://app.example.com";
-        let result = validate_return_url(Some("https://evil.com/phishing"), frontend);
+        let result = validate_return_url(Some("https://evil.com/phishing"), frontend, &[]);
         assert_eq!(result, "https://app.example.com");
     }
 
     #[test]
     fn test_validate_return_url_handles_none() {
         let frontend = "https://app.example.com";
-        let result = validate_return_url(None, frontend);
+        let result = validate_return_url(None, frontend, &[]);
         assert_eq!(result, "https://app.example.com");
     }
 
     #[test]
     fn test_validate_return_url_handles_invalid_url() {
         let frontend = "https://app.example.com";
-        let result = validate_return_url(Some("not-a-valid-url"), frontend);
+        let result = validate_return_url(Some("not-a-valid-url"), frontend, &[]);
+        assert_eq!(result, "https://app.example.com");
+    }
+
+    #[test]
+    fn test_validate_return_url_allows_listed_origin() {
+        let frontend = "https://app.example.com";
+        let allowed = vec!["https://m.example.com".to_string()];
+        let result = validate_return_url(
+            Some("https://M.example.com:443/profile"),
+            frontend,
+            &allowed,
+        );
+        assert_eq!(result, "https://m.example.com/profile");
+
+        let result = validate_return_url(Some("https://other.example.com/"), frontend, &allowed);
+        assert_eq!(result, "https://app.example.com");
+    }
+
+    #[test]
+    fn test_validate_return_url_blocks_userinfo_tricks() {
+        let frontend = "https://app.example.com";
+        let result = validate_return_url(Some("https://app.example.com@evil.com/"), frontend, &[]);
+        assert_eq!(result, "https://app.example.com");
+
+        let result = validate_return_url(
+            Some("https://user:pw@app.example.com/dashboard"),
+            frontend,
+            &[],
+        );
+        assert_eq!(result, "https://app.example.com");
+
+        let result = validate_return_url(Some("//evil.com/dashboard"), frontend, &[]);
         assert_eq!(result, "https://app.example.com");
     }
 
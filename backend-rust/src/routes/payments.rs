@@ -8,6 +8,7 @@ use axum::{
     routing::get,
     Json, Router,
 };
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -15,6 +16,7 @@ use crate::error::{AppError, AppResult};
 use crate::middleware::auth::{auth_middleware, AuthUser};
 use crate::services::promptpay::PromptPayService;
 use crate::state::AppState;
+use crate::utils::format_money;
 
 // ==================== REQUEST/RESPONSE TYPES ====================
 
@@ -28,6 +30,8 @@ pub struct QrCodeQuery {
 pub struct QrCodeResponse {
     pub svg: String,
     pub amount: f64,
+    /// `amount` formatted for display, e.g. `฿1,500.00`
+    pub amount_display: String,
     pub currency: String,
 }
 
@@ -75,9 +79,14 @@ async fn generate_promptpay_qr(
         "PromptPay QR generated"
     );
 
+    let amount_display = Decimal::try_from(params.amount)
+        .map(|amount| format_money(amount, "THB"))
+        .unwrap_or_default();
+
     Ok(Json(QrCodeResponse {
         svg,
         amount: params.amount,
+        amount_display,
         currency: "THB".to_string(),
     }))
 }
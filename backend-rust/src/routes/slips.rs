@@ -58,6 +58,20 @@ impl SlipStorageConfig {
     }
 }
 
+/// Resolve a `/storage/slips/<file>` URL (as returned by `POST /upload`)
+/// to the file on disk.
+///
+/// Returns `None` for anything that isn't a bare filename under the slips
+/// prefix, so a crafted URL can't point outside the slips directory.
+pub(crate) fn slip_file_path(slip_url: &str) -> Option<PathBuf> {
+    let filename = slip_url.strip_prefix("/storage/slips/")?;
+    if filename.is_empty() || filename.contains('/') || filename.contains("..") {
+        return None;
+    }
+
+    Some(SlipStorageConfig::default().get_slips_path().join(filename))
+}
+
 // ============================================================================
 // Response Types
 // ============================================================================
@@ -310,6 +324,17 @@ mod tests {
         assert_eq!(get_extension_from_mime("unknown/type"), ".bin");
     }
 
+    #[test]
+    fn test_slip_file_path() {
+        let path = slip_file_path("/storage/slips/abc.jpg").unwrap();
+        assert!(path.ends_with("slips/abc.jpg"));
+
+        assert!(slip_file_path("/storage/slips/").is_none());
+        assert!(slip_file_path("/storage/slips/../secret").is_none());
+        assert!(slip_file_path("/storage/slips/a/b.jpg").is_none());
+        assert!(slip_file_path("https://attacker.com/slip.png").is_none());
+    }
+
     #[test]
     fn test_slip_storage_config_default() {
         let config = SlipStorageConfig::default();
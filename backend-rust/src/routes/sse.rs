@@ -21,7 +21,7 @@ use tokio_stream::wrappers::BroadcastStream;
 
 use crate::error::ErrorResponse;
 use crate::middleware::auth::{auth_middleware, AuthUser};
-use crate::services::sse::{get_sse_service, SseEvent};
+use crate::services::sse::{get_sse_service, SseEvent, SseEventFilter, SseEventType};
 use crate::state::AppState;
 
 /// Query parameters for SSE connection
@@ -29,6 +29,19 @@ use crate::state::AppState;
 pub struct SseQuery {
     /// Optional JWT token for EventSource (which can't set headers)
     pub token: Option<String>,
+    /// Optional comma-separated event types to receive (e.g.
+    /// `notification,loyalty_update`); empty or absent means all
+    pub types: Option<String>,
+}
+
+impl SseQuery {
+    /// The event filter requested by `types`
+    fn event_filter(&self) -> SseEventFilter {
+        self.types
+            .as_deref()
+            .map(SseEventFilter::parse)
+            .unwrap_or_default()
+    }
 }
 
 /// SSE event handler - establishes SSE connection for authenticated users
@@ -43,17 +56,22 @@ pub struct SseQuery {
 /// 1. Authorization header (Bearer token)
 /// 2. Query parameter `token` (for EventSource which can't set headers)
 ///
+/// The `types` query parameter limits the connection to the listed event
+/// types; unknown names are ignored and the `connected` event is always
+/// sent.
+///
 /// # Response
 /// Returns a stream of SSE events. The connection is kept alive with
 /// periodic heartbeat messages every 30 seconds.
 async fn sse_events_handler(
     Extension(user): Extension<AuthUser>,
+    filter: SseEventFilter,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
     let user_id = user.id.clone();
     let sse_service = get_sse_service();
 
     // Add client and get receiver
-    let (client_id, receiver) = sse_service.add_client(&user_id).await;
+    let (client_id, receiver) = sse_service.add_client_with_filter(&user_id, filter).await;
 
     tracing::info!(
         user_id = %user_id,
@@ -166,10 +184,12 @@ async fn _sse_events_with_query(
     Query(query): Query<SseQuery>,
     auth_result: Result<Extension<AuthUser>, Response>,
 ) -> Response {
+    let filter = query.event_filter();
+
     // If we have a valid AuthUser from middleware, use it
     match auth_result {
         Ok(Extension(user)) => {
-            let sse = sse_events_handler(Extension(user)).await;
+            let sse = sse_events_handler(Extension(user), filter).await;
             sse.into_response()
         },
         Err(_) => {
@@ -180,7 +200,7 @@ async fn _sse_events_with_query(
                 // Validate token manually
                 match validate_token_from_query(&token, &jwt_secret) {
                     Ok(user) => {
-                        let sse = sse_events_handler(Extension(user)).await;
+                        let sse = sse_events_handler(Extension(user), filter).await;
                         sse.into_response()
                     },
                     Err(response) => response,
@@ -233,9 +253,11 @@ async fn sse_handler_with_fallback(
             .unwrap_or_else(|_| "development-secret-change-in-production".to_string())
     });
 
+    let filter = query.event_filter();
+
     match auth_user {
         Some(Extension(user)) => {
-            let sse = sse_events_handler(Extension(user)).await;
+            let sse = sse_events_handler(Extension(user), filter).await;
             sse.into_response()
         },
         None => {
@@ -243,7 +265,7 @@ async fn sse_handler_with_fallback(
             if let Some(token) = query.token {
                 match validate_token_from_query(&token, &jwt_secret) {
                     Ok(user) => {
-                        let sse = sse_events_handler(Extension(user)).await;
+                        let sse = sse_events_handler(Extension(user), filter).await;
                         sse.into_response()
                     },
                     Err(response) => response,
@@ -268,13 +290,7 @@ async fn sse_info_handler(Extension(user): Extension<AuthUser>) -> Json<serde_js
     Json(serde_json::json!({
         "userId": user.id,
         "connectedClients": client_count,
-        "supportedEvents": [
-            "notification",
-            "loyalty_update",
-            "coupon_assigned",
-            "connected",
-            "heartbeat"
-        ]
+        "supportedEvents": SseEventType::ALL.map(|t| t.to_string())
     }))
 }
 
@@ -313,6 +329,10 @@ mod tests {
 
         let query: SseQuery = serde_json::from_str(r#"{}"#).unwrap();
         assert!(query.token.is_none());
+        assert_eq!(query.event_filter(), SseEventFilter::default());
+
+        let query: SseQuery = serde_json::from_str(r#"{"types": "notification,unknown"}"#).unwrap();
+        assert_eq!(query.event_filter(), SseEventFilter::parse("notification"));
     }
 
     #[test]
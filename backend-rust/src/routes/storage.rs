@@ -6,6 +6,11 @@
 //! - POST /api/storage/upload - General file upload (authenticated)
 //! - POST /api/storage/avatar - Avatar upload (authenticated; user derived from JWT)
 //! - POST /api/storage/slip - Slip upload (authenticated)
+//! - POST /api/storage/uploads - Start a chunked upload (authenticated)
+//! - GET /api/storage/uploads/:upload_id - Chunked upload progress (authenticated)
+//! - PUT /api/storage/uploads/:upload_id/chunks/:index - Upload one chunk (authenticated)
+//! - POST /api/storage/uploads/:upload_id/complete - Assemble a chunked upload (authenticated)
+//! - DELETE /api/storage/uploads/:upload_id - Abort a chunked upload (authenticated)
 //! - GET /api/storage/files/:filename - Serve uploaded files (public)
 //! - GET /api/storage/avatars/:filename - Serve avatar images (public)
 //! - GET /api/storage/slips/:filename - Serve slip images (public)
@@ -24,7 +29,7 @@ use axum::{
 };
 use bytes::Bytes;
 use redis::AsyncCommands;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::fs::File;
 use tokio_util::io::ReaderStream;
@@ -33,7 +38,9 @@ use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
 use crate::middleware::auth::{auth_middleware, has_role, require_role, AuthUser};
-use crate::services::storage::{StorageReport, StorageService};
+use crate::services::storage::{
+    ChunkedUpload, ChunkedUploadProgress, StorageReport, StorageService, UploadKind,
+};
 use crate::state::AppState;
 
 /// State for storage routes
@@ -97,6 +104,42 @@ pub struct SlipUploadResponse {
     pub url: String,
 }
 
+/// Request to start a chunked upload
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitChunkedUploadRequest {
+    pub kind: UploadKind,
+    pub filename: String,
+    pub content_type: String,
+    pub total_size: u64,
+    pub chunk_size: u64,
+}
+
+/// Response for a started chunked upload
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkedUploadResponse {
+    pub upload_id: Uuid,
+    pub kind: UploadKind,
+    pub total_size: u64,
+    pub chunk_size: u64,
+    pub total_chunks: u32,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ChunkedUpload> for ChunkedUploadResponse {
+    fn from(upload: ChunkedUpload) -> Self {
+        Self {
+            upload_id: upload.upload_id,
+            kind: upload.kind,
+            total_size: upload.total_size,
+            chunk_size: upload.chunk_size,
+            total_chunks: upload.total_chunks,
+            expires_at: upload.expires_at,
+        }
+    }
+}
+
 /// Response for backup trigger
 #[derive(Debug, Serialize)]
 pub struct BackupResponse {
@@ -280,6 +323,106 @@ async fn upload_slip(
     Ok(Json(SlipUploadResponse { url }))
 }
 
+/// Start a chunked upload
+///
+/// POST /storage/uploads
+/// Authentication: required (Bearer token)
+///
+/// Declares the file up front (`kind` is `file` or `slip`); its type and
+/// size are checked against the direct-upload limits before any data is
+/// sent. Returns 429 when the user already has the maximum number of
+/// unfinished uploads.
+async fn init_chunked_upload(
+    State(state): State<StorageState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(payload): Json<InitChunkedUploadRequest>,
+) -> AppResult<(StatusCode, Json<ChunkedUploadResponse>)> {
+    let upload = state
+        .storage
+        .init_chunked_upload(
+            &auth_user.id,
+            payload.kind,
+            &payload.filename,
+            &payload.content_type,
+            payload.total_size,
+            payload.chunk_size,
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(upload.into())))
+}
+
+/// Get chunked upload progress, including the chunks still missing
+///
+/// GET /storage/uploads/:upload_id
+async fn get_chunked_upload(
+    State(state): State<StorageState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(upload_id): Path<Uuid>,
+) -> AppResult<Json<ChunkedUploadProgress>> {
+    let progress = state
+        .storage
+        .chunked_upload_status(&auth_user.id, upload_id)
+        .await?;
+    Ok(Json(progress))
+}
+
+/// Upload one chunk
+///
+/// PUT /storage/uploads/:upload_id/chunks/:index
+/// Content-Type: application/octet-stream (raw chunk bytes)
+///
+/// Chunks may arrive in any order and may be re-sent.
+async fn upload_chunk(
+    State(state): State<StorageState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path((upload_id, index)): Path<(Uuid, u32)>,
+    body: Bytes,
+) -> AppResult<Json<ChunkedUploadProgress>> {
+    let progress = state
+        .storage
+        .upload_chunk(&auth_user.id, upload_id, index, body)
+        .await?;
+    Ok(Json(progress))
+}
+
+/// Assemble a chunked upload once every chunk has arrived
+///
+/// POST /storage/uploads/:upload_id/complete
+///
+/// The assembled file goes through the same validation as a direct upload.
+async fn complete_chunked_upload(
+    State(state): State<StorageState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(upload_id): Path<Uuid>,
+) -> AppResult<Json<UploadResponse>> {
+    let url = state
+        .storage
+        .complete_chunked_upload(&auth_user.id, upload_id)
+        .await?;
+
+    Ok(Json(UploadResponse {
+        success: true,
+        url,
+        message: "File uploaded successfully".to_string(),
+    }))
+}
+
+/// Abort a chunked upload, discarding its chunks
+///
+/// DELETE /storage/uploads/:upload_id
+async fn abort_chunked_upload(
+    State(state): State<StorageState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(upload_id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    state
+        .storage
+        .abort_chunked_upload(&auth_user.id, upload_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 /// Serve uploaded files
 ///
 /// GET /storage/files/:filename
@@ -543,6 +686,19 @@ pub fn routes() -> Router<StorageState> {
         .route("/upload", post(upload_file))
         .route("/avatar", post(upload_avatar))
         .route("/slip", post(upload_slip))
+        .route("/uploads", post(init_chunked_upload))
+        .route(
+            "/uploads/:upload_id",
+            get(get_chunked_upload).delete(abort_chunked_upload),
+        )
+        .route(
+            "/uploads/:upload_id/chunks/:index",
+            axum::routing::put(upload_chunk),
+        )
+        .route(
+            "/uploads/:upload_id/complete",
+            post(complete_chunked_upload),
+        )
         .layer(middleware::from_fn(auth_middleware));
 
     // Admin routes - require authentication + admin role
@@ -2,6 +2,10 @@
 //!
 //! Provides endpoints for survey management including listing surveys,
 //! viewing details, submitting responses, and administrative functions.
+//! Invitation links (`/invite/:token`) are public so guests without an
+//! account can respond.
+
+use std::collections::HashMap;
 
 use axum::{
     extract::{Extension, Path, Query, State},
@@ -10,17 +14,27 @@ use axum::{
     routing::{delete, get, post, put},
     Json, Router,
 };
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool};
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::middleware::auth::{auth_middleware, has_role, AuthUser};
+use crate::middleware::auth::{auth_middleware, has_role, require_recent_auth, AuthUser};
 use crate::models::survey::{
     CreateSurveyRequest, SurveyAnswerDto, SurveyResponseDto, UpdateSurveyRequest,
 };
+use crate::services::email::{email_service_for, templates as email_templates};
+use crate::services::notification::{
+    email_recipients, spawn_notification_emails, NotificationEmail,
+};
+use crate::services::survey::{
+    generate_invitation_token, invitation_link, invitation_token_hash, load_member_segment,
+    lock_link_invitation, validate_survey_answers, validate_targeting_tiers, MemberSegment,
+    SurveyTargeting, SURVEY_INVITATION_DEFAULT_TTL_DAYS, SURVEY_INVITATION_MAX_TTL_DAYS,
+};
 use crate::state::AppState;
+use crate::utils::validation::validate_email;
 
 /// Pagination query parameters for survey listing
 #[derive(Debug, Deserialize)]
@@ -79,6 +93,57 @@ pub struct SubmitResponseResponse {
     pub message: String,
 }
 
+/// Most recipients one `POST /:id/invitations/send` may address
+const SURVEY_INVITATION_MAX_RECIPIENTS: usize = 1000;
+
+/// Request body for sending invitation links
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendSurveyInvitationsRequest {
+    /// Members to invite; the link goes to their account email
+    #[serde(default)]
+    pub user_ids: Vec<Uuid>,
+    /// Guests without an account to invite
+    #[serde(default)]
+    pub emails: Vec<String>,
+    /// How long the links stay valid (default 14, at most 90)
+    pub expires_in_days: Option<u32>,
+}
+
+/// One invitation link that was sent
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SentSurveyInvitation {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub email: Option<String>,
+    /// The single-use link, for sharing through another channel
+    pub link: String,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+/// Result of sending invitation links
+#[derive(Debug, Serialize)]
+pub struct SendSurveyInvitationsResponse {
+    pub sent: usize,
+    pub invitations: Vec<SentSurveyInvitation>,
+}
+
+/// A survey opened through an invitation link
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvitedSurveyResponse {
+    pub survey: SurveyResponseDto,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+/// Request body for submitting through an invitation link. A link is
+/// single-use, so the submission is always final.
+#[derive(Debug, Deserialize)]
+pub struct SubmitInvitationResponseRequest {
+    pub answers: serde_json::Value,
+}
+
 /// Success response for mutations
 #[derive(Debug, Serialize)]
 pub struct SuccessResponse {
@@ -137,6 +202,7 @@ impl From<SurveyRow> for SurveyResponseDto {
             scheduled_start: row.scheduled_start,
             scheduled_end: row.scheduled_end,
             access_type: row.access_type,
+            target_segment: row.target_segment,
             created_at: row.created_at,
         }
     }
@@ -187,16 +253,49 @@ impl From<SurveyResponseRow> for SurveyAnswerDto {
 // Database Operations
 // ============================================================================
 
+/// List surveys for `member`, or every survey when `member` is `None`
+/// (admins).
 async fn query_surveys(
     db: &PgPool,
-    is_admin: bool,
+    member: Option<&MemberSegment>,
     status_filter: Option<&str>,
     page: i32,
     limit: i32,
 ) -> Result<(Vec<SurveyResponseDto>, i64), AppError> {
     let offset = (page - 1) * limit;
 
-    let (surveys, total) = if is_admin {
+    let (surveys, total) = if let Some(member) = member {
+        // Members only see active public surveys they're targeted by.
+        // Targeting lives in JSON and is evaluated in Rust, so filter the
+        // (small) set of active surveys here and paginate afterwards.
+        let rows: Vec<SurveyRow> = sqlx::query_as(
+            r#"
+            SELECT id, title, description, questions, target_segment, status,
+                   scheduled_start, scheduled_end, created_by, created_at, updated_at, access_type
+            FROM surveys
+            WHERE status = 'active' AND access_type = 'public'
+            ORDER BY created_at DESC
+            "#,
+        )
+        .fetch_all(db)
+        .await?;
+
+        let today = Utc::now().date_naive();
+        let eligible: Vec<SurveyRow> = rows
+            .into_iter()
+            .filter(|row| {
+                SurveyTargeting::from_stored(row.target_segment.as_ref()).matches(member, today)
+            })
+            .collect();
+        let total = eligible.len() as i64;
+        let page_rows = eligible
+            .into_iter()
+            .skip(offset.max(0) as usize)
+            .take(limit.max(0) as usize)
+            .collect();
+
+        (page_rows, total)
+    } else {
         // Admin can see all surveys
         if let Some(status) = status_filter {
             let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM surveys WHERE status = $1")
@@ -242,30 +341,6 @@ async fn query_surveys(
 
             (rows, total.0)
         }
-    } else {
-        // Regular users only see active public surveys
-        let total: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM surveys WHERE status = 'active' AND access_type = 'public'",
-        )
-        .fetch_one(db)
-        .await?;
-
-        let rows: Vec<SurveyRow> = sqlx::query_as(
-            r#"
-            SELECT id, title, description, questions, target_segment, status,
-                   scheduled_start, scheduled_end, created_by, created_at, updated_at, access_type
-            FROM surveys
-            WHERE status = 'active' AND access_type = 'public'
-            ORDER BY created_at DESC
-            LIMIT $1 OFFSET $2
-            "#,
-        )
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(db)
-        .await?;
-
-        (rows, total.0)
     };
 
     let responses: Vec<SurveyResponseDto> =
@@ -306,8 +381,17 @@ async fn can_user_access_survey(
     .await?;
 
     if let Some(s) = survey {
+        // Public surveys are open to the members they target; anyone else
+        // (e.g. following a shared direct link) needs an invitation.
         if s.access_type == "public" && s.status.as_deref() == Some("active") {
-            return Ok(true);
+            let targeting = SurveyTargeting::from_stored(s.target_segment.as_ref());
+            if targeting == SurveyTargeting::default() {
+                return Ok(true);
+            }
+            let member = load_member_segment(db, user_id).await?;
+            if targeting.matches(&member, Utc::now().date_naive()) {
+                return Ok(true);
+            }
         }
 
         // Check if user has an invitation
@@ -557,8 +641,9 @@ async fn query_survey_responses(
 ///
 /// GET /api/surveys
 ///
-/// Returns a paginated list of surveys. Regular users only see active surveys,
-/// while admins can see all surveys including drafts and archived ones.
+/// Returns a paginated list of surveys. Regular users only see active public
+/// surveys whose targeting (tier, nights, last stay) they meet, while admins
+/// can see all surveys including drafts, archived and targeted ones.
 ///
 /// Query parameters:
 /// - page: Page number (default: 1)
@@ -573,16 +658,21 @@ async fn list_surveys(
     let limit = params.limit.unwrap_or(10).clamp(1, 100);
     let is_admin = has_role(&user, "admin");
 
-    // For admin, allow status filter; for users, always filter active
-    let status_filter = if is_admin {
-        params
+    // Admins see every survey, regardless of targeting, and may filter by
+    // status; members only see active surveys they're targeted by.
+    let (member, status_filter) = if is_admin {
+        let status_filter = params
             .active
-            .map(|active| if active { "active" } else { "draft" })
+            .map(|active| if active { "active" } else { "draft" });
+        (None, status_filter)
     } else {
-        Some("active")
+        let user_id = Uuid::parse_str(&user.id)
+            .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
+        (Some(load_member_segment(state.db(), user_id).await?), None)
     };
 
-    let (surveys, total) = query_surveys(state.db(), is_admin, status_filter, page, limit).await?;
+    let (surveys, total) =
+        query_surveys(state.db(), member.as_ref(), status_filter, page, limit).await?;
     let total_pages = ((total as f64) / (limit as f64)).ceil() as i32;
 
     Ok(Json(PaginatedResponse {
@@ -655,6 +745,10 @@ async fn get_user_invitations(
 /// Request body:
 /// - answers: JSON object with question_id -> answer mappings
 /// - is_completed: Whether this is a final submission (default: true)
+///
+/// Answers are checked against each question's rules; a final submission
+/// must also answer every required question. Failures are a 400 with
+/// `details` keyed by question id.
 async fn submit_response(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
@@ -681,6 +775,8 @@ async fn submit_response(
         ));
     }
 
+    validate_survey_answers(&survey.questions, &payload.answers, payload.is_completed)?;
+
     // Submit or update response
     let response = insert_or_update_response(
         state.db(),
@@ -741,6 +837,8 @@ async fn create_survey(
             "At least one question is required".to_string(),
         ));
     }
+    let targeting = SurveyTargeting::parse(payload.target_segment.as_ref())?;
+    validate_targeting_tiers(state.db(), &targeting).await?;
 
     let survey = insert_survey(state.db(), &payload, created_by).await?;
 
@@ -766,6 +864,11 @@ async fn update_survey(
         ));
     }
 
+    if payload.target_segment.is_some() {
+        let targeting = SurveyTargeting::parse(payload.target_segment.as_ref())?;
+        validate_targeting_tiers(state.db(), &targeting).await?;
+    }
+
     let survey = update_survey_in_db(state.db(), survey_id, &payload)
         .await?
         .ok_or_else(|| AppError::NotFound("Survey not found".to_string()))?;
@@ -831,6 +934,7 @@ async fn delete_survey(
             "Admin access required to delete surveys".to_string(),
         ));
     }
+    require_recent_auth(&user, state.config().auth.reauth_max_age_secs)?;
 
     let deleted = soft_delete_survey(state.db(), survey_id).await?;
 
@@ -959,18 +1063,286 @@ async fn get_survey_invitations(
 ///
 /// POST /api/surveys/:surveyId/invitations/send
 ///
-/// Sends invitations to users based on target segment criteria.
+/// Emails each member in `userIds` and each guest in `emails` a
+/// single-use link to the survey that expires after `expiresInDays`.
+/// Members who switched off email still get the in-app invitation, and
+/// inviting a member again replaces their earlier link. The links are
+/// also returned so they can be shared another way.
 async fn send_survey_invitations(
+    State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
     Path(survey_id): Path<Uuid>,
-) -> Result<(StatusCode, Json<NotImplementedResponse>), AppError> {
+    Json(payload): Json<SendSurveyInvitationsRequest>,
+) -> Result<(StatusCode, Json<SendSurveyInvitationsResponse>), AppError> {
     if !has_role(&user, "admin") {
         return Err(AppError::Forbidden(
             "Admin access required to send invitations".to_string(),
         ));
     }
-    let _survey_id = survey_id;
-    Ok(not_implemented_response())
+
+    let expires_in_days = payload
+        .expires_in_days
+        .unwrap_or(SURVEY_INVITATION_DEFAULT_TTL_DAYS);
+    if !(1..=SURVEY_INVITATION_MAX_TTL_DAYS).contains(&expires_in_days) {
+        return Err(AppError::Validation(format!(
+            "expiresInDays must be between 1 and {}",
+            SURVEY_INVITATION_MAX_TTL_DAYS
+        )));
+    }
+
+    let mut user_ids = payload.user_ids;
+    user_ids.sort();
+    user_ids.dedup();
+    let mut emails: Vec<String> = payload
+        .emails
+        .iter()
+        .map(|email| email.trim().to_lowercase())
+        .collect();
+    emails.sort();
+    emails.dedup();
+    let recipient_count = user_ids.len() + emails.len();
+    if recipient_count == 0 {
+        return Err(AppError::Validation(
+            "At least one of userIds or emails is required".to_string(),
+        ));
+    }
+    if recipient_count > SURVEY_INVITATION_MAX_RECIPIENTS {
+        return Err(AppError::Validation(format!(
+            "At most {} recipients per request",
+            SURVEY_INVITATION_MAX_RECIPIENTS
+        )));
+    }
+    if let Some(invalid) = emails.iter().find(|email| !validate_email(email)) {
+        return Err(AppError::Validation(format!(
+            "Invalid email address: {}",
+            invalid
+        )));
+    }
+
+    let survey = query_survey_by_id(state.db(), survey_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Survey not found".to_string()))?;
+    if survey.status.as_deref() != Some("active") {
+        return Err(AppError::BadRequest("Survey is not active".to_string()));
+    }
+
+    let known: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE id = ANY($1)")
+        .bind(&user_ids)
+        .fetch_one(state.db())
+        .await?;
+    if known != user_ids.len() as i64 {
+        return Err(AppError::Validation(
+            "userIds references an unknown user".to_string(),
+        ));
+    }
+
+    let recipients = user_ids
+        .iter()
+        .map(|&id| (Some(id), None))
+        .chain(emails.into_iter().map(|email| (None, Some(email))));
+
+    let frontend_url = &state.config().server.frontend_url;
+    let mut tx = state.db().begin().await?;
+    let mut invitations = Vec::with_capacity(recipient_count);
+    for (user_id, email) in recipients {
+        let token = generate_invitation_token();
+        let (id, expires_at): (Uuid, Option<NaiveDateTime>) = sqlx::query_as(
+            r#"
+            INSERT INTO survey_invitations
+                (survey_id, user_id, guest_email, token_hash, status, sent_at, expires_at)
+            VALUES ($1, $2, $3, $4, 'pending', NOW(), NOW() + make_interval(days => $5))
+            ON CONFLICT (survey_id, user_id)
+            DO UPDATE SET
+                token_hash = EXCLUDED.token_hash,
+                status = 'pending',
+                sent_at = EXCLUDED.sent_at,
+                viewed_at = NULL,
+                used_at = NULL,
+                expires_at = EXCLUDED.expires_at,
+                updated_at = NOW()
+            RETURNING id, expires_at
+            "#,
+        )
+        .bind(survey_id)
+        .bind(user_id)
+        .bind(&email)
+        .bind(invitation_token_hash(&token))
+        .bind(expires_in_days as i32)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        invitations.push(SentSurveyInvitation {
+            id,
+            user_id,
+            email,
+            link: invitation_link(frontend_url, &token),
+            expires_at,
+        });
+    }
+    tx.commit().await?;
+
+    // Members get the link at their account email unless they opted out
+    let member_emails: HashMap<Uuid, String> = email_recipients(state.db(), &user_ids)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Failed to load survey invitation email recipients");
+            Vec::new()
+        })
+        .into_iter()
+        .collect();
+    let subject = format!("You're invited: {}", survey.title);
+    let emails = invitations
+        .iter()
+        .filter_map(|invitation| {
+            let to = match invitation.user_id {
+                Some(user_id) => member_emails.get(&user_id).cloned(),
+                None => invitation.email.clone(),
+            }?;
+            Some(NotificationEmail {
+                to,
+                subject: subject.clone(),
+                html_body: email_templates::survey_invitation_template(
+                    &survey.title,
+                    &invitation.link,
+                    expires_in_days,
+                ),
+            })
+        })
+        .collect();
+    spawn_notification_emails(email_service_for(state.config(), state.db()), emails);
+
+    Ok((
+        StatusCode::CREATED,
+        Json(SendSurveyInvitationsResponse {
+            sent: invitations.len(),
+            invitations,
+        }),
+    ))
+}
+
+// ============================================================================
+// Invitation Link Routes (public)
+// ============================================================================
+
+/// Get the survey behind an invitation link
+///
+/// GET /api/surveys/invite/:token
+///
+/// No login needed: the token is the credential. Records when the link
+/// was first opened. A used link is a 409, an expired one a 400.
+async fn get_invited_survey(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Json<InvitedSurveyResponse>, AppError> {
+    let mut tx = state.db().begin().await?;
+    let invitation = lock_link_invitation(&mut tx, &token).await?;
+
+    let survey = query_survey_by_id(state.db(), invitation.survey_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Survey not found".to_string()))?;
+    if survey.status.as_deref() != Some("active") {
+        return Err(AppError::BadRequest("Survey is not active".to_string()));
+    }
+
+    sqlx::query(
+        "UPDATE survey_invitations SET viewed_at = COALESCE(viewed_at, NOW()) WHERE id = $1",
+    )
+    .bind(invitation.id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(InvitedSurveyResponse {
+        survey,
+        expires_at: invitation.expires_at,
+    }))
+}
+
+/// Submit a response through an invitation link
+///
+/// POST /api/surveys/invite/:token/submit
+///
+/// Records a completed response (against the invited member, if the
+/// invitation is for one) and marks the invitation used, so the link
+/// can't be submitted again. Answers are validated as in
+/// `POST /:id/responses`.
+async fn submit_invited_response(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    Json(payload): Json<SubmitInvitationResponseRequest>,
+) -> Result<(StatusCode, Json<SubmitResponseResponse>), AppError> {
+    let mut tx = state.db().begin().await?;
+    let invitation = lock_link_invitation(&mut tx, &token).await?;
+
+    let survey = query_survey_by_id(state.db(), invitation.survey_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Survey not found".to_string()))?;
+    if survey.status.as_deref() != Some("active") {
+        return Err(AppError::BadRequest("Survey is not active".to_string()));
+    }
+    validate_survey_answers(&survey.questions, &payload.answers, true)?;
+
+    // A guest response has no user_id, so it never hits the conflict
+    let response_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO survey_responses
+            (survey_id, user_id, invitation_id, answers, is_completed, progress, completed_at)
+        VALUES ($1, $2, $3, $4, true, 100, NOW())
+        ON CONFLICT (survey_id, user_id)
+        DO UPDATE SET
+            answers = EXCLUDED.answers,
+            invitation_id = EXCLUDED.invitation_id,
+            is_completed = true,
+            progress = 100,
+            completed_at = COALESCE(survey_responses.completed_at, EXCLUDED.completed_at),
+            updated_at = NOW()
+        RETURNING id
+        "#,
+    )
+    .bind(invitation.survey_id)
+    .bind(invitation.user_id)
+    .bind(invitation.id)
+    .bind(&payload.answers)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE survey_invitations
+        SET status = 'completed', used_at = NOW(), updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(invitation.id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    // Completion coupons go to members only; as with in-app submission a
+    // failure here doesn't undo the response
+    if invitation.user_id.is_some() {
+        if let Err(e) = sqlx::query("SELECT award_survey_completion_coupons($1)")
+            .bind(response_id)
+            .execute(state.db())
+            .await
+        {
+            tracing::warn!(
+                error = ?e,
+                response_id = %response_id,
+                "Failed to award survey completion coupons; non-fatal",
+            );
+        }
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(SubmitResponseResponse {
+            id: response_id,
+            survey_id: invitation.survey_id,
+            is_completed: true,
+            message: "Survey response submitted successfully".to_string(),
+        }),
+    ))
 }
 
 // ============================================================================
@@ -1037,14 +1409,19 @@ async fn resend_invitation_stub(
 
 /// Create survey routes with authentication middleware
 ///
-/// All survey routes require authentication. Admin-specific routes
-/// perform additional role checks in the handlers.
+/// All survey routes except the invitation links require authentication.
+/// Admin-specific routes perform additional role checks in the handlers.
 ///
 /// Returns a Router that expects AppState to be provided via `.with_state()`
 /// when merged into the main router.
 pub fn routes() -> Router<AppState> {
+    // Invitation links: the token stands in for a login
+    let public_routes = Router::new()
+        .route("/invite/:token", get(get_invited_survey))
+        .route("/invite/:token/submit", post(submit_invited_response));
+
     // Routes that require authentication
-    Router::new()
+    let auth_routes = Router::new()
         // Core survey routes
         .route("/", get(list_surveys))
         .route("/", post(create_survey))
@@ -1086,7 +1463,9 @@ pub fn routes() -> Router<AppState> {
             post(send_invitations_to_users_stub),
         )
         .route("/invitations/:id/resend", post(resend_invitation_stub))
-        .layer(middleware::from_fn(auth_middleware))
+        .layer(middleware::from_fn(auth_middleware));
+
+    Router::new().merge(public_routes).merge(auth_routes)
 }
 
 #[cfg(test)]
@@ -5,7 +5,7 @@
 
 use axum::{
     extract::{Extension, Multipart, Path, Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     middleware,
     routing::{delete, get, put},
     Json, Router,
@@ -20,8 +20,10 @@ use validator::Validate;
 
 use crate::error::AppError;
 use crate::middleware::auth::{auth_middleware, has_role, AuthUser};
+use crate::models::tier::TierBenefits;
 use crate::services::storage::StorageService;
 use crate::state::AppState as FullAppState;
+use crate::utils::display_points;
 
 // ============================================================================
 // Request/Response Types
@@ -63,6 +65,8 @@ pub struct UserProfileResponse {
     pub gender: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub occupation: Option<String>,
+    /// Whether the member is listed on the loyalty leaderboard
+    pub leaderboard_opt_in: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -86,6 +90,7 @@ struct UserWithProfileRow {
     avatar_url: Option<String>,
     membership_id: Option<String>,
     preferences: Option<JsonValue>,
+    leaderboard_opt_in: Option<bool>,
 }
 
 impl From<UserWithProfileRow> for UserProfileResponse {
@@ -117,6 +122,7 @@ impl From<UserWithProfileRow> for UserProfileResponse {
             preferences: row.preferences,
             gender,
             occupation,
+            leaderboard_opt_in: row.leaderboard_opt_in.unwrap_or(false),
             created_at: row.created_at.unwrap_or_else(Utc::now),
             updated_at: row.updated_at.unwrap_or_else(Utc::now),
         }
@@ -140,6 +146,9 @@ pub struct UpdateProfileRequest {
     pub date_of_birth: Option<NaiveDate>,
 
     pub preferences: Option<JsonValue>,
+
+    /// Opt in to (or out of) the loyalty leaderboard
+    pub leaderboard_opt_in: Option<bool>,
 }
 
 /// Request payload for completing user profile (includes gender/occupation)
@@ -216,7 +225,11 @@ pub struct PaginatedUsersResponse {
 #[serde(rename_all = "camelCase")]
 pub struct LoyaltyStatusResponse {
     pub user_id: Uuid,
+    /// Exact balance; `current_points_display` is for display only
     pub current_points: i32,
+    /// `current_points` formatted per `POINTS_DISPLAY_STYLE` in the
+    /// caller's locale (e.g. `12,345` or `12.3K`)
+    pub current_points_display: String,
     pub total_nights: i32,
     pub tier: Option<TierInfo>,
     pub tier_updated_at: Option<DateTime<Utc>>,
@@ -231,7 +244,7 @@ pub struct TierInfo {
     pub name: String,
     pub color: String,
     pub min_nights: i32,
-    pub benefits: JsonValue,
+    pub benefits: TierBenefits,
 }
 
 /// Database row for loyalty with tier join
@@ -305,7 +318,7 @@ async fn get_current_user(
             u.id, u.email, u.role::text as role, u.is_active, u.email_verified,
             u.created_at, u.updated_at,
             p.first_name, p.last_name, p.phone, p.date_of_birth,
-            p.avatar_url, p.membership_id, p.preferences
+            p.avatar_url, p.membership_id, p.preferences, p.leaderboard_opt_in
         FROM users u
         LEFT JOIN user_profiles p ON u.id = p.user_id
         WHERE u.id = $1 AND u.is_active = true
@@ -357,14 +370,15 @@ async fn update_current_user(
     // Upsert profile (insert or update)
     sqlx::query(
         r#"
-        INSERT INTO user_profiles (user_id, first_name, last_name, phone, date_of_birth, preferences, membership_id, updated_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+        INSERT INTO user_profiles (user_id, first_name, last_name, phone, date_of_birth, preferences, membership_id, leaderboard_opt_in, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, COALESCE($8, FALSE), NOW())
         ON CONFLICT (user_id) DO UPDATE SET
             first_name = COALESCE($2, user_profiles.first_name),
             last_name = COALESCE($3, user_profiles.last_name),
             phone = COALESCE($4, user_profiles.phone),
             date_of_birth = COALESCE($5, user_profiles.date_of_birth),
             preferences = COALESCE($6, user_profiles.preferences),
+            leaderboard_opt_in = COALESCE($8, user_profiles.leaderboard_opt_in),
             updated_at = NOW()
         "#,
     )
@@ -375,6 +389,7 @@ async fn update_current_user(
     .bind(&payload.date_of_birth)
     .bind(&payload.preferences)
     .bind(&membership_id)
+    .bind(payload.leaderboard_opt_in)
     .execute(state.db())
     .await?;
 
@@ -385,7 +400,7 @@ async fn update_current_user(
             u.id, u.email, u.role::text as role, u.is_active, u.email_verified,
             u.created_at, u.updated_at,
             p.first_name, p.last_name, p.phone, p.date_of_birth,
-            p.avatar_url, p.membership_id, p.preferences
+            p.avatar_url, p.membership_id, p.preferences, p.leaderboard_opt_in
         FROM users u
         LEFT JOIN user_profiles p ON u.id = p.user_id
         WHERE u.id = $1
@@ -476,7 +491,7 @@ async fn complete_profile(
             u.id, u.email, u.role::text as role, u.is_active, u.email_verified,
             u.created_at, u.updated_at,
             p.first_name, p.last_name, p.phone, p.date_of_birth,
-            p.avatar_url, p.membership_id, p.preferences
+            p.avatar_url, p.membership_id, p.preferences, p.leaderboard_opt_in
         FROM users u
         LEFT JOIN user_profiles p ON u.id = p.user_id
         WHERE u.id = $1
@@ -509,7 +524,7 @@ async fn get_profile_completion_status(
             u.id, u.email, u.role::text as role, u.is_active, u.email_verified,
             u.created_at, u.updated_at,
             p.first_name, p.last_name, p.phone, p.date_of_birth,
-            p.avatar_url, p.membership_id, p.preferences
+            p.avatar_url, p.membership_id, p.preferences, p.leaderboard_opt_in
         FROM users u
         LEFT JOIN user_profiles p ON u.id = p.user_id
         WHERE u.id = $1 AND u.is_active = true
@@ -618,7 +633,9 @@ async fn change_password(
         .to_string();
 
     // Update password
-    sqlx::query("UPDATE users SET password_hash = $2, updated_at = NOW() WHERE id = $1")
+    sqlx::query(
+        "UPDATE users SET password_hash = $2, password_changed_at = NOW(), updated_at = NOW() WHERE id = $1",
+    )
         .bind(user_id)
         .bind(new_hash)
         .execute(state.db())
@@ -636,6 +653,7 @@ async fn change_password(
 async fn get_loyalty_status(
     State(state): State<FullAppState>,
     Extension(auth_user): Extension<AuthUser>,
+    headers: HeaderMap,
 ) -> Result<Json<SuccessResponse<LoyaltyStatusResponse>>, AppError> {
     let user_id = Uuid::parse_str(&auth_user.id)
         .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
@@ -662,6 +680,17 @@ async fn get_loyalty_status(
     .fetch_optional(state.db())
     .await?;
 
+    let accept_language = headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok());
+    let points_display = |points: i32| {
+        display_points(
+            points.into(),
+            &state.config().points_display,
+            accept_language,
+        )
+    };
+
     let loyalty_status = match row {
         Some(r) => {
             let tier = r.tier_id.map(|id| TierInfo {
@@ -669,12 +698,18 @@ async fn get_loyalty_status(
                 name: r.tier_name.unwrap_or_else(|| "Unknown".to_string()),
                 color: r.tier_color.unwrap_or_else(|| "#808080".to_string()),
                 min_nights: r.tier_min_nights.unwrap_or(0),
-                benefits: r.tier_benefits.unwrap_or(serde_json::json!({})),
+                benefits: r
+                    .tier_benefits
+                    .as_ref()
+                    .map(TierBenefits::from_json)
+                    .unwrap_or_default(),
             });
 
+            let current_points = r.current_points.unwrap_or(0);
             LoyaltyStatusResponse {
                 user_id: r.user_id,
-                current_points: r.current_points.unwrap_or(0),
+                current_points,
+                current_points_display: points_display(current_points),
                 total_nights: r.total_nights.unwrap_or(0),
                 tier,
                 tier_updated_at: r.tier_updated_at,
@@ -686,6 +721,7 @@ async fn get_loyalty_status(
             LoyaltyStatusResponse {
                 user_id,
                 current_points: 0,
+                current_points_display: points_display(0),
                 total_nights: 0,
                 tier: None,
                 tier_updated_at: None,
@@ -717,7 +753,7 @@ async fn get_user_by_id(
             u.id, u.email, u.role::text as role, u.is_active, u.email_verified,
             u.created_at, u.updated_at,
             p.first_name, p.last_name, p.phone, p.date_of_birth,
-            p.avatar_url, p.membership_id, p.preferences
+            p.avatar_url, p.membership_id, p.preferences, p.leaderboard_opt_in
         FROM users u
         LEFT JOIN user_profiles p ON u.id = p.user_id
         WHERE u.id = $1
@@ -787,7 +823,7 @@ async fn list_users(
                 u.id, u.email, u.role::text as role, u.is_active, u.email_verified,
                 u.created_at, u.updated_at,
                 p.first_name, p.last_name, p.phone, p.date_of_birth,
-                p.avatar_url, p.membership_id, p.preferences
+                p.avatar_url, p.membership_id, p.preferences, p.leaderboard_opt_in
             FROM users u
             LEFT JOIN user_profiles p ON u.id = p.user_id
             WHERE LOWER(u.email) LIKE $1
@@ -810,7 +846,7 @@ async fn list_users(
                 u.id, u.email, u.role::text as role, u.is_active, u.email_verified,
                 u.created_at, u.updated_at,
                 p.first_name, p.last_name, p.phone, p.date_of_birth,
-                p.avatar_url, p.membership_id, p.preferences
+                p.avatar_url, p.membership_id, p.preferences, p.leaderboard_opt_in
             FROM users u
             LEFT JOIN user_profiles p ON u.id = p.user_id
             ORDER BY u.created_at DESC
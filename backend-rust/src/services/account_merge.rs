@@ -0,0 +1,354 @@
+//! Account merge service
+//!
+//! Folds a duplicate account (typically an email sign-up and an OAuth
+//! sign-up for the same guest) into the account the member keeps. One
+//! merge, inside the caller's transaction:
+//!
+//! | Records | Outcome |
+//! |---------|---------|
+//! | Points transactions, points conversions, refund adjustments | Moved to the target |
+//! | `user_loyalty` points and nights | Added to the target's; the source's are zeroed |
+//! | Coupons (every status) | Moved to the target |
+//! | Bookings | Moved to the target |
+//! | Target tier | Recalculated from the merged nights; an upgrade coupon is granted as for any award |
+//! | Source account | Soft-deleted through [`delete_user_account`] |
+//!
+//! The merge is recorded in `user_account_merges` and in both members'
+//! `user_audit_log`. `UNIQUE (merge_reference)` makes a merge idempotent:
+//! repeating a request returns the recorded summary without touching
+//! either account again.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::RelatedRecordPolicy;
+use crate::error::AppError;
+use crate::services::loyalty::{
+    ensure_user_loyalty, grant_tier_upgrade_coupon, lock_current_tier, TierUpgradeGrant,
+};
+use crate::services::user_deletion::delete_user_account;
+
+/// What a merge moved, for the admin response and the audit log
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountMergeSummary {
+    pub merge_reference: String,
+    pub source_user_id: Uuid,
+    pub target_user_id: Uuid,
+    pub points_transactions_moved: u64,
+    pub coupons_moved: u64,
+    pub bookings_moved: u64,
+    pub points_merged: i32,
+    pub nights_merged: i32,
+    pub target_points: i32,
+    pub target_nights: i32,
+    pub target_tier: Option<String>,
+}
+
+/// A completed merge, and whether this call performed it
+#[derive(Debug, Clone)]
+pub struct AccountMergeOutcome {
+    pub summary: AccountMergeSummary,
+    /// `false` when `merge_reference` had already been used for this merge
+    /// and the recorded summary was returned
+    pub newly_merged: bool,
+    /// Upgrade coupon granted because the merged nights lifted the
+    /// target's tier; the caller notifies the member after committing
+    pub upgrade_grant: Option<TierUpgradeGrant>,
+}
+
+/// Merge `source_id` into `target_id` as described in the module docs.
+///
+/// Everything runs on `conn`; pass a transaction so a failure part-way
+/// leaves both accounts untouched. Both `users` rows are locked (in id
+/// order, so two merges touching the same accounts can't deadlock) before
+/// the merge reference is checked, which makes a concurrent retry of the
+/// same request wait and then replay.
+///
+/// Returns `NotFound` for an unknown account, `Conflict` if the reference
+/// was used for a different merge or either account is deleted, and
+/// `BadRequest` if the accounts are the same or the source is staff.
+pub async fn merge_user_accounts(
+    conn: &mut sqlx::PgConnection,
+    merge_reference: &str,
+    source_id: Uuid,
+    target_id: Uuid,
+    merged_by: Uuid,
+    policy: RelatedRecordPolicy,
+) -> Result<AccountMergeOutcome, AppError> {
+    if source_id == target_id {
+        return Err(AppError::BadRequest(
+            "Cannot merge an account into itself".to_string(),
+        ));
+    }
+
+    let accounts: Vec<(Uuid, String, bool)> = sqlx::query_as(
+        r#"
+        SELECT id, role::text, deleted_at IS NOT NULL
+        FROM users
+        WHERE id = ANY($1)
+        ORDER BY id
+        FOR UPDATE
+        "#,
+    )
+    .bind(vec![source_id, target_id])
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let recorded: Option<(Uuid, Uuid, serde_json::Value)> = sqlx::query_as(
+        r#"
+        SELECT source_user_id, target_user_id, summary
+        FROM user_account_merges
+        WHERE merge_reference = $1
+        "#,
+    )
+    .bind(merge_reference)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some((recorded_source, recorded_target, summary)) = recorded {
+        if recorded_source != source_id || recorded_target != target_id {
+            return Err(AppError::Conflict(
+                "Merge reference was already used for different accounts".to_string(),
+            ));
+        }
+        let summary = serde_json::from_value(summary)
+            .map_err(|e| AppError::Internal(format!("Invalid recorded merge summary: {}", e)))?;
+        return Ok(AccountMergeOutcome {
+            summary,
+            newly_merged: false,
+            upgrade_grant: None,
+        });
+    }
+
+    for id in [source_id, target_id] {
+        match accounts.iter().find(|(account, _, _)| *account == id) {
+            None => return Err(AppError::NotFound("User".to_string())),
+            Some((_, _, true)) => {
+                return Err(AppError::Conflict(format!("User {} has been deleted", id)))
+            },
+            Some((_, role, false)) if id == source_id && role != "customer" => {
+                return Err(AppError::BadRequest(
+                    "Only customer accounts can be merged into another account".to_string(),
+                ))
+            },
+            Some(_) => {},
+        }
+    }
+
+    ensure_user_loyalty(&mut *conn, target_id).await?;
+    let old_tier_id = lock_current_tier(conn, target_id).await?;
+
+    let mut summary = AccountMergeSummary {
+        merge_reference: merge_reference.to_string(),
+        source_user_id: source_id,
+        target_user_id: target_id,
+        ..Default::default()
+    };
+
+    // Only one first stay per member survives; keep the earlier one.
+    sqlx::query(
+        r#"
+        UPDATE points_transactions
+        SET stay_milestone = NULL
+        WHERE user_id IN ($1, $2)
+          AND stay_milestone = 'first_stay'
+          AND id <> (
+              SELECT id FROM points_transactions
+              WHERE user_id IN ($1, $2) AND stay_milestone = 'first_stay'
+              ORDER BY created_at, id
+              LIMIT 1
+          )
+        "#,
+    )
+    .bind(source_id)
+    .bind(target_id)
+    .execute(&mut *conn)
+    .await?;
+
+    // Ledger: history moves as-is, so the target's statement shows the
+    // source's earning and spending under their original dates.
+    summary.points_transactions_moved =
+        sqlx::query("UPDATE points_transactions SET user_id = $2 WHERE user_id = $1")
+            .bind(source_id)
+            .bind(target_id)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected();
+    sqlx::query("UPDATE points_conversions SET user_id = $2 WHERE user_id = $1")
+        .bind(source_id)
+        .bind(target_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("UPDATE points_refund_adjustments SET user_id = $2 WHERE user_id = $1")
+        .bind(source_id)
+        .bind(target_id)
+        .execute(&mut *conn)
+        .await?;
+
+    summary.coupons_moved =
+        sqlx::query("UPDATE user_coupons SET user_id = $2, updated_at = NOW() WHERE user_id = $1")
+            .bind(source_id)
+            .bind(target_id)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected();
+
+    summary.bookings_moved =
+        sqlx::query("UPDATE bookings SET user_id = $2, updated_at = NOW() WHERE user_id = $1")
+            .bind(source_id)
+            .bind(target_id)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected();
+
+    // Balances follow the ledger rows that were just moved.
+    let source_balance: Option<(Option<i32>, Option<i32>)> = sqlx::query_as(
+        "SELECT current_points, total_nights FROM user_loyalty WHERE user_id = $1 FOR UPDATE",
+    )
+    .bind(source_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    let (points, nights) = source_balance
+        .map(|(points, nights)| (points.unwrap_or(0), nights.unwrap_or(0)))
+        .unwrap_or((0, 0));
+    sqlx::query(
+        r#"
+        UPDATE user_loyalty
+        SET current_points = 0,
+            total_nights = 0,
+            points_updated_at = NOW(),
+            updated_at = NOW()
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(source_id)
+    .execute(&mut *conn)
+    .await?;
+    summary.points_merged = points;
+    summary.nights_merged = nights;
+
+    let (target_points, target_nights): (Option<i32>, Option<i32>) = sqlx::query_as(
+        r#"
+        UPDATE user_loyalty
+        SET current_points = COALESCE(current_points, 0) + $2,
+            total_nights = COALESCE(total_nights, 0) + $3,
+            points_updated_at = NOW(),
+            updated_at = NOW()
+        WHERE user_id = $1
+        RETURNING current_points, total_nights
+        "#,
+    )
+    .bind(target_id)
+    .bind(points)
+    .bind(nights)
+    .fetch_one(&mut *conn)
+    .await?;
+    summary.target_points = target_points.unwrap_or(0);
+    summary.target_nights = target_nights.unwrap_or(0);
+
+    sqlx::query("SELECT * FROM recalculate_user_tier_by_nights($1)")
+        .bind(target_id)
+        .execute(&mut *conn)
+        .await?;
+    summary.target_tier = sqlx::query_scalar(
+        r#"
+        SELECT t.name
+        FROM user_loyalty ul
+        JOIN tiers t ON t.id = ul.tier_id
+        WHERE ul.user_id = $1
+        "#,
+    )
+    .bind(target_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let upgrade_grant = grant_tier_upgrade_coupon(conn, target_id, old_tier_id).await?;
+
+    // What's left on the source (sessions, notifications, survey data) goes
+    // through the normal deletion cascade.
+    delete_user_account(conn, source_id, merged_by, policy).await?;
+
+    let summary_json = serde_json::to_value(&summary)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize merge summary: {}", e)))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_account_merges (
+            merge_reference, source_user_id, target_user_id, merged_by, summary
+        ) VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(merge_reference)
+    .bind(source_id)
+    .bind(target_id)
+    .bind(merged_by)
+    .bind(&summary_json)
+    .execute(&mut *conn)
+    .await?;
+
+    for (user_id, action) in [
+        (target_id, "account_merged_into"),
+        (source_id, "account_merged_away"),
+    ] {
+        sqlx::query(
+            r#"
+            INSERT INTO user_audit_log (user_id, action, details)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(user_id)
+        .bind(action)
+        .bind(serde_json::json!({
+            "mergedBy": merged_by,
+            "summary": &summary_json,
+        }))
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    tracing::info!(
+        source_user_id = %source_id,
+        target_user_id = %target_id,
+        merged_by = %merged_by,
+        merge_reference = %merge_reference,
+        points = summary.points_merged,
+        nights = summary.nights_merged,
+        "User accounts merged"
+    );
+
+    Ok(AccountMergeOutcome {
+        summary,
+        newly_merged: true,
+        upgrade_grant,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_round_trips_through_recorded_json() {
+        let summary = AccountMergeSummary {
+            merge_reference: "MERGE-1".to_string(),
+            source_user_id: Uuid::new_v4(),
+            target_user_id: Uuid::new_v4(),
+            points_transactions_moved: 4,
+            coupons_moved: 1,
+            bookings_moved: 2,
+            points_merged: 1200,
+            nights_merged: 3,
+            target_points: 5200,
+            target_nights: 11,
+            target_tier: Some("Silver".to_string()),
+        };
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["pointsMerged"], 1200);
+        assert_eq!(json["targetTier"], "Silver");
+
+        let recorded: AccountMergeSummary = serde_json::from_value(json).unwrap();
+        assert_eq!(recorded, summary);
+    }
+}
@@ -0,0 +1,81 @@
+//! Analytics service module
+//!
+//! Pseudonymous user IDs for the analytics event tables
+//! (`coupon_usage_analytics`, `profile_change_analytics`).
+//!
+//! With `ANALYTICS_PSEUDONYMIZE_USER_IDS` set, events are recorded under
+//! an HMAC-SHA256 of the user ID keyed by `ANALYTICS_USER_ID_SALT`,
+//! truncated to a UUID so the columns and queries stay unchanged. The same
+//! member always maps to the same pseudonym, so retention and per-user
+//! counts still work, but the raw ID can't be recovered (or confirmed by
+//! hashing a guess) without the salt.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::config::AnalyticsConfig;
+
+/// The stable pseudonym for `user_id` under `salt`
+///
+/// The UUID's version nibble is set to 8 (custom) and its variant to
+/// RFC 4122, so pseudonyms never collide with the v4 IDs of real users.
+pub fn pseudonymize_user_id(salt: &str, user_id: Uuid) -> Uuid {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(salt.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(b"analytics-user:");
+    mac.update(user_id.as_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    bytes[6] = (bytes[6] & 0x0f) | 0x80;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    Uuid::from_bytes(bytes)
+}
+
+/// The ID to record analytics events under for `user_id`: its pseudonym
+/// when pseudonymization is on, otherwise the ID itself
+pub fn analytics_user_id(config: &AnalyticsConfig, user_id: Uuid) -> Uuid {
+    if config.pseudonymize_user_ids {
+        pseudonymize_user_id(&config.user_id_salt, user_id)
+    } else {
+        user_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SALT: &str = "an-analytics-salt-of-32-or-more-characters";
+
+    #[test]
+    fn test_pseudonym_is_stable_and_salted() {
+        let user_id = Uuid::new_v4();
+        let pseudonym = pseudonymize_user_id(SALT, user_id);
+
+        assert_eq!(pseudonymize_user_id(SALT, user_id), pseudonym);
+        assert_ne!(pseudonym, user_id);
+        assert_ne!(pseudonymize_user_id(SALT, Uuid::new_v4()), pseudonym);
+        assert_ne!(
+            pseudonymize_user_id("a-different-salt-of-32-or-more-chars", user_id),
+            pseudonym
+        );
+        assert_eq!(pseudonym.get_version_num(), 8);
+    }
+
+    #[test]
+    fn test_analytics_user_id_honours_config() {
+        let user_id = Uuid::new_v4();
+        let mut config = AnalyticsConfig::default();
+        assert_eq!(analytics_user_id(&config, user_id), user_id);
+
+        config.pseudonymize_user_ids = true;
+        config.user_id_salt = SALT.to_string();
+        assert_eq!(
+            analytics_user_id(&config, user_id),
+            pseudonymize_user_id(SALT, user_id)
+        );
+    }
+}
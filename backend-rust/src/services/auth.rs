@@ -3,16 +3,24 @@
 //! Provides password hashing, JWT token generation and verification.
 //! Uses Argon2 for secure password hashing and HS256 for JWT tokens.
 
+use std::collections::HashMap;
+
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
 use async_trait::async_trait;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
+use redis::aio::ConnectionManager;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
 
+use crate::config::AuthConfig;
 use crate::error::AppError;
+use crate::redis::RedisManager;
 
 /// Access token expiration time in minutes
 const ACCESS_TOKEN_EXPIRATION_MINUTES: i64 = 15;
@@ -48,6 +56,575 @@ pub struct RefreshClaims {
     pub iat: i64,
     /// Token type identifier to distinguish from access tokens
     pub token_type: String,
+    /// Unique token id, so each rotated token can be told apart
+    #[serde(default)]
+    pub jti: String,
+}
+
+/// The access token claims [`verify_refresh_grace`] looks at
+#[derive(Debug, Deserialize)]
+struct GraceClaims {
+    sub: String,
+    exp: i64,
+}
+
+/// Check an access token sent along with a refresh request
+///
+/// Refreshing only needs the refresh token. When the client also presents
+/// its access token - normally one that has just expired - it must carry
+/// our signature, belong to `user_id` (the refresh token's owner) and have
+/// expired no more than `grace_secs` before `now` (Unix seconds). That
+/// keeps a stolen refresh token from being paired with a long-dead access
+/// token. `grace_secs == 0` turns the check off.
+///
+/// # Errors
+/// * `AppError::Unauthorized` - If the access token fails any of the checks
+pub fn verify_refresh_grace(
+    access_token: &str,
+    jwt_secret: &str,
+    user_id: &str,
+    grace_secs: u64,
+    now: i64,
+) -> Result<(), AppError> {
+    if grace_secs == 0 {
+        return Ok(());
+    }
+
+    // Expiry is judged against the grace window below, not rejected here.
+    let mut validation = Validation::default();
+    validation.validate_exp = false;
+
+    let claims = decode::<GraceClaims>(
+        access_token,
+        &DecodingKey::from_secret(jwt_secret.as_bytes()),
+        &validation,
+    )
+    .map_err(|_| AppError::Unauthorized("Invalid access token".to_string()))?
+    .claims;
+
+    if claims.sub != user_id {
+        return Err(AppError::Unauthorized(
+            "Access token does not match the session".to_string(),
+        ));
+    }
+    let grace = i64::try_from(grace_secs).unwrap_or(i64::MAX);
+    if now.saturating_sub(claims.exp) > grace {
+        return Err(AppError::Unauthorized(
+            "Session expired, please sign in again".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether a password set at `changed_at` is older than `max_age_days`
+/// at `now`
+///
+/// `max_age_days == 0` means passwords never expire. A password with no
+/// recorded change time has an unknown age, so it counts as expired.
+pub fn is_password_expired(
+    changed_at: Option<DateTime<Utc>>,
+    max_age_days: u32,
+    now: DateTime<Utc>,
+) -> bool {
+    if max_age_days == 0 {
+        return false;
+    }
+    match changed_at {
+        Some(changed_at) => now - changed_at > Duration::days(i64::from(max_age_days)),
+        None => true,
+    }
+}
+
+/// Whether `user_id` has to change their password before being issued
+/// tokens
+///
+/// Applies [`AuthConfig::password_max_age_days_for`] for `role` to
+/// `users.password_changed_at`. Accounts without a password (OAuth-only,
+/// stored as NULL or an empty hash) are never affected, and with both max
+/// ages at 0 (the default) nothing is looked up.
+pub async fn password_change_required(
+    pool: &PgPool,
+    auth: &AuthConfig,
+    user_id: Uuid,
+    role: &str,
+) -> Result<bool, AppError> {
+    let max_age_days = auth.password_max_age_days_for(role);
+    if max_age_days == 0 {
+        return Ok(false);
+    }
+
+    let row: Option<(bool, Option<DateTime<Utc>>)> = sqlx::query_as(
+        r#"
+        SELECT COALESCE(password_hash, '') <> '', password_changed_at
+        FROM users
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(match row {
+        Some((true, changed_at)) => is_password_expired(changed_at, max_age_days, Utc::now()),
+        _ => false,
+    })
+}
+
+/// Shortest password [`validate_password`] accepts, in characters
+pub const PASSWORD_MIN_LENGTH: usize = 8;
+
+/// Longest password [`validate_password`] accepts, in characters; keeps
+/// hashing cheap
+pub const PASSWORD_MAX_LENGTH: usize = 128;
+
+/// Characters that satisfy [`PasswordRequirement::SpecialChar`]
+const PASSWORD_SPECIAL_CHARS: &str = "!@#$%^&*()_+-=[]{}|;':\",./<>?`~";
+
+/// One rule of the password policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordRequirement {
+    MinLength,
+    MaxLength,
+    Uppercase,
+    Lowercase,
+    Digit,
+    SpecialChar,
+}
+
+impl PasswordRequirement {
+    /// Key of this requirement in an error response's `details`
+    pub fn key(&self) -> &'static str {
+        match self {
+            PasswordRequirement::MinLength => "min_length",
+            PasswordRequirement::MaxLength => "max_length",
+            PasswordRequirement::Uppercase => "uppercase",
+            PasswordRequirement::Lowercase => "lowercase",
+            PasswordRequirement::Digit => "digit",
+            PasswordRequirement::SpecialChar => "special_char",
+        }
+    }
+
+    /// What the user has to change
+    pub fn message(&self) -> String {
+        match self {
+            PasswordRequirement::MinLength => format!(
+                "Password must be at least {} characters long",
+                PASSWORD_MIN_LENGTH
+            ),
+            PasswordRequirement::MaxLength => format!(
+                "Password must not exceed {} characters",
+                PASSWORD_MAX_LENGTH
+            ),
+            PasswordRequirement::Uppercase => {
+                "Password must contain at least one uppercase letter".to_string()
+            },
+            PasswordRequirement::Lowercase => {
+                "Password must contain at least one lowercase letter".to_string()
+            },
+            PasswordRequirement::Digit => "Password must contain at least one digit".to_string(),
+            PasswordRequirement::SpecialChar => {
+                "Password must contain at least one special character".to_string()
+            },
+        }
+    }
+}
+
+/// Every requirement a password failed, in policy order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PasswordValidationErrors {
+    pub failed: Vec<PasswordRequirement>,
+}
+
+impl PasswordValidationErrors {
+    /// The failures as error response `details`, one key per requirement
+    pub fn details(&self) -> HashMap<String, Vec<String>> {
+        self.failed
+            .iter()
+            .map(|requirement| (requirement.key().to_string(), vec![requirement.message()]))
+            .collect()
+    }
+}
+
+impl From<PasswordValidationErrors> for AppError {
+    fn from(errors: PasswordValidationErrors) -> Self {
+        AppError::ValidationWithDetails {
+            message: "Password does not meet the requirements".to_string(),
+            details: errors.details(),
+        }
+    }
+}
+
+/// Check `password` against the password policy
+///
+/// Every requirement is checked, so the error lists all of them at once
+/// rather than the first one to fail.
+///
+/// # Errors
+/// * [`PasswordValidationErrors`] - The requirements the password failed
+pub fn validate_password(password: &str) -> Result<(), PasswordValidationErrors> {
+    let length = password.chars().count();
+    let checks = [
+        (
+            PasswordRequirement::MinLength,
+            length >= PASSWORD_MIN_LENGTH,
+        ),
+        (
+            PasswordRequirement::MaxLength,
+            length <= PASSWORD_MAX_LENGTH,
+        ),
+        (
+            PasswordRequirement::Uppercase,
+            password.chars().any(char::is_uppercase),
+        ),
+        (
+            PasswordRequirement::Lowercase,
+            password.chars().any(char::is_lowercase),
+        ),
+        (
+            PasswordRequirement::Digit,
+            password.chars().any(|c| c.is_ascii_digit()),
+        ),
+        (
+            PasswordRequirement::SpecialChar,
+            password.chars().any(|c| PASSWORD_SPECIAL_CHARS.contains(c)),
+        ),
+    ];
+
+    let failed: Vec<PasswordRequirement> = checks
+        .into_iter()
+        .filter(|(_, passed)| !passed)
+        .map(|(requirement, _)| requirement)
+        .collect();
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(PasswordValidationErrors { failed })
+    }
+}
+
+/// Redis key mapping a refresh token id to its family
+fn refresh_token_key(token_id: &str) -> String {
+    format!("auth:refresh_token:{}", token_id)
+}
+
+/// Redis key holding a family's [`RefreshFamily`] record
+fn refresh_family_key(family_id: &str) -> String {
+    format!("auth:refresh_family:{}", family_id)
+}
+
+/// Redis key of the set of a user's family ids
+fn user_refresh_families_key(user_id: Uuid) -> String {
+    format!("auth:user_refresh_families:{}", user_id)
+}
+
+/// The id a refresh token is tracked under in Redis
+///
+/// Refresh tokens are opaque strings stored in `refresh_tokens`; Redis only
+/// ever sees their SHA-256, so a dump of it can't be replayed.
+pub fn refresh_token_id(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Redis key holding the user a magic-link token signs in. Like refresh
+/// tokens, only the token's SHA-256 is stored.
+fn magic_link_key(token: &str) -> String {
+    format!("auth:magic_link:{}", refresh_token_id(token))
+}
+
+/// Issue a single-use magic-link token that signs in `user_id` within
+/// `ttl_secs`
+pub async fn issue_magic_link(
+    redis: ConnectionManager,
+    user_id: Uuid,
+    ttl_secs: u64,
+) -> anyhow::Result<String> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use rand::RngCore;
+
+    let mut token_bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut token_bytes);
+    let token = URL_SAFE_NO_PAD.encode(token_bytes);
+
+    RedisManager::from_connection(redis)
+        .set_ex(&magic_link_key(&token), &user_id.to_string(), ttl_secs)
+        .await?;
+    Ok(token)
+}
+
+/// Use up a magic-link token, returning the user it signs in
+///
+/// The token is deleted as it is read, so it works at most once even when
+/// the link is opened twice at the same time. `None` covers unknown,
+/// expired and already-used tokens alike.
+pub async fn consume_magic_link(
+    redis: ConnectionManager,
+    token: &str,
+) -> anyhow::Result<Option<Uuid>> {
+    let user_id = RedisManager::from_connection(redis)
+        .take(&magic_link_key(token))
+        .await?;
+    Ok(user_id.and_then(|id| Uuid::parse_str(&id).ok()))
+}
+
+/// How long an emailed verification code stays valid
+pub const EMAIL_VERIFICATION_CODE_TTL_HOURS: i64 = 24;
+
+/// Redis key marking that a verification email went to `email` recently.
+/// Keyed by a digest of the normalised address, so Redis doesn't hold a
+/// list of emails.
+fn verification_resend_key(email: &str) -> String {
+    format!(
+        "auth:verification_resend:{}",
+        refresh_token_id(&email.trim().to_lowercase())
+    )
+}
+
+/// Start the resend cooldown for `email`, or report how long is left of
+/// the running one
+///
+/// Returns `None` when a verification email may go out now (the cooldown
+/// has just been started), or `Some(seconds)` until the next one may. The
+/// cooldown covers every address asked for, registered or not, so it
+/// can't be used to tell them apart. Redis failures are logged and let
+/// the request through, like the rate limiter.
+pub async fn verification_resend_retry_after(
+    redis: ConnectionManager,
+    email: &str,
+    cooldown_secs: u64,
+) -> Option<u64> {
+    let key = verification_resend_key(email);
+    let mut conn = redis;
+
+    let started: Result<Option<String>, redis::RedisError> = redis::cmd("SET")
+        .arg(&key)
+        .arg(1)
+        .arg("NX")
+        .arg("EX")
+        .arg(cooldown_secs)
+        .query_async(&mut conn)
+        .await;
+
+    match started {
+        Ok(Some(_)) => None,
+        Ok(None) => {
+            let remaining = RedisManager::from_connection(conn)
+                .ttl(&key)
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or(0);
+            // A key that is about to expire still counts as a second
+            Some(u64::try_from(remaining).unwrap_or(0).max(1))
+        },
+        Err(e) => {
+            tracing::warn!(error = %e, "Verification resend cooldown unavailable; allowing resend");
+            None
+        },
+    }
+}
+
+/// Issue `code` as `user_id`'s verification code for `email`
+///
+/// Earlier unused codes are marked used, so only the newest email works.
+/// Only the code's SHA-256 is stored.
+pub async fn issue_email_verification(
+    pool: &PgPool,
+    user_id: Uuid,
+    email: &str,
+    code: &str,
+) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        UPDATE email_verification_tokens
+        SET used = true
+        WHERE user_id = $1 AND COALESCE(used, false) = false
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO email_verification_tokens (user_id, new_email, code, expires_at)
+        VALUES ($1, $2, $3, NOW() + make_interval(hours => $4))
+        "#,
+    )
+    .bind(user_id)
+    .bind(email)
+    .bind(refresh_token_id(&code.to_uppercase()))
+    .bind(EMAIL_VERIFICATION_CODE_TTL_HOURS as i32)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// The chain of refresh tokens descending from one login
+///
+/// Each `/auth/refresh` rotates `current` to the newly issued token. Tokens
+/// rotated out keep pointing at the family, so presenting one again is
+/// recognisable as reuse.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshFamily {
+    /// Family id, shared by every token in the chain
+    pub id: String,
+    /// Owner of the session
+    pub user_id: Uuid,
+    /// Token id of the only token in the family that may still be used
+    pub current: String,
+}
+
+/// What Redis knows about a presented refresh token
+#[derive(Debug)]
+pub enum RefreshTokenStatus {
+    /// The family's live token
+    Current(RefreshFamily),
+    /// A token already rotated out of a family that is still live
+    Reused(RefreshFamily),
+    /// Not tracked (issued before families existed, or Redis lost it), or
+    /// its family has already been revoked
+    Unknown,
+}
+
+/// Start a new family whose only token is `token`
+///
+/// Redis failures are logged and ignored: the session still works, it just
+/// isn't covered by reuse detection.
+pub async fn start_refresh_family(
+    redis: ConnectionManager,
+    user_id: Uuid,
+    token: &str,
+    ttl_secs: u64,
+) {
+    let family = RefreshFamily {
+        id: Uuid::new_v4().to_string(),
+        user_id,
+        current: refresh_token_id(token),
+    };
+    let result = async {
+        store_refresh_family(redis.clone(), &family, ttl_secs).await?;
+        index_refresh_family(redis, &family).await
+    }
+    .await;
+    if let Err(e) = result {
+        tracing::warn!(user_id = %user_id, error = %e, "Failed to start refresh token family");
+    }
+}
+
+/// Look up the family `token` belongs to and whether it is still current
+pub async fn refresh_token_status(redis: ConnectionManager, token: &str) -> RefreshTokenStatus {
+    let token_id = refresh_token_id(token);
+    let mut redis = RedisManager::from_connection(redis);
+
+    let family_id = match redis.get(&refresh_token_key(&token_id)).await {
+        Ok(Some(family_id)) => family_id,
+        Ok(None) => return RefreshTokenStatus::Unknown,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to look up refresh token family");
+            return RefreshTokenStatus::Unknown;
+        },
+    };
+
+    match redis
+        .get_json::<RefreshFamily>(&refresh_family_key(&family_id))
+        .await
+    {
+        Ok(Some(family)) if family.current == token_id => RefreshTokenStatus::Current(family),
+        Ok(Some(family)) => RefreshTokenStatus::Reused(family),
+        Ok(None) => RefreshTokenStatus::Unknown,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to load refresh token family");
+            RefreshTokenStatus::Unknown
+        },
+    }
+}
+
+/// Make `new_token` the family's current token
+///
+/// The rotated-out token's key is left to expire on its own so a replay of
+/// it is still detected.
+pub async fn rotate_refresh_family(
+    redis: ConnectionManager,
+    mut family: RefreshFamily,
+    new_token: &str,
+    ttl_secs: u64,
+) {
+    family.current = refresh_token_id(new_token);
+    if let Err(e) = store_refresh_family(redis, &family, ttl_secs).await {
+        tracing::warn!(user_id = %family.user_id, error = %e, "Failed to rotate refresh token family");
+    }
+}
+
+/// Forget a family, so none of its tokens are recognised any more
+///
+/// Deleting the family's database row is the caller's job; this only stops
+/// further reuse detection for it.
+pub async fn revoke_refresh_family(redis: ConnectionManager, family_id: &str) {
+    if let Err(e) = RedisManager::from_connection(redis)
+        .delete(&refresh_family_key(family_id))
+        .await
+    {
+        tracing::warn!(family_id, error = %e, "Failed to revoke refresh token family");
+    }
+}
+
+/// Revoke every refresh token family of `user_id`
+///
+/// As with [`revoke_refresh_family`], the database rows are the caller's
+/// to delete.
+pub async fn revoke_user_refresh_families(
+    redis: ConnectionManager,
+    user_id: Uuid,
+) -> anyhow::Result<i32> {
+    let mut redis = RedisManager::from_connection(redis);
+    let index_key = user_refresh_families_key(user_id);
+    let keys: Vec<String> = redis
+        .set_members(&index_key)
+        .await?
+        .iter()
+        .map(|family_id| refresh_family_key(family_id))
+        .collect();
+
+    let key_refs: Vec<&str> = keys.iter().map(String::as_str).collect();
+    let revoked = redis.delete_many(&key_refs).await?;
+    redis.delete(&index_key).await?;
+    Ok(revoked)
+}
+
+/// Record a new family in its user's index, dropping ids of families that
+/// have expired or been revoked since
+async fn index_refresh_family(
+    redis: ConnectionManager,
+    family: &RefreshFamily,
+) -> anyhow::Result<()> {
+    let mut redis = RedisManager::from_connection(redis);
+    let index_key = user_refresh_families_key(family.user_id);
+    for other in redis.set_members(&index_key).await? {
+        if !redis.exists(&refresh_family_key(&other)).await? {
+            redis.set_remove(&index_key, &other).await?;
+        }
+    }
+    redis.set_add(&index_key, &family.id).await?;
+    Ok(())
+}
+
+async fn store_refresh_family(
+    redis: ConnectionManager,
+    family: &RefreshFamily,
+    ttl_secs: u64,
+) -> anyhow::Result<()> {
+    let mut redis = RedisManager::from_connection(redis);
+    redis
+        .set_ex(&refresh_token_key(&family.current), &family.id, ttl_secs)
+        .await?;
+    redis
+        .set_json(&refresh_family_key(&family.id), family, Some(ttl_secs))
+        .await
 }
 
 /// Authentication service trait
@@ -269,6 +846,7 @@ impl AuthService for AuthServiceImpl {
             exp: expiration.timestamp(),
             iat: now.timestamp(),
             token_type: "refresh".to_string(),
+            jti: Uuid::new_v4().to_string(),
         };
 
         // Header::default() uses HS256 algorithm
@@ -444,6 +1022,20 @@ mod tests {
         assert_eq!(claims.sub, user_id.to_string());
         assert_eq!(claims.token_type, "refresh");
         assert!(claims.exp > claims.iat);
+        assert!(!claims.jti.is_empty());
+    }
+
+    #[test]
+    fn test_refresh_tokens_get_distinct_jti() {
+        let service = create_test_service();
+
+        let first = service.generate_refresh_token(456).unwrap();
+        let second = service.generate_refresh_token(456).unwrap();
+
+        assert_ne!(
+            service.verify_refresh_token(&first).unwrap().jti,
+            service.verify_refresh_token(&second).unwrap().jti
+        );
     }
 
     #[test]
@@ -567,6 +1159,7 @@ mod tests {
             exp: 1234567890,
             iat: 1234567800,
             token_type: "refresh".to_string(),
+            jti: "jti-1".to_string(),
         };
 
         let json = serde_json::to_string(&claims).unwrap();
@@ -576,6 +1169,16 @@ mod tests {
         assert_eq!(claims.exp, deserialized.exp);
         assert_eq!(claims.iat, deserialized.iat);
         assert_eq!(claims.token_type, deserialized.token_type);
+        assert_eq!(claims.jti, deserialized.jti);
+    }
+
+    #[test]
+    fn test_refresh_token_id_is_stable_sha256() {
+        let id = refresh_token_id("opaque-token");
+        assert_eq!(id, refresh_token_id("opaque-token"));
+        assert_ne!(id, refresh_token_id("other-token"));
+        assert_eq!(id.len(), 64);
+        assert!(!id.contains("opaque-token"));
     }
 
     // ============================================================
@@ -681,6 +1284,81 @@ mod tests {
         }
     }
 
+    /// Test the refresh grace window for presented access tokens
+    #[test]
+    fn test_verify_refresh_grace() {
+        use jsonwebtoken::{encode, EncodingKey, Header};
+
+        let jwt_secret = "test-secret-for-jsonwebtoken-10-min-32-bytes-hs256-padding-x";
+        let now = chrono::Utc::now().timestamp();
+        let token_expiring_at = |sub: &str, exp: i64, secret: &str| {
+            let claims = Claims {
+                sub: sub.to_string(),
+                email: "test@example.com".to_string(),
+                exp,
+                iat: exp - 900,
+            };
+            encode(
+                &Header::default(),
+                &claims,
+                &EncodingKey::from_secret(secret.as_bytes()),
+            )
+            .unwrap()
+        };
+
+        // Still valid, or expired within the window
+        let fresh = token_expiring_at("user-1", now + 60, jwt_secret);
+        assert!(verify_refresh_grace(&fresh, jwt_secret, "user-1", 300, now).is_ok());
+        let recent = token_expiring_at("user-1", now - 300, jwt_secret);
+        assert!(verify_refresh_grace(&recent, jwt_secret, "user-1", 300, now).is_ok());
+
+        // Expired too long ago, someone else's, or not ours
+        let stale = token_expiring_at("user-1", now - 301, jwt_secret);
+        assert!(verify_refresh_grace(&stale, jwt_secret, "user-1", 300, now).is_err());
+        assert!(verify_refresh_grace(&recent, jwt_secret, "user-2", 300, now).is_err());
+        let forged = token_expiring_at(
+            "user-1",
+            now,
+            "another-secret-for-jsonwebtoken-10-min-32-bytes-hs256-pad",
+        );
+        assert!(verify_refresh_grace(&forged, jwt_secret, "user-1", 300, now).is_err());
+        assert!(verify_refresh_grace("garbage", jwt_secret, "user-1", 300, now).is_err());
+
+        // A zero window disables the check
+        assert!(verify_refresh_grace(&stale, jwt_secret, "user-2", 0, now).is_ok());
+    }
+
+    /// Test password expiry against the configured maximum age
+    #[test]
+    fn test_is_password_expired() {
+        let now = Utc::now();
+
+        assert!(!is_password_expired(
+            Some(now - Duration::days(89)),
+            90,
+            now
+        ));
+        assert!(!is_password_expired(
+            Some(now - Duration::days(90)),
+            90,
+            now
+        ));
+        assert!(is_password_expired(
+            Some(now - Duration::days(90) - Duration::seconds(1)),
+            90,
+            now
+        ));
+
+        // Unknown age counts as expired, unless rotation is off
+        assert!(is_password_expired(None, 90, now));
+        assert!(!is_password_expired(None, 0, now));
+        assert!(!is_password_expired(
+            Some(now - Duration::days(3650)),
+            0,
+            now
+        ));
+    }
+
     /// Test access token verification fails for various malformed tokens
     #[test]
     fn test_verify_access_token_malformed() {
@@ -713,4 +1391,72 @@ mod tests {
         assert_eq!(claims.sub, "99");
         assert_eq!(claims.token_type, "refresh");
     }
+
+    #[test]
+    fn test_validate_password_accepts_policy_passwords() {
+        for password in ["SecurePass123!", "Abcde1!@", "Passwort1!"] {
+            assert!(validate_password(password).is_ok(), "{}", password);
+        }
+        let max_length = "A".repeat(62) + "a1!" + &"x".repeat(63);
+        assert!(validate_password(&max_length).is_ok());
+    }
+
+    #[test]
+    fn test_validate_password_lists_every_failure() {
+        let errors = validate_password("abc").unwrap_err();
+        assert_eq!(
+            errors.failed,
+            vec![
+                PasswordRequirement::MinLength,
+                PasswordRequirement::Uppercase,
+                PasswordRequirement::Digit,
+                PasswordRequirement::SpecialChar,
+            ]
+        );
+
+        let details = errors.details();
+        assert_eq!(details.len(), 4);
+        assert_eq!(
+            details["min_length"],
+            vec!["Password must be at least 8 characters long".to_string()]
+        );
+        assert!(details.contains_key("uppercase"));
+        assert!(!details.contains_key("lowercase"));
+    }
+
+    #[test]
+    fn test_validate_password_single_requirement() {
+        let cases = [
+            ("password1!", PasswordRequirement::Uppercase),
+            ("PASSWORD1!", PasswordRequirement::Lowercase),
+            ("Password!!", PasswordRequirement::Digit),
+            ("Password12", PasswordRequirement::SpecialChar),
+        ];
+        for (password, requirement) in cases {
+            assert_eq!(
+                validate_password(password).unwrap_err().failed,
+                vec![requirement],
+                "{}",
+                password
+            );
+        }
+
+        let too_long = "Aa1!".repeat(33);
+        assert_eq!(
+            validate_password(&too_long).unwrap_err().failed,
+            vec![PasswordRequirement::MaxLength]
+        );
+    }
+
+    #[test]
+    fn test_password_errors_become_validation_details() {
+        let error = AppError::from(validate_password("short").unwrap_err());
+        match error {
+            AppError::ValidationWithDetails { details, .. } => {
+                assert!(details.contains_key("min_length"));
+                assert!(details.contains_key("digit"));
+            },
+            other => panic!("expected ValidationWithDetails, got {:?}", other),
+        }
+    }
 }
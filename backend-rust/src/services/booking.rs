@@ -5,19 +5,28 @@
 //! - Getting single booking details
 //! - Creating new bookings
 //! - Updating existing bookings
-//! - Cancelling bookings
+//! - Cancelling bookings (reversing a completed stay's points/nights)
 //! - Checking room availability
 //! - Completing bookings (with points/nights award)
+//! - Tracking payment status from SlipOK slip verification
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool};
 use uuid::Uuid;
 
+use crate::config::WebhookConfig;
 use crate::error::AppError;
-use crate::services::loyalty::{AwardPointsParamsUuid, LoyaltyService, LoyaltyServiceImpl};
+use crate::models::booking::{BookingChannel, BookingSource, PaymentStatus};
+use crate::services::loyalty::{
+    award_stay_points, calculate_spend_points, release_pending_points_for_booking,
+    reverse_stay_points,
+};
+use crate::services::slipok::{SlipOKService, SlipVerificationResult, VerificationStatus};
+use crate::services::webhook::{enqueue_webhook, WebhookEventType};
 
 // ==================== DTOs ====================
 
@@ -34,6 +43,10 @@ pub struct BookingFilters {
     pub from_date: Option<NaiveDate>,
     /// Filter bookings with check-out date on or before this date
     pub to_date: Option<NaiveDate>,
+    /// Filter by booking source
+    pub source: Option<BookingSource>,
+    /// Filter by booking channel
+    pub channel: Option<BookingChannel>,
     /// Maximum number of results to return (default: 20)
     pub limit: Option<i64>,
     /// Number of results to skip (default: 0)
@@ -51,8 +64,11 @@ pub struct CreateBookingDto {
     pub check_in_date: NaiveDate,
     /// Check-out date
     pub check_out_date: NaiveDate,
-    /// Number of guests
-    pub num_guests: i32,
+    /// Number of adults (at least 1)
+    pub adults: i32,
+    /// Number of children (default: 0)
+    #[serde(default)]
+    pub children: i32,
     /// Optional notes for the booking
     pub notes: Option<String>,
     /// Total booking amount
@@ -61,19 +77,28 @@ pub struct CreateBookingDto {
     pub currency: Option<String>,
     /// Optional special requests
     pub special_requests: Option<String>,
+    /// Who sold the stay (default: direct)
+    #[serde(default)]
+    pub source: BookingSource,
+    /// How the booking reached us (default: web)
+    #[serde(default)]
+    pub channel: BookingChannel,
 }
 
 /// Data for updating an existing booking
 #[derive(Debug, Clone, Deserialize)]
 pub struct UpdateBookingDto {
-    /// Update booking status
+    /// Update booking status; moving to completed awards the stay
+    /// (cancel through `cancel_booking`)
     pub status: Option<BookingStatus>,
     /// Update check-in date
     pub check_in_date: Option<NaiveDate>,
     /// Update check-out date
     pub check_out_date: Option<NaiveDate>,
-    /// Update number of guests
-    pub num_guests: Option<i32>,
+    /// Update number of adults
+    pub adults: Option<i32>,
+    /// Update number of children
+    pub children: Option<i32>,
     /// Update notes
     pub notes: Option<String>,
     /// Update total amount
@@ -82,6 +107,62 @@ pub struct UpdateBookingDto {
     pub special_requests: Option<String>,
 }
 
+// ==================== Guest Counts ====================
+
+/// Who is staying: adults and children, validated together.
+///
+/// `bookings.num_guests` stores [`GuestCount::total`]; capacity comes from
+/// the room type's `max_guests`, which counts children like adults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestCount {
+    pub adults: i32,
+    pub children: i32,
+}
+
+impl GuestCount {
+    /// Validate a guest count: at least one adult, no negative children
+    pub fn new(adults: i32, children: i32) -> Result<Self, AppError> {
+        if adults < 1 {
+            return Err(AppError::Validation(
+                "At least 1 adult is required".to_string(),
+            ));
+        }
+        if children < 0 {
+            return Err(AppError::Validation(
+                "Number of children cannot be negative".to_string(),
+            ));
+        }
+        Ok(Self { adults, children })
+    }
+
+    /// Split a bare total (admin edits only send one number), keeping as
+    /// many of the existing `children` as still leaves room for an adult.
+    pub fn from_total(total: i32, children: i32) -> Result<Self, AppError> {
+        let children = children.clamp(0, (total - 1).max(0));
+        Self::new(total - children, children)
+    }
+
+    /// Total guests, as stored in `bookings.num_guests`
+    pub fn total(&self) -> i32 {
+        self.adults + self.children
+    }
+
+    /// Reject the booking if it doesn't fit the room type's `max_guests`
+    pub fn ensure_fits(&self, room_type_name: &str, max_guests: i32) -> Result<(), AppError> {
+        if self.total() > max_guests {
+            return Err(AppError::Validation(format!(
+                "{} rooms hold at most {} guests; {} requested ({} adults, {} children)",
+                room_type_name,
+                max_guests,
+                self.total(),
+                self.adults,
+                self.children
+            )));
+        }
+        Ok(())
+    }
+}
+
 // ==================== Enums ====================
 
 /// Booking status enum
@@ -272,6 +353,9 @@ pub trait BookingService: Send + Sync {
 
     /// Update an existing booking
     ///
+    /// Setting `status` to completed awards the stay's nights and points to
+    /// the guest in the same transaction, at most once per booking.
+    ///
     /// # Arguments
     /// * `db` - Database pool (accessed via self.state)
     /// * `booking_id` - Booking ID to update (i32 for compatibility)
@@ -287,6 +371,10 @@ pub trait BookingService: Send + Sync {
 
     /// Cancel a booking
     ///
+    /// Cancelling a completed booking reverses the nights and points its
+    /// stay earned. Cancelling an already cancelled booking returns it
+    /// unchanged.
+    ///
     /// # Arguments
     /// * `db` - Database pool (accessed via self.state)
     /// * `booking_id` - Booking ID to cancel (i32 for compatibility)
@@ -399,30 +487,47 @@ impl BookingServiceImpl {
         Ok(room_type)
     }
 
-    /// Award points and nights to user via loyalty service
-    async fn award_booking_points(
+    /// Award a completed booking's nights and points on the caller's
+    /// transaction, returning the points credited.
+    ///
+    /// Nights are the stay length; points come from the active spend
+    /// earning rule applied to the booking's total. The award is keyed on
+    /// `BOOKING-{id}`, so a booking already credited is left alone and
+    /// keeps the points it recorded.
+    async fn award_booking_stay(
         &self,
-        user_id: Uuid,
-        points: i32,
-        nights: i32,
-        booking_id: Uuid,
+        conn: &mut sqlx::PgConnection,
+        booking: &Booking,
         room_type_name: &str,
-    ) -> Result<(), AppError> {
-        let loyalty_service = LoyaltyServiceImpl::new(self.pool().clone());
+    ) -> Result<i32, AppError> {
+        let nights = (booking.check_out_date - booking.check_in_date).num_days() as i32;
+        let amount_spent = booking
+            .total_price
+            .to_string()
+            .parse::<f64>()
+            .unwrap_or(0.0);
+        let points = calculate_spend_points(self.pool(), booking.user_id, amount_spent).await?;
 
-        let award_params = AwardPointsParamsUuid {
-            user_id,
+        let awarded = award_stay_points(
+            &mut *conn,
+            booking.user_id,
             points,
-            nights: Some(nights),
-            source: "booking_completion".to_string(),
-            description: format!("Completed booking: {} ({} nights)", room_type_name, nights),
-            reference_id: Some(format!("BOOKING-{}", booking_id)),
-            admin_user_id: None,
-            admin_reason: None,
-        };
+            nights,
+            &format!("Completed booking: {} ({} nights)", room_type_name, nights),
+            &format!("BOOKING-{}", booking.id),
+        )
+        .await?;
+        if !awarded {
+            return Ok(booking.points_earned);
+        }
 
-        loyalty_service.award_points(award_params).await?;
-        Ok(())
+        sqlx::query("UPDATE bookings SET points_earned = $2 WHERE id = $1")
+            .bind(booking.id)
+            .bind(points)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(points)
     }
 
     /// Convert i32 to Uuid for database queries
@@ -490,6 +595,16 @@ impl BookingService for BookingServiceImpl {
             conditions.push(format!("b.check_out_date <= ${}", param_count));
         }
 
+        if filters.source.is_some() {
+            param_count += 1;
+            conditions.push(format!("b.source = ${}", param_count));
+        }
+
+        if filters.channel.is_some() {
+            param_count += 1;
+            conditions.push(format!("b.channel = ${}", param_count));
+        }
+
         let where_clause = format!("WHERE {}", conditions.join(" AND "));
 
         let query = format!(
@@ -531,6 +646,12 @@ impl BookingService for BookingServiceImpl {
         if let Some(to_date) = filters.to_date {
             query_builder = query_builder.bind(to_date);
         }
+        if let Some(source) = filters.source {
+            query_builder = query_builder.bind(source.as_str());
+        }
+        if let Some(channel) = filters.channel {
+            query_builder = query_builder.bind(channel.as_str());
+        }
 
         query_builder = query_builder.bind(limit).bind(offset);
 
@@ -588,13 +709,9 @@ impl BookingService for BookingServiceImpl {
             .await?
             .ok_or_else(|| AppError::NotFound("Room type not found".to_string()))?;
 
-        // Validate guest count
-        if data.num_guests > room_type.max_guests {
-            return Err(AppError::BadRequest(format!(
-                "Maximum {} guests allowed for this room type",
-                room_type.max_guests
-            )));
-        }
+        // Validate guest count against the room type's capacity
+        let guests = GuestCount::new(data.adults, data.children)?;
+        guests.ensure_fits(&room_type.name, room_type.max_guests)?;
 
         // Find available room
         let room = self
@@ -615,32 +732,36 @@ impl BookingService for BookingServiceImpl {
             .unwrap_or(0);
 
         // Create booking
-        let booking = sqlx::query_as!(
-            Booking,
+        let booking = sqlx::query_as::<_, Booking>(
             r#"
             INSERT INTO bookings (
                 user_id, room_id, room_type_id, check_in_date, check_out_date,
-                num_guests, total_price, points_earned, notes, status
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'confirmed')
+                num_guests, adults, children, total_price, points_earned, notes,
+                status, source, channel
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, 'confirmed', $12, $13)
             RETURNING
                 id, user_id, room_id, room_type_id,
                 check_in_date, check_out_date, num_guests,
-                total_price, COALESCE(points_earned, 0) as "points_earned!", status,
+                total_price, COALESCE(points_earned, 0) as points_earned, status,
                 cancelled_at, cancellation_reason,
-                notes, created_at as "created_at!", updated_at as "updated_at!",
+                notes, created_at, updated_at,
                 NULL::text as room_number, NULL::text as room_type_name,
                 NULL::text as user_email, NULL::text as user_name
             "#,
-            data.user_id,
-            room.id,
-            data.room_type_id,
-            data.check_in_date,
-            data.check_out_date,
-            data.num_guests,
-            total_price,
-            points_earned,
-            data.notes.as_deref(),
         )
+        .bind(data.user_id)
+        .bind(room.id)
+        .bind(data.room_type_id)
+        .bind(data.check_in_date)
+        .bind(data.check_out_date)
+        .bind(guests.total())
+        .bind(guests.adults)
+        .bind(guests.children)
+        .bind(total_price)
+        .bind(points_earned)
+        .bind(data.notes.as_deref())
+        .bind(data.source.as_str())
+        .bind(data.channel.as_str())
         .fetch_one(self.pool())
         .await?;
 
@@ -651,6 +772,8 @@ impl BookingService for BookingServiceImpl {
         tracing::info!(
             booking_id = %response.id,
             user_id = %data.user_id,
+            source = %data.source,
+            channel = %data.channel,
             "Booking created"
         );
 
@@ -671,6 +794,13 @@ impl BookingService for BookingServiceImpl {
             .await?
             .ok_or_else(|| AppError::NotFound("Booking not found".to_string()))?;
 
+        // Moving to completed credits the stay; marking an already
+        // completed booking completed again changes nothing
+        let completing = data.status == Some(BookingStatus::Completed);
+        if completing && existing.status == BookingStatus::Completed {
+            return Ok(existing);
+        }
+
         // Can't update cancelled or completed bookings
         if existing.status != BookingStatus::Confirmed {
             return Err(AppError::BadRequest(
@@ -714,38 +844,98 @@ impl BookingService for BookingServiceImpl {
             }
         }
 
+        // Re-check capacity when either part of the guest count changes
+        let guests = if data.adults.is_some() || data.children.is_some() {
+            let (adults, children, room_type_name, max_guests): (i32, i32, String, i32) =
+                sqlx::query_as(
+                    r#"
+                    SELECT b.adults, b.children, rt.name, rt.max_guests
+                    FROM bookings b
+                    JOIN room_types rt ON b.room_type_id = rt.id
+                    WHERE b.id = $1
+                    "#,
+                )
+                .bind(booking_uuid)
+                .fetch_one(self.pool())
+                .await?;
+
+            let guests = GuestCount::new(
+                data.adults.unwrap_or(adults),
+                data.children.unwrap_or(children),
+            )?;
+            guests.ensure_fits(&room_type_name, max_guests)?;
+            Some(guests)
+        } else {
+            None
+        };
+
+        // The status change and the stay award commit together, so a failed
+        // award leaves the booking confirmed
+        let mut tx = self.pool().begin().await?;
+
+        // Lock the booking so two concurrent completions can't both see it
+        // confirmed
+        let status: String =
+            sqlx::query_scalar("SELECT status FROM bookings WHERE id = $1 FOR UPDATE")
+                .bind(booking_uuid)
+                .fetch_one(&mut *tx)
+                .await?;
+        if status.parse::<BookingStatus>().ok() != Some(BookingStatus::Confirmed) {
+            return Err(AppError::Conflict(
+                "Booking is no longer confirmed".to_string(),
+            ));
+        }
+
         // Update booking
-        let booking = sqlx::query_as!(
-            Booking,
+        let mut booking = sqlx::query_as::<_, Booking>(
             r#"
             UPDATE bookings SET
+                status = CASE WHEN $9 THEN 'completed' ELSE status END,
                 check_in_date = COALESCE($2, check_in_date),
                 check_out_date = COALESCE($3, check_out_date),
                 num_guests = COALESCE($4, num_guests),
-                notes = COALESCE($5, notes),
-                total_price = COALESCE($6, total_price),
+                adults = COALESCE($5, adults),
+                children = COALESCE($6, children),
+                notes = COALESCE($7, notes),
+                total_price = COALESCE($8, total_price),
                 updated_at = NOW()
             WHERE id = $1
             RETURNING
                 id, user_id, room_id, room_type_id,
                 check_in_date, check_out_date, num_guests,
-                total_price, COALESCE(points_earned, 0) as "points_earned!", status,
+                total_price, COALESCE(points_earned, 0) as points_earned, status,
                 cancelled_at, cancellation_reason,
-                notes, created_at as "created_at!", updated_at as "updated_at!",
+                notes, created_at, updated_at,
                 NULL::text as room_number, NULL::text as room_type_name,
                 NULL::text as user_email, NULL::text as user_name
             "#,
-            booking_uuid,
-            data.check_in_date,
-            data.check_out_date,
-            data.num_guests,
-            data.notes.as_deref(),
-            data.total_amount,
         )
-        .fetch_one(self.pool())
+        .bind(booking_uuid)
+        .bind(data.check_in_date)
+        .bind(data.check_out_date)
+        .bind(guests.map(|g| g.total()))
+        .bind(guests.map(|g| g.adults))
+        .bind(guests.map(|g| g.children))
+        .bind(data.notes.as_deref())
+        .bind(data.total_amount)
+        .bind(completing)
+        .fetch_one(&mut *tx)
         .await?;
 
-        tracing::info!(booking_id = booking_id, "Booking updated");
+        if completing {
+            let room_type_name = existing.room_type_name.as_deref().unwrap_or("Room");
+            booking.points_earned = self
+                .award_booking_stay(&mut tx, &booking, room_type_name)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        tracing::info!(
+            booking_id = booking_id,
+            completed = completing,
+            "Booking updated"
+        );
 
         Ok(BookingResponse::from(booking))
     }
@@ -760,42 +950,56 @@ impl BookingService for BookingServiceImpl {
             .await?
             .ok_or_else(|| AppError::NotFound("Booking not found".to_string()))?;
 
+        // Cancelling again changes nothing, so a retry can't reverse the
+        // stay twice
         if existing.status == BookingStatus::Cancelled {
-            return Err(AppError::BadRequest(
-                "Booking is already cancelled".to_string(),
-            ));
+            return Ok(existing);
         }
 
-        if existing.status == BookingStatus::Completed {
-            return Err(AppError::BadRequest(
-                "Cannot cancel a completed booking".to_string(),
-            ));
-        }
+        // The status change and any reversal commit together
+        let mut tx = self.pool().begin().await?;
 
-        // Update booking status
-        let booking = sqlx::query_as!(
-            Booking,
+        // Guarded on the status so only one of two concurrent cancellations
+        // gets a row back
+        let booking = sqlx::query_as::<_, Booking>(
             r#"
             UPDATE bookings SET
                 status = 'cancelled',
                 cancelled_at = NOW(),
                 updated_at = NOW()
-            WHERE id = $1
+            WHERE id = $1 AND status IN ('confirmed', 'completed')
             RETURNING
                 id, user_id, room_id, room_type_id,
                 check_in_date, check_out_date, num_guests,
-                total_price, COALESCE(points_earned, 0) as "points_earned!", status,
+                total_price, COALESCE(points_earned, 0) as points_earned, status,
                 cancelled_at, cancellation_reason,
-                notes, created_at as "created_at!", updated_at as "updated_at!",
+                notes, created_at, updated_at,
                 NULL::text as room_number, NULL::text as room_type_name,
                 NULL::text as user_email, NULL::text as user_name
             "#,
-            booking_uuid,
         )
-        .fetch_one(self.pool())
+        .bind(booking_uuid)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Booking can no longer be cancelled".to_string()))?;
+
+        // A completed stay has been credited; take its nights and points back
+        let reversal = reverse_stay_points(
+            &mut tx,
+            booking.user_id,
+            &format!("BOOKING-{}", booking.id),
+            "Cancelled booking: stay reversed",
+        )
         .await?;
 
-        tracing::info!(booking_id = booking_id, "Booking cancelled");
+        tx.commit().await?;
+
+        tracing::info!(
+            booking_id = booking_id,
+            points_reversed = reversal.map_or(0, |r| r.points_deducted),
+            nights_reversed = reversal.map_or(0, |r| r.nights_deducted),
+            "Booking cancelled"
+        );
 
         Ok(BookingResponse::from(booking))
     }
@@ -824,51 +1028,40 @@ impl BookingService for BookingServiceImpl {
             ));
         }
 
-        // Update booking status to completed
-        let booking = sqlx::query_as!(
-            Booking,
+        let mut tx = self.pool().begin().await?;
+
+        // Guarded on the status so a concurrent completion awards nothing
+        let mut booking = sqlx::query_as::<_, Booking>(
             r#"
             UPDATE bookings SET
                 status = 'completed',
                 updated_at = NOW()
-            WHERE id = $1
+            WHERE id = $1 AND status = 'confirmed'
             RETURNING
                 id, user_id, room_id, room_type_id,
                 check_in_date, check_out_date, num_guests,
-                total_price, COALESCE(points_earned, 0) as "points_earned!", status,
+                total_price, COALESCE(points_earned, 0) as points_earned, status,
                 cancelled_at, cancellation_reason,
-                notes, created_at as "created_at!", updated_at as "updated_at!",
+                notes, created_at, updated_at,
                 NULL::text as room_number, NULL::text as room_type_name,
                 NULL::text as user_email, NULL::text as user_name
             "#,
-            booking_uuid,
         )
-        .fetch_one(self.pool())
-        .await?;
+        .bind(booking_uuid)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Booking is no longer confirmed".to_string()))?;
 
-        let response = BookingResponse::from(booking);
-
-        // Award points and nights to the user
+        // Award points and nights to the user; a failure rolls back the
+        // status change
         let room_type_name = existing.room_type_name.as_deref().unwrap_or("Room");
+        booking.points_earned = self
+            .award_booking_stay(&mut tx, &booking, room_type_name)
+            .await?;
 
-        // Try to award points; log error but don't fail if it doesn't work
-        if let Err(e) = self
-            .award_booking_points(
-                response.user_id,
-                response.points_earned,
-                response.nights_count,
-                booking_uuid,
-                room_type_name,
-            )
-            .await
-        {
-            tracing::error!(
-                booking_id = booking_id,
-                user_id = %response.user_id,
-                error = %e,
-                "Failed to award points for completed booking"
-            );
-        }
+        tx.commit().await?;
+
+        let response = BookingResponse::from(booking);
 
         tracing::info!(
             booking_id = booking_id,
@@ -942,6 +1135,231 @@ impl BookingService for BookingServiceImpl {
     }
 }
 
+// ==================== Payment Status ====================
+
+/// Payment status a SlipOK verification outcome moves a booking towards.
+///
+/// Only a verified slip marks the booking paid. A failed verification (or
+/// an exhausted SlipOK quota) keeps it at `pending_verification` so the
+/// slip stays in the admin review queue instead of looking unpaid.
+pub fn payment_status_for_verification(status: VerificationStatus) -> PaymentStatus {
+    match status {
+        VerificationStatus::Verified => PaymentStatus::Paid,
+        VerificationStatus::Failed | VerificationStatus::QuotaExceeded => {
+            PaymentStatus::PendingVerification
+        },
+    }
+}
+
+/// Value written to `booking_slips.slipok_status` for a verification outcome
+fn slipok_status_column(status: VerificationStatus) -> &'static str {
+    match status {
+        VerificationStatus::Verified => "verified",
+        VerificationStatus::Failed => "failed",
+        VerificationStatus::QuotaExceeded => "quota_exceeded",
+    }
+}
+
+/// Move a booking from `unpaid` to `pending_verification` after a slip is
+/// attached to it.
+///
+/// The UPDATE is guarded on `payment_status = 'unpaid'`, so attaching
+/// another slip to a booking that is already pending or paid is a no-op.
+pub async fn mark_payment_pending_verification<'c, E>(
+    executor: E,
+    booking_id: Uuid,
+) -> Result<(), AppError>
+where
+    E: sqlx::PgExecutor<'c>,
+{
+    sqlx::query(
+        r#"
+        UPDATE bookings
+        SET payment_status = 'pending_verification', updated_at = NOW()
+        WHERE id = $1 AND payment_status = 'unpaid'
+        "#,
+    )
+    .bind(booking_id)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Whether `booking_id`'s payment has been verified, by SlipOK (the booking
+/// is `paid`) or by an admin reviewing one of its slips.
+pub async fn has_verified_payment<'c, E>(executor: E, booking_id: Uuid) -> Result<bool, AppError>
+where
+    E: sqlx::PgExecutor<'c>,
+{
+    let verified: Option<bool> = sqlx::query_scalar(
+        r#"
+        SELECT b.payment_status = 'paid'
+            OR EXISTS (
+                SELECT 1 FROM booking_slips s
+                WHERE s.booking_id = b.id AND s.admin_status = 'verified'
+            )
+        FROM bookings b
+        WHERE b.id = $1
+        "#,
+    )
+    .bind(booking_id)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(verified.unwrap_or(false))
+}
+
+/// Record a SlipOK verification result against a slip and its booking.
+///
+/// Writes the outcome to the `booking_slips` row and advances the parent
+/// booking's `payment_status` via [`PaymentStatus::advance_to`], so the
+/// status never moves backwards. Both writes happen in one transaction with
+/// the booking row locked. Returns the booking's resulting payment status.
+///
+/// A verified slip also releases any points held as pending for the booking
+/// (see [`release_pending_points_for_booking`]).
+///
+/// When webhooks are configured, a verified or rejected slip also queues a
+/// `slip.verified` / `slip.rejected` event for the PMS in the same
+/// transaction (see [`slip_webhook_payload`]), so it's only sent once this
+/// state has committed. A quota error isn't a verdict and sends nothing.
+pub async fn apply_slip_verification(
+    pool: &PgPool,
+    slip_id: Uuid,
+    result: &SlipVerificationResult,
+    webhooks: &WebhookConfig,
+) -> Result<PaymentStatus, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let verified = result.status == VerificationStatus::Verified;
+    let response = serde_json::to_value(result).ok();
+
+    let (booking_id,): (Uuid,) = sqlx::query_as(
+        r#"
+        UPDATE booking_slips
+        SET slipok_status = $2,
+            slipok_verified_at = CASE WHEN $3 THEN NOW() ELSE slipok_verified_at END,
+            slipok_response = $4,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING booking_id
+        "#,
+    )
+    .bind(slip_id)
+    .bind(slipok_status_column(result.status))
+    .bind(verified)
+    .bind(response)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Slip {}", slip_id)))?;
+
+    let (current, total_price): (String, Decimal) =
+        sqlx::query_as("SELECT payment_status, total_price FROM bookings WHERE id = $1 FOR UPDATE")
+            .bind(booking_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+    let current: PaymentStatus = current.parse().unwrap_or_default();
+    let next = current.advance_to(payment_status_for_verification(result.status));
+
+    if next != current {
+        sqlx::query("UPDATE bookings SET payment_status = $2, updated_at = NOW() WHERE id = $1")
+            .bind(booking_id)
+            .bind(next.as_str())
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    // Points held back while the payment was unverified become available
+    if next == PaymentStatus::Paid {
+        release_pending_points_for_booking(&mut tx, booking_id).await?;
+    }
+
+    if webhooks.is_configured() {
+        if let Some(event_type) = slip_webhook_event(result.status) {
+            let payload = slip_webhook_payload(slip_id, booking_id, total_price, next, result);
+            enqueue_webhook(&mut *tx, event_type, payload).await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    tracing::info!(
+        slip_id = %slip_id,
+        booking_id = %booking_id,
+        slipok_status = slipok_status_column(result.status),
+        payment_status = %next,
+        "Applied SlipOK verification to booking"
+    );
+
+    Ok(next)
+}
+
+/// The webhook event a SlipOK outcome is reported as, if any
+fn slip_webhook_event(status: VerificationStatus) -> Option<WebhookEventType> {
+    match status {
+        VerificationStatus::Verified => Some(WebhookEventType::SlipVerified),
+        VerificationStatus::Failed => Some(WebhookEventType::SlipRejected),
+        VerificationStatus::QuotaExceeded => None,
+    }
+}
+
+/// Body of a `slip.verified` / `slip.rejected` event: enough for the PMS to
+/// match the payment to its booking (the booking ID is the reference passed
+/// to SlipOK) and compare the slip amount with what's owed.
+fn slip_webhook_payload(
+    slip_id: Uuid,
+    booking_id: Uuid,
+    booking_total: Decimal,
+    payment_status: PaymentStatus,
+    result: &SlipVerificationResult,
+) -> serde_json::Value {
+    serde_json::json!({
+        "bookingId": booking_id,
+        "bookingReference": booking_id.to_string(),
+        "slipId": slip_id,
+        "status": slipok_status_column(result.status),
+        "paymentStatus": payment_status.as_str(),
+        "amount": result.amount.map(|amount| amount.to_string()),
+        "bookingTotal": booking_total.to_string(),
+        "currency": "THB",
+        "transactionRef": result.transaction_id,
+        "transactionDate": result.transaction_date,
+        "errorCode": result.error_code,
+        "errorMessage": result.error_message,
+    })
+}
+
+/// Verify a slip image with SlipOK and apply the result to its booking.
+///
+/// Thin glue between [`SlipOKService::verify_slip_with_context`] and
+/// [`apply_slip_verification`]; callers run it off the request path since
+/// the SlipOK round trip can take several seconds. `force` skips a cached
+/// SlipOK result (see [`SlipOKService::reverify_slip_with_context`]).
+pub async fn verify_booking_slip(
+    pool: &PgPool,
+    slipok: &SlipOKService,
+    slip_id: Uuid,
+    booking_id: Uuid,
+    slip_image: Bytes,
+    webhooks: &WebhookConfig,
+    force: bool,
+) -> Result<PaymentStatus, AppError> {
+    let booking_ref = booking_id.to_string();
+    let result = if force {
+        slipok
+            .reverify_slip_with_context(slip_image, Some(&booking_ref))
+            .await?
+    } else {
+        slipok
+            .verify_slip_with_context(slip_image, Some(&booking_ref))
+            .await?
+    };
+
+    apply_slip_verification(pool, slip_id, &result, webhooks).await
+}
+
 // ==================== Tests ====================
 
 #[cfg(test)]
@@ -984,6 +1402,54 @@ mod tests {
         assert!(filters.offset.is_none());
     }
 
+    #[test]
+    fn test_slip_webhook_event_and_payload() {
+        use rust_decimal_macros::dec;
+
+        assert_eq!(
+            slip_webhook_event(VerificationStatus::Verified),
+            Some(WebhookEventType::SlipVerified)
+        );
+        assert_eq!(
+            slip_webhook_event(VerificationStatus::Failed),
+            Some(WebhookEventType::SlipRejected)
+        );
+        assert_eq!(slip_webhook_event(VerificationStatus::QuotaExceeded), None);
+
+        let slip_id = Uuid::new_v4();
+        let booking_id = Uuid::new_v4();
+        let result = SlipVerificationResult {
+            success: true,
+            status: VerificationStatus::Verified,
+            amount: Some(dec!(1500.00)),
+            sender_name: None,
+            receiver_name: None,
+            transaction_date: None,
+            transaction_id: Some("TXN123".to_string()),
+            bank_code: None,
+            receiving_bank_code: None,
+            error_code: None,
+            error_message: None,
+            raw_response: None,
+        };
+
+        let payload = slip_webhook_payload(
+            slip_id,
+            booking_id,
+            dec!(1500.00),
+            PaymentStatus::Paid,
+            &result,
+        );
+        assert_eq!(payload["bookingReference"], booking_id.to_string());
+        assert_eq!(payload["slipId"], slip_id.to_string());
+        assert_eq!(payload["status"], "verified");
+        assert_eq!(payload["paymentStatus"], "paid");
+        assert_eq!(payload["amount"], "1500.00");
+        assert_eq!(payload["bookingTotal"], "1500.00");
+        assert_eq!(payload["transactionRef"], "TXN123");
+        assert!(payload["errorCode"].is_null());
+    }
+
     #[test]
     fn test_booking_response_from_booking() {
         use rust_decimal_macros::dec;
@@ -1016,6 +1482,30 @@ mod tests {
         assert_eq!(response.room_number, Some("101".to_string()));
     }
 
+    #[test]
+    fn test_payment_status_for_verification() {
+        assert_eq!(
+            payment_status_for_verification(VerificationStatus::Verified),
+            PaymentStatus::Paid
+        );
+        // A failed verification must not walk the booking back to unpaid
+        assert_eq!(
+            payment_status_for_verification(VerificationStatus::Failed),
+            PaymentStatus::PendingVerification
+        );
+        assert_eq!(
+            payment_status_for_verification(VerificationStatus::QuotaExceeded),
+            PaymentStatus::PendingVerification
+        );
+    }
+
+    #[test]
+    fn test_failed_verification_keeps_paid_booking_paid() {
+        let next = PaymentStatus::Paid
+            .advance_to(payment_status_for_verification(VerificationStatus::Failed));
+        assert_eq!(next, PaymentStatus::Paid);
+    }
+
     #[test]
     fn test_create_booking_dto() {
         use rust_decimal_macros::dec;
@@ -1025,14 +1515,55 @@ mod tests {
             room_type_id: Uuid::new_v4(),
             check_in_date: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
             check_out_date: NaiveDate::from_ymd_opt(2024, 6, 5).unwrap(),
-            num_guests: 2,
+            adults: 2,
+            children: 0,
             notes: Some("Early check-in requested".to_string()),
             total_amount: dec!(5000),
             currency: Some("THB".to_string()),
             special_requests: None,
+            source: BookingSource::Ota,
+            channel: BookingChannel::default(),
         };
 
-        assert_eq!(dto.num_guests, 2);
+        assert_eq!(dto.adults, 2);
+        assert_eq!(dto.channel, BookingChannel::Web);
         assert_eq!((dto.check_out_date - dto.check_in_date).num_days(), 4);
     }
+
+    #[test]
+    fn test_guest_count_requires_an_adult() {
+        assert!(GuestCount::new(1, 0).is_ok());
+        assert!(GuestCount::new(0, 2).is_err());
+        assert!(GuestCount::new(2, -1).is_err());
+    }
+
+    #[test]
+    fn test_guest_count_capacity() {
+        let guests = GuestCount::new(2, 1).unwrap();
+        assert_eq!(guests.total(), 3);
+        assert!(guests.ensure_fits("Deluxe", 3).is_ok());
+
+        let err = guests.ensure_fits("Standard", 2).unwrap_err();
+        assert!(err.to_string().contains("at most 2 guests"));
+        assert!(err.to_string().contains("2 adults, 1 children"));
+    }
+
+    #[test]
+    fn test_guest_count_from_total_keeps_an_adult() {
+        assert_eq!(
+            GuestCount::from_total(4, 1).unwrap(),
+            GuestCount {
+                adults: 3,
+                children: 1
+            }
+        );
+        assert_eq!(
+            GuestCount::from_total(2, 3).unwrap(),
+            GuestCount {
+                adults: 1,
+                children: 1
+            }
+        );
+        assert!(GuestCount::from_total(0, 0).is_err());
+    }
 }
@@ -5,14 +5,29 @@
 //! - User coupon assignments
 //! - Coupon redemption
 //! - Eligibility checking
+//! - Per-type amount, currency and date validation for new coupons
+//!   ([`validate_coupon_terms`])
+//! - Validity windows given as the hotel's local dates
+//!   ([`resolve_coupon_window`]) and one rule for checking them
+//!   ([`coupon_window_open`], [`coupon_expired`])
+//! - Auto-generated unique coupon codes ([`generate_unique_coupon_code`])
+//! - System-initiated assignment that respects coupon limits
+//!   ([`try_assign_coupon`])
+//! - Stacking several coupons on one transaction
+//!   ([`validate_coupon_stack`], [`apply_coupon_stack`])
+//! - Expiring coupons past their dates or limits on a schedule
+//!   ([`sweep_expired_coupons`], [`spawn_coupon_expiry_sweep`])
+//! - PNG images of user coupon QR codes ([`render_qr_png`])
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sqlx::{Connection, FromRow, PgPool};
+use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::config::{CouponConfig, CouponStacking};
 use crate::error::AppError;
 use crate::models::coupon::{
     Coupon, CouponResponse, CouponStatus, CouponType, UserCoupon, UserCouponStatus,
@@ -38,6 +53,8 @@ pub struct CouponFilters {
 /// DTO for creating a new coupon
 #[derive(Debug, Clone, Deserialize)]
 pub struct CreateCouponDto {
+    /// Coupon code; a unique one is generated when empty or omitted
+    #[serde(default)]
     pub code: String,
     pub name: String,
     pub description: Option<String>,
@@ -49,6 +66,10 @@ pub struct CreateCouponDto {
     pub maximum_discount: Option<Decimal>,
     pub valid_from: Option<DateTime<Utc>>,
     pub valid_until: Option<DateTime<Utc>>,
+    /// Local-day alternatives to `valid_from` / `valid_until` (see
+    /// [`resolve_coupon_window`])
+    pub valid_from_date: Option<NaiveDate>,
+    pub valid_until_date: Option<NaiveDate>,
     pub usage_limit: Option<i32>,
     pub usage_limit_per_user: Option<i32>,
     pub tier_restrictions: Option<Vec<String>>,
@@ -70,6 +91,10 @@ pub struct UpdateCouponDto {
     pub maximum_discount: Option<Decimal>,
     pub valid_from: Option<DateTime<Utc>>,
     pub valid_until: Option<DateTime<Utc>>,
+    /// Local-day alternatives to `valid_from` / `valid_until` (see
+    /// [`resolve_coupon_window`])
+    pub valid_from_date: Option<NaiveDate>,
+    pub valid_until_date: Option<NaiveDate>,
     pub usage_limit: Option<i32>,
     pub usage_limit_per_user: Option<i32>,
     pub tier_restrictions: Option<Vec<String>>,
@@ -232,23 +257,42 @@ pub trait CouponService: Send + Sync {
 
     /// Check if a user is eligible for a coupon
     async fn check_eligibility(&self, user_id: Uuid, coupon_id: Uuid) -> Result<bool, AppError>;
+
+    /// Expire coupons past `valid_until`, exhaust those at their usage
+    /// limit, and expire the user coupons that can no longer be used
+    async fn sweep_expired_coupons(&self) -> Result<CouponSweep, AppError>;
 }
 
 /// Implementation of the CouponService trait
 pub struct CouponServiceImpl {
     pool: PgPool,
+    config: CouponConfig,
 }
 
 impl CouponServiceImpl {
     /// Create a new CouponServiceImpl instance
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            config: CouponConfig::default(),
+        }
+    }
+
+    /// Use `config` (allowed/default currencies) instead of the defaults
+    pub fn with_config(mut self, config: CouponConfig) -> Self {
+        self.config = config;
+        self
     }
 
     /// Get a reference to the database pool
     fn pool(&self) -> &PgPool {
         &self.pool
     }
+
+    /// Hotel-local UTC offset used to interpret date-only coupon bounds
+    fn local_offset(&self) -> Result<FixedOffset, AppError> {
+        self.config.local_offset().map_err(AppError::Internal)
+    }
 }
 
 #[async_trait]
@@ -419,10 +463,43 @@ impl CouponService for CouponServiceImpl {
         data: CreateCouponDto,
         created_by: Uuid,
     ) -> Result<Coupon, AppError> {
+        let currency = data
+            .currency
+            .clone()
+            .unwrap_or_else(|| self.config.default_currency.clone())
+            .to_ascii_uppercase();
+        let (valid_from, valid_until) = resolve_coupon_window(
+            data.valid_from,
+            data.valid_from_date,
+            data.valid_until,
+            data.valid_until_date,
+            self.local_offset()?,
+        )?;
+        let valid_from = valid_from.unwrap_or_else(Utc::now);
+
+        validate_coupon_terms(
+            &CouponTerms {
+                coupon_type: data.coupon_type,
+                value: data.value,
+                currency: Some(&currency),
+                minimum_spend: data.minimum_spend,
+                maximum_discount: data.maximum_discount,
+                valid_from: Some(valid_from),
+                valid_until,
+            },
+            &self.config,
+        )?;
+
+        let code = if data.code.trim().is_empty() {
+            generate_unique_coupon_code(self.pool(), &self.config).await?
+        } else {
+            data.code.clone()
+        };
+
         // Check if code already exists
         let existing = sqlx::query_scalar!(
             r#"SELECT COUNT(*) as "count!" FROM coupons WHERE code = $1"#,
-            &data.code,
+            &code,
         )
         .fetch_one(self.pool())
         .await
@@ -445,8 +522,6 @@ impl CouponService for CouponServiceImpl {
         let available_languages = serde_json::to_value(vec![&original_language])
             .unwrap_or(serde_json::Value::Array(vec![]));
 
-        let currency = data.currency.unwrap_or_else(|| "THB".to_string());
-        let valid_from = data.valid_from.unwrap_or_else(Utc::now);
         let usage_limit_per_user = data.usage_limit_per_user.unwrap_or(1);
 
         let row = sqlx::query!(
@@ -465,7 +540,7 @@ impl CouponService for CouponServiceImpl {
                       created_by, created_at, updated_at, original_language,
                       available_languages, last_translated, translation_status
             "#,
-            &data.code,
+            &code,
             &data.name,
             data.description.as_deref(),
             data.terms_and_conditions.as_deref(),
@@ -475,7 +550,7 @@ impl CouponService for CouponServiceImpl {
             data.minimum_spend,
             data.maximum_discount,
             valid_from,
-            data.valid_until,
+            valid_until,
             data.usage_limit,
             usage_limit_per_user,
             tier_restrictions_json as Option<serde_json::Value>,
@@ -517,7 +592,7 @@ impl CouponService for CouponServiceImpl {
         };
 
         tracing::info!(
-            coupon_code = %data.code,
+            coupon_code = %code,
             created_by = %created_by,
             "Coupon created"
         );
@@ -555,6 +630,24 @@ impl CouponService for CouponServiceImpl {
             }
         }
 
+        let (valid_from, valid_until) = resolve_coupon_window(
+            data.valid_from,
+            data.valid_from_date,
+            data.valid_until,
+            data.valid_until_date,
+            self.local_offset()?,
+        )?;
+        if let (Some(from), Some(until)) = (
+            valid_from.or(existing.valid_from),
+            valid_until.or(existing.valid_until),
+        ) {
+            if (valid_from.is_some() || valid_until.is_some()) && from >= until {
+                return Err(AppError::Validation(
+                    "Valid until must be after valid from".to_string(),
+                ));
+            }
+        }
+
         let tier_restrictions_json = data
             .tier_restrictions
             .map(|t| serde_json::to_value(t).unwrap_or(serde_json::Value::Array(vec![])));
@@ -598,8 +691,8 @@ impl CouponService for CouponServiceImpl {
             data.currency.as_deref(),
             data.minimum_spend,
             data.maximum_discount,
-            data.valid_from,
-            data.valid_until,
+            valid_from,
+            valid_until,
             data.usage_limit,
             data.usage_limit_per_user,
             tier_restrictions_json as Option<serde_json::Value>,
@@ -837,41 +930,26 @@ impl CouponService for CouponServiceImpl {
     }
 
     async fn redeem_coupon(&self, user_coupon_id: Uuid) -> Result<UserCoupon, AppError> {
-        // Get the user coupon
-        let uc_row = sqlx::query!(
+        let mut tx = self.pool().begin().await?;
+
+        // Lock the user coupon so a second redemption of the same one waits
+        // here and then sees it already used
+        let user_coupon: UserCoupon = sqlx::query_as(
             r#"
-            SELECT id, user_id, coupon_id,
-                   status as "status: UserCouponStatus",
-                   qr_code, used_at,
+            SELECT id, user_id, coupon_id, status, qr_code, used_at,
                    used_by_admin, redemption_location, redemption_details,
                    assigned_by, assigned_reason, expires_at, created_at, updated_at
             FROM user_coupons
             WHERE id = $1
+            FOR UPDATE
             "#,
-            user_coupon_id,
         )
-        .fetch_optional(self.pool())
+        .bind(user_coupon_id)
+        .fetch_optional(&mut *tx)
         .await
         .map_err(|e| AppError::DatabaseQuery(format!("Failed to fetch user coupon: {}", e)))?
         .ok_or_else(|| AppError::NotFound("User coupon not found".to_string()))?;
 
-        let user_coupon = UserCoupon {
-            id: uc_row.id,
-            user_id: uc_row.user_id,
-            coupon_id: uc_row.coupon_id,
-            status: uc_row.status,
-            qr_code: uc_row.qr_code,
-            used_at: uc_row.used_at,
-            used_by_admin: uc_row.used_by_admin,
-            redemption_location: uc_row.redemption_location,
-            redemption_details: uc_row.redemption_details,
-            assigned_by: uc_row.assigned_by,
-            assigned_reason: uc_row.assigned_reason,
-            expires_at: uc_row.expires_at,
-            created_at: uc_row.created_at,
-            updated_at: uc_row.updated_at,
-        };
-
         // Check if coupon is available
         if user_coupon.status != Some(UserCouponStatus::Available) {
             return Err(AppError::BadRequest(
@@ -880,55 +958,40 @@ impl CouponService for CouponServiceImpl {
         }
 
         // Check if expired
-        if let Some(expires_at) = user_coupon.expires_at {
-            if expires_at < Utc::now() {
-                return Err(AppError::BadRequest("Coupon has expired".to_string()));
-            }
+        if coupon_expired(user_coupon.expires_at, Utc::now()) {
+            return Err(AppError::BadRequest("Coupon has expired".to_string()));
         }
 
-        // Update the user coupon status
-        let upd_row = sqlx::query!(
+        check_tier_restrictions(&mut *tx, user_coupon.user_id, user_coupon.coupon_id).await?;
+
+        // Count the redemption first: the limit check and the increment are
+        // one UPDATE on the coupon row, so concurrent redemptions of the
+        // coupon queue on its lock and only the ones that fit succeed. A
+        // refusal returns before the user coupon is touched, and dropping
+        // `tx` rolls back the row lock.
+        increment_coupon_usage(
+            &mut *tx,
+            user_coupon.coupon_id,
+            self.config.exhaust_at_limit,
+        )
+        .await?;
+
+        let updated_coupon: UserCoupon = sqlx::query_as(
             r#"
             UPDATE user_coupons
             SET status = 'used', used_at = NOW(), updated_at = NOW()
-            WHERE id = $1
-            RETURNING id, user_id, coupon_id,
-                      status as "status: UserCouponStatus",
-                      qr_code, used_at,
+            WHERE id = $1 AND status = 'available'
+            RETURNING id, user_id, coupon_id, status, qr_code, used_at,
                       used_by_admin, redemption_location, redemption_details,
                       assigned_by, assigned_reason, expires_at, created_at, updated_at
             "#,
-            user_coupon_id,
         )
-        .fetch_one(self.pool())
+        .bind(user_coupon_id)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| AppError::DatabaseQuery(format!("Failed to redeem coupon: {}", e)))?;
 
-        let updated_coupon = UserCoupon {
-            id: upd_row.id,
-            user_id: upd_row.user_id,
-            coupon_id: upd_row.coupon_id,
-            status: upd_row.status,
-            qr_code: upd_row.qr_code,
-            used_at: upd_row.used_at,
-            used_by_admin: upd_row.used_by_admin,
-            redemption_location: upd_row.redemption_location,
-            redemption_details: upd_row.redemption_details,
-            assigned_by: upd_row.assigned_by,
-            assigned_reason: upd_row.assigned_reason,
-            expires_at: upd_row.expires_at,
-            created_at: upd_row.created_at,
-            updated_at: upd_row.updated_at,
-        };
-
-        // Increment the coupon's used count
-        sqlx::query!(
-            "UPDATE coupons SET used_count = COALESCE(used_count, 0) + 1, updated_at = NOW() WHERE id = $1",
-            user_coupon.coupon_id,
-        )
-        .execute(self.pool())
-        .await
-        .map_err(|e| AppError::DatabaseQuery(format!("Failed to update coupon used count: {}", e)))?;
+        tx.commit().await?;
 
         tracing::info!(
             user_coupon_id = %user_coupon_id,
@@ -950,16 +1013,8 @@ impl CouponService for CouponServiceImpl {
         }
 
         // Check validity period
-        let now = Utc::now();
-        if let Some(valid_from) = coupon.valid_from {
-            if now < valid_from {
-                return Ok(false);
-            }
-        }
-        if let Some(valid_until) = coupon.valid_until {
-            if now > valid_until {
-                return Ok(false);
-            }
+        if !coupon_window_open(coupon.valid_from, coupon.valid_until, Utc::now()) {
+            return Ok(false);
         }
 
         // Check global usage limit
@@ -1023,12 +1078,916 @@ impl CouponService for CouponServiceImpl {
 
         Ok(true)
     }
+
+    async fn sweep_expired_coupons(&self) -> Result<CouponSweep, AppError> {
+        sweep_expired_coupons(self.pool()).await
+    }
+}
+
+/// The amount, currency and date terms of a coupon being created
+#[derive(Debug, Clone, Copy)]
+pub struct CouponTerms<'a> {
+    pub coupon_type: CouponType,
+    pub value: Option<Decimal>,
+    pub currency: Option<&'a str>,
+    pub minimum_spend: Option<Decimal>,
+    pub maximum_discount: Option<Decimal>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
+/// Validate a new coupon's terms, reporting every problem by field.
+///
+/// `value` is governed by the coupon type:
+/// - `percentage`: required, `0 < value <= 100`
+/// - `fixed_amount`: required and positive, and the coupon needs a
+///   currency (pass the resolved one, i.e. after defaulting)
+/// - `bogo`, `free_upgrade`, `free_service`: the benefit is the item
+///   itself, so `value` may be null; when given (a cap on the free item's
+///   worth) it must be positive
+///
+/// For every type, a currency must be one of `COUPON_ALLOWED_CURRENCIES`,
+/// `minimum_spend` / `maximum_discount` can't be negative, and `valid_from`
+/// must be before `valid_until` when both are set.
+pub fn validate_coupon_terms(
+    terms: &CouponTerms<'_>,
+    config: &CouponConfig,
+) -> Result<(), AppError> {
+    let mut details: HashMap<String, Vec<String>> = HashMap::new();
+    let mut reject = |field: &str, message: &str| {
+        details
+            .entry(field.to_string())
+            .or_default()
+            .push(message.to_string());
+    };
+
+    match terms.coupon_type {
+        CouponType::Percentage => match terms.value {
+            None => reject("value", "Value is required for percentage coupons"),
+            Some(v) if v <= Decimal::ZERO || v > Decimal::from(100) => reject(
+                "value",
+                "Percentage value must be greater than 0 and at most 100",
+            ),
+            Some(_) => {},
+        },
+        CouponType::FixedAmount => {
+            match terms.value {
+                None => reject("value", "Value is required for fixed_amount coupons"),
+                Some(v) if v <= Decimal::ZERO => {
+                    reject("value", "Value must be positive for fixed_amount coupons")
+                },
+                Some(_) => {},
+            }
+            if !matches!(terms.currency, Some(c) if !c.trim().is_empty()) {
+                reject("currency", "Currency is required for fixed_amount coupons");
+            }
+        },
+        CouponType::Bogo | CouponType::FreeUpgrade | CouponType::FreeService => {
+            if matches!(terms.value, Some(v) if v <= Decimal::ZERO) {
+                reject("value", "Value must be positive when set");
+            }
+        },
+    }
+
+    if let Some(currency) = terms.currency.filter(|c| !c.trim().is_empty()) {
+        if !config.is_allowed_currency(currency) {
+            reject(
+                "currency",
+                &format!(
+                    "Currency must be one of: {}",
+                    config.allowed_currencies().join(", ")
+                ),
+            );
+        }
+    }
+
+    if matches!(terms.minimum_spend, Some(v) if v < Decimal::ZERO) {
+        reject("minimum_spend", "Minimum spend cannot be negative");
+    }
+    if matches!(terms.maximum_discount, Some(v) if v < Decimal::ZERO) {
+        reject("maximum_discount", "Maximum discount cannot be negative");
+    }
+
+    if let (Some(from), Some(until)) = (terms.valid_from, terms.valid_until) {
+        if from >= until {
+            reject("valid_until", "Valid until must be after valid from");
+        }
+    }
+
+    if details.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::ValidationWithDetails {
+            message: "Invalid coupon terms".to_string(),
+            details,
+        })
+    }
+}
+
+/// Start of `date` at the hotel's local midnight, in UTC
+pub fn local_day_start(date: NaiveDate, offset: FixedOffset) -> DateTime<Utc> {
+    let local_midnight = date.and_time(NaiveTime::MIN);
+    (local_midnight - Duration::seconds(offset.local_minus_utc().into())).and_utc()
+}
+
+/// A coupon's validity bounds from what an admin sent: exact UTC
+/// timestamps (`valid_from` / `valid_until`) or the hotel's local days
+/// (`valid_from_date` / `valid_until_date`), in `offset`.
+///
+/// A local `valid_from_date` starts at local midnight that day; a
+/// `valid_until_date` runs to the end of that day, so the stored bound is
+/// the following local midnight. Giving a timestamp and a date for the same
+/// bound is rejected.
+pub fn resolve_coupon_window(
+    valid_from: Option<DateTime<Utc>>,
+    valid_from_date: Option<NaiveDate>,
+    valid_until: Option<DateTime<Utc>>,
+    valid_until_date: Option<NaiveDate>,
+    offset: FixedOffset,
+) -> Result<(Option<DateTime<Utc>>, Option<DateTime<Utc>>), AppError> {
+    let mut details: HashMap<String, Vec<String>> = HashMap::new();
+    if valid_from.is_some() && valid_from_date.is_some() {
+        details.insert(
+            "valid_from_date".to_string(),
+            vec!["Give either valid_from or valid_from_date, not both".to_string()],
+        );
+    }
+    if valid_until.is_some() && valid_until_date.is_some() {
+        details.insert(
+            "valid_until_date".to_string(),
+            vec!["Give either valid_until or valid_until_date, not both".to_string()],
+        );
+    }
+    if !details.is_empty() {
+        return Err(AppError::ValidationWithDetails {
+            message: "Invalid coupon dates".to_string(),
+            details,
+        });
+    }
+
+    let from = valid_from.or_else(|| valid_from_date.map(|date| local_day_start(date, offset)));
+    let until = valid_until.or_else(|| {
+        valid_until_date
+            .and_then(|date| date.succ_opt())
+            .map(|date| local_day_start(date, offset))
+    });
+    Ok((from, until))
+}
+
+/// Whether a coupon is usable at `now`: from `valid_from` (inclusive) up
+/// to, but not including, `valid_until`. Every eligibility, validation and
+/// redemption check uses this rule, so they agree at the boundaries.
+pub fn coupon_window_open(
+    valid_from: Option<DateTime<Utc>>,
+    valid_until: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> bool {
+    valid_from.map_or(true, |from| from <= now) && !coupon_expired(valid_until, now)
+}
+
+/// Whether a coupon (or user coupon) ending at `expires_at` has expired
+/// by `now`; the end itself is exclusive
+pub fn coupon_expired(expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    matches!(expires_at, Some(expires_at) if expires_at <= now)
+}
+
+/// Attempts at finding an unused auto-generated coupon code before giving up
+const CODE_GENERATION_ATTEMPTS: usize = 10;
+
+/// A random coupon code: `config.code_prefix` followed by
+/// `config.code_length` characters from [`CouponConfig::code_charset`]
+/// (uppercase, without look-alike characters)
+pub fn random_coupon_code(config: &CouponConfig) -> String {
+    use rand::Rng;
+
+    let charset = config.code_charset();
+    let mut rng = rand::thread_rng();
+    let mut code = config.code_prefix.clone();
+    code.extend((0..config.code_length).map(|_| charset[rng.gen_range(0..charset.len())] as char));
+    code
+}
+
+/// Generate a coupon code that no existing coupon uses.
+///
+/// Retries on collision up to `CODE_GENERATION_ATTEMPTS` times, then fails
+/// with a conflict so the admin can supply a code or widen
+/// `COUPON_CODE_LENGTH` / `COUPON_CODE_CHARSET`. The unique index on
+/// `coupons.code` still guards the insert against a concurrent create.
+pub async fn generate_unique_coupon_code(
+    pool: &PgPool,
+    config: &CouponConfig,
+) -> Result<String, AppError> {
+    if config.code_charset().is_empty() {
+        return Err(AppError::Internal(
+            "COUPON_CODE_CHARSET has no usable characters".to_string(),
+        ));
+    }
+
+    for _ in 0..CODE_GENERATION_ATTEMPTS {
+        // Generate in sync context (rng is not Send)
+        let code = random_coupon_code(config);
+
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM coupons WHERE code = $1)")
+                .bind(&code)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| AppError::DatabaseQuery(e.to_string()))?;
+
+        if !exists {
+            return Ok(code);
+        }
+        tracing::debug!(code = %code, "Generated coupon code already in use, retrying");
+    }
+
+    Err(AppError::Conflict(
+        "Could not generate a unique coupon code; supply one instead".to_string(),
+    ))
+}
+
+/// Assign `coupon_id` to `user_id` on behalf of the system, honouring the
+/// coupon's own limits.
+///
+/// Goes through the `assign_coupon_to_user` SP, which rejects inactive or
+/// expired coupons and exhausted `usage_limit` / `usage_limit_per_user`
+/// with `RAISE EXCEPTION` (SQLSTATE P0001). That rejection is not an error
+/// for automatic grants — the member simply doesn't get the coupon — so it
+/// is reported as `Ok(None)`. The call runs in a savepoint so the failed
+/// statement doesn't abort the caller's transaction.
+pub async fn try_assign_coupon(
+    conn: &mut sqlx::PgConnection,
+    coupon_id: Uuid,
+    user_id: Uuid,
+    assigned_reason: &str,
+) -> Result<Option<Uuid>, AppError> {
+    let mut savepoint = conn.begin().await?;
+
+    let result =
+        sqlx::query_scalar::<_, Uuid>("SELECT assign_coupon_to_user($1, $2, NULL::uuid, $3)")
+            .bind(coupon_id)
+            .bind(user_id)
+            .bind(assigned_reason)
+            .fetch_one(&mut *savepoint)
+            .await;
+
+    match result {
+        Ok(user_coupon_id) => {
+            savepoint.commit().await?;
+            Ok(Some(user_coupon_id))
+        },
+        Err(sqlx::Error::Database(db_err)) if db_err.code().as_deref() == Some("P0001") => {
+            savepoint.rollback().await?;
+            tracing::info!(
+                coupon_id = %coupon_id,
+                user_id = %user_id,
+                reason = %db_err.message(),
+                "Coupon assignment skipped: coupon limits not met"
+            );
+            Ok(None)
+        },
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// A coupon's usage after counting one more redemption
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CouponUsage {
+    pub used_count: i32,
+    /// The redemption reached `usage_limit` and flipped the coupon to
+    /// `exhausted`
+    pub exhausted: bool,
+}
+
+/// Count one redemption of `coupon_id`.
+///
+/// The limit check and the increment are one `UPDATE`, so concurrent
+/// redemptions queue on the row lock and each sees the count left by the
+/// previous one: at most `usage_limit` of them succeed, and the rest get
+/// [`AppError::CouponLimitReached`]. With `exhaust_at_limit`, the
+/// redemption that reaches the limit also sets the status to `exhausted`.
+/// Run it in the same transaction that marks the user coupon used, so a
+/// refused redemption rolls that back too.
+pub async fn increment_coupon_usage<'c, E>(
+    executor: E,
+    coupon_id: Uuid,
+    exhaust_at_limit: bool,
+) -> Result<CouponUsage, AppError>
+where
+    E: sqlx::PgExecutor<'c>,
+{
+    let row: Option<(i32, bool)> = sqlx::query_as(
+        r#"
+        UPDATE coupons
+        SET used_count = COALESCE(used_count, 0) + 1,
+            status = CASE
+                WHEN $2 AND usage_limit IS NOT NULL
+                     AND COALESCE(used_count, 0) + 1 >= usage_limit
+                THEN 'exhausted'::coupon_status
+                ELSE status
+            END,
+            updated_at = NOW()
+        WHERE id = $1
+          AND (usage_limit IS NULL OR COALESCE(used_count, 0) < usage_limit)
+        RETURNING used_count, status = 'exhausted'::coupon_status
+        "#,
+    )
+    .bind(coupon_id)
+    .bind(exhaust_at_limit)
+    .fetch_optional(executor)
+    .await?;
+
+    let (used_count, exhausted) =
+        row.ok_or_else(|| AppError::CouponLimitReached("Coupon usage limit reached".to_string()))?;
+
+    if exhausted {
+        tracing::info!(coupon_id = %coupon_id, used_count, "Coupon usage limit reached");
+    }
+
+    Ok(CouponUsage {
+        used_count,
+        exhausted,
+    })
+}
+
+/// What one [`sweep_expired_coupons`] run changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CouponSweep {
+    /// Coupons moved to `expired` because `valid_until` has passed
+    pub expired_coupons: u64,
+    /// Coupons moved to `exhausted` because `used_count` reached
+    /// `usage_limit`
+    pub exhausted_coupons: u64,
+    /// Available user coupons moved to `expired`
+    pub expired_user_coupons: u64,
+}
+
+/// Bring coupon statuses in line with their dates and limits.
+///
+/// Active and paused coupons whose `valid_until` has passed become
+/// `expired`, and those whose `used_count` has reached `usage_limit`
+/// become `exhausted` (redemption does this itself unless
+/// `COUPON_EXHAUST_AT_LIMIT` is off, and a lowered limit can leave a
+/// coupon over it). Available user coupons of expired coupons, or past
+/// their own `expires_at`, become `expired`. User coupons of an exhausted
+/// coupon are left available: raising the limit makes them usable again.
+///
+/// One transaction, so a coupon never shows as expired while its user
+/// coupons are still available.
+pub async fn sweep_expired_coupons(pool: &PgPool) -> Result<CouponSweep, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let expired_coupons = sqlx::query(
+        r#"
+        UPDATE coupons
+        SET status = 'expired', updated_at = NOW()
+        WHERE status IN ('active', 'paused')
+          AND valid_until IS NOT NULL
+          AND valid_until <= NOW()
+        "#,
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let exhausted_coupons = sqlx::query(
+        r#"
+        UPDATE coupons
+        SET status = 'exhausted', updated_at = NOW()
+        WHERE status IN ('active', 'paused')
+          AND usage_limit IS NOT NULL
+          AND COALESCE(used_count, 0) >= usage_limit
+        "#,
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let expired_user_coupons = sqlx::query(
+        r#"
+        UPDATE user_coupons uc
+        SET status = 'expired', updated_at = NOW()
+        FROM coupons c
+        WHERE c.id = uc.coupon_id
+          AND uc.status = 'available'
+          AND (c.status = 'expired' OR uc.expires_at <= NOW())
+        "#,
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    tx.commit().await?;
+
+    let sweep = CouponSweep {
+        expired_coupons,
+        exhausted_coupons,
+        expired_user_coupons,
+    };
+    if sweep != CouponSweep::default() {
+        tracing::info!(
+            expired_coupons,
+            exhausted_coupons,
+            expired_user_coupons,
+            "Coupon expiry sweep updated statuses"
+        );
+    }
+    Ok(sweep)
+}
+
+/// Run [`sweep_expired_coupons`] every `period` until `shutdown` flips to
+/// `true` (or its sender is dropped).
+///
+/// The first run happens immediately, and a run in progress when shutdown
+/// is signalled finishes first. Errors are logged and the next tick
+/// retries.
+pub fn spawn_coupon_expiry_sweep(
+    pool: PgPool,
+    period: std::time::Duration,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = sweep_expired_coupons(&pool).await {
+                        tracing::error!(error = %e, "Scheduled coupon expiry sweep failed");
+                    }
+                },
+                changed = shutdown.changed() => {
+                    if changed.is_err() || *shutdown.borrow() {
+                        tracing::info!("Coupon expiry sweep worker stopped");
+                        break;
+                    }
+                },
+            }
+        }
+    })
+}
+
+/// Light modules around a QR code, as the QR specification asks for, so
+/// scanners can find its edges against any background
+const QR_QUIET_ZONE: u32 = 4;
+
+/// Render `content` as a square PNG QR code `size` pixels wide.
+///
+/// Modules are scaled by a whole number of pixels so they stay sharp, and
+/// the code is centred on a white background to fill `size`. Content too
+/// long to fit at one pixel per module gets an image just big enough.
+pub fn render_qr_png(content: &str, size: u32) -> Result<Vec<u8>, AppError> {
+    let code = qrcode::QrCode::new(content.as_bytes())
+        .map_err(|e| AppError::Internal(format!("Failed to create QR code: {}", e)))?;
+    let modules = code.width() as u32;
+    let colors = code.to_colors();
+
+    let scale = (size / (modules + 2 * QR_QUIET_ZONE)).max(1);
+    let side = size.max((modules + 2 * QR_QUIET_ZONE) * scale);
+    let offset = (side - modules * scale) / 2;
+
+    let img = image::GrayImage::from_fn(side, side, |x, y| {
+        let dark = x >= offset
+            && y >= offset
+            && (x - offset) / scale < modules
+            && (y - offset) / scale < modules
+            && colors[(((y - offset) / scale) * modules + (x - offset) / scale) as usize]
+                == qrcode::Color::Dark;
+        image::Luma([if dark { 0 } else { 255 }])
+    });
+
+    let mut buf = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut buf), image::ImageFormat::Png)
+        .map_err(|e| AppError::Internal(format!("Failed to encode QR code: {}", e)))?;
+    Ok(buf)
+}
+
+/// The tier names a coupon's `tier_restrictions` limits it to.
+///
+/// `NULL`, an empty array and anything that isn't an array of names
+/// restrict nothing, so this is empty for an unrestricted coupon.
+pub fn restricted_tiers(tier_restrictions: Option<&serde_json::Value>) -> Vec<&str> {
+    tier_restrictions
+        .and_then(|v| v.as_array())
+        .map(|tiers| tiers.iter().filter_map(|t| t.as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// Refuse a redemption of `coupon_id` by `user_id` when the coupon is
+/// restricted to tiers the user isn't currently in.
+///
+/// A member without a tier only passes unrestricted coupons.
+pub async fn check_tier_restrictions(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+    coupon_id: Uuid,
+) -> Result<(), AppError> {
+    let (tier_restrictions, user_tier): (Option<serde_json::Value>, Option<String>) =
+        sqlx::query_as(
+            r#"
+            SELECT c.tier_restrictions,
+                   (SELECT t.name
+                    FROM user_loyalty ul
+                    JOIN tiers t ON t.id = ul.tier_id
+                    WHERE ul.user_id = $2)
+            FROM coupons c
+            WHERE c.id = $1
+            "#,
+        )
+        .bind(coupon_id)
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Coupon".to_string()))?;
+
+    let tiers = restricted_tiers(tier_restrictions.as_ref());
+    if tiers.is_empty() || user_tier.is_some_and(|tier| tiers.contains(&tier.as_str())) {
+        return Ok(());
+    }
+
+    Err(AppError::Forbidden(format!(
+        "This coupon is only available to {} members",
+        tiers.join(", ")
+    )))
+}
+
+/// Members a coupon is meant for, as sent to
+/// `POST /coupons/assign-by-segment` and stored in `coupons.customer_segment`.
+///
+/// Criteria combine with AND; an omitted one doesn't narrow anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CouponSegment {
+    /// Member must currently be in one of these tiers (by name). `None`
+    /// allows any tier; an empty list, left by [`CouponSegment::narrow`]
+    /// when two tier lists don't overlap, matches nobody.
+    pub tiers: Option<Vec<String>>,
+    /// Member must have stayed at least this many nights in total
+    pub min_nights: Option<i32>,
+    /// Member's most recent completed stay must have ended within this
+    /// many days
+    pub last_stay_within_days: Option<i32>,
+}
+
+impl CouponSegment {
+    /// Parse and validate a segment sent by an admin
+    pub fn parse(value: Option<&serde_json::Value>) -> Result<Self, AppError> {
+        let mut segment: Self = match value {
+            None | Some(serde_json::Value::Null) => Self::default(),
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|e| AppError::Validation(format!("Invalid customer segment: {}", e)))?,
+        };
+
+        if segment.min_nights.is_some_and(|n| n < 0) {
+            return Err(AppError::Validation(
+                "minNights cannot be negative".to_string(),
+            ));
+        }
+        if segment.last_stay_within_days.is_some_and(|d| d < 1) {
+            return Err(AppError::Validation(
+                "lastStayWithinDays must be at least 1".to_string(),
+            ));
+        }
+
+        let tiers = segment.tiers.take().unwrap_or_default();
+        Ok(segment.with_tiers(tiers))
+    }
+
+    /// The segment a coupon restricts itself to: its `customer_segment`
+    /// narrowed by its `tier_restrictions`.
+    ///
+    /// Stored values that don't parse restrict nothing, and an empty tier
+    /// list means any tier, as in `check_eligibility`.
+    pub fn for_coupon(
+        customer_segment: Option<&serde_json::Value>,
+        tier_restrictions: Option<&serde_json::Value>,
+    ) -> Self {
+        let mut segment: Self = customer_segment
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        let tiers = segment.tiers.take().unwrap_or_default();
+
+        let restricted = restricted_tiers(tier_restrictions)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        segment
+            .with_tiers(tiers)
+            .narrow(Self::default().with_tiers(restricted))
+    }
+
+    /// Replace the tier list, treating an empty one as "any tier"
+    pub fn with_tiers(mut self, tiers: Vec<String>) -> Self {
+        self.tiers = if tiers.is_empty() { None } else { Some(tiers) };
+        self
+    }
+
+    /// Members matching both `self` and `other`
+    pub fn narrow(self, other: Self) -> Self {
+        let tiers = match (self.tiers, other.tiers) {
+            (None, tiers) | (tiers, None) => tiers,
+            (Some(ours), Some(theirs)) => Some(
+                ours.into_iter()
+                    .filter(|tier| theirs.contains(tier))
+                    .collect(),
+            ),
+        };
+
+        Self {
+            tiers,
+            min_nights: self.min_nights.max(other.min_nights),
+            last_stay_within_days: match (self.last_stay_within_days, other.last_stay_within_days) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (days, None) | (None, days) => days,
+            },
+        }
+    }
+
+    /// Whether the segment narrows anything at all
+    pub fn is_unrestricted(&self) -> bool {
+        self.tiers.is_none() && self.min_nights.is_none() && self.last_stay_within_days.is_none()
+    }
+}
+
+/// IDs of the active members in `segment`, oldest account first
+pub async fn find_segment_members<'c, E>(
+    executor: E,
+    segment: &CouponSegment,
+) -> Result<Vec<Uuid>, AppError>
+where
+    E: sqlx::PgExecutor<'c>,
+{
+    let user_ids = sqlx::query_scalar(
+        r#"
+        SELECT u.id
+        FROM users u
+        LEFT JOIN user_loyalty ul ON ul.user_id = u.id
+        LEFT JOIN tiers t ON t.id = ul.tier_id
+        WHERE COALESCE(u.is_active, true)
+          AND ($1::text[] IS NULL OR t.name = ANY($1))
+          AND ($2::int IS NULL OR COALESCE(ul.total_nights, 0) >= $2)
+          AND ($3::int IS NULL OR EXISTS (
+                SELECT 1 FROM bookings b
+                WHERE b.user_id = u.id
+                  AND b.status IN ('completed', 'checked_out')
+                  AND b.check_out_date >= CURRENT_DATE - $3
+          ))
+        ORDER BY u.created_at, u.id
+        "#,
+    )
+    .bind(&segment.tiers)
+    .bind(segment.min_nights)
+    .bind(segment.last_stay_within_days)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(user_ids)
+}
+
+/// Most coupons `POST /coupons/redeem-multiple` accepts in one request
+pub const MAX_STACKED_COUPONS: usize = 10;
+
+/// A user coupon offered for a stacked redemption, with its coupon's terms
+#[derive(Debug, Clone, FromRow)]
+pub struct StackedCoupon {
+    pub user_coupon_id: Uuid,
+    pub user_id: Uuid,
+    pub coupon_id: Uuid,
+    pub qr_code: String,
+    pub status: Option<UserCouponStatus>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub coupon_type: CouponType,
+    pub value: Option<Decimal>,
+    pub currency: Option<String>,
+    pub minimum_spend: Option<Decimal>,
+    pub maximum_discount: Option<Decimal>,
+}
+
+/// One coupon's share of a stacked discount
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StackedDiscount {
+    #[serde(rename = "userCouponId")]
+    pub user_coupon_id: Uuid,
+    #[serde(rename = "couponId")]
+    pub coupon_id: Uuid,
+    #[serde(rename = "qrCode")]
+    pub qr_code: String,
+    #[serde(rename = "type")]
+    pub coupon_type: CouponType,
+    /// The amount this coupon was applied to (after earlier coupons)
+    #[serde(rename = "amountBefore")]
+    pub amount_before: Decimal,
+    #[serde(rename = "discountAmount")]
+    pub discount_amount: Decimal,
+}
+
+/// Check that `coupons` may be redeemed together on a transaction of
+/// `original_amount` under `stacking`.
+///
+/// Every coupon must be available, unexpired and belong to the same
+/// member, meet its own `minimum_spend` against `original_amount`, and the
+/// monetary coupons must share one currency. On top of that:
+/// - `none`: a single coupon only
+/// - `one_per_type`: no two coupons of the same type
+/// - `unlimited`: any combination
+pub fn validate_coupon_stack(
+    coupons: &[StackedCoupon],
+    original_amount: Decimal,
+    stacking: CouponStacking,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    if coupons.is_empty() {
+        return Err(AppError::Validation(
+            "At least one coupon is required".to_string(),
+        ));
+    }
+    if coupons.len() > MAX_STACKED_COUPONS {
+        return Err(AppError::Validation(format!(
+            "At most {} coupons can be redeemed together",
+            MAX_STACKED_COUPONS
+        )));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    if !coupons.iter().all(|c| seen.insert(c.user_coupon_id)) {
+        return Err(AppError::Validation(
+            "The same coupon was given more than once".to_string(),
+        ));
+    }
+
+    match stacking {
+        CouponStacking::None if coupons.len() > 1 => {
+            return Err(AppError::Validation(
+                "Coupons cannot be combined".to_string(),
+            ));
+        },
+        CouponStacking::OnePerType => {
+            let mut types = std::collections::HashSet::new();
+            if let Some(dup) = coupons.iter().find(|c| !types.insert(c.coupon_type)) {
+                return Err(AppError::Validation(format!(
+                    "Only one {} coupon can be used per transaction",
+                    coupon_type_name(dup.coupon_type)
+                )));
+            }
+        },
+        _ => {},
+    }
+
+    if coupons.iter().any(|c| c.user_id != coupons[0].user_id) {
+        return Err(AppError::Validation(
+            "Coupons from different members cannot be combined".to_string(),
+        ));
+    }
+
+    for coupon in coupons {
+        if coupon.status != Some(UserCouponStatus::Available) {
+            return Err(AppError::Validation(format!(
+                "Coupon {} is not available for use",
+                coupon.qr_code
+            )));
+        }
+        if coupon_expired(coupon.expires_at, now) {
+            return Err(AppError::Validation(format!(
+                "Coupon {} has expired",
+                coupon.qr_code
+            )));
+        }
+        if let Some(min_spend) = coupon.minimum_spend {
+            if original_amount < min_spend {
+                return Err(AppError::Validation(format!(
+                    "Coupon {} requires a minimum spend of {} {}",
+                    coupon.qr_code,
+                    min_spend,
+                    coupon.currency.as_deref().unwrap_or("THB")
+                )));
+            }
+        }
+    }
+
+    let mut currencies = coupons
+        .iter()
+        .filter(|c| is_monetary(c.coupon_type))
+        .filter_map(|c| c.currency.as_deref())
+        .map(|c| c.trim().to_ascii_uppercase());
+    if let Some(first) = currencies.next() {
+        if currencies.any(|c| c != first) {
+            return Err(AppError::Validation(
+                "Coupons in different currencies cannot be combined".to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply a validated stack of coupons to `original_amount`.
+///
+/// The order is fixed so the same coupons always give the same total:
+/// 1. `fixed_amount` coupons, largest value first
+/// 2. `percentage` coupons, highest percentage first, each taken of the
+///    amount left after the coupons before it
+/// 3. `bogo`, `free_upgrade` and `free_service` coupons, which carry no
+///    monetary discount
+///
+/// Ties keep the order of the user coupon IDs. Each coupon's
+/// `maximum_discount` caps that coupon's own discount only, and no coupon
+/// takes the amount below zero. Returned in application order.
+pub fn apply_coupon_stack(
+    original_amount: Decimal,
+    coupons: &[StackedCoupon],
+) -> Vec<StackedDiscount> {
+    let mut ordered: Vec<&StackedCoupon> = coupons.iter().collect();
+    ordered.sort_by(|a, b| {
+        stack_rank(a.coupon_type)
+            .cmp(&stack_rank(b.coupon_type))
+            .then_with(|| {
+                b.value
+                    .unwrap_or_default()
+                    .cmp(&a.value.unwrap_or_default())
+            })
+            .then_with(|| a.user_coupon_id.cmp(&b.user_coupon_id))
+    });
+
+    let mut remaining = original_amount.max(Decimal::ZERO);
+    ordered
+        .into_iter()
+        .map(|coupon| {
+            let value = coupon.value.unwrap_or(Decimal::ZERO);
+            let discount = match coupon.coupon_type {
+                CouponType::FixedAmount => value,
+                CouponType::Percentage => remaining * value / Decimal::from(100),
+                _ => Decimal::ZERO,
+            };
+            let discount = match coupon.maximum_discount {
+                Some(max_discount) => discount.min(max_discount),
+                None => discount,
+            }
+            .min(remaining)
+            .max(Decimal::ZERO);
+
+            let applied = StackedDiscount {
+                user_coupon_id: coupon.user_coupon_id,
+                coupon_id: coupon.coupon_id,
+                qr_code: coupon.qr_code.clone(),
+                coupon_type: coupon.coupon_type,
+                amount_before: remaining,
+                discount_amount: discount,
+            };
+            remaining -= discount;
+            applied
+        })
+        .collect()
+}
+
+/// Position of a coupon type in the stacking order
+fn stack_rank(coupon_type: CouponType) -> u8 {
+    match coupon_type {
+        CouponType::FixedAmount => 0,
+        CouponType::Percentage => 1,
+        CouponType::Bogo | CouponType::FreeUpgrade | CouponType::FreeService => 2,
+    }
+}
+
+/// Whether a coupon type discounts an amount of money
+fn is_monetary(coupon_type: CouponType) -> bool {
+    matches!(
+        coupon_type,
+        CouponType::FixedAmount | CouponType::Percentage
+    )
+}
+
+fn coupon_type_name(coupon_type: CouponType) -> &'static str {
+    match coupon_type {
+        CouponType::Percentage => "percentage",
+        CouponType::FixedAmount => "fixed_amount",
+        CouponType::Bogo => "bogo",
+        CouponType::FreeUpgrade => "free_upgrade",
+        CouponType::FreeService => "free_service",
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_random_coupon_code() {
+        let config = CouponConfig {
+            code_prefix: "PROMO-".to_string(),
+            code_length: 8,
+            ..Default::default()
+        };
+        let charset = config.code_charset();
+
+        for _ in 0..50 {
+            let code = random_coupon_code(&config);
+            assert_eq!(code.len(), 14);
+            let random = code.strip_prefix("PROMO-").expect("prefix kept");
+            assert!(random.bytes().all(|c| charset.contains(&c)));
+            assert!(random.bytes().all(|c| !b"0O1IL".contains(&c)));
+            assert_eq!(random, random.to_ascii_uppercase());
+        }
+    }
+
     #[test]
     fn test_coupon_filters_default() {
         let filters = CouponFilters::default();
@@ -1053,6 +2012,8 @@ mod tests {
             maximum_discount: None,
             valid_from: None,
             valid_until: None,
+            valid_from_date: None,
+            valid_until_date: None,
             usage_limit: Some(100),
             usage_limit_per_user: Some(1),
             tier_restrictions: Some(vec!["Gold".to_string(), "Platinum".to_string()]),
@@ -1078,6 +2039,8 @@ mod tests {
             maximum_discount: None,
             valid_from: None,
             valid_until: None,
+            valid_from_date: None,
+            valid_until_date: None,
             usage_limit: None,
             usage_limit_per_user: None,
             tier_restrictions: None,
@@ -1089,4 +2052,346 @@ mod tests {
         assert_eq!(dto.name, Some("Updated Name".to_string()));
         assert_eq!(dto.status, Some(CouponStatus::Active));
     }
+
+    fn terms(coupon_type: CouponType, value: Option<Decimal>) -> CouponTerms<'static> {
+        CouponTerms {
+            coupon_type,
+            value,
+            currency: Some("THB"),
+            minimum_spend: None,
+            maximum_discount: None,
+            valid_from: None,
+            valid_until: None,
+        }
+    }
+
+    fn field_errors(result: Result<(), AppError>) -> Vec<String> {
+        match result {
+            Ok(()) => vec![],
+            Err(AppError::ValidationWithDetails { details, .. }) => {
+                let mut fields: Vec<String> = details.into_keys().collect();
+                fields.sort();
+                fields
+            },
+            Err(other) => panic!("expected field-level errors, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_coupon_terms_percentage() {
+        let config = CouponConfig::default();
+        let check = |v| {
+            field_errors(validate_coupon_terms(
+                &terms(CouponType::Percentage, v),
+                &config,
+            ))
+        };
+
+        assert!(check(Some(Decimal::new(15, 0))).is_empty());
+        assert!(check(Some(Decimal::from(100))).is_empty());
+        assert_eq!(check(None), vec!["value"]);
+        assert_eq!(check(Some(Decimal::ZERO)), vec!["value"]);
+        assert_eq!(check(Some(Decimal::new(1005, 1))), vec!["value"]);
+    }
+
+    #[test]
+    fn test_validate_coupon_terms_fixed_amount_needs_allowed_currency() {
+        let config = CouponConfig::default();
+        let mut fixed = terms(CouponType::FixedAmount, Some(Decimal::from(500)));
+        assert!(field_errors(validate_coupon_terms(&fixed, &config)).is_empty());
+
+        fixed.currency = None;
+        assert_eq!(
+            field_errors(validate_coupon_terms(&fixed, &config)),
+            vec!["currency"]
+        );
+
+        fixed.currency = Some("USD");
+        assert_eq!(
+            field_errors(validate_coupon_terms(&fixed, &config)),
+            vec!["currency"]
+        );
+
+        fixed.value = Some(Decimal::from(-1));
+        assert_eq!(
+            field_errors(validate_coupon_terms(&fixed, &config)),
+            vec!["currency", "value"]
+        );
+    }
+
+    #[test]
+    fn test_validate_coupon_terms_item_types_allow_null_value() {
+        let config = CouponConfig::default();
+        for coupon_type in [
+            CouponType::Bogo,
+            CouponType::FreeUpgrade,
+            CouponType::FreeService,
+        ] {
+            let mut t = terms(coupon_type, None);
+            t.currency = None;
+            assert!(field_errors(validate_coupon_terms(&t, &config)).is_empty());
+
+            t.value = Some(Decimal::ZERO);
+            assert_eq!(
+                field_errors(validate_coupon_terms(&t, &config)),
+                vec!["value"]
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_coupon_terms_amounts_and_dates() {
+        let config = CouponConfig::default();
+        let now = Utc::now();
+        let mut t = terms(CouponType::Percentage, Some(Decimal::from(10)));
+        t.minimum_spend = Some(Decimal::from(-100));
+        t.maximum_discount = Some(Decimal::from(-5));
+        t.valid_from = Some(now);
+        t.valid_until = Some(now);
+
+        assert_eq!(
+            field_errors(validate_coupon_terms(&t, &config)),
+            vec!["maximum_discount", "minimum_spend", "valid_until"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_coupon_window_local_dates() {
+        let bangkok = FixedOffset::east_opt(7 * 3600).unwrap();
+        let day = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+        let utc = |t: &str| t.parse::<DateTime<Utc>>().unwrap();
+
+        // "Until 31 March" runs to the end of that day in Bangkok, not to
+        // midnight UTC
+        let (from, until) = resolve_coupon_window(
+            None,
+            Some(day("2026-03-01")),
+            None,
+            Some(day("2026-03-31")),
+            bangkok,
+        )
+        .unwrap();
+        assert_eq!(from, Some(utc("2026-02-28T17:00:00Z")));
+        assert_eq!(until, Some(utc("2026-03-31T17:00:00Z")));
+
+        // Explicit timestamps pass through untouched
+        let exact = utc("2026-03-31T12:00:00Z");
+        assert_eq!(
+            resolve_coupon_window(None, None, Some(exact), None, bangkok).unwrap(),
+            (None, Some(exact))
+        );
+
+        let err = resolve_coupon_window(Some(exact), Some(day("2026-03-01")), None, None, bangkok)
+            .unwrap_err();
+        assert!(
+            matches!(err, AppError::ValidationWithDetails { ref details, .. }
+            if details.contains_key("valid_from_date"))
+        );
+    }
+
+    #[test]
+    fn test_coupon_window_boundaries() {
+        let now = Utc::now();
+        let hour = Duration::hours(1);
+
+        assert!(coupon_window_open(None, None, now));
+        assert!(coupon_window_open(Some(now), Some(now + hour), now));
+        assert!(!coupon_window_open(Some(now + hour), None, now));
+        assert!(!coupon_window_open(None, Some(now), now));
+
+        assert!(coupon_expired(Some(now), now));
+        assert!(!coupon_expired(Some(now + hour), now));
+        assert!(!coupon_expired(None, now));
+    }
+
+    fn stacked(coupon_type: CouponType, value: Option<Decimal>) -> StackedCoupon {
+        StackedCoupon {
+            user_coupon_id: Uuid::new_v4(),
+            user_id: Uuid::nil(),
+            coupon_id: Uuid::new_v4(),
+            qr_code: "QR".to_string(),
+            status: Some(UserCouponStatus::Available),
+            expires_at: None,
+            coupon_type,
+            value,
+            currency: Some("THB".to_string()),
+            minimum_spend: None,
+            maximum_discount: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_coupon_stack_rules() {
+        let now = Utc::now();
+        let amount = Decimal::from(1000);
+        let pct = stacked(CouponType::Percentage, Some(Decimal::from(10)));
+        let pct2 = stacked(CouponType::Percentage, Some(Decimal::from(5)));
+        let fixed = stacked(CouponType::FixedAmount, Some(Decimal::from(100)));
+
+        let one = [pct.clone()];
+        let mixed = [pct.clone(), fixed.clone()];
+        let same_type = [pct.clone(), pct2.clone()];
+
+        assert!(validate_coupon_stack(&one, amount, CouponStacking::None, now).is_ok());
+        assert!(validate_coupon_stack(&mixed, amount, CouponStacking::None, now).is_err());
+
+        assert!(validate_coupon_stack(&mixed, amount, CouponStacking::OnePerType, now).is_ok());
+        assert!(
+            validate_coupon_stack(&same_type, amount, CouponStacking::OnePerType, now).is_err()
+        );
+
+        assert!(validate_coupon_stack(&same_type, amount, CouponStacking::Unlimited, now).is_ok());
+        assert!(validate_coupon_stack(
+            &[pct.clone(), pct.clone()],
+            amount,
+            CouponStacking::Unlimited,
+            now
+        )
+        .is_err());
+
+        let mut usd = fixed.clone();
+        usd.currency = Some("USD".to_string());
+        assert!(validate_coupon_stack(
+            &[pct.clone(), fixed.clone(), usd],
+            amount,
+            CouponStacking::Unlimited,
+            now
+        )
+        .is_err());
+
+        let mut used = fixed.clone();
+        used.status = Some(UserCouponStatus::Used);
+        assert!(validate_coupon_stack(&[used], amount, CouponStacking::Unlimited, now).is_err());
+
+        let mut min_spend = fixed;
+        min_spend.minimum_spend = Some(Decimal::from(2000));
+        assert!(
+            validate_coupon_stack(&[min_spend], amount, CouponStacking::Unlimited, now).is_err()
+        );
+    }
+
+    #[test]
+    fn test_apply_coupon_stack_order_and_caps() {
+        let mut pct20 = stacked(CouponType::Percentage, Some(Decimal::from(20)));
+        pct20.maximum_discount = Some(Decimal::from(50));
+        let pct10 = stacked(CouponType::Percentage, Some(Decimal::from(10)));
+        let fixed = stacked(CouponType::FixedAmount, Some(Decimal::from(100)));
+        let bogo = stacked(CouponType::Bogo, None);
+
+        // Input order doesn't matter
+        let applied = apply_coupon_stack(
+            Decimal::from(1000),
+            &[bogo.clone(), pct10.clone(), pct20.clone(), fixed.clone()],
+        );
+        let order: Vec<Uuid> = applied.iter().map(|d| d.user_coupon_id).collect();
+        assert_eq!(
+            order,
+            vec![
+                fixed.user_coupon_id,
+                pct20.user_coupon_id,
+                pct10.user_coupon_id,
+                bogo.user_coupon_id
+            ]
+        );
+
+        // 1000 - 100 fixed = 900; 20% of 900 = 180, capped at 50 → 850;
+        // 10% of 850 = 85 → 765; BOGO adds nothing
+        let discounts: Vec<Decimal> = applied.iter().map(|d| d.discount_amount).collect();
+        assert_eq!(
+            discounts,
+            vec![
+                Decimal::from(100),
+                Decimal::from(50),
+                Decimal::from(85),
+                Decimal::ZERO
+            ]
+        );
+        assert_eq!(applied[2].amount_before, Decimal::from(850));
+
+        // A fixed discount never takes the amount below zero
+        let applied = apply_coupon_stack(Decimal::from(60), &[fixed, pct10]);
+        assert_eq!(applied[0].discount_amount, Decimal::from(60));
+        assert_eq!(applied[1].discount_amount, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_render_qr_png() {
+        let png = render_qr_png("QR-TEST-1234", 256).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+        let decoded = image::load_from_memory(&png).unwrap().to_luma8();
+        assert_eq!(decoded.dimensions(), (256, 256));
+        // Quiet zone corner is light; the finder pattern just inside it is dark
+        assert_eq!(decoded.get_pixel(0, 0).0, [255]);
+        assert!(decoded.pixels().any(|p| p.0 == [0]));
+
+        // Too small to fit even at one pixel per module: grows to fit
+        let png = render_qr_png("QR-TEST-1234", 1).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap();
+        assert!(decoded.width() > 1 && decoded.width() == decoded.height());
+    }
+
+    #[test]
+    fn test_restricted_tiers() {
+        assert_eq!(
+            restricted_tiers(Some(&serde_json::json!(["Gold", "Platinum"]))),
+            vec!["Gold", "Platinum"]
+        );
+        assert!(restricted_tiers(None).is_empty());
+        assert!(restricted_tiers(Some(&serde_json::Value::Null)).is_empty());
+        assert!(restricted_tiers(Some(&serde_json::json!([]))).is_empty());
+        assert!(restricted_tiers(Some(&serde_json::json!({ "tier": "Gold" }))).is_empty());
+    }
+
+    #[test]
+    fn test_coupon_segment_narrowing() {
+        let gold_or_platinum =
+            CouponSegment::default().with_tiers(vec!["Gold".to_string(), "Platinum".to_string()]);
+        let platinum_regulars = CouponSegment {
+            min_nights: Some(5),
+            last_stay_within_days: Some(90),
+            ..CouponSegment::default()
+        }
+        .with_tiers(vec!["Platinum".to_string(), "Silver".to_string()]);
+
+        let narrowed = gold_or_platinum.clone().narrow(platinum_regulars);
+        assert_eq!(narrowed.tiers, Some(vec!["Platinum".to_string()]));
+        assert_eq!(narrowed.min_nights, Some(5));
+        assert_eq!(narrowed.last_stay_within_days, Some(90));
+
+        // Disjoint tier lists leave nobody rather than everybody
+        let silver = CouponSegment::default().with_tiers(vec!["Silver".to_string()]);
+        assert_eq!(gold_or_platinum.narrow(silver).tiers, Some(vec![]));
+
+        assert!(CouponSegment::default().is_unrestricted());
+        assert!(CouponSegment::default()
+            .with_tiers(Vec::new())
+            .is_unrestricted());
+    }
+
+    #[test]
+    fn test_coupon_segment_parse_and_stored() {
+        let segment =
+            CouponSegment::parse(Some(&serde_json::json!({ "tiers": [], "minNights": 3 })))
+                .unwrap();
+        assert_eq!(segment.tiers, None);
+        assert_eq!(segment.min_nights, Some(3));
+        assert!(CouponSegment::parse(Some(&serde_json::json!({ "minNights": -1 }))).is_err());
+        assert!(
+            CouponSegment::parse(Some(&serde_json::json!({ "lastStayWithinDays": 0 }))).is_err()
+        );
+
+        // A coupon's tier restrictions narrow its stored segment; junk in
+        // either column restricts nothing
+        let segment = CouponSegment::for_coupon(
+            Some(&serde_json::json!({ "tiers": ["Gold", "Silver"] })),
+            Some(&serde_json::json!(["Gold"])),
+        );
+        assert_eq!(segment.tiers, Some(vec!["Gold".to_string()]));
+        assert!(CouponSegment::for_coupon(
+            Some(&serde_json::json!("vip")),
+            Some(&serde_json::json!([]))
+        )
+        .is_unrestricted());
+    }
 }
@@ -4,8 +4,11 @@
 //! - SMTP configuration from environment variables
 //! - Send generic emails with HTML content
 //! - Send password reset emails
+//! - Send magic-link sign-in emails
 //! - Send welcome emails
-//! - Email templates
+//! - Email templates (including coupon-assignment notices, survey
+//!   invitation links and monthly points statements)
+//! - Test-mode capture to the `captured_emails` table (`EMAIL_CAPTURE_MODE`)
 
 use async_trait::async_trait;
 use lettre::{
@@ -14,10 +17,11 @@ use lettre::{
     AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
 };
 use rand::Rng;
+use sqlx::PgPool;
 use std::sync::Arc;
 use tracing::{info, warn};
 
-use crate::config::SmtpConfig;
+use crate::config::{Environment, Settings, SmtpConfig};
 use crate::error::AppError;
 
 /// Email templates module
@@ -68,6 +72,54 @@ pub mod templates {
         )
     }
 
+    /// Generate the magic-link sign-in email HTML template
+    ///
+    /// # Arguments
+    /// * `token` - The single-use sign-in token
+    /// * `frontend_url` - The frontend URL for constructing the sign-in link
+    /// * `ttl_minutes` - How long the link stays valid
+    ///
+    /// # Returns
+    /// The HTML content for the magic-link email
+    pub fn magic_link_template(token: &str, frontend_url: &str, ttl_minutes: u64) -> String {
+        let sign_in_link = format!("{}/magic-link?token={}", frontend_url, token);
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Your Sign-in Link</title>
+</head>
+<body style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto; padding: 20px; background-color: #f5f5f5;">
+    <div style="background-color: #ffffff; padding: 30px; border-radius: 10px; box-shadow: 0 2px 10px rgba(0,0,0,0.1);">
+        <h2 style="color: #333; margin-bottom: 20px;">Sign In to Your Account</h2>
+        <p style="color: #666; line-height: 1.6;">
+            Click the button below to sign in. No password needed:
+        </p>
+        <div style="text-align: center; margin: 30px 0;">
+            <a href="{sign_in_link}" style="background-color: #4CAF50; color: white; padding: 14px 28px; text-decoration: none; border-radius: 5px; font-weight: bold; display: inline-block;">
+                Sign In
+            </a>
+        </div>
+        <p style="color: #666; line-height: 1.6;">
+            If the button doesn't work, copy and paste this link into your browser:
+        </p>
+        <p style="background-color: #f5f5f5; padding: 10px; border-radius: 5px; word-break: break-all; font-size: 14px; color: #666;">
+            {sign_in_link}
+        </p>
+        <p style="color: #999; font-size: 12px; margin-top: 30px; border-top: 1px solid #eee; padding-top: 20px;">
+            This link expires in {ttl_minutes} minutes and can only be used once. If you didn't ask to sign in, you can ignore this email.
+        </p>
+    </div>
+</body>
+</html>"#,
+            sign_in_link = sign_in_link,
+            ttl_minutes = ttl_minutes
+        )
+    }
+
     /// Generate the welcome email HTML template
     ///
     /// # Arguments
@@ -193,6 +245,196 @@ pub mod templates {
             code = code
         )
     }
+
+    /// Generate the "you've received a coupon" email template
+    ///
+    /// # Arguments
+    /// * `coupon_name` - Display name of the assigned coupon (HTML-escaped here)
+    /// * `frontend_url` - The frontend URL for the "view my coupons" link
+    ///
+    /// # Returns
+    /// The HTML content for the coupon assignment email
+    pub fn coupon_assigned_template(coupon_name: &str, frontend_url: &str) -> String {
+        let coupons_link = format!("{}/coupons", frontend_url.trim_end_matches('/'));
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>You've Received a Coupon</title>
+</head>
+<body style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto; padding: 20px; background-color: #f5f5f5;">
+    <div style="background-color: #ffffff; padding: 30px; border-radius: 10px; box-shadow: 0 2px 10px rgba(0,0,0,0.1);">
+        <h2 style="color: #4CAF50; margin-bottom: 20px;">You've Received a Coupon!</h2>
+        <p style="color: #666; line-height: 1.6;">
+            A new coupon has been added to your account:
+        </p>
+        <h3 style="color: #333; background: #f9f9f9; padding: 20px; text-align: center; border-radius: 5px;">
+            {coupon_name}
+        </h3>
+        <div style="text-align: center; margin: 30px 0;">
+            <a href="{coupons_link}" style="background-color: #4CAF50; color: white; padding: 12px 30px; text-decoration: none; border-radius: 5px; display: inline-block;">
+                View My Coupons
+            </a>
+        </div>
+        <p style="color: #999; font-size: 12px; margin-top: 30px; border-top: 1px solid #eee; padding-top: 20px;">
+            You can manage email notifications in your profile settings.
+        </p>
+    </div>
+</body>
+</html>"#,
+            coupon_name = escape_html(coupon_name),
+            coupons_link = coupons_link
+        )
+    }
+
+    /// Generate the survey invitation email template
+    ///
+    /// # Arguments
+    /// * `survey_title` - Title of the survey (HTML-escaped here)
+    /// * `link` - The invitation's single-use link
+    /// * `expires_in_days` - How long the link stays valid
+    ///
+    /// # Returns
+    /// The HTML content for the survey invitation email
+    pub fn survey_invitation_template(
+        survey_title: &str,
+        link: &str,
+        expires_in_days: u32,
+    ) -> String {
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>We'd Love Your Feedback</title>
+</head>
+<body style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto; padding: 20px; background-color: #f5f5f5;">
+    <div style="background-color: #ffffff; padding: 30px; border-radius: 10px; box-shadow: 0 2px 10px rgba(0,0,0,0.1);">
+        <h2 style="color: #333; margin-bottom: 20px;">We'd Love Your Feedback</h2>
+        <p style="color: #666; line-height: 1.6;">
+            You're invited to take part in our survey:
+        </p>
+        <h3 style="color: #333; background: #f9f9f9; padding: 20px; text-align: center; border-radius: 5px;">
+            {survey_title}
+        </h3>
+        <div style="text-align: center; margin: 30px 0;">
+            <a href="{link}" style="background-color: #4CAF50; color: white; padding: 12px 30px; text-decoration: none; border-radius: 5px; display: inline-block;">
+                Take the Survey
+            </a>
+        </div>
+        <p style="color: #666; line-height: 1.6;">
+            If the button doesn't work, copy and paste this link into your browser:
+        </p>
+        <p style="background-color: #f5f5f5; padding: 10px; border-radius: 5px; word-break: break-all; font-size: 14px; color: #666;">
+            {link}
+        </p>
+        <p style="color: #999; font-size: 12px; margin-top: 30px; border-top: 1px solid #eee; padding-top: 20px;">
+            This link expires in {expires_in_days} days and can only be used once.
+        </p>
+    </div>
+</body>
+</html>"#,
+            survey_title = escape_html(survey_title),
+            link = link,
+            expires_in_days = expires_in_days
+        )
+    }
+
+    /// What a monthly points statement shows, with points already formatted
+    /// for display
+    pub struct PointsStatementFigures<'a> {
+        /// Member's first name, if known
+        pub name: Option<&'a str>,
+        /// The month covered, e.g. "April 2026"
+        pub period: &'a str,
+        pub earned: &'a str,
+        pub redeemed: &'a str,
+        pub expiring: &'a str,
+        /// Window `expiring` covers, in days from sending
+        pub expiring_within_days: u32,
+        pub balance: &'a str,
+        pub tier_name: &'a str,
+    }
+
+    /// Generate the monthly points statement email template
+    ///
+    /// # Arguments
+    /// * `figures` - The member's activity for the month (HTML-escaped here)
+    /// * `frontend_url` - The frontend URL for the "view my points" link
+    ///
+    /// # Returns
+    /// The HTML content for the points statement email
+    pub fn points_statement_template(
+        figures: &PointsStatementFigures<'_>,
+        frontend_url: &str,
+    ) -> String {
+        let dashboard_link = format!("{}/dashboard", frontend_url.trim_end_matches('/'));
+        let greeting = match figures.name {
+            Some(name) if !name.trim().is_empty() => format!("Hi {},", escape_html(name.trim())),
+            _ => "Hi,".to_string(),
+        };
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Your Points Statement</title>
+</head>
+<body style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto; padding: 20px; background-color: #f5f5f5;">
+    <div style="background-color: #ffffff; padding: 30px; border-radius: 10px; box-shadow: 0 2px 10px rgba(0,0,0,0.1);">
+        <h2 style="color: #4CAF50; margin-bottom: 20px;">Your Points Statement for {period}</h2>
+        <p style="color: #666; line-height: 1.6;">
+            {greeting} here's a summary of your points activity last month.
+        </p>
+        <table style="width: 100%; border-collapse: collapse; margin: 20px 0; color: #333;">
+            <tr><td style="padding: 10px; border-bottom: 1px solid #eee;">Points earned</td><td style="padding: 10px; border-bottom: 1px solid #eee; text-align: right;">{earned}</td></tr>
+            <tr><td style="padding: 10px; border-bottom: 1px solid #eee;">Points redeemed</td><td style="padding: 10px; border-bottom: 1px solid #eee; text-align: right;">{redeemed}</td></tr>
+            <tr><td style="padding: 10px; border-bottom: 1px solid #eee;">Expiring in the next {expiring_within_days} days</td><td style="padding: 10px; border-bottom: 1px solid #eee; text-align: right;">{expiring}</td></tr>
+            <tr><td style="padding: 10px; border-bottom: 1px solid #eee;"><strong>Current balance</strong></td><td style="padding: 10px; border-bottom: 1px solid #eee; text-align: right;"><strong>{balance}</strong></td></tr>
+            <tr><td style="padding: 10px;">Tier</td><td style="padding: 10px; text-align: right;">{tier_name}</td></tr>
+        </table>
+        <div style="text-align: center; margin: 30px 0;">
+            <a href="{dashboard_link}" style="background-color: #4CAF50; color: white; padding: 12px 30px; text-decoration: none; border-radius: 5px; display: inline-block;">
+                View My Points
+            </a>
+        </div>
+        <p style="color: #999; font-size: 12px; margin-top: 30px; border-top: 1px solid #eee; padding-top: 20px;">
+            You can manage email notifications in your profile settings.
+        </p>
+    </div>
+</body>
+</html>"#,
+            period = escape_html(figures.period),
+            greeting = greeting,
+            earned = escape_html(figures.earned),
+            redeemed = escape_html(figures.redeemed),
+            expiring = escape_html(figures.expiring),
+            expiring_within_days = figures.expiring_within_days,
+            balance = escape_html(figures.balance),
+            tier_name = escape_html(figures.tier_name),
+            dashboard_link = dashboard_link
+        )
+    }
+
+    /// Minimal HTML escaping for admin-supplied text interpolated into templates
+    fn escape_html(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for ch in value.chars() {
+            match ch {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#39;"),
+                _ => escaped.push(ch),
+            }
+        }
+        escaped
+    }
 }
 
 /// SMTP email configuration
@@ -275,6 +517,19 @@ pub trait EmailService: Send + Sync {
     /// * `reset_token` - The password reset token
     async fn send_password_reset_email(&self, to: &str, reset_token: &str) -> Result<(), AppError>;
 
+    /// Send a magic-link sign-in email
+    ///
+    /// # Arguments
+    /// * `to` - Recipient email address
+    /// * `token` - The single-use sign-in token
+    /// * `ttl_minutes` - How long the link stays valid
+    async fn send_magic_link_email(
+        &self,
+        to: &str,
+        token: &str,
+        ttl_minutes: u64,
+    ) -> Result<(), AppError>;
+
     /// Send a welcome email
     ///
     /// # Arguments
@@ -408,20 +663,7 @@ impl EmailService for EmailServiceImpl {
         let from_mailbox = Self::parse_mailbox(&config.from)?;
         let to_mailbox = Self::parse_mailbox(to)?;
 
-        // Create plain text version by stripping HTML tags (simple approach)
-        let plain_text = html_body
-            .replace("<br>", "\n")
-            .replace("<br/>", "\n")
-            .replace("<br />", "\n")
-            .replace("</p>", "\n\n")
-            .replace("</li>", "\n")
-            .replace("</h1>", "\n\n")
-            .replace("</h2>", "\n\n")
-            .replace("</h3>", "\n\n");
-        // Remove remaining HTML tags
-        let plain_text = regex_lite::Regex::new(r"<[^>]+>")
-            .map(|re| re.replace_all(&plain_text, "").to_string())
-            .unwrap_or_else(|_| plain_text);
+        let plain_text = html_to_plain_text(html_body);
 
         let email = Message::builder()
             .from(from_mailbox)
@@ -457,6 +699,16 @@ impl EmailService for EmailServiceImpl {
         self.send_email(to, "Reset Your Password", &html_body).await
     }
 
+    async fn send_magic_link_email(
+        &self,
+        to: &str,
+        token: &str,
+        ttl_minutes: u64,
+    ) -> Result<(), AppError> {
+        let html_body = templates::magic_link_template(token, self.frontend_url(), ttl_minutes);
+        self.send_email(to, "Your Sign-in Link", &html_body).await
+    }
+
     async fn send_welcome_email(&self, to: &str, name: &str) -> Result<(), AppError> {
         let html_body = templates::welcome_template(name);
         self.send_email(to, "Welcome to Our Loyalty Program!", &html_body)
@@ -503,21 +755,162 @@ impl EmailService for EmailServiceImpl {
     }
 
     fn generate_verification_code(&self) -> String {
-        // Use uppercase only - frontend normalizes input to uppercase for user convenience
-        const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
-        let mut rng = rand::thread_rng();
-        let code: String = (0..8)
-            .map(|_| {
-                let idx = rng.gen_range(0..CHARS.len());
-                CHARS[idx] as char
-            })
-            .collect();
-
-        // Format as XXXX-XXXX
-        format!("{}-{}", &code[0..4], &code[4..8])
+        random_verification_code()
+    }
+}
+
+/// Create a plain text version of an HTML email by stripping tags
+/// (simple approach — good enough for our own templates)
+fn html_to_plain_text(html_body: &str) -> String {
+    let plain_text = html_body
+        .replace("<br>", "\n")
+        .replace("<br/>", "\n")
+        .replace("<br />", "\n")
+        .replace("</p>", "\n\n")
+        .replace("</li>", "\n")
+        .replace("</h1>", "\n\n")
+        .replace("</h2>", "\n\n")
+        .replace("</h3>", "\n\n");
+    // Remove remaining HTML tags
+    regex_lite::Regex::new(r"<[^>]+>")
+        .map(|re| re.replace_all(&plain_text, "").to_string())
+        .unwrap_or(plain_text)
+}
+
+/// Generate an `XXXX-XXXX` verification code
+fn random_verification_code() -> String {
+    // Use uppercase only - frontend normalizes input to uppercase for user convenience
+    const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    let mut rng = rand::thread_rng();
+    let code: String = (0..8)
+        .map(|_| {
+            let idx = rng.gen_range(0..CHARS.len());
+            CHARS[idx] as char
+        })
+        .collect();
+
+    // Format as XXXX-XXXX
+    format!("{}-{}", &code[0..4], &code[4..8])
+}
+
+/// Test-mode email service that records messages instead of sending them
+///
+/// Every email is rendered exactly as `EmailServiceImpl` would send it and
+/// written to `captured_emails`, where admins can inspect it via
+/// `GET /api/admin/email/captured`. Only built by [`email_service_for`]
+/// when `EMAIL_CAPTURE_MODE` is on and the environment isn't production.
+pub struct CapturingEmailService {
+    pool: PgPool,
+    frontend_url: String,
+}
+
+impl CapturingEmailService {
+    /// Create a capturing service writing to `pool`
+    pub fn new(pool: PgPool, frontend_url: impl Into<String>) -> Self {
+        Self {
+            pool,
+            frontend_url: frontend_url.into(),
+        }
     }
 }
 
+#[async_trait]
+impl EmailService for CapturingEmailService {
+    async fn send_email(&self, to: &str, subject: &str, html_body: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO captured_emails (channel, recipient, subject, html_body, text_body)
+            VALUES ('email', $1, $2, $3, $4)
+            "#,
+        )
+        .bind(to)
+        .bind(subject)
+        .bind(html_body)
+        .bind(html_to_plain_text(html_body))
+        .execute(&self.pool)
+        .await?;
+
+        info!("[Capture] Stored email to {} with subject: {}", to, subject);
+        Ok(())
+    }
+
+    async fn send_password_reset_email(&self, to: &str, reset_token: &str) -> Result<(), AppError> {
+        let html_body = templates::password_reset_template(reset_token, &self.frontend_url);
+        self.send_email(to, "Reset Your Password", &html_body).await
+    }
+
+    async fn send_magic_link_email(
+        &self,
+        to: &str,
+        token: &str,
+        ttl_minutes: u64,
+    ) -> Result<(), AppError> {
+        let html_body = templates::magic_link_template(token, &self.frontend_url, ttl_minutes);
+        self.send_email(to, "Your Sign-in Link", &html_body).await
+    }
+
+    async fn send_welcome_email(&self, to: &str, name: &str) -> Result<(), AppError> {
+        let html_body = templates::welcome_template(name);
+        self.send_email(to, "Welcome to Our Loyalty Program!", &html_body)
+            .await
+    }
+
+    async fn send_verification_email(&self, to: &str, code: &str) -> Result<(), AppError> {
+        let html_body = templates::verification_template(code);
+        self.send_email(to, "Verify your new email address", &html_body)
+            .await
+    }
+
+    async fn send_registration_verification_email(
+        &self,
+        to: &str,
+        code: &str,
+    ) -> Result<(), AppError> {
+        let html_body = templates::registration_verification_template(code);
+        self.send_email(to, "Welcome! Please verify your email address", &html_body)
+            .await
+    }
+
+    fn is_configured(&self) -> bool {
+        // Capture always "delivers" — callers should go ahead and render
+        true
+    }
+
+    async fn verify_connection(&self) -> Result<bool, AppError> {
+        // No SMTP transport in capture mode; nothing to probe.
+        Ok(false)
+    }
+
+    fn generate_verification_code(&self) -> String {
+        random_verification_code()
+    }
+}
+
+/// Build the email service for the current settings.
+///
+/// Returns [`CapturingEmailService`] when `email.capture_mode` is on,
+/// otherwise the SMTP-backed [`EmailServiceImpl`]. Production never
+/// captures: `Settings::validate` already refuses to start with capture
+/// mode on, and this re-checks so a hand-built `Settings` can't slip
+/// through either.
+pub fn email_service_for(settings: &Settings, pool: &PgPool) -> Arc<dyn EmailService> {
+    if settings.email.capture_mode {
+        if settings.environment == Environment::Production {
+            warn!("EMAIL_CAPTURE_MODE ignored in production; sending via SMTP");
+        } else {
+            return Arc::new(CapturingEmailService::new(
+                pool.clone(),
+                settings.server.frontend_url.clone(),
+            ));
+        }
+    }
+
+    Arc::new(EmailServiceImpl::from_smtp_config(
+        &settings.email.smtp,
+        &settings.server.frontend_url,
+    ))
+}
+
 /// No-op email service for testing or when email is disabled
 pub struct NoOpEmailService;
 
@@ -552,6 +945,16 @@ impl EmailService for NoOpEmailService {
         Ok(())
     }
 
+    async fn send_magic_link_email(
+        &self,
+        to: &str,
+        _token: &str,
+        _ttl_minutes: u64,
+    ) -> Result<(), AppError> {
+        info!("[NoOp] Would send magic link email to {}", to);
+        Ok(())
+    }
+
     async fn send_welcome_email(&self, to: &str, name: &str) -> Result<(), AppError> {
         info!("[NoOp] Would send welcome email to {} ({})", to, name);
         Ok(())
@@ -603,6 +1006,13 @@ mod tests {
         assert!(template.contains("expires in 1 hour"));
     }
 
+    #[test]
+    fn test_magic_link_template() {
+        let template = templates::magic_link_template("m4g1c", "https://example.com", 15);
+        assert!(template.contains("https://example.com/magic-link?token=m4g1c"));
+        assert!(template.contains("expires in 15 minutes"));
+    }
+
     #[test]
     fn test_welcome_template() {
         let template = templates::welcome_template("John");
@@ -627,6 +1037,56 @@ mod tests {
         assert!(template.contains("verify your email"));
     }
 
+    #[test]
+    fn test_coupon_assigned_template() {
+        let template = templates::coupon_assigned_template("20% off <Spa>", "https://example.com/");
+        assert!(template.contains("20% off &lt;Spa&gt;"));
+        assert!(!template.contains("<Spa>"));
+        assert!(template.contains("https://example.com/coupons"));
+        assert!(template.contains("You've Received a Coupon"));
+    }
+
+    #[test]
+    fn test_survey_invitation_template() {
+        let template = templates::survey_invitation_template(
+            "Stay <feedback>",
+            "https://example.com/surveys/invite/t0k3n",
+            14,
+        );
+        assert!(template.contains("Stay &lt;feedback&gt;"));
+        assert!(template.contains("https://example.com/surveys/invite/t0k3n"));
+        assert!(template.contains("expires in 14 days"));
+    }
+
+    #[test]
+    fn test_points_statement_template() {
+        let template = templates::points_statement_template(
+            &templates::PointsStatementFigures {
+                name: Some("<Ann>"),
+                period: "April 2026",
+                earned: "1,500",
+                redeemed: "200",
+                expiring: "300",
+                expiring_within_days: 30,
+                balance: "4,800",
+                tier_name: "Gold",
+            },
+            "https://example.com/",
+        );
+        assert!(template.contains("Your Points Statement for April 2026"));
+        assert!(template.contains("Hi &lt;Ann&gt;,"));
+        assert!(template.contains("1,500"));
+        assert!(template.contains("Expiring in the next 30 days"));
+        assert!(template.contains("4,800"));
+        assert!(template.contains("https://example.com/dashboard"));
+    }
+
+    #[test]
+    fn test_html_to_plain_text() {
+        let text = html_to_plain_text("<h2>Hello</h2><p>Line one<br>Line two</p>");
+        assert_eq!(text, "Hello\n\nLine one\nLine two\n\n");
+    }
+
     #[test]
     fn test_email_service_not_configured() {
         let service = EmailServiceImpl::new(None);
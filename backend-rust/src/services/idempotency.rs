@@ -36,10 +36,36 @@
 //! (e.g. 24h) are deleted by an out-of-band job (pg_cron or a
 //! periodic worker). This module only writes rows — see
 //! `migrations/20260513010000_idempotency_keys.sql` for the schema.
+//!
+//! ## Redis replay cache
+//!
+//! The admin points adjustment endpoints (award, deduct, award spending
+//! with nights) delegate to services that own their transaction, so
+//! there is no connection to write a row into. They use
+//! [`reserve_replay`] / [`store_replay`] instead: the key is scoped to
+//! `(endpoint, member, Idempotency-Key)`, reserved with `SET NX` before
+//! the work runs and overwritten with the JSON result afterwards, which
+//! a repeat within [`REPLAY_TTL_SECS`] gets back unchanged.
 
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::de::DeserializeOwned;
 use sqlx::PgExecutor;
 use uuid::Uuid;
 
+/// How long a handled key replays its original result (24 hours)
+pub const REPLAY_TTL_SECS: u64 = 86_400;
+
+/// How long a key stays reserved while its first request runs. Bounds
+/// how long a crashed request can block retries.
+const REPLAY_PENDING_TTL_SECS: u64 = 60;
+
+/// Redis key prefix for the replay cache
+const REPLAY_KEY_PREFIX: &str = "idempotency:";
+
+/// Placeholder value while the first request is still running
+const REPLAY_PENDING: &str = "pending";
+
 /// Outcome of [`take_or_replay`].
 #[derive(Debug)]
 pub enum IdempotencyOutcome {
@@ -182,3 +208,89 @@ where
     .await?;
     Ok(())
 }
+
+/// Outcome of [`reserve_replay`].
+#[derive(Debug)]
+pub enum ReplayOutcome<T> {
+    /// The key is now reserved for this request. The caller runs the
+    /// side effect, then calls [`store_replay`] (or [`release_replay`]
+    /// if it failed).
+    Fresh,
+    /// A previous request with the same key completed with this result.
+    Replay(T),
+    /// A request with the same key is still running.
+    InFlight,
+}
+
+/// Redis key for `key` sent to `endpoint` on behalf of `user_id`
+pub fn replay_key(endpoint: &str, user_id: Uuid, key: &str) -> String {
+    format!("{}{}:{}:{}", REPLAY_KEY_PREFIX, endpoint, user_id, key)
+}
+
+/// Reserve `redis_key` if it's fresh, otherwise return the cached
+/// result of the request that used it first.
+pub async fn reserve_replay<T: DeserializeOwned>(
+    redis: &mut ConnectionManager,
+    redis_key: &str,
+) -> Result<ReplayOutcome<T>, redis::RedisError> {
+    let reserved: Option<String> = redis::cmd("SET")
+        .arg(redis_key)
+        .arg(REPLAY_PENDING)
+        .arg("NX")
+        .arg("EX")
+        .arg(REPLAY_PENDING_TTL_SECS)
+        .query_async(redis)
+        .await?;
+    if reserved.is_some() {
+        return Ok(ReplayOutcome::Fresh);
+    }
+
+    // Either the placeholder (still running) or the finished result. A
+    // key that expired in between is also reported as in flight; the
+    // client's next retry reserves it.
+    let cached: Option<String> = redis.get(redis_key).await?;
+    Ok(
+        match cached
+            .filter(|value| value != REPLAY_PENDING)
+            .and_then(|value| serde_json::from_str(&value).ok())
+        {
+            Some(result) => ReplayOutcome::Replay(result),
+            None => ReplayOutcome::InFlight,
+        },
+    )
+}
+
+/// Cache the JSON `result` of a freshly-handled request for
+/// [`REPLAY_TTL_SECS`]
+pub async fn store_replay(
+    redis: &mut ConnectionManager,
+    redis_key: &str,
+    result: &str,
+) -> Result<(), redis::RedisError> {
+    redis.set_ex(redis_key, result, REPLAY_TTL_SECS).await
+}
+
+/// Drop the reservation of a request that failed, so a retry runs again
+pub async fn release_replay(
+    redis: &mut ConnectionManager,
+    redis_key: &str,
+) -> Result<(), redis::RedisError> {
+    redis.del(redis_key).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_key_is_scoped_to_endpoint_and_user() {
+        let user_id = Uuid::nil();
+        let key = replay_key("award-points", user_id, "abc");
+        assert_eq!(
+            key,
+            "idempotency:award-points:00000000-0000-0000-0000-000000000000:abc"
+        );
+        assert_ne!(key, replay_key("deduct-points", user_id, "abc"));
+        assert_ne!(key, replay_key("award-points", Uuid::new_v4(), "abc"));
+    }
+}
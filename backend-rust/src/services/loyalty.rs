@@ -3,11 +3,22 @@
 //! Provides loyalty program functionality including:
 //! - User loyalty status management
 //! - Points transactions and awarding
-//! - Tier management and recalculation
+//! - Tier management and recalculation, with a downgrade grace period
+//!   ([`recalculate_tier_with_grace`])
+//! - Tier upgrade coupon grants ([`grant_tier_upgrade_coupon`])
+//! - First-stay and win-back detection ([`record_stay_milestone`])
+//! - Points expiration, on demand or on a schedule ([`expire_points`])
+//! - Points redemption at the member's tier rate ([`redeem_points`])
+//! - Pending points for stays awaiting slip verification
+//!   ([`hold_pending_points`])
+//! - The opt-in members leaderboard ([`fetch_leaderboard`])
+//! - The Redis-cached active tier list ([`LoyaltyService::get_tiers_cached`])
 //! - Transaction history
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use redis::aio::ConnectionManager;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use sqlx::{FromRow, PgPool};
@@ -15,6 +26,9 @@ use tracing::info;
 use uuid::Uuid;
 
 use crate::error::AppError;
+use crate::models::tier::TierBenefits;
+use crate::redis::RedisManager;
+use crate::services::coupon::try_assign_coupon;
 
 /// User loyalty status entity from the database
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -45,7 +59,7 @@ pub struct UserLoyaltyWithTier {
     pub total_nights: i32,
     pub tier_name: String,
     pub tier_color: String,
-    pub tier_benefits: JsonValue,
+    pub tier_benefits: TierBenefits,
     pub tier_level: i32,
     pub progress_percentage: i64,
     pub next_tier_nights: Option<i32>,
@@ -78,33 +92,7 @@ pub struct Tier {
     pub updated_at: Option<DateTime<Utc>>,
 }
 
-/// Points transaction type enum
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
-#[sqlx(type_name = "points_transaction_type", rename_all = "snake_case")]
-#[serde(rename_all = "snake_case")]
-pub enum PointsTransactionType {
-    EarnedStay,
-    EarnedBonus,
-    Redeemed,
-    Expired,
-    AdminAdjustment,
-    AdminAward,
-    AdminDeduction,
-}
-
-impl std::fmt::Display for PointsTransactionType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            PointsTransactionType::EarnedStay => write!(f, "earned_stay"),
-            PointsTransactionType::EarnedBonus => write!(f, "earned_bonus"),
-            PointsTransactionType::Redeemed => write!(f, "redeemed"),
-            PointsTransactionType::Expired => write!(f, "expired"),
-            PointsTransactionType::AdminAdjustment => write!(f, "admin_adjustment"),
-            PointsTransactionType::AdminAward => write!(f, "admin_award"),
-            PointsTransactionType::AdminDeduction => write!(f, "admin_deduction"),
-        }
-    }
-}
+pub use crate::models::points_transaction::PointsTransactionType;
 
 /// Points transaction entity from the database
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -170,6 +158,25 @@ pub struct AwardPointsParamsUuid {
     pub admin_reason: Option<String>,
 }
 
+/// Parameters for [`LoyaltyService::award_points_atomic`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PointsAdjustmentParams {
+    /// User whose balance changes
+    pub user_id: Uuid,
+    /// Signed change to the balance: positive awards, negative deducts
+    pub points: i32,
+    /// Transaction type recorded for the change (e.g. `admin_award`)
+    pub transaction_type: PointsTransactionType,
+    /// Description of the change
+    pub description: String,
+    /// Reference ID for external systems
+    pub reference_id: Option<String>,
+    /// Admin user ID if this was an admin action
+    pub admin_user_id: Option<Uuid>,
+    /// Admin reason for the action
+    pub admin_reason: Option<String>,
+}
+
 /// Pagination parameters for transaction queries
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionPagination {
@@ -216,6 +223,14 @@ pub trait LoyaltyService: Send + Sync {
         params: AwardPointsParamsUuid,
     ) -> Result<PointsTransaction, AppError>;
 
+    /// Add `params.points` (negative to deduct) to a user's balance in a
+    /// single database-side increment, rejecting deductions that would
+    /// take the balance below zero
+    async fn award_points_atomic(
+        &self,
+        params: PointsAdjustmentParams,
+    ) -> Result<AwardPointsResult, AppError>;
+
     /// Get a user's transaction history with pagination
     async fn get_transactions(
         &self,
@@ -232,19 +247,53 @@ pub trait LoyaltyService: Send + Sync {
     /// Get all available tiers
     async fn get_all_tiers(&self) -> Result<Vec<Tier>, AppError>;
 
+    /// Get all available tiers, served from Redis when a tiers cache is
+    /// configured (see [`LoyaltyServiceImpl::with_tiers_cache`]) and
+    /// straight from the database otherwise
+    async fn get_tiers_cached(&self) -> Result<Vec<Tier>, AppError>;
+
     /// Initialize loyalty status for a new user
     async fn initialize_user_loyalty(&self, user_id: Uuid) -> Result<UserLoyalty, AppError>;
 }
 
+/// Redis key holding the active tier list
+pub const TIERS_CACHE_KEY: &str = "loyalty:tiers:active";
+
 /// Implementation of the LoyaltyService trait
 pub struct LoyaltyServiceImpl {
     db: PgPool,
+    /// Redis connection and TTL (seconds) for `get_tiers_cached`
+    tiers_cache: Option<(RedisManager, u64)>,
 }
 
 impl LoyaltyServiceImpl {
     /// Create a new LoyaltyServiceImpl instance
     pub fn new(db: PgPool) -> Self {
-        Self { db }
+        Self {
+            db,
+            tiers_cache: None,
+        }
+    }
+
+    /// Cache the active tier list in Redis for `ttl_secs` seconds.
+    /// A TTL of `0` leaves caching off.
+    pub fn with_tiers_cache(mut self, redis: ConnectionManager, ttl_secs: u64) -> Self {
+        self.tiers_cache = (ttl_secs > 0).then(|| (RedisManager::from_connection(redis), ttl_secs));
+        self
+    }
+}
+
+/// Drop the cached active tier list, so the next read goes to the database.
+///
+/// Called after every tier change. A Redis failure is logged rather than
+/// returned: the change has already committed, and the entry still
+/// expires on its own.
+pub async fn invalidate_tiers_cache(redis: ConnectionManager) {
+    if let Err(e) = RedisManager::from_connection(redis)
+        .delete(TIERS_CACHE_KEY)
+        .await
+    {
+        tracing::warn!(error = %e, "Failed to invalidate the tiers cache");
     }
 }
 
@@ -336,6 +385,9 @@ impl LoyaltyService for LoyaltyServiceImpl {
     ) -> Result<PointsTransaction, AppError> {
         let nights = params.nights.unwrap_or(0);
 
+        // The SP assumes the loyalty row exists
+        ensure_user_loyalty(&self.db, params.user_id).await?;
+
         // Call the award_points stored procedure
         let result: JsonValue = sqlx::query_scalar!(
             r#"
@@ -399,6 +451,81 @@ impl LoyaltyService for LoyaltyServiceImpl {
         })
     }
 
+    async fn award_points_atomic(
+        &self,
+        params: PointsAdjustmentParams,
+    ) -> Result<AwardPointsResult, AppError> {
+        if params.points == 0 {
+            return Err(AppError::Validation("Points must not be zero".to_string()));
+        }
+
+        let mut tx = self.db.begin().await?;
+
+        // Enroll first so a never-enrolled user gets a clean "insufficient
+        // points" rather than a missing-record error.
+        ensure_user_loyalty(&mut *tx, params.user_id).await?;
+
+        // The increment happens in the database, under the row lock the
+        // UPDATE takes, so concurrent adjustments can't lose each other's
+        // writes. A concurrent UPDATE waits for ours and re-checks the
+        // balance guard against the committed value; no row back means the
+        // deduction would overdraw the balance. The CHECK constraint stays
+        // as the backstop.
+        let new_points_balance: Option<i32> = sqlx::query_scalar(
+            r#"
+            UPDATE user_loyalty
+            SET current_points = COALESCE(current_points, 0) + $1,
+                points_updated_at = NOW(),
+                updated_at = NOW()
+            WHERE user_id = $2
+              AND COALESCE(current_points, 0) + $1 >= 0
+            RETURNING current_points
+            "#,
+        )
+        .bind(params.points)
+        .bind(params.user_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_balance_violation)?;
+
+        let new_points_balance = new_points_balance
+            .ok_or_else(|| AppError::Validation("Insufficient points for deduction".to_string()))?;
+
+        let transaction_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO points_transactions
+                (user_id, points, type, description, reference_id, admin_user_id, admin_reason)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id
+            "#,
+        )
+        .bind(params.user_id)
+        .bind(params.points)
+        .bind(params.transaction_type)
+        .bind(&params.description)
+        .bind(params.reference_id.as_deref())
+        .bind(params.admin_user_id)
+        .bind(params.admin_reason.as_deref())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        info!(
+            user_id = %params.user_id,
+            points = params.points,
+            new_points_balance,
+            transaction_id = %transaction_id,
+            "Adjusted user points balance"
+        );
+
+        Ok(AwardPointsResult {
+            transaction_id,
+            new_points_balance,
+            nights_added: 0,
+        })
+    }
+
     async fn get_transactions(
         &self,
         user_id: Uuid,
@@ -544,6 +671,30 @@ impl LoyaltyService for LoyaltyServiceImpl {
         Ok(tiers)
     }
 
+    async fn get_tiers_cached(&self) -> Result<Vec<Tier>, AppError> {
+        let Some((redis, ttl_secs)) = &self.tiers_cache else {
+            return self.get_all_tiers().await;
+        };
+        let mut redis = redis.clone();
+
+        // Redis being unavailable degrades to a database read, never an error
+        match redis.get_json::<Vec<Tier>>(TIERS_CACHE_KEY).await {
+            Ok(Some(tiers)) => return Ok(tiers),
+            Ok(None) => {},
+            Err(e) => tracing::warn!(error = %e, "Failed to read the tiers cache"),
+        }
+
+        let tiers = self.get_all_tiers().await?;
+        if let Err(e) = redis
+            .set_json(TIERS_CACHE_KEY, &tiers, Some(*ttl_secs))
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to populate the tiers cache");
+        }
+
+        Ok(tiers)
+    }
+
     async fn initialize_user_loyalty(&self, user_id: Uuid) -> Result<UserLoyalty, AppError> {
         // Get the Bronze tier (lowest tier)
         let bronze_tier: Uuid = sqlx::query_scalar!(
@@ -608,7 +759,7 @@ impl From<UserLoyaltyWithTierRow> for UserLoyaltyWithTier {
             total_nights: row.total_nights,
             tier_name: row.tier_name,
             tier_color: row.tier_color,
-            tier_benefits: row.tier_benefits,
+            tier_benefits: TierBenefits::from_json(&row.tier_benefits),
             tier_level: row.tier_level,
             progress_percentage: row.progress_percentage,
             next_tier_nights: row.next_tier_nights,
@@ -661,6 +812,1886 @@ pub fn determine_tier_by_nights(total_nights: i32) -> TierName {
     }
 }
 
+/// Apply the minimum-nights-for-points rule to a stay award
+///
+/// Returns the points to award for a stay of `nights` nights. A stay
+/// shorter than `min_nights` earns zero points (its nights are still
+/// credited by the caller). Awards without nights (`nights <= 0`) are not
+/// stays and pass through unchanged, as does everything when `min_nights`
+/// is 0. See `LoyaltyConfig::min_nights_for_points`.
+pub fn apply_min_nights_for_points(points: i32, nights: i32, min_nights: u32) -> i32 {
+    if min_nights > 0 && nights > 0 && (nights as u32) < min_nights && points > 0 {
+        0
+    } else {
+        points
+    }
+}
+
+/// Points per THB spent when no spend earning rule is active
+pub const DEFAULT_SPEND_POINTS_PER_UNIT: f64 = 10.0;
+
+/// `unit_type`s of `points_earning_rules` that convert spending into points
+const SPEND_UNIT_TYPES: [&str; 2] = ["currency", "spend"];
+
+/// The active earning rule for spending
+#[derive(Debug, Clone, FromRow)]
+pub struct SpendEarningRule {
+    pub points_per_unit: f64,
+    pub multiplier_by_tier: Option<JsonValue>,
+}
+
+impl Default for SpendEarningRule {
+    fn default() -> Self {
+        Self {
+            points_per_unit: DEFAULT_SPEND_POINTS_PER_UNIT,
+            multiplier_by_tier: None,
+        }
+    }
+}
+
+impl SpendEarningRule {
+    /// Multiplier for `tier_name` from `multiplier_by_tier`
+    /// (`{"Gold": 1.5, ...}`, matched case-insensitively); 1.0 for members
+    /// without a tier or tiers the rule doesn't list.
+    pub fn multiplier_for(&self, tier_name: Option<&str>) -> f64 {
+        let (Some(multipliers), Some(tier_name)) = (
+            self.multiplier_by_tier
+                .as_ref()
+                .and_then(JsonValue::as_object),
+            tier_name,
+        ) else {
+            return 1.0;
+        };
+
+        multipliers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(tier_name))
+            .and_then(|(_, multiplier)| multiplier.as_f64())
+            .filter(|multiplier| multiplier.is_finite() && *multiplier >= 0.0)
+            .unwrap_or(1.0)
+    }
+
+    /// Points for spending `amount` as a `tier_name` member, rounded down
+    pub fn points_for(&self, amount: f64, tier_name: Option<&str>) -> i32 {
+        (amount * self.points_per_unit * self.multiplier_for(tier_name)).floor() as i32
+    }
+}
+
+/// Load the active spend earning rule from `points_earning_rules`.
+///
+/// The newest active rule with a spend `unit_type` inside its validity
+/// window wins; without one, [`SpendEarningRule::default`] keeps the
+/// original 10 points per THB with no tier multiplier.
+pub async fn load_spend_earning_rule(pool: &PgPool) -> Result<SpendEarningRule, AppError> {
+    let rule = sqlx::query_as::<_, SpendEarningRule>(
+        r#"
+        SELECT points_per_unit::float8 AS points_per_unit, multiplier_by_tier
+        FROM points_earning_rules
+        WHERE is_active = true
+          AND unit_type = ANY($1)
+          AND (valid_from IS NULL OR valid_from <= NOW())
+          AND (valid_until IS NULL OR valid_until > NOW())
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(&SPEND_UNIT_TYPES[..])
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rule.unwrap_or_default())
+}
+
+/// Points `user_id` earns for spending `amount_spent` THB, under the
+/// active spend earning rule and their current tier's multiplier.
+pub async fn calculate_spend_points(
+    pool: &PgPool,
+    user_id: Uuid,
+    amount_spent: f64,
+) -> Result<i32, AppError> {
+    let rule = load_spend_earning_rule(pool).await?;
+
+    let tier_name: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT t.name
+        FROM user_loyalty ul
+        JOIN tiers t ON t.id = ul.tier_id
+        WHERE ul.user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rule.points_for(amount_spent, tier_name.as_deref()))
+}
+
+/// CHECK constraint keeping `user_loyalty.current_points` non-negative
+pub const CHK_CURRENT_POINTS_NON_NEGATIVE: &str = "chk_user_loyalty_current_points_non_negative";
+
+/// CHECK constraint keeping `user_loyalty.total_nights` non-negative
+pub const CHK_TOTAL_NIGHTS_NON_NEGATIVE: &str = "chk_user_loyalty_total_nights_non_negative";
+
+/// Convert a non-negative balance violation into a validation error
+///
+/// Deduct handlers pre-check the balance, but two concurrent deductions
+/// can both pass that check; the CHECK constraints on `user_loyalty` are
+/// the backstop. This maps their violation (SQLSTATE 23514) to the same
+/// message the pre-check gives and passes every other error through.
+pub fn map_balance_violation(err: sqlx::Error) -> AppError {
+    if let sqlx::Error::Database(db_err) = &err {
+        if db_err.code().as_deref() == Some("23514") {
+            match db_err.constraint() {
+                Some(CHK_CURRENT_POINTS_NON_NEGATIVE) => {
+                    return AppError::Validation("Insufficient points for deduction".to_string());
+                },
+                Some(CHK_TOTAL_NIGHTS_NON_NEGATIVE) => {
+                    return AppError::Validation("Insufficient nights for deduction".to_string());
+                },
+                _ => {},
+            }
+        }
+    }
+    AppError::from(err)
+}
+
+/// Points earned for `nights` nights under the fixed points-per-night rule
+///
+/// Non-positive night counts earn nothing, and the product saturates at
+/// `i32::MAX` rather than wrapping. See `LoyaltyConfig::points_per_night`.
+pub fn points_for_nights(nights: i32, points_per_night: u32) -> i32 {
+    if nights <= 0 {
+        return 0;
+    }
+    let points = i64::from(nights) * i64::from(points_per_night);
+    i32::try_from(points).unwrap_or(i32::MAX)
+}
+
+/// Ensure `user_id` has a `user_loyalty` row, creating one at the entry tier
+/// if it's missing.
+///
+/// The entry tier is the active tier with the lowest `sort_order` — the
+/// same rule `initialize_user_loyalty` uses — rather than a hardcoded name,
+/// so renaming or reordering tiers doesn't silently skip enrollment.
+/// Existing rows are left untouched (`ON CONFLICT DO NOTHING`), which makes
+/// this safe to call at the top of any award/deduct path. Pass the caller's
+/// transaction so the row shares its atomicity.
+///
+/// Returns an error if no active tier exists, since every downstream path
+/// (the `award_points` SP, tier recalculation) assumes the row is there.
+pub async fn ensure_user_loyalty<'c, E>(executor: E, user_id: Uuid) -> Result<(), AppError>
+where
+    E: sqlx::PgExecutor<'c>,
+{
+    // One statement so a single executor (a `&mut Transaction` can only be
+    // used once by value) covers both the "already enrolled" and "no
+    // active tier" checks: the CTE reports whether a row exists either way.
+    let enrolled: bool = sqlx::query_scalar(
+        r#"
+        WITH inserted AS (
+            INSERT INTO user_loyalty (user_id, tier_id, current_points, total_nights)
+            SELECT $1, t.id, 0, 0
+            FROM tiers t
+            WHERE t.is_active = true
+            ORDER BY t.sort_order ASC
+            LIMIT 1
+            ON CONFLICT (user_id) DO NOTHING
+            RETURNING user_id
+        )
+        SELECT EXISTS (SELECT 1 FROM inserted)
+            OR EXISTS (SELECT 1 FROM user_loyalty WHERE user_id = $1)
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(executor)
+    .await?;
+
+    if !enrolled {
+        return Err(AppError::Internal(
+            "No active tiers found in the system".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// A completed points redemption
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PointsRedemption {
+    /// The `redeemed` transaction recording it
+    pub transaction_id: Uuid,
+    /// Points taken from the balance
+    pub points: i32,
+    /// Currency value of one point, from the member's tier or the default
+    pub rate: Decimal,
+    /// Currency value of the redeemed points
+    pub value: Decimal,
+    pub new_points_balance: i32,
+}
+
+/// Redeem `points` from `user_id`'s balance for their currency value.
+///
+/// The points are priced at the member's tier rate through
+/// [`TierBenefits::redemption_value`], falling back to `default_rate`
+/// (`LOYALTY_POINTS_REDEMPTION_RATE`) when the tier sets none. The tier is
+/// read under the row lock the deduction takes, so a concurrent tier change
+/// can't price the redemption at a stale rate. A redemption that would
+/// overdraw the balance is rejected.
+pub async fn redeem_points(
+    pool: &PgPool,
+    user_id: Uuid,
+    points: i32,
+    reference_id: &str,
+    description: Option<&str>,
+    default_rate: Decimal,
+) -> Result<PointsRedemption, AppError> {
+    if points <= 0 {
+        return Err(AppError::Validation(
+            "Points must be at least 1".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+    ensure_user_loyalty(&mut *tx, user_id).await?;
+
+    let (balance, tier_benefits): (Option<i32>, Option<JsonValue>) = sqlx::query_as(
+        r#"
+        SELECT ul.current_points, t.benefits
+        FROM user_loyalty ul
+        LEFT JOIN tiers t ON t.id = ul.tier_id
+        WHERE ul.user_id = $1
+        FOR UPDATE OF ul
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if balance.unwrap_or(0) < points {
+        return Err(AppError::Validation(
+            "Insufficient points for redemption".to_string(),
+        ));
+    }
+
+    let benefits = tier_benefits
+        .as_ref()
+        .map(TierBenefits::from_json)
+        .unwrap_or_default();
+    let rate = benefits.redemption_rate(default_rate);
+    let value = benefits.redemption_value(points, default_rate);
+
+    let new_points_balance: i32 = sqlx::query_scalar(
+        r#"
+        UPDATE user_loyalty
+        SET current_points = COALESCE(current_points, 0) - $1,
+            points_updated_at = NOW(),
+            updated_at = NOW()
+        WHERE user_id = $2
+        RETURNING current_points
+        "#,
+    )
+    .bind(points)
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(map_balance_violation)?;
+
+    let description = description
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Redeemed {} points", points));
+    let transaction_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO points_transactions (user_id, points, type, description, reference_id)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(-points)
+    .bind(PointsTransactionType::Redeemed)
+    .bind(&description)
+    .bind(reference_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    info!(
+        user_id = %user_id,
+        points,
+        %rate,
+        %value,
+        new_points_balance,
+        "Redeemed points"
+    );
+
+    Ok(PointsRedemption {
+        transaction_id,
+        points,
+        rate,
+        value,
+        new_points_balance,
+    })
+}
+
+/// Award bonus points (`earned_bonus`) through the `award_points` SP.
+///
+/// Runs on the caller's connection so the award commits or rolls back with
+/// whatever triggered it (e.g. a referral qualifying). Enrolls the user
+/// first via [`ensure_user_loyalty`]. Awards of zero points are skipped.
+pub async fn award_bonus_points(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+    points: i32,
+    description: &str,
+    reference_id: &str,
+) -> Result<(), AppError> {
+    if points <= 0 {
+        return Ok(());
+    }
+
+    ensure_user_loyalty(&mut *conn, user_id).await?;
+
+    sqlx::query_scalar::<_, JsonValue>(
+        r#"
+        SELECT award_points($1, $2, 'earned_bonus'::varchar, $3, $4, NULL::uuid, NULL::text, 0)
+        "#,
+    )
+    .bind(user_id)
+    .bind(points)
+    .bind(description)
+    .bind(reference_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Credit a completed stay (`earned_stay`) through the `award_points` SP,
+/// which adds `nights` to the member's total and recalculates their tier.
+///
+/// Runs on the caller's connection so the award commits or rolls back with
+/// the booking's status change. `reference_id` identifies the stay: if an
+/// `earned_stay` transaction with it already exists nothing is written and
+/// `false` is returned, so completing a booking twice credits it once.
+pub async fn award_stay_points(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+    points: i32,
+    nights: i32,
+    description: &str,
+    reference_id: &str,
+) -> Result<bool, AppError> {
+    ensure_user_loyalty(&mut *conn, user_id).await?;
+
+    // The member's loyalty row serializes concurrent awards for the same
+    // stay, so the existence check below can't race
+    sqlx::query("SELECT 1 FROM user_loyalty WHERE user_id = $1 FOR UPDATE")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let already_awarded: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM points_transactions
+            WHERE user_id = $1 AND type = 'earned_stay' AND reference_id = $2
+        )
+        "#,
+    )
+    .bind(user_id)
+    .bind(reference_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if already_awarded {
+        return Ok(false);
+    }
+
+    sqlx::query_scalar::<_, JsonValue>(
+        r#"
+        SELECT award_points($1, $2, 'earned_stay'::varchar, $3, $4, NULL::uuid, NULL::text, $5)
+        "#,
+    )
+    .bind(user_id)
+    .bind(points.max(0))
+    .bind(description)
+    .bind(reference_id)
+    .bind(nights.max(0))
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(true)
+}
+
+/// What [`reverse_stay_points`] took back from the member
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StayReversal {
+    /// The `refund_adjustment` transaction, absent when earlier partial
+    /// adjustments had already taken back the whole award
+    pub transaction_id: Option<Uuid>,
+    pub points_deducted: i32,
+    pub nights_deducted: i32,
+}
+
+/// Take back the stay credited by [`award_stay_points`] under
+/// `reference_id`, on the caller's transaction.
+///
+/// Deducts whatever of the `earned_stay` award partial refund adjustments
+/// haven't already taken, as a `refund_adjustment` transaction, and
+/// recalculates the member's tier. The reversal is recorded in
+/// `points_refund_adjustments` under `{reference_id}-CANCEL`, so it
+/// happens at most once. Returns `None` when there is no award to reverse
+/// or it was reversed before. A member who has spent the points since
+/// gets the same "Insufficient points" error as any other deduction.
+pub async fn reverse_stay_points(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+    reference_id: &str,
+    description: &str,
+) -> Result<Option<StayReversal>, AppError> {
+    // Locking the award queues this behind any partial adjustment of it,
+    // as `admin_partial_adjust` locks the same row
+    let original: Option<(Uuid, i32, i32)> = sqlx::query_as(
+        r#"
+        SELECT id, points, COALESCE(nights_stayed, 0)
+        FROM points_transactions
+        WHERE user_id = $1 AND type = 'earned_stay' AND reference_id = $2
+        ORDER BY created_at ASC
+        LIMIT 1
+        FOR UPDATE
+        "#,
+    )
+    .bind(user_id)
+    .bind(reference_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some((original_id, original_points, original_nights)) = original else {
+        return Ok(None);
+    };
+
+    let (points_adjusted, nights_adjusted): (i32, i32) = sqlx::query_as(
+        r#"
+        SELECT COALESCE(SUM(points_deducted), 0)::INT,
+               COALESCE(SUM(nights_deducted), 0)::INT
+        FROM points_refund_adjustments
+        WHERE original_transaction_id = $1
+        "#,
+    )
+    .bind(original_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let points = (original_points - points_adjusted).max(0);
+    let nights = (original_nights - nights_adjusted).max(0);
+
+    let adjustment_id: Option<Uuid> = sqlx::query_scalar(
+        r#"
+        INSERT INTO points_refund_adjustments
+            (user_id, original_transaction_id, refund_reference, refunded_fraction,
+             points_deducted, nights_deducted)
+        VALUES ($1, $2, $3, 1, $4, $5)
+        ON CONFLICT (refund_reference) DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(original_id)
+    .bind(format!("{}-CANCEL", reference_id))
+    .bind(points)
+    .bind(nights)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some(adjustment_id) = adjustment_id else {
+        return Ok(None);
+    };
+
+    if points == 0 && nights == 0 {
+        return Ok(Some(StayReversal {
+            transaction_id: None,
+            points_deducted: 0,
+            nights_deducted: 0,
+        }));
+    }
+
+    let sp_result: JsonValue = sqlx::query_scalar(
+        r#"
+        SELECT award_points($1, $2, 'refund_adjustment'::varchar, $3, $4, NULL::uuid, NULL::text, $5)
+        "#,
+    )
+    .bind(user_id)
+    .bind(-points)
+    .bind(description)
+    .bind(reference_id)
+    .bind(-nights)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(map_balance_violation)?;
+
+    let transaction_id = sp_result
+        .get("transaction_id")
+        .and_then(|v| v.as_str())
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .ok_or_else(|| {
+            AppError::Internal("award_points SP did not return a transaction_id".to_string())
+        })?;
+
+    // The SP only recalculates the tier for positive nights
+    if nights > 0 {
+        sqlx::query("SELECT * FROM recalculate_user_tier_by_nights($1)")
+            .bind(user_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    sqlx::query("UPDATE points_refund_adjustments SET transaction_id = $2 WHERE id = $1")
+        .bind(adjustment_id)
+        .bind(transaction_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(Some(StayReversal {
+        transaction_id: Some(transaction_id),
+        points_deducted: points,
+        nights_deducted: nights,
+    }))
+}
+
+/// Outcome of [`recalculate_tier_with_grace`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TierRecalculation {
+    /// The tier the member holds afterwards
+    pub tier_id: Option<Uuid>,
+    pub tier_name: Option<String>,
+    pub tier_changed: bool,
+    /// When a pending downgrade takes effect; `None` if none is pending
+    pub downgrade_eligible_at: Option<DateTime<Utc>>,
+}
+
+/// Recalculate `user_id`'s tier from their nights, on the caller's
+/// transaction.
+///
+/// Goes through the `recalculate_user_tier_by_nights` SP. Upgrades apply
+/// immediately; a downgrade is held for `grace_days`
+/// (`LOYALTY_TIER_DOWNGRADE_GRACE_DAYS`) by scheduling it in
+/// `user_loyalty.tier_downgrade_eligible_at`, and only applied once that
+/// has passed (see [`apply_due_tier_downgrades`]). With `grace_days` of 0
+/// a downgrade applies straight away unless one is already pending.
+pub async fn recalculate_tier_with_grace(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+    grace_days: u32,
+) -> Result<TierRecalculation, AppError> {
+    let result = sqlx::query_as::<_, TierRecalculationResult>(
+        "SELECT * FROM recalculate_user_tier_by_nights($1, $2)",
+    )
+    .bind(user_id)
+    .bind(i32::try_from(grace_days).unwrap_or(i32::MAX))
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let downgrade_eligible_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT tier_downgrade_eligible_at FROM user_loyalty WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(TierRecalculation {
+        tier_id: result.new_tier_id,
+        tier_name: result.new_tier_name,
+        tier_changed: result.tier_changed.unwrap_or(false),
+        downgrade_eligible_at,
+    })
+}
+
+/// Apply tier downgrades whose grace period has ended, returning how many
+/// members were downgraded.
+///
+/// Members who earned their tier back in the meantime had the pending
+/// downgrade cleared when they did, so everything due here is lowered.
+/// Handles at most 500 members per call; the rest are picked up next run.
+pub async fn apply_due_tier_downgrades(pool: &PgPool) -> Result<i64, AppError> {
+    let downgraded: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FILTER (WHERE r.tier_changed)
+        FROM (
+            SELECT user_id
+            FROM user_loyalty
+            WHERE tier_downgrade_eligible_at <= NOW()
+            ORDER BY tier_downgrade_eligible_at
+            LIMIT 500
+        ) due
+        CROSS JOIN LATERAL recalculate_user_tier_by_nights(due.user_id) r
+        "#,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if downgraded > 0 {
+        info!(downgraded, "Applied tier downgrades after grace period");
+    }
+
+    Ok(downgraded)
+}
+
+/// `pg_advisory_xact_lock` key serializing admin changes to the tier ladder
+const TIER_LADDER_LOCK_KEY: i64 = 0x5449_4552_4c41_4444; // "TIERLADD"
+
+/// Serialize admin changes to the tier ladder until the end of `conn`'s
+/// transaction, so two edits can't each pass [`check_tier_ladder`] against
+/// a ladder the other is changing.
+pub async fn lock_tier_ladder(conn: &mut sqlx::PgConnection) -> Result<(), AppError> {
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(TIER_LADDER_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Check that the active tiers, as `(sort_order, min_nights)` pairs in any
+/// order, still form a ladder the "next tier" lookups can walk.
+///
+/// Those lookups join on `sort_order + 1`, so active sort orders must be
+/// unique and consecutive, and each step up must need more nights than the
+/// one below it; otherwise members would be shown the wrong next tier (or
+/// none) and the nights-based recalculation would skip a tier.
+pub fn check_tier_ladder(tiers: &[(i32, i32)]) -> Result<(), String> {
+    let mut ladder = tiers.to_vec();
+    ladder.sort_unstable();
+
+    for pair in ladder.windows(2) {
+        let ((lower_order, lower_nights), (upper_order, upper_nights)) = (pair[0], pair[1]);
+        if upper_order == lower_order {
+            return Err(format!(
+                "Another active tier already uses sort order {}",
+                upper_order
+            ));
+        }
+        if upper_order != lower_order + 1 {
+            return Err(format!(
+                "Active tier sort orders must be consecutive (gap between {} and {})",
+                lower_order, upper_order
+            ));
+        }
+        if upper_nights <= lower_nights {
+            return Err(format!(
+                "Tier at sort order {} must require more than {} nights",
+                upper_order, lower_nights
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Deactivate `tier_id` and move its members to the nearest active tier,
+/// on the caller's transaction (which should hold [`lock_tier_ladder`]).
+///
+/// Active tiers above it shift down one `sort_order` to close the gap, so
+/// the ladder stays consecutive. Members are recalculated straight away
+/// through `recalculate_user_tier_by_nights` with no grace period: any
+/// pending downgrade is cleared first, since it can't keep them on a tier
+/// that no longer exists. Returns how many members were reassigned; the
+/// last active tier can't be deactivated, as they'd have nowhere to go.
+pub async fn deactivate_tier(
+    conn: &mut sqlx::PgConnection,
+    tier_id: Uuid,
+    sort_order: i32,
+) -> Result<i64, AppError> {
+    let others_active: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM tiers WHERE is_active = true AND id <> $1)",
+    )
+    .bind(tier_id)
+    .fetch_one(&mut *conn)
+    .await?;
+    if !others_active {
+        return Err(AppError::Conflict(
+            "Cannot deactivate the only active tier".to_string(),
+        ));
+    }
+
+    sqlx::query("UPDATE tiers SET is_active = false, updated_at = NOW() WHERE id = $1")
+        .bind(tier_id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE tiers
+        SET sort_order = sort_order - 1, updated_at = NOW()
+        WHERE is_active = true AND sort_order > $1
+        "#,
+    )
+    .bind(sort_order)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE user_loyalty
+        SET tier_downgrade_eligible_at = NULL, updated_at = NOW()
+        WHERE tier_id = $1 AND tier_downgrade_eligible_at IS NOT NULL
+        "#,
+    )
+    .bind(tier_id)
+    .execute(&mut *conn)
+    .await?;
+
+    let reassigned: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FILTER (WHERE r.tier_changed)
+        FROM (
+            SELECT user_id
+            FROM user_loyalty
+            WHERE tier_id = $1
+            ORDER BY user_id
+        ) affected
+        CROSS JOIN LATERAL recalculate_user_tier_by_nights(affected.user_id) r
+        "#,
+    )
+    .bind(tier_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    info!(tier_id = %tier_id, reassigned, "Tier deactivated");
+
+    Ok(reassigned)
+}
+
+/// `pg_try_advisory_lock` key held for the whole of a bulk tier
+/// recalculation
+const TIER_RECALCULATION_LOCK_KEY: i64 = 0x5245_4341_4c43_5449; // "RECALCTI"
+
+/// Take the bulk tier recalculation lock on `conn`'s session, or `false`
+/// if a run on any instance already holds it.
+///
+/// A run spans many transactions, so this is a session lock rather than
+/// a transaction one: keep `conn` for the whole run and release it with
+/// [`unlock_tier_recalculation`]. If the process dies, Postgres drops the
+/// lock with the connection.
+pub async fn try_lock_tier_recalculation(conn: &mut sqlx::PgConnection) -> Result<bool, AppError> {
+    let locked = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+        .bind(TIER_RECALCULATION_LOCK_KEY)
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(locked)
+}
+
+/// Release the lock taken by [`try_lock_tier_recalculation`]
+pub async fn unlock_tier_recalculation(conn: &mut sqlx::PgConnection) -> Result<(), AppError> {
+    sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(TIER_RECALCULATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// How many members a bulk tier recalculation resuming after `after` (or
+/// starting, for `None`) still has to go through
+pub async fn count_tier_recalculation_remaining(
+    pool: &PgPool,
+    after: Option<Uuid>,
+) -> Result<i64, AppError> {
+    let remaining = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM user_loyalty WHERE $1::uuid IS NULL OR user_id > $1",
+    )
+    .bind(after)
+    .fetch_one(pool)
+    .await?;
+    Ok(remaining)
+}
+
+/// Outcome of one [`recalculate_tier_batch`] call
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TierRecalculationBatch {
+    /// Members recalculated
+    pub processed: i64,
+    /// Members whose tier changed
+    pub changed: i64,
+    /// The last member recalculated, where the next batch carries on;
+    /// `None` once nobody is left
+    pub last_user_id: Option<Uuid>,
+    /// Upgrade coupons granted, by member
+    pub grants: Vec<(Uuid, TierUpgradeGrant)>,
+}
+
+/// Recalculate the next `limit` members after `after` (in `user_id`
+/// order) in one transaction, for the bulk tier recalculation.
+///
+/// Each member goes through [`recalculate_tier_with_grace`] exactly as a
+/// single recalculation does, including the upgrade coupon, so running a
+/// batch twice changes nothing the second time. Keeping batches small
+/// keeps row locks short; the caller walks the whole table by passing
+/// back `last_user_id`.
+pub async fn recalculate_tier_batch(
+    pool: &PgPool,
+    after: Option<Uuid>,
+    limit: i64,
+    grace_days: u32,
+) -> Result<TierRecalculationBatch, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let user_ids: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT user_id
+        FROM user_loyalty
+        WHERE $1::uuid IS NULL OR user_id > $1
+        ORDER BY user_id
+        LIMIT $2
+        "#,
+    )
+    .bind(after)
+    .bind(limit)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut batch = TierRecalculationBatch {
+        last_user_id: user_ids.last().copied(),
+        ..Default::default()
+    };
+    for user_id in user_ids {
+        let previous_tier_id = lock_current_tier(&mut tx, user_id).await?;
+        let recalculation = recalculate_tier_with_grace(&mut tx, user_id, grace_days).await?;
+        batch.processed += 1;
+        if recalculation.tier_changed {
+            batch.changed += 1;
+            if let Some(grant) =
+                grant_tier_upgrade_coupon(&mut tx, user_id, previous_tier_id).await?
+            {
+                batch.grants.push((user_id, grant));
+            }
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(batch)
+}
+
+/// Most pending holds released per [`release_due_pending_points`] call
+const PENDING_POINTS_BATCH_SIZE: i64 = 500;
+
+/// A `pending_points` hold not yet released
+#[derive(Debug, Clone, FromRow)]
+struct PendingPointsRow {
+    id: Uuid,
+    user_id: Uuid,
+    points: i32,
+    reference_id: Option<String>,
+}
+
+/// Hold a stay's points as pending until its booking's payment slip is
+/// verified (`LOYALTY_PENDING_POINTS_ENABLED`).
+///
+/// Nothing is added to `current_points`, so held points can't be redeemed.
+/// They're credited by [`release_pending_points_for_booking`] once a slip
+/// for the booking is verified, or by [`release_due_pending_points`] after
+/// `clear_after_days` (`0` waits for the slip). A booking is held at most
+/// once; repeating the call is a no-op.
+pub async fn hold_pending_points<'c, E>(
+    executor: E,
+    user_id: Uuid,
+    booking_id: Uuid,
+    points: i32,
+    reference_id: &str,
+    clear_after_days: u32,
+) -> Result<(), AppError>
+where
+    E: sqlx::PgExecutor<'c>,
+{
+    if points <= 0 {
+        return Ok(());
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO pending_points (user_id, booking_id, points, reference_id, clears_at)
+        VALUES ($1, $2, $3, $4,
+                CASE WHEN $5 > 0 THEN NOW() + make_interval(days => $5) END)
+        ON CONFLICT (booking_id) WHERE booking_id IS NOT NULL DO NOTHING
+        "#,
+    )
+    .bind(user_id)
+    .bind(booking_id)
+    .bind(points)
+    .bind(reference_id)
+    .bind(clear_after_days.min(i32::MAX as u32) as i32)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Credit one held amount: an `earned_stay` transaction for the points,
+/// the balance bump, and the hold marked available, all on `conn`.
+async fn release_pending_row(
+    conn: &mut sqlx::PgConnection,
+    row: &PendingPointsRow,
+) -> Result<(), AppError> {
+    let transaction_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO points_transactions (user_id, points, type, description, reference_id, nights_stayed)
+        VALUES ($1, $2, 'earned_stay'::text::points_transaction_type, 'Pending stay points released', $3, 0)
+        RETURNING id
+        "#,
+    )
+    .bind(row.user_id)
+    .bind(row.points)
+    .bind(&row.reference_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE user_loyalty
+        SET current_points = current_points + $2,
+            points_updated_at = NOW(),
+            updated_at = NOW()
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(row.user_id)
+    .bind(row.points)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE pending_points
+        SET status = 'available', cleared_at = NOW(), transaction_id = $2
+        WHERE id = $1
+        "#,
+    )
+    .bind(row.id)
+    .bind(transaction_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Release the points held for `booking_id` now that its payment slip is
+/// verified, returning how many were credited (`0` if nothing was held).
+///
+/// Call inside the transaction that records the verification, so the
+/// points only become available if the verification commits.
+pub async fn release_pending_points_for_booking(
+    conn: &mut sqlx::PgConnection,
+    booking_id: Uuid,
+) -> Result<i32, AppError> {
+    let held: Option<PendingPointsRow> = sqlx::query_as(
+        r#"
+        SELECT id, user_id, points, reference_id
+        FROM pending_points
+        WHERE booking_id = $1 AND status = 'pending'
+        FOR UPDATE
+        "#,
+    )
+    .bind(booking_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some(held) = held else {
+        return Ok(0);
+    };
+
+    release_pending_row(conn, &held).await?;
+
+    info!(
+        user_id = %held.user_id,
+        booking_id = %booking_id,
+        points = held.points,
+        "Released pending points on slip verification"
+    );
+
+    Ok(held.points)
+}
+
+/// Release holds whose clearing period has ended without a verified slip,
+/// returning how many were released.
+///
+/// Handles at most 500 holds per call; the rest are picked up next run.
+/// `SKIP LOCKED` leaves holds being released by a verification alone.
+pub async fn release_due_pending_points(pool: &PgPool) -> Result<usize, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let due: Vec<PendingPointsRow> = sqlx::query_as(
+        r#"
+        SELECT id, user_id, points, reference_id
+        FROM pending_points
+        WHERE status = 'pending' AND clears_at <= NOW()
+        ORDER BY clears_at
+        LIMIT $1
+        FOR UPDATE SKIP LOCKED
+        "#,
+    )
+    .bind(PENDING_POINTS_BATCH_SIZE)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for row in &due {
+        release_pending_row(&mut tx, row).await?;
+    }
+
+    tx.commit().await?;
+
+    if !due.is_empty() {
+        info!(
+            released = due.len(),
+            "Released pending points after the clearing period"
+        );
+    }
+
+    Ok(due.len())
+}
+
+/// Points held as pending for `user_id`, not yet available to redeem
+pub async fn pending_points_balance<'c, E>(executor: E, user_id: Uuid) -> Result<i32, AppError>
+where
+    E: sqlx::PgExecutor<'c>,
+{
+    let pending: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(points), 0) FROM pending_points WHERE user_id = $1 AND status = 'pending'",
+    )
+    .bind(user_id)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(pending.min(i64::from(i32::MAX)) as i32)
+}
+
+/// Largest page the leaderboard returns
+pub const LEADERBOARD_MAX_LIMIT: i64 = 100;
+
+/// What the leaderboard ranks members by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeaderboardMetric {
+    #[default]
+    Nights,
+    Points,
+}
+
+/// One opted-in member on the leaderboard
+#[derive(Debug, Clone, FromRow)]
+pub struct LeaderboardRow {
+    pub rank: i64,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub total_nights: i32,
+    pub current_points: i32,
+    pub tier_name: Option<String>,
+    pub tier_color: Option<String>,
+}
+
+/// Top members by `metric`, limited to those who set
+/// `user_profiles.leaderboard_opt_in`
+///
+/// Ties share a rank; `limit` is clamped to 1..=[`LEADERBOARD_MAX_LIMIT`].
+pub async fn fetch_leaderboard<'c, E>(
+    executor: E,
+    metric: LeaderboardMetric,
+    limit: i64,
+) -> Result<Vec<LeaderboardRow>, AppError>
+where
+    E: sqlx::PgExecutor<'c>,
+{
+    // Both arms are fixed strings, never user input
+    let (primary, secondary) = match metric {
+        LeaderboardMetric::Nights => ("ul.total_nights", "ul.current_points"),
+        LeaderboardMetric::Points => ("ul.current_points", "ul.total_nights"),
+    };
+
+    let sql = format!(
+        r#"
+        SELECT
+            RANK() OVER (ORDER BY {primary} DESC) AS rank,
+            p.first_name, p.last_name,
+            ul.total_nights, ul.current_points,
+            t.name AS tier_name, t.color AS tier_color
+        FROM user_loyalty ul
+        JOIN users u ON u.id = ul.user_id
+        JOIN user_profiles p ON p.user_id = ul.user_id
+        LEFT JOIN tiers t ON t.id = ul.tier_id
+        WHERE p.leaderboard_opt_in = TRUE
+          AND u.is_active = TRUE
+        ORDER BY {primary} DESC, {secondary} DESC, ul.user_id
+        LIMIT $1
+        "#
+    );
+
+    let rows = sqlx::query_as::<_, LeaderboardRow>(&sql)
+        .bind(limit.clamp(1, LEADERBOARD_MAX_LIMIT))
+        .fetch_all(executor)
+        .await?;
+
+    Ok(rows)
+}
+
+/// Public form of a member's name: first name plus last initial
+/// ("Somchai P."), or "Member" when no first name is on file
+pub fn mask_display_name(first_name: Option<&str>, last_name: Option<&str>) -> String {
+    let first = first_name.map(str::trim).filter(|s| !s.is_empty());
+    let initial = last_name
+        .map(str::trim)
+        .and_then(|s| s.chars().next())
+        .map(|c| c.to_uppercase().collect::<String>());
+
+    match (first, initial) {
+        (Some(first), Some(initial)) => format!("{first} {initial}."),
+        (Some(first), None) => first.to_string(),
+        (None, _) => "Member".to_string(),
+    }
+}
+
+/// A coupon granted because a member moved up into a tier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TierUpgradeGrant {
+    pub tier_id: Uuid,
+    pub tier_name: String,
+    pub coupon_id: Uuid,
+    pub user_coupon_id: Uuid,
+}
+
+/// Lock `user_id`'s loyalty row and return its current tier.
+///
+/// Call at the start of a transaction that may change the tier, so the
+/// "previous tier" passed to [`grant_tier_upgrade_coupon`] can't be
+/// changed underneath it by a concurrent award.
+pub async fn lock_current_tier(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+) -> Result<Option<Uuid>, AppError> {
+    let tier_id: Option<Option<Uuid>> =
+        sqlx::query_scalar("SELECT tier_id FROM user_loyalty WHERE user_id = $1 FOR UPDATE")
+            .bind(user_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+    Ok(tier_id.flatten())
+}
+
+/// Grant the upgrade coupon configured on `user_id`'s current tier, if the
+/// member just moved up into it from `previous_tier_id`.
+///
+/// Call after the tier has been recalculated, on the same transaction.
+/// Nothing is granted when there was no previous tier (first enrollment),
+/// the tier is unchanged or lower, or the tier has no `upgrade_coupon_id`.
+///
+/// The grant is recorded in `tier_upgrade_grants` first; its
+/// `UNIQUE (user_id, tier_id)` makes this fire at most once per member per
+/// tier, so recalculations, retries and concurrent awards that observe the
+/// same upgrade don't grant again. The coupon goes through
+/// [`try_assign_coupon`], so its status, expiry and usage limits apply; a
+/// rejected assignment leaves the ledger row with no `user_coupon_id` and
+/// returns `None`.
+pub async fn grant_tier_upgrade_coupon(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+    previous_tier_id: Option<Uuid>,
+) -> Result<Option<TierUpgradeGrant>, AppError> {
+    let Some(previous_tier_id) = previous_tier_id else {
+        return Ok(None);
+    };
+
+    let upgrade: Option<(Uuid, String, Uuid)> = sqlx::query_as(
+        r#"
+        SELECT t.id, t.name, t.upgrade_coupon_id
+        FROM user_loyalty ul
+        JOIN tiers t ON t.id = ul.tier_id
+        JOIN tiers prev ON prev.id = $2
+        WHERE ul.user_id = $1
+          AND t.id <> prev.id
+          AND t.sort_order > prev.sort_order
+          AND t.upgrade_coupon_id IS NOT NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(previous_tier_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some((tier_id, tier_name, coupon_id)) = upgrade else {
+        return Ok(None);
+    };
+
+    let grant_id: Option<Uuid> = sqlx::query_scalar(
+        r#"
+        INSERT INTO tier_upgrade_grants (user_id, tier_id, coupon_id)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, tier_id) DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(tier_id)
+    .bind(coupon_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some(grant_id) = grant_id else {
+        return Ok(None);
+    };
+
+    let reason = format!("Tier upgrade: {}", tier_name);
+    let Some(user_coupon_id) = try_assign_coupon(&mut *conn, coupon_id, user_id, &reason).await?
+    else {
+        return Ok(None);
+    };
+
+    sqlx::query("UPDATE tier_upgrade_grants SET user_coupon_id = $2 WHERE id = $1")
+        .bind(grant_id)
+        .bind(user_coupon_id)
+        .execute(&mut *conn)
+        .await?;
+
+    info!(
+        user_id = %user_id,
+        tier = %tier_name,
+        coupon_id = %coupon_id,
+        user_coupon_id = %user_coupon_id,
+        "Tier upgrade coupon granted"
+    );
+
+    Ok(Some(TierUpgradeGrant {
+        tier_id,
+        tier_name,
+        coupon_id,
+        user_coupon_id,
+    }))
+}
+
+/// A stay that marketing treats specially, stored in
+/// `points_transactions.stay_milestone`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StayMilestone {
+    /// The member's first `earned_stay`
+    FirstStay,
+    /// A stay after at least `LOYALTY_WIN_BACK_AFTER_DAYS` without one
+    WinBack,
+}
+
+impl StayMilestone {
+    /// Column value, also used as the notification type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StayMilestone::FirstStay => "first_stay",
+            StayMilestone::WinBack => "win_back",
+        }
+    }
+
+    /// Title and message of the notification sent for this milestone
+    pub fn notification_text(&self) -> (&'static str, &'static str) {
+        match self {
+            StayMilestone::FirstStay => (
+                "Welcome to your first stay!",
+                "Thanks for staying with us. Your first stay has been added to your membership.",
+            ),
+            StayMilestone::WinBack => (
+                "Welcome back!",
+                "It's great to see you again. Your stay has been added to your membership.",
+            ),
+        }
+    }
+}
+
+/// Classify a stay from the time of the member's previous stay.
+///
+/// No previous stay makes it a first stay. A gap of at least
+/// `win_back_after_days` makes it a win-back; `0` disables that check.
+pub fn classify_stay(
+    previous_stay_at: Option<DateTime<Utc>>,
+    stay_at: DateTime<Utc>,
+    win_back_after_days: u32,
+) -> Option<StayMilestone> {
+    let Some(previous_stay_at) = previous_stay_at else {
+        return Some(StayMilestone::FirstStay);
+    };
+    if win_back_after_days > 0
+        && stay_at - previous_stay_at >= chrono::Duration::days(i64::from(win_back_after_days))
+    {
+        return Some(StayMilestone::WinBack);
+    }
+    None
+}
+
+/// Flag the `earned_stay` transaction `transaction_id` as the member's
+/// first stay or a win-back, based on their earlier `earned_stay` rows.
+///
+/// Call on the award's transaction after [`lock_current_tier`]: the row
+/// lock serializes concurrent awards for the member, so the second of two
+/// simultaneous first stays sees the first one in the ledger. The partial
+/// unique index on `stay_milestone = 'first_stay'` backs this up — a second
+/// first-stay flag fails the award instead of being recorded.
+pub async fn record_stay_milestone(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+    transaction_id: Uuid,
+    win_back_after_days: u32,
+) -> Result<Option<StayMilestone>, AppError> {
+    let (has_previous, previous_stay_at, stay_at): (
+        bool,
+        Option<DateTime<Utc>>,
+        Option<DateTime<Utc>>,
+    ) = sqlx::query_as(
+        r#"
+        SELECT
+            COUNT(*) > 0,
+            MAX(created_at),
+            (SELECT created_at FROM points_transactions WHERE id = $2)
+        FROM points_transactions
+        WHERE user_id = $1
+          AND type = 'earned_stay'
+          AND id <> $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(transaction_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    // Earlier stays with no timestamp still rule out a first stay; they
+    // just leave no gap to measure for a win-back.
+    let milestone = match (has_previous, previous_stay_at) {
+        (false, _) => classify_stay(None, Utc::now(), win_back_after_days),
+        (true, Some(previous_stay_at)) => classify_stay(
+            Some(previous_stay_at),
+            stay_at.unwrap_or_else(Utc::now),
+            win_back_after_days,
+        ),
+        (true, None) => None,
+    };
+
+    let Some(milestone) = milestone else {
+        return Ok(None);
+    };
+
+    sqlx::query("UPDATE points_transactions SET stay_milestone = $2 WHERE id = $1")
+        .bind(transaction_id)
+        .bind(milestone.as_str())
+        .execute(&mut *conn)
+        .await?;
+
+    info!(
+        user_id = %user_id,
+        transaction_id = %transaction_id,
+        milestone = milestone.as_str(),
+        "Stay milestone recorded"
+    );
+
+    Ok(Some(milestone))
+}
+
+/// Send the member the in-app notification for a stay milestone.
+///
+/// Call after the award has committed. Failures are logged, never
+/// returned — the ledger flag is the record of truth.
+pub async fn notify_stay_milestone(pool: &PgPool, user_id: Uuid, milestone: StayMilestone) {
+    use crate::models::notification::{NotificationPriority, NotificationType};
+    use crate::services::notification::{
+        CreateNotificationDto, NotificationService, NotificationServiceImpl,
+    };
+
+    let (title, message) = milestone.notification_text();
+    let result = NotificationServiceImpl::new(pool.clone())
+        .create_notification(CreateNotificationDto {
+            user_id,
+            title: title.to_string(),
+            message: message.to_string(),
+            notification_type: Some(NotificationType::Reward),
+            priority: NotificationPriority::Normal,
+            category: Some("loyalty".to_string()),
+            data: Some(serde_json::json!({ "milestone": milestone.as_str() })),
+            expires_at: None,
+        })
+        .await;
+    if let Err(e) = result {
+        tracing::warn!(
+            error = %e,
+            user_id = %user_id,
+            milestone = milestone.as_str(),
+            "Failed to send stay milestone notification"
+        );
+    }
+}
+
+/// `pg_advisory_xact_lock` key serializing points expiration runs
+const POINTS_EXPIRY_LOCK_KEY: i64 = 0x504f_494e_5453_4558; // "POINTSEX"
+
+/// A member's balance after [`expire_points`] took points from it
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct ExpiredPointsBalance {
+    pub user_id: Uuid,
+    pub expired_points: i32,
+    pub expired_transactions: i64,
+    pub current_points: i32,
+    pub total_nights: i32,
+    pub tier_name: Option<String>,
+}
+
+/// Expire every earning whose `expires_at` has passed.
+///
+/// Each expired earning gets one negative `expired` transaction whose
+/// `reference_id` is the earning's id, and the member's `current_points`
+/// drops by the same amount (never below zero — points already spent
+/// can't be taken back). Runs in its own transaction under an advisory
+/// lock, so an admin-triggered run and the scheduled worker queue up
+/// instead of racing; the second one finds nothing left to expire. The
+/// unique index on the `expired` reference is the backstop.
+///
+/// Returns the affected balances, one per member, for the caller to push
+/// to connected clients.
+pub async fn expire_points(pool: &PgPool) -> Result<Vec<ExpiredPointsBalance>, AppError> {
+    let balances = run_points_expiry(pool, true).await?;
+
+    if !balances.is_empty() {
+        info!(
+            members = balances.len(),
+            transactions = balances.iter().map(|b| b.expired_transactions).sum::<i64>(),
+            "Points expired"
+        );
+    }
+
+    Ok(balances)
+}
+
+/// What [`expire_points`] would do right now, without changing anything.
+///
+/// Runs the identical statement and rolls it back, so the preview can't
+/// drift from the real run: an earning that already has an `expired`
+/// transaction is skipped here too, and each member appears once with
+/// the total of their newly expiring earnings.
+pub async fn preview_points_expiry(pool: &PgPool) -> Result<Vec<ExpiredPointsBalance>, AppError> {
+    run_points_expiry(pool, false).await
+}
+
+async fn run_points_expiry(
+    pool: &PgPool,
+    commit: bool,
+) -> Result<Vec<ExpiredPointsBalance>, AppError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(POINTS_EXPIRY_LOCK_KEY)
+        .execute(&mut *tx)
+        .await?;
+
+    let balances: Vec<ExpiredPointsBalance> = sqlx::query_as(
+        r#"
+        WITH expired AS (
+            INSERT INTO points_transactions (user_id, points, type, description, reference_id, created_at)
+            SELECT
+                pt.user_id,
+                -pt.points,
+                'expired'::points_transaction_type,
+                'Points expired automatically',
+                pt.id::text,
+                NOW()
+            FROM points_transactions pt
+            WHERE pt.expires_at <= NOW()
+            AND pt.points > 0
+            AND NOT EXISTS (
+                SELECT 1
+                FROM points_transactions e
+                WHERE e.type = 'expired'
+                AND e.reference_id = pt.id::text
+            )
+            RETURNING user_id, points
+        ),
+        totals AS (
+            SELECT user_id, (-SUM(points))::int AS expired_points, COUNT(*) AS expired_transactions
+            FROM expired
+            GROUP BY user_id
+        )
+        UPDATE user_loyalty ul
+        SET current_points = GREATEST(COALESCE(ul.current_points, 0) - t.expired_points, 0),
+            points_updated_at = NOW(),
+            updated_at = NOW()
+        FROM totals t
+        WHERE ul.user_id = t.user_id
+        RETURNING
+            ul.user_id,
+            t.expired_points,
+            t.expired_transactions,
+            COALESCE(ul.current_points, 0) AS current_points,
+            COALESCE(ul.total_nights, 0) AS total_nights,
+            (SELECT name FROM tiers WHERE id = ul.tier_id) AS tier_name
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if commit {
+        tx.commit().await?;
+    } else {
+        tx.rollback().await?;
+    }
+
+    Ok(balances)
+}
+
+/// Push each expired member's new balance over SSE so open clients refresh.
+pub async fn notify_points_expired(balances: &[ExpiredPointsBalance]) {
+    for balance in balances {
+        crate::services::sse::helpers::send_loyalty_update(
+            &balance.user_id.to_string(),
+            balance.current_points,
+            balance.tier_name.as_deref().unwrap_or_default(),
+            balance.total_nights,
+        )
+        .await;
+    }
+}
+
+/// Run [`expire_points`], then [`apply_due_tier_downgrades`] and
+/// [`release_due_pending_points`], every `period` until `shutdown` flips
+/// to `true` (or its sender is dropped).
+///
+/// The first run happens immediately. A run in progress when shutdown is
+/// signalled finishes first, so its transaction is never cut off halfway.
+/// Errors are logged and the next tick retries.
+pub fn spawn_points_expiry(
+    pool: PgPool,
+    period: std::time::Duration,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    match expire_points(&pool).await {
+                        Ok(balances) => notify_points_expired(&balances).await,
+                        Err(e) => tracing::error!(error = %e, "Scheduled points expiration failed"),
+                    }
+                    if let Err(e) = apply_due_tier_downgrades(&pool).await {
+                        tracing::error!(error = %e, "Scheduled tier downgrades failed");
+                    }
+                    if let Err(e) = release_due_pending_points(&pool).await {
+                        tracing::error!(error = %e, "Scheduled pending points release failed");
+                    }
+                },
+                changed = shutdown.changed() => {
+                    if changed.is_err() || *shutdown.borrow() {
+                        info!("Points expiry worker stopped");
+                        break;
+                    }
+                },
+            }
+        }
+    })
+}
+
+/// `pg_try_advisory_xact_lock` key serializing points statement claims
+const POINTS_STATEMENT_LOCK_KEY: i64 = 0x5354_4154_454d_454e; // "STATEMEN"
+
+/// Most statements claimed per [`send_points_statements`] call
+pub const POINTS_STATEMENT_BATCH_SIZE: usize = 500;
+
+/// A member's points activity for one statement month, as recorded in
+/// `points_statements`
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct PointsStatement {
+    pub user_id: Uuid,
+    pub email: String,
+    pub first_name: Option<String>,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub points_earned: i32,
+    pub points_redeemed: i32,
+    /// Earnings expiring within `POINTS_STATEMENT_EXPIRING_DAYS` of
+    /// sending, capped at the balance
+    pub points_expiring: i32,
+    pub current_points: i32,
+    pub tier_name: Option<String>,
+}
+
+/// Outcome of one [`send_points_statements`] call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PointsStatementRun {
+    /// Statements recorded in this call
+    pub claimed: usize,
+    /// Of those, how many emails went out
+    pub sent: usize,
+}
+
+/// The month a statement sent at `now` covers: the previous calendar
+/// month (UTC), as `[start, end)`.
+pub fn statement_period(now: DateTime<Utc>) -> (NaiveDate, NaiveDate) {
+    let today = now.date_naive();
+    let end = today.with_day(1).unwrap_or(today);
+    let start = end
+        .checked_sub_months(chrono::Months::new(1))
+        .unwrap_or(end);
+    (start, end)
+}
+
+/// Email the next batch of last month's points statements.
+///
+/// A member gets a statement when they had at least one points
+/// transaction in the month, still have an email address, and haven't
+/// switched off the `email` or `points` notification preference.
+///
+/// Statements are claimed by inserting them into `points_statements`
+/// under an advisory lock and committed before any email goes out, so
+/// each member is sent at most one per month even if several instances
+/// run the job or a send fails. An instance that finds the lock taken
+/// returns straight away and leaves the batch to the holder. At most
+/// [`POINTS_STATEMENT_BATCH_SIZE`] are claimed per call; call again while
+/// a full batch comes back.
+pub async fn send_points_statements(
+    pool: &PgPool,
+    email_service: &dyn crate::services::email::EmailService,
+    config: &crate::config::PointsStatementConfig,
+    points_display: &crate::config::PointsDisplayConfig,
+    frontend_url: &str,
+) -> Result<PointsStatementRun, AppError> {
+    let (period_start, period_end) = statement_period(Utc::now());
+
+    let Some(statements) =
+        claim_points_statements(pool, period_start, period_end, config.expiring_within_days)
+            .await?
+    else {
+        return Ok(PointsStatementRun::default());
+    };
+
+    let mut run = PointsStatementRun {
+        claimed: statements.len(),
+        sent: 0,
+    };
+    for (i, statement) in statements.iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(crate::services::notification::BULK_EMAIL_INTERVAL).await;
+        }
+        let html_body = points_statement_email(statement, config, points_display, frontend_url);
+        let subject = format!(
+            "Your points statement for {}",
+            statement.period_start.format("%B %Y")
+        );
+        match email_service
+            .send_email(&statement.email, &subject, &html_body)
+            .await
+        {
+            Ok(()) => run.sent += 1,
+            Err(e) => tracing::warn!(
+                error = %e,
+                user_id = %statement.user_id,
+                "Failed to send points statement"
+            ),
+        }
+    }
+
+    if run.claimed > 0 {
+        info!(
+            period_start = %period_start,
+            claimed = run.claimed,
+            sent = run.sent,
+            "Points statements sent"
+        );
+    }
+
+    Ok(run)
+}
+
+/// Record the next batch of statements for `[period_start, period_end)`,
+/// or `None` if another instance holds the statement lock.
+async fn claim_points_statements(
+    pool: &PgPool,
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+    expiring_within_days: u32,
+) -> Result<Option<Vec<PointsStatement>>, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_xact_lock($1)")
+        .bind(POINTS_STATEMENT_LOCK_KEY)
+        .fetch_one(&mut *tx)
+        .await?;
+    if !locked {
+        return Ok(None);
+    }
+
+    let statements: Vec<PointsStatement> = sqlx::query_as(
+        r#"
+        WITH activity AS (
+            SELECT
+                pt.user_id,
+                COALESCE(SUM(pt.points) FILTER (WHERE pt.points > 0), 0) AS points_earned,
+                COALESCE(-SUM(pt.points) FILTER (WHERE pt.type = 'redeemed'), 0) AS points_redeemed
+            FROM points_transactions pt
+            WHERE pt.created_at >= ($1::date)::timestamp AT TIME ZONE 'UTC'
+              AND pt.created_at < ($2::date)::timestamp AT TIME ZONE 'UTC'
+            GROUP BY pt.user_id
+        ),
+        inserted AS (
+            INSERT INTO points_statements (
+                user_id, period_start, period_end, points_earned, points_redeemed,
+                points_expiring, current_points, tier_name
+            )
+            SELECT
+                a.user_id,
+                $1,
+                $2,
+                LEAST(a.points_earned, 2147483647)::int,
+                LEAST(a.points_redeemed, 2147483647)::int,
+                LEAST(
+                    COALESCE(ul.current_points, 0),
+                    COALESCE((
+                        SELECT SUM(e.points)
+                        FROM points_transactions e
+                        WHERE e.user_id = a.user_id
+                          AND e.points > 0
+                          AND e.expires_at > NOW()
+                          AND e.expires_at <= NOW() + make_interval(days => $3)
+                    ), 0)
+                )::int,
+                COALESCE(ul.current_points, 0),
+                t.name
+            FROM activity a
+            JOIN users u ON u.id = a.user_id
+            JOIN user_loyalty ul ON ul.user_id = a.user_id
+            LEFT JOIN tiers t ON t.id = ul.tier_id
+            WHERE u.email IS NOT NULL
+              AND COALESCE(u.is_active, true)
+              AND NOT EXISTS (
+                  SELECT 1 FROM notification_preferences np
+                  WHERE np.user_id = u.id
+                    AND np.type IN ('email', 'points')
+                    AND np.enabled = false
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM points_statements ps
+                  WHERE ps.user_id = a.user_id AND ps.period_start = $1
+              )
+            ORDER BY a.user_id
+            LIMIT $4
+            ON CONFLICT (user_id, period_start) DO NOTHING
+            RETURNING *
+        )
+        SELECT
+            i.user_id, u.email, up.first_name, i.period_start, i.period_end,
+            i.points_earned, i.points_redeemed, i.points_expiring, i.current_points,
+            i.tier_name
+        FROM inserted i
+        JOIN users u ON u.id = i.user_id
+        LEFT JOIN user_profiles up ON up.user_id = i.user_id
+        ORDER BY i.user_id
+        "#,
+    )
+    .bind(period_start)
+    .bind(period_end)
+    .bind(i32::try_from(expiring_within_days).unwrap_or(i32::MAX))
+    .bind(POINTS_STATEMENT_BATCH_SIZE as i64)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Some(statements))
+}
+
+fn points_statement_email(
+    statement: &PointsStatement,
+    config: &crate::config::PointsStatementConfig,
+    points_display: &crate::config::PointsDisplayConfig,
+    frontend_url: &str,
+) -> String {
+    use crate::services::email::templates::{points_statement_template, PointsStatementFigures};
+    use crate::utils::display_points;
+
+    let period = statement.period_start.format("%B %Y").to_string();
+    let earned = display_points(statement.points_earned.into(), points_display, None);
+    let redeemed = display_points(statement.points_redeemed.into(), points_display, None);
+    let expiring = display_points(statement.points_expiring.into(), points_display, None);
+    let balance = display_points(statement.current_points.into(), points_display, None);
+
+    points_statement_template(
+        &PointsStatementFigures {
+            name: statement.first_name.as_deref(),
+            period: &period,
+            earned: &earned,
+            redeemed: &redeemed,
+            expiring: &expiring,
+            expiring_within_days: config.expiring_within_days,
+            balance: &balance,
+            tier_name: statement.tier_name.as_deref().unwrap_or("Bronze"),
+        },
+        frontend_url,
+    )
+}
+
+/// Run [`send_points_statements`] every `period` until `shutdown` flips
+/// to `true` (or its sender is dropped).
+///
+/// Each tick keeps claiming batches while they come back full, so the
+/// whole month goes out in one tick; shutdown is checked between batches.
+pub fn spawn_points_statements(
+    pool: PgPool,
+    email_service: std::sync::Arc<dyn crate::services::email::EmailService>,
+    settings: &crate::config::Settings,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) -> tokio::task::JoinHandle<()> {
+    let config = settings.points_statements.clone();
+    let points_display = settings.points_display.clone();
+    let frontend_url = settings.server.frontend_url.clone();
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(config.interval_secs));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    drain_points_statements(
+                        &pool,
+                        email_service.as_ref(),
+                        &config,
+                        &points_display,
+                        &frontend_url,
+                        &shutdown,
+                    )
+                    .await;
+                },
+                changed = shutdown.changed() => {
+                    if changed.is_err() || *shutdown.borrow() {
+                        info!("Points statement worker stopped");
+                        break;
+                    }
+                },
+            }
+        }
+    })
+}
+
+/// Call [`send_points_statements`] until a batch comes back short, a
+/// call fails (logged; the next tick retries) or shutdown is signalled.
+async fn drain_points_statements(
+    pool: &PgPool,
+    email_service: &dyn crate::services::email::EmailService,
+    config: &crate::config::PointsStatementConfig,
+    points_display: &crate::config::PointsDisplayConfig,
+    frontend_url: &str,
+    shutdown: &tokio::sync::watch::Receiver<bool>,
+) {
+    loop {
+        match send_points_statements(pool, email_service, config, points_display, frontend_url)
+            .await
+        {
+            Ok(run) if run.claimed < POINTS_STATEMENT_BATCH_SIZE => break,
+            Ok(_) if *shutdown.borrow() => break,
+            Ok(_) => {},
+            Err(e) => {
+                tracing::error!(error = %e, "Points statement run failed");
+                break;
+            },
+        }
+    }
+}
+
 /// Validation error types for AwardPointsParams
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AwardPointsValidationError {
@@ -718,6 +2749,100 @@ impl AwardPointsParams {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_min_nights_for_points_disabled_preserves_points() {
+        assert_eq!(apply_min_nights_for_points(5000, 1, 0), 5000);
+        assert_eq!(apply_min_nights_for_points(5000, 0, 0), 5000);
+    }
+
+    #[test]
+    fn test_min_nights_for_points_short_stay_earns_zero() {
+        assert_eq!(apply_min_nights_for_points(5000, 1, 2), 0);
+        assert_eq!(apply_min_nights_for_points(5000, 2, 2), 5000);
+        assert_eq!(apply_min_nights_for_points(5000, 3, 2), 5000);
+    }
+
+    #[test]
+    fn test_min_nights_for_points_ignores_non_stays() {
+        // Spending-only awards and deductions are not stays
+        assert_eq!(apply_min_nights_for_points(5000, 0, 3), 5000);
+        assert_eq!(apply_min_nights_for_points(-500, 1, 3), -500);
+    }
+
+    #[test]
+    fn test_map_balance_violation_passes_other_errors_through() {
+        assert!(matches!(
+            map_balance_violation(sqlx::Error::RowNotFound),
+            AppError::Database(sqlx::Error::RowNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_classify_stay() {
+        let now = Utc::now();
+
+        assert_eq!(classify_stay(None, now, 0), Some(StayMilestone::FirstStay));
+        assert_eq!(
+            classify_stay(None, now, 365),
+            Some(StayMilestone::FirstStay)
+        );
+
+        let last_year = now - chrono::Duration::days(400);
+        assert_eq!(
+            classify_stay(Some(last_year), now, 365),
+            Some(StayMilestone::WinBack)
+        );
+        // Win-back detection is off at 0
+        assert_eq!(classify_stay(Some(last_year), now, 0), None);
+
+        let last_month = now - chrono::Duration::days(30);
+        assert_eq!(classify_stay(Some(last_month), now, 365), None);
+    }
+
+    #[test]
+    fn test_points_for_nights() {
+        assert_eq!(points_for_nights(3, 0), 0);
+        assert_eq!(points_for_nights(3, 250), 750);
+        assert_eq!(points_for_nights(0, 250), 0);
+        assert_eq!(points_for_nights(-2, 250), 0);
+        assert_eq!(points_for_nights(i32::MAX, u32::MAX), i32::MAX);
+    }
+
+    #[test]
+    fn test_statement_period_is_previous_month() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let at = |y, m, d| date(y, m, d).and_hms_opt(9, 30, 0).unwrap().and_utc();
+
+        assert_eq!(
+            statement_period(at(2026, 5, 16)),
+            (date(2026, 4, 1), date(2026, 5, 1))
+        );
+        assert_eq!(
+            statement_period(at(2026, 1, 1)),
+            (date(2025, 12, 1), date(2026, 1, 1))
+        );
+        assert_eq!(
+            statement_period(at(2026, 3, 31)),
+            (date(2026, 2, 1), date(2026, 3, 1))
+        );
+    }
+
+    #[test]
+    fn test_spend_earning_rule_tier_multiplier() {
+        let rule = SpendEarningRule {
+            points_per_unit: 10.0,
+            multiplier_by_tier: Some(serde_json::json!({"Bronze": 1.0, "Gold": 1.5})),
+        };
+        assert_eq!(rule.points_for(1000.0, Some("Bronze")), 10_000);
+        assert_eq!(rule.points_for(1000.0, Some("gold")), 15_000);
+        assert_eq!(rule.points_for(1000.0, Some("Platinum")), 10_000);
+        assert_eq!(rule.points_for(1000.0, None), 10_000);
+        assert_eq!(rule.points_for(99.99, Some("Gold")), 1499);
+
+        let default = SpendEarningRule::default();
+        assert_eq!(default.points_for(150.5, Some("Gold")), 1505);
+    }
+
     #[test]
     fn test_pagination_default() {
         let pagination = TransactionPagination::default();
@@ -982,4 +3107,31 @@ mod tests {
         };
         assert!(zero_nights.validate().is_ok());
     }
+
+    #[test]
+    fn test_check_tier_ladder() {
+        assert!(check_tier_ladder(&[(3, 10), (1, 0), (2, 5), (4, 20)]).is_ok());
+        assert!(check_tier_ladder(&[]).is_ok());
+        assert!(check_tier_ladder(&[(1, 0)]).is_ok());
+
+        // Duplicate sort order
+        assert!(check_tier_ladder(&[(1, 0), (2, 5), (2, 8)]).is_err());
+        // Gap breaks the `sort_order + 1` lookup
+        assert!(check_tier_ladder(&[(1, 0), (3, 5)]).is_err());
+        // Nights must increase with sort order
+        assert!(check_tier_ladder(&[(1, 0), (2, 10), (3, 10)]).is_err());
+        assert!(check_tier_ladder(&[(1, 5), (2, 0)]).is_err());
+    }
+
+    #[test]
+    fn test_mask_display_name() {
+        assert_eq!(
+            mask_display_name(Some("Somchai"), Some("prasert")),
+            "Somchai P."
+        );
+        assert_eq!(mask_display_name(Some(" Anna "), None), "Anna");
+        assert_eq!(mask_display_name(Some("Anna"), Some("  ")), "Anna");
+        assert_eq!(mask_display_name(None, Some("Smith")), "Member");
+        assert_eq!(mask_display_name(Some(""), None), "Member");
+    }
 }
@@ -3,8 +3,20 @@
 //! Provides membership ID generation and validation functionality:
 //! - Sequential membership ID generation using database sequence
 //! - Membership ID format validation
+//! - Signed, rotatable membership codes for cards and QR codes
+//!
+//! The membership ID is permanent — it is on record with the hotel. What
+//! gets printed on a card or shown as a QR code is a *membership code*:
+//! `<membership ID>.<version>.<signature>`, signed with the JWT secret.
+//! Regenerating the code ([`rotate_membership_code`]) bumps the version,
+//! so a lost card's code stops verifying against the profile while the
+//! membership ID stays the same.
 
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
 use sqlx::PgPool;
+use uuid::Uuid;
 
 use crate::error::AppError;
 
@@ -119,6 +131,107 @@ pub fn validate_membership_id(id: &str) -> bool {
     numeric_part.chars().all(|c| c.is_ascii_digit())
 }
 
+/// Bytes of the HMAC kept in a membership code (128 bits)
+const MEMBERSHIP_CODE_SIGNATURE_BYTES: usize = 16;
+
+/// Minimum time between two code regenerations for one member
+pub const MEMBERSHIP_CODE_REGENERATION_COOLDOWN_SECS: i64 = 3600;
+
+fn membership_code_mac(secret: &str, membership_id: &str, version: i32) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(b"membership-code:");
+    mac.update(membership_id.as_bytes());
+    mac.update(b":");
+    mac.update(version.to_string().as_bytes());
+    mac
+}
+
+/// The scannable code for `membership_id` at code `version`
+pub fn membership_code(secret: &str, membership_id: &str, version: i32) -> String {
+    let signature = membership_code_mac(secret, membership_id, version)
+        .finalize()
+        .into_bytes();
+    format!(
+        "{}.{}.{}",
+        membership_id,
+        version,
+        URL_SAFE_NO_PAD.encode(&signature[..MEMBERSHIP_CODE_SIGNATURE_BYTES])
+    )
+}
+
+/// Verify a membership code's signature, returning the membership ID and
+/// code version it carries. A valid signature only proves the code was
+/// issued by us; the caller must still compare the version with the
+/// profile's current one to reject codes that have since been replaced.
+pub fn verify_membership_code(secret: &str, code: &str) -> Option<(String, i32)> {
+    let mut parts = code.trim().splitn(3, '.');
+    let membership_id = parts.next()?;
+    let version: i32 = parts.next()?.parse().ok()?;
+    let signature = URL_SAFE_NO_PAD.decode(parts.next()?).ok()?;
+    if membership_id.is_empty() || signature.len() != MEMBERSHIP_CODE_SIGNATURE_BYTES {
+        return None;
+    }
+
+    membership_code_mac(secret, membership_id, version)
+        .verify_truncated_left(&signature)
+        .ok()?;
+    Some((membership_id.to_string(), version))
+}
+
+/// Replace `user_id`'s membership code, invalidating the previous one.
+///
+/// Returns the (unchanged) membership ID and the new code version. At most
+/// one regeneration per [`MEMBERSHIP_CODE_REGENERATION_COOLDOWN_SECS`]; the
+/// check and the bump are one statement, so concurrent requests can't both
+/// get through.
+pub async fn rotate_membership_code(db: &PgPool, user_id: Uuid) -> Result<(String, i32), AppError> {
+    let rotated: Option<(Option<String>, i32)> = sqlx::query_as(
+        r#"
+        UPDATE user_profiles
+        SET membership_code_version = membership_code_version + 1,
+            membership_code_rotated_at = NOW(),
+            updated_at = NOW()
+        WHERE user_id = $1
+          AND membership_id IS NOT NULL
+          AND (membership_code_rotated_at IS NULL
+               OR membership_code_rotated_at <= NOW() - make_interval(secs => $2))
+        RETURNING membership_id, membership_code_version
+        "#,
+    )
+    .bind(user_id)
+    .bind(MEMBERSHIP_CODE_REGENERATION_COOLDOWN_SECS as f64)
+    .fetch_optional(db)
+    .await?;
+
+    if let Some((Some(membership_id), version)) = rotated {
+        return Ok((membership_id, version));
+    }
+
+    // Nothing updated: tell a missing membership apart from the cooldown
+    let profile: Option<(Option<String>, Option<i64>)> = sqlx::query_as(
+        r#"
+        SELECT membership_id,
+               EXTRACT(EPOCH FROM (membership_code_rotated_at + make_interval(secs => $2) - NOW()))::BIGINT
+        FROM user_profiles
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .bind(MEMBERSHIP_CODE_REGENERATION_COOLDOWN_SECS as f64)
+    .fetch_optional(db)
+    .await?;
+
+    match profile {
+        Some((Some(_), retry_after)) => Err(AppError::TooManyRequests(
+            retry_after
+                .unwrap_or(MEMBERSHIP_CODE_REGENERATION_COOLDOWN_SECS)
+                .max(1) as u64,
+        )),
+        _ => Err(AppError::NotFound("Membership ID".to_string())),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,4 +279,37 @@ mod tests {
         assert_eq!(MEMBERSHIP_ID_DIGITS, 6);
         assert_eq!(MEMBERSHIP_ID_LENGTH, 7);
     }
+
+    #[test]
+    fn test_membership_code_round_trip() {
+        let secret = "test-secret";
+        let code = membership_code(secret, "26900001", 3);
+        assert!(code.starts_with("26900001.3."));
+        assert_eq!(
+            verify_membership_code(secret, &code),
+            Some(("26900001".to_string(), 3))
+        );
+    }
+
+    #[test]
+    fn test_membership_code_rejects_tampering() {
+        let secret = "test-secret";
+        let code = membership_code(secret, "26900001", 3);
+
+        // Another secret, another member or another version
+        assert_eq!(verify_membership_code("other-secret", &code), None);
+        let (_, rest) = code.split_once('.').unwrap();
+        assert_eq!(
+            verify_membership_code(secret, &format!("26900002.{rest}")),
+            None
+        );
+        let signature = code.rsplit('.').next().unwrap();
+        assert_eq!(
+            verify_membership_code(secret, &format!("26900001.4.{signature}")),
+            None
+        );
+
+        assert_eq!(verify_membership_code(secret, "26900001"), None);
+        assert_eq!(verify_membership_code(secret, "26900001.3.!!"), None);
+    }
 }
@@ -3,6 +3,8 @@
 //! Contains the core business logic for the loyalty application.
 //! Services are defined as traits to allow for easy testing and mocking.
 
+pub mod account_merge;
+pub mod analytics;
 pub mod auth;
 pub mod booking;
 pub mod coupon;
@@ -13,37 +15,46 @@ pub mod membership_id;
 pub mod notification;
 pub mod oauth;
 pub mod promptpay;
+pub mod referral;
 pub mod slipok;
 pub mod sse;
 pub mod storage;
 pub mod survey;
 pub mod user;
+pub mod user_deletion;
+pub mod webhook;
 
 // Re-export service traits and implementations
 pub use auth::{AuthService, AuthServiceImpl, Claims, RefreshClaims};
 pub use booking::{
     BookingFilters, BookingResponse, BookingService, BookingServiceImpl, BookingStatus,
-    CreateBookingDto, UpdateBookingDto,
+    CreateBookingDto, GuestCount, UpdateBookingDto,
 };
 pub use coupon::{
     CouponFilters, CouponListResponse, CouponService, CouponServiceImpl, CreateCouponDto,
     UpdateCouponDto, UserCouponListResponse, UserCouponWithDetailsResponse,
 };
-pub use email::{EmailConfig, EmailService, EmailServiceImpl, NoOpEmailService};
+pub use email::{
+    CapturingEmailService, EmailConfig, EmailService, EmailServiceImpl, NoOpEmailService,
+};
 pub use loyalty::{
     AwardPointsParams, AwardPointsParamsUuid, LoyaltyService, LoyaltyServiceImpl,
     PointsTransaction, PointsTransactionType, Tier, TierRecalculationResult, TransactionPagination,
     UserLoyalty, UserLoyaltyWithTier,
 };
-pub use membership_id::{generate_membership_id, validate_membership_id};
+pub use membership_id::{
+    generate_membership_id, membership_code, rotate_membership_code, validate_membership_id,
+    verify_membership_code,
+};
 pub use notification::{
-    CreateNotificationDto, NotificationFilters, NotificationListResponse, NotificationService,
-    NotificationServiceImpl,
+    CreateNotificationDto, NotificationEmail, NotificationFilters, NotificationListResponse,
+    NotificationService, NotificationServiceImpl, NotificationThrottle,
 };
 pub use oauth::{
     GoogleTokens, GoogleUserInfo, LineTokens, LineUserInfo, OAuthAuthResult, OAuthService,
     OAuthServiceImpl, OAuthUser, OAuthUserInfo,
 };
+pub use referral::{Referral, ReferralStatus};
 pub use slipok::{
     SlipOKConfig, SlipOKHealthStatus, SlipOKService, SlipOkService, SlipVerificationResult,
     VerificationStatus,
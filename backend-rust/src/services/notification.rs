@@ -3,18 +3,351 @@
 //! Provides notification management functionality including:
 //! - Listing notifications with filtering
 //! - Getting unread notification count
-//! - Creating notifications
+//! - Creating notifications (and pushing them to connected SSE clients)
+//! - Batch creation and paced email delivery for fan-out sends
+//! - Per-user, per-type throttling of noisy notification sources
+//! - Quiet hours: holding non-critical pushes until a member's window ends
 //! - Marking notifications as read
 //! - Deleting notifications
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, NaiveTime, Utc};
+use redis::aio::ConnectionManager;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 
+use crate::config::NotificationThrottleConfig;
 use crate::error::AppError;
-use crate::models::notification::Notification;
+use crate::models::notification::{Notification, NotificationPriority, NotificationType};
+use crate::services::email::EmailService;
+use crate::services::sse::helpers as sse_helpers;
+
+/// Fan-outs to more recipients than this take the batch path: one INSERT
+/// for all in-app notifications and paced, background email delivery.
+pub const BULK_NOTIFICATION_THRESHOLD: usize = 1;
+
+/// Pause between messages when draining a bulk email batch, so assigning a
+/// coupon to 100 users doesn't hit the SMTP relay with 100 sends at once.
+pub(crate) const BULK_EMAIL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Whether a fan-out to `recipients` users should use the batch path
+pub fn is_bulk_send(recipients: usize) -> bool {
+    recipients > BULK_NOTIFICATION_THRESHOLD
+}
+
+/// Notifications dropped by the throttle since startup
+static THROTTLED_NOTIFICATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Number of notifications dropped by [`NotificationThrottle`] since the
+/// process started. Per-type totals are kept in Redis under
+/// `notification_throttle:dropped:{type}`.
+pub fn throttled_notification_count() -> u64 {
+    THROTTLED_NOTIFICATIONS.load(Ordering::Relaxed)
+}
+
+/// Whether a notification is exempt from throttling: `high` priority, the
+/// `security` category and types that aren't deferrable (see
+/// [`NotificationType::dispatch`]) are always delivered.
+pub fn bypasses_throttle(data: &CreateNotificationDto) -> bool {
+    !data.kind().dispatch().deferrable
+        || is_critical(data.effective_priority(), data.category.as_deref())
+}
+
+/// Whether a stored notification's push may wait for the member's quiet
+/// hours to end
+fn is_deferrable(notification: &Notification) -> bool {
+    notification.kind().dispatch().deferrable
+        && !is_critical(notification.priority, notification.category.as_deref())
+}
+
+/// `high` priority and `security` notifications, which are never throttled
+/// or held for quiet hours
+pub fn is_critical(priority: NotificationPriority, category: Option<&str>) -> bool {
+    priority == NotificationPriority::High
+        || category.is_some_and(|category| category.eq_ignore_ascii_case("security"))
+}
+
+/// Most held notifications released per [`release_held_notifications`] call
+pub const QUIET_HOURS_RELEASE_BATCH_SIZE: i64 = 500;
+
+/// When a quiet window from `start` to `end` (local wall-clock times;
+/// `start > end` spans midnight) that contains `local_now` ends, or `None`
+/// when `local_now` is outside it
+pub fn quiet_hours_release(
+    start: NaiveTime,
+    end: NaiveTime,
+    local_now: NaiveDateTime,
+) -> Option<NaiveDateTime> {
+    let now = local_now.time();
+    let quiet = if start < end {
+        now >= start && now < end
+    } else if start > end {
+        now >= start || now < end
+    } else {
+        false
+    };
+    if !quiet {
+        return None;
+    }
+
+    let release = local_now.date().and_time(end);
+    if release <= local_now {
+        Some(release + chrono::Duration::days(1))
+    } else {
+        Some(release)
+    }
+}
+
+/// A member's quiet-hours setting with the current time in their zone
+#[derive(Debug, FromRow)]
+struct QuietHoursRow {
+    user_id: Uuid,
+    start_time: NaiveTime,
+    end_time: NaiveTime,
+    timezone: String,
+    local_now: NaiveDateTime,
+}
+
+/// For each of `user_ids` currently inside their quiet window, the local
+/// end of the window and their time zone
+async fn quiet_hours_releases(
+    pool: &PgPool,
+    user_ids: &[Uuid],
+) -> Result<HashMap<Uuid, (NaiveDateTime, String)>, AppError> {
+    // The zone conversion happens in Postgres so DST rules come from its
+    // tz database
+    let rows: Vec<QuietHoursRow> = sqlx::query_as(
+        r#"
+        SELECT user_id, start_time, end_time, timezone,
+               (NOW() AT TIME ZONE timezone) AS local_now
+        FROM notification_quiet_hours
+        WHERE user_id = ANY($1) AND enabled = TRUE
+        "#,
+    )
+    .bind(user_ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            quiet_hours_release(row.start_time, row.end_time, row.local_now)
+                .map(|release| (row.user_id, (release, row.timezone)))
+        })
+        .collect())
+}
+
+/// Result of one [`release_held_notifications`] run
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHoursRelease {
+    /// Held notifications pushed
+    pub delivered: usize,
+    /// Held notifications that were stale, expired or already read, and
+    /// stay in the inbox without a push
+    pub dropped: usize,
+}
+
+/// Push notifications whose quiet window has ended.
+///
+/// Claims up to [`QUIET_HOURS_RELEASE_BATCH_SIZE`] due holds. Each member
+/// gets one SSE event carrying all of their released notifications rather
+/// than a burst of them. Notifications created more than
+/// `max_delay_hours` ago, expired or already read are not pushed.
+pub async fn release_held_notifications(
+    pool: &PgPool,
+    max_delay_hours: u32,
+) -> Result<QuietHoursRelease, AppError> {
+    let released: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        WITH due AS (
+            SELECT notification_id
+            FROM held_notifications
+            WHERE release_at <= NOW()
+            ORDER BY release_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        DELETE FROM held_notifications h
+        USING due
+        WHERE h.notification_id = due.notification_id
+        RETURNING h.notification_id
+        "#,
+    )
+    .bind(QUIET_HOURS_RELEASE_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    if released.is_empty() {
+        return Ok(QuietHoursRelease::default());
+    }
+
+    let fresh = sqlx::query_as::<_, Notification>(
+        r#"
+        SELECT id, user_id, title, message, type, priority, category, data,
+               read_at, created_at, updated_at, expires_at
+        FROM notifications
+        WHERE id = ANY($1)
+          AND read_at IS NULL
+          AND (expires_at IS NULL OR expires_at > NOW())
+          AND created_at > NOW() - make_interval(hours => $2)
+        ORDER BY created_at
+        "#,
+    )
+    .bind(&released)
+    .bind(max_delay_hours as i32)
+    .fetch_all(pool)
+    .await?;
+
+    let mut by_user: HashMap<Uuid, Vec<serde_json::Value>> = HashMap::new();
+    for notification in &fresh {
+        by_user
+            .entry(notification.user_id)
+            .or_default()
+            .push(notification_payload(notification));
+    }
+    for (user_id, notifications) in by_user {
+        sse_helpers::send_notification(
+            &user_id.to_string(),
+            serde_json::json!({
+                "batch": true,
+                "count": notifications.len(),
+                "notifications": notifications,
+            }),
+        )
+        .await;
+    }
+
+    let result = QuietHoursRelease {
+        delivered: fresh.len(),
+        dropped: released.len() - fresh.len(),
+    };
+    tracing::info!(
+        delivered = result.delivered,
+        dropped = result.dropped,
+        "Held notifications released"
+    );
+
+    Ok(result)
+}
+
+/// Run [`release_held_notifications`] every `period` until `shutdown`
+/// flips to `true` (or its sender is dropped). A run in progress finishes
+/// first.
+pub fn spawn_quiet_hours_release(
+    pool: PgPool,
+    period: Duration,
+    max_delay_hours: u32,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = release_held_notifications(&pool, max_delay_hours).await {
+                        tracing::error!(error = %e, "Held notification release failed");
+                    }
+                },
+                changed = shutdown.changed() => {
+                    if changed.is_err() || *shutdown.borrow() {
+                        tracing::info!("Quiet hours release worker stopped");
+                        break;
+                    }
+                },
+            }
+        }
+    })
+}
+
+/// Caps how many notifications of one type a user receives per window,
+/// dropping the excess.
+///
+/// Counters live in Redis under `notification_throttle:{user}:{type}` and
+/// expire with the window, which starts at the first notification. Redis
+/// errors fail open so a Redis blip never loses a notification.
+#[derive(Clone)]
+pub struct NotificationThrottle {
+    redis: ConnectionManager,
+    config: NotificationThrottleConfig,
+}
+
+impl NotificationThrottle {
+    pub fn new(redis: ConnectionManager, config: NotificationThrottleConfig) -> Self {
+        Self { redis, config }
+    }
+
+    /// Count the notification against its user's window and return whether
+    /// it may be delivered. Dropped notifications are counted and logged.
+    pub async fn allow(&self, data: &CreateNotificationDto) -> bool {
+        if bypasses_throttle(data) {
+            return true;
+        }
+        let kind = data.kind();
+        let limit = self.config.limit_for(kind.as_str());
+        if limit == 0 {
+            return true;
+        }
+
+        let key = format!("notification_throttle:{}:{}", data.user_id, kind);
+        let mut conn = self.redis.clone();
+        let script = redis::Script::new(
+            r#"
+            local count = redis.call('INCR', KEYS[1])
+            if count == 1 then
+                redis.call('EXPIRE', KEYS[1], ARGV[1])
+            end
+            return count
+            "#,
+        );
+
+        let count = match script
+            .key(&key)
+            .arg(self.config.window_secs as i64)
+            .invoke_async::<_, i64>(&mut conn)
+            .await
+        {
+            Ok(count) => count,
+            Err(e) => {
+                tracing::warn!(
+                    user_id = %data.user_id,
+                    notification_type = %kind,
+                    error = %e,
+                    "Redis notification throttle INCR failed — failing open"
+                );
+                return true;
+            },
+        };
+
+        if count <= i64::from(limit) {
+            return true;
+        }
+
+        let dropped = THROTTLED_NOTIFICATIONS.fetch_add(1, Ordering::Relaxed) + 1;
+        let dropped_key = format!("notification_throttle:dropped:{}", kind);
+        if let Err(e) = redis::cmd("INCR")
+            .arg(&dropped_key)
+            .query_async::<_, i64>(&mut conn)
+            .await
+        {
+            tracing::debug!(error = %e, "Failed to record throttled notification");
+        }
+        tracing::warn!(
+            user_id = %data.user_id,
+            notification_type = %kind,
+            limit,
+            window_secs = self.config.window_secs,
+            dropped_total = dropped,
+            "Notification throttled"
+        );
+        false
+    }
+}
 
 /// Filters for listing notifications
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -52,11 +385,40 @@ pub struct CreateNotificationDto {
     pub user_id: Uuid,
     pub title: String,
     pub message: String,
-    pub notification_type: Option<String>,
+    /// `info` when omitted
+    pub notification_type: Option<NotificationType>,
+    /// Delivery priority; `normal` when omitted. `high` skips digest
+    /// batching (see [`NotificationPriority::bypasses_digest`]). Raised to
+    /// the type's minimum when lower.
+    #[serde(default)]
+    pub priority: NotificationPriority,
+    /// Optional free-form category (e.g. `security`, `promotion`) for
+    /// client-side grouping and sounds
+    pub category: Option<String>,
     pub data: Option<serde_json::Value>,
     pub expires_at: Option<DateTime<Utc>>,
 }
 
+impl CreateNotificationDto {
+    /// The notification's type (`info` when omitted)
+    pub fn kind(&self) -> NotificationType {
+        self.notification_type.unwrap_or_default()
+    }
+
+    /// The priority the notification is stored with
+    pub fn effective_priority(&self) -> NotificationPriority {
+        self.kind().priority(self.priority)
+    }
+}
+
+/// An email sent alongside an in-app notification
+#[derive(Debug, Clone)]
+pub struct NotificationEmail {
+    pub to: String,
+    pub subject: String,
+    pub html_body: String,
+}
+
 /// Response containing paginated notifications
 #[derive(Debug, Clone, Serialize)]
 pub struct NotificationListResponse {
@@ -82,11 +444,26 @@ pub trait NotificationService: Send + Sync {
     async fn get_unread_count(&self, user_id: Uuid) -> Result<i64, AppError>;
 
     /// Create a new notification
+    ///
+    /// Fails with [`AppError::RateLimitExceeded`] when the service's
+    /// throttle drops it. A non-critical notification for a member in their
+    /// quiet hours is stored but its push is held (see
+    /// [`release_held_notifications`]).
     async fn create_notification(
         &self,
         data: CreateNotificationDto,
     ) -> Result<Notification, AppError>;
 
+    /// Create notifications for many users in a single statement
+    ///
+    /// Bulk counterpart of `create_notification` for fan-out operations
+    /// (see [`is_bulk_send`]). Each created notification is still pushed to
+    /// its owner's SSE streams. Throttled items are left out of the result.
+    async fn create_notifications_batch(
+        &self,
+        items: Vec<CreateNotificationDto>,
+    ) -> Result<Vec<Notification>, AppError>;
+
     /// Mark a specific notification as read
     async fn mark_as_read(
         &self,
@@ -108,18 +485,87 @@ pub trait NotificationService: Send + Sync {
 /// Implementation of the NotificationService trait
 pub struct NotificationServiceImpl {
     pool: PgPool,
+    throttle: Option<NotificationThrottle>,
 }
 
 impl NotificationServiceImpl {
     /// Create a new NotificationServiceImpl instance
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            throttle: None,
+        }
+    }
+
+    /// Apply per-user, per-type throttling to created notifications
+    pub fn with_throttle(mut self, throttle: NotificationThrottle) -> Self {
+        self.throttle = Some(throttle);
+        self
+    }
+
+    /// Whether the throttle (if any) lets this notification through
+    async fn throttle_allows(&self, data: &CreateNotificationDto) -> bool {
+        match &self.throttle {
+            Some(throttle) => throttle.allow(data).await,
+            None => true,
+        }
     }
 
     /// Get a reference to the database pool
     fn pool(&self) -> &PgPool {
         &self.pool
     }
+
+    /// Push freshly created notifications of pushed types, holding
+    /// deferrable ones for members inside their quiet hours until the
+    /// window ends
+    async fn deliver(&self, notifications: &[Notification]) -> Result<(), AppError> {
+        let notifications: Vec<&Notification> = notifications
+            .iter()
+            .filter(|n| n.kind().dispatch().push)
+            .collect();
+        let holdable: Vec<Uuid> = notifications
+            .iter()
+            .filter(|n| is_deferrable(n))
+            .map(|n| n.user_id)
+            .collect();
+        let releases = if holdable.is_empty() {
+            HashMap::new()
+        } else {
+            quiet_hours_releases(self.pool(), &holdable).await?
+        };
+
+        for notification in notifications {
+            let release = releases
+                .get(&notification.user_id)
+                .filter(|_| is_deferrable(notification));
+            match release {
+                Some((release_at, timezone)) => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO held_notifications (notification_id, user_id, release_at)
+                        VALUES ($1, $2, $3::timestamp AT TIME ZONE $4)
+                        "#,
+                    )
+                    .bind(notification.id)
+                    .bind(notification.user_id)
+                    .bind(release_at)
+                    .bind(timezone)
+                    .execute(self.pool())
+                    .await?;
+
+                    tracing::debug!(
+                        notification_id = %notification.id,
+                        user_id = %notification.user_id,
+                        "Notification push held for quiet hours"
+                    );
+                },
+                None => push_notification(notification).await,
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Internal row type for count queries
@@ -194,6 +640,8 @@ impl NotificationService for NotificationServiceImpl {
                         title,
                         message,
                         type as notification_type,
+                        priority,
+                        category,
                         data,
                         read_at,
                         created_at,
@@ -227,6 +675,8 @@ impl NotificationService for NotificationServiceImpl {
                         title,
                         message,
                         type as notification_type,
+                        priority,
+                        category,
                         data,
                         read_at,
                         created_at,
@@ -284,32 +734,40 @@ impl NotificationService for NotificationServiceImpl {
         &self,
         data: CreateNotificationDto,
     ) -> Result<Notification, AppError> {
-        let notification_type = data.notification_type.unwrap_or_else(|| "info".to_string());
+        let notification_type = data.kind();
+
+        if !self.throttle_allows(&data).await {
+            return Err(AppError::RateLimitExceeded);
+        }
 
-        let row = sqlx::query!(
+        let notification = sqlx::query_as::<_, Notification>(
             r#"
             INSERT INTO notifications (
-                user_id, title, message, type, data, expires_at
-            ) VALUES ($1, $2, $3, $4, $5, $6)
+                user_id, title, message, type, priority, category, data, expires_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             RETURNING
                 id,
                 user_id,
                 title,
                 message,
-                type as notification_type,
+                type,
+                priority,
+                category,
                 data,
                 read_at,
                 created_at,
                 updated_at,
                 expires_at
             "#,
-            data.user_id,
-            &data.title,
-            &data.message,
-            &notification_type,
-            data.data as Option<serde_json::Value>,
-            data.expires_at,
         )
+        .bind(data.user_id)
+        .bind(&data.title)
+        .bind(&data.message)
+        .bind(notification_type.as_str())
+        .bind(data.effective_priority())
+        .bind(&data.category)
+        .bind(&data.data)
+        .bind(data.expires_at)
         .fetch_one(self.pool())
         .await
         .map_err(|e| {
@@ -317,35 +775,106 @@ impl NotificationService for NotificationServiceImpl {
             AppError::Database(e)
         })?;
 
-        let notification = Notification {
-            id: row.id,
-            user_id: row.user_id,
-            title: row.title,
-            message: row.message,
-            notification_type: row.notification_type,
-            data: row.data,
-            read_at: row.read_at,
-            created_at: row.created_at,
-            updated_at: row.updated_at,
-            expires_at: row.expires_at,
-        };
-
         tracing::info!(
             notification_id = %notification.id,
             user_id = %data.user_id,
             notification_type = %notification_type,
+            priority = %notification.priority,
             "Notification created"
         );
 
+        self.deliver(std::slice::from_ref(&notification)).await?;
+
         Ok(notification)
     }
 
+    async fn create_notifications_batch(
+        &self,
+        items: Vec<CreateNotificationDto>,
+    ) -> Result<Vec<Notification>, AppError> {
+        let mut allowed = Vec::with_capacity(items.len());
+        for item in items {
+            if self.throttle_allows(&item).await {
+                allowed.push(item);
+            }
+        }
+        let items = allowed;
+
+        if items.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut user_ids = Vec::with_capacity(items.len());
+        let mut titles = Vec::with_capacity(items.len());
+        let mut messages = Vec::with_capacity(items.len());
+        let mut types = Vec::with_capacity(items.len());
+        let mut priorities = Vec::with_capacity(items.len());
+        let mut categories = Vec::with_capacity(items.len());
+        let mut data = Vec::with_capacity(items.len());
+        let mut expires = Vec::with_capacity(items.len());
+        for item in items {
+            user_ids.push(item.user_id);
+            titles.push(item.title);
+            messages.push(item.message);
+            types.push(item.kind().as_str().to_string());
+            priorities.push(item.effective_priority().as_str().to_string());
+            categories.push(item.category);
+            data.push(item.data);
+            expires.push(item.expires_at);
+        }
+
+        let notifications = sqlx::query_as::<_, Notification>(
+            r#"
+            INSERT INTO notifications (
+                user_id, title, message, type, priority, category, data, expires_at
+            )
+            SELECT * FROM UNNEST(
+                $1::uuid[], $2::varchar[], $3::text[], $4::varchar[],
+                $5::varchar[], $6::varchar[], $7::jsonb[], $8::timestamptz[]
+            )
+            RETURNING
+                id,
+                user_id,
+                title,
+                message,
+                type,
+                priority,
+                category,
+                data,
+                read_at,
+                created_at,
+                updated_at,
+                expires_at
+            "#,
+        )
+        .bind(&user_ids)
+        .bind(&titles)
+        .bind(&messages)
+        .bind(&types)
+        .bind(&priorities)
+        .bind(&categories)
+        .bind(&data)
+        .bind(&expires)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to create notification batch: {}", e);
+            AppError::Database(e)
+        })?;
+
+        tracing::info!(count = notifications.len(), "Notification batch created");
+
+        self.deliver(&notifications).await?;
+
+        Ok(notifications)
+    }
+
     async fn mark_as_read(
         &self,
         notification_id: Uuid,
         user_id: Uuid,
     ) -> Result<Notification, AppError> {
-        let row = sqlx::query!(
+        let notification = sqlx::query_as::<_, Notification>(
             r#"
             UPDATE notifications
             SET read_at = NOW(), updated_at = NOW()
@@ -355,33 +884,22 @@ impl NotificationService for NotificationServiceImpl {
                 user_id,
                 title,
                 message,
-                type as notification_type,
+                type,
+                priority,
+                category,
                 data,
                 read_at,
                 created_at,
                 updated_at,
                 expires_at
             "#,
-            notification_id,
-            user_id,
         )
+        .bind(notification_id)
+        .bind(user_id)
         .fetch_optional(self.pool())
         .await?
         .ok_or_else(|| AppError::NotFound("Notification not found".to_string()))?;
 
-        let notification = Notification {
-            id: row.id,
-            user_id: row.user_id,
-            title: row.title,
-            message: row.message,
-            notification_type: row.notification_type,
-            data: row.data,
-            read_at: row.read_at,
-            created_at: row.created_at,
-            updated_at: row.updated_at,
-            expires_at: row.expires_at,
-        };
-
         tracing::info!(
             notification_id = %notification_id,
             user_id = %user_id,
@@ -444,6 +962,92 @@ impl NotificationService for NotificationServiceImpl {
     }
 }
 
+/// Push a freshly created notification to any open SSE streams so clients
+/// can pick a sound / banner from `priority` and `category` without
+/// refetching the list.
+async fn push_notification(notification: &Notification) {
+    sse_helpers::send_notification(
+        &notification.user_id.to_string(),
+        notification_payload(notification),
+    )
+    .await;
+}
+
+/// SSE payload for one notification
+fn notification_payload(notification: &Notification) -> serde_json::Value {
+    serde_json::json!({
+        "id": notification.id,
+        "title": notification.title,
+        "message": notification.message,
+        "type": notification.notification_type,
+        "priority": notification.priority,
+        "category": notification.category,
+        "data": notification.data,
+        "createdAt": notification.created_at,
+    })
+}
+
+/// Look up email addresses for notification recipients.
+///
+/// Skips users without an email and users who switched off the `email`
+/// notification preference.
+pub async fn email_recipients(
+    pool: &PgPool,
+    user_ids: &[Uuid],
+) -> Result<Vec<(Uuid, String)>, AppError> {
+    let rows: Vec<(Uuid, String)> = sqlx::query_as(
+        r#"
+        SELECT u.id, u.email
+        FROM users u
+        WHERE u.id = ANY($1)
+          AND u.email IS NOT NULL
+          AND NOT EXISTS (
+              SELECT 1 FROM notification_preferences np
+              WHERE np.user_id = u.id AND np.type = 'email' AND np.enabled = false
+          )
+        "#,
+    )
+    .bind(user_ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Deliver notification emails in the background.
+///
+/// A single email goes out immediately. A bulk batch (see
+/// [`is_bulk_send`]) is drained sequentially by one task with
+/// `BULK_EMAIL_INTERVAL` between messages. Failures are logged and never
+/// surface to the caller — the in-app notification is the record of truth.
+pub fn spawn_notification_emails(
+    email_service: Arc<dyn EmailService>,
+    emails: Vec<NotificationEmail>,
+) {
+    if emails.is_empty() || !email_service.is_configured() {
+        return;
+    }
+
+    let paced = is_bulk_send(emails.len());
+    tokio::spawn(async move {
+        let total = emails.len();
+        let mut failed = 0usize;
+        for (i, email) in emails.into_iter().enumerate() {
+            if paced && i > 0 {
+                tokio::time::sleep(BULK_EMAIL_INTERVAL).await;
+            }
+            if let Err(e) = email_service
+                .send_email(&email.to, &email.subject, &email.html_body)
+                .await
+            {
+                failed += 1;
+                tracing::warn!(error = %e, "Failed to send notification email");
+            }
+        }
+        tracing::info!(total, failed, "Notification email batch finished");
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -493,7 +1097,9 @@ mod tests {
             user_id: Uuid::new_v4(),
             title: "Test Title".to_string(),
             message: "Test Message".to_string(),
-            notification_type: Some("info".to_string()),
+            notification_type: Some(NotificationType::Info),
+            priority: NotificationPriority::default(),
+            category: None,
             data: Some(serde_json::json!({"key": "value"})),
             expires_at: None,
         };
@@ -503,6 +1109,86 @@ mod tests {
         assert!(dto.notification_type.is_some());
     }
 
+    #[test]
+    fn test_throttle_bypass() {
+        let mut dto = CreateNotificationDto {
+            user_id: Uuid::new_v4(),
+            title: "New coupon".to_string(),
+            message: "You've received a coupon".to_string(),
+            notification_type: Some(NotificationType::Coupon),
+            priority: NotificationPriority::Normal,
+            category: Some("coupon".to_string()),
+            data: None,
+            expires_at: None,
+        };
+        assert!(!bypasses_throttle(&dto));
+
+        dto.priority = NotificationPriority::High;
+        assert!(bypasses_throttle(&dto));
+
+        dto.priority = NotificationPriority::Low;
+        dto.category = Some("Security".to_string());
+        assert!(bypasses_throttle(&dto));
+
+        // Types that can't wait are never throttled, and go out as `high`
+        dto.category = None;
+        dto.notification_type = Some(NotificationType::Error);
+        assert!(bypasses_throttle(&dto));
+        assert_eq!(dto.effective_priority(), NotificationPriority::High);
+    }
+
+    #[test]
+    fn test_create_notification_dto_type_from_json() {
+        let dto = |notification_type: serde_json::Value| {
+            serde_json::from_value::<CreateNotificationDto>(serde_json::json!({
+                "user_id": Uuid::new_v4(),
+                "title": "Tier upgraded",
+                "message": "Welcome to Gold",
+                "notification_type": notification_type,
+            }))
+        };
+
+        let upgraded = dto(serde_json::json!("tier_change")).unwrap();
+        assert_eq!(upgraded.kind(), NotificationType::TierChange);
+        assert_eq!(upgraded.effective_priority(), NotificationPriority::Normal);
+        assert_eq!(
+            dto(serde_json::Value::Null).unwrap().kind(),
+            NotificationType::Info
+        );
+        assert!(dto(serde_json::json!("tierchange")).is_err());
+    }
+
+    #[test]
+    fn test_create_notification_dto_priority_defaults_to_normal() {
+        let dto: CreateNotificationDto = serde_json::from_value(serde_json::json!({
+            "user_id": Uuid::new_v4(),
+            "title": "Points earned",
+            "message": "You earned 100 points",
+        }))
+        .unwrap();
+
+        assert_eq!(dto.priority, NotificationPriority::Normal);
+        assert!(dto.category.is_none());
+        assert!(!dto.priority.bypasses_digest());
+    }
+
+    #[test]
+    fn test_create_notification_dto_high_priority_bypasses_digest() {
+        let dto: CreateNotificationDto = serde_json::from_value(serde_json::json!({
+            "user_id": Uuid::new_v4(),
+            "title": "New sign-in",
+            "message": "Your account was accessed from a new device",
+            "priority": "high",
+            "category": "security",
+        }))
+        .unwrap();
+
+        assert_eq!(dto.priority, NotificationPriority::High);
+        assert_eq!(dto.category.as_deref(), Some("security"));
+        assert!(dto.priority.bypasses_digest());
+        assert!(!NotificationPriority::Low.bypasses_digest());
+    }
+
     #[test]
     fn test_notification_list_response() {
         let response = NotificationListResponse {
@@ -518,4 +1204,61 @@ mod tests {
         assert_eq!(response.unread, 25);
         assert_eq!(response.total_pages, 5);
     }
+
+    #[test]
+    fn test_is_bulk_send() {
+        assert!(!is_bulk_send(0));
+        assert!(!is_bulk_send(1));
+        assert!(is_bulk_send(2));
+        assert!(is_bulk_send(100));
+    }
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn on(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(2026, 3, day)
+            .unwrap()
+            .and_time(at(hour, minute))
+    }
+
+    #[test]
+    fn test_quiet_hours_spanning_midnight() {
+        let (start, end) = (at(22, 0), at(7, 0));
+        assert_eq!(
+            quiet_hours_release(start, end, on(10, 23, 30)),
+            Some(on(11, 7, 0))
+        );
+        assert_eq!(
+            quiet_hours_release(start, end, on(11, 2, 15)),
+            Some(on(11, 7, 0))
+        );
+        assert_eq!(quiet_hours_release(start, end, on(11, 7, 0)), None);
+        assert_eq!(quiet_hours_release(start, end, on(11, 12, 0)), None);
+        assert_eq!(
+            quiet_hours_release(start, end, on(11, 22, 0)),
+            Some(on(12, 7, 0))
+        );
+    }
+
+    #[test]
+    fn test_quiet_hours_within_one_day() {
+        let (start, end) = (at(13, 0), at(15, 30));
+        assert_eq!(
+            quiet_hours_release(start, end, on(10, 14, 0)),
+            Some(on(10, 15, 30))
+        );
+        assert_eq!(quiet_hours_release(start, end, on(10, 12, 59)), None);
+        assert_eq!(quiet_hours_release(start, end, on(10, 15, 30)), None);
+        // An empty window is never quiet
+        assert_eq!(quiet_hours_release(start, start, on(10, 13, 0)), None);
+    }
+
+    #[test]
+    fn test_critical_notifications() {
+        assert!(is_critical(NotificationPriority::High, None));
+        assert!(is_critical(NotificationPriority::Low, Some("SECURITY")));
+        assert!(!is_critical(NotificationPriority::Normal, Some("points")));
+    }
 }
@@ -915,30 +915,9 @@ impl OAuthService for OAuthServiceImpl {
         .await
         .map_err(|e| AppError::DatabaseQuery(format!("Failed to log OAuth login: {}", e)))?;
 
-        // Enroll in loyalty program with the default Bronze tier. Same
-        // shape as `ensure_loyalty_enrollment`, inlined to stay inside
-        // the transaction.
-        let tier_id: Option<Uuid> =
-            sqlx::query_scalar!(r#"SELECT id FROM tiers WHERE name = 'Bronze' LIMIT 1"#,)
-                .fetch_optional(&mut *tx)
-                .await
-                .map_err(|e| {
-                    AppError::DatabaseQuery(format!("Failed to get default tier: {}", e))
-                })?;
-        let tier_id =
-            tier_id.ok_or_else(|| AppError::NotFound("Default tier not found".to_string()))?;
-        sqlx::query!(
-            r#"
-            INSERT INTO user_loyalty (user_id, tier_id, current_points, total_nights)
-            VALUES ($1, $2, 0, 0)
-            ON CONFLICT (user_id) DO NOTHING
-            "#,
-            new_user.id,
-            tier_id,
-        )
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| AppError::DatabaseQuery(format!("Failed to create loyalty record: {}", e)))?;
+        // Enroll in loyalty program at the entry tier, inside the
+        // provisioning transaction.
+        crate::services::loyalty::ensure_user_loyalty(&mut *tx, new_user.id).await?;
 
         tx.commit().await.map_err(|e| {
             AppError::DatabaseQuery(format!("Failed to commit OAuth provisioning tx: {}", e))
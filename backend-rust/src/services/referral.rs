@@ -0,0 +1,260 @@
+//! Referral service module
+//!
+//! Provides the member referral program:
+//! - Recording a referral when a new member registers with a referral code
+//! - Qualifying a referral when the referee completes their first stay,
+//!   awarding the configured bonus points to both members
+//! - Listing a member's referrals
+//!
+//! A member's referral code is their membership ID, so there is nothing
+//! extra to generate or keep unique.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::config::LoyaltyConfig;
+use crate::error::AppError;
+use crate::services::loyalty::award_bonus_points;
+
+/// Referral lifecycle state, stored in `referrals.status`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferralStatus {
+    /// Referee registered but hasn't completed a stay yet
+    #[default]
+    Pending,
+    /// Referee completed their first stay; bonuses were awarded
+    Rewarded,
+}
+
+impl ReferralStatus {
+    /// Column / wire value for this status
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferralStatus::Pending => "pending",
+            ReferralStatus::Rewarded => "rewarded",
+        }
+    }
+}
+
+impl std::fmt::Display for ReferralStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ReferralStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(ReferralStatus::Pending),
+            "rewarded" => Ok(ReferralStatus::Rewarded),
+            _ => Err(format!("Invalid referral status: {}", s)),
+        }
+    }
+}
+
+/// Referral database entity
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Referral {
+    pub id: Uuid,
+    pub referrer_id: Uuid,
+    pub referee_id: Uuid,
+    pub referral_code: String,
+    pub status: String,
+    pub referrer_points: i32,
+    pub referee_points: i32,
+    pub qualifying_reference: Option<String>,
+    pub qualified_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Normalize a user-entered referral code (membership IDs are uppercase
+/// alphanumerics; users type them in any case with stray whitespace).
+pub fn normalize_referral_code(code: &str) -> String {
+    code.trim().to_ascii_uppercase()
+}
+
+/// Record that `referee_id` registered with `code`.
+///
+/// Call inside the registration transaction. Rejects unknown codes, codes
+/// belonging to inactive accounts, and self-referral with a validation
+/// error. A member can only be referred once: a second call for the same
+/// referee is rejected rather than silently replacing the referrer.
+pub async fn record_referral(
+    conn: &mut sqlx::PgConnection,
+    referee_id: Uuid,
+    code: &str,
+) -> Result<Referral, AppError> {
+    let code = normalize_referral_code(code);
+
+    let referrer_id: Option<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT u.id
+        FROM user_profiles up
+        JOIN users u ON u.id = up.user_id
+        WHERE up.membership_id = $1 AND COALESCE(u.is_active, true)
+        "#,
+    )
+    .bind(&code)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let referrer_id =
+        referrer_id.ok_or_else(|| AppError::Validation("Invalid referral code".to_string()))?;
+
+    if referrer_id == referee_id {
+        return Err(AppError::Validation(
+            "You cannot use your own referral code".to_string(),
+        ));
+    }
+
+    let referral: Option<Referral> = sqlx::query_as(
+        r#"
+        INSERT INTO referrals (referrer_id, referee_id, referral_code)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (referee_id) DO NOTHING
+        RETURNING id, referrer_id, referee_id, referral_code, status,
+                  referrer_points, referee_points, qualifying_reference,
+                  qualified_at, created_at, updated_at
+        "#,
+    )
+    .bind(referrer_id)
+    .bind(referee_id)
+    .bind(&code)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let referral = referral
+        .ok_or_else(|| AppError::Conflict("This account has already been referred".to_string()))?;
+
+    info!(
+        referral_id = %referral.id,
+        referrer_id = %referrer_id,
+        referee_id = %referee_id,
+        "Referral recorded"
+    );
+
+    Ok(referral)
+}
+
+/// Qualify `referee_id`'s pending referral and award both bonuses.
+///
+/// Call when the referee completes a genuine qualifying action — a
+/// completed stay with at least one night. `reference` identifies that
+/// action (e.g. `BOOKING-<id>`) and is stored on the referral and on both
+/// points transactions.
+///
+/// The status flip is a guarded `UPDATE ... WHERE status = 'pending'`, so
+/// the bonuses fire at most once per referee even if several stays
+/// complete concurrently; everything runs on `conn`, so pass a transaction
+/// to keep the flip and the awards atomic. Returns `None` when the referee
+/// has no pending referral.
+pub async fn qualify_referral(
+    conn: &mut sqlx::PgConnection,
+    referee_id: Uuid,
+    reference: &str,
+    config: &LoyaltyConfig,
+) -> Result<Option<Referral>, AppError> {
+    let referrer_points = i32::try_from(config.referrer_bonus_points).unwrap_or(i32::MAX);
+    let referee_points = i32::try_from(config.referee_bonus_points).unwrap_or(i32::MAX);
+
+    let referral: Option<Referral> = sqlx::query_as(
+        r#"
+        UPDATE referrals
+        SET status = 'rewarded',
+            referrer_points = $2,
+            referee_points = $3,
+            qualifying_reference = $4,
+            qualified_at = NOW(),
+            updated_at = NOW()
+        WHERE referee_id = $1 AND status = 'pending'
+        RETURNING id, referrer_id, referee_id, referral_code, status,
+                  referrer_points, referee_points, qualifying_reference,
+                  qualified_at, created_at, updated_at
+        "#,
+    )
+    .bind(referee_id)
+    .bind(referrer_points)
+    .bind(referee_points)
+    .bind(reference)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some(referral) = referral else {
+        return Ok(None);
+    };
+
+    let reference_id = format!("REFERRAL-{}", referral.id);
+    award_bonus_points(
+        &mut *conn,
+        referral.referrer_id,
+        referrer_points,
+        "Referral bonus: referred member completed their first stay",
+        &reference_id,
+    )
+    .await?;
+    award_bonus_points(
+        &mut *conn,
+        referral.referee_id,
+        referee_points,
+        "Referral bonus: welcome stay completed",
+        &reference_id,
+    )
+    .await?;
+
+    info!(
+        referral_id = %referral.id,
+        referrer_id = %referral.referrer_id,
+        referee_id = %referral.referee_id,
+        referrer_points,
+        referee_points,
+        "Referral qualified"
+    );
+
+    Ok(Some(referral))
+}
+
+/// List the referrals made by `referrer_id`, newest first
+pub async fn list_referrals(pool: &PgPool, referrer_id: Uuid) -> Result<Vec<Referral>, AppError> {
+    let referrals = sqlx::query_as(
+        r#"
+        SELECT id, referrer_id, referee_id, referral_code, status,
+               referrer_points, referee_points, qualifying_reference,
+               qualified_at, created_at, updated_at
+        FROM referrals
+        WHERE referrer_id = $1
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(referrer_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(referrals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_referral_code() {
+        assert_eq!(normalize_referral_code("  ab12cd34 "), "AB12CD34");
+        assert_eq!(normalize_referral_code("AB12CD34"), "AB12CD34");
+    }
+
+    #[test]
+    fn test_referral_status_round_trip() {
+        assert_eq!(ReferralStatus::default(), ReferralStatus::Pending);
+        for status in [ReferralStatus::Pending, ReferralStatus::Rewarded] {
+            assert_eq!(status.as_str().parse::<ReferralStatus>(), Ok(status));
+        }
+        assert!("cancelled".parse::<ReferralStatus>().is_err());
+    }
+}
@@ -14,6 +14,16 @@
 //!
 //! SlipOK API endpoint: `https://api.slipok.com/api/line/apikey/{branchId}`
 //!
+//! # Result caching
+//!
+//! With [`SlipOKService::with_result_cache`], definitive results (verified,
+//! or rejected by SlipOK) for slip images are kept in Redis under the
+//! image's SHA-256, so verifying the same slip again — a retried upload,
+//! a re-run job — doesn't spend SlipOK quota. Quota errors, HTTP failures
+//! and transport errors are never cached. An admin force-reverify goes
+//! through [`SlipOKService::reverify_slip_with_context`], which skips the
+//! cached result and replaces it with the fresh one.
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -33,7 +43,11 @@ use serde::{Deserialize, Serialize};
 use std::env;
 use std::time::Duration;
 
+use redis::aio::ConnectionManager;
+use sha2::{Digest, Sha256};
+
 use crate::error::AppError;
+use crate::redis::RedisManager;
 use crate::utils::logging::sanitize_log_value;
 
 /// Default SlipOK API base URL
@@ -45,6 +59,16 @@ const DEFAULT_TIMEOUT_SECS: u64 = 30;
 /// SlipOK quota exceeded error code
 const QUOTA_EXCEEDED_ERROR_CODE: i32 = 1008;
 
+/// Redis key prefix for cached verification results, followed by the
+/// slip's [`slip_fingerprint`]
+const RESULT_CACHE_KEY_PREFIX: &str = "slipok:result:";
+
+/// Error code prefix of results built from a non-2xx SlipOK response
+const HTTP_ERROR_CODE_PREFIX: &str = "HTTP_";
+
+/// Error code of the result returned while SlipOK isn't configured
+const NOT_CONFIGURED_ERROR_CODE: &str = "NOT_CONFIGURED";
+
 /// Verification status indicating the result of slip verification
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -177,10 +201,29 @@ impl SlipVerificationResult {
     /// Create a not configured result
     fn not_configured() -> Self {
         Self::failed(
-            "NOT_CONFIGURED",
+            NOT_CONFIGURED_ERROR_CODE,
             "SlipOK API key or branch ID not configured",
         )
     }
+
+    /// Whether SlipOK gave a verdict on the slip itself — verified, or
+    /// rejected with one of its error codes — rather than failing to
+    /// answer (quota, HTTP error, not configured). Only these results are
+    /// worth caching: asking again would give the same answer.
+    pub fn is_definitive(&self) -> bool {
+        match self.status {
+            VerificationStatus::Verified => true,
+            VerificationStatus::QuotaExceeded => false,
+            VerificationStatus::Failed => self.error_code.as_deref().is_some_and(|code| {
+                !code.starts_with(HTTP_ERROR_CODE_PREFIX) && code != NOT_CONFIGURED_ERROR_CODE
+            }),
+        }
+    }
+}
+
+/// Fingerprint of a slip image: the hex SHA-256 of its bytes
+pub fn slip_fingerprint(slip_image: &[u8]) -> String {
+    hex::encode(Sha256::digest(slip_image))
 }
 
 /// Sender/Receiver account information from SlipOK response
@@ -332,6 +375,21 @@ impl SlipOKConfig {
     }
 }
 
+/// Redis connection and TTL (seconds) for cached verification results
+#[derive(Clone)]
+struct ResultCache {
+    redis: RedisManager,
+    ttl_secs: u64,
+}
+
+impl std::fmt::Debug for ResultCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResultCache")
+            .field("ttl_secs", &self.ttl_secs)
+            .finish_non_exhaustive()
+    }
+}
+
 /// SlipOK service for payment slip verification
 ///
 /// This is the main service struct for verifying payment slips via the SlipOK API.
@@ -342,6 +400,8 @@ pub struct SlipOKService {
     client: Client,
     /// Service configuration (None if not configured)
     config: Option<SlipOKConfig>,
+    /// Cache of definitive results by slip fingerprint (None if off)
+    result_cache: Option<ResultCache>,
 }
 
 /// Simplified SlipOK service alias with direct credential constructor
@@ -368,7 +428,11 @@ impl SlipOKService {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client, config }
+        Self {
+            client,
+            config,
+            result_cache: None,
+        }
     }
 
     /// Create a new SlipOK service instance with API key and branch ID
@@ -391,6 +455,7 @@ impl SlipOKService {
                     .build()
                     .expect("Failed to create HTTP client"),
                 config: None,
+                result_cache: None,
             };
         }
 
@@ -409,6 +474,7 @@ impl SlipOKService {
         Self {
             client,
             config: Some(config),
+            result_cache: None,
         }
     }
 
@@ -422,9 +488,20 @@ impl SlipOKService {
         Self {
             client,
             config: Some(config),
+            result_cache: None,
         }
     }
 
+    /// Cache definitive verification results in Redis for `ttl_secs`
+    /// seconds (see the module docs). A TTL of `0` leaves caching off.
+    pub fn with_result_cache(mut self, redis: ConnectionManager, ttl_secs: u64) -> Self {
+        self.result_cache = (ttl_secs > 0).then(|| ResultCache {
+            redis: RedisManager::from_connection(redis),
+            ttl_secs,
+        });
+        self
+    }
+
     /// Check if the service is configured
     pub fn is_configured(&self) -> bool {
         self.config.is_some()
@@ -521,6 +598,8 @@ impl SlipOKService {
 
     /// Verify a payment slip using raw image bytes with optional booking context
     ///
+    /// Returns the cached result for the same image when there is one.
+    ///
     /// # Arguments
     ///
     /// * `slip_image` - Raw bytes of the slip image
@@ -533,6 +612,27 @@ impl SlipOKService {
         &self,
         slip_image: Bytes,
         booking_id: Option<&str>,
+    ) -> Result<SlipVerificationResult, AppError> {
+        self.verify_slip_bytes(slip_image, booking_id, false).await
+    }
+
+    /// Like [`Self::verify_slip_with_context`], but always asks SlipOK.
+    ///
+    /// For an admin force-reverify: a cached result is ignored, and a
+    /// definitive fresh one replaces it.
+    pub async fn reverify_slip_with_context(
+        &self,
+        slip_image: Bytes,
+        booking_id: Option<&str>,
+    ) -> Result<SlipVerificationResult, AppError> {
+        self.verify_slip_bytes(slip_image, booking_id, true).await
+    }
+
+    async fn verify_slip_bytes(
+        &self,
+        slip_image: Bytes,
+        booking_id: Option<&str>,
+        bypass_cache: bool,
     ) -> Result<SlipVerificationResult, AppError> {
         let booking_ref = booking_id
             .map(|id| sanitize_log_value(id, None))
@@ -547,6 +647,21 @@ impl SlipOKService {
             },
         };
 
+        let fingerprint = self
+            .result_cache
+            .is_some()
+            .then(|| slip_fingerprint(&slip_image));
+        if let (Some(fingerprint), false) = (&fingerprint, bypass_cache) {
+            if let Some(cached) = self.cached_result(fingerprint).await {
+                tracing::info!(
+                    booking_id = %booking_ref,
+                    status = ?cached.status,
+                    "Using cached SlipOK verification result"
+                );
+                return Ok(cached);
+            }
+        }
+
         tracing::info!(
             booking_id = %booking_ref,
             image_size = slip_image.len(),
@@ -563,7 +678,44 @@ impl SlipOKService {
             log: true,
         };
 
-        self.make_api_request(config, &request_body).await
+        let result = self.make_api_request(config, &request_body).await?;
+        if let Some(fingerprint) = &fingerprint {
+            self.cache_result(fingerprint, &result).await;
+        }
+        Ok(result)
+    }
+
+    /// The cached result for a slip, if caching is on and there is one.
+    /// A Redis failure is logged and treated as a miss.
+    async fn cached_result(&self, fingerprint: &str) -> Option<SlipVerificationResult> {
+        let mut cache = self.result_cache.clone()?;
+        let key = format!("{}{}", RESULT_CACHE_KEY_PREFIX, fingerprint);
+        match cache.redis.get_json(&key).await {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to read cached SlipOK result");
+                None
+            },
+        }
+    }
+
+    /// Cache `result` for a slip if it is definitive. A Redis failure is
+    /// logged: the verification itself has already succeeded.
+    async fn cache_result(&self, fingerprint: &str, result: &SlipVerificationResult) {
+        let Some(mut cache) = self.result_cache.clone() else {
+            return;
+        };
+        if !result.is_definitive() {
+            return;
+        }
+        let key = format!("{}{}", RESULT_CACHE_KEY_PREFIX, fingerprint);
+        if let Err(e) = cache
+            .redis
+            .set_json(&key, result, Some(cache.ttl_secs))
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to cache SlipOK result");
+        }
     }
 
     /// Make the actual API request to SlipOK
@@ -610,7 +762,7 @@ impl SlipOKService {
             }
 
             return Ok(SlipVerificationResult::failed(
-                format!("HTTP_{}", status.as_u16()),
+                format!("{}{}", HTTP_ERROR_CODE_PREFIX, status.as_u16()),
                 error_text,
             ));
         }
@@ -860,6 +1012,33 @@ mod tests {
         assert_eq!(result.error_code, Some("QUOTA_EXCEEDED".to_string()));
     }
 
+    #[test]
+    fn test_only_definitive_results_are_cacheable() {
+        let verified = SlipVerificationResult {
+            success: true,
+            status: VerificationStatus::Verified,
+            ..SlipVerificationResult::failed("", "")
+        };
+        assert!(verified.is_definitive());
+
+        // Rejected by SlipOK itself (e.g. 1012: duplicate slip)
+        assert!(SlipVerificationResult::failed("1012", "Duplicate slip").is_definitive());
+        assert!(SlipVerificationResult::failed("VERIFICATION_FAILED", "Failed").is_definitive());
+
+        // SlipOK didn't answer about the slip
+        assert!(!SlipVerificationResult::quota_exceeded(None).is_definitive());
+        assert!(!SlipVerificationResult::failed("HTTP_502", "Bad gateway").is_definitive());
+        assert!(!SlipVerificationResult::not_configured().is_definitive());
+    }
+
+    #[test]
+    fn test_slip_fingerprint() {
+        let fingerprint = slip_fingerprint(b"slip image");
+        assert_eq!(fingerprint.len(), 64);
+        assert_eq!(fingerprint, slip_fingerprint(b"slip image"));
+        assert_ne!(fingerprint, slip_fingerprint(b"other slip image"));
+    }
+
     #[test]
     fn test_parse_thai_datetime() {
         let result = parse_thai_datetime(Some("20240115"), Some("14:30:00"));
@@ -5,7 +5,7 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
 use uuid::Uuid;
@@ -14,7 +14,7 @@ use uuid::Uuid;
 const DEFAULT_CHANNEL_CAPACITY: usize = 100;
 
 /// SSE event types supported by the system
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SseEventType {
     /// New notification received
@@ -29,6 +29,8 @@ pub enum SseEventType {
     Heartbeat,
     /// Slip uploaded (for admin notifications)
     SlipUploaded,
+    /// Progress of a bulk tier recalculation (to the admin who started it)
+    TierRecalculation,
 }
 
 impl std::fmt::Display for SseEventType {
@@ -40,11 +42,65 @@ impl std::fmt::Display for SseEventType {
             SseEventType::Connected => "connected",
             SseEventType::Heartbeat => "heartbeat",
             SseEventType::SlipUploaded => "slip_uploaded",
+            SseEventType::TierRecalculation => "tier_recalculation",
         };
         write!(f, "{}", s)
     }
 }
 
+impl SseEventType {
+    /// Every event type, in declaration order
+    pub const ALL: [SseEventType; 7] = [
+        SseEventType::Notification,
+        SseEventType::LoyaltyUpdate,
+        SseEventType::CouponAssigned,
+        SseEventType::Connected,
+        SseEventType::Heartbeat,
+        SseEventType::SlipUploaded,
+        SseEventType::TierRecalculation,
+    ];
+
+    /// Parse an event name as sent on the wire (e.g. `loyalty_update`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.to_string() == name)
+    }
+}
+
+/// Which event types a connection receives
+///
+/// An empty filter delivers everything. `Connected` is always delivered,
+/// since clients rely on it to know the stream is up.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SseEventFilter {
+    types: HashSet<SseEventType>,
+}
+
+impl SseEventFilter {
+    /// Parse a comma-separated list of event names (the `types` query
+    /// parameter). Unknown names are ignored, so a client built against a
+    /// newer server doesn't lose its stream; if none are known the filter
+    /// is empty and delivers everything.
+    pub fn parse(types: &str) -> Self {
+        let mut filter = Self::default();
+        for name in types.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            match SseEventType::from_name(name) {
+                Some(event_type) => {
+                    filter.types.insert(event_type);
+                },
+                None => tracing::debug!(event_type = %name, "Ignoring unknown SSE event type"),
+            }
+        }
+        filter
+    }
+
+    /// Whether an event of `event_type` is delivered
+    pub fn allows(&self, event_type: SseEventType) -> bool {
+        self.types.is_empty()
+            || event_type == SseEventType::Connected
+            || self.types.contains(&event_type)
+    }
+}
+
 /// SSE event structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SseEvent {
@@ -116,6 +172,8 @@ pub struct ClientConnection {
     pub user_id: String,
     /// Broadcast sender for this client
     sender: broadcast::Sender<SseEvent>,
+    /// Event types this client subscribed to
+    filter: SseEventFilter,
 }
 
 impl ClientConnection {
@@ -162,10 +220,23 @@ impl SseConnectionManager {
         }
     }
 
-    /// Add a new client connection for a user
+    /// Add a new client connection for a user, receiving every event type
     ///
     /// Returns the client ID and a receiver for SSE events.
     pub async fn add_client(&self, user_id: &str) -> (Uuid, broadcast::Receiver<SseEvent>) {
+        self.add_client_with_filter(user_id, SseEventFilter::default())
+            .await
+    }
+
+    /// Add a new client connection for a user that only receives the event
+    /// types `filter` allows
+    ///
+    /// Returns the client ID and a receiver for SSE events.
+    pub async fn add_client_with_filter(
+        &self,
+        user_id: &str,
+        filter: SseEventFilter,
+    ) -> (Uuid, broadcast::Receiver<SseEvent>) {
         let client_id = Uuid::new_v4();
         let (sender, receiver) = broadcast::channel(DEFAULT_CHANNEL_CAPACITY);
 
@@ -173,6 +244,7 @@ impl SseConnectionManager {
             client_id,
             user_id: user_id.to_string(),
             sender,
+            filter,
         };
 
         let mut connections = self.connections.write().await;
@@ -219,6 +291,9 @@ impl SseConnectionManager {
             let mut error_count = 0;
 
             for (client_id, connection) in user_connections.iter() {
+                if !connection.filter.allows(event.event_type) {
+                    continue;
+                }
                 match connection.sender.send(event.clone()) {
                     Ok(_) => sent_count += 1,
                     Err(e) => {
@@ -264,6 +339,9 @@ impl SseConnectionManager {
 
         for (user_id, user_connections) in connections.iter() {
             for (client_id, connection) in user_connections.iter() {
+                if !connection.filter.allows(event.event_type) {
+                    continue;
+                }
                 match connection.sender.send(event.clone()) {
                     Ok(_) => sent_count += 1,
                     Err(e) => {
@@ -457,6 +535,66 @@ mod tests {
         assert_eq!(received_2.event_type, SseEventType::Heartbeat);
     }
 
+    #[tokio::test]
+    async fn test_filtered_client_only_receives_subscribed_types() {
+        let manager = SseConnectionManager::new();
+        let user_id = "user-filter";
+
+        let (_all_id, mut all_receiver) = manager.add_client(user_id).await;
+        let (_filtered_id, mut filtered_receiver) = manager
+            .add_client_with_filter(user_id, SseEventFilter::parse("coupon_assigned"))
+            .await;
+
+        manager
+            .send_to_user(user_id, SseEvent::notification(serde_json::json!({})))
+            .await;
+        manager
+            .send_to_user(user_id, SseEvent::coupon_assigned(serde_json::json!({})))
+            .await;
+
+        assert_eq!(
+            all_receiver.recv().await.unwrap().event_type,
+            SseEventType::Notification
+        );
+        assert_eq!(
+            all_receiver.recv().await.unwrap().event_type,
+            SseEventType::CouponAssigned
+        );
+        assert_eq!(
+            filtered_receiver.recv().await.unwrap().event_type,
+            SseEventType::CouponAssigned
+        );
+        assert!(filtered_receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_sse_event_filter_parse() {
+        let filter = SseEventFilter::parse("notification, loyalty_update,,bogus");
+        assert!(filter.allows(SseEventType::Notification));
+        assert!(filter.allows(SseEventType::LoyaltyUpdate));
+        assert!(filter.allows(SseEventType::Connected));
+        assert!(!filter.allows(SseEventType::CouponAssigned));
+        assert!(!filter.allows(SseEventType::SlipUploaded));
+
+        // Empty, or nothing known, means everything
+        for types in ["", " , ", "bogus,other"] {
+            let filter = SseEventFilter::parse(types);
+            assert_eq!(filter, SseEventFilter::default());
+            assert!(SseEventType::ALL.into_iter().all(|t| filter.allows(t)));
+        }
+    }
+
+    #[test]
+    fn test_sse_event_type_from_name() {
+        for event_type in SseEventType::ALL {
+            assert_eq!(
+                SseEventType::from_name(&event_type.to_string()),
+                Some(event_type)
+            );
+        }
+        assert_eq!(SseEventType::from_name("Notification"), None);
+    }
+
     #[test]
     fn test_sse_event_to_string() {
         let event = SseEvent::connected("Test connection");
@@ -5,18 +5,26 @@
 //! - Avatar upload with processing
 //! - File deletion and management
 //! - Storage statistics
+//! - Chunked (resumable) uploads for general files and slips
 //!
 //! Configuration via environment variables:
 //! - UPLOAD_DIR: Base directory for uploads (default: ./uploads)
 //! - MAX_FILE_SIZE: Maximum file size in bytes (default: 5MB)
+//! - UPLOAD_MAX_CONCURRENT: Unfinished chunked uploads allowed per user (default: 3)
+//! - UPLOAD_MAX_CHUNK_SIZE: Largest accepted chunk in bytes (default: 1MB)
+//! - UPLOAD_SESSION_TTL_SECS: Lifetime of an unfinished chunked upload (default: 3600)
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
 use image::ImageReader;
+use serde::{Deserialize, Serialize};
 use std::env;
 use std::io::Cursor;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
@@ -28,6 +36,22 @@ const DEFAULT_UPLOAD_DIR: &str = "./uploads";
 /// Default maximum file size (5MB)
 const DEFAULT_MAX_FILE_SIZE: usize = 5 * 1024 * 1024;
 
+/// Default number of unfinished chunked uploads a user may hold
+const DEFAULT_MAX_CONCURRENT_UPLOADS: usize = 3;
+
+/// Default maximum chunk size (1MB)
+const DEFAULT_MAX_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Default lifetime of an unfinished chunked upload (1 hour)
+const DEFAULT_UPLOAD_SESSION_TTL_SECS: u64 = 60 * 60;
+
+/// Manifest file describing a chunked upload, inside its session directory
+const UPLOAD_MANIFEST: &str = "manifest.json";
+
+/// Serializes the per-user concurrency check with session creation, so two
+/// simultaneous `init` calls cannot both squeeze under the cap.
+static UPLOAD_SESSIONS_LOCK: Mutex<()> = Mutex::const_new(());
+
 /// Storage configuration
 #[derive(Debug, Clone)]
 pub struct StorageConfig {
@@ -39,6 +63,8 @@ pub struct StorageConfig {
     pub slips_dir: PathBuf,
     /// Backup directory
     pub backup_dir: PathBuf,
+    /// Directory holding unfinished chunked uploads
+    pub chunks_dir: PathBuf,
     /// Maximum file size in bytes (default: 5MB)
     pub max_file_size: usize,
     /// Maximum avatar file size in bytes (default: 15MB for processing)
@@ -49,6 +75,12 @@ pub struct StorageConfig {
     pub max_storage_size: u64,
     /// Avatar size in pixels (width and height)
     pub avatar_size: u32,
+    /// Unfinished chunked uploads allowed per user (default: 3)
+    pub max_concurrent_uploads: usize,
+    /// Largest accepted chunk in bytes (default: 1MB)
+    pub max_chunk_size: usize,
+    /// How long an unfinished chunked upload is kept (default: 1 hour)
+    pub upload_session_ttl: Duration,
 }
 
 impl Default for StorageConfig {
@@ -67,6 +99,7 @@ impl Default for StorageConfig {
             upload_dir: base_dir.clone(),
             avatars_dir: base_dir.join("avatars"),
             slips_dir: base_dir.join("slips"),
+            chunks_dir: base_dir.join("chunks"),
             backup_dir,
             max_file_size: env::var("MAX_FILE_SIZE")
                 .ok()
@@ -76,6 +109,20 @@ impl Default for StorageConfig {
             max_slip_size: 10 * 1024 * 1024,   // 10MB for slips
             max_storage_size: 10 * 1024 * 1024 * 1024, // 10GB total
             avatar_size: 400,                  // 400x400 pixels (2x for retina)
+            max_concurrent_uploads: env::var("UPLOAD_MAX_CONCURRENT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(DEFAULT_MAX_CONCURRENT_UPLOADS),
+            max_chunk_size: env::var("UPLOAD_MAX_CHUNK_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(DEFAULT_MAX_CHUNK_SIZE),
+            upload_session_ttl: Duration::from_secs(
+                env::var("UPLOAD_SESSION_TTL_SECS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(DEFAULT_UPLOAD_SESSION_TTL_SECS),
+            ),
         }
     }
 }
@@ -87,6 +134,7 @@ impl StorageConfig {
         Self {
             avatars_dir: base_dir.join("avatars"),
             slips_dir: base_dir.join("slips"),
+            chunks_dir: base_dir.join("chunks"),
             backup_dir: base_dir.join("backup"),
             upload_dir: base_dir,
             ..Default::default()
@@ -185,6 +233,58 @@ pub struct StorageReportData {
     pub usage_percent: f64,
 }
 
+/// What a chunked upload becomes once assembled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UploadKind {
+    /// General file, stored like `POST /storage/upload`
+    File,
+    /// Payment slip, stored like `POST /storage/slip`
+    Slip,
+}
+
+/// An unfinished chunked upload, persisted as `manifest.json` in its
+/// session directory under `chunks_dir`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkedUpload {
+    pub upload_id: Uuid,
+    pub user_id: String,
+    pub kind: UploadKind,
+    pub filename: String,
+    pub content_type: String,
+    pub total_size: u64,
+    pub chunk_size: u64,
+    pub total_chunks: u32,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl ChunkedUpload {
+    /// Exact length chunk `index` must have; only the last may be short
+    fn expected_chunk_len(&self, index: u32) -> u64 {
+        if index + 1 < self.total_chunks {
+            self.chunk_size
+        } else {
+            self.total_size - self.chunk_size * u64::from(self.total_chunks - 1)
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+}
+
+/// Progress of a chunked upload after a chunk is stored
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkedUploadProgress {
+    pub upload_id: Uuid,
+    pub received_chunks: u32,
+    pub total_chunks: u32,
+    /// Chunk indexes still missing, so a client can resume after a dropout
+    pub missing_chunks: Vec<u32>,
+}
+
 /// Storage service providing file management functionality
 #[derive(Clone)]
 pub struct StorageService {
@@ -210,6 +310,7 @@ impl StorageService {
             &self.config.upload_dir,
             &self.config.avatars_dir,
             &self.config.slips_dir,
+            &self.config.chunks_dir,
             &self.config.backup_dir,
         ];
 
@@ -708,6 +809,349 @@ impl StorageService {
     }
 }
 
+// ============================================================================
+// Chunked uploads
+// ============================================================================
+//
+// Flow: `init_chunked_upload` validates the declared type and size and
+// creates `chunks_dir/<upload_id>/` with a manifest; `upload_chunk` writes
+// `<index>.part` files (re-sending a chunk overwrites it, which is what makes
+// the upload resumable); `complete_chunked_upload` assembles the parts and
+// hands the result to `save_file` / `save_slip`, so the final file passes
+// exactly the same MIME and size validation as a direct upload.
+//
+// Unfinished sessions expire after `upload_session_ttl`; expired sessions
+// are rejected on access and removed by `cleanup_expired_uploads`, which
+// `spawn_upload_cleanup` runs periodically.
+
+impl StorageService {
+    fn max_upload_size(&self, kind: UploadKind) -> usize {
+        match kind {
+            UploadKind::File => self.config.max_file_size,
+            UploadKind::Slip => self.config.max_slip_size,
+        }
+    }
+
+    fn upload_dir_for(&self, upload_id: Uuid) -> PathBuf {
+        self.config.chunks_dir.join(upload_id.to_string())
+    }
+
+    async fn read_manifest(dir: &Path) -> Option<ChunkedUpload> {
+        let raw = fs::read(dir.join(UPLOAD_MANIFEST)).await.ok()?;
+        serde_json::from_slice(&raw).ok()
+    }
+
+    /// Every session under `chunks_dir` with its directory; unreadable
+    /// directories are returned with `None` so cleanup can remove them.
+    async fn list_uploads(&self) -> Vec<(PathBuf, Option<ChunkedUpload>)> {
+        let mut uploads = Vec::new();
+        if let Ok(mut entries) = fs::read_dir(&self.config.chunks_dir).await {
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let path = entry.path();
+                if path.is_dir() {
+                    let manifest = Self::read_manifest(&path).await;
+                    uploads.push((path, manifest));
+                }
+            }
+        }
+        uploads
+    }
+
+    /// Load `upload_id` for `user_id`, rejecting expired sessions and other
+    /// users' uploads (reported as not found, so ids can't be probed).
+    async fn load_upload(&self, user_id: &str, upload_id: Uuid) -> AppResult<ChunkedUpload> {
+        let dir = self.upload_dir_for(upload_id);
+        match Self::read_manifest(&dir).await {
+            Some(upload) if upload.user_id == user_id && !upload.is_expired() => Ok(upload),
+            _ => Err(AppError::NotFound("Upload".to_string())),
+        }
+    }
+
+    /// Start a chunked upload of `total_size` bytes split into `chunk_size`
+    /// pieces.
+    ///
+    /// The declared type and size are checked against the same limits as a
+    /// direct upload so a doomed upload fails before any data is sent. A user
+    /// holding `max_concurrent_uploads` unfinished uploads gets
+    /// `TooManyRequests` until one completes, is aborted or expires.
+    pub async fn init_chunked_upload(
+        &self,
+        user_id: &str,
+        kind: UploadKind,
+        filename: &str,
+        content_type: &str,
+        total_size: u64,
+        chunk_size: u64,
+    ) -> AppResult<ChunkedUpload> {
+        let type_allowed = match kind {
+            UploadKind::File => AllowedMimeTypes::is_valid_type(content_type),
+            UploadKind::Slip => AllowedMimeTypes::is_valid_slip_type(content_type),
+        };
+        if !type_allowed {
+            return Err(AppError::UnsupportedMediaType(format!(
+                "Unsupported content type: {}",
+                content_type
+            )));
+        }
+        if total_size == 0 {
+            return Err(AppError::Validation(
+                "totalSize must be positive".to_string(),
+            ));
+        }
+        if total_size > self.max_upload_size(kind) as u64 {
+            return Err(AppError::PayloadTooLarge);
+        }
+        if chunk_size == 0 || chunk_size > self.config.max_chunk_size as u64 {
+            return Err(AppError::Validation(format!(
+                "chunkSize must be between 1 and {} bytes",
+                self.config.max_chunk_size
+            )));
+        }
+        let total_chunks = u32::try_from(total_size.div_ceil(chunk_size))
+            .map_err(|_| AppError::Validation("Too many chunks".to_string()))?;
+
+        let _guard = UPLOAD_SESSIONS_LOCK.lock().await;
+
+        let now = Utc::now();
+        let active: Vec<ChunkedUpload> = self
+            .list_uploads()
+            .await
+            .into_iter()
+            .filter_map(|(_, upload)| upload)
+            .filter(|upload| upload.user_id == user_id && !upload.is_expired())
+            .collect();
+        if active.len() >= self.config.max_concurrent_uploads {
+            let retry_after = active
+                .iter()
+                .map(|upload| (upload.expires_at - now).num_seconds().max(1) as u64)
+                .min()
+                .unwrap_or(1);
+            return Err(AppError::TooManyRequests(retry_after));
+        }
+
+        let ttl = chrono::Duration::from_std(self.config.upload_session_ttl)
+            .unwrap_or_else(|_| chrono::Duration::seconds(DEFAULT_UPLOAD_SESSION_TTL_SECS as i64));
+        let upload = ChunkedUpload {
+            upload_id: Uuid::new_v4(),
+            user_id: user_id.to_string(),
+            kind,
+            filename: sanitize_filename(filename),
+            content_type: content_type.to_lowercase(),
+            total_size,
+            chunk_size,
+            total_chunks,
+            expires_at: now + ttl,
+        };
+
+        let dir = self.upload_dir_for(upload.upload_id);
+        fs::create_dir_all(&dir).await.map_err(|e| {
+            error!("Failed to create upload session directory {:?}: {}", dir, e);
+            AppError::Internal(format!("Failed to start upload: {}", e))
+        })?;
+        let manifest = serde_json::to_vec(&upload)
+            .map_err(|e| AppError::Internal(format!("Failed to encode upload manifest: {}", e)))?;
+        fs::write(dir.join(UPLOAD_MANIFEST), manifest)
+            .await
+            .map_err(|e| {
+                error!("Failed to write upload manifest in {:?}: {}", dir, e);
+                AppError::Internal(format!("Failed to start upload: {}", e))
+            })?;
+
+        info!(
+            "Chunked upload {} started by user {}: {} bytes in {} chunks",
+            upload.upload_id, user_id, total_size, total_chunks
+        );
+        Ok(upload)
+    }
+
+    /// Store chunk `index` of an upload. Re-sending a chunk replaces it.
+    pub async fn upload_chunk(
+        &self,
+        user_id: &str,
+        upload_id: Uuid,
+        index: u32,
+        data: Bytes,
+    ) -> AppResult<ChunkedUploadProgress> {
+        let upload = self.load_upload(user_id, upload_id).await?;
+
+        if index >= upload.total_chunks {
+            return Err(AppError::Validation(format!(
+                "Chunk index must be below {}",
+                upload.total_chunks
+            )));
+        }
+        let expected = upload.expected_chunk_len(index);
+        if data.len() as u64 != expected {
+            return Err(AppError::Validation(format!(
+                "Chunk {} must be exactly {} bytes, got {}",
+                index,
+                expected,
+                data.len()
+            )));
+        }
+
+        // Write to a temporary name and rename, so an interrupted write never
+        // leaves a truncated part that looks complete.
+        let dir = self.upload_dir_for(upload_id);
+        let part_path = dir.join(format!("{}.part", index));
+        let tmp_path = dir.join(format!("{}.part.{}", index, Uuid::new_v4()));
+        fs::write(&tmp_path, &data).await.map_err(|e| {
+            error!("Failed to write chunk {:?}: {}", tmp_path, e);
+            AppError::Internal(format!("Failed to write chunk: {}", e))
+        })?;
+        fs::rename(&tmp_path, &part_path).await.map_err(|e| {
+            error!("Failed to store chunk {:?}: {}", part_path, e);
+            AppError::Internal(format!("Failed to store chunk: {}", e))
+        })?;
+
+        self.upload_progress(&upload).await
+    }
+
+    async fn upload_progress(&self, upload: &ChunkedUpload) -> AppResult<ChunkedUploadProgress> {
+        let dir = self.upload_dir_for(upload.upload_id);
+        let mut missing_chunks = Vec::new();
+        for index in 0..upload.total_chunks {
+            let len = fs::metadata(dir.join(format!("{}.part", index)))
+                .await
+                .map(|m| m.len())
+                .ok();
+            if len != Some(upload.expected_chunk_len(index)) {
+                missing_chunks.push(index);
+            }
+        }
+
+        Ok(ChunkedUploadProgress {
+            upload_id: upload.upload_id,
+            received_chunks: upload.total_chunks - missing_chunks.len() as u32,
+            total_chunks: upload.total_chunks,
+            missing_chunks,
+        })
+    }
+
+    /// Progress of an unfinished upload, for resuming after a dropout
+    pub async fn chunked_upload_status(
+        &self,
+        user_id: &str,
+        upload_id: Uuid,
+    ) -> AppResult<ChunkedUploadProgress> {
+        let upload = self.load_upload(user_id, upload_id).await?;
+        self.upload_progress(&upload).await
+    }
+
+    /// Assemble every chunk and store the result as a general file or slip.
+    ///
+    /// Fails with `Validation` (keeping the session) while chunks are
+    /// missing. Once assembled, the session is removed whether or not the
+    /// final validation in `save_file` / `save_slip` passes.
+    pub async fn complete_chunked_upload(
+        &self,
+        user_id: &str,
+        upload_id: Uuid,
+    ) -> AppResult<String> {
+        let upload = self.load_upload(user_id, upload_id).await?;
+
+        let progress = self.upload_progress(&upload).await?;
+        if !progress.missing_chunks.is_empty() {
+            return Err(AppError::Validation(format!(
+                "Upload is incomplete: {} of {} chunks missing",
+                progress.missing_chunks.len(),
+                upload.total_chunks
+            )));
+        }
+
+        let dir = self.upload_dir_for(upload_id);
+        let mut assembled = BytesMut::with_capacity(upload.total_size as usize);
+        for index in 0..upload.total_chunks {
+            let part = fs::read(dir.join(format!("{}.part", index)))
+                .await
+                .map_err(|e| {
+                    error!(
+                        "Failed to read chunk {} of upload {}: {}",
+                        index, upload_id, e
+                    );
+                    AppError::Internal(format!("Failed to read chunk: {}", e))
+                })?;
+            assembled.extend_from_slice(&part);
+        }
+        self.remove_upload_dir(&dir).await;
+
+        let data = assembled.freeze();
+        if data.len() as u64 != upload.total_size {
+            return Err(AppError::Validation(
+                "Assembled upload does not match the declared size".to_string(),
+            ));
+        }
+
+        let url = match upload.kind {
+            UploadKind::File => {
+                self.save_file(data, &upload.filename, &upload.content_type)
+                    .await?
+            },
+            UploadKind::Slip => self.save_slip(data, &upload.content_type).await?,
+        };
+
+        info!("Chunked upload {} completed: {}", upload_id, url);
+        Ok(url)
+    }
+
+    /// Discard an unfinished upload and its chunks
+    pub async fn abort_chunked_upload(&self, user_id: &str, upload_id: Uuid) -> AppResult<()> {
+        self.load_upload(user_id, upload_id).await?;
+        self.remove_upload_dir(&self.upload_dir_for(upload_id))
+            .await;
+        info!("Chunked upload {} aborted by user {}", upload_id, user_id);
+        Ok(())
+    }
+
+    async fn remove_upload_dir(&self, dir: &Path) {
+        if let Err(e) = fs::remove_dir_all(dir).await {
+            warn!("Failed to remove upload session {:?}: {}", dir, e);
+        }
+    }
+
+    /// Remove expired (or unreadable) chunked upload sessions. Returns the
+    /// number of sessions removed.
+    pub async fn cleanup_expired_uploads(&self) -> u64 {
+        let mut removed = 0u64;
+        for (dir, upload) in self.list_uploads().await {
+            // A directory without a readable manifest is either mid-`init` or
+            // debris; only remove it once it is older than the session TTL.
+            let stale = match upload {
+                Some(upload) => upload.is_expired(),
+                None => fs::metadata(&dir)
+                    .await
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|modified| modified.elapsed().ok())
+                    .is_some_and(|age| age > self.config.upload_session_ttl),
+            };
+            if stale {
+                self.remove_upload_dir(&dir).await;
+                removed += 1;
+            }
+        }
+        if removed > 0 {
+            info!("Removed {} expired chunked upload(s)", removed);
+        }
+        removed
+    }
+
+    /// Periodically garbage-collect abandoned chunked uploads in the
+    /// background. The sweep runs at a tenth of the session TTL, clamped to
+    /// between one minute and one hour.
+    pub fn spawn_upload_cleanup(self) -> tokio::task::JoinHandle<()> {
+        let period = (self.config.upload_session_ttl / 10)
+            .clamp(Duration::from_secs(60), Duration::from_secs(60 * 60));
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                self.cleanup_expired_uploads().await;
+            }
+        })
+    }
+}
+
 impl Default for StorageService {
     fn default() -> Self {
         Self::new()
@@ -1063,6 +1507,143 @@ mod tests {
         assert!(result.is_err());
     }
 
+    fn chunked_service(dir: &Path) -> StorageService {
+        let mut config = StorageConfig::new(dir);
+        config.max_chunk_size = 4;
+        config.max_concurrent_uploads = 2;
+        config.upload_session_ttl = Duration::from_secs(60);
+        StorageService::with_config(config)
+    }
+
+    #[tokio::test]
+    async fn test_chunked_upload_assembles_and_saves() {
+        let temp_dir = tempdir().unwrap();
+        let service = chunked_service(temp_dir.path());
+
+        let upload = service
+            .init_chunked_upload(
+                "user-1",
+                UploadKind::File,
+                "doc.pdf",
+                "application/pdf",
+                10,
+                4,
+            )
+            .await
+            .unwrap();
+        assert_eq!(upload.total_chunks, 3);
+
+        // Out of order and with a retry, as a flaky mobile client would.
+        service
+            .upload_chunk("user-1", upload.upload_id, 2, Bytes::from_static(b"89"))
+            .await
+            .unwrap();
+        service
+            .upload_chunk("user-1", upload.upload_id, 0, Bytes::from_static(b"0123"))
+            .await
+            .unwrap();
+        let progress = service
+            .chunked_upload_status("user-1", upload.upload_id)
+            .await
+            .unwrap();
+        assert_eq!(progress.missing_chunks, vec![1]);
+        assert!(service
+            .complete_chunked_upload("user-1", upload.upload_id)
+            .await
+            .is_err());
+
+        service
+            .upload_chunk("user-1", upload.upload_id, 1, Bytes::from_static(b"4567"))
+            .await
+            .unwrap();
+        let url = service
+            .complete_chunked_upload("user-1", upload.upload_id)
+            .await
+            .unwrap();
+
+        let filename = url.rsplit('/').next().unwrap();
+        let saved = fs::read(service.get_file_path(filename)).await.unwrap();
+        assert_eq!(saved, b"0123456789");
+        assert!(!service.upload_dir_for(upload.upload_id).exists());
+    }
+
+    #[tokio::test]
+    async fn test_chunked_upload_validates_like_direct_upload() {
+        let temp_dir = tempdir().unwrap();
+        let service = chunked_service(temp_dir.path());
+
+        let wrong_type = service
+            .init_chunked_upload(
+                "user-1",
+                UploadKind::Slip,
+                "slip.pdf",
+                "application/pdf",
+                8,
+                4,
+            )
+            .await;
+        assert!(matches!(wrong_type, Err(AppError::UnsupportedMediaType(_))));
+
+        let too_large = service
+            .init_chunked_upload(
+                "user-1",
+                UploadKind::Slip,
+                "slip.png",
+                "image/png",
+                service.config.max_slip_size as u64 + 1,
+                4,
+            )
+            .await;
+        assert!(matches!(too_large, Err(AppError::PayloadTooLarge)));
+
+        let upload = service
+            .init_chunked_upload("user-1", UploadKind::Slip, "slip.png", "image/png", 8, 4)
+            .await
+            .unwrap();
+        let short_chunk = service
+            .upload_chunk("user-1", upload.upload_id, 0, Bytes::from_static(b"01"))
+            .await;
+        assert!(matches!(short_chunk, Err(AppError::Validation(_))));
+
+        let other_user = service
+            .upload_chunk("user-2", upload.upload_id, 0, Bytes::from_static(b"0123"))
+            .await;
+        assert!(matches!(other_user, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_chunked_upload_concurrency_cap_and_cleanup() {
+        let temp_dir = tempdir().unwrap();
+        let service = chunked_service(temp_dir.path());
+
+        for _ in 0..2 {
+            service
+                .init_chunked_upload("user-1", UploadKind::File, "a.png", "image/png", 4, 4)
+                .await
+                .unwrap();
+        }
+        let third = service
+            .init_chunked_upload("user-1", UploadKind::File, "a.png", "image/png", 4, 4)
+            .await;
+        assert!(matches!(third, Err(AppError::TooManyRequests(_))));
+
+        // Other users have their own allowance.
+        service
+            .init_chunked_upload("user-2", UploadKind::File, "a.png", "image/png", 4, 4)
+            .await
+            .unwrap();
+
+        // Once the sessions expire they are swept and the cap frees up.
+        let mut expired = service.clone();
+        expired.config.upload_session_ttl = Duration::ZERO;
+        let abandoned = expired
+            .init_chunked_upload("user-3", UploadKind::File, "a.png", "image/png", 4, 4)
+            .await
+            .unwrap();
+        assert_eq!(service.cleanup_expired_uploads().await, 1);
+        assert!(!service.upload_dir_for(abandoned.upload_id).exists());
+    }
+
     #[test]
     fn test_file_exists_sync() {
         let config = StorageConfig::new("/nonexistent");
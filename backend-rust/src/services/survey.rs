@@ -5,15 +5,23 @@
 //! - Survey listing with filters
 //! - Survey response submission
 //! - User survey invitations
+//! - Member targeting (tier, nights, last stay) via [`SurveyTargeting`]
+//! - Reminders for unanswered invitations ([`send_survey_reminders`])
+//! - Answer validation against question rules ([`validate_survey_answers`])
+//! - Single-use invitation links for guests ([`lock_link_invitation`])
+
+use std::collections::HashMap;
 
 use async_trait::async_trait;
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgConnection, PgPool};
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::models::survey::{Survey, SurveyQuestion, SurveyResponse};
+use crate::models::survey::{Survey, SurveyQuestion, SurveyQuestionType, SurveyResponse};
+use crate::utils::validation::{validate_email, validate_phone};
 
 // ============================================================================
 // DTOs (Data Transfer Objects)
@@ -130,6 +138,362 @@ pub struct SurveyResponseListResponse {
     pub total_pages: i32,
 }
 
+// ============================================================================
+// Targeting
+// ============================================================================
+
+/// Which members a survey is for, stored in `surveys.target_segment`.
+///
+/// Every criterion is optional and they combine with AND; an empty object
+/// (the column default) targets everyone. Targeting narrows the
+/// member-facing list and who may respond. It never hides a survey from
+/// admins, and an explicit invitation overrides it (the admin picked that
+/// member on purpose).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SurveyTargeting {
+    /// Member must currently be in one of these tiers
+    pub tier_ids: Vec<Uuid>,
+    /// Member must have stayed at least this many nights in total
+    pub min_nights: Option<i32>,
+    /// Member's most recent completed stay must have ended within this
+    /// many days
+    pub last_stay_within_days: Option<i32>,
+}
+
+/// The member attributes targeting is evaluated against
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemberSegment {
+    pub tier_id: Option<Uuid>,
+    pub total_nights: i32,
+    /// Check-out date of the latest completed stay
+    pub last_stay: Option<NaiveDate>,
+}
+
+impl SurveyTargeting {
+    /// Parse and validate targeting sent by an admin. `None` and `{}` mean
+    /// "everyone".
+    pub fn parse(value: Option<&serde_json::Value>) -> Result<Self, AppError> {
+        let targeting: Self = match value {
+            None | Some(serde_json::Value::Null) => Self::default(),
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|e| AppError::Validation(format!("Invalid survey targeting: {}", e)))?,
+        };
+
+        if targeting.min_nights.is_some_and(|n| n < 0) {
+            return Err(AppError::Validation(
+                "minNights cannot be negative".to_string(),
+            ));
+        }
+        if targeting.last_stay_within_days.is_some_and(|d| d < 1) {
+            return Err(AppError::Validation(
+                "lastStayWithinDays must be at least 1".to_string(),
+            ));
+        }
+
+        Ok(targeting)
+    }
+
+    /// Targeting as stored on a survey row.
+    ///
+    /// Rows written before targeting was enforced may hold arbitrary JSON;
+    /// anything that doesn't parse targets everyone, as it always did.
+    pub fn from_stored(value: Option<&serde_json::Value>) -> Self {
+        value
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Whether `member` qualifies, as of `today`
+    pub fn matches(&self, member: &MemberSegment, today: NaiveDate) -> bool {
+        if !self.tier_ids.is_empty()
+            && !member
+                .tier_id
+                .is_some_and(|tier_id| self.tier_ids.contains(&tier_id))
+        {
+            return false;
+        }
+
+        if self.min_nights.is_some_and(|min| member.total_nights < min) {
+            return false;
+        }
+
+        if let Some(days) = self.last_stay_within_days {
+            let cutoff = today - chrono::Duration::days(i64::from(days));
+            if !member.last_stay.is_some_and(|last| last >= cutoff) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Load the attributes survey targeting looks at for `user_id`.
+///
+/// A member without a loyalty row has no tier and zero nights.
+pub async fn load_member_segment(pool: &PgPool, user_id: Uuid) -> Result<MemberSegment, AppError> {
+    let (tier_id, total_nights, last_stay): (Option<Uuid>, Option<i32>, Option<NaiveDate>) =
+        sqlx::query_as(
+            r#"
+            SELECT
+                (SELECT tier_id FROM user_loyalty WHERE user_id = $1),
+                (SELECT total_nights FROM user_loyalty WHERE user_id = $1),
+                (SELECT MAX(check_out_date) FROM bookings
+                 WHERE user_id = $1 AND status IN ('completed', 'checked_out'))
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(MemberSegment {
+        tier_id,
+        total_nights: total_nights.unwrap_or(0),
+        last_stay,
+    })
+}
+
+/// Check that every tier named in `targeting` exists
+pub async fn validate_targeting_tiers(
+    pool: &PgPool,
+    targeting: &SurveyTargeting,
+) -> Result<(), AppError> {
+    if targeting.tier_ids.is_empty() {
+        return Ok(());
+    }
+
+    let found: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tiers WHERE id = ANY($1)")
+        .bind(&targeting.tier_ids)
+        .fetch_one(pool)
+        .await?;
+    let mut unique = targeting.tier_ids.clone();
+    unique.sort();
+    unique.dedup();
+    if found != unique.len() as i64 {
+        return Err(AppError::Validation(
+            "Survey targeting references an unknown tier".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Answer Validation
+// ============================================================================
+
+/// Check submitted `answers` (question id -> answer) against each
+/// question's `required` flag and `validation` rules.
+///
+/// Required questions are only enforced on a completed submission, so a
+/// draft can be saved part-way through; answers that are given are always
+/// checked. Fails with `ValidationWithDetails` keyed by question id.
+pub fn validate_survey_answers(
+    questions: &[SurveyQuestion],
+    answers: &serde_json::Value,
+    is_completed: bool,
+) -> Result<(), AppError> {
+    let mut details: HashMap<String, Vec<String>> = HashMap::new();
+
+    for question in questions {
+        let answer = answers
+            .get(&question.id)
+            .filter(|answer| !is_blank_answer(answer));
+        let errors = match answer {
+            Some(answer) => answer_errors(question, answer),
+            None if question.required && is_completed => {
+                vec!["This question is required".to_string()]
+            },
+            None => continue,
+        };
+        if !errors.is_empty() {
+            details.insert(question.id.clone(), errors);
+        }
+    }
+
+    if details.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::ValidationWithDetails {
+            message: "Some answers are invalid".to_string(),
+            details,
+        })
+    }
+}
+
+/// Whether an answer counts as not given: null, whitespace or no choices
+fn is_blank_answer(answer: &serde_json::Value) -> bool {
+    match answer {
+        serde_json::Value::Null => true,
+        serde_json::Value::String(text) => text.trim().is_empty(),
+        serde_json::Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+/// What is wrong with a given answer to `question`, if anything. A failed
+/// min/max rule reports the question's `custom_error` when it has one.
+fn answer_errors(question: &SurveyQuestion, answer: &serde_json::Value) -> Vec<String> {
+    let rules = question.validation.as_ref();
+    let rule_error = |default: String| {
+        rules
+            .and_then(|rules| rules.custom_error.clone())
+            .unwrap_or(default)
+    };
+    let mut errors = Vec::new();
+
+    match question.question_type {
+        SurveyQuestionType::Number | SurveyQuestionType::Scale | SurveyQuestionType::Rating => {
+            let number = answer
+                .as_f64()
+                .or_else(|| answer.as_str().and_then(|text| text.trim().parse().ok()));
+            let Some(number) = number else {
+                return vec!["Answer must be a number".to_string()];
+            };
+            if let Some(min) = rules.and_then(|rules| rules.min_value) {
+                if number < min {
+                    errors.push(rule_error(format!("Answer must be at least {}", min)));
+                }
+            }
+            if let Some(max) = rules.and_then(|rules| rules.max_value) {
+                if number > max {
+                    errors.push(rule_error(format!("Answer must be at most {}", max)));
+                }
+            }
+        },
+        SurveyQuestionType::Text | SurveyQuestionType::TextArea => {
+            let Some(text) = answer.as_str() else {
+                return vec!["Answer must be text".to_string()];
+            };
+            let length = text.chars().count() as i64;
+            if let Some(min) = rules.and_then(|rules| rules.min_length) {
+                if length < i64::from(min) {
+                    errors.push(rule_error(format!(
+                        "Answer must be at least {} characters",
+                        min
+                    )));
+                }
+            }
+            if let Some(max) = rules.and_then(|rules| rules.max_length) {
+                if length > i64::from(max) {
+                    errors.push(rule_error(format!(
+                        "Answer must be at most {} characters",
+                        max
+                    )));
+                }
+            }
+        },
+        SurveyQuestionType::Email => {
+            if !answer
+                .as_str()
+                .is_some_and(|text| validate_email(text.trim()))
+            {
+                errors.push("Answer must be a valid email address".to_string());
+            }
+        },
+        SurveyQuestionType::Phone => {
+            if !answer
+                .as_str()
+                .is_some_and(|text| validate_phone(text.trim()))
+            {
+                errors.push("Answer must be a valid phone number".to_string());
+            }
+        },
+        SurveyQuestionType::SingleChoice
+        | SurveyQuestionType::MultipleChoice
+        | SurveyQuestionType::Date => {},
+    }
+
+    errors
+}
+
+// ============================================================================
+// Invitation Links
+// ============================================================================
+
+/// Days an invitation link stays valid when the sender doesn't say
+pub const SURVEY_INVITATION_DEFAULT_TTL_DAYS: u32 = 14;
+
+/// Longest validity a sender may give an invitation link
+pub const SURVEY_INVITATION_MAX_TTL_DAYS: u32 = 90;
+
+/// Generate a random invitation link token (32 bytes, URL-safe base64)
+pub fn generate_invitation_token() -> String {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use rand::RngCore;
+
+    let mut token_bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut token_bytes);
+    URL_SAFE_NO_PAD.encode(token_bytes)
+}
+
+/// What `survey_invitations.token_hash` stores for a link token; the token
+/// itself is never persisted
+pub fn invitation_token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// The frontend page an invitation link opens
+pub fn invitation_link(frontend_url: &str, token: &str) -> String {
+    format!(
+        "{}/surveys/invite/{}",
+        frontend_url.trim_end_matches('/'),
+        token
+    )
+}
+
+/// An invitation found by its link token
+#[derive(Debug, Clone, FromRow)]
+pub struct LinkInvitation {
+    pub id: Uuid,
+    pub survey_id: Uuid,
+    /// The invited member; `None` for a guest
+    pub user_id: Option<Uuid>,
+    pub expires_at: Option<NaiveDateTime>,
+    used: bool,
+    expired: bool,
+}
+
+/// Find the invitation behind a link token and lock it for the caller's
+/// transaction, so two submissions of the same link can't both get
+/// through.
+///
+/// Fails with `NotFound` for an unknown token, `Conflict` once the link
+/// has been used and `BadRequest` after it has expired.
+pub async fn lock_link_invitation(
+    conn: &mut PgConnection,
+    token: &str,
+) -> Result<LinkInvitation, AppError> {
+    let invitation: LinkInvitation = sqlx::query_as(
+        r#"
+        SELECT id, survey_id, user_id, expires_at,
+               used_at IS NOT NULL AS used,
+               (expires_at IS NOT NULL AND expires_at <= NOW()) AS expired
+        FROM survey_invitations
+        WHERE token_hash = $1 AND survey_id IS NOT NULL
+        FOR UPDATE
+        "#,
+    )
+    .bind(invitation_token_hash(token))
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Survey invitation".to_string()))?;
+
+    if invitation.used {
+        return Err(AppError::Conflict(
+            "This survey invitation has already been used".to_string(),
+        ));
+    }
+    if invitation.expired {
+        return Err(AppError::BadRequest(
+            "This survey invitation has expired".to_string(),
+        ));
+    }
+
+    Ok(invitation)
+}
+
 // ============================================================================
 // Survey Service Trait
 // ============================================================================
@@ -465,6 +829,8 @@ impl SurveyService for SurveyServiceImpl {
         let questions_json = serde_json::to_value(&normalized_questions)
             .map_err(|e| AppError::Serialization(e.to_string()))?;
 
+        let targeting = SurveyTargeting::parse(data.target_segment.as_ref())?;
+        validate_targeting_tiers(self.pool(), &targeting).await?;
         let target_segment_json = data.target_segment.unwrap_or(serde_json::json!({}));
         let access_type = data.access_type.unwrap_or_else(|| "public".to_string());
         let status = data.status.unwrap_or_else(|| "draft".to_string());
@@ -529,6 +895,9 @@ impl SurveyService for SurveyServiceImpl {
             .await?
             .ok_or_else(|| AppError::NotFound("Survey".to_string()))?;
 
+        let is_completed = data.is_completed.unwrap_or(false);
+        validate_survey_answers(&survey.questions, &data.answers, is_completed)?;
+
         let total_questions = survey.questions.len();
 
         // Count answered questions from the answers JSON
@@ -543,7 +912,6 @@ impl SurveyService for SurveyServiceImpl {
             0
         };
 
-        let is_completed = data.is_completed.unwrap_or(false);
         let completed_at: Option<NaiveDateTime> = if is_completed {
             Some(chrono::Utc::now().naive_utc())
         } else {
@@ -660,6 +1028,156 @@ impl SurveyService for SurveyServiceImpl {
     }
 }
 
+// ============================================================================
+// Invitation Reminders
+// ============================================================================
+
+/// Most reminders sent per run; the rest go out on the next tick
+const SURVEY_REMINDER_BATCH_SIZE: i64 = 500;
+
+/// An invitation claimed for a reminder by [`send_survey_reminders`]
+#[derive(Debug, Clone, FromRow)]
+struct DueSurveyReminder {
+    invitation_id: Uuid,
+    survey_id: Uuid,
+    user_id: Uuid,
+    survey_title: String,
+}
+
+/// Send one reminder for each invitation left unanswered `delay_hours`
+/// after it was sent.
+///
+/// An invitation is due only while it can still be answered: it hasn't
+/// been completed or expired, the member has no completed response (an
+/// answer submitted without going through the invitation counts), and
+/// the survey is `active` and not past `scheduled_end`. Each due
+/// invitation is stamped with `reminder_sent_at` and committed before the
+/// notification goes out, so a member is reminded at most once even if
+/// two instances run the job together (`SKIP LOCKED`) or a notification
+/// fails.
+///
+/// Returns the number of reminders sent.
+pub async fn send_survey_reminders(pool: &PgPool, delay_hours: u64) -> Result<u64, AppError> {
+    let delay_hours = i32::try_from(delay_hours).unwrap_or(i32::MAX);
+
+    let due: Vec<DueSurveyReminder> = sqlx::query_as(
+        r#"
+        UPDATE survey_invitations si
+        SET reminder_sent_at = NOW(), updated_at = NOW()
+        FROM (
+            SELECT i.id, s.title
+            FROM survey_invitations i
+            JOIN surveys s ON s.id = i.survey_id
+            WHERE i.reminder_sent_at IS NULL
+              AND i.user_id IS NOT NULL
+              AND i.status IN ('pending', 'sent', 'viewed')
+              AND COALESCE(i.sent_at, i.created_at) <= NOW() - make_interval(hours => $1)
+              AND (i.expires_at IS NULL OR i.expires_at > NOW())
+              AND s.status = 'active'
+              AND (s.scheduled_end IS NULL OR s.scheduled_end > NOW())
+              AND NOT EXISTS (
+                  SELECT 1 FROM survey_responses r
+                  WHERE r.survey_id = i.survey_id
+                    AND r.user_id = i.user_id
+                    AND r.is_completed = true
+              )
+            ORDER BY COALESCE(i.sent_at, i.created_at)
+            LIMIT $2
+            FOR UPDATE OF i SKIP LOCKED
+        ) due
+        WHERE si.id = due.id
+          AND si.reminder_sent_at IS NULL
+        RETURNING si.id AS invitation_id, si.survey_id, si.user_id, due.title AS survey_title
+        "#,
+    )
+    .bind(delay_hours)
+    .bind(SURVEY_REMINDER_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    let mut sent = 0;
+    for reminder in &due {
+        if notify_survey_reminder(pool, reminder).await {
+            sent += 1;
+        }
+    }
+
+    if !due.is_empty() {
+        tracing::info!(due = due.len(), sent, "Survey reminders sent");
+    }
+
+    Ok(sent)
+}
+
+async fn notify_survey_reminder(pool: &PgPool, reminder: &DueSurveyReminder) -> bool {
+    use crate::models::notification::{NotificationPriority, NotificationType};
+    use crate::services::notification::{
+        CreateNotificationDto, NotificationService, NotificationServiceImpl,
+    };
+
+    let result = NotificationServiceImpl::new(pool.clone())
+        .create_notification(CreateNotificationDto {
+            user_id: reminder.user_id,
+            title: "Your feedback is still welcome".to_string(),
+            message: format!(
+                "You haven't finished \"{}\" yet. It only takes a few minutes.",
+                reminder.survey_title
+            ),
+            notification_type: Some(NotificationType::Survey),
+            priority: NotificationPriority::Normal,
+            category: Some("survey".to_string()),
+            data: Some(serde_json::json!({
+                "surveyId": reminder.survey_id,
+                "invitationId": reminder.invitation_id,
+                "reminder": true,
+            })),
+            expires_at: None,
+        })
+        .await;
+
+    match result {
+        Ok(_) => true,
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                invitation_id = %reminder.invitation_id,
+                user_id = %reminder.user_id,
+                "Failed to send survey reminder"
+            );
+            false
+        },
+    }
+}
+
+/// Run [`send_survey_reminders`] every `period` until `shutdown` flips to
+/// `true` (or its sender is dropped). A run in progress finishes first.
+pub fn spawn_survey_reminders(
+    pool: PgPool,
+    period: std::time::Duration,
+    delay_hours: u64,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = send_survey_reminders(&pool, delay_hours).await {
+                        tracing::error!(error = %e, "Survey reminder run failed");
+                    }
+                },
+                changed = shutdown.changed() => {
+                    if changed.is_err() || *shutdown.borrow() {
+                        tracing::info!("Survey reminder worker stopped");
+                        break;
+                    }
+                },
+            }
+        }
+    })
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -667,6 +1185,27 @@ impl SurveyService for SurveyServiceImpl {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::survey::SurveyQuestionValidation;
+
+    fn question(id: &str, question_type: SurveyQuestionType, required: bool) -> SurveyQuestion {
+        SurveyQuestion {
+            id: id.to_string(),
+            question_type,
+            text: "Question".to_string(),
+            description: None,
+            required,
+            options: None,
+            validation: None,
+            order: 1,
+        }
+    }
+
+    fn answer_details(result: Result<(), AppError>) -> HashMap<String, Vec<String>> {
+        match result {
+            Err(AppError::ValidationWithDetails { details, .. }) => details,
+            other => panic!("expected validation details, got {:?}", other),
+        }
+    }
 
     #[test]
     fn test_survey_filters_default() {
@@ -722,4 +1261,158 @@ mod tests {
         assert_eq!(response.limit, 10);
         assert_eq!(response.total_pages, 0);
     }
+
+    fn member(
+        tier_id: Option<Uuid>,
+        total_nights: i32,
+        last_stay: Option<NaiveDate>,
+    ) -> MemberSegment {
+        MemberSegment {
+            tier_id,
+            total_nights,
+            last_stay,
+        }
+    }
+
+    #[test]
+    fn test_untargeted_survey_matches_everyone() {
+        let targeting = SurveyTargeting::parse(Some(&serde_json::json!({}))).unwrap();
+        let today = NaiveDate::from_ymd_opt(2026, 5, 16).unwrap();
+        assert!(targeting.matches(&MemberSegment::default(), today));
+    }
+
+    #[test]
+    fn test_targeting_criteria_combine() {
+        let gold = Uuid::new_v4();
+        let targeting = SurveyTargeting::parse(Some(&serde_json::json!({
+            "tierIds": [gold],
+            "minNights": 5,
+            "lastStayWithinDays": 30
+        })))
+        .unwrap();
+        let today = NaiveDate::from_ymd_opt(2026, 5, 16).unwrap();
+        let recent = NaiveDate::from_ymd_opt(2026, 5, 1);
+        let stale = NaiveDate::from_ymd_opt(2026, 1, 1);
+
+        assert!(targeting.matches(&member(Some(gold), 5, recent), today));
+        assert!(!targeting.matches(&member(Some(Uuid::new_v4()), 5, recent), today));
+        assert!(!targeting.matches(&member(None, 5, recent), today));
+        assert!(!targeting.matches(&member(Some(gold), 4, recent), today));
+        assert!(!targeting.matches(&member(Some(gold), 5, stale), today));
+        assert!(!targeting.matches(&member(Some(gold), 5, None), today));
+    }
+
+    #[test]
+    fn test_targeting_parse_rejects_bad_values() {
+        assert!(SurveyTargeting::parse(Some(&serde_json::json!({ "minNights": -1 }))).is_err());
+        assert!(
+            SurveyTargeting::parse(Some(&serde_json::json!({ "lastStayWithinDays": 0 }))).is_err()
+        );
+        assert!(SurveyTargeting::parse(Some(&serde_json::json!({ "tierIds": "gold" }))).is_err());
+        // Stored legacy JSON that doesn't parse targets everyone
+        assert_eq!(
+            SurveyTargeting::from_stored(Some(&serde_json::json!({ "tierIds": "gold" }))),
+            SurveyTargeting::default()
+        );
+    }
+
+    #[test]
+    fn test_invitation_tokens() {
+        let token = generate_invitation_token();
+        assert_eq!(token.len(), 43);
+        assert_ne!(token, generate_invitation_token());
+
+        let hash = invitation_token_hash(&token);
+        assert_eq!(hash.len(), 64);
+        assert_ne!(hash, token);
+        assert_eq!(hash, invitation_token_hash(&token));
+
+        assert_eq!(
+            invitation_link("https://example.com/", "abc"),
+            "https://example.com/surveys/invite/abc"
+        );
+    }
+
+    #[test]
+    fn test_required_answers_are_enforced_on_completion_only() {
+        let questions = vec![
+            question("q1", SurveyQuestionType::SingleChoice, true),
+            question("q2", SurveyQuestionType::Text, false),
+        ];
+        let answers = serde_json::json!({"q1": "  ", "q2": "Lovely stay"});
+
+        let details = answer_details(validate_survey_answers(&questions, &answers, true));
+        assert_eq!(details.len(), 1);
+        assert_eq!(details["q1"], vec!["This question is required".to_string()]);
+
+        assert!(validate_survey_answers(&questions, &answers, false).is_ok());
+    }
+
+    #[test]
+    fn test_scale_answers_must_be_in_range() {
+        let mut scale = question("q1", SurveyQuestionType::Scale, true);
+        scale.validation = Some(SurveyQuestionValidation {
+            min_length: None,
+            max_length: None,
+            min_value: Some(1.0),
+            max_value: Some(10.0),
+            pattern: None,
+            custom_error: None,
+        });
+        let questions = vec![scale];
+
+        assert!(validate_survey_answers(&questions, &serde_json::json!({"q1": 7}), true).is_ok());
+        assert!(
+            validate_survey_answers(&questions, &serde_json::json!({"q1": "10"}), true).is_ok()
+        );
+
+        let details = answer_details(validate_survey_answers(
+            &questions,
+            &serde_json::json!({"q1": 11}),
+            true,
+        ));
+        assert_eq!(details["q1"], vec!["Answer must be at most 10".to_string()]);
+
+        let details = answer_details(validate_survey_answers(
+            &questions,
+            &serde_json::json!({"q1": "lots"}),
+            false,
+        ));
+        assert_eq!(details["q1"], vec!["Answer must be a number".to_string()]);
+    }
+
+    #[test]
+    fn test_text_contact_answers_are_checked() {
+        let mut comment = question("comment", SurveyQuestionType::TextArea, false);
+        comment.validation = Some(SurveyQuestionValidation {
+            min_length: None,
+            max_length: Some(5),
+            min_value: None,
+            max_value: None,
+            pattern: None,
+            custom_error: Some("Keep it short".to_string()),
+        });
+        let questions = vec![
+            comment,
+            question("email", SurveyQuestionType::Email, false),
+            question("phone", SurveyQuestionType::Phone, false),
+        ];
+
+        let valid = serde_json::json!({
+            "comment": "Great",
+            "email": "guest@example.com",
+            "phone": "0812345678"
+        });
+        assert!(validate_survey_answers(&questions, &valid, true).is_ok());
+
+        let invalid = serde_json::json!({
+            "comment": "Great stay",
+            "email": "not-an-email",
+            "phone": "123"
+        });
+        let details = answer_details(validate_survey_answers(&questions, &invalid, true));
+        assert_eq!(details["comment"], vec!["Keep it short".to_string()]);
+        assert!(details.contains_key("email"));
+        assert!(details.contains_key("phone"));
+    }
 }
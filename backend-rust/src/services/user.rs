@@ -514,17 +514,7 @@ impl UserService for UserServiceImpl {
         .await?;
 
         // Create initial loyalty record
-        sqlx::query!(
-            r#"
-            INSERT INTO user_loyalty (user_id, current_points, total_nights, tier_id)
-            SELECT $1, 0, 0, id
-            FROM tiers
-            WHERE name = 'Bronze'
-            "#,
-            user.id,
-        )
-        .execute(&mut *tx)
-        .await?;
+        crate::services::loyalty::ensure_user_loyalty(&mut *tx, user.id).await?;
 
         // Commit transaction
         tx.commit().await?;
@@ -0,0 +1,268 @@
+//! User deletion service
+//!
+//! Deleting an account runs one cascade, inside the caller's transaction,
+//! that treats each kind of related record according to what it is:
+//!
+//! | Records | Outcome |
+//! |---------|---------|
+//! | `users`, `user_profiles` | Kept as a tombstone with every personal field cleared and `deleted_at` set |
+//! | Refresh / password-reset tokens | Deleted, so no new access token can be minted |
+//! | Points transactions, referrals, tier upgrade grants | Kept unchanged; they reference only the tombstone |
+//! | Bookings | Kept; guest free-text `notes` cleared |
+//! | Used / expired / revoked coupons | Kept (redemption history) |
+//! | `user_audit_log` | Kept; IP address and user agent cleared |
+//! | Notifications, notification preferences, idempotency keys, captured emails | Deleted; they are personal and have no aggregate value |
+//! | Survey responses and invitations, unused coupons | Per [`RelatedRecordPolicy`] (`USER_DELETION_RELATED_RECORDS`) |
+//!
+//! Under `anonymize`, survey responses and invitations are detached from the
+//! user (`user_id = NULL`) so survey analytics still count them, and
+//! available coupons are revoked. Under `delete`, they are removed. Either way
+//! a response that earned a reward is detached rather than deleted, because
+//! its `survey_reward_history` row is audit data.
+//!
+//! Because the `users` row survives, every financial aggregate (points
+//! issued, revenue, tier counts) is unchanged by a deletion. Only access
+//! tokens live outside the database: the caller revokes them through
+//! `SessionRevocations::revoke_all` before committing.
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::config::RelatedRecordPolicy;
+use crate::error::AppError;
+
+/// What a deletion did, for the admin response and the audit log
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserDeletionSummary {
+    pub user_id: Uuid,
+    pub related_records: &'static str,
+    pub refresh_tokens_revoked: u64,
+    pub coupons_revoked: u64,
+    pub coupons_deleted: u64,
+    pub survey_responses_anonymized: u64,
+    pub survey_responses_deleted: u64,
+    pub notifications_deleted: u64,
+}
+
+/// Delete `user_id`'s account, applying the cascade described in the
+/// module docs.
+///
+/// Everything runs on `conn`; pass a transaction so a failure part-way
+/// leaves the account untouched. Returns `NotFound` for an unknown user and
+/// `Conflict` if the account was already deleted.
+pub async fn delete_user_account(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+    deleted_by: Uuid,
+    policy: RelatedRecordPolicy,
+) -> Result<UserDeletionSummary, AppError> {
+    let deleted_at: Option<Option<chrono::DateTime<chrono::Utc>>> =
+        sqlx::query_scalar("SELECT deleted_at FROM users WHERE id = $1 FOR UPDATE")
+            .bind(user_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+    match deleted_at {
+        None => return Err(AppError::NotFound("User".to_string())),
+        Some(Some(_)) => {
+            return Err(AppError::Conflict(
+                "User has already been deleted".to_string(),
+            ))
+        },
+        Some(None) => {},
+    }
+
+    let mut summary = UserDeletionSummary {
+        user_id,
+        related_records: policy.as_str(),
+        ..Default::default()
+    };
+
+    // Sessions: without refresh tokens no new access token can be issued.
+    summary.refresh_tokens_revoked = sqlx::query("DELETE FROM refresh_tokens WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+    sqlx::query("DELETE FROM password_reset_tokens WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    // Unused coupons. Redeemed/expired/revoked ones are redemption history
+    // and stay as they are.
+    match policy {
+        RelatedRecordPolicy::Anonymize => {
+            summary.coupons_revoked = sqlx::query(
+                r#"
+                UPDATE user_coupons
+                SET status = 'revoked', updated_at = NOW()
+                WHERE user_id = $1 AND status = 'available'
+                "#,
+            )
+            .bind(user_id)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected();
+        },
+        RelatedRecordPolicy::Delete => {
+            summary.coupons_deleted =
+                sqlx::query("DELETE FROM user_coupons WHERE user_id = $1 AND status = 'available'")
+                    .bind(user_id)
+                    .execute(&mut *conn)
+                    .await?
+                    .rows_affected();
+        },
+    }
+
+    // Survey data. Rewarded responses are always detached, never deleted.
+    if policy == RelatedRecordPolicy::Delete {
+        summary.survey_responses_deleted = sqlx::query(
+            r#"
+            DELETE FROM survey_responses sr
+            WHERE sr.user_id = $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM survey_reward_history h
+                  WHERE h.survey_response_id = sr.id
+              )
+            "#,
+        )
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+        sqlx::query("DELETE FROM survey_invitations WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+    summary.survey_responses_anonymized = sqlx::query(
+        "UPDATE survey_responses SET user_id = NULL, updated_at = NOW() WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+    sqlx::query(
+        "UPDATE survey_invitations SET user_id = NULL, updated_at = NOW() WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+
+    // Purely personal records.
+    summary.notifications_deleted = sqlx::query("DELETE FROM notifications WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+    sqlx::query("DELETE FROM notification_preferences WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("DELETE FROM idempotency_keys WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query(
+        r#"
+        DELETE FROM captured_emails
+        WHERE LOWER(recipient) = (SELECT LOWER(email) FROM users WHERE id = $1)
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+
+    // Financial/audit records are kept; strip the free text and network
+    // identifiers they carry.
+    sqlx::query("UPDATE bookings SET notes = NULL, updated_at = NOW() WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query(
+        "UPDATE user_audit_log SET ip_address = NULL, user_agent = NULL WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+
+    // Tombstone: the row stays so retained records keep their foreign keys.
+    // `membership_id` is a random code, not personal data, and is NOT NULL.
+    sqlx::query(
+        r#"
+        UPDATE user_profiles
+        SET first_name = NULL,
+            last_name = NULL,
+            phone = NULL,
+            date_of_birth = NULL,
+            preferences = '{}',
+            avatar_url = NULL,
+            updated_at = NOW()
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET email = NULL,
+            password_hash = NULL,
+            oauth_provider = NULL,
+            oauth_provider_id = NULL,
+            email_verified = false,
+            is_active = false,
+            deleted_at = NOW(),
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_audit_log (user_id, action, details)
+        VALUES ($1, 'account_deleted', $2)
+        "#,
+    )
+    .bind(user_id)
+    .bind(serde_json::json!({
+        "deletedBy": deleted_by,
+        "summary": &summary,
+    }))
+    .execute(&mut *conn)
+    .await?;
+
+    tracing::info!(
+        user_id = %user_id,
+        deleted_by = %deleted_by,
+        related_records = summary.related_records,
+        "User account deleted"
+    );
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_serializes_camel_case() {
+        let summary = UserDeletionSummary {
+            user_id: Uuid::nil(),
+            related_records: RelatedRecordPolicy::Anonymize.as_str(),
+            coupons_revoked: 2,
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["relatedRecords"], "anonymize");
+        assert_eq!(json["couponsRevoked"], 2);
+        assert_eq!(json["refreshTokensRevoked"], 0);
+    }
+}
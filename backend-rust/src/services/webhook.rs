@@ -0,0 +1,310 @@
+//! Outbound webhook service module
+//!
+//! Notifies the PMS of events it needs to reconcile against, through a
+//! transactional outbox:
+//! - [`enqueue_webhook`] writes an event to `webhook_events` on the
+//!   caller's transaction, so it only exists once the change it describes
+//!   has committed
+//! - [`deliver_pending_webhooks`] POSTs due events to `WEBHOOK_URL`,
+//!   retrying failures with exponential backoff
+//! - [`spawn_webhook_delivery`] runs delivery in the background
+//!
+//! Each request carries `X-Webhook-Event`, `X-Webhook-Id` (stable across
+//! retries, for de-duplication), `X-Webhook-Timestamp` and
+//! `X-Webhook-Signature: sha256=<hex>`, an HMAC-SHA256 with
+//! `WEBHOOK_SECRET` over `<timestamp>.<body>`.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde_json::Value as JsonValue;
+use sha2::Sha256;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::WebhookConfig;
+use crate::error::AppError;
+
+/// Events delivered per poll
+const DELIVERY_BATCH_SIZE: i64 = 50;
+
+/// Longest wait between two attempts at one event
+const MAX_RETRY_DELAY_SECS: u64 = 6 * 3600;
+
+/// Kinds of event sent to the PMS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEventType {
+    /// A payment slip passed SlipOK verification
+    SlipVerified,
+    /// SlipOK rejected a payment slip
+    SlipRejected,
+}
+
+impl WebhookEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::SlipVerified => "slip.verified",
+            WebhookEventType::SlipRejected => "slip.rejected",
+        }
+    }
+}
+
+impl std::fmt::Display for WebhookEventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Queue `payload` for delivery as an `event_type` event, returning its ID.
+///
+/// Pass the transaction that makes the change the event reports: the event
+/// becomes visible to the delivery worker only when that commits, and is
+/// discarded with it on rollback.
+pub async fn enqueue_webhook<'c, E>(
+    executor: E,
+    event_type: WebhookEventType,
+    payload: JsonValue,
+) -> Result<Uuid, AppError>
+where
+    E: sqlx::PgExecutor<'c>,
+{
+    let id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO webhook_events (event_type, payload)
+        VALUES ($1, $2)
+        RETURNING id
+        "#,
+    )
+    .bind(event_type.as_str())
+    .bind(payload)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(id)
+}
+
+/// An event claimed for delivery
+#[derive(Debug, FromRow)]
+struct PendingWebhook {
+    id: Uuid,
+    event_type: String,
+    payload: JsonValue,
+    attempts: i32,
+    created_at: DateTime<Utc>,
+}
+
+/// `X-Webhook-Signature` value for `body` sent at `timestamp`
+pub fn webhook_signature(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Wait before retrying an event that has failed `attempts` times: 30s,
+/// doubling each time, capped at six hours.
+pub fn retry_delay(attempts: u32) -> Duration {
+    let secs = 30u64.saturating_mul(1u64 << attempts.saturating_sub(1).min(20));
+    Duration::from_secs(secs.min(MAX_RETRY_DELAY_SECS))
+}
+
+/// Build the HTTP client used for deliveries
+pub fn webhook_client(config: &WebhookConfig) -> Client {
+    Client::builder()
+        .timeout(Duration::from_secs(config.timeout_secs))
+        .build()
+        .expect("Failed to create HTTP client")
+}
+
+/// Deliver the events that are due, returning how many were accepted.
+///
+/// Events are claimed with `SKIP LOCKED` and their `next_attempt_at`
+/// pushed past the request timeout before sending, so concurrent workers
+/// (e.g. several replicas) never send the same event at once. A 2xx
+/// response marks an event delivered; anything else schedules a retry
+/// (see [`retry_delay`]) until `max_attempts` is reached.
+pub async fn deliver_pending_webhooks(
+    pool: &PgPool,
+    client: &Client,
+    config: &WebhookConfig,
+) -> Result<usize, AppError> {
+    let (Some(url), Some(secret)) = (config.url.as_deref(), config.secret.as_deref()) else {
+        return Ok(0);
+    };
+
+    let lease_secs = i64::try_from(config.timeout_secs)
+        .unwrap_or(i64::MAX / 2)
+        .saturating_add(60);
+    let claimed = sqlx::query_as::<_, PendingWebhook>(
+        r#"
+        UPDATE webhook_events e
+        SET attempts = e.attempts + 1,
+            next_attempt_at = NOW() + make_interval(secs => $2)
+        FROM (
+            SELECT id
+            FROM webhook_events
+            WHERE delivered_at IS NULL
+              AND failed_at IS NULL
+              AND next_attempt_at <= NOW()
+            ORDER BY next_attempt_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        ) due
+        WHERE e.id = due.id
+        RETURNING e.id, e.event_type, e.payload, e.attempts, e.created_at
+        "#,
+    )
+    .bind(DELIVERY_BATCH_SIZE)
+    .bind(lease_secs as f64)
+    .fetch_all(pool)
+    .await?;
+
+    let mut delivered = 0;
+    for event in claimed {
+        match send_webhook(client, url, secret, &event).await {
+            Ok(()) => {
+                sqlx::query(
+                    "UPDATE webhook_events SET delivered_at = NOW(), last_error = NULL WHERE id = $1",
+                )
+                .bind(event.id)
+                .execute(pool)
+                .await?;
+                delivered += 1;
+            },
+            Err(error) => {
+                let attempts = u32::try_from(event.attempts).unwrap_or(u32::MAX);
+                let gave_up = attempts >= config.max_attempts;
+                warn!(
+                    event_id = %event.id,
+                    event_type = %event.event_type,
+                    attempts,
+                    gave_up,
+                    error = %error,
+                    "Webhook delivery failed"
+                );
+                sqlx::query(
+                    r#"
+                    UPDATE webhook_events
+                    SET last_error = $2,
+                        failed_at = CASE WHEN $3 THEN NOW() END,
+                        next_attempt_at = NOW() + make_interval(secs => $4)
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(event.id)
+                .bind(error)
+                .bind(gave_up)
+                .bind(retry_delay(attempts).as_secs_f64())
+                .execute(pool)
+                .await?;
+            },
+        }
+    }
+
+    Ok(delivered)
+}
+
+/// POST one event, returning why it failed if the endpoint didn't accept it
+async fn send_webhook(
+    client: &Client,
+    url: &str,
+    secret: &str,
+    event: &PendingWebhook,
+) -> Result<(), String> {
+    let body = serde_json::to_vec(&serde_json::json!({
+        "id": event.id,
+        "type": event.event_type,
+        "createdAt": event.created_at,
+        "data": event.payload,
+    }))
+    .map_err(|e| e.to_string())?;
+    let timestamp = Utc::now().timestamp();
+
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-Webhook-Event", &event.event_type)
+        .header("X-Webhook-Id", event.id.to_string())
+        .header("X-Webhook-Timestamp", timestamp.to_string())
+        .header(
+            "X-Webhook-Signature",
+            webhook_signature(secret, timestamp, &body),
+        )
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", response.status()))
+    }
+}
+
+/// Run [`deliver_pending_webhooks`] every `WEBHOOK_POLL_INTERVAL_SECS`
+/// until `shutdown` flips to `true` (or its sender is dropped).
+///
+/// A batch in progress when shutdown is signalled finishes first; events
+/// it doesn't reach stay queued for the next start. Errors are logged and
+/// the next tick retries.
+pub fn spawn_webhook_delivery(
+    pool: PgPool,
+    config: WebhookConfig,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) -> tokio::task::JoinHandle<()> {
+    let client = webhook_client(&config);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.poll_interval_secs));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = deliver_pending_webhooks(&pool, &client, &config).await {
+                        tracing::error!(error = %e, "Webhook delivery run failed");
+                    }
+                },
+                changed = shutdown.changed() => {
+                    if changed.is_err() || *shutdown.borrow() {
+                        info!("Webhook delivery worker stopped");
+                        break;
+                    }
+                },
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_event_type_names() {
+        assert_eq!(WebhookEventType::SlipVerified.as_str(), "slip.verified");
+        assert_eq!(WebhookEventType::SlipRejected.to_string(), "slip.rejected");
+    }
+
+    #[test]
+    fn test_webhook_signature() {
+        let signature = webhook_signature("secret", 1_700_000_000, b"{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(signature, webhook_signature("secret", 1_700_000_000, b"{}"));
+        assert_ne!(signature, webhook_signature("secret", 1_700_000_001, b"{}"));
+        assert_ne!(signature, webhook_signature("other", 1_700_000_000, b"{}"));
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_and_caps() {
+        assert_eq!(retry_delay(1), Duration::from_secs(30));
+        assert_eq!(retry_delay(2), Duration::from_secs(60));
+        assert_eq!(retry_delay(5), Duration::from_secs(480));
+        assert_eq!(retry_delay(30), Duration::from_secs(MAX_RETRY_DELAY_SECS));
+    }
+}
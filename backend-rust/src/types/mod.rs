@@ -110,6 +110,9 @@ pub struct ApiResponse<T> {
     /// Error message (present on failure)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Human-readable note about a successful operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
     /// Optional pagination metadata for list endpoints
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pagination: Option<Pagination>,
@@ -122,16 +125,26 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            message: None,
             pagination: None,
         }
     }
 
+    /// Creates a successful response with data and a message.
+    pub fn with_message(data: T, message: impl Into<String>) -> Self {
+        Self {
+            message: Some(message.into()),
+            ..Self::success(data)
+        }
+    }
+
     /// Creates a successful response with data and pagination.
     pub fn success_with_pagination(data: T, pagination: Pagination) -> Self {
         Self {
             success: true,
             data: Some(data),
             error: None,
+            message: None,
             pagination: Some(pagination),
         }
     }
@@ -142,6 +155,7 @@ impl<T> ApiResponse<T> {
             success: false,
             data: None,
             error: Some(message.into()),
+            message: None,
             pagination: None,
         }
     }
@@ -334,6 +348,19 @@ mod tests {
         assert_eq!(response.error, Some("Something went wrong".to_string()));
     }
 
+    #[test]
+    fn api_response_with_message() {
+        let response = ApiResponse::with_message(42, "Points awarded");
+        assert!(response.success);
+        assert_eq!(response.data, Some(42));
+        assert_eq!(response.message, Some("Points awarded".to_string()));
+
+        let json = serde_json::to_value(ApiResponse::success(1)).unwrap();
+        assert!(json.get("message").is_none());
+        assert!(json.get("error").is_none());
+        assert!(json.get("pagination").is_none());
+    }
+
     #[test]
     fn claims_expiration() {
         let claims = Claims::new(
@@ -0,0 +1,32 @@
+//! CSV export helpers
+
+/// Quote a free-text CSV field, neutralising spreadsheet formulas
+pub fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field_quotes_and_neutralises_formulas() {
+        assert_eq!(csv_field("Stay bonus"), "Stay bonus");
+        assert_eq!(
+            csv_field("Room 12, late checkout"),
+            "\"Room 12, late checkout\""
+        );
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("-5 adjustment"), "'-5 adjustment");
+    }
+}
@@ -1,7 +1,9 @@
 //! Logging and Tracing Utilities
 //!
 //! This module provides:
-//! - Tracing subscriber initialization with JSON (production) or pretty (development) formatting
+//! - Tracing subscriber initialization with JSON (production) or pretty (development)
+//!   formatting, overridable with `LOG_FORMAT`
+//! - The JSON formatting layer shared with the server binary
 //! - Request logging middleware setup for tower-http
 //! - Log sanitization utilities to prevent log injection attacks
 //!
@@ -22,14 +24,21 @@ use tower_http::{
     trace::{DefaultOnRequest, DefaultOnResponse, MakeSpan, OnFailure, TraceLayer},
     LatencyUnit,
 };
-use tracing::{Level, Span};
+use tracing::{Level, Span, Subscriber};
 use tracing_subscriber::{
-    fmt::{self, format::FmtSpan},
+    fmt::{
+        self,
+        format::{FmtSpan, Format, Json, JsonFields},
+        MakeWriter,
+    },
     layer::SubscriberExt,
+    registry::LookupSpan,
     util::SubscriberInitExt,
     EnvFilter,
 };
 
+use crate::config::LogFormat;
+
 /// Maximum length for sanitized log values to prevent log flooding
 const MAX_LOG_LENGTH: usize = 500;
 
@@ -299,6 +308,31 @@ impl Environment {
     }
 }
 
+/// The JSON-lines formatting layer, writing to `make_writer`.
+///
+/// Each line is one object with the standard `timestamp`, `level`,
+/// `target` and `fields` (the message included) keys. `span` holds the
+/// innermost span's name and fields and `spans` the whole stack, so the
+/// `http_request` span's `request_id` — the per-request correlation id —
+/// lands on every line logged while handling a request. Values are
+/// written as they were recorded, so callers still pass emails and IPs
+/// through [`sanitize_email`] and [`sanitize_ip`].
+pub fn json_fmt_layer<S, W>(make_writer: W) -> fmt::Layer<S, JsonFields, Format<Json>, W>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + 'static,
+{
+    fmt::layer()
+        .json()
+        .with_current_span(true)
+        .with_span_list(true)
+        .with_target(true)
+        .with_thread_ids(false)
+        .with_file(false)
+        .with_line_number(false)
+        .with_writer(make_writer)
+}
+
 /// Initialize the tracing subscriber with appropriate formatting.
 ///
 /// # Configuration
@@ -306,7 +340,8 @@ impl Environment {
 /// - **Production**: JSON format for structured logging, info level default
 /// - **Development**: Pretty format with colors, debug level default
 ///
-/// The log level can be overridden using the `RUST_LOG` environment variable.
+/// `LOG_FORMAT=json|pretty` overrides the format either way. The log level
+/// can be overridden using the `RUST_LOG` environment variable.
 ///
 /// # Examples
 ///
@@ -337,24 +372,25 @@ pub fn init_tracing(env: Option<Environment>) {
         ))
     });
 
-    match environment {
-        Environment::Production => {
+    let format = LogFormat::resolve(
+        LogFormat::from_env(),
+        environment == Environment::Development,
+    );
+
+    match format {
+        LogFormat::Json => {
             // JSON format for production - structured logging for log aggregators
             tracing_subscriber::registry()
                 .with(env_filter)
                 .with(
-                    fmt::layer()
-                        .json()
-                        .with_current_span(true)
-                        .with_span_list(true)
+                    json_fmt_layer(std::io::stdout)
                         .with_file(true)
                         .with_line_number(true)
-                        .with_thread_ids(true)
-                        .with_target(true),
+                        .with_thread_ids(true),
                 )
                 .init();
         },
-        Environment::Development => {
+        LogFormat::Pretty => {
             // Pretty format for development - human-readable with colors
             tracing_subscriber::registry()
                 .with(env_filter)
@@ -373,6 +409,7 @@ pub fn init_tracing(env: Option<Environment>) {
 
     tracing::info!(
         environment = ?environment,
+        format = ?format,
         "Tracing initialized"
     );
 }
@@ -485,6 +522,69 @@ pub fn create_trace_layer() -> TraceLayer<
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    /// Log output collected in memory
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl CapturedLogs {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for CapturedLogs {
+        type Writer = CapturedLogs;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_json_layer_writes_one_object_per_line() {
+        let logs = CapturedLogs::default();
+        let subscriber = tracing_subscriber::registry().with(json_fmt_layer(logs.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("http_request", request_id = "req-123");
+            let _entered = span.enter();
+            tracing::info!(
+                email = %sanitize_email("john.doe@example.com"),
+                client_ip = %sanitize_ip(Some("10.0.0.1\nforged")),
+                "Login succeeded"
+            );
+        });
+
+        let output = logs.contents();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1, "output: {}", output);
+
+        let line: serde_json::Value =
+            serde_json::from_str(lines[0]).expect("Log line should be valid JSON");
+        assert!(line["timestamp"].is_string());
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["target"], module_path!());
+        assert_eq!(line["fields"]["message"], "Login succeeded");
+        assert_eq!(line["fields"]["email"], "jo***@example.com");
+        assert_eq!(line["fields"]["client_ip"], "invalid-ip");
+        assert_eq!(line["span"]["name"], "http_request");
+        assert_eq!(line["span"]["request_id"], "req-123");
+        assert_eq!(line["spans"][0]["request_id"], "req-123");
+    }
 
     #[test]
     fn test_sanitize_log_value_removes_newlines() {
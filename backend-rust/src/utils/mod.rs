@@ -2,16 +2,24 @@
 //!
 //! Contains helper functions used across the application.
 
+pub mod csv;
 pub mod email_hash;
 pub mod logging;
+pub mod money;
+pub mod points;
+pub mod search;
 pub mod validation;
 
 // Re-export commonly used items for convenience
+pub use csv::csv_field;
 pub use email_hash::hash_email;
 pub use logging::{
     create_trace_layer, init_tracing, sanitize_email, sanitize_ip, sanitize_log_value,
     sanitize_url, sanitize_user_id, Environment, SanitizeOptions,
 };
+pub use money::format_money;
+pub use points::display_points;
+pub use search::search_pattern;
 
 pub use validation::{
     // Utility functions
@@ -0,0 +1,131 @@
+//! Display formatting for monetary amounts.
+//!
+//! API responses return amounts as raw decimals, which the frontend used to
+//! format in several different ways (`1234.5`, `฿1,234.50`, `1,234 THB`).
+//! `format_money` produces one canonical display string per currency so
+//! responses can ship a pre-formatted `*Display` field next to the numeric
+//! value. The numeric field stays authoritative — the display string is for
+//! humans only and must never be parsed back.
+//!
+//! Formatting follows the en-US/th-TH convention shared by every currency
+//! we accept: comma thousands separators, a period decimal separator, and
+//! the currency's minor-unit count (2 for THB/USD/EUR, 0 for JPY). Unknown
+//! ISO codes fall back to two decimals with the code as a suffix.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Symbol placement and minor units for a currency.
+struct CurrencyFormat {
+    symbol: Option<&'static str>,
+    decimals: u32,
+}
+
+fn currency_format(currency: &str) -> CurrencyFormat {
+    match currency.to_ascii_uppercase().as_str() {
+        "THB" => CurrencyFormat {
+            symbol: Some("฿"),
+            decimals: 2,
+        },
+        "USD" => CurrencyFormat {
+            symbol: Some("$"),
+            decimals: 2,
+        },
+        "EUR" => CurrencyFormat {
+            symbol: Some("€"),
+            decimals: 2,
+        },
+        "GBP" => CurrencyFormat {
+            symbol: Some("£"),
+            decimals: 2,
+        },
+        "JPY" => CurrencyFormat {
+            symbol: Some("¥"),
+            decimals: 0,
+        },
+        _ => CurrencyFormat {
+            symbol: None,
+            decimals: 2,
+        },
+    }
+}
+
+/// Format `amount` in `currency` (ISO 4217 code, case-insensitive) for
+/// display.
+///
+/// ```ignore
+/// assert_eq!(format_money(dec!(1234.5), "THB"), "฿1,234.50");
+/// assert_eq!(format_money(dec!(-20), "usd"), "-$20.00");
+/// assert_eq!(format_money(dec!(1500), "XYZ"), "1,500.00 XYZ");
+/// ```
+///
+/// Rounds half away from zero to the currency's minor units.
+pub fn format_money(amount: Decimal, currency: &str) -> String {
+    let format = currency_format(currency);
+
+    let rounded =
+        amount.round_dp_with_strategy(format.decimals, RoundingStrategy::MidpointAwayFromZero);
+    let negative = rounded.is_sign_negative() && !rounded.is_zero();
+
+    // `abs()` then render with a fixed number of decimals so `5` becomes
+    // `5.00` rather than `5`.
+    let plain = format!("{:.*}", format.decimals as usize, rounded.abs());
+    let (int_part, frac_part) = match plain.split_once('.') {
+        Some((i, f)) => (i, Some(f)),
+        None => (plain.as_str(), None),
+    };
+
+    let mut grouped = String::with_capacity(int_part.len() + int_part.len() / 3);
+    for (i, ch) in int_part.chars().enumerate() {
+        if i > 0 && (int_part.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(ch);
+    }
+    if let Some(frac) = frac_part {
+        grouped.push('.');
+        grouped.push_str(frac);
+    }
+
+    let sign = if negative { "-" } else { "" };
+    match format.symbol {
+        Some(symbol) => format!("{sign}{symbol}{grouped}"),
+        None => format!("{sign}{grouped} {}", currency.to_ascii_uppercase()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn format_money_thb_with_grouping() {
+        assert_eq!(format_money(dec!(1234.5), "THB"), "฿1,234.50");
+        assert_eq!(format_money(dec!(1234567.891), "THB"), "฿1,234,567.89");
+        assert_eq!(format_money(dec!(999), "THB"), "฿999.00");
+    }
+
+    #[test]
+    fn format_money_is_case_insensitive() {
+        assert_eq!(format_money(dec!(20), "usd"), "$20.00");
+    }
+
+    #[test]
+    fn format_money_handles_negative_and_zero() {
+        assert_eq!(format_money(dec!(-1500.25), "THB"), "-฿1,500.25");
+        assert_eq!(format_money(dec!(0), "THB"), "฿0.00");
+        // Rounds to zero: no stray minus sign
+        assert_eq!(format_money(dec!(-0.001), "THB"), "฿0.00");
+    }
+
+    #[test]
+    fn format_money_respects_minor_units() {
+        assert_eq!(format_money(dec!(1234.5), "JPY"), "¥1,235");
+        assert_eq!(format_money(dec!(0.005), "EUR"), "€0.01");
+    }
+
+    #[test]
+    fn format_money_unknown_currency_uses_code_suffix() {
+        assert_eq!(format_money(dec!(1500), "xyz"), "1,500.00 XYZ");
+    }
+}
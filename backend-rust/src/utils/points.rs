@@ -0,0 +1,213 @@
+//! Display formatting for points balances.
+//!
+//! Loyalty status responses carry the exact integer balance
+//! (`current_points`) for programmatic clients and, next to it, a
+//! pre-formatted string for display. Like `money::format_money`, the
+//! display string is for humans only and must never be parsed back.
+//!
+//! The separators follow the caller's locale (from `Accept-Language`,
+//! falling back to `POINTS_DISPLAY_LOCALE`):
+//!
+//! | Locale (language) | Full | Compact |
+//! |-------------------|------|---------|
+//! | en, th, ja, zh, ko, and anything unknown | `12,345` | `12.3K` |
+//! | de, es, it, nl, pt, id, da, tr, vi | `12.345` | `12,3K` |
+//! | fr | `12 345` (narrow no-break space) | `12,3K` |
+//! | ru, pl, cs, sv, nb, fi, uk | `12 345` (no-break space) | `12,3K` |
+//!
+//! The compact suffixes (`K`, `M`, `B`) are the same in every locale.
+
+use crate::config::{PointsDisplayConfig, PointsDisplayStyle};
+
+/// Digit grouping and decimal separators for a locale
+struct Separators {
+    group: &'static str,
+    decimal: char,
+}
+
+fn separators(locale: &str) -> Separators {
+    let language = locale
+        .split(['-', '_'])
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+
+    match language.as_str() {
+        "de" | "es" | "it" | "nl" | "pt" | "id" | "da" | "tr" | "vi" => Separators {
+            group: ".",
+            decimal: ',',
+        },
+        "fr" => Separators {
+            group: "\u{202F}",
+            decimal: ',',
+        },
+        "ru" | "pl" | "cs" | "sv" | "nb" | "fi" | "uk" => Separators {
+            group: "\u{00A0}",
+            decimal: ',',
+        },
+        _ => Separators {
+            group: ",",
+            decimal: '.',
+        },
+    }
+}
+
+/// Group the digits of a non-negative integer
+fn group_digits(value: u128, group: &str) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 * group.len());
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push_str(group);
+        }
+        grouped.push(ch);
+    }
+    grouped
+}
+
+/// Format `points` for display in `locale` (a BCP 47 tag such as `th-TH`).
+///
+/// ```ignore
+/// assert_eq!(format_points(12_345, PointsDisplayStyle::Full, "en-US"), "12,345");
+/// assert_eq!(format_points(12_345, PointsDisplayStyle::Compact, "de-DE"), "12,3K");
+/// ```
+///
+/// Compact values below 1,000 are shown in full; larger ones are rounded
+/// half away from zero to one decimal, dropping a trailing `.0`.
+pub fn format_points(points: i64, style: PointsDisplayStyle, locale: &str) -> String {
+    let separators = separators(locale);
+    let sign = if points < 0 { "-" } else { "" };
+    let value = u128::from(points.unsigned_abs());
+
+    if style == PointsDisplayStyle::Full || value < 1_000 {
+        return format!("{sign}{}", group_digits(value, separators.group));
+    }
+
+    // Pick the smallest unit that keeps the rounded value under 1000, so
+    // 999,950 shows as `1M` rather than `1000K`.
+    const UNITS: [(u128, &str); 3] = [(1_000, "K"), (1_000_000, "M"), (1_000_000_000, "B")];
+    let (tenths, suffix) = UNITS
+        .iter()
+        .map(|&(unit, suffix)| ((value * 10 + unit / 2) / unit, suffix))
+        .find(|&(tenths, _)| tenths < 10_000)
+        .unwrap_or_else(|| {
+            let (unit, suffix) = UNITS[UNITS.len() - 1];
+            ((value * 10 + unit / 2) / unit, suffix)
+        });
+
+    let whole = group_digits(tenths / 10, separators.group);
+    match tenths % 10 {
+        0 => format!("{sign}{whole}{suffix}"),
+        fraction => format!("{sign}{whole}{}{fraction}{suffix}", separators.decimal),
+    }
+}
+
+/// The preferred language tag from an `Accept-Language` header.
+///
+/// Takes the first listed tag (clients list their preference first);
+/// quality values and the `*` wildcard are ignored.
+pub fn locale_from_accept_language(header: Option<&str>) -> Option<&str> {
+    header?
+        .split(',')
+        .map(|entry| entry.split(';').next().unwrap_or("").trim())
+        .find(|tag| !tag.is_empty() && *tag != "*")
+}
+
+/// Format `points` with the configured style, in the request's locale if
+/// it sent one and the configured default otherwise.
+pub fn display_points(
+    points: i64,
+    config: &PointsDisplayConfig,
+    accept_language: Option<&str>,
+) -> String {
+    let locale =
+        locale_from_accept_language(accept_language).unwrap_or(config.default_locale.as_str());
+    format_points(points, config.style, locale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_points_full_groups_by_locale() {
+        assert_eq!(
+            format_points(1_234_567, PointsDisplayStyle::Full, "en-US"),
+            "1,234,567"
+        );
+        assert_eq!(
+            format_points(1_234_567, PointsDisplayStyle::Full, "th-TH"),
+            "1,234,567"
+        );
+        assert_eq!(
+            format_points(1_234_567, PointsDisplayStyle::Full, "de-DE"),
+            "1.234.567"
+        );
+        assert_eq!(
+            format_points(1_234_567, PointsDisplayStyle::Full, "fr-FR"),
+            "1\u{202F}234\u{202F}567"
+        );
+        assert_eq!(format_points(999, PointsDisplayStyle::Full, "en-US"), "999");
+        assert_eq!(
+            format_points(-1_500, PointsDisplayStyle::Full, "en-US"),
+            "-1,500"
+        );
+    }
+
+    #[test]
+    fn format_points_compact() {
+        assert_eq!(
+            format_points(999, PointsDisplayStyle::Compact, "en-US"),
+            "999"
+        );
+        assert_eq!(
+            format_points(1_000, PointsDisplayStyle::Compact, "en-US"),
+            "1K"
+        );
+        assert_eq!(
+            format_points(12_345, PointsDisplayStyle::Compact, "en-US"),
+            "12.3K"
+        );
+        assert_eq!(
+            format_points(12_350, PointsDisplayStyle::Compact, "en-US"),
+            "12.4K"
+        );
+        assert_eq!(
+            format_points(12_345, PointsDisplayStyle::Compact, "de-DE"),
+            "12,3K"
+        );
+        assert_eq!(
+            format_points(999_950, PointsDisplayStyle::Compact, "en-US"),
+            "1M"
+        );
+        assert_eq!(
+            format_points(2_500_000, PointsDisplayStyle::Compact, "en-US"),
+            "2.5M"
+        );
+        assert_eq!(
+            format_points(-12_345, PointsDisplayStyle::Compact, "en-US"),
+            "-12.3K"
+        );
+    }
+
+    #[test]
+    fn locale_from_accept_language_takes_first_tag() {
+        assert_eq!(
+            locale_from_accept_language(Some("th-TH,th;q=0.9,en;q=0.8")),
+            Some("th-TH")
+        );
+        assert_eq!(locale_from_accept_language(Some("*")), None);
+        assert_eq!(locale_from_accept_language(Some("")), None);
+        assert_eq!(locale_from_accept_language(None), None);
+    }
+
+    #[test]
+    fn display_points_falls_back_to_configured_locale() {
+        let config = PointsDisplayConfig {
+            style: PointsDisplayStyle::Full,
+            default_locale: "de-DE".to_string(),
+        };
+        assert_eq!(display_points(12_345, &config, None), "12.345");
+        assert_eq!(display_points(12_345, &config, Some("en-GB")), "12,345");
+    }
+}
@@ -0,0 +1,56 @@
+//! Partial-text search terms
+//!
+//! Admin searches match `column ILIKE '%term%'`, served by the pg_trgm GIN
+//! indexes from the `trigram_search_indexes` migration. A trigram index
+//! can only narrow a search down with at least three characters, so
+//! shorter terms are rejected up front instead of scanning every row.
+
+use crate::error::AppError;
+
+/// Shortest search term accepted by default (`SEARCH_MIN_LENGTH`)
+pub const DEFAULT_MIN_SEARCH_LENGTH: usize = 3;
+
+/// The `%term%` ILIKE pattern for an optional search parameter.
+///
+/// A missing or blank term means no filter (`Ok(None)`). A term shorter
+/// than `min_length` characters (ignoring surrounding whitespace) is a
+/// validation error. The pattern itself keeps the term as given, so
+/// results match the plain ILIKE search.
+pub fn search_pattern(search: Option<&str>, min_length: usize) -> Result<Option<String>, AppError> {
+    let Some(search) = search.filter(|s| !s.trim().is_empty()) else {
+        return Ok(None);
+    };
+
+    if search.trim().chars().count() < min_length {
+        return Err(AppError::Validation(format!(
+            "Search term must be at least {} characters",
+            min_length
+        )));
+    }
+
+    Ok(Some(format!("%{}%", search)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_pattern() {
+        assert_eq!(search_pattern(None, 3).unwrap(), None);
+        assert_eq!(search_pattern(Some("  "), 3).unwrap(), None);
+        assert_eq!(
+            search_pattern(Some("john"), 3).unwrap(),
+            Some("%john%".to_string())
+        );
+        // Counted in characters, not bytes
+        assert_eq!(
+            search_pattern(Some("สมชาย"), 3).unwrap(),
+            Some("%สมชาย%".to_string())
+        );
+
+        assert!(search_pattern(Some("jo"), 3).is_err());
+        assert!(search_pattern(Some(" j "), 3).is_err());
+        assert!(search_pattern(Some("jo"), 2).is_ok());
+    }
+}
@@ -328,6 +328,49 @@ async fn ensure_template_db() -> Result<(), Box<dyn std::error::Error + Send + S
         include_str!("../../migrations/20260513020000_bookings_no_overlap.sql");
     template_pool.execute(bookings_no_overlap_migration).await?;
 
+    let captured_emails_migration =
+        include_str!("../../migrations/20260515010000_captured_emails.sql");
+    template_pool.execute(captured_emails_migration).await?;
+
+    let tier_downgrade_grace_migration =
+        include_str!("../../migrations/20260516160000_tier_downgrade_grace.sql");
+    template_pool
+        .execute(tier_downgrade_grace_migration)
+        .await?;
+
+    let points_statements_migration =
+        include_str!("../../migrations/20260516180000_points_statements.sql");
+    template_pool.execute(points_statements_migration).await?;
+    let pending_points_migration =
+        include_str!("../../migrations/20260516190000_pending_points.sql");
+    template_pool.execute(pending_points_migration).await?;
+    let leaderboard_opt_in_migration =
+        include_str!("../../migrations/20260516200000_leaderboard_opt_in.sql");
+    template_pool.execute(leaderboard_opt_in_migration).await?;
+    let quiet_hours_migration =
+        include_str!("../../migrations/20260516210000_notification_quiet_hours.sql");
+    template_pool.execute(quiet_hours_migration).await?;
+    let password_changed_at_migration =
+        include_str!("../../migrations/20260516220000_password_changed_at.sql");
+    template_pool.execute(password_changed_at_migration).await?;
+    let booking_notes_migration = include_str!("../../migrations/20260516230000_booking_notes.sql");
+    template_pool.execute(booking_notes_migration).await?;
+    let refresh_token_session_id_migration =
+        include_str!("../../migrations/20260516240000_refresh_token_session_id.sql");
+    template_pool
+        .execute(refresh_token_session_id_migration)
+        .await?;
+    let email_verification_tokens_migration =
+        include_str!("../../migrations/20260516250000_email_verification_tokens.sql");
+    template_pool
+        .execute(email_verification_tokens_migration)
+        .await?;
+    let survey_invitation_tokens_migration =
+        include_str!("../../migrations/20260516260000_survey_invitation_tokens.sql");
+    template_pool
+        .execute(survey_invitation_tokens_migration)
+        .await?;
+
     // Seed tiers
     template_pool
         .execute(
@@ -645,12 +688,16 @@ fn create_test_config() -> loyalty_backend::Settings {
             host: "127.0.0.1".to_string(),
             frontend_url: "http://localhost:3201".to_string(),
             log_level: "debug".to_string(),
+            log_format: None,
+            shutdown_timeout_secs: 5,
         },
         database: DatabaseConfig {
             url: test_database_url(),
             max_connections: 3,
             min_connections: 0,
             connection_timeout_secs: 10,
+            run_migrations_on_startup: true,
+            slow_query_threshold_ms: 500,
         },
         redis: RedisConfig {
             url: test_redis_url(),
@@ -663,6 +710,7 @@ fn create_test_config() -> loyalty_backend::Settings {
             session_secret: "test-session-secret-key-for-testing-only-minimum-32-chars".to_string(),
             access_token_expiry_secs: 3600,
             refresh_token_expiry_secs: 86400,
+            ..AuthConfig::default()
         },
         oauth: OAuthConfig::default(),
         email: EmailConfig::default(),
@@ -1258,7 +1306,11 @@ impl TestClient {
 #[derive(Debug)]
 pub struct TestResponse {
     pub status: u16,
+    /// The body as text; binary bodies (images) are decoded lossily, so
+    /// read those from `bytes`
     pub body: String,
+    /// The raw body
+    pub bytes: Vec<u8>,
     /// All response headers, captured so tests can assert on `Set-Cookie`
     /// and other response metadata.
     pub headers: HeaderMap,
@@ -1272,11 +1324,13 @@ impl TestResponse {
         let body = axum::body::to_bytes(response.into_body(), usize::MAX)
             .await
             .unwrap();
-        let body = String::from_utf8(body.to_vec()).unwrap();
+        let bytes = body.to_vec();
+        let body = String::from_utf8_lossy(&bytes).into_owned();
 
         Self {
             status,
             body,
+            bytes,
             headers,
         }
     }
@@ -1365,8 +1419,11 @@ pub fn generate_test_token_with_role(user_id: &Uuid, email: &str, role: &str) ->
         role: String,
         exp: i64,
         iat: Option<i64>,
+        last_auth_at: Option<i64>,
     }
 
+    // Minted as if the user had just logged in, so sensitive admin
+    // actions don't ask for a re-auth
     let now = Utc::now();
     let claims = Claims {
         id: user_id.to_string(),
@@ -1374,6 +1431,7 @@ pub fn generate_test_token_with_role(user_id: &Uuid, email: &str, role: &str) ->
         role: role.to_string(),
         exp: (now + Duration::hours(1)).timestamp(),
         iat: Some(now.timestamp()),
+        last_auth_at: Some(now.timestamp()),
     };
 
     encode(
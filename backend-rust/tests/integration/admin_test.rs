@@ -553,6 +553,69 @@ async fn test_broadcast_notification() {
     app.cleanup().await.ok();
 }
 
+/// Broadcast types are stored with their column value (`tier_change`, not
+/// the Rust variant name) and at the type's minimum priority
+/// POST /api/admin/notifications/broadcast
+#[tokio::test]
+async fn test_broadcast_notification_stores_type_and_priority() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let admin = create_admin_user(app.db()).await;
+    let user = TestUser::new(&unique_email("broadcast_typed"));
+    user.insert(app.db())
+        .await
+        .expect("Failed to insert test user");
+
+    let client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+
+    for (notification_type, priority) in [("tier_change", "normal"), ("system", "high")] {
+        let response = client
+            .post(
+                "/api/admin/notifications/broadcast",
+                &json!({
+                    "title": format!("Broadcast {}", notification_type),
+                    "message": "Typed broadcast",
+                    "notification_type": notification_type,
+                    "active_only": false
+                }),
+            )
+            .await;
+        response.assert_status(200);
+
+        let stored: (String, String) = sqlx::query_as(
+            "SELECT type, priority FROM notifications WHERE user_id = $1 AND title = $2",
+        )
+        .bind(user.id)
+        .bind(format!("Broadcast {}", notification_type))
+        .fetch_one(app.db())
+        .await
+        .expect("Broadcast notification should be stored");
+        assert_eq!(
+            stored,
+            (notification_type.to_string(), priority.to_string())
+        );
+    }
+
+    // Types outside the stored set are rejected up front
+    let response = client
+        .post(
+            "/api/admin/notifications/broadcast",
+            &json!({
+                "title": "Unknown type",
+                "message": "Typed broadcast",
+                "notification_type": "tierchange"
+            }),
+        )
+        .await;
+    assert!(
+        (400..500).contains(&response.status),
+        "Unknown notification type should be rejected, got {}",
+        response.status
+    );
+
+    app.cleanup().await.ok();
+}
+
 /// Test broadcast with title validation
 /// POST /api/admin/notifications/broadcast with empty title
 #[tokio::test]
@@ -2084,6 +2147,58 @@ async fn test_admin_verify_slip_forbidden_for_customer() {
     app.cleanup().await.ok();
 }
 
+/// Only admins can force a SlipOK re-verification.
+#[tokio::test]
+async fn test_admin_reverify_slip_forbidden_for_customer() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let customer = create_regular_user(app.db()).await;
+    let (_booking_id, slip_id) = seed_slip_for_booking(app.db(), customer.id).await;
+
+    let client = app.authenticated_client(&customer.id, &customer.email);
+    let response = client
+        .post(
+            &format!("/api/admin/bookings/slips/{}/reverify", slip_id),
+            &json!({}),
+        )
+        .await;
+    response.assert_status(403);
+
+    app.cleanup().await.ok();
+}
+
+/// Without SlipOK credentials there is nothing to re-run: 503, and the
+/// slip is left untouched.
+#[tokio::test]
+async fn test_admin_reverify_slip_unavailable_without_slipok() {
+    if std::env::var("SLIPOK_API_KEY").is_ok_and(|key| !key.is_empty()) {
+        return;
+    }
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let admin = create_admin_user(app.db()).await;
+    let customer = create_regular_user(app.db()).await;
+    let (_booking_id, slip_id) = seed_slip_for_booking(app.db(), customer.id).await;
+
+    let client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+    let response = client
+        .post(
+            &format!("/api/admin/bookings/slips/{}/reverify", slip_id),
+            &json!({}),
+        )
+        .await;
+    response.assert_status(503);
+
+    let slipok_status: Option<String> =
+        sqlx::query_scalar("SELECT slipok_status FROM booking_slips WHERE id = $1")
+            .bind(slip_id)
+            .fetch_one(app.db())
+            .await
+            .unwrap();
+    assert_eq!(slipok_status.as_deref(), Some("pending"));
+
+    app.cleanup().await.ok();
+}
+
 /// `POST /api/admin/bookings/:id/cancel` rejects double-cancels.
 #[tokio::test]
 async fn test_admin_cancel_rejects_already_cancelled() {
@@ -5,6 +5,7 @@
 //! - User login
 //! - Token refresh (Phase 3: cookie-only)
 //! - Logout (Phase 3: cookie-only)
+//! - Active session listing and revocation
 //!
 //! # Phase 3 cookie-only contract
 //!
@@ -14,9 +15,11 @@
 //! `/auth/logout` no longer exist — see `test_refresh_with_body_only_is_rejected`
 //! and the cookie-only variants for the new contract.
 
+use loyalty_backend::redis::{RedisManager, UserSession};
 use serde_json::{json, Value};
+use uuid::Uuid;
 
-use crate::common::{TestApp, TestClient, TestResponse};
+use crate::common::{TestApp, TestClient, TestResponse, TestUser};
 
 // ============================================================================
 // Test Helpers
@@ -435,6 +438,94 @@ async fn test_refresh_token() {
     app.cleanup().await.ok();
 }
 
+#[tokio::test]
+async fn test_refresh_rotation_chain() {
+    // Each refresh retires the presented token: the rotated one keeps
+    // working, and the chain can be followed indefinitely.
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let (_email, _register_response, first) = register_user(&app.client()).await;
+
+    let response = app
+        .client()
+        .with_cookie(&format!("refresh_token={first}"))
+        .post("/api/auth/refresh", &json!({}))
+        .await;
+    response.assert_status(200);
+    let second = parse_cookie_value(
+        &response
+            .set_cookie_for("refresh_token")
+            .expect("Refresh must rotate the refresh_token cookie"),
+    )
+    .to_string();
+
+    let response = app
+        .client()
+        .with_cookie(&format!("refresh_token={second}"))
+        .post("/api/auth/refresh", &json!({}))
+        .await;
+    response.assert_status(200);
+    let third = parse_cookie_value(
+        &response
+            .set_cookie_for("refresh_token")
+            .expect("Refresh must rotate the refresh_token cookie"),
+    )
+    .to_string();
+
+    assert_ne!(first, second);
+    assert_ne!(second, third);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_refresh_token_reuse_revokes_family() {
+    // Replaying a rotated-out token is treated as theft: the replay fails
+    // and the legitimate holder's current token is revoked with it. A
+    // separate login (another family) is unaffected.
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = app.client();
+    let (email, _register_response, stolen) = register_user(&client).await;
+    let (_login_response, other_device) = login_user(&client, &email, "SecurePass123!").await;
+
+    let response = app
+        .client()
+        .with_cookie(&format!("refresh_token={stolen}"))
+        .post("/api/auth/refresh", &json!({}))
+        .await;
+    response.assert_status(200);
+    let current = parse_cookie_value(
+        &response
+            .set_cookie_for("refresh_token")
+            .expect("Refresh must rotate the refresh_token cookie"),
+    )
+    .to_string();
+
+    // The replay of the rotated-out token
+    let replay = app
+        .client()
+        .with_cookie(&format!("refresh_token={stolen}"))
+        .post("/api/auth/refresh", &json!({}))
+        .await;
+    replay.assert_status(401);
+
+    // The family's current token went down with it
+    let revoked = app
+        .client()
+        .with_cookie(&format!("refresh_token={current}"))
+        .post("/api/auth/refresh", &json!({}))
+        .await;
+    revoked.assert_status(401);
+
+    let other = app
+        .client()
+        .with_cookie(&format!("refresh_token={other_device}"))
+        .post("/api/auth/refresh", &json!({}))
+        .await;
+    other.assert_status(200);
+
+    app.cleanup().await.ok();
+}
+
 // ============================================================================
 // Logout Tests
 // ============================================================================
@@ -528,6 +619,225 @@ async fn test_logout() {
     app.cleanup().await.ok();
 }
 
+#[tokio::test]
+async fn test_logout_all_ends_every_session() {
+    // Two Redis sessions and two refresh tokens (register + a second
+    // login) are all gone after one logout-all.
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = app.client();
+    let (email, register_response, first_device) = register_user(&client).await;
+    let (_login_response, second_device) = login_user(&client, &email, "SecurePass123!").await;
+
+    let body: Value = register_response.json().expect("Register returns JSON");
+    let access_token = body["tokens"]["accessToken"]
+        .as_str()
+        .expect("Register returns an access token")
+        .to_string();
+    let user_id: Uuid = body["user"]["id"]
+        .as_str()
+        .and_then(|id| id.parse().ok())
+        .expect("Register returns the user id");
+
+    let mut redis = RedisManager::from_connection(app.redis());
+    for session_id in ["logout-all-a", "logout-all-b"] {
+        let session = UserSession::new(user_id, email.clone(), "customer".to_string(), None, None);
+        redis
+            .set_session(session_id, user_id, &session, None)
+            .await
+            .expect("Failed to store session");
+    }
+
+    let response = app
+        .client()
+        .with_auth(&access_token)
+        .with_cookie(&format!("refresh_token={first_device}"))
+        .post("/api/auth/logout-all", &json!({}))
+        .await;
+    response.assert_status(200);
+    assert!(
+        response
+            .set_cookie_for("refresh_token")
+            .is_some_and(|c| c.contains("Max-Age=0")),
+        "logout-all MUST clear the refresh_token cookie"
+    );
+
+    for session_id in ["logout-all-a", "logout-all-b"] {
+        assert!(
+            !redis.session_exists(session_id).await.unwrap(),
+            "session {session_id} should be deleted"
+        );
+    }
+
+    for cookie in [&first_device, &second_device] {
+        let refresh = app
+            .client()
+            .with_cookie(&format!("refresh_token={cookie}"))
+            .post("/api/auth/refresh", &json!({}))
+            .await;
+        refresh.assert_status(401);
+    }
+
+    // The access token the call was made with is revoked too
+    let me = app
+        .client()
+        .with_auth(&access_token)
+        .get("/api/auth/me")
+        .await;
+    me.assert_status(401);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_logout_all_requires_auth() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let response = app.client().post("/api/auth/logout-all", &json!({})).await;
+    response.assert_status(401);
+
+    app.cleanup().await.ok();
+}
+
+/// Access token from a register or login response
+fn access_token_of(response: &TestResponse) -> String {
+    let body: Value = response.json().expect("Auth response is JSON");
+    body["tokens"]["accessToken"]
+        .as_str()
+        .expect("Auth response carries an access token")
+        .to_string()
+}
+
+/// `GET /api/auth/sessions` as the holder of `access_token`
+async fn list_sessions(app: &TestApp, access_token: &str) -> Vec<Value> {
+    let response = app
+        .client()
+        .with_auth(access_token)
+        .get("/api/auth/sessions")
+        .await;
+    response.assert_status(200);
+    let body: Value = response.json().expect("Sessions response is JSON");
+    body["sessions"]
+        .as_array()
+        .expect("Sessions response has a sessions array")
+        .clone()
+}
+
+#[tokio::test]
+async fn test_list_sessions_flags_current() {
+    // Registering and then logging in leaves two sessions; each token
+    // sees the same two, with only its own flagged current.
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = app.client();
+    let (email, register_response, _) = register_user(&client).await;
+    let (login_response, _) = login_user(&client, &email, "SecurePass123!").await;
+
+    let mut current_ids = Vec::new();
+    for token in [
+        access_token_of(&register_response),
+        access_token_of(&login_response),
+    ] {
+        let sessions = list_sessions(&app, &token).await;
+        assert_eq!(sessions.len(), 2, "both sessions are listed: {sessions:?}");
+        assert!(sessions
+            .iter()
+            .all(|s| s["createdAt"].is_string() && s["lastActivity"].is_string()));
+
+        let current: Vec<&Value> = sessions.iter().filter(|s| s["current"] == true).collect();
+        assert_eq!(current.len(), 1, "exactly one session is current");
+        current_ids.push(current[0]["id"].as_str().unwrap().to_string());
+    }
+    assert_ne!(
+        current_ids[0], current_ids[1],
+        "each token is flagged on its own session"
+    );
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_revoke_session_ends_only_that_session() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = app.client();
+    let (email, register_response, first_device) = register_user(&client).await;
+    let (login_response, second_device) = login_user(&client, &email, "SecurePass123!").await;
+    let first_token = access_token_of(&register_response);
+    let second_token = access_token_of(&login_response);
+
+    let sessions = list_sessions(&app, &second_token).await;
+    let other_id = sessions
+        .iter()
+        .find(|s| s["current"] == false)
+        .and_then(|s| s["id"].as_str())
+        .expect("the registration session is listed")
+        .to_string();
+
+    let response = app
+        .client()
+        .with_auth(&second_token)
+        .delete(&format!("/api/auth/sessions/{other_id}"))
+        .await;
+    response.assert_status(200);
+
+    let sessions = list_sessions(&app, &second_token).await;
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0]["current"], true);
+
+    // The revoked session's refresh token and access token stop working
+    let refresh = app
+        .client()
+        .with_cookie(&format!("refresh_token={first_device}"))
+        .post("/api/auth/refresh", &json!({}))
+        .await;
+    refresh.assert_status(401);
+    let me = app
+        .client()
+        .with_auth(&first_token)
+        .get("/api/auth/me")
+        .await;
+    me.assert_status(401);
+
+    // The other session is untouched
+    let me = app
+        .client()
+        .with_auth(&second_token)
+        .get("/api/auth/me")
+        .await;
+    me.assert_status(200);
+    let refresh = app
+        .client()
+        .with_cookie(&format!("refresh_token={second_device}"))
+        .post("/api/auth/refresh", &json!({}))
+        .await;
+    refresh.assert_status(200);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_revoke_session_of_another_user_is_not_found() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = app.client();
+    let (_, victim_response, _) = register_user(&client).await;
+    let (_, attacker_response, _) = register_user(&client).await;
+    let victim_token = access_token_of(&victim_response);
+
+    let victim_session = list_sessions(&app, &victim_token).await[0]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let response = app
+        .client()
+        .with_auth(&access_token_of(&attacker_response))
+        .delete(&format!("/api/auth/sessions/{victim_session}"))
+        .await;
+    response.assert_status(404);
+
+    assert_eq!(list_sessions(&app, &victim_token).await.len(), 1);
+
+    app.cleanup().await.ok();
+}
+
 // ============================================================================
 // Additional Edge Case Tests
 // ============================================================================
@@ -580,6 +890,368 @@ async fn test_register_short_password() {
     app.cleanup().await.ok();
 }
 
+#[tokio::test]
+async fn test_register_weak_password_lists_each_requirement() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let client = app.client();
+
+    let response = client
+        .post(
+            "/api/auth/register",
+            &json!({
+                "email": unique_email(),
+                "password": "short",
+                "firstName": "Test",
+                "lastName": "User"
+            }),
+        )
+        .await;
+    response.assert_status(400);
+
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["error"], "validation_error");
+    let details = json["details"]
+        .as_object()
+        .expect("Weak password should come with details");
+    for requirement in ["min_length", "uppercase", "digit", "special_char"] {
+        assert!(
+            details.contains_key(requirement),
+            "Expected '{}' in details, got {:?}",
+            requirement,
+            details.keys().collect::<Vec<_>>()
+        );
+    }
+    assert!(!details.contains_key("lowercase"), "'short' has lowercase");
+    assert!(
+        !details.contains_key("password"),
+        "The requirements replace the generic password entry"
+    );
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_register_password_missing_one_requirement() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let client = app.client();
+
+    let cases = [
+        ("lowercase1!", "uppercase"),
+        ("UPPERCASE1!", "lowercase"),
+        ("NoDigitsHere!", "digit"),
+        ("NoSpecial123", "special_char"),
+    ];
+    for (password, requirement) in cases {
+        let response = client
+            .post(
+                "/api/auth/register",
+                &json!({
+                    "email": unique_email(),
+                    "password": password,
+                    "firstName": "Test",
+                    "lastName": "User"
+                }),
+            )
+            .await;
+        response.assert_status(400);
+
+        let json: Value = response.json().expect("Response should be valid JSON");
+        let keys: Vec<&String> = json["details"]
+            .as_object()
+            .expect("Weak password should come with details")
+            .keys()
+            .collect();
+        assert_eq!(keys, vec![requirement], "password {:?}", password);
+    }
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_reset_password_weak_password_lists_requirements() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let client = app.client();
+
+    // The password is checked before the token is looked up
+    let response = client
+        .post(
+            "/api/auth/reset-password",
+            &json!({ "token": "any-token", "password": "alllowercase" }),
+        )
+        .await;
+    response.assert_status(400);
+
+    let json: Value = response.json().expect("Response should be valid JSON");
+    let details = json["details"]
+        .as_object()
+        .expect("Weak password should come with details");
+    assert_eq!(details.len(), 3);
+    assert!(details.contains_key("uppercase"));
+    assert!(details.contains_key("digit"));
+    assert!(details.contains_key("special_char"));
+
+    app.cleanup().await.ok();
+}
+
+/// A client on a router that captures emails in `captured_emails`, so a
+/// test can follow the link in one
+fn capturing_client(app: &TestApp) -> TestClient {
+    let mut config = crate::common::test_app_state_config();
+    config.email.capture_mode = true;
+    let state = loyalty_backend::AppState::new(app.db().clone(), app.redis(), config);
+    TestClient::new(loyalty_backend::routes::create_router(state))
+}
+
+/// The token in the latest magic-link email captured for `email`
+async fn captured_magic_link_token(app: &TestApp, email: &str) -> Option<String> {
+    let html: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT html_body FROM captured_emails
+        WHERE recipient = $1 AND subject = 'Your Sign-in Link'
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(email)
+    .fetch_optional(app.db())
+    .await
+    .expect("Failed to read captured emails")
+    .flatten();
+
+    let html = html?;
+    let start = html.find("magic-link?token=")? + "magic-link?token=".len();
+    let token: String = html[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect();
+    Some(token)
+}
+
+#[tokio::test]
+async fn test_magic_link_round_trip() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = capturing_client(&app);
+
+    let user = TestUser::new(&unique_email());
+    user.insert(app.db()).await.expect("Failed to insert user");
+
+    let response = client
+        .post("/api/auth/magic-link", &json!({ "email": user.email }))
+        .await;
+    response.assert_status(200);
+
+    let token = captured_magic_link_token(&app, &user.email)
+        .await
+        .expect("A sign-in link should have been emailed");
+
+    let verify_uri = format!("/api/auth/magic-link/verify?token={}", token);
+    let response = client.get(&verify_uri).await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert!(json["tokens"]["accessToken"].is_string());
+    assert_eq!(json["user"]["email"], user.email);
+    assert!(
+        response
+            .headers
+            .get_all("set-cookie")
+            .iter()
+            .any(|v| v.to_str().unwrap_or("").starts_with("refresh_token=")),
+        "A magic-link login should start a normal session"
+    );
+
+    // Single use
+    let response = client.get(&verify_uri).await;
+    response.assert_status(400);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_magic_link_unknown_email_gets_same_response() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = capturing_client(&app);
+
+    let user = TestUser::new(&unique_email());
+    user.insert(app.db()).await.expect("Failed to insert user");
+
+    let known = client
+        .post("/api/auth/magic-link", &json!({ "email": user.email }))
+        .await;
+    let unknown_email = unique_email();
+    let unknown = client
+        .post("/api/auth/magic-link", &json!({ "email": unknown_email }))
+        .await;
+
+    known.assert_status(200);
+    unknown.assert_status(200);
+    assert_eq!(known.body, unknown.body);
+    assert!(captured_magic_link_token(&app, &unknown_email)
+        .await
+        .is_none());
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_magic_link_fake_or_expired_token_is_bad_request() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = app.client();
+
+    let response = client
+        .get("/api/auth/magic-link/verify?token=not-a-real-token")
+        .await;
+    response.assert_status(400);
+
+    let user = TestUser::new(&unique_email());
+    user.insert(app.db()).await.expect("Failed to insert user");
+    let token = loyalty_backend::services::auth::issue_magic_link(app.redis(), user.id, 1)
+        .await
+        .expect("Failed to issue magic link");
+    tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+
+    let response = client
+        .get(&format!("/api/auth/magic-link/verify?token={}", token))
+        .await;
+    response.assert_status(400);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["message"], "Invalid or expired sign-in link");
+
+    app.cleanup().await.ok();
+}
+
+/// Verification emails captured for `email`
+async fn captured_verification_count(app: &TestApp, email: &str) -> i64 {
+    sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM captured_emails
+        WHERE recipient = $1 AND subject = 'Welcome! Please verify your email address'
+        "#,
+    )
+    .bind(email)
+    .fetch_one(app.db())
+    .await
+    .expect("Failed to read captured emails")
+}
+
+/// Unused verification codes issued to `user_id`
+async fn open_verification_tokens(app: &TestApp, user_id: uuid::Uuid) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM email_verification_tokens WHERE user_id = $1 AND NOT used",
+    )
+    .bind(user_id)
+    .fetch_one(app.db())
+    .await
+    .expect("Failed to read verification tokens")
+}
+
+#[tokio::test]
+async fn test_resend_verification_sends_fresh_code() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = capturing_client(&app);
+
+    let user = TestUser::unverified(&unique_email());
+    user.insert(app.db()).await.expect("Failed to insert user");
+
+    let response = client
+        .post(
+            "/api/auth/resend-verification",
+            &json!({ "email": user.email }),
+        )
+        .await;
+    response.assert_status(200);
+
+    assert_eq!(captured_verification_count(&app, &user.email).await, 1);
+    assert_eq!(open_verification_tokens(&app, user.id).await, 1);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_resend_verification_cooldown_returns_retry_after() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = capturing_client(&app);
+
+    let user = TestUser::unverified(&unique_email());
+    user.insert(app.db()).await.expect("Failed to insert user");
+    let payload = json!({ "email": user.email });
+
+    client
+        .post("/api/auth/resend-verification", &payload)
+        .await
+        .assert_status(200);
+
+    let response = client.post("/api/auth/resend-verification", &payload).await;
+    response.assert_status(429);
+    let retry_after: u64 = response
+        .headers
+        .get("retry-after")
+        .expect("A 429 should carry Retry-After")
+        .to_str()
+        .unwrap()
+        .parse()
+        .expect("Retry-After should be whole seconds");
+    assert!(
+        (1..=60).contains(&retry_after),
+        "retry-after = {}",
+        retry_after
+    );
+
+    // The second request sent nothing
+    assert_eq!(captured_verification_count(&app, &user.email).await, 1);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_resend_verification_skips_verified_and_unknown_emails() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = capturing_client(&app);
+
+    let verified = TestUser::new(&unique_email());
+    verified
+        .insert(app.db())
+        .await
+        .expect("Failed to insert user");
+    let unverified = TestUser::unverified(&unique_email());
+    unverified
+        .insert(app.db())
+        .await
+        .expect("Failed to insert user");
+
+    let pending = client
+        .post(
+            "/api/auth/resend-verification",
+            &json!({ "email": unverified.email }),
+        )
+        .await;
+    let already = client
+        .post(
+            "/api/auth/resend-verification",
+            &json!({ "email": verified.email }),
+        )
+        .await;
+    let unknown = client
+        .post(
+            "/api/auth/resend-verification",
+            &json!({ "email": unique_email() }),
+        )
+        .await;
+
+    pending.assert_status(200);
+    already.assert_status(200);
+    unknown.assert_status(200);
+    assert_eq!(pending.body, already.body);
+    assert_eq!(pending.body, unknown.body);
+
+    assert_eq!(captured_verification_count(&app, &verified.email).await, 0);
+    assert_eq!(open_verification_tokens(&app, verified.id).await, 0);
+
+    app.cleanup().await.ok();
+}
+
 #[tokio::test]
 async fn test_login_nonexistent_email() {
     let app = TestApp::new().await.expect("Failed to create test app");
@@ -1088,3 +1760,441 @@ async fn test_login_rate_limit_returns_429() {
 
     app.cleanup().await.ok();
 }
+
+// A rejected request tells the client how long to back off: `Retry-After`
+// holds the seconds left in the window and the body uses the standard
+// error shape.
+#[tokio::test]
+async fn test_rate_limit_rejection_has_retry_after_and_error_body() {
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use axum::routing::get;
+    use axum::{middleware as axum_middleware, Router};
+    use loyalty_backend::middleware::rate_limit::{
+        rate_limit_middleware, RateLimitConfig, RateLimiter, RATE_LIMITED_ERROR,
+    };
+    use tower::ServiceExt;
+
+    let router: Router<()> = Router::new()
+        .route("/ping", get(|| async { "pong" }))
+        .layer(axum_middleware::from_fn_with_state(
+            RateLimiter::new(RateLimitConfig::new(2, 30)),
+            rate_limit_middleware,
+        ));
+    let ping = || Request::builder().uri("/ping").body(Body::empty()).unwrap();
+
+    for _ in 0..2 {
+        let resp = router.clone().oneshot(ping()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(resp.headers().get(header::RETRY_AFTER).is_none());
+    }
+
+    let resp = router.clone().oneshot(ping()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = resp.headers()[header::RETRY_AFTER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .expect("Retry-After should be a number of seconds");
+    assert!(
+        (1..=30).contains(&retry_after),
+        "Retry-After should fall within the 30s window, got {retry_after}"
+    );
+
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"], RATE_LIMITED_ERROR);
+    assert_eq!(body["error"], "rate_limited");
+    assert!(body["message"]
+        .as_str()
+        .unwrap()
+        .contains(&retry_after.to_string()));
+}
+
+// Per-user keying (RATE_LIMIT_KEY=user): users sharing one address (NAT,
+// corporate proxy) each get their own Redis-backed quota, while the IP
+// strategy still lumps them together. Requests built with `oneshot` have no
+// `ConnectInfo`, so every request here comes from the same fallback IP.
+
+/// Send `tokens` (None = anonymous) through `limiter` in order and collect
+/// the status codes
+async fn statuses_through_limiter(
+    limiter: loyalty_backend::middleware::rate_limit::RedisRateLimiter,
+    tokens: &[Option<&str>],
+) -> Vec<u16> {
+    use axum::body::Body;
+    use axum::http::Request;
+    use axum::routing::get;
+    use axum::{middleware as axum_middleware, Router};
+    use loyalty_backend::middleware::auth::JwtSecret;
+    use loyalty_backend::middleware::rate_limit::redis_rate_limit_middleware;
+    use tower::ServiceExt;
+
+    let jwt_secret = JwtSecret(crate::common::test_app_state_config().auth.jwt_secret);
+    let router: Router<()> = Router::new()
+        .route("/ping", get(|| async { "pong" }))
+        .layer(axum_middleware::from_fn_with_state(
+            limiter,
+            redis_rate_limit_middleware,
+        ))
+        .layer(axum::Extension(jwt_secret));
+
+    let mut statuses = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let mut req = Request::builder().uri("/ping");
+        if let Some(token) = token {
+            req = req.header("Authorization", format!("Bearer {}", token));
+        }
+        let resp = router
+            .clone()
+            .oneshot(req.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        statuses.push(resp.status().as_u16());
+    }
+    statuses
+}
+
+#[tokio::test]
+async fn test_rate_limit_keys_on_user_or_ip() {
+    use loyalty_backend::middleware::rate_limit::{
+        RateLimitConfig, RateLimitKeyStrategy, RedisRateLimiter,
+    };
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let alice = crate::common::generate_test_token(&uuid::Uuid::new_v4(), "alice@example.com");
+    let bob = crate::common::generate_test_token(&uuid::Uuid::new_v4(), "bob@example.com");
+    let requests = [
+        Some(alice.as_str()),
+        Some(alice.as_str()),
+        Some(bob.as_str()),
+        Some(bob.as_str()),
+        None,
+        Some(alice.as_str()),
+    ];
+
+    let limiter = |strategy| {
+        RedisRateLimiter::new(
+            app.redis(),
+            RateLimitConfig::new(2, 60).with_key_strategy(strategy),
+            format!("test_rate_key_{}", uuid::Uuid::new_v4().simple()),
+        )
+    };
+
+    // Per user: alice and bob get two requests each, the anonymous request
+    // falls back to the (shared) IP bucket, and alice's third is refused
+    let per_user = statuses_through_limiter(limiter(RateLimitKeyStrategy::User), &requests).await;
+    assert_eq!(per_user, vec![200, 200, 200, 200, 200, 429]);
+
+    // Per IP: everyone shares one bucket, so only the first two get through
+    let per_ip = statuses_through_limiter(limiter(RateLimitKeyStrategy::Ip), &requests).await;
+    assert_eq!(per_ip, vec![200, 200, 429, 429, 429, 429]);
+
+    app.cleanup().await.ok();
+}
+
+// Two replicas behind a load balancer: each holds its own Redis connection
+// but the limit applies to their combined traffic.
+#[tokio::test]
+async fn test_redis_rate_limit_is_shared_across_instances() {
+    use loyalty_backend::middleware::rate_limit::{
+        RateLimitConfig, RateLimitError, RedisRateLimiter,
+    };
+    use redis::aio::ConnectionManager;
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = redis::Client::open(crate::common::test_redis_url()).unwrap();
+    let other_conn = ConnectionManager::new(client).await.unwrap();
+
+    let mut security = crate::common::test_app_state_config().security;
+    security.rate_limit_max_requests = 4;
+    security.rate_limit_window_ms = 60_000;
+    let config = RateLimitConfig::from_security(&security);
+    let prefix = format!("test_rate_shared_{}", uuid::Uuid::new_v4().simple());
+    let first = RedisRateLimiter::new(app.redis(), config.clone(), prefix.clone());
+    let second = RedisRateLimiter::new(other_conn, config, prefix);
+    let ip: std::net::IpAddr = "203.0.113.7".parse().unwrap();
+
+    // Alternate between the instances: four requests in total are allowed
+    for (i, limiter) in [&first, &second, &first, &second].iter().enumerate() {
+        let status = limiter.check_with_status(ip).await.unwrap();
+        assert_eq!(status.remaining, 3 - i as u32);
+        assert!(status.reset_secs > 0 && status.reset_secs <= 60);
+    }
+
+    // ...and the fifth is refused whichever instance receives it
+    for limiter in [&first, &second] {
+        match limiter.check(ip).await {
+            Err(RateLimitError::TooManyRequests { retry_after }) => {
+                assert!(retry_after > 0 && retry_after <= 60)
+            },
+            other => panic!("expected 429, got {:?}", other.map(|_| ())),
+        }
+    }
+    assert_eq!(second.get_count(ip).await.unwrap(), 6);
+
+    first.reset(ip).await.unwrap();
+    app.cleanup().await.ok();
+}
+
+// ============================================================================
+// Password rotation (ADMIN_PASSWORD_MAX_AGE_DAYS)
+// ============================================================================
+
+#[tokio::test]
+async fn test_expired_admin_password_must_be_changed_before_tokens_are_issued() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    // Rotation on for admins only
+    let mut config = crate::common::test_app_state_config();
+    config.auth.admin_password_max_age_days = 90;
+    let state = loyalty_backend::AppState::new(app.db().clone(), app.redis(), config);
+    let client = TestClient::new(loyalty_backend::routes::create_router(state));
+
+    let admin_email = unique_email();
+    let customer_email = unique_email();
+    let password = "SecurePass123!";
+    for email in [&admin_email, &customer_email] {
+        client
+            .post(
+                "/api/auth/register",
+                &json!({
+                    "email": email,
+                    "password": password,
+                    "firstName": "Rotation",
+                    "lastName": "Test"
+                }),
+            )
+            .await
+            .assert_status(200);
+    }
+    sqlx::query(
+        "UPDATE users SET password_changed_at = NOW() - INTERVAL '91 days' WHERE email = ANY($1)",
+    )
+    .bind(vec![admin_email.clone(), customer_email.clone()])
+    .execute(app.db())
+    .await
+    .expect("Failed to age passwords");
+    let admin_id: uuid::Uuid =
+        sqlx::query_scalar("UPDATE users SET role = 'admin' WHERE email = $1 RETURNING id")
+            .bind(&admin_email)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to promote admin");
+
+    // Customers are unaffected while only the admin age is set
+    client
+        .post(
+            "/api/auth/login",
+            &json!({ "email": customer_email, "password": password }),
+        )
+        .await
+        .assert_status(200);
+
+    // The admin gets no tokens until the password is changed
+    let response = client
+        .post(
+            "/api/auth/login",
+            &json!({ "email": admin_email, "password": password }),
+        )
+        .await;
+    response.assert_status(403);
+    let body: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(body["error"], "password_expired");
+    assert!(body.get("tokens").is_none());
+    assert!(response.set_cookie_for("refresh_token").is_none());
+
+    // A session from before the expiry can't be refreshed around it
+    sqlx::query(
+        "INSERT INTO refresh_tokens (user_id, token, expires_at) VALUES ($1, 'pre-expiry-token', NOW() + INTERVAL '1 hour')",
+    )
+    .bind(admin_id)
+    .execute(app.db())
+    .await
+    .expect("Failed to insert refresh token");
+    let response = client
+        .clone()
+        .with_cookie("refresh_token=pre-expiry-token")
+        .post_empty("/api/auth/refresh")
+        .await;
+    response.assert_status(403);
+    let remaining: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM refresh_tokens WHERE token = 'pre-expiry-token'")
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to count refresh tokens");
+    assert_eq!(remaining, 0);
+
+    // The change needs the current password and a different new one
+    let response = client
+        .post(
+            "/api/auth/password/expired",
+            &json!({
+                "email": admin_email,
+                "currentPassword": "WrongPass123!",
+                "newPassword": "BrandNewPass456!"
+            }),
+        )
+        .await;
+    response.assert_status(401);
+    let response = client
+        .post(
+            "/api/auth/password/expired",
+            &json!({
+                "email": admin_email,
+                "currentPassword": password,
+                "newPassword": password
+            }),
+        )
+        .await;
+    response.assert_status(400);
+
+    let response = client
+        .post(
+            "/api/auth/password/expired",
+            &json!({
+                "email": admin_email,
+                "currentPassword": password,
+                "newPassword": "BrandNewPass456!"
+            }),
+        )
+        .await;
+    response.assert_status(200);
+    let body: Value = response.json().expect("Response should be valid JSON");
+    assert!(body["tokens"]["accessToken"].is_string());
+    assert!(response.set_cookie_for("refresh_token").is_some());
+
+    client
+        .post(
+            "/api/auth/login",
+            &json!({ "email": admin_email, "password": "BrandNewPass456!" }),
+        )
+        .await
+        .assert_status(200);
+
+    app.cleanup().await.ok();
+}
+
+// ============================================================================
+// CSRF double-submit check
+// ============================================================================
+
+/// A client whose router enforces the CSRF check on `/api/auth`
+fn csrf_client(app: &TestApp) -> TestClient {
+    let mut config = crate::common::test_app_state_config();
+    config.security.csrf_protected_paths = "/api/auth".to_string();
+    let state = loyalty_backend::AppState::new(app.db().clone(), app.redis(), config);
+    TestClient::new(loyalty_backend::routes::create_router(state))
+}
+
+/// Fetch a CSRF token, checking it is also set as the `csrf_token` cookie
+async fn fetch_csrf_token(client: &TestClient) -> String {
+    let response = client.get("/api/auth/csrf-token").await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    let token = json["csrfToken"]
+        .as_str()
+        .expect("csrfToken should be a string")
+        .to_string();
+
+    let cookie = response
+        .set_cookie_for("csrf_token")
+        .expect("The token should be set as a cookie");
+    assert_eq!(parse_cookie_value(&cookie), token);
+    assert!(
+        !cookie.contains("HttpOnly"),
+        "The frontend must be able to read it"
+    );
+    token
+}
+
+#[tokio::test]
+async fn test_csrf_matching_token_is_accepted() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = csrf_client(&app);
+
+    // No cookies yet, so registering needs no token
+    let (_, _, refresh) = register_user(&client).await;
+    let token = fetch_csrf_token(&client).await;
+
+    let response = client
+        .clone()
+        .with_cookie(&format!("refresh_token={}; csrf_token={}", refresh, token))
+        .post_with_headers(
+            "/api/auth/refresh",
+            &json!({}),
+            &[("X-CSRF-Token", token.as_str())],
+        )
+        .await;
+    response.assert_status(200);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_csrf_missing_or_mismatched_token_is_rejected() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = csrf_client(&app);
+
+    let (_, _, refresh) = register_user(&client).await;
+    let token = fetch_csrf_token(&client).await;
+    let cookie_client = client
+        .clone()
+        .with_cookie(&format!("refresh_token={}; csrf_token={}", refresh, token));
+
+    let response = cookie_client.post("/api/auth/refresh", &json!({})).await;
+    response.assert_status(403);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["error"], "csrf_failed");
+
+    let other = fetch_csrf_token(&csrf_client(&app)).await;
+    assert_ne!(other, token);
+    let response = cookie_client
+        .post_with_headers(
+            "/api/auth/refresh",
+            &json!({}),
+            &[("X-CSRF-Token", other.as_str())],
+        )
+        .await;
+    response.assert_status(403);
+
+    // A token in the header without the cookie proves nothing either
+    let response = client
+        .clone()
+        .with_cookie(&format!("refresh_token={}", refresh))
+        .post_with_headers(
+            "/api/auth/refresh",
+            &json!({}),
+            &[("X-CSRF-Token", token.as_str())],
+        )
+        .await;
+    response.assert_status(403);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_csrf_bearer_requests_are_exempt() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = csrf_client(&app);
+
+    let (_, register_response, refresh) = register_user(&client).await;
+    let json: Value = register_response
+        .json()
+        .expect("Response should be valid JSON");
+    let access_token = json["tokens"]["accessToken"]
+        .as_str()
+        .expect("accessToken should be a string");
+
+    let response = client
+        .clone()
+        .with_auth(access_token)
+        .with_cookie(&format!("refresh_token={}", refresh))
+        .post("/api/auth/logout-all", &json!({}))
+        .await;
+    response.assert_status(200);
+
+    app.cleanup().await.ok();
+}
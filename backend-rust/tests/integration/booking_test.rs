@@ -7,6 +7,7 @@
 //! - Cancelling bookings
 //! - Completing bookings (admin)
 //! - Checking room availability
+//! - Booking notes and their visibility scopes
 
 use chrono::{Duration, Utc};
 use serde_json::{json, Value};
@@ -758,6 +759,185 @@ async fn test_complete_booking_cannot_complete_cancelled() {
     app.cleanup().await.ok();
 }
 
+/// Create a 3-night booking `BookingServiceImpl` can address as
+/// `service_id`: the service maps its i32 ids to v5 UUIDs, so the booking is
+/// re-keyed to that UUID, which is returned.
+async fn create_service_booking(
+    pool: &sqlx::PgPool,
+    user_id: Uuid,
+    service_id: i32,
+    status: &str,
+) -> Uuid {
+    let booking_id = create_test_booking(pool, user_id, status, -5, -2)
+        .await
+        .expect("Failed to create booking");
+    let service_uuid = Uuid::new_v5(&Uuid::NAMESPACE_OID, service_id.to_string().as_bytes());
+    sqlx::query("UPDATE bookings SET id = $2 WHERE id = $1")
+        .bind(booking_id)
+        .bind(service_uuid)
+        .execute(pool)
+        .await
+        .expect("Failed to re-key booking");
+    service_uuid
+}
+
+fn complete_booking_dto() -> loyalty_backend::services::UpdateBookingDto {
+    loyalty_backend::services::UpdateBookingDto {
+        status: Some(loyalty_backend::services::BookingStatus::Completed),
+        check_in_date: None,
+        check_out_date: None,
+        adults: None,
+        children: None,
+        notes: None,
+        total_amount: None,
+        special_requests: None,
+    }
+}
+
+/// `(total_nights, current_points)` from the member's loyalty row
+async fn loyalty_totals(pool: &sqlx::PgPool, user_id: Uuid) -> (i32, i32) {
+    sqlx::query_as("SELECT total_nights, current_points FROM user_loyalty WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .expect("User should be enrolled")
+}
+
+#[tokio::test]
+async fn test_update_booking_to_completed_awards_nights_once() {
+    use loyalty_backend::services::{BookingService, BookingServiceImpl, BookingStatus};
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("update-complete@test.com");
+    user.insert(app.db()).await.expect("Failed to insert user");
+
+    // A 3-night stay
+    let service_uuid = create_service_booking(app.db(), user.id, 4242, "confirmed").await;
+
+    let service = BookingServiceImpl::new(app.db().clone());
+    let first = service
+        .update_booking(4242, complete_booking_dto())
+        .await
+        .expect("First completion should succeed");
+    assert_eq!(first.status, BookingStatus::Completed);
+    assert!(first.points_earned > 0, "Stay should earn points");
+
+    // Marking it completed again must not credit the stay a second time
+    let second = service
+        .update_booking(4242, complete_booking_dto())
+        .await
+        .expect("Repeated completion should be a no-op");
+    assert_eq!(second.status, BookingStatus::Completed);
+
+    let (total_nights, current_points) = loyalty_totals(app.db(), user.id).await;
+    assert_eq!(total_nights, 3);
+    assert_eq!(current_points, first.points_earned);
+
+    let stay_transactions: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM points_transactions
+        WHERE user_id = $1 AND type = 'earned_stay' AND reference_id = $2
+        "#,
+    )
+    .bind(user.id)
+    .bind(format!("BOOKING-{}", service_uuid))
+    .fetch_one(app.db())
+    .await
+    .expect("Failed to count stay transactions");
+    assert_eq!(stay_transactions, 1);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_cancel_completed_booking_reverses_stay_once() {
+    use loyalty_backend::services::{BookingService, BookingServiceImpl, BookingStatus};
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("cancel-after-award@test.com");
+    user.insert(app.db()).await.expect("Failed to insert user");
+
+    let service_uuid = create_service_booking(app.db(), user.id, 4343, "confirmed").await;
+    let service = BookingServiceImpl::new(app.db().clone());
+    let completed = service
+        .update_booking(4343, complete_booking_dto())
+        .await
+        .expect("Completion should succeed");
+    assert!(completed.points_earned > 0);
+    assert_eq!(loyalty_totals(app.db(), user.id).await.0, 3);
+
+    let cancelled = service
+        .cancel_booking(4343)
+        .await
+        .expect("A completed booking can be cancelled");
+    assert_eq!(cancelled.status, BookingStatus::Cancelled);
+    assert_eq!(loyalty_totals(app.db(), user.id).await, (0, 0));
+
+    // Cancelling again must not take the stay back a second time
+    let again = service
+        .cancel_booking(4343)
+        .await
+        .expect("Repeated cancellation should be a no-op");
+    assert_eq!(again.status, BookingStatus::Cancelled);
+    assert_eq!(loyalty_totals(app.db(), user.id).await, (0, 0));
+
+    let reversals: Vec<(i32, i32)> = sqlx::query_as(
+        r#"
+        SELECT points, nights_stayed FROM points_transactions
+        WHERE user_id = $1 AND type = 'refund_adjustment' AND reference_id = $2
+        "#,
+    )
+    .bind(user.id)
+    .bind(format!("BOOKING-{}", service_uuid))
+    .fetch_all(app.db())
+    .await
+    .expect("Failed to load reversals");
+    assert_eq!(reversals, vec![(-completed.points_earned, -3)]);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_cancel_booking_without_award_reverses_nothing() {
+    use loyalty_backend::services::{BookingService, BookingServiceImpl, BookingStatus};
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("cancel-no-award@test.com");
+    user.insert(app.db()).await.expect("Failed to insert user");
+    sqlx::query(
+        "INSERT INTO user_loyalty (user_id, current_points, total_nights) VALUES ($1, 500, 4)",
+    )
+    .bind(user.id)
+    .execute(app.db())
+    .await
+    .expect("Failed to create user_loyalty");
+
+    create_service_booking(app.db(), user.id, 4444, "confirmed").await;
+    let service = BookingServiceImpl::new(app.db().clone());
+
+    let cancelled = service
+        .cancel_booking(4444)
+        .await
+        .expect("Cancellation should succeed");
+    assert_eq!(cancelled.status, BookingStatus::Cancelled);
+
+    // Nothing was credited for the booking, so the balance is untouched
+    assert_eq!(loyalty_totals(app.db(), user.id).await, (4, 500));
+    let adjustments: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM points_transactions WHERE user_id = $1 AND type = 'refund_adjustment'",
+    )
+    .bind(user.id)
+    .fetch_one(app.db())
+    .await
+    .expect("Failed to count adjustments");
+    assert_eq!(adjustments, 0);
+
+    app.cleanup().await.ok();
+}
+
 // ============================================================================
 // test_check_availability - GET /api/bookings/availability
 // ============================================================================
@@ -927,6 +1107,101 @@ async fn test_check_availability_invalid_dates() {
     app.cleanup().await.ok();
 }
 
+#[tokio::test]
+async fn test_check_availability_counts_overlapping_bookings() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("availability-overlap@test.com");
+    user.insert(app.db())
+        .await
+        .expect("Failed to insert test user");
+
+    // Two Suites make up the inventory
+    let room_type_id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO room_types (id, name, price_per_night, max_guests, is_active)
+        VALUES ($1, 'Suite', 4000.00, 2, true)
+        "#,
+    )
+    .bind(room_type_id)
+    .execute(app.db())
+    .await
+    .expect("Failed to insert room type");
+
+    let mut room_ids = Vec::new();
+    for room_number in ["401", "402"] {
+        let room_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO rooms (id, room_type_id, room_number, floor, is_active)
+            VALUES ($1, $2, $3, 4, true)
+            "#,
+        )
+        .bind(room_id)
+        .bind(room_type_id)
+        .bind(room_number)
+        .execute(app.db())
+        .await
+        .expect("Failed to insert room");
+        room_ids.push(room_id);
+    }
+
+    // 401 is booked for nights 40-42, 402 for nights 41-44; a cancelled
+    // booking on 401 for nights 50-51 doesn't hold the room
+    let today = Utc::now().date_naive();
+    for (room_id, check_in, check_out, status) in [
+        (room_ids[0], 40, 43, "confirmed"),
+        (room_ids[1], 41, 45, "confirmed"),
+        (room_ids[0], 50, 52, "cancelled"),
+    ] {
+        sqlx::query(
+            r#"
+            INSERT INTO bookings (user_id, room_id, room_type_id, check_in_date, check_out_date, num_guests, total_price, status)
+            VALUES ($1, $2, $3, $4, $5, 2, 8000.00, $6)
+            "#,
+        )
+        .bind(user.id)
+        .bind(room_id)
+        .bind(room_type_id)
+        .bind(today + Duration::days(check_in))
+        .bind(today + Duration::days(check_out))
+        .bind(status)
+        .execute(app.db())
+        .await
+        .expect("Failed to insert booking");
+    }
+
+    let client = app.authenticated_client(&user.id, &user.email);
+    let available_rooms = |check_in: i64, check_out: i64| {
+        let client = client.clone();
+        async move {
+            let response = client
+                .get(&format!(
+                    "/api/bookings/availability?check_in={}&check_out={}&room_type=suite",
+                    (today + Duration::days(check_in)).format("%Y-%m-%d"),
+                    (today + Duration::days(check_out)).format("%Y-%m-%d")
+                ))
+                .await;
+            response.assert_status(200);
+            let json: Value = response.json().expect("Response should be valid JSON");
+            let rooms = json["availableRooms"].as_i64().expect("availableRooms");
+            assert_eq!(json["available"], rooms > 0);
+            rooms
+        }
+    };
+
+    // Fully booked: both bookings cover nights 41-42
+    assert_eq!(available_rooms(41, 43).await, 0);
+    // Partially overlapping: 401 frees up on day 43, 402 is still taken
+    assert_eq!(available_rooms(43, 46).await, 1);
+    // Free: arriving the day 402 checks out
+    assert_eq!(available_rooms(45, 48).await, 2);
+    assert_eq!(available_rooms(50, 52).await, 2);
+
+    app.cleanup().await.ok();
+}
+
 // ============================================================================
 // Additional Edge Case Tests
 // ============================================================================
@@ -1573,3 +1848,241 @@ async fn test_admin_can_view_all_bookings() {
 
     app.cleanup().await.ok();
 }
+
+// ============================================================================
+// Pending points for stays awaiting slip verification
+// ============================================================================
+
+#[tokio::test]
+async fn test_pending_points_released_on_verification() {
+    use loyalty_backend::services::loyalty::{
+        hold_pending_points, pending_points_balance, release_due_pending_points,
+        release_pending_points_for_booking,
+    };
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("pending-points@test.com");
+    user.insert(app.db()).await.expect("Failed to insert user");
+    sqlx::query(
+        "INSERT INTO user_loyalty (user_id, current_points, total_nights) VALUES ($1, 0, 0)",
+    )
+    .bind(user.id)
+    .execute(app.db())
+    .await
+    .expect("Failed to create user_loyalty");
+
+    let verified_booking = create_test_booking(app.db(), user.id, "completed", -5, -2)
+        .await
+        .expect("Failed to create booking");
+    let overdue_booking = create_test_booking(app.db(), user.id, "completed", -10, -8)
+        .await
+        .expect("Failed to create booking");
+
+    hold_pending_points(app.db(), user.id, verified_booking, 500, "BOOKING-A", 14)
+        .await
+        .expect("Failed to hold points");
+    // Holding the same booking again is a no-op
+    hold_pending_points(app.db(), user.id, verified_booking, 500, "BOOKING-A", 14)
+        .await
+        .expect("Failed to repeat hold");
+    hold_pending_points(app.db(), user.id, overdue_booking, 200, "BOOKING-B", 14)
+        .await
+        .expect("Failed to hold points");
+
+    assert_eq!(
+        pending_points_balance(app.db(), user.id).await.unwrap(),
+        700
+    );
+
+    let client = app.authenticated_client(&user.id, &user.email);
+    let response = client.get("/api/loyalty/status").await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(
+        json.pointer("/data/pending_points")
+            .and_then(|v| v.as_i64()),
+        Some(700)
+    );
+    assert_eq!(
+        json.pointer("/data/available_points")
+            .and_then(|v| v.as_i64()),
+        Some(0)
+    );
+
+    // Verifying the slip credits that booking's points
+    let mut conn = app
+        .db()
+        .acquire()
+        .await
+        .expect("Failed to acquire connection");
+    let released = release_pending_points_for_booking(&mut conn, verified_booking)
+        .await
+        .expect("Failed to release points");
+    assert_eq!(released, 500);
+    let again = release_pending_points_for_booking(&mut conn, verified_booking)
+        .await
+        .expect("Failed to repeat release");
+    assert_eq!(again, 0);
+    drop(conn);
+
+    // The other hold clears once its period has passed
+    assert_eq!(release_due_pending_points(app.db()).await.unwrap(), 0);
+    sqlx::query(
+        "UPDATE pending_points SET clears_at = NOW() - INTERVAL '1 minute' WHERE booking_id = $1",
+    )
+    .bind(overdue_booking)
+    .execute(app.db())
+    .await
+    .expect("Failed to age hold");
+    assert_eq!(release_due_pending_points(app.db()).await.unwrap(), 1);
+
+    let current_points: i32 =
+        sqlx::query_scalar("SELECT current_points FROM user_loyalty WHERE user_id = $1")
+            .bind(user.id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to fetch balance");
+    assert_eq!(current_points, 700);
+    assert_eq!(pending_points_balance(app.db(), user.id).await.unwrap(), 0);
+
+    let released_rows: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM points_transactions WHERE user_id = $1 AND type = 'earned_stay'",
+    )
+    .bind(user.id)
+    .fetch_one(app.db())
+    .await
+    .expect("Failed to count transactions");
+    assert_eq!(released_rows, 2);
+
+    app.cleanup().await.ok();
+}
+
+// ============================================================================
+// Booking notes - GET/POST /api/bookings/:id/notes
+// ============================================================================
+
+#[tokio::test]
+async fn test_booking_notes_guest_sees_only_guest_notes() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let admin = TestUser::admin("admin-notes@test.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin");
+    let guest = TestUser::new("guest-notes@test.com");
+    guest
+        .insert(app.db())
+        .await
+        .expect("Failed to insert guest");
+
+    let booking_id = create_test_booking(app.db(), guest.id, "confirmed", 3, 5)
+        .await
+        .expect("Failed to create booking");
+
+    let staff = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+    let notes_url = format!("/api/bookings/{}/notes", booking_id);
+
+    let response = staff
+        .post(
+            &notes_url,
+            &json!({ "body": "Guest disputed the minibar charge" }),
+        )
+        .await;
+    response.assert_status(201);
+    let internal: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(internal["visibility"], "internal");
+
+    let response = staff
+        .post(
+            &notes_url,
+            &json!({ "body": "Late check-out approved until 14:00", "visibility": "guest" }),
+        )
+        .await;
+    response.assert_status(201);
+
+    // Staff see both notes
+    let response = staff.get(&notes_url).await;
+    response.assert_status(200);
+    let notes: Vec<Value> = response.json().expect("Response should be valid JSON");
+    assert_eq!(notes.len(), 2);
+
+    // The guest sees only the guest-visible one, and nothing of the other
+    let response = app
+        .authenticated_client(&guest.id, &guest.email)
+        .get(&notes_url)
+        .await;
+    response.assert_status(200);
+    assert!(
+        !response.body.contains("minibar"),
+        "Internal note leaked to the guest: {}",
+        response.body
+    );
+    let notes: Vec<Value> = response.json().expect("Response should be valid JSON");
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0]["visibility"], "guest");
+    assert_eq!(notes[0]["body"], "Late check-out approved until 14:00");
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_booking_notes_guest_cannot_add_notes() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let guest = TestUser::new("guest-add-note@test.com");
+    guest
+        .insert(app.db())
+        .await
+        .expect("Failed to insert guest");
+    let booking_id = create_test_booking(app.db(), guest.id, "confirmed", 3, 5)
+        .await
+        .expect("Failed to create booking");
+
+    let response = app
+        .authenticated_client(&guest.id, &guest.email)
+        .post(
+            &format!("/api/bookings/{}/notes", booking_id),
+            &json!({ "body": "Please upgrade me", "visibility": "internal" }),
+        )
+        .await;
+    response.assert_status(403);
+
+    let stored: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM booking_notes WHERE booking_id = $1")
+            .bind(booking_id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to count notes");
+    assert_eq!(stored, 0);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_booking_notes_forbidden_for_other_user() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let owner = TestUser::new("notes-owner@test.com");
+    owner
+        .insert(app.db())
+        .await
+        .expect("Failed to insert owner");
+    let other = TestUser::new("notes-other@test.com");
+    other
+        .insert(app.db())
+        .await
+        .expect("Failed to insert other");
+    let booking_id = create_test_booking(app.db(), owner.id, "confirmed", 3, 5)
+        .await
+        .expect("Failed to create booking");
+
+    let response = app
+        .authenticated_client(&other.id, &other.email)
+        .get(&format!("/api/bookings/{}/notes", booking_id))
+        .await;
+    response.assert_status(403);
+
+    app.cleanup().await.ok();
+}
@@ -5,8 +5,14 @@
 //! - Getting user's assigned coupons
 //! - Creating coupons (admin only)
 //! - Assigning coupons to users
+//! - Assigning coupons to a tier or segment
+//! - QR code images of user coupons
 //! - Redeeming coupons
 //! - Redemption validation
+//! - Previewing a coupon's discount
+//! - BOGO, free upgrade and free service coupon math
+//! - Usage limits under concurrent redemptions
+//! - The expiry sweep
 
 use chrono::{Duration, Utc};
 use serde_json::{json, Value};
@@ -45,6 +51,36 @@ async fn insert_user_coupon(
     Ok((user_coupon_id, qr_code))
 }
 
+/// Read a decimal amount from a response, whether serialized as a number
+/// or a string
+fn amount(data: &Value, field: &str) -> f64 {
+    data.get(field)
+        .and_then(|v| {
+            v.as_f64()
+                .or_else(|| v.as_str().and_then(|s| s.parse::<f64>().ok()))
+        })
+        .unwrap_or_else(|| panic!("Response should have {}: {:?}", field, data))
+}
+
+/// Insert a member with a loyalty row in the named tier
+async fn insert_tier_member(pool: &sqlx::PgPool, email: &str, tier: &str, nights: i32) -> Uuid {
+    let user = TestUser::new(email);
+    user.insert(pool).await.expect("Failed to insert user");
+    sqlx::query(
+        r#"
+        INSERT INTO user_loyalty (user_id, tier_id, current_points, total_nights)
+        SELECT $1, id, 0, $3 FROM tiers WHERE name = $2
+        "#,
+    )
+    .bind(user.id)
+    .bind(tier)
+    .bind(nights)
+    .execute(pool)
+    .await
+    .expect("Failed to insert loyalty row");
+    user.id
+}
+
 // ============================================================================
 // Test: List Coupons
 // ============================================================================
@@ -702,3 +738,861 @@ async fn test_assign_coupon_to_multiple_users() {
 
     app.cleanup().await.ok();
 }
+
+// ============================================================================
+// Test: Assign Coupon By Segment
+// ============================================================================
+
+#[tokio::test]
+async fn test_assign_coupon_by_tier_only_reaches_that_tier() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let admin = TestUser::admin("admin_segment@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin user");
+
+    let bronze = insert_tier_member(app.db(), "bronze@example.com", "Bronze", 0).await;
+    let gold = insert_tier_member(app.db(), "gold@example.com", "Gold", 12).await;
+    let gold_holder = insert_tier_member(app.db(), "gold-holder@example.com", "Gold", 15).await;
+    let platinum = insert_tier_member(app.db(), "platinum@example.com", "Platinum", 25).await;
+
+    let coupon = TestCoupon::percentage("GOLDONLY", 15.0);
+    coupon
+        .insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+    insert_user_coupon(app.db(), gold_holder, coupon.id, "available")
+        .await
+        .expect("Failed to insert user coupon");
+
+    let client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+    let response = client
+        .post(
+            "/api/coupons/assign-by-segment",
+            &json!({ "couponId": coupon.id, "tiers": ["Gold"] }),
+        )
+        .await;
+    response.assert_status(200);
+
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["data"]["assigned"], 1);
+    assert_eq!(json["data"]["skipped"], 1);
+
+    let holders: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT user_id FROM user_coupons WHERE coupon_id = $1 ORDER BY user_id",
+    )
+    .bind(coupon.id)
+    .fetch_all(app.db())
+    .await
+    .expect("Failed to fetch user coupons");
+    let mut expected = vec![gold, gold_holder];
+    expected.sort();
+    assert_eq!(holders, expected);
+    assert!(!holders.contains(&bronze) && !holders.contains(&platinum));
+
+    // The coupon's own tier restrictions narrow the request: a
+    // Platinum-only coupon sent to Gold and Platinum reaches Platinum only
+    let platinum_coupon = TestCoupon::percentage("PLATONLY", 20.0);
+    platinum_coupon
+        .insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+    sqlx::query("UPDATE coupons SET tier_restrictions = '[\"Platinum\"]' WHERE id = $1")
+        .bind(platinum_coupon.id)
+        .execute(app.db())
+        .await
+        .expect("Failed to set tier restrictions");
+
+    let response = client
+        .post(
+            "/api/coupons/assign-by-segment",
+            &json!({
+                "couponId": platinum_coupon.id,
+                "tiers": ["Gold", "Platinum"],
+                "segment": { "minNights": 20 }
+            }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["data"]["assigned"], 1);
+    assert_eq!(json["data"]["skipped"], 0);
+
+    let holders: Vec<Uuid> =
+        sqlx::query_scalar("SELECT user_id FROM user_coupons WHERE coupon_id = $1")
+            .bind(platinum_coupon.id)
+            .fetch_all(app.db())
+            .await
+            .expect("Failed to fetch user coupons");
+    assert_eq!(holders, vec![platinum]);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_assign_coupon_by_segment_rejects_missing_or_unknown_criteria() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let admin = TestUser::admin("admin_segment_invalid@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin user");
+    let coupon = TestCoupon::percentage("NOTARGET", 10.0);
+    coupon
+        .insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+
+    let client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+
+    // Without any criterion the request would reach every member
+    client
+        .post(
+            "/api/coupons/assign-by-segment",
+            &json!({ "couponId": coupon.id }),
+        )
+        .await
+        .assert_status(400);
+    client
+        .post(
+            "/api/coupons/assign-by-segment",
+            &json!({ "couponId": coupon.id, "tiers": ["Diamond"] }),
+        )
+        .await
+        .assert_status(400);
+
+    let assigned: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM user_coupons WHERE coupon_id = $1")
+            .bind(coupon.id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to count user coupons");
+    assert_eq!(assigned, 0);
+
+    app.cleanup().await.ok();
+}
+
+// ============================================================================
+// Test: Concurrent Redemptions Respect Usage Limit
+// ============================================================================
+
+#[tokio::test]
+async fn test_concurrent_redemptions_stop_at_usage_limit() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let coupon = TestCoupon::percentage("LIMIT2", 10.0);
+    coupon
+        .insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+    sqlx::query("UPDATE coupons SET usage_limit = 2, used_count = 0 WHERE id = $1")
+        .bind(coupon.id)
+        .execute(app.db())
+        .await
+        .expect("Failed to set usage limit");
+
+    let mut handles = Vec::new();
+    for i in 0..4 {
+        let user = TestUser::new(&format!("limit-redeemer-{}@example.com", i));
+        user.insert(app.db()).await.expect("Failed to insert user");
+        let (_, qr_code) = insert_user_coupon(app.db(), user.id, coupon.id, "available")
+            .await
+            .expect("Failed to insert user coupon");
+
+        let client = app.authenticated_client(&user.id, &user.email);
+        handles.push(tokio::spawn(async move {
+            client
+                .post(
+                    "/api/coupons/redeem",
+                    &json!({ "qrCode": qr_code, "originalAmount": 500.00 }),
+                )
+                .await
+                .status
+        }));
+    }
+
+    let statuses: Vec<u16> = futures::future::join_all(handles)
+        .await
+        .into_iter()
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(statuses.iter().filter(|s| **s == 200).count(), 2);
+    assert_eq!(statuses.iter().filter(|s| **s == 400).count(), 2);
+
+    let (used_count, status): (i32, String) =
+        sqlx::query_as("SELECT used_count, status::text FROM coupons WHERE id = $1")
+            .bind(coupon.id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to fetch coupon");
+    assert_eq!(used_count, 2);
+    assert_eq!(status, "exhausted");
+
+    // Refused redemptions leave their user coupons available
+    let used: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM user_coupons WHERE coupon_id = $1 AND status = 'used'",
+    )
+    .bind(coupon.id)
+    .fetch_one(app.db())
+    .await
+    .expect("Failed to count used coupons");
+    assert_eq!(used, 2);
+
+    app.cleanup().await.ok();
+}
+
+/// `CouponService::redeem_coupon` lets exactly one of N simultaneous
+/// redemptions through on a single-use coupon, refuses the rest with
+/// `CouponLimitReached`, and never redeems the same user coupon twice
+#[tokio::test]
+async fn test_service_redemption_enforces_usage_limit_atomically() {
+    use loyalty_backend::error::AppError;
+    use loyalty_backend::services::coupon::{CouponService, CouponServiceImpl};
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let coupon = TestCoupon::percentage("SINGLE1", 10.0);
+    coupon
+        .insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+    sqlx::query("UPDATE coupons SET usage_limit = 1, used_count = 0 WHERE id = $1")
+        .bind(coupon.id)
+        .execute(app.db())
+        .await
+        .expect("Failed to set usage limit");
+
+    let mut user_coupon_ids = Vec::new();
+    for i in 0..5 {
+        let user = TestUser::new(&format!("single-redeemer-{}@example.com", i));
+        user.insert(app.db()).await.expect("Failed to insert user");
+        let (user_coupon_id, _) = insert_user_coupon(app.db(), user.id, coupon.id, "available")
+            .await
+            .expect("Failed to insert user coupon");
+        user_coupon_ids.push(user_coupon_id);
+    }
+
+    let handles: Vec<_> = user_coupon_ids
+        .iter()
+        .map(|&user_coupon_id| {
+            let service = CouponServiceImpl::new(app.db().clone());
+            tokio::spawn(async move { service.redeem_coupon(user_coupon_id).await })
+        })
+        .collect();
+    let results: Vec<_> = futures::future::join_all(handles)
+        .await
+        .into_iter()
+        .map(|r| r.unwrap())
+        .collect();
+
+    assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+    assert!(results
+        .iter()
+        .filter_map(|r| r.as_ref().err())
+        .all(|e| matches!(e, AppError::CouponLimitReached(_))));
+
+    let used_count: i32 = sqlx::query_scalar("SELECT used_count FROM coupons WHERE id = $1")
+        .bind(coupon.id)
+        .fetch_one(app.db())
+        .await
+        .expect("Failed to fetch coupon");
+    assert_eq!(used_count, 1);
+
+    let used: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM user_coupons WHERE coupon_id = $1 AND status = 'used'",
+    )
+    .bind(coupon.id)
+    .fetch_one(app.db())
+    .await
+    .expect("Failed to count used coupons");
+    assert_eq!(used, 1);
+
+    // Redeeming one user coupon twice at once counts it once
+    let unlimited = TestCoupon::percentage("UNLIMITED1", 10.0);
+    unlimited
+        .insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+    let user = TestUser::new("double-redeemer@example.com");
+    user.insert(app.db()).await.expect("Failed to insert user");
+    let (user_coupon_id, _) = insert_user_coupon(app.db(), user.id, unlimited.id, "available")
+        .await
+        .expect("Failed to insert user coupon");
+
+    let service = CouponServiceImpl::new(app.db().clone());
+    let (first, second) = tokio::join!(
+        service.redeem_coupon(user_coupon_id),
+        service.redeem_coupon(user_coupon_id)
+    );
+    assert_eq!([&first, &second].iter().filter(|r| r.is_ok()).count(), 1);
+
+    let used_count: i32 = sqlx::query_scalar("SELECT used_count FROM coupons WHERE id = $1")
+        .bind(unlimited.id)
+        .fetch_one(app.db())
+        .await
+        .expect("Failed to fetch coupon");
+    assert_eq!(used_count, 1);
+
+    app.cleanup().await.ok();
+}
+
+/// Redemption enforces the coupon's `tier_restrictions` against the
+/// member's current tier, and a refused one leaves the coupon unused
+#[tokio::test]
+async fn test_service_redemption_enforces_tier_restrictions() {
+    use loyalty_backend::error::AppError;
+    use loyalty_backend::services::coupon::{CouponService, CouponServiceImpl};
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let coupon = TestCoupon::percentage("GOLDTIER", 10.0);
+    coupon
+        .insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+    sqlx::query("UPDATE coupons SET tier_restrictions = '[\"Gold\"]' WHERE id = $1")
+        .bind(coupon.id)
+        .execute(app.db())
+        .await
+        .expect("Failed to set tier restrictions");
+
+    let bronze = insert_tier_member(app.db(), "tier-bronze@example.com", "Bronze", 0).await;
+    let gold = insert_tier_member(app.db(), "tier-gold@example.com", "Gold", 12).await;
+    let (bronze_coupon, _) = insert_user_coupon(app.db(), bronze, coupon.id, "available")
+        .await
+        .expect("Failed to insert user coupon");
+    let (gold_coupon, _) = insert_user_coupon(app.db(), gold, coupon.id, "available")
+        .await
+        .expect("Failed to insert user coupon");
+
+    let service = CouponServiceImpl::new(app.db().clone());
+
+    let refused = service.redeem_coupon(bronze_coupon).await;
+    assert!(
+        matches!(&refused, Err(AppError::Forbidden(msg)) if msg.contains("Gold")),
+        "Bronze member should be refused, got {:?}",
+        refused
+    );
+    let status: String = sqlx::query_scalar("SELECT status::text FROM user_coupons WHERE id = $1")
+        .bind(bronze_coupon)
+        .fetch_one(app.db())
+        .await
+        .expect("Failed to fetch user coupon");
+    assert_eq!(status, "available");
+
+    let redeemed = service
+        .redeem_coupon(gold_coupon)
+        .await
+        .expect("Gold member should redeem");
+    assert_eq!(redeemed.user_id, gold);
+
+    let used_count: i32 = sqlx::query_scalar("SELECT used_count FROM coupons WHERE id = $1")
+        .bind(coupon.id)
+        .fetch_one(app.db())
+        .await
+        .expect("Failed to fetch coupon");
+    assert_eq!(used_count, 1);
+
+    app.cleanup().await.ok();
+}
+
+// ============================================================================
+// Test: Calculate Coupon Discount
+// ============================================================================
+
+#[tokio::test]
+async fn test_calculate_coupon_applies_cap_and_minimum_spend_without_redeeming() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("calculate@example.com");
+    user.insert(app.db()).await.expect("Failed to insert user");
+    let client = app.authenticated_client(&user.id, &user.email);
+
+    // 20% of 1000 is 200, capped at 50
+    let capped = TestCoupon::percentage("CAPPED20", 20.0);
+    capped
+        .insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+    sqlx::query("UPDATE coupons SET maximum_discount = 50 WHERE id = $1")
+        .bind(capped.id)
+        .execute(app.db())
+        .await
+        .expect("Failed to set maximum discount");
+    let (capped_user_coupon, capped_qr) =
+        insert_user_coupon(app.db(), user.id, capped.id, "available")
+            .await
+            .expect("Failed to insert user coupon");
+
+    let response = client
+        .post(
+            "/api/coupons/calculate",
+            &json!({ "qrCode": capped_qr, "originalAmount": 1000.00 }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    let data = &json["data"];
+    assert_eq!(data["applicable"], true);
+    assert_eq!(amount(data, "discountAmount"), 50.0);
+    assert_eq!(amount(data, "finalAmount"), 950.0);
+
+    // 100 off, but only on orders of 500 or more
+    let fixed = TestCoupon::fixed_amount("FIXED100", 100.0);
+    fixed
+        .insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+    sqlx::query("UPDATE coupons SET minimum_spend = 500 WHERE id = $1")
+        .bind(fixed.id)
+        .execute(app.db())
+        .await
+        .expect("Failed to set minimum spend");
+    let (_, fixed_qr) = insert_user_coupon(app.db(), user.id, fixed.id, "available")
+        .await
+        .expect("Failed to insert user coupon");
+
+    let response = client
+        .post(
+            "/api/coupons/calculate",
+            &json!({ "qrCode": fixed_qr, "originalAmount": 300.00 }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    let data = &json["data"];
+    assert_eq!(data["applicable"], false);
+    assert!(data["reason"]
+        .as_str()
+        .is_some_and(|reason| reason.contains("Minimum spend")));
+    assert_eq!(amount(data, "discountAmount"), 0.0);
+    assert_eq!(amount(data, "finalAmount"), 300.0);
+
+    // Previewing consumed nothing
+    let status: String = sqlx::query_scalar("SELECT status::text FROM user_coupons WHERE id = $1")
+        .bind(capped_user_coupon)
+        .fetch_one(app.db())
+        .await
+        .expect("Failed to fetch user coupon");
+    assert_eq!(status, "available");
+    let used_count: i32 =
+        sqlx::query_scalar("SELECT COALESCE(used_count, 0) FROM coupons WHERE id = $1")
+            .bind(capped.id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to fetch coupon");
+    assert_eq!(used_count, 0);
+
+    client
+        .post(
+            "/api/coupons/calculate",
+            &json!({ "qrCode": "QR-UNKNOWN", "originalAmount": 100.00 }),
+        )
+        .await
+        .assert_status(404);
+
+    app.cleanup().await.ok();
+}
+
+// ============================================================================
+// Test: Non-Monetary Coupon Types
+// ============================================================================
+
+/// Insert a coupon of `coupon_type` with `description`, and a user coupon
+/// of it for `user_id`; returns the QR code
+async fn insert_typed_coupon(
+    pool: &sqlx::PgPool,
+    user_id: Uuid,
+    code: &str,
+    coupon_type: &str,
+    description: Option<&str>,
+) -> String {
+    let coupon = TestCoupon {
+        coupon_type: coupon_type.to_string(),
+        value: None,
+        ..TestCoupon::percentage(code, 0.0)
+    };
+    coupon.insert(pool).await.expect("Failed to insert coupon");
+    sqlx::query("UPDATE coupons SET description = $2 WHERE id = $1")
+        .bind(coupon.id)
+        .bind(description)
+        .execute(pool)
+        .await
+        .expect("Failed to set description");
+    let (_, qr_code) = insert_user_coupon(pool, user_id, coupon.id, "available")
+        .await
+        .expect("Failed to insert user coupon");
+    qr_code
+}
+
+#[tokio::test]
+async fn test_bogo_coupon_makes_cheaper_item_of_each_pair_free() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("bogo@example.com");
+    user.insert(app.db()).await.expect("Failed to insert user");
+    let client = app.authenticated_client(&user.id, &user.email);
+    let qr_code = insert_typed_coupon(app.db(), user.id, "BOGO1", "bogo", None).await;
+
+    // Without the items there is nothing to make free
+    client
+        .post(
+            "/api/coupons/redeem",
+            &json!({ "qrCode": qr_code, "originalAmount": 800.00 }),
+        )
+        .await
+        .assert_status(400);
+
+    // Units by price: 300, 200, 200, 100 -> the 200 and the 100 are free
+    let response = client
+        .post(
+            "/api/coupons/redeem",
+            &json!({
+                "qrCode": qr_code,
+                "originalAmount": 800.00,
+                "lineItems": [
+                    { "name": "Dinner set", "unitPrice": 200.00, "quantity": 2 },
+                    { "name": "Spa", "unitPrice": 300.00 },
+                    { "name": "Dessert", "unitPrice": 100.00 }
+                ]
+            }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    let data = &json["data"];
+    assert_eq!(amount(data, "discountAmount"), 300.0);
+    assert_eq!(amount(data, "finalAmount"), 500.0);
+    assert_eq!(
+        data["benefitDescription"],
+        "Buy one, get one free: 2 free items"
+    );
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_free_upgrade_and_service_coupons_describe_their_benefit() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("benefit@example.com");
+    user.insert(app.db()).await.expect("Failed to insert user");
+    let client = app.authenticated_client(&user.id, &user.email);
+
+    let upgrade_qr = insert_typed_coupon(
+        app.db(),
+        user.id,
+        "UPGRADE1",
+        "free_upgrade",
+        Some("Deluxe to Junior Suite"),
+    )
+    .await;
+    let response = client
+        .post(
+            "/api/coupons/redeem",
+            &json!({ "qrCode": upgrade_qr, "originalAmount": 3000.00 }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    let data = &json["data"];
+    assert_eq!(amount(data, "discountAmount"), 0.0);
+    assert_eq!(amount(data, "finalAmount"), 3000.0);
+    assert_eq!(
+        data["benefitDescription"],
+        "Free upgrade: Deluxe to Junior Suite"
+    );
+
+    // Without a description the coupon's name describes it
+    let service_qr = insert_typed_coupon(app.db(), user.id, "SERVICE1", "free_service", None).await;
+    let response = client
+        .post(
+            "/api/coupons/calculate",
+            &json!({ "qrCode": service_qr, "originalAmount": 500.00 }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    let data = &json["data"];
+    assert_eq!(data["applicable"], true);
+    assert_eq!(amount(data, "discountAmount"), 0.0);
+    assert_eq!(data["benefitDescription"], "Free service: 0% Off");
+
+    // Monetary coupons carry no benefit description
+    let percentage = TestCoupon::percentage("PLAIN10", 10.0);
+    percentage
+        .insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+    let (_, percentage_qr) = insert_user_coupon(app.db(), user.id, percentage.id, "available")
+        .await
+        .expect("Failed to insert user coupon");
+    let response = client
+        .post(
+            "/api/coupons/redeem",
+            &json!({ "qrCode": percentage_qr, "originalAmount": 1000.00 }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(amount(&json["data"], "discountAmount"), 100.0);
+    assert!(json["data"].get("benefitDescription").is_none());
+
+    app.cleanup().await.ok();
+}
+
+// ============================================================================
+// Test: User Coupon QR Image
+// ============================================================================
+
+#[tokio::test]
+async fn test_user_coupon_qr_image_is_png_for_owner_only() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let owner = TestUser::new("qr-owner@example.com");
+    owner.insert(app.db()).await.expect("Failed to insert user");
+    let other = TestUser::new("qr-other@example.com");
+    other.insert(app.db()).await.expect("Failed to insert user");
+    let admin = TestUser::admin("qr-admin@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin");
+
+    let coupon = TestCoupon::percentage("QRIMAGE", 10.0);
+    coupon
+        .insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+    let (user_coupon_id, _) = insert_user_coupon(app.db(), owner.id, coupon.id, "available")
+        .await
+        .expect("Failed to insert user coupon");
+    let uri = format!("/api/coupons/user/{}/qr.png?size=128", user_coupon_id);
+
+    let response = app
+        .authenticated_client(&owner.id, &owner.email)
+        .get(&uri)
+        .await;
+    response.assert_status(200);
+    assert_eq!(
+        response
+            .headers
+            .get("content-type")
+            .and_then(|v| v.to_str().ok()),
+        Some("image/png")
+    );
+    assert!(
+        response.bytes.starts_with(b"\x89PNG\r\n\x1a\n"),
+        "Body should start with the PNG signature"
+    );
+
+    app.authenticated_client_with_role(&admin.id, &admin.email, "admin")
+        .get(&uri)
+        .await
+        .assert_status(200);
+    app.authenticated_client(&other.id, &other.email)
+        .get(&uri)
+        .await
+        .assert_status(403);
+    app.authenticated_client(&owner.id, &owner.email)
+        .get(&format!("/api/coupons/user/{}/qr.png", Uuid::new_v4()))
+        .await
+        .assert_status(404);
+
+    app.cleanup().await.ok();
+}
+
+// ============================================================================
+// Test: Coupon Expiry Sweep
+// ============================================================================
+
+#[tokio::test]
+async fn test_expiry_sweep_expires_and_exhausts_coupons() {
+    use loyalty_backend::services::coupon::{CouponService, CouponServiceImpl, CouponSweep};
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("sweep@example.com");
+    user.insert(app.db()).await.expect("Failed to insert user");
+
+    let mut past = TestCoupon::percentage("PASTDUE", 10.0);
+    past.valid_from = Some(Utc::now() - Duration::days(30));
+    past.valid_until = Some(Utc::now() - Duration::hours(1));
+    past.insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+    let (past_user_coupon, _) = insert_user_coupon(app.db(), user.id, past.id, "available")
+        .await
+        .expect("Failed to insert user coupon");
+
+    let used_up = TestCoupon::percentage("USEDUP", 10.0);
+    used_up
+        .insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+    sqlx::query("UPDATE coupons SET usage_limit = 2, used_count = 2 WHERE id = $1")
+        .bind(used_up.id)
+        .execute(app.db())
+        .await
+        .expect("Failed to set usage");
+    let (used_up_user_coupon, _) = insert_user_coupon(app.db(), user.id, used_up.id, "available")
+        .await
+        .expect("Failed to insert user coupon");
+
+    let current = TestCoupon::percentage("CURRENT", 10.0);
+    current
+        .insert(app.db())
+        .await
+        .expect("Failed to insert coupon");
+
+    let service = CouponServiceImpl::new(app.db().clone());
+    let sweep = service
+        .sweep_expired_coupons()
+        .await
+        .expect("Sweep should succeed");
+    assert_eq!(
+        sweep,
+        CouponSweep {
+            expired_coupons: 1,
+            exhausted_coupons: 1,
+            expired_user_coupons: 1,
+        }
+    );
+
+    let coupon_status = |id: Uuid| {
+        sqlx::query_scalar::<_, String>("SELECT status::text FROM coupons WHERE id = $1")
+            .bind(id)
+            .fetch_one(app.db())
+    };
+    assert_eq!(coupon_status(past.id).await.unwrap(), "expired");
+    assert_eq!(coupon_status(used_up.id).await.unwrap(), "exhausted");
+    assert_eq!(coupon_status(current.id).await.unwrap(), "active");
+
+    let user_coupon_status = |id: Uuid| {
+        sqlx::query_scalar::<_, String>("SELECT status::text FROM user_coupons WHERE id = $1")
+            .bind(id)
+            .fetch_one(app.db())
+    };
+    assert_eq!(
+        user_coupon_status(past_user_coupon).await.unwrap(),
+        "expired"
+    );
+    assert_eq!(
+        user_coupon_status(used_up_user_coupon).await.unwrap(),
+        "available"
+    );
+
+    // Nothing left to change on a second run
+    let sweep = service
+        .sweep_expired_coupons()
+        .await
+        .expect("Sweep should succeed");
+    assert_eq!(sweep, CouponSweep::default());
+
+    app.cleanup().await.ok();
+}
+
+// ============================================================================
+// Request-scoped transactions (used by the redeem handlers)
+// ============================================================================
+
+/// `transaction_layer` commits a `Tx` handler's writes on success, rolls
+/// them back on any `AppError` (even one returned after the writes), and
+/// leaves handlers without a `Tx` alone
+#[tokio::test]
+async fn test_request_transaction_commits_on_success_and_rolls_back_on_error() {
+    use axum::body::Body;
+    use axum::extract::Path;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::{get, post};
+    use axum::{middleware as axum_middleware, Router};
+    use loyalty_backend::error::AppError;
+    use loyalty_backend::middleware::transaction::{transaction_layer, Tx};
+    use tower::ServiceExt;
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let table = format!("tx_probe_{}", Uuid::new_v4().simple());
+    sqlx::query(&format!("CREATE TABLE {} (value TEXT NOT NULL)", table))
+        .execute(app.db())
+        .await
+        .expect("Failed to create probe table");
+
+    let insert = format!("INSERT INTO {} (value) VALUES ($1)", table);
+    let write = {
+        let insert = insert.clone();
+        move |mut tx: Tx, Path(value): Path<String>| {
+            let insert = insert.clone();
+            async move {
+                sqlx::query(&insert).bind(&value).execute(&mut *tx).await?;
+                if value == "fail" {
+                    return Err(AppError::Validation("rejected after writing".to_string()));
+                }
+                Ok::<_, AppError>("written")
+            }
+        }
+    };
+    let early_commit = move |tx: Tx| async move {
+        tx.commit().await?;
+        Ok::<_, AppError>("committed")
+    };
+
+    let state = loyalty_backend::AppState::new(
+        app.db().clone(),
+        app.redis(),
+        crate::common::test_app_state_config(),
+    );
+    let layered: Router<()> = Router::new()
+        .route("/write/:value", post(write.clone()))
+        .route("/commit", post(early_commit))
+        .route("/read", get(|| async { "no transaction" }))
+        .layer(axum_middleware::from_fn(transaction_layer))
+        .with_state(state.clone());
+    let unlayered: Router<()> = Router::new()
+        .route("/write/:value", post(write))
+        .with_state(state);
+
+    let send = |router: Router<()>, method: &str, uri: &str| {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        async move { router.oneshot(request).await.unwrap().status() }
+    };
+
+    assert_eq!(
+        send(layered.clone(), "POST", "/write/ok").await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        send(layered.clone(), "POST", "/write/fail").await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        send(layered.clone(), "POST", "/commit").await,
+        StatusCode::OK
+    );
+    assert_eq!(send(layered.clone(), "GET", "/read").await, StatusCode::OK);
+
+    // Without the layer nothing would commit, so `Tx` refuses to run
+    assert_eq!(
+        send(unlayered, "POST", "/write/orphan").await,
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+
+    let values: Vec<String> =
+        sqlx::query_scalar(&format!("SELECT value FROM {} ORDER BY value", table))
+            .fetch_all(app.db())
+            .await
+            .expect("Failed to read probe table");
+    assert_eq!(values, vec!["ok".to_string()]);
+
+    sqlx::query(&format!("DROP TABLE {}", table))
+        .execute(app.db())
+        .await
+        .ok();
+    app.cleanup().await.ok();
+}
@@ -13,6 +13,8 @@
 //! - `GET /api/health/db` - Database connectivity check
 //! - `GET /api/health/redis` - Redis connectivity check
 //! - `GET /api/health/full` - Full system health check (alias)
+//! - `GET /api/health/live` - Liveness probe (no dependency checks)
+//! - `GET /api/health/ready` - Readiness probe (database + redis)
 
 use serde_json::Value;
 
@@ -200,6 +202,35 @@ async fn test_health_endpoint_services_structure() {
     );
 }
 
+/// Test that the full health check reports pool and Redis metrics.
+#[tokio::test]
+async fn test_health_endpoint_reports_pool_and_redis_metrics() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = app.client();
+
+    let response = client.get("/api/health/full").await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+
+    let pool_size = json["db_pool_size"]
+        .as_u64()
+        .expect("db_pool_size should be a number");
+    let pool_idle = json["db_pool_idle"]
+        .as_u64()
+        .expect("db_pool_idle should be a number");
+    assert!(pool_size >= 1, "The health check itself used a connection");
+    assert!(pool_idle <= pool_size);
+
+    let latency = json["redis_latency_ms"]
+        .as_f64()
+        .expect("redis_latency_ms should be a number while Redis is up");
+    assert!(latency >= 0.0);
+
+    // The string statuses are still there for existing consumers
+    assert_eq!(json["services"]["database"], "healthy");
+    assert_eq!(json["services"]["redis"], "healthy");
+}
+
 // ============================================================================
 // Database Health Check Tests (/api/health/db)
 // ============================================================================
@@ -316,6 +347,107 @@ async fn test_health_redis_json_structure() {
     );
 }
 
+// ============================================================================
+// Liveness / Readiness Probe Tests (/api/health/live, /api/health/ready)
+// ============================================================================
+
+/// A Redis connection routed through a local proxy, and the proxy task.
+/// Aborting the task closes the proxy's port and every connection through
+/// it, so the connection manager is left pointing at a dead address.
+async fn proxied_redis() -> (redis::aio::ConnectionManager, tokio::task::JoinHandle<()>) {
+    let info = redis::Client::open(crate::common::test_redis_url())
+        .expect("Invalid test Redis URL")
+        .get_connection_info()
+        .clone();
+    let redis::ConnectionAddr::Tcp(host, port) = info.addr.clone() else {
+        panic!("Test Redis must be reachable over plain TCP");
+    };
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Failed to bind proxy port");
+    let proxy_port = listener.local_addr().unwrap().port();
+    let proxy = tokio::spawn(async move {
+        let mut connections = tokio::task::JoinSet::new();
+        while let Ok((mut inbound, _)) = listener.accept().await {
+            let upstream = format!("{}:{}", host, port);
+            connections.spawn(async move {
+                if let Ok(mut outbound) = tokio::net::TcpStream::connect(upstream).await {
+                    let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+                }
+            });
+        }
+    });
+
+    let proxied = redis::ConnectionInfo {
+        addr: redis::ConnectionAddr::Tcp("127.0.0.1".to_string(), proxy_port),
+        redis: info.redis,
+    };
+    let client = redis::Client::open(proxied).expect("Invalid proxied Redis address");
+    let connection = redis::aio::ConnectionManager::new(client)
+        .await
+        .expect("Failed to connect to Redis through the proxy");
+    (connection, proxy)
+}
+
+/// Test that the liveness probe answers 200 with no dependency checks.
+#[tokio::test]
+async fn test_health_live_returns_ok() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = app.client();
+
+    let response = client.get("/api/health/live").await;
+    response.assert_status(200);
+
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["status"], "alive");
+}
+
+/// Test that the readiness probe answers 200 when the database and Redis
+/// are both reachable.
+#[tokio::test]
+async fn test_health_ready_when_dependencies_up() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = app.client();
+
+    let response = client.get("/api/health/ready").await;
+    response.assert_status(200);
+
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["status"], "ready");
+    assert_eq!(json["database"], "connected");
+    assert_eq!(json["redis"], "connected");
+}
+
+/// Test that losing Redis fails readiness with a 503 while liveness stays
+/// 200.
+#[tokio::test]
+async fn test_health_ready_503_when_redis_down() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let (redis, proxy) = proxied_redis().await;
+    let state = loyalty_backend::AppState::new(
+        app.db().clone(),
+        redis,
+        crate::common::test_app_state_config(),
+    );
+    let client = TestClient::new(loyalty_backend::routes::create_router(state));
+
+    client.get("/api/health/ready").await.assert_status(200);
+
+    proxy.abort();
+    let _ = proxy.await;
+
+    let response = client.get("/api/health/ready").await;
+    response.assert_status(503);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["status"], "not_ready");
+    assert_eq!(json["database"], "connected");
+    assert_eq!(json["redis"], "disconnected");
+
+    client.get("/api/health/live").await.assert_status(200);
+}
+
 // ============================================================================
 // Error Scenario Tests
 // ============================================================================
@@ -2,15 +2,19 @@
 //!
 //! Tests for the /api/loyalty endpoints including:
 //! - Get loyalty status
-//! - Get transactions (paginated)
-//! - Get tier definitions
+//! - Get transactions (paginated, by page or cursor)
+//! - Get tier definitions, including the Redis tiers cache
 //! - Award points (admin only)
-//! - Tier recalculation
+//! - Concurrent deductions (`LoyaltyService::award_points_atomic`)
+//! - Tier recalculation, including the downgrade grace period and the
+//!   bulk `admin/recalculate-all` job
+//! - The opt-in leaderboard
+//! - Points redemption at tier rates
 
 use serde_json::{json, Value};
 use uuid::Uuid;
 
-use crate::common::{TestApp, TestUser};
+use crate::common::{TestApp, TestClient, TestUser};
 
 // ============================================================================
 // Test Setup Helpers
@@ -240,6 +244,13 @@ async fn test_get_transactions_pagination() {
         "Total pages should be 3"
     );
 
+    let pagination = json
+        .get("pagination")
+        .expect("Response should have 'pagination' field");
+    assert_eq!(pagination.get("page").and_then(|v| v.as_i64()), Some(2));
+    assert_eq!(pagination.get("limit").and_then(|v| v.as_i64()), Some(10));
+    assert_eq!(pagination.get("total").and_then(|v| v.as_i64()), Some(25));
+
     let transactions = data.get("transactions").unwrap().as_array().unwrap();
     assert_eq!(
         transactions.len(),
@@ -250,6 +261,106 @@ async fn test_get_transactions_pagination() {
     app.cleanup().await.ok();
 }
 
+#[tokio::test]
+async fn test_get_transactions_cursor_pagination_with_tied_timestamps() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("transactions_cursor@example.com");
+    let user_id = insert_user_with_loyalty(app.db(), &user, 1000, 10)
+        .await
+        .expect("Failed to insert user with loyalty");
+
+    // 50 transactions sharing one timestamp: only the id breaks the tie.
+    let created_at = chrono::Utc::now() - chrono::Duration::hours(1);
+    let mut inserted: Vec<String> = Vec::new();
+    for i in 0..50 {
+        let (id,): (Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO points_transactions (user_id, points, type, description, created_at)
+            VALUES ($1, $2, 'earned_stay'::points_transaction_type, $3, $4)
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(10 * (i + 1))
+        .bind(format!("Tied transaction {}", i + 1))
+        .bind(created_at)
+        .fetch_one(app.db())
+        .await
+        .expect("Failed to insert transaction");
+        inserted.push(id.to_string());
+    }
+
+    let client = app.authenticated_client(&user_id, &user.email);
+    let mut seen: Vec<String> = Vec::new();
+    let mut cursor: Option<String> = None;
+    let mut pages = 0;
+
+    loop {
+        let path = match &cursor {
+            Some(cursor) => format!("/api/loyalty/transactions?limit=7&cursor={}", cursor),
+            None => "/api/loyalty/transactions?limit=7".to_string(),
+        };
+        let response = client.get(&path).await;
+        response.assert_status(200);
+
+        let json: Value = response.json().expect("Response should be valid JSON");
+        let data = json.get("data").expect("Response should have 'data' field");
+        assert_eq!(data.get("total").and_then(|v| v.as_i64()), Some(50));
+
+        for transaction in data.get("transactions").unwrap().as_array().unwrap() {
+            seen.push(transaction["id"].as_str().unwrap().to_string());
+        }
+
+        pages += 1;
+        assert!(pages <= 8, "Cursor pagination should finish in 8 pages");
+
+        match data.get("next_cursor").and_then(|v| v.as_str()) {
+            Some(next) => cursor = Some(next.to_string()),
+            None => break,
+        }
+    }
+
+    assert_eq!(pages, 8, "50 transactions at 7 per page is 8 pages");
+    assert_eq!(
+        seen.len(),
+        50,
+        "No transaction should be skipped or repeated"
+    );
+
+    let mut unique = seen.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), 50, "No transaction should appear twice");
+
+    inserted.sort();
+    assert_eq!(
+        unique, inserted,
+        "Every inserted transaction should be returned"
+    );
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_get_transactions_rejects_invalid_cursor() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("transactions_bad_cursor@example.com");
+    let user_id = insert_user_with_loyalty(app.db(), &user, 0, 0)
+        .await
+        .expect("Failed to insert user with loyalty");
+
+    let client = app.authenticated_client(&user_id, &user.email);
+    let response = client
+        .get("/api/loyalty/transactions?cursor=not-a-cursor")
+        .await;
+
+    response.assert_status(400);
+
+    app.cleanup().await.ok();
+}
+
 // ============================================================================
 // Test: GET /api/loyalty/tiers
 // ============================================================================
@@ -310,6 +421,498 @@ async fn test_get_tiers() {
     app.cleanup().await.ok();
 }
 
+#[tokio::test]
+async fn test_get_tiers_served_from_cache_until_invalidated() {
+    use redis::AsyncCommands;
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = app.client();
+
+    let admin = TestUser::admin("tier_cache_admin@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin");
+    let admin_client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+
+    let tier_names = |json: Value| -> Vec<String> {
+        json["data"]
+            .as_array()
+            .expect("Data should be an array of tiers")
+            .iter()
+            .filter_map(|t| t["name"].as_str().map(str::to_string))
+            .collect()
+    };
+
+    let mut redis = app.redis();
+    let _: () = redis
+        .del("loyalty:tiers:active")
+        .await
+        .expect("Failed to clear tiers cache");
+
+    // First read populates the cache
+    let response = client.get("/api/loyalty/tiers").await;
+    response.assert_status(200);
+    let names = tier_names(response.json().expect("Response should be valid JSON"));
+    assert!(names.contains(&"Gold".to_string()));
+    let ttl: i64 = redis
+        .ttl("loyalty:tiers:active")
+        .await
+        .expect("Failed to read cache TTL");
+    assert!(
+        ttl > 0 && ttl <= 300,
+        "Cache entry should expire, got TTL {}",
+        ttl
+    );
+
+    // A change made behind the API's back isn't seen: the list comes from Redis
+    sqlx::query("UPDATE tiers SET name = 'Gilded' WHERE name = 'Gold'")
+        .execute(app.db())
+        .await
+        .expect("Failed to rename tier");
+    let response = client.get("/api/loyalty/tiers").await;
+    response.assert_status(200);
+    let names = tier_names(response.json().expect("Response should be valid JSON"));
+    assert!(
+        names.contains(&"Gold".to_string()),
+        "Expected the cached list"
+    );
+    assert!(!names.contains(&"Gilded".to_string()));
+
+    // Any admin tier change drops the entry
+    let gold_id: Uuid = sqlx::query_scalar("SELECT id FROM tiers WHERE name = 'Gilded'")
+        .fetch_one(app.db())
+        .await
+        .expect("Renamed tier should exist");
+    let response = admin_client
+        .put(
+            &format!("/api/loyalty/admin/tiers/{}", gold_id),
+            &json!({ "color": "#D4AF37" }),
+        )
+        .await;
+    response.assert_status(200);
+
+    let response = client.get("/api/loyalty/tiers").await;
+    response.assert_status(200);
+    let names = tier_names(response.json().expect("Response should be valid JSON"));
+    assert!(
+        names.contains(&"Gilded".to_string()),
+        "Expected a fresh list"
+    );
+
+    let _: () = redis
+        .del("loyalty:tiers:active")
+        .await
+        .expect("Failed to clear tiers cache");
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_tiers_response_cache_hit_and_invalidated_by_tier_write() {
+    use redis::AsyncCommands;
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    // Response cache on for tiers, the service-level tiers cache off so
+    // every miss reaches the database
+    let mut config = crate::common::test_app_state_config();
+    config.http_cache.response_cache_ttls = "tiers=60".to_string();
+    config.loyalty.tiers_cache_ttl_secs = 0;
+    let state = loyalty_backend::AppState::new(app.db().clone(), app.redis(), config);
+    let router = loyalty_backend::routes::create_router(state);
+    let client = TestClient::new(router.clone());
+
+    let admin = TestUser::admin("tier_response_cache_admin@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin");
+    let admin_client = TestClient::new(router).with_auth(
+        &crate::common::generate_test_token_with_role(&admin.id, &admin.email, "admin"),
+    );
+
+    let mut redis = app.redis();
+    let _: () = redis
+        .del("response_cache:tiers")
+        .await
+        .expect("Failed to clear response cache");
+
+    let x_cache = |response: &crate::common::TestResponse| {
+        response
+            .headers
+            .get("x-cache")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+
+    let response = client.get("/api/loyalty/tiers").await;
+    response.assert_status(200);
+    assert_eq!(x_cache(&response).as_deref(), Some("MISS"));
+
+    // A change behind the API's back isn't seen until the entry goes
+    sqlx::query("UPDATE tiers SET name = 'Gilded' WHERE name = 'Gold'")
+        .execute(app.db())
+        .await
+        .expect("Failed to rename tier");
+    let response = client.get("/api/loyalty/tiers").await;
+    response.assert_status(200);
+    assert_eq!(x_cache(&response).as_deref(), Some("HIT"));
+    assert!(
+        !response.body.contains("Gilded"),
+        "Expected the cached list"
+    );
+
+    // A tier write through the admin API invalidates the scope
+    let gold_id: Uuid = sqlx::query_scalar("SELECT id FROM tiers WHERE name = 'Gilded'")
+        .fetch_one(app.db())
+        .await
+        .expect("Renamed tier should exist");
+    admin_client
+        .put(
+            &format!("/api/loyalty/admin/tiers/{}", gold_id),
+            &json!({ "color": "#D4AF37" }),
+        )
+        .await
+        .assert_status(200);
+
+    let response = client.get("/api/loyalty/tiers").await;
+    response.assert_status(200);
+    assert_eq!(x_cache(&response).as_deref(), Some("MISS"));
+    assert!(response.body.contains("Gilded"), "Expected a fresh list");
+
+    let _: () = redis
+        .del("response_cache:tiers")
+        .await
+        .expect("Failed to clear response cache");
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_response_cache_off_unless_scope_configured() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+    let client = app.client();
+
+    let response = client.get("/api/loyalty/tiers").await;
+    response.assert_status(200);
+    assert!(
+        response.headers.get("x-cache").is_none(),
+        "the cache is off unless RESPONSE_CACHE_TTLS names the scope"
+    );
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_admin_get_tier_by_id() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let admin = TestUser::admin("tier_admin@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin");
+    let admin_client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+
+    let gold_id: Uuid = sqlx::query_scalar("SELECT id FROM tiers WHERE name = 'Gold'")
+        .fetch_one(app.db())
+        .await
+        .expect("Gold tier should be seeded");
+    let retired_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO tiers (name, min_points, min_nights, color, sort_order, is_active)
+        VALUES ('Retired', 0, 500, '#777777', 99, false)
+        RETURNING id
+        "#,
+    )
+    .fetch_one(app.db())
+    .await
+    .expect("Failed to insert inactive tier");
+
+    let response = admin_client
+        .get(&format!("/api/loyalty/tiers/{}", gold_id))
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["data"]["id"], gold_id.to_string());
+    assert_eq!(json["data"]["name"], "Gold");
+
+    // Inactive tiers are hidden from the list but returned by id
+    let response = admin_client
+        .get(&format!("/api/loyalty/tiers/{}", retired_id))
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["data"]["name"], "Retired");
+    assert_eq!(json["data"]["is_active"], false);
+
+    let response = admin_client
+        .get(&format!("/api/loyalty/tiers/{}", Uuid::new_v4()))
+        .await;
+    response.assert_status(404);
+
+    // Members can't look tiers up by id
+    let member = TestUser::new("tier_member@example.com");
+    member
+        .insert(app.db())
+        .await
+        .expect("Failed to insert member");
+    let response = app
+        .authenticated_client(&member.id, &member.email)
+        .get(&format!("/api/loyalty/tiers/{}", gold_id))
+        .await;
+    response.assert_status(403);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_admin_deactivate_tier_reassigns_members() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let admin = TestUser::admin("tier_deactivate_admin@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin");
+    let admin_client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+
+    let gold_member = TestUser::new("tier_deactivate_gold@example.com");
+    insert_user_with_loyalty(app.db(), &gold_member, 0, 12)
+        .await
+        .expect("Failed to insert Gold member");
+    let platinum_member = TestUser::new("tier_deactivate_platinum@example.com");
+    insert_user_with_loyalty(app.db(), &platinum_member, 0, 25)
+        .await
+        .expect("Failed to insert Platinum member");
+
+    // A downgrade pending on the deactivated tier doesn't keep the member there
+    sqlx::query(
+        "UPDATE user_loyalty SET tier_downgrade_eligible_at = NOW() + INTERVAL '30 days' WHERE user_id = $1",
+    )
+    .bind(gold_member.id)
+    .execute(app.db())
+    .await
+    .expect("Failed to schedule downgrade");
+
+    let gold_id: Uuid = sqlx::query_scalar("SELECT id FROM tiers WHERE name = 'Gold'")
+        .fetch_one(app.db())
+        .await
+        .expect("Gold tier should be seeded");
+
+    let response = admin_client
+        .delete(&format!("/api/loyalty/admin/tiers/{}", gold_id))
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["data"]["tier"]["is_active"], false);
+    assert_eq!(json["data"]["reassigned_members"], 1);
+
+    let tier_name_of = |user_id: Uuid| {
+        let pool = app.db().clone();
+        async move {
+            sqlx::query_scalar::<_, String>(
+                "SELECT t.name FROM user_loyalty ul JOIN tiers t ON t.id = ul.tier_id WHERE ul.user_id = $1",
+            )
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .expect("Member should have a tier")
+        }
+    };
+    assert_eq!(tier_name_of(gold_member.id).await, "Silver");
+    assert_eq!(tier_name_of(platinum_member.id).await, "Platinum");
+
+    // Platinum closes the gap so the `sort_order + 1` lookups still reach it
+    let platinum_order: i32 =
+        sqlx::query_scalar("SELECT sort_order FROM tiers WHERE name = 'Platinum'")
+            .fetch_one(app.db())
+            .await
+            .expect("Platinum tier should be seeded");
+    assert_eq!(platinum_order, 3);
+
+    let response = app
+        .authenticated_client(&gold_member.id, &gold_member.email)
+        .get("/api/loyalty/status")
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["data"]["tier"]["name"], "Silver");
+    assert_eq!(json["data"]["next_tier"]["name"], "Platinum");
+
+    // Deactivating through PUT takes the same path
+    let silver_id: Uuid = sqlx::query_scalar("SELECT id FROM tiers WHERE name = 'Silver'")
+        .fetch_one(app.db())
+        .await
+        .expect("Silver tier should be seeded");
+    let response = admin_client
+        .put(
+            &format!("/api/loyalty/admin/tiers/{}", silver_id),
+            &json!({ "is_active": false }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["data"]["reassigned_members"], 1);
+    assert_eq!(tier_name_of(gold_member.id).await, "Bronze");
+
+    // Deactivating again is a no-op
+    let response = admin_client
+        .delete(&format!("/api/loyalty/admin/tiers/{}", gold_id))
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["data"]["reassigned_members"], 0);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_admin_tier_create_and_update_keep_ladder_valid() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let admin = TestUser::admin("tier_ladder_admin@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin");
+    let admin_client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+
+    // Sort order 3 is Gold's
+    let response = admin_client
+        .post(
+            "/api/loyalty/admin/tiers",
+            &json!({ "name": "Diamond", "min_nights": 50, "color": "#B9F2FF", "sort_order": 3 }),
+        )
+        .await;
+    response.assert_status(409);
+
+    // Above Platinum, but needing fewer nights
+    let response = admin_client
+        .post(
+            "/api/loyalty/admin/tiers",
+            &json!({ "name": "Diamond", "min_nights": 15, "color": "#B9F2FF", "sort_order": 5 }),
+        )
+        .await;
+    response.assert_status(409);
+
+    let response = admin_client
+        .post(
+            "/api/loyalty/admin/tiers",
+            &json!({ "name": "Diamond", "min_nights": 50, "color": "#B9F2FF", "sort_order": 5 }),
+        )
+        .await;
+    response.assert_status(201);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["data"]["name"], "Diamond");
+    assert_eq!(json["data"]["is_active"], true);
+    let diamond_id = json["data"]["id"].as_str().unwrap().to_string();
+
+    let response = admin_client
+        .post(
+            "/api/loyalty/admin/tiers",
+            &json!({ "name": "Diamond", "min_nights": 80, "color": "#B9F2FF", "sort_order": 6 }),
+        )
+        .await;
+    response.assert_status(409);
+
+    let response = admin_client
+        .put(
+            &format!("/api/loyalty/admin/tiers/{}", diamond_id),
+            &json!({ "min_nights": 20 }),
+        )
+        .await;
+    response.assert_status(409);
+
+    let response = admin_client
+        .put(
+            &format!("/api/loyalty/admin/tiers/{}", diamond_id),
+            &json!({ "min_nights": 40, "color": "#A0E0FF" }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["data"]["tier"]["min_nights"], 40);
+    assert_eq!(json["data"]["tier"]["color"], "#A0E0FF");
+    assert_eq!(json["data"]["tier"]["name"], "Diamond");
+
+    // Members can't manage tiers
+    let member = TestUser::new("tier_ladder_member@example.com");
+    member
+        .insert(app.db())
+        .await
+        .expect("Failed to insert member");
+    let response = app
+        .authenticated_client(&member.id, &member.email)
+        .delete(&format!("/api/loyalty/admin/tiers/{}", diamond_id))
+        .await;
+    response.assert_status(403);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_admin_users_progress_is_capped() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let admin = TestUser::admin("progress_admin@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin");
+    let admin_client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+
+    // 30 nights but still on Silver (e.g. during a grace period): three
+    // times what Gold needs
+    let over = TestUser::new("progress_over_threshold@example.com");
+    insert_user_with_loyalty(app.db(), &over, 0, 30)
+        .await
+        .expect("Failed to insert member");
+    sqlx::query(
+        "UPDATE user_loyalty SET tier_id = (SELECT id FROM tiers WHERE name = 'Silver') WHERE user_id = $1",
+    )
+    .bind(over.id)
+    .execute(app.db())
+    .await
+    .expect("Failed to pin member to Silver");
+
+    // A next tier needing 0 nights used to divide by zero
+    let bronze = TestUser::new("progress_zero_threshold@example.com");
+    insert_user_with_loyalty(app.db(), &bronze, 0, 0)
+        .await
+        .expect("Failed to insert member");
+    sqlx::query("UPDATE tiers SET min_nights = 0 WHERE name = 'Silver'")
+        .execute(app.db())
+        .await
+        .expect("Failed to lower Silver threshold");
+
+    for path in [
+        "/api/loyalty/admin/users",
+        "/api/loyalty/admin/users?search=progress_",
+    ] {
+        let response = admin_client.get(path).await;
+        response.assert_status(200);
+        let json: Value = response.json().expect("Response should be valid JSON");
+        let users = json["data"]["users"].as_array().expect("users array");
+        let find = |id: Uuid| {
+            users
+                .iter()
+                .find(|u| u["user_id"] == id.to_string())
+                .unwrap_or_else(|| panic!("{} missing from {}", id, path))
+        };
+
+        let over_row = find(over.id);
+        assert_eq!(over_row["next_tier_name"], "Gold");
+        assert_eq!(over_row["progress_percentage"], 100.0);
+        assert_eq!(over_row["nights_to_next_tier"], 0);
+
+        let bronze_row = find(bronze.id);
+        assert_eq!(bronze_row["next_tier_name"], "Silver");
+        assert_eq!(bronze_row["progress_percentage"], 100.0);
+    }
+
+    app.cleanup().await.ok();
+}
+
 // ============================================================================
 // Test: POST /api/loyalty/award (Admin Only)
 // ============================================================================
@@ -489,16 +1092,233 @@ async fn test_award_points_idempotent_on_same_key() {
     app.cleanup().await.ok();
 }
 
-// ============================================================================
-// Test: POST /api/loyalty/award - Non-Admin Fails
-// ============================================================================
-
+/// A PMS retrying `POST /api/loyalty/admin/award-spending-with-nights`
+/// with the same `Idempotency-Key` gets the original result back instead
+/// of a second stay award.
 #[tokio::test]
-async fn test_award_points_non_admin_fails() {
+async fn test_award_spending_with_nights_replays_same_key() {
     let app = TestApp::new().await.expect("Failed to create test app");
 
-    let regular_user = TestUser::new("regular_user@example.com");
-    let regular_user_id = insert_user_with_loyalty(app.db(), &regular_user, 100, 2)
+    let admin = TestUser::admin("admin_spend_idem@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin user");
+
+    let target = TestUser::new("target_spend_idem@example.com");
+    let target_id = insert_user_with_loyalty(app.db(), &target, 0, 0)
+        .await
+        .expect("Failed to insert target user");
+
+    let client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+    let key = Uuid::new_v4().to_string();
+    let payload = json!({
+        "userId": target_id.to_string(),
+        "amountSpent": 100.0,
+        "nightsStayed": 2,
+        "referenceId": "PMS-IDEM-1"
+    });
+
+    let mut transaction_ids = Vec::new();
+    for _ in 0..2 {
+        let response = client
+            .post_with_headers(
+                "/api/loyalty/admin/award-spending-with-nights",
+                &payload,
+                &[("Idempotency-Key", key.as_str())],
+            )
+            .await;
+        response.assert_status(200);
+        let json: Value = response.json().expect("Response should be valid JSON");
+        transaction_ids.push(
+            json.pointer("/data/transactionId")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        );
+    }
+    assert!(transaction_ids[0].is_some());
+    assert_eq!(
+        transaction_ids[0], transaction_ids[1],
+        "The replay must reference the original transaction"
+    );
+
+    let txn_count: (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM points_transactions WHERE user_id = $1")
+            .bind(target_id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to count transactions");
+    assert_eq!(
+        txn_count.0, 1,
+        "The retried award must only be applied once"
+    );
+
+    let total_nights: (i32,) =
+        sqlx::query_as("SELECT total_nights FROM user_loyalty WHERE user_id = $1")
+            .bind(target_id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to fetch loyalty");
+    assert_eq!(total_nights.0, 2);
+
+    app.cleanup().await.ok();
+}
+
+/// `POST /api/loyalty/admin/award-preview` projects exactly what the
+/// award would do, tier change included, and writes nothing.
+#[tokio::test]
+async fn test_award_preview_matches_award_without_writing() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let admin = TestUser::admin("admin_award_preview@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin user");
+
+    let target = TestUser::new("target_award_preview@example.com");
+    let target_id = insert_user_with_loyalty(app.db(), &target, 0, 0)
+        .await
+        .expect("Failed to insert target user");
+
+    let client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+    let payload = json!({
+        "userId": target_id.to_string(),
+        "amountSpent": 250.0,
+        "nightsStayed": 2
+    });
+
+    let response = client
+        .post("/api/loyalty/admin/award-preview", &payload)
+        .await;
+    response.assert_status(200);
+    let preview: Value = response.json().expect("Response should be valid JSON");
+    let preview = &preview["data"];
+    assert_eq!(preview["currentPoints"], 0);
+    assert_eq!(preview["currentTotalNights"], 0);
+    assert_eq!(preview["projectedTotalNights"], 2);
+    assert_eq!(preview["projectedTierName"], "Silver");
+    assert_eq!(preview["tierChanged"], true);
+    assert_eq!(preview["projectedPoints"], preview["pointsEarned"]);
+    assert!(preview["pointsEarned"].as_i64().unwrap_or(0) > 0);
+
+    let txn_count: (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM points_transactions WHERE user_id = $1")
+            .bind(target_id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to count transactions");
+    assert_eq!(txn_count.0, 0, "The preview must not write a transaction");
+
+    let total_nights: (i32,) =
+        sqlx::query_as("SELECT total_nights FROM user_loyalty WHERE user_id = $1")
+            .bind(target_id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to fetch loyalty");
+    assert_eq!(total_nights.0, 0);
+
+    // Submitting the same body gives what was projected
+    let response = client
+        .post("/api/loyalty/admin/award-spending-with-nights", &payload)
+        .await;
+    response.assert_status(200);
+    let award: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(award["data"]["pointsEarned"], preview["pointsEarned"]);
+    assert_eq!(
+        award["data"]["newTotalNights"],
+        preview["projectedTotalNights"]
+    );
+    assert_eq!(award["data"]["newTierName"], preview["projectedTierName"]);
+
+    app.cleanup().await.ok();
+}
+
+/// The preview is admin-only, like the award it projects
+#[tokio::test]
+async fn test_award_preview_requires_admin() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("customer_award_preview@example.com");
+    let user_id = insert_user_with_loyalty(app.db(), &user, 0, 0)
+        .await
+        .expect("Failed to insert user");
+
+    let response = app
+        .authenticated_client(&user.id, &user.email)
+        .post(
+            "/api/loyalty/admin/award-preview",
+            &json!({ "userId": user_id.to_string(), "amountSpent": 100.0 }),
+        )
+        .await;
+    response.assert_status(403);
+
+    app.cleanup().await.ok();
+}
+
+/// Idempotency keys are scoped per endpoint: the same key on the award
+/// and deduct endpoints runs both, while repeating either is a replay.
+#[tokio::test]
+async fn test_admin_points_idempotency_scoped_per_endpoint() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let admin = TestUser::admin("admin_scope_idem@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin user");
+
+    let target = TestUser::new("target_scope_idem@example.com");
+    let target_id = insert_user_with_loyalty(app.db(), &target, 1000, 0)
+        .await
+        .expect("Failed to insert target user");
+
+    let client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+    let key = Uuid::new_v4().to_string();
+    let award = json!({ "userId": target_id.to_string(), "points": 300 });
+    let deduct = json!({ "userId": target_id.to_string(), "points": 100, "reason": "Correction" });
+
+    for (path, payload) in [
+        ("/api/loyalty/admin/award-points", &award),
+        ("/api/loyalty/admin/award-points", &award),
+        ("/api/loyalty/admin/deduct-points", &deduct),
+        ("/api/loyalty/admin/deduct-points", &deduct),
+    ] {
+        client
+            .post_with_headers(path, payload, &[("Idempotency-Key", key.as_str())])
+            .await
+            .assert_status(200);
+    }
+
+    let txn_count: (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM points_transactions WHERE user_id = $1")
+            .bind(target_id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to count transactions");
+    assert_eq!(txn_count.0, 2, "One award and one deduction");
+
+    let points: (i32,) =
+        sqlx::query_as("SELECT current_points FROM user_loyalty WHERE user_id = $1")
+            .bind(target_id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to fetch loyalty");
+    assert_eq!(points.0, 1200);
+
+    app.cleanup().await.ok();
+}
+
+// ============================================================================
+// Test: POST /api/loyalty/award - Non-Admin Fails
+// ============================================================================
+
+#[tokio::test]
+async fn test_award_points_non_admin_fails() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let regular_user = TestUser::new("regular_user@example.com");
+    let regular_user_id = insert_user_with_loyalty(app.db(), &regular_user, 100, 2)
         .await
         .expect("Failed to insert regular user");
 
@@ -539,6 +1359,82 @@ async fn test_award_points_non_admin_fails() {
     app.cleanup().await.ok();
 }
 
+#[tokio::test]
+async fn test_concurrent_deductions_never_overdraw() {
+    use loyalty_backend::error::AppError;
+    use loyalty_backend::services::loyalty::{
+        LoyaltyService, LoyaltyServiceImpl, PointsAdjustmentParams, PointsTransactionType,
+    };
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let member = TestUser::new("concurrent_deduct@example.com");
+    let member_id = insert_user_with_loyalty(app.db(), &member, 100, 0)
+        .await
+        .expect("Failed to insert member");
+
+    let deduct = |points: i32| {
+        let service = LoyaltyServiceImpl::new(app.db().clone());
+        async move {
+            service
+                .award_points_atomic(PointsAdjustmentParams {
+                    user_id: member_id,
+                    points: -points,
+                    transaction_type: PointsTransactionType::AdminDeduction,
+                    description: "Concurrent deduction".to_string(),
+                    reference_id: None,
+                    admin_user_id: None,
+                    admin_reason: None,
+                })
+                .await
+        }
+    };
+
+    // Either deduction fits the balance on its own, but not both.
+    let (first, second) = tokio::join!(deduct(60), deduct(60));
+    let results = [first, second];
+
+    let succeeded: Vec<_> = results.iter().filter_map(|r| r.as_ref().ok()).collect();
+    assert_eq!(succeeded.len(), 1, "Exactly one deduction should succeed");
+    assert_eq!(succeeded[0].new_points_balance, 40);
+    assert!(
+        results
+            .iter()
+            .any(|r| matches!(r, Err(AppError::Validation(msg)) if msg.contains("Insufficient"))),
+        "The other deduction should be rejected for insufficient points"
+    );
+
+    // Two deductions that fit together both apply; neither is lost.
+    let (first, second) = tokio::join!(deduct(15), deduct(25));
+    first.expect("First deduction should succeed");
+    second.expect("Second deduction should succeed");
+
+    let balance: (i32,) =
+        sqlx::query_as("SELECT current_points FROM user_loyalty WHERE user_id = $1")
+            .bind(member_id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to fetch loyalty");
+    assert_eq!(
+        balance.0, 0,
+        "Balance should reflect every applied deduction"
+    );
+
+    let recorded: (i64,) = sqlx::query_as(
+        "SELECT COALESCE(SUM(points), 0)::bigint FROM points_transactions WHERE user_id = $1",
+    )
+    .bind(member_id)
+    .fetch_one(app.db())
+    .await
+    .expect("Failed to sum transactions");
+    assert_eq!(
+        recorded.0, -100,
+        "Only applied deductions should be recorded"
+    );
+
+    app.cleanup().await.ok();
+}
+
 // ============================================================================
 // Test: Tier Recalculation
 // ============================================================================
@@ -695,3 +1591,710 @@ async fn test_tier_recalculation_no_change() {
 
     app.cleanup().await.ok();
 }
+
+/// Current tier name and pending downgrade for a member
+async fn tier_state(
+    pool: &sqlx::PgPool,
+    user_id: Uuid,
+) -> (Option<String>, Option<chrono::DateTime<chrono::Utc>>) {
+    sqlx::query_as(
+        r#"
+        SELECT t.name, ul.tier_downgrade_eligible_at
+        FROM user_loyalty ul
+        LEFT JOIN tiers t ON ul.tier_id = t.id
+        WHERE ul.user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to fetch tier state")
+}
+
+#[tokio::test]
+async fn test_tier_downgrade_held_within_grace_period() {
+    use loyalty_backend::services::loyalty::recalculate_tier_with_grace;
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    // 12 nights: Gold (10+)
+    let member = TestUser::new("grace_within@example.com");
+    let member_id = insert_user_with_loyalty(app.db(), &member, 0, 12)
+        .await
+        .expect("Failed to insert member");
+
+    // Drop to 5 nights (Silver range)
+    sqlx::query("UPDATE user_loyalty SET total_nights = 5 WHERE user_id = $1")
+        .bind(member_id)
+        .execute(app.db())
+        .await
+        .expect("Failed to deduct nights");
+
+    let mut conn = app
+        .db()
+        .acquire()
+        .await
+        .expect("Failed to acquire connection");
+    let recalculation = recalculate_tier_with_grace(&mut conn, member_id, 30)
+        .await
+        .expect("Recalculation should succeed");
+
+    assert!(!recalculation.tier_changed, "Downgrade should be deferred");
+    assert_eq!(recalculation.tier_name.as_deref(), Some("Gold"));
+    let eligible_at = recalculation
+        .downgrade_eligible_at
+        .expect("Downgrade should be scheduled");
+    let days_out = (eligible_at - chrono::Utc::now()).num_days();
+    assert!(
+        (29..=30).contains(&days_out),
+        "Expected ~30 days, got {}",
+        days_out
+    );
+
+    // Recalculating again within the grace period keeps the tier and the
+    // original schedule, even for callers that pass no grace period.
+    let again = recalculate_tier_with_grace(&mut conn, member_id, 0)
+        .await
+        .expect("Recalculation should succeed");
+    assert!(!again.tier_changed);
+    assert_eq!(again.tier_name.as_deref(), Some("Gold"));
+    assert_eq!(again.downgrade_eligible_at, Some(eligible_at));
+
+    // Earning the tier back cancels the pending downgrade.
+    sqlx::query("UPDATE user_loyalty SET total_nights = 10 WHERE user_id = $1")
+        .bind(member_id)
+        .execute(app.db())
+        .await
+        .expect("Failed to award nights");
+    let recovered = recalculate_tier_with_grace(&mut conn, member_id, 30)
+        .await
+        .expect("Recalculation should succeed");
+    assert_eq!(recovered.tier_name.as_deref(), Some("Gold"));
+    assert_eq!(recovered.downgrade_eligible_at, None);
+
+    assert_eq!(
+        tier_state(app.db(), member_id).await,
+        (Some("Gold".to_string()), None)
+    );
+
+    drop(conn);
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_tier_downgrade_applied_after_grace_period() {
+    use loyalty_backend::services::loyalty::{
+        apply_due_tier_downgrades, recalculate_tier_with_grace,
+    };
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let member = TestUser::new("grace_past@example.com");
+    let member_id = insert_user_with_loyalty(app.db(), &member, 0, 12)
+        .await
+        .expect("Failed to insert member");
+
+    sqlx::query("UPDATE user_loyalty SET total_nights = 5 WHERE user_id = $1")
+        .bind(member_id)
+        .execute(app.db())
+        .await
+        .expect("Failed to deduct nights");
+
+    let mut conn = app
+        .db()
+        .acquire()
+        .await
+        .expect("Failed to acquire connection");
+    recalculate_tier_with_grace(&mut conn, member_id, 30)
+        .await
+        .expect("Recalculation should succeed");
+    drop(conn);
+
+    // Nothing is due yet
+    assert_eq!(apply_due_tier_downgrades(app.db()).await.unwrap(), 0);
+    assert_eq!(
+        tier_state(app.db(), member_id).await.0.as_deref(),
+        Some("Gold")
+    );
+
+    // Let the grace period run out
+    sqlx::query(
+        "UPDATE user_loyalty SET tier_downgrade_eligible_at = NOW() - INTERVAL '1 minute' WHERE user_id = $1",
+    )
+    .bind(member_id)
+    .execute(app.db())
+    .await
+    .expect("Failed to expire grace period");
+
+    assert_eq!(apply_due_tier_downgrades(app.db()).await.unwrap(), 1);
+    assert_eq!(
+        tier_state(app.db(), member_id).await,
+        (Some("Silver".to_string()), None)
+    );
+
+    let audited: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM user_audit_log WHERE user_id = $1 AND action = 'tier_downgrade_by_nights'",
+    )
+    .bind(member_id)
+    .fetch_one(app.db())
+    .await
+    .expect("Failed to count audit rows");
+    assert_eq!(audited.0, 1, "The downgrade should be audited");
+
+    // Without a grace period the downgrade is immediate
+    sqlx::query("UPDATE user_loyalty SET total_nights = 0 WHERE user_id = $1")
+        .bind(member_id)
+        .execute(app.db())
+        .await
+        .expect("Failed to deduct nights");
+    let mut conn = app
+        .db()
+        .acquire()
+        .await
+        .expect("Failed to acquire connection");
+    let immediate = recalculate_tier_with_grace(&mut conn, member_id, 0)
+        .await
+        .expect("Recalculation should succeed");
+    assert!(immediate.tier_changed);
+    assert_eq!(immediate.tier_name.as_deref(), Some("Bronze"));
+    drop(conn);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_spend_points_use_earning_rule_tier_multiplier() {
+    use loyalty_backend::services::loyalty::calculate_spend_points;
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    sqlx::query(
+        r#"
+        INSERT INTO points_earning_rules (name, points_per_unit, unit_type, multiplier_by_tier)
+        VALUES ('Standard Earning', 10.00, 'currency', '{"Bronze": 1.0, "Gold": 1.5}')
+        "#,
+    )
+    .execute(app.db())
+    .await
+    .expect("Failed to insert earning rule");
+
+    let bronze = TestUser::new("spend_bronze@example.com");
+    let bronze_id = insert_user_with_loyalty(app.db(), &bronze, 0, 0)
+        .await
+        .expect("Failed to insert Bronze member");
+    let gold = TestUser::new("spend_gold@example.com");
+    let gold_id = insert_user_with_loyalty(app.db(), &gold, 0, 12)
+        .await
+        .expect("Failed to insert Gold member");
+
+    let bronze_points = calculate_spend_points(app.db(), bronze_id, 1000.0)
+        .await
+        .expect("Bronze calculation should succeed");
+    let gold_points = calculate_spend_points(app.db(), gold_id, 1000.0)
+        .await
+        .expect("Gold calculation should succeed");
+
+    assert_eq!(bronze_points, 10_000);
+    assert_eq!(gold_points, 15_000);
+    assert!(gold_points > bronze_points);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_spend_points_default_without_earning_rule() {
+    use loyalty_backend::services::loyalty::calculate_spend_points;
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let gold = TestUser::new("spend_default@example.com");
+    let gold_id = insert_user_with_loyalty(app.db(), &gold, 0, 12)
+        .await
+        .expect("Failed to insert member");
+
+    // No active spend rule: the original 10 points per THB
+    let points = calculate_spend_points(app.db(), gold_id, 250.0)
+        .await
+        .expect("Calculation should succeed");
+    assert_eq!(points, 2500);
+
+    app.cleanup().await.ok();
+}
+
+// ============================================================================
+// Test: POST /api/loyalty/admin/bulk-award
+// ============================================================================
+
+#[tokio::test]
+async fn test_admin_bulk_award_reports_per_user_results() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let admin = TestUser::admin("bulk_admin@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin user");
+
+    let first = TestUser::new("bulk_first@example.com");
+    let first_id = insert_user_with_loyalty(app.db(), &first, 100, 0)
+        .await
+        .expect("Failed to insert first member");
+    let second = TestUser::new("bulk_second@example.com");
+    let second_id = insert_user_with_loyalty(app.db(), &second, 0, 9)
+        .await
+        .expect("Failed to insert second member");
+    // A user with no loyalty record is reported, not enrolled
+    let unenrolled = TestUser::new("bulk_unenrolled@example.com");
+    unenrolled
+        .insert(app.db())
+        .await
+        .expect("Failed to insert unenrolled user");
+
+    let client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+    let response = client
+        .post(
+            "/api/loyalty/admin/bulk-award",
+            &json!({
+                "userIds": [first_id, second_id, unenrolled.id, first_id],
+                "points": 250,
+                "nights": 1,
+                "description": "Songkran promotion"
+            }),
+        )
+        .await;
+    response.assert_status(200);
+
+    let json: Value = response.json().expect("Response should be valid JSON");
+    let data = json.get("data").expect("Response should have 'data' field");
+    assert_eq!(data["succeeded"], 2);
+    assert_eq!(data["failed"], 1);
+
+    let results = data["results"]
+        .as_array()
+        .expect("results should be an array");
+    assert_eq!(results.len(), 3, "Duplicate IDs are awarded once");
+    assert_eq!(results[0]["userId"], first_id.to_string());
+    assert_eq!(results[0]["success"], true);
+    assert_eq!(results[0]["currentPoints"], 350);
+    assert_eq!(results[1]["totalNights"], 10);
+    assert_eq!(results[1]["tierName"], "Gold");
+    assert_eq!(results[2]["userId"], unenrolled.id.to_string());
+    assert_eq!(results[2]["success"], false);
+    assert!(results[2]["error"].is_string());
+
+    let reference_id = data["referenceId"].as_str().expect("referenceId");
+    let batch_transactions: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM points_transactions WHERE reference_id = $1")
+            .bind(reference_id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to count transactions");
+    assert_eq!(batch_transactions, 2);
+
+    let enrolled: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_loyalty WHERE user_id = $1")
+        .bind(unenrolled.id)
+        .fetch_one(app.db())
+        .await
+        .expect("Failed to check enrollment");
+    assert_eq!(enrolled, 0);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_admin_bulk_award_rejects_oversized_batch() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let admin = TestUser::admin("bulk_cap_admin@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin user");
+
+    let user_ids: Vec<Uuid> = (0..501).map(|_| Uuid::new_v4()).collect();
+    let client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+    let response = client
+        .post(
+            "/api/loyalty/admin/bulk-award",
+            &json!({
+                "userIds": user_ids,
+                "points": 100,
+                "description": "Too many"
+            }),
+        )
+        .await;
+    response.assert_status(400);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_admin_recalculate_all_tiers_in_batches() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let admin = TestUser::admin("recalc_all_admin@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin user");
+
+    // Imported members whose nights were loaded without recalculating:
+    // two are owed Platinum, one is already on the right tier
+    let mut stale = Vec::new();
+    for email in ["recalc_a@example.com", "recalc_b@example.com"] {
+        let member = TestUser::new(email);
+        let id = insert_user_with_loyalty(app.db(), &member, 0, 0)
+            .await
+            .expect("Failed to insert member");
+        sqlx::query("UPDATE user_loyalty SET total_nights = 25 WHERE user_id = $1")
+            .bind(id)
+            .execute(app.db())
+            .await
+            .expect("Failed to set nights");
+        stale.push(id);
+    }
+    let settled = TestUser::new("recalc_settled@example.com");
+    let settled_id = insert_user_with_loyalty(app.db(), &settled, 0, 10)
+        .await
+        .expect("Failed to insert member");
+
+    let client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+    let response = client
+        .post(
+            "/api/loyalty/admin/recalculate-all",
+            &json!({ "batchSize": 2 }),
+        )
+        .await;
+    response.assert_status(202);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    let job_id = json["data"]["jobId"].as_str().expect("jobId").to_string();
+    assert_eq!(json["data"]["total"], 3);
+
+    // Poll until the background run finishes
+    let mut job = Value::Null;
+    for _ in 0..100 {
+        let response = client
+            .get(&format!("/api/loyalty/admin/recalculate-all/{}", job_id))
+            .await;
+        response.assert_status(200);
+        job = response.json::<Value>().expect("valid JSON")["data"].clone();
+        if job["status"] != "running" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert_eq!(job["status"], "completed", "job: {job}");
+    assert_eq!(job["processed"], 3);
+    assert_eq!(job["changed"], 2);
+
+    for id in &stale {
+        assert_eq!(
+            tier_state(app.db(), *id).await.0.as_deref(),
+            Some("Platinum")
+        );
+    }
+    assert_eq!(
+        tier_state(app.db(), settled_id).await.0.as_deref(),
+        Some("Gold")
+    );
+
+    // Resuming a finished job is a no-op that returns it
+    let response = client
+        .post(
+            "/api/loyalty/admin/recalculate-all",
+            &json!({ "resumeJobId": job_id }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["data"]["changed"], 2);
+
+    // Non-admins can't start a run
+    let customer = app.authenticated_client(&stale[0], "recalc_a@example.com");
+    customer
+        .post("/api/loyalty/admin/recalculate-all", &json!({}))
+        .await
+        .assert_status(403);
+
+    app.cleanup().await.ok();
+}
+
+// ============================================================================
+// Test: monthly points statements
+// ============================================================================
+
+#[tokio::test]
+async fn test_points_statements_sent_once_to_active_opted_in_members() {
+    use loyalty_backend::config::{PointsDisplayConfig, PointsStatementConfig};
+    use loyalty_backend::services::loyalty::{send_points_statements, statement_period};
+    use loyalty_backend::services::NoOpEmailService;
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let (period_start, _) = statement_period(chrono::Utc::now());
+    let in_period = period_start
+        .and_hms_opt(12, 0, 0)
+        .expect("valid time")
+        .and_utc();
+
+    let active = TestUser::new("statement_active@example.com");
+    let active_id = insert_user_with_loyalty(app.db(), &active, 900, 0)
+        .await
+        .expect("Failed to insert active member");
+    let opted_out = TestUser::new("statement_opted_out@example.com");
+    let opted_out_id = insert_user_with_loyalty(app.db(), &opted_out, 900, 0)
+        .await
+        .expect("Failed to insert opted-out member");
+    let idle = TestUser::new("statement_idle@example.com");
+    insert_user_with_loyalty(app.db(), &idle, 900, 0)
+        .await
+        .expect("Failed to insert idle member");
+
+    for (user_id, points, kind) in [
+        (active_id, 1000, "earned_stay"),
+        (active_id, -100, "redeemed"),
+        (opted_out_id, 1000, "earned_stay"),
+    ] {
+        sqlx::query(
+            r#"
+            INSERT INTO points_transactions (user_id, points, type, description, created_at)
+            VALUES ($1, $2, $3::points_transaction_type, 'Statement test', $4)
+            "#,
+        )
+        .bind(user_id)
+        .bind(points)
+        .bind(kind)
+        .bind(in_period)
+        .execute(app.db())
+        .await
+        .expect("Failed to insert transaction");
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO notification_preferences (user_id, type, enabled)
+        VALUES ($1, 'email', false)
+        ON CONFLICT (user_id, type) DO UPDATE SET enabled = false
+        "#,
+    )
+    .bind(opted_out_id)
+    .execute(app.db())
+    .await
+    .expect("Failed to opt out");
+
+    let config = PointsStatementConfig::default();
+    let display = PointsDisplayConfig::default();
+    let email = NoOpEmailService::new();
+
+    let run = send_points_statements(app.db(), &email, &config, &display, "http://localhost")
+        .await
+        .expect("Statement run should succeed");
+    assert_eq!(run.claimed, 1, "Only the active, opted-in member");
+    assert_eq!(run.sent, 1);
+
+    let (user_id, earned, redeemed, balance): (Uuid, i32, i32, i32) = sqlx::query_as(
+        r#"
+        SELECT user_id, points_earned, points_redeemed, current_points
+        FROM points_statements
+        WHERE period_start = $1
+        "#,
+    )
+    .bind(period_start)
+    .fetch_one(app.db())
+    .await
+    .expect("Statement should be recorded");
+    assert_eq!(user_id, active_id);
+    assert_eq!((earned, redeemed, balance), (1000, 100, 900));
+
+    // The same period is never sent twice
+    let again = send_points_statements(app.db(), &email, &config, &display, "http://localhost")
+        .await
+        .expect("Statement run should succeed");
+    assert_eq!(again.claimed, 0);
+
+    app.cleanup().await.ok();
+}
+
+// ============================================================================
+// Leaderboard Tests
+// ============================================================================
+
+/// Give `user` a profile and set their leaderboard opt-in directly
+async fn insert_leaderboard_profile(
+    pool: &sqlx::PgPool,
+    user: &TestUser,
+    first_name: &str,
+    last_name: &str,
+    opt_in: bool,
+) {
+    sqlx::query(
+        r#"
+        INSERT INTO user_profiles (user_id, first_name, last_name, membership_id, leaderboard_opt_in)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(user.id)
+    .bind(first_name)
+    .bind(last_name)
+    .bind(user.id.to_string()[..8].to_uppercase())
+    .bind(opt_in)
+    .execute(pool)
+    .await
+    .expect("Failed to insert profile");
+}
+
+#[tokio::test]
+async fn test_leaderboard_ranks_opted_in_members_only() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let alice = TestUser::new("leaderboard_alice@example.com");
+    insert_user_with_loyalty(app.db(), &alice, 100, 30)
+        .await
+        .expect("Failed to insert alice");
+    insert_leaderboard_profile(app.db(), &alice, "Alice", "smith", true).await;
+
+    let carol = TestUser::new("leaderboard_carol@example.com");
+    insert_user_with_loyalty(app.db(), &carol, 900, 10)
+        .await
+        .expect("Failed to insert carol");
+    insert_leaderboard_profile(app.db(), &carol, "Carol", "White", true).await;
+
+    // Tops both metrics but never opted in
+    let dave = TestUser::new("leaderboard_dave@example.com");
+    insert_user_with_loyalty(app.db(), &dave, 9999, 99)
+        .await
+        .expect("Failed to insert dave");
+    insert_leaderboard_profile(app.db(), &dave, "Dave", "Hidden", false).await;
+
+    // Opts in through the profile endpoint
+    let bob = TestUser::new("leaderboard_bob@example.com");
+    insert_user_with_loyalty(app.db(), &bob, 50, 50)
+        .await
+        .expect("Failed to insert bob");
+    let bob_client = app.authenticated_client(&bob.id, &bob.email);
+    let response = bob_client
+        .put(
+            "/api/users/me",
+            &json!({ "firstName": "Bob", "lastName": "Jones", "leaderboardOptIn": true }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["data"]["profile"]["leaderboardOptIn"], true);
+
+    let client = app.authenticated_client(&alice.id, &alice.email);
+
+    let response = client.get("/api/loyalty/leaderboard?metric=nights").await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["data"]["metric"], "nights");
+    let entries = json["data"]["entries"].as_array().expect("entries array");
+    let ranked: Vec<(i64, &str, i64)> = entries
+        .iter()
+        .map(|e| {
+            (
+                e["rank"].as_i64().unwrap(),
+                e["displayName"].as_str().unwrap(),
+                e["value"].as_i64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        ranked,
+        vec![(1, "Bob J.", 50), (2, "Alice S.", 30), (3, "Carol W.", 10)]
+    );
+    assert!(entries[0]["tierName"].is_string());
+
+    let response = client
+        .get("/api/loyalty/leaderboard?metric=points&limit=2")
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    let names: Vec<&str> = json["data"]["entries"]
+        .as_array()
+        .expect("entries array")
+        .iter()
+        .map(|e| e["displayName"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Carol W.", "Alice S."]);
+
+    let response = client.get("/api/loyalty/leaderboard?metric=stars").await;
+    response.assert_status(400);
+
+    app.cleanup().await.ok();
+}
+
+#[tokio::test]
+async fn test_redeem_points_uses_tier_rate_with_default_fallback() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    // Platinum sets its own rate; Bronze falls back to the 0.1 default
+    sqlx::query(
+        r#"
+        UPDATE tiers
+        SET benefits = COALESCE(benefits, '{}'::jsonb) || '{"points_redemption_rate": 0.25}'::jsonb
+        WHERE name = 'Platinum'
+        "#,
+    )
+    .execute(app.db())
+    .await
+    .expect("Failed to set Platinum redemption rate");
+
+    let value_of = |json: &Value| -> f64 {
+        json["data"]["value"]
+            .as_str()
+            .and_then(|v| v.parse().ok())
+            .expect("value should be a decimal string")
+    };
+
+    let platinum = TestUser::new("redeem_platinum@example.com");
+    insert_user_with_loyalty(app.db(), &platinum, 5000, 30)
+        .await
+        .expect("Failed to insert Platinum member");
+    let response = app
+        .authenticated_client(&platinum.id, &platinum.email)
+        .post(
+            "/api/loyalty/redeem",
+            &json!({ "points": 1000, "reference_id": "ORDER-P1" }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(value_of(&json), 250.0);
+    assert_eq!(json["data"]["new_points_balance"], 4000);
+
+    let bronze = TestUser::new("redeem_bronze@example.com");
+    insert_user_with_loyalty(app.db(), &bronze, 5000, 0)
+        .await
+        .expect("Failed to insert Bronze member");
+    let bronze_client = app.authenticated_client(&bronze.id, &bronze.email);
+    let response = bronze_client
+        .post(
+            "/api/loyalty/redeem",
+            &json!({ "points": 1000, "reference_id": "ORDER-B1" }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(value_of(&json), 100.0);
+
+    let recorded: i32 = sqlx::query_scalar(
+        "SELECT points FROM points_transactions WHERE user_id = $1 AND type = 'redeemed'",
+    )
+    .bind(bronze.id)
+    .fetch_one(app.db())
+    .await
+    .expect("Redemption should be recorded");
+    assert_eq!(recorded, -1000);
+
+    // More than the remaining balance
+    let response = bronze_client
+        .post(
+            "/api/loyalty/redeem",
+            &json!({ "points": 4001, "reference_id": "ORDER-B2" }),
+        )
+        .await;
+    response.assert_status(400);
+
+    app.cleanup().await.ok();
+}
@@ -5,6 +5,7 @@
 //! - Getting unread count
 //! - Marking notifications as read (single and all)
 //! - Deleting notifications
+//! - Quiet hours
 
 use chrono::{Duration, Utc};
 use serde_json::Value;
@@ -736,3 +737,145 @@ async fn test_notification_response_structure() {
 
     app.cleanup().await.ok();
 }
+
+// ============================================================================
+// Quiet Hours Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_quiet_hours_hold_non_critical_pushes_until_window_ends() {
+    use loyalty_backend::models::notification::{NotificationPriority, NotificationType};
+    use loyalty_backend::services::notification::{
+        release_held_notifications, CreateNotificationDto, NotificationService,
+        NotificationServiceImpl,
+    };
+
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("quiet-hours@example.com");
+    user.insert(app.db()).await.expect("Failed to insert user");
+    let client = app.authenticated_client(&user.id, &user.email);
+
+    // Unknown zones are refused
+    let response = client
+        .put(
+            "/api/notifications/quiet-hours",
+            &serde_json::json!({ "start": "22:00", "end": "07:00", "timezone": "Mars/Olympus" }),
+        )
+        .await;
+    response.assert_status(400);
+
+    // A window around the current Bangkok (UTC+7) wall-clock time
+    let bangkok_now = Utc::now() + Duration::hours(7);
+    let start = (bangkok_now - Duration::hours(1))
+        .format("%H:%M")
+        .to_string();
+    let end = (bangkok_now + Duration::hours(1))
+        .format("%H:%M")
+        .to_string();
+    let response = client
+        .put(
+            "/api/notifications/quiet-hours",
+            &serde_json::json!({ "start": start, "end": end, "timezone": "Asia/Bangkok" }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["quiet_hours"]["timezone"], "Asia/Bangkok");
+    assert_eq!(json["quiet_hours"]["start"], start.as_str());
+
+    let service = NotificationServiceImpl::new(app.db().clone());
+    let dto = |title: &str, priority: NotificationPriority| CreateNotificationDto {
+        user_id: user.id,
+        title: title.to_string(),
+        message: "Quiet hours test".to_string(),
+        notification_type: Some(NotificationType::Points),
+        priority,
+        category: None,
+        data: None,
+        expires_at: None,
+    };
+
+    let held = service
+        .create_notification(dto("Points earned", NotificationPriority::Normal))
+        .await
+        .expect("Failed to create notification");
+    let urgent = service
+        .create_notification(dto("Password changed", NotificationPriority::High))
+        .await
+        .expect("Failed to create notification");
+
+    let held_ids: Vec<Uuid> =
+        sqlx::query_scalar("SELECT notification_id FROM held_notifications WHERE user_id = $1")
+            .bind(user.id)
+            .fetch_all(app.db())
+            .await
+            .expect("Failed to fetch holds");
+    assert_eq!(
+        held_ids,
+        vec![held.id],
+        "Only the non-critical push is held"
+    );
+    assert_ne!(held_ids[0], urgent.id);
+
+    // The hold is released at the end of the window, within the next hour
+    let release_in: f64 = sqlx::query_scalar(
+        "SELECT EXTRACT(EPOCH FROM release_at - NOW())::float8 FROM held_notifications WHERE notification_id = $1",
+    )
+    .bind(held.id)
+    .fetch_one(app.db())
+    .await
+    .expect("Failed to fetch release time");
+    assert!(
+        release_in > 0.0 && release_in <= 3600.0,
+        "release in {}s",
+        release_in
+    );
+
+    // Nothing is due yet
+    let run = release_held_notifications(app.db(), 12)
+        .await
+        .expect("Release should succeed");
+    assert_eq!((run.delivered, run.dropped), (0, 0));
+
+    // A stale hold is dropped, a fresh one delivered
+    let stale = service
+        .create_notification(dto("Old news", NotificationPriority::Low))
+        .await
+        .expect("Failed to create notification");
+    sqlx::query("UPDATE notifications SET created_at = NOW() - INTERVAL '2 days' WHERE id = $1")
+        .bind(stale.id)
+        .execute(app.db())
+        .await
+        .expect("Failed to age notification");
+    sqlx::query(
+        "UPDATE held_notifications SET release_at = NOW() - INTERVAL '1 minute' WHERE user_id = $1",
+    )
+    .bind(user.id)
+    .execute(app.db())
+    .await
+    .expect("Failed to end window");
+
+    let run = release_held_notifications(app.db(), 12)
+        .await
+        .expect("Release should succeed");
+    assert_eq!((run.delivered, run.dropped), (1, 1));
+
+    let remaining: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM held_notifications WHERE user_id = $1")
+            .bind(user.id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to count holds");
+    assert_eq!(remaining, 0);
+
+    // Held notifications were in the inbox all along
+    let inbox: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM notifications WHERE user_id = $1")
+        .bind(user.id)
+        .fetch_one(app.db())
+        .await
+        .expect("Failed to count notifications");
+    assert_eq!(inbox, 3);
+
+    app.cleanup().await.ok();
+}
@@ -57,6 +57,8 @@ fn mint_test_token(user_id: &str, role: &str) -> String {
         role: role.to_string(),
         iat: Some(now),
         exp: now + 3600,
+        last_auth_at: Some(now),
+        sid: None,
     };
     encode(
         &Header::default(),
@@ -7,6 +7,7 @@
 //! - Submitting survey responses
 //! - Admin survey creation
 //! - Admin viewing survey responses
+//! - Single-use invitation links for guests
 
 use chrono::{Duration, Utc};
 use serde_json::{json, Value};
@@ -410,6 +411,94 @@ async fn test_submit_survey_response_partial() {
     app.cleanup().await.ok();
 }
 
+/// Test POST /api/surveys/:id/responses rejects answers that break the
+/// questions' rules
+#[tokio::test]
+async fn test_submit_survey_response_validates_answers() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let user = TestUser::new("survey_validation_test@example.com");
+    user.insert(app.db())
+        .await
+        .expect("Failed to insert test user");
+
+    let survey_id = create_test_survey(app.db(), "Validated Survey", "active", "public", None)
+        .await
+        .expect("Failed to create survey");
+    let questions = json!([
+        {
+            "id": "q1",
+            "question_type": "single_choice",
+            "text": "How satisfied are you?",
+            "description": null,
+            "required": true,
+            "options": [{"id": "o1", "text": "Very satisfied", "value": "1"}],
+            "validation": null,
+            "order": 1
+        },
+        {
+            "id": "q2",
+            "question_type": "scale",
+            "text": "How likely are you to recommend us?",
+            "description": null,
+            "required": false,
+            "options": null,
+            "validation": {"min_value": 0, "max_value": 10},
+            "order": 2
+        }
+    ]);
+    sqlx::query("UPDATE surveys SET questions = $2 WHERE id = $1")
+        .bind(survey_id)
+        .bind(&questions)
+        .execute(app.db())
+        .await
+        .expect("Failed to set questions");
+
+    let client = app.authenticated_client(&user.id, &user.email);
+    let path = format!("/api/surveys/{}/responses", survey_id);
+
+    // Missing required answer
+    let response = client
+        .post(
+            &path,
+            &json!({ "answers": { "q2": 9 }, "is_completed": true }),
+        )
+        .await;
+    response.assert_status(400);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["details"]["q1"][0], "This question is required");
+    assert!(json["details"].get("q2").is_none());
+
+    // Out-of-range scale value, even on a draft
+    let response = client
+        .post(
+            &path,
+            &json!({ "answers": { "q2": 11 }, "is_completed": false }),
+        )
+        .await;
+    response.assert_status(400);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["details"]["q2"][0], "Answer must be at most 10");
+
+    let stored: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM survey_responses WHERE survey_id = $1")
+            .bind(survey_id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to count responses");
+    assert_eq!(stored, 0);
+
+    client
+        .post(
+            &path,
+            &json!({ "answers": { "q1": "1", "q2": 10 }, "is_completed": true }),
+        )
+        .await
+        .assert_status(200);
+
+    app.cleanup().await.ok();
+}
+
 /// Test POST /api/surveys/:id/responses without auth returns 401
 #[tokio::test]
 async fn test_submit_survey_response_unauthorized() {
@@ -783,3 +872,176 @@ async fn test_get_survey_responses_with_pagination() {
 
     app.cleanup().await.ok();
 }
+
+// ============================================================================
+// Test: Invitation Links
+// ============================================================================
+
+/// Send an invitation link for `survey_id` to a guest `email` through the
+/// admin API and return the link's token
+async fn send_guest_invitation(app: &TestApp, survey_id: Uuid, email: &str) -> String {
+    let admin = TestUser::admin("survey_invite_admin@example.com");
+    admin
+        .insert(app.db())
+        .await
+        .expect("Failed to insert admin");
+    let client = app.authenticated_client_with_role(&admin.id, &admin.email, "admin");
+
+    let response = client
+        .post(
+            &format!("/api/surveys/{}/invitations/send", survey_id),
+            &json!({ "emails": [email], "expiresInDays": 7 }),
+        )
+        .await;
+    response.assert_status(201);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["sent"], 1);
+    let invitation = &json["invitations"][0];
+    assert_eq!(invitation["email"], email);
+    assert!(invitation["userId"].is_null());
+
+    let link = invitation["link"]
+        .as_str()
+        .expect("link should be a string");
+    link.rsplit('/').next().unwrap().to_string()
+}
+
+/// Test a guest can open and answer a survey through an invitation link
+#[tokio::test]
+async fn test_invitation_link_submission_by_guest() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let survey_id = create_test_survey(app.db(), "Guest Survey", "active", "invite_only", None)
+        .await
+        .expect("Failed to create survey");
+    let token = send_guest_invitation(&app, survey_id, "guest@example.com").await;
+    let guest = app.client();
+
+    let response = guest.get(&format!("/api/surveys/invite/{}", token)).await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["survey"]["id"], survey_id.to_string());
+    assert!(json["expiresAt"].is_string());
+
+    // Answers are validated as for members
+    guest
+        .post(
+            &format!("/api/surveys/invite/{}/submit", token),
+            &json!({ "answers": { "q2": "Lovely" } }),
+        )
+        .await
+        .assert_status(400);
+
+    let response = guest
+        .post(
+            &format!("/api/surveys/invite/{}/submit", token),
+            &json!({ "answers": { "q1": "1", "q2": "Lovely" } }),
+        )
+        .await;
+    response.assert_status(200);
+    let json: Value = response.json().expect("Response should be valid JSON");
+    assert_eq!(json["isCompleted"], true);
+
+    let (user_id, is_completed, status): (Option<Uuid>, Option<bool>, Option<String>) =
+        sqlx::query_as(
+            r#"
+            SELECT r.user_id, r.is_completed, i.status
+            FROM survey_responses r
+            JOIN survey_invitations i ON i.id = r.invitation_id
+            WHERE r.survey_id = $1
+            "#,
+        )
+        .bind(survey_id)
+        .fetch_one(app.db())
+        .await
+        .expect("Response should be linked to the invitation");
+    assert!(user_id.is_none());
+    assert_eq!(is_completed, Some(true));
+    assert_eq!(status.as_deref(), Some("completed"));
+
+    app.cleanup().await.ok();
+}
+
+/// Test an invitation link can only be submitted once
+#[tokio::test]
+async fn test_invitation_link_cannot_be_reused() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let survey_id = create_test_survey(app.db(), "Reuse Survey", "active", "invite_only", None)
+        .await
+        .expect("Failed to create survey");
+    let token = send_guest_invitation(&app, survey_id, "reuse@example.com").await;
+    let guest = app.client();
+    let path = format!("/api/surveys/invite/{}/submit", token);
+
+    guest
+        .post(&path, &json!({ "answers": { "q1": "2" } }))
+        .await
+        .assert_status(200);
+    guest
+        .post(&path, &json!({ "answers": { "q1": "4" } }))
+        .await
+        .assert_status(409);
+    guest
+        .get(&format!("/api/surveys/invite/{}", token))
+        .await
+        .assert_status(409);
+
+    let responses: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM survey_responses WHERE survey_id = $1")
+            .bind(survey_id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to count responses");
+    assert_eq!(responses, 1);
+
+    // An unknown token is simply not found
+    guest
+        .get("/api/surveys/invite/not-a-real-token")
+        .await
+        .assert_status(404);
+
+    app.cleanup().await.ok();
+}
+
+/// Test an expired invitation link is rejected
+#[tokio::test]
+async fn test_invitation_link_expires() {
+    let app = TestApp::new().await.expect("Failed to create test app");
+
+    let survey_id = create_test_survey(app.db(), "Expired Survey", "active", "invite_only", None)
+        .await
+        .expect("Failed to create survey");
+    let token = send_guest_invitation(&app, survey_id, "late@example.com").await;
+
+    sqlx::query(
+        "UPDATE survey_invitations SET expires_at = NOW() - INTERVAL '1 day' WHERE survey_id = $1",
+    )
+    .bind(survey_id)
+    .execute(app.db())
+    .await
+    .expect("Failed to expire invitation");
+
+    let guest = app.client();
+    guest
+        .get(&format!("/api/surveys/invite/{}", token))
+        .await
+        .assert_status(400);
+    guest
+        .post(
+            &format!("/api/surveys/invite/{}/submit", token),
+            &json!({ "answers": { "q1": "1" } }),
+        )
+        .await
+        .assert_status(400);
+
+    let responses: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM survey_responses WHERE survey_id = $1")
+            .bind(survey_id)
+            .fetch_one(app.db())
+            .await
+            .expect("Failed to count responses");
+    assert_eq!(responses, 0);
+
+    app.cleanup().await.ok();
+}